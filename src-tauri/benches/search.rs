@@ -0,0 +1,40 @@
+//! Ranking a library-sized candidate list, as the search box does on every
+//! keystroke. Only the first iteration converts the list to pinyin; the
+//! rest measure matching, which is what typing costs. Run with
+//! `cargo bench --bench search`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use gmplayer_lib::algorithms::search::fuzzy_match;
+
+const SYLLABLES: &[&str] = &[
+    "周", "杰", "伦", "晴", "天", "陈", "奕", "迅", "林", "俊", "音", "乐", "重", "庆", "长", "大",
+    "Love", "Story", " ", "-", "Live", "(", ")", "Remix",
+];
+
+/// 10k names of two to eight pieces, mixing Chinese and Latin text.
+fn candidates() -> Vec<String> {
+    let mut state = 0x2545_f491_u32;
+    (0..10_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let len = 2 + state as usize % 7;
+            (0..len)
+                .map(|i| SYLLABLES[(state as usize >> (i * 3)) % SYLLABLES.len()])
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_search(c: &mut Criterion) {
+    let candidates = candidates();
+    for query in ["zhoujielun", "zjl", "love", "晴天"] {
+        c.bench_function(&format!("rank 10k / {}", query), |b| {
+            b.iter(|| fuzzy_match(black_box(query), &candidates, 100))
+        });
+    }
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
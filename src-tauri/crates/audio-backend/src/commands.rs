@@ -10,6 +10,7 @@ use tauri::State;
 use tracing::{info, warn};
 
 use crate::automix::{self, AutomixAnalyzeRequest, AutomixAnalyzeSourceRequest, TrackAnalysis};
+use crate::output::devices::{self, DeviceCapabilities, RecommendedAudioConfig};
 use crate::player::Player;
 use crate::types::*;
 
@@ -58,6 +59,19 @@ impl PlayerState {
             .map_err(|e| e.to_string())?
     }
 
+    /// Sync read of the current playback state, without initializing the
+    /// player. `None` until the player has been created.
+    pub fn snapshot(&self) -> Option<PlaybackSnapshot> {
+        let p = self.inner.player.lock().as_ref().cloned()?;
+        Some(PlaybackSnapshot {
+            state: state_name(p.state()).into(),
+            music_id: p.music_id(),
+            position: p.position(),
+            duration: p.duration(),
+            volume: p.volume(),
+        })
+    }
+
     async fn player(&self) -> Result<Arc<Player>, String> {
         if let Some(player) = self.inner.player.lock().as_ref().cloned() {
             return Ok(player);
@@ -139,6 +153,16 @@ pub struct AudioStateResponse {
     pub duration: f64,
 }
 
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackSnapshot {
+    pub state: String,
+    pub music_id: String,
+    pub position: f64,
+    pub duration: f64,
+    pub volume: f64,
+}
+
 fn state_name(s: PlaybackState) -> &'static str {
     match s {
         PlaybackState::Stopped => "stopped",
@@ -240,3 +264,34 @@ pub async fn audio_analyze_automix_source(
         .await
         .map_err(|e| e.to_string())?
 }
+
+// ═══════════════════════════════════════════════════════════════════
+//  Output device capabilities
+// ═══════════════════════════════════════════════════════════════════
+
+/// Supported rates / channel counts and the current mix format of an output
+/// device (`"default"` or a device name). Cached per device until the OS mix
+/// format changes.
+#[tauri::command]
+pub async fn get_device_capabilities(device_id: String) -> Result<DeviceCapabilities, String> {
+    tauri::async_runtime::spawn_blocking(move || devices::device_capabilities(&device_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// AudioContext sample rate / channel count that avoids OS resampling on the
+/// default output device.
+#[tauri::command]
+pub async fn get_recommended_audio_config() -> Result<RecommendedAudioConfig, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        devices::device_capabilities("default").map(|caps| devices::recommend(&caps))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Start emitting `audio-device-format-changed` when the default output
+/// device or its mix format changes.
+pub fn watch_device_format(app_handle: tauri::AppHandle) {
+    devices::spawn_format_watcher(app_handle);
+}
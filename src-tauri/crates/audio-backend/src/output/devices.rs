@@ -0,0 +1,253 @@
+//! Output device capability queries for the frontend.
+//!
+//! The "mix format" is CPAL's default output config: WASAPI returns the
+//! shared-mode `GetMixFormat` result and CoreAudio the device's nominal
+//! sample rate, so on those platforms it is exactly the format the OS mixes
+//! at. Elsewhere it is the backend's preferred config.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::Emitter;
+use tracing::warn;
+
+use super::{device_name, resolve_output_device, OutputDeviceSelector};
+
+pub const DEVICE_FORMAT_CHANGED_EVENT: &str = "audio-device-format-changed";
+
+/// Rates reported as supported when they fall inside a device's ranges.
+const COMMON_RATES: [u32; 8] = [
+    22_050, 32_000, 44_100, 48_000, 88_200, 96_000, 176_400, 192_000,
+];
+const FORMAT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MixFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    pub device_id: String,
+    pub name: String,
+    pub sample_rates: Vec<u32>,
+    pub channel_counts: Vec<u16>,
+    pub sample_formats: Vec<String>,
+    pub mix_format: Option<MixFormat>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedAudioConfig {
+    pub device_id: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// `true` when the rate is the device's mix format, i.e. the OS will
+    /// not resample.
+    pub native: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceFormatChanged {
+    pub device_id: String,
+    pub mix_format: Option<MixFormat>,
+}
+
+/// Capabilities per device id. Enumerating configs can take tens of
+/// milliseconds (and wake Bluetooth devices), so results are kept until the
+/// device's mix format changes.
+static CAPABILITIES: LazyLock<Mutex<HashMap<String, DeviceCapabilities>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn mix_format(device: &cpal::Device) -> Option<MixFormat> {
+    let config = device.default_output_config().ok()?;
+    Some(MixFormat {
+        sample_rate: config.sample_rate(),
+        channels: config.channels(),
+        sample_format: config.sample_format().to_string(),
+    })
+}
+
+fn supported_rates(ranges: &[(u32, u32)]) -> Vec<u32> {
+    COMMON_RATES
+        .into_iter()
+        .filter(|rate| ranges.iter().any(|(min, max)| min <= rate && rate <= max))
+        .collect()
+}
+
+fn query(device_id: &str) -> Result<DeviceCapabilities, String> {
+    let host = cpal::default_host();
+    let selector = OutputDeviceSelector::from_name(device_id);
+    let device = resolve_output_device(&host, &selector)?;
+    let mix_format = mix_format(&device);
+
+    let mut ranges = Vec::new();
+    let mut channel_counts = Vec::new();
+    let mut sample_formats = Vec::new();
+    let configs = device
+        .supported_output_configs()
+        .map_err(|e| format!("query output configs: {e:?}"))?;
+    for range in configs {
+        ranges.push((range.min_sample_rate(), range.max_sample_rate()));
+        channel_counts.push(range.channels());
+        sample_formats.push(range.sample_format().to_string());
+    }
+    let mut sample_rates = supported_rates(&ranges);
+    if let Some(format) = &mix_format {
+        sample_rates.push(format.sample_rate);
+        channel_counts.push(format.channels);
+    }
+    sample_rates.sort_unstable();
+    sample_rates.dedup();
+    channel_counts.sort_unstable();
+    channel_counts.dedup();
+    sample_formats.sort_unstable();
+    sample_formats.dedup();
+
+    Ok(DeviceCapabilities {
+        device_id: device_id.to_string(),
+        name: device_name(&device),
+        sample_rates,
+        channel_counts,
+        sample_formats,
+        mix_format,
+    })
+}
+
+/// Capabilities of `device_id` (a device name, or `"default"`), cached.
+pub fn device_capabilities(device_id: &str) -> Result<DeviceCapabilities, String> {
+    if let Some(cached) = CAPABILITIES.lock().get(device_id) {
+        return Ok(cached.clone());
+    }
+    let capabilities = query(device_id)?;
+    CAPABILITIES
+        .lock()
+        .insert(device_id.to_string(), capabilities.clone());
+    Ok(capabilities)
+}
+
+/// Best AudioContext config for a device: its mix rate when known (no OS
+/// resampling), else 48 kHz, 44.1 kHz or the highest supported rate; stereo
+/// unless the device only does mono.
+pub fn recommend(capabilities: &DeviceCapabilities) -> RecommendedAudioConfig {
+    let rates = &capabilities.sample_rates;
+    let (sample_rate, native) = match &capabilities.mix_format {
+        Some(format) => (format.sample_rate, true),
+        None => {
+            let rate = [48_000, 44_100]
+                .into_iter()
+                .find(|rate| rates.contains(rate))
+                .or_else(|| rates.last().copied())
+                .unwrap_or(48_000);
+            (rate, false)
+        }
+    };
+    let max_channels = capabilities
+        .channel_counts
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(2);
+    RecommendedAudioConfig {
+        device_id: capabilities.device_id.clone(),
+        sample_rate,
+        channels: max_channels.clamp(1, 2),
+        native,
+    }
+}
+
+/// Default device identity plus its mix format, for change detection.
+fn default_device_format() -> Option<(String, Option<MixFormat>)> {
+    let device = cpal::default_host().default_output_device()?;
+    Some((device_name(&device), mix_format(&device)))
+}
+
+/// Poll the default device's mix format and emit
+/// [`DEVICE_FORMAT_CHANGED_EVENT`] when it (or the default device) changes,
+/// so the frontend can rebuild its AudioContext. CPAL has no change
+/// notification, hence the poll; one default-config query is cheap.
+pub fn spawn_format_watcher(app: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("audio-format-watcher".into())
+        .spawn(move || {
+            let mut last = default_device_format();
+            loop {
+                std::thread::sleep(FORMAT_POLL_INTERVAL);
+                let current = default_device_format();
+                if current == last {
+                    continue;
+                }
+                CAPABILITIES.lock().clear();
+                if let Some((name, mix_format)) = &current {
+                    let _ = app.emit(
+                        DEVICE_FORMAT_CHANGED_EVENT,
+                        DeviceFormatChanged {
+                            device_id: name.clone(),
+                            mix_format: mix_format.clone(),
+                        },
+                    );
+                }
+                last = current;
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn audio format watcher: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(
+        rates: &[u32],
+        channels: &[u16],
+        mix: Option<(u32, u16)>,
+    ) -> DeviceCapabilities {
+        DeviceCapabilities {
+            device_id: "default".into(),
+            name: "Speakers".into(),
+            sample_rates: rates.to_vec(),
+            channel_counts: channels.to_vec(),
+            sample_formats: vec!["f32".into()],
+            mix_format: mix.map(|(sample_rate, channels)| MixFormat {
+                sample_rate,
+                channels,
+                sample_format: "f32".into(),
+            }),
+        }
+    }
+
+    #[test]
+    fn rates_are_picked_from_ranges() {
+        assert_eq!(
+            supported_rates(&[(44_100, 48_000), (96_000, 96_000)]),
+            vec![44_100, 48_000, 96_000]
+        );
+        assert!(supported_rates(&[(8_000, 16_000)]).is_empty());
+    }
+
+    #[test]
+    fn recommendation_prefers_the_mix_rate() {
+        let native = recommend(&capabilities(&[44_100, 48_000], &[2, 8], Some((48_000, 8))));
+        assert_eq!(
+            (native.sample_rate, native.channels, native.native),
+            (48_000, 2, true)
+        );
+
+        let fallback = recommend(&capabilities(&[44_100, 96_000], &[1], None));
+        assert_eq!(
+            (fallback.sample_rate, fallback.channels, fallback.native),
+            (44_100, 1, false)
+        );
+    }
+}
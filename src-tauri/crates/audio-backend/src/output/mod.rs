@@ -12,6 +12,7 @@ use rtrb::{Consumer, Producer, PushError, RingBuffer};
 use tracing::{info, warn};
 
 mod config;
+pub mod devices;
 mod platform;
 mod render;
 
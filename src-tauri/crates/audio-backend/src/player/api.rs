@@ -64,6 +64,9 @@ pub struct PlayerShared {
     pub state: AtomicU8,
     pub position_ms: AtomicU64,
     pub duration_ms: AtomicU64,
+    /// `f64::to_bits` of the last reported volume (0.0..=1.0).
+    pub volume_bits: AtomicU64,
+    pub music_id: parking_lot::Mutex<String>,
     pub event_buf: parking_lot::Mutex<EventBuffer>,
     /// Event sink registered by the frontend via `audio_subscribe_events`.
     /// The forwarder streams every `AudioThreadEventMessage` here; when no
@@ -84,6 +87,8 @@ impl Player {
             state: AtomicU8::new(PlaybackState::Stopped as u8),
             position_ms: AtomicU64::new(0),
             duration_ms: AtomicU64::new(0),
+            volume_bits: AtomicU64::new(1.0f64.to_bits()),
+            music_id: parking_lot::Mutex::new(String::new()),
             event_buf: parking_lot::Mutex::new(EventBuffer::new(0)),
             event_channel: parking_lot::Mutex::new(None),
         });
@@ -241,6 +246,14 @@ impl Player {
         self.state() == PlaybackState::Playing
     }
 
+    pub fn volume(&self) -> f64 {
+        f64::from_bits(self.shared.volume_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn music_id(&self) -> String {
+        self.shared.music_id.lock().clone()
+    }
+
     pub fn poll_events(&self, session_id: u64) -> Vec<AudioThreadEvent> {
         self.shared.event_buf.lock().drain(session_id)
     }
@@ -294,11 +307,17 @@ fn update_shared_from_event(shared: &Arc<PlayerShared>, event: &AudioThreadEvent
                 .store((position * 1000.0).max(0.0) as u64, Ordering::Relaxed);
         }
         AudioThreadEvent::SyncStatus {
+            music_id,
             is_playing,
             position,
             duration,
+            volume,
             ..
         } => {
+            *shared.music_id.lock() = music_id.clone();
+            shared
+                .volume_bits
+                .store(volume.to_bits(), Ordering::Relaxed);
             let s = if *is_playing {
                 PlaybackState::Playing
             } else {
@@ -312,6 +331,15 @@ fn update_shared_from_event(shared: &Arc<PlayerShared>, event: &AudioThreadEvent
                 .duration_ms
                 .store((duration * 1000.0).max(0.0) as u64, Ordering::Relaxed);
         }
+        AudioThreadEvent::LoadAudio { music_id, .. }
+        | AudioThreadEvent::LoadingAudio { music_id, .. } => {
+            *shared.music_id.lock() = music_id.clone();
+        }
+        AudioThreadEvent::VolumeChanged { volume } => {
+            shared
+                .volume_bits
+                .store(volume.to_bits(), Ordering::Relaxed);
+        }
         AudioThreadEvent::AudioPlayFinished { .. } => {
             PlaybackState::Ended.store(&shared.state);
         }
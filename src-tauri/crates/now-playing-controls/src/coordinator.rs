@@ -1,4 +1,4 @@
-use std::thread;
+use std::{thread, time::Instant};
 
 use tokio::{
     runtime::Builder,
@@ -11,9 +11,11 @@ use tracing::{error, warn};
 use crate::{
     discord::DiscordAdapter,
     model::{
-        DiscordConfigPayload, MetadataPayload, PlayModePayload, PlayStatePayload, TimelinePayload,
+        DiscordConfigPayload, MetadataPayload, PlayModePayload, PlayStatePayload, PlaybackStatus,
+        TimelinePayload,
     },
     platform::PlatformMediaControls,
+    timeline::{TimelinePush, TimelineTracker},
     EventCallback, NowPlayingOptions,
 };
 
@@ -21,6 +23,8 @@ use crate::{
 pub enum Command {
     UpdateMetadata(MetadataPayload),
     UpdatePlayState(PlayStatePayload),
+    UpdatePlaybackState { playing: bool, position: Duration },
+    NotifySeek(Duration),
     UpdatePlaybackRate(f64),
     UpdateVolume(f64),
     UpdateTimeline(TimelinePayload),
@@ -58,6 +62,7 @@ pub fn spawn_coordinator_loop(
             };
 
             let mut discord_adapter = DiscordAdapter::new(options.discord);
+            let mut timeline = TimelineTracker::default();
 
             let mut ticker = interval(Duration::from_secs(5));
 
@@ -68,7 +73,7 @@ pub fn spawn_coordinator_loop(
                             break;
                         };
 
-                        if handle_command(cmd, &mut os_adapter, &mut discord_adapter).await {
+                        if handle_command(cmd, &mut os_adapter, &mut discord_adapter, &mut timeline).await {
                             break;
                         }
                     }
@@ -90,6 +95,7 @@ async fn handle_command(
     cmd: Command,
     os_adapter: &mut PlatformMediaControls,
     discord_adapter: &mut DiscordAdapter,
+    timeline: &mut TimelineTracker,
 ) -> bool {
     match cmd {
         Command::EnableSystemMedia => {
@@ -104,6 +110,10 @@ async fn handle_command(
         }
 
         Command::UpdateMetadata(payload) => {
+            timeline.reset();
+            if let Some(duration) = payload.duration {
+                timeline.total = duration;
+            }
             if let Err(e) = discord_adapter.update_metadata(payload.clone()).await {
                 warn!("更新 Discord 元数据失败: {e}");
             }
@@ -112,6 +122,10 @@ async fn handle_command(
             }
         }
         Command::UpdatePlayState(payload) => {
+            let playing = payload.status == PlaybackStatus::Playing;
+            if let Some(push) = timeline.set_playing(playing, Instant::now()) {
+                push_timeline(os_adapter, discord_adapter, timeline.total, push, false).await;
+            }
             if let Err(e) = discord_adapter.update_play_state(payload).await {
                 warn!("更新 Discord 播放状态失败: {e}");
             }
@@ -119,7 +133,33 @@ async fn handle_command(
                 warn!("更新系统媒体播放状态失败: {e}");
             }
         }
+        Command::UpdatePlaybackState { playing, position } => {
+            let was_playing = timeline.is_playing();
+            if let Some(push) = timeline.observe(playing, position, Instant::now()) {
+                if was_playing != playing {
+                    let status = if playing {
+                        PlaybackStatus::Playing
+                    } else {
+                        PlaybackStatus::Paused
+                    };
+                    let payload = PlayStatePayload { status };
+                    if let Err(e) = discord_adapter.update_play_state(payload).await {
+                        warn!("更新 Discord 播放状态失败: {e}");
+                    }
+                    if let Err(e) = os_adapter.update_play_state(payload).await {
+                        warn!("更新系统媒体播放状态失败: {e}");
+                    }
+                }
+                push_timeline(os_adapter, discord_adapter, timeline.total, push, false).await;
+            }
+        }
+        Command::NotifySeek(position) => {
+            let push = timeline.seek(position, Instant::now());
+            push_timeline(os_adapter, discord_adapter, timeline.total, push, true).await;
+        }
         Command::UpdateTimeline(payload) => {
+            timeline.total = payload.total_time;
+            timeline.pushed(payload.current_time, Instant::now());
             if let Err(e) = discord_adapter.update_timeline(payload).await {
                 warn!("更新 Discord 时间线失败: {e}");
             }
@@ -131,6 +171,9 @@ async fn handle_command(
             if let Err(e) = os_adapter.update_playback_rate(rate).await {
                 warn!("更新播放速率失败: {e}");
             }
+            if let Some(push) = timeline.set_rate(rate, Instant::now()) {
+                push_timeline(os_adapter, discord_adapter, timeline.total, push, false).await;
+            }
         }
         Command::UpdateVolume(volume) => {
             if let Err(e) = os_adapter.update_volume(volume).await {
@@ -166,3 +209,25 @@ async fn handle_command(
 
     false
 }
+
+#[allow(clippy::future_not_send, clippy::needless_pass_by_ref_mut)]
+async fn push_timeline(
+    os_adapter: &mut PlatformMediaControls,
+    discord_adapter: &mut DiscordAdapter,
+    total: Duration,
+    push: TimelinePush,
+    seeked: bool,
+) {
+    let payload = TimelinePayload {
+        current_time: push.position,
+        total_time: total,
+        seeked: Some(seeked),
+        rate: Some(push.rate),
+    };
+    if let Err(e) = discord_adapter.update_timeline(payload).await {
+        warn!("更新 Discord 时间线失败: {e}");
+    }
+    if let Err(e) = os_adapter.update_timeline(payload).await {
+        warn!("更新系统媒体时间线失败: {e}");
+    }
+}
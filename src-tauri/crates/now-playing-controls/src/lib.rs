@@ -2,7 +2,7 @@
 //!
 //! 目前支持 Windows、Linux 和 MacOS 的媒体控件交互
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Result;
 use tokio::sync::mpsc;
@@ -11,6 +11,7 @@ mod coordinator;
 mod discord;
 pub mod model;
 mod platform;
+pub mod timeline;
 
 use crate::{
     coordinator::Command,
@@ -56,6 +57,23 @@ impl NowPlayingSession {
         let _ = self.tx.send(Command::UpdatePlayState(payload));
     }
 
+    /// 上报当前播放状态和进度。只有当系统外推的进度偏离超过
+    /// [`timeline::DIVERGENCE_THRESHOLD`] 或播放状态变化时才会推送给系统，
+    /// 因此可以按较高频率调用
+    pub fn update_playback_state(&self, playing: bool, position_ms: u64) {
+        let _ = self.tx.send(Command::UpdatePlaybackState {
+            playing,
+            position: Duration::from_millis(position_ms),
+        });
+    }
+
+    /// 显式跳转后立即推送进度（MPRIS 会发出 Seeked 信号）
+    pub fn notify_seek(&self, position_ms: u64) {
+        let _ = self
+            .tx
+            .send(Command::NotifySeek(Duration::from_millis(position_ms)));
+    }
+
     pub fn update_playback_rate(&self, rate: f64) {
         let _ = self.tx.send(Command::UpdatePlaybackRate(rate));
     }
@@ -13,6 +13,10 @@ pub enum SystemMediaEventType {
     SetVolume,
     /// 绝对位置，毫秒
     Seek,
+    /// 请求显示主窗口（仅 MPRIS）
+    Raise,
+    /// 请求退出应用（仅 MPRIS）
+    Quit,
 }
 
 #[derive(Clone, Debug)]
@@ -143,6 +147,11 @@ pub struct TimelinePayload {
 
     /// 是否为 seek 操作触发的更新
     pub seeked: Option<bool>,
+
+    /// 系统外推进度所用的播放速率，暂停时为 0。`None` 表示不更新速率
+    ///
+    /// 目前只有 macOS 使用（`MPNowPlayingInfoPropertyPlaybackRate`）
+    pub rate: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy)]
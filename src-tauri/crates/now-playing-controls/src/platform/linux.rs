@@ -52,6 +52,8 @@ impl LinuxImpl {
             .can_go_previous(true)
             .can_seek(true)
             .can_control(true)
+            .can_raise(true)
+            .can_quit(true)
             .minimum_rate(0.2)
             .maximum_rate(2.0)
             .playback_status(MprisPlaybackStatus::Stopped);
@@ -163,6 +165,20 @@ impl LinuxImpl {
             d(SystemMediaEvent::set_volume(new_volume));
         });
 
+        // 显示主窗口
+        let d = dispatch.clone();
+        player.connect_raise(move |_| {
+            debug!("收到 raise 命令");
+            d(SystemMediaEvent::new(SystemMediaEventType::Raise));
+        });
+
+        // 退出
+        let d = dispatch.clone();
+        player.connect_quit(move |_| {
+            debug!("收到 quit 命令");
+            d(SystemMediaEvent::new(SystemMediaEventType::Quit));
+        });
+
         // 相对跳转
         // 通过 Player 内部维护的进度来计算绝对跳转位置
         let d = dispatch.clone();
@@ -440,6 +440,14 @@ impl MacosImpl {
                 NSNumber::new_f64(payload.total_time.as_secs_f64()),
             );
 
+            // 外推速率，暂停时为 0，避免 Control Center 继续走进度
+            if let Some(rate) = payload.rate {
+                self.set_info_object(
+                    MPNowPlayingInfoPropertyPlaybackRate,
+                    NSNumber::new_f64(rate),
+                );
+            }
+
             self.np_info_ctr.setNowPlayingInfo(Some(&self.info));
         }
         Ok(())
@@ -0,0 +1,205 @@
+//! 系统媒体时间线的推送节流
+//!
+//! SMTC / Control Center / MPRIS 会根据上一次推送的进度和播放速率自行外推当前进度，
+//! 所以没有必要频繁推送。这里记录上一次推送时的锚点（进度、单调时钟时间戳、速率、
+//! 是否在播放），只有当系统外推出的进度与真实进度相差超过 [`DIVERGENCE_THRESHOLD`]
+//! （跳转、暂停、恢复、改变速率）时才需要重新推送。
+
+use std::time::{Duration, Instant};
+
+/// 外推进度与真实进度的最大允许偏差
+pub const DIVERGENCE_THRESHOLD: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    position: Duration,
+    at: Instant,
+    rate: f64,
+    playing: bool,
+}
+
+impl Anchor {
+    /// 系统在 `now` 时刻外推出的进度
+    fn extrapolate(&self, now: Instant) -> Duration {
+        if !self.playing {
+            return self.position;
+        }
+        let elapsed = now.saturating_duration_since(self.at).as_secs_f64() * self.rate;
+        self.position + Duration::from_secs_f64(elapsed.max(0.0))
+    }
+}
+
+/// 需要推送给系统的时间线
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelinePush {
+    pub position: Duration,
+    /// 系统用于外推的速率，暂停时为 0
+    pub rate: f64,
+}
+
+#[derive(Debug)]
+pub struct TimelineTracker {
+    anchor: Option<Anchor>,
+    rate: f64,
+    playing: bool,
+    /// 当前歌曲总时长
+    pub total: Duration,
+}
+
+impl Default for TimelineTracker {
+    fn default() -> Self {
+        Self {
+            anchor: None,
+            rate: 1.0,
+            playing: false,
+            total: Duration::ZERO,
+        }
+    }
+}
+
+impl TimelineTracker {
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// 记录一次播放状态上报，返回是否需要重新推送时间线
+    pub fn observe(
+        &mut self,
+        playing: bool,
+        position: Duration,
+        now: Instant,
+    ) -> Option<TimelinePush> {
+        self.playing = playing;
+        let diverged = self.anchor.map_or(true, |anchor| {
+            anchor.playing != playing
+                || abs_diff(anchor.extrapolate(now), position) > DIVERGENCE_THRESHOLD
+        });
+        diverged.then(|| self.reanchor(position, now))
+    }
+
+    /// 显式跳转，总是需要推送
+    pub fn seek(&mut self, position: Duration, now: Instant) -> TimelinePush {
+        self.reanchor(position, now)
+    }
+
+    /// 播放 / 暂停切换。进度取系统当前的外推值
+    pub fn set_playing(&mut self, playing: bool, now: Instant) -> Option<TimelinePush> {
+        if self.playing == playing {
+            return None;
+        }
+        self.playing = playing;
+        let position = self.anchor?.extrapolate(now);
+        Some(self.reanchor(position, now))
+    }
+
+    /// 改变播放速率。进度取系统当前的外推值
+    pub fn set_rate(&mut self, rate: f64, now: Instant) -> Option<TimelinePush> {
+        if !rate.is_finite() || rate <= 0.0 || (rate - self.rate).abs() < f64::EPSILON {
+            return None;
+        }
+        let position = self.anchor.map(|anchor| anchor.extrapolate(now));
+        self.rate = rate;
+        position.map(|position| self.reanchor(position, now))
+    }
+
+    /// 外部直接推送了时间线（如 `update_timeline`），以它为新的锚点
+    pub fn pushed(&mut self, position: Duration, now: Instant) {
+        self.reanchor(position, now);
+    }
+
+    /// 切歌时丢弃锚点，下一次上报一定会推送
+    pub fn reset(&mut self) {
+        self.anchor = None;
+    }
+
+    fn reanchor(&mut self, position: Duration, now: Instant) -> TimelinePush {
+        self.anchor = Some(Anchor {
+            position,
+            at: now,
+            rate: self.rate,
+            playing: self.playing,
+        });
+        TimelinePush {
+            position,
+            rate: if self.playing { self.rate } else { 0.0 },
+        }
+    }
+}
+
+fn abs_diff(a: Duration, b: Duration) -> Duration {
+    a.checked_sub(b)
+        .or_else(|| b.checked_sub(a))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    const fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn steady_playback_is_not_repushed() {
+        let start = Instant::now();
+        let mut tracker = TimelineTracker::default();
+        assert!(tracker.observe(true, secs(10), start).is_some());
+        // 正常播放：真实进度与外推一致，即使有少量抖动
+        assert!(tracker.observe(true, secs(15), start + secs(5)).is_none());
+        let jitter = secs(20) + Duration::from_millis(600);
+        assert!(tracker.observe(true, jitter, start + secs(10)).is_none());
+    }
+
+    #[test]
+    fn seek_and_stall_are_detected() {
+        let start = Instant::now();
+        let mut tracker = TimelineTracker::default();
+        tracker.observe(true, secs(10), start);
+        // 往后跳转
+        let push = tracker.observe(true, secs(60), start + secs(2)).unwrap();
+        assert_eq!(push.position, secs(60));
+        // 缓冲卡住：真实进度不动，外推已经走了 3 秒
+        assert!(tracker.observe(true, secs(61), start + secs(6)).is_some());
+    }
+
+    #[test]
+    fn pause_and_resume_push_with_zero_rate_while_paused() {
+        let start = Instant::now();
+        let mut tracker = TimelineTracker::default();
+        tracker.observe(true, secs(10), start);
+        let paused = tracker.observe(false, secs(12), start + secs(2)).unwrap();
+        assert_eq!(paused.rate, 0.0);
+        // 暂停时外推停止，长时间后也不会偏离
+        assert!(tracker
+            .observe(false, secs(12), start + secs(600))
+            .is_none());
+        let resumed = tracker.set_playing(true, start + secs(601)).unwrap();
+        assert_eq!(resumed.position, secs(12));
+        assert_eq!(resumed.rate, 1.0);
+    }
+
+    #[test]
+    fn rate_change_reanchors_at_extrapolated_position() {
+        let start = Instant::now();
+        let mut tracker = TimelineTracker::default();
+        tracker.observe(true, secs(0), start);
+        let push = tracker.set_rate(2.0, start + secs(4)).unwrap();
+        assert_eq!(push.position, secs(4));
+        assert_eq!(push.rate, 2.0);
+        // 2 倍速下 3 秒后到 10 秒
+        assert!(tracker.observe(true, secs(10), start + secs(7)).is_none());
+        assert!(tracker.set_rate(2.0, start + secs(8)).is_none());
+    }
+
+    #[test]
+    fn explicit_seek_and_reset_always_push() {
+        let start = Instant::now();
+        let mut tracker = TimelineTracker::default();
+        tracker.observe(true, secs(5), start);
+        assert_eq!(tracker.seek(secs(5), start).position, secs(5));
+        tracker.reset();
+        assert!(tracker.observe(true, secs(5), start).is_some());
+    }
+}
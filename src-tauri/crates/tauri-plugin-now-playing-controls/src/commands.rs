@@ -15,7 +15,7 @@ use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Manager, Runtime};
 use tracing::warn;
 
-const MEDIA_ACTION_EVENT: &str = "now-playing-controls:media-action";
+pub const MEDIA_ACTION_EVENT: &str = "now-playing-controls:media-action";
 const MAX_COVER_BYTES: u64 = 4 * 1024 * 1024;
 
 #[derive(Default)]
@@ -73,6 +73,22 @@ pub struct MediaActionPayload {
 }
 
 impl NowPlayingState {
+    /// Push to the running session only; never creates one.
+    pub(crate) fn sync_playback(&self, playing: bool, position_ms: u64, seeked: bool) {
+        let Some(session) = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.session.clone())
+        else {
+            return;
+        };
+        session.update_playback_state(playing, position_ms);
+        if seeked {
+            session.notify_seek(position_ms);
+        }
+    }
+
     fn ensure_session<R: Runtime>(
         &self,
         app: &tauri::AppHandle<R>,
@@ -145,6 +161,8 @@ impl From<SystemMediaEvent> for MediaActionPayload {
             SystemMediaEventType::SetRate => "setRate",
             SystemMediaEventType::SetVolume => "setVolume",
             SystemMediaEventType::Seek => "seek",
+            SystemMediaEventType::Raise => "raise",
+            SystemMediaEventType::Quit => "quit",
         }
         .to_string();
 
@@ -229,6 +247,7 @@ pub async fn update_state<R: Runtime>(
             current_time: duration_from_secs(current_time),
             total_time: duration_from_secs(total_time),
             seeked: None,
+            rate: None,
         });
     }
 
@@ -258,10 +277,36 @@ pub fn update_timeline<R: Runtime>(
         current_time: duration_from_secs(payload.position),
         total_time: duration_from_secs(payload.duration.unwrap_or_else(|| state.last_duration())),
         seeked: payload.seeked,
+        rate: None,
     });
     Ok(())
 }
 
+/// High-frequency progress report; the session only re-pushes the OS
+/// timeline when its extrapolated position drifts or play state changes.
+#[tauri::command]
+pub fn update_playback_state<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, NowPlayingState>,
+    playing: bool,
+    position_ms: u64,
+) -> Result<(), String> {
+    let session = state.ensure_session(&app)?;
+    session.update_playback_state(playing, position_ms);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn notify_seek<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, NowPlayingState>,
+    position_ms: u64,
+) -> Result<(), String> {
+    let session = state.ensure_session(&app)?;
+    session.notify_seek(position_ms);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn update_play_mode<R: Runtime>(
     app: tauri::AppHandle<R>,
@@ -1,8 +1,12 @@
 mod commands;
 
+/// Every system media action, as `{ action, position?, rate?, volume? }`.
+/// `raise` and `quit` come from MPRIS and are left to the app's backend.
+pub use commands::MEDIA_ACTION_EVENT;
+
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    AppHandle, Manager, Runtime,
 };
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
@@ -10,8 +14,10 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         .invoke_handler(tauri::generate_handler![
             commands::clear,
             commands::initialize,
+            commands::notify_seek,
             commands::set_enabled,
             commands::update_play_mode,
+            commands::update_playback_state,
             commands::update_state,
             commands::update_timeline,
         ])
@@ -21,3 +27,17 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         })
         .build()
 }
+
+/// Called by the app's playback clock on play, pause and seek instead of
+/// periodic progress reports. Does nothing until the frontend initialized
+/// the session.
+pub fn sync_playback<R: Runtime>(
+    app: &AppHandle<R>,
+    playing: bool,
+    position_ms: u64,
+    seeked: bool,
+) {
+    if let Some(state) = app.try_state::<commands::NowPlayingState>() {
+        state.sync_playback(playing, position_ms, seeked);
+    }
+}
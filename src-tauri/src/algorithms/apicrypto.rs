@@ -0,0 +1,292 @@
+//! Request parameter encryption / signing for upstream music APIs.
+//!
+//! Pure crypto only — nothing here touches the network. The frontend (or the
+//! backend HTTP helpers) builds the request and uses these to produce the
+//! encrypted form fields:
+//!
+//! - `weapi`: AES-128-CBC twice (preset key, then a random per-call key),
+//!   with the per-call key sent RSA-encrypted as `encSecKey`.
+//! - `eapi`: MD5-digested envelope, AES-128-ECB, upper-case hex.
+//! - `linuxapi`: the request wrapped as `{method, url, params}` JSON,
+//!   AES-128-ECB, upper-case hex.
+//! - `md5_sign`: sorted `k=v&...` form plus a shared secret, MD5 hex.
+
+use std::collections::BTreeMap;
+
+use aes::Aes128;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit};
+use md5::{Digest, Md5};
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::Rng;
+use serde::Serialize;
+
+/// Inputs above this size are rejected; real API payloads are a few KB.
+pub const MAX_INPUT_BYTES: usize = 1024 * 1024;
+
+const WEAPI_PRESET_KEY: &[u8; 16] = b"0CoJUm6Qyw8W8jud";
+const WEAPI_IV: &[u8; 16] = b"0102030405060708";
+const WEAPI_KEY_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+const WEAPI_RSA_EXPONENT: u32 = 0x10001;
+const WEAPI_RSA_MODULUS: &str = "00e0b509f6259df8642dbc35662901477df22677ec152b5ff68ace615bb7b725152b3ab17a876aea8a5aa76d2e417629ec4ee341f56135fccf695280104e0312ecbda92557c93870114af6c9d05c4f7f0c3685b7a46bee255932575cce10b424d813cfe4875d3e82047b97ddef52741d546b8e289dc6935b3ece0462db0a22b8e7";
+const EAPI_KEY: &[u8; 16] = b"e82ckenh8dichen8";
+const EAPI_SEPARATOR: &str = "-36cd479b6b5-";
+const LINUXAPI_KEY: &[u8; 16] = b"rFgB&h#%2?^eDg:Q";
+
+type Aes128CbcEnc = cbc::Encryptor<Aes128>;
+type Aes128EcbEnc = ecb::Encryptor<Aes128>;
+type Aes128EcbDec = ecb::Decryptor<Aes128>;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeapiForm {
+    pub params: String,
+    pub enc_sec_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EapiForm {
+    pub params: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinuxapiForm {
+    pub eparams: String,
+}
+
+fn check_size(len: usize) -> Result<(), String> {
+    if len > MAX_INPUT_BYTES {
+        return Err(format!(
+            "Input is {} bytes, larger than the {} byte limit",
+            len, MAX_INPUT_BYTES
+        ));
+    }
+    Ok(())
+}
+
+fn aes_cbc_base64(data: &[u8], key: &[u8; 16]) -> String {
+    let encrypted =
+        Aes128CbcEnc::new(key.into(), WEAPI_IV.into()).encrypt_padded_vec_mut::<Pkcs7>(data);
+    BASE64.encode(encrypted)
+}
+
+fn md5_hex(data: &[u8]) -> String {
+    hex::encode(Md5::digest(data))
+}
+
+fn random_weapi_key() -> [u8; 16] {
+    let mut key = [0u8; 16];
+    for byte in &mut key {
+        *byte = WEAPI_KEY_ALPHABET[OsRng.gen_range(0..WEAPI_KEY_ALPHABET.len())];
+    }
+    key
+}
+
+/// Textbook RSA over the reversed key, as the reference implementation does.
+fn weapi_rsa(secret_key: &[u8; 16]) -> String {
+    let mut reversed = *secret_key;
+    reversed.reverse();
+    let modulus =
+        BigUint::parse_bytes(WEAPI_RSA_MODULUS.as_bytes(), 16).expect("valid RSA modulus");
+    let encrypted = BigUint::from_bytes_be(&reversed).modpow(&WEAPI_RSA_EXPONENT.into(), &modulus);
+    format!("{:0>256}", encrypted.to_str_radix(16))
+}
+
+/// Parse a caller-supplied `weapi` key: 16 ASCII letters or digits, like
+/// the generated ones.
+pub fn parse_weapi_key(key: &str) -> Result<[u8; 16], String> {
+    let bytes: [u8; 16] = key
+        .as_bytes()
+        .try_into()
+        .map_err(|_| format!("weapi key must be 16 characters, got {}", key.len()))?;
+    if !bytes.iter().all(u8::is_ascii_alphanumeric) {
+        return Err("weapi key must be ASCII letters and digits".to_string());
+    }
+    Ok(bytes)
+}
+
+/// Encrypt a JSON body for `weapi` endpoints with a given per-call key.
+/// Only for reproducible output (tests, comparing against captures); real
+/// requests should use [`weapi_encrypt`].
+pub fn weapi_encrypt_with_key(json: &str, secret_key: &[u8; 16]) -> Result<WeapiForm, String> {
+    check_size(json.len())?;
+    let first = aes_cbc_base64(json.as_bytes(), WEAPI_PRESET_KEY);
+    Ok(WeapiForm {
+        params: aes_cbc_base64(first.as_bytes(), secret_key),
+        enc_sec_key: weapi_rsa(secret_key),
+    })
+}
+
+/// Encrypt a JSON body for `weapi` endpoints with a fresh random key.
+pub fn weapi_encrypt(json: &str) -> Result<WeapiForm, String> {
+    weapi_encrypt_with_key(json, &random_weapi_key())
+}
+
+/// Encrypt a JSON body for `eapi` endpoints. `url` is the API path
+/// (e.g. `/api/song/lyric`), not the full request URL.
+pub fn eapi_encrypt(url: &str, json: &str) -> Result<EapiForm, String> {
+    check_size(url.len() + json.len())?;
+    let digest = md5_hex(format!("nobody{}use{}md5forencrypt", url, json).as_bytes());
+    let data = format!(
+        "{url}{sep}{json}{sep}{digest}",
+        url = url,
+        sep = EAPI_SEPARATOR,
+        json = json,
+        digest = digest
+    );
+    let encrypted =
+        Aes128EcbEnc::new(EAPI_KEY.into()).encrypt_padded_vec_mut::<Pkcs7>(data.as_bytes());
+    Ok(EapiForm {
+        params: hex::encode_upper(encrypted),
+    })
+}
+
+/// Encrypt a request for `linuxapi` endpoints. `json` is the whole
+/// envelope (`{"method":"POST","url":"https://…","params":{…}}`), not just
+/// the parameters.
+pub fn linuxapi_encrypt(json: &str) -> Result<LinuxapiForm, String> {
+    check_size(json.len())?;
+    let encrypted =
+        Aes128EcbEnc::new(LINUXAPI_KEY.into()).encrypt_padded_vec_mut::<Pkcs7>(json.as_bytes());
+    Ok(LinuxapiForm {
+        eparams: hex::encode_upper(encrypted),
+    })
+}
+
+/// Decrypt an `eapi` response body (or a captured `params` value after hex decoding).
+pub fn eapi_decrypt(data: &[u8]) -> Result<String, String> {
+    check_size(data.len())?;
+    let decrypted = Aes128EcbDec::new(EAPI_KEY.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map_err(|_| "Invalid eapi ciphertext".to_string())?;
+    String::from_utf8(decrypted).map_err(|e| e.to_string())
+}
+
+/// MD5 signature over `k=v` pairs sorted by key, joined with `&`, followed
+/// by `secret`. Values are used verbatim (no URL encoding).
+pub fn md5_sign(form: &BTreeMap<String, String>, secret: &str) -> Result<String, String> {
+    let mut payload = form
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+    payload.push_str(secret);
+    check_size(payload.len())?;
+    Ok(md5_hex(payload.as_bytes()))
+}
+
+/// Tauri commands; thin wrappers so invoke names match the functions above.
+pub mod commands {
+    use std::collections::BTreeMap;
+
+    use super::{EapiForm, LinuxapiForm, WeapiForm};
+
+    /// `secret_key` fixes the per-call key for reproducible output.
+    #[tauri::command]
+    pub fn weapi_encrypt(json: String, secret_key: Option<String>) -> Result<WeapiForm, String> {
+        match secret_key {
+            Some(key) => super::weapi_encrypt_with_key(&json, &super::parse_weapi_key(&key)?),
+            None => super::weapi_encrypt(&json),
+        }
+    }
+
+    #[tauri::command]
+    pub fn eapi_encrypt(url: String, json: String) -> Result<EapiForm, String> {
+        super::eapi_encrypt(&url, &json)
+    }
+
+    #[tauri::command]
+    pub fn linuxapi_encrypt(json: String) -> Result<LinuxapiForm, String> {
+        super::linuxapi_encrypt(&json)
+    }
+
+    #[tauri::command]
+    pub fn eapi_decrypt(data: Vec<u8>) -> Result<String, String> {
+        super::eapi_decrypt(&data)
+    }
+
+    #[tauri::command]
+    pub fn md5_sign(form: BTreeMap<String, String>, secret: String) -> Result<String, String> {
+        super::md5_sign(&form, &secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fixtures computed independently with OpenSSL using the reference
+    // keys, IV and envelope format.
+    const BODY: &str = r#"{"id":"347230","c":"[{\"id\":347230}]"}"#;
+
+    #[test]
+    fn weapi_matches_reference() {
+        let form =
+            weapi_encrypt_with_key(BODY, &parse_weapi_key("abcdefghijklmnop").unwrap()).unwrap();
+        assert_eq!(
+            form.params,
+            "eUFgifluj1Fx1eg2TfeLecJYjGA/njlJRpGZRehek7fu6IZruqHjV0mq4EOYiYgIKg4iRq7thUv8NLbR8i88nI7+BU/92Jb5Vao2rDJ5GlE="
+        );
+        assert_eq!(
+            form.enc_sec_key,
+            "d15a1683c992095d0c234c19966605c5c5964911268bbeda8cb8d08d834913e59d53b32358903a121b5fca784c1f5ae44951fd02524df58ecc98e52cc7cf8689b42c2e93ddf05b0592512d87f5960467e2f086c018849d76014d323500e30f13ef4cafbb0cf5a66731a3f1776c75ca35d0062dac70a3e33245afabcf47938487"
+        );
+    }
+
+    #[test]
+    fn weapi_keys_are_random_and_well_formed() {
+        let a = weapi_encrypt(BODY).unwrap();
+        let b = weapi_encrypt(BODY).unwrap();
+        assert_ne!(a.enc_sec_key, b.enc_sec_key);
+        assert_eq!(a.enc_sec_key.len(), 256);
+        let key = random_weapi_key();
+        assert!(key.iter().all(|c| c.is_ascii_alphanumeric()));
+        assert!(parse_weapi_key("short").is_err());
+        assert!(parse_weapi_key("abcdefghijklmno!").is_err());
+    }
+
+    #[test]
+    fn eapi_matches_reference_and_round_trips() {
+        let form = eapi_encrypt("/api/song/lyric", BODY).unwrap();
+        assert_eq!(
+            form.params,
+            "9B1595479B79266065903BA79E9883029338432CD7D98D1A3626E997A2C5312134648387F96CBB109B417039D277B3F045FCA6E46A4E793AD1AA8260CCF696C3D2CE5ADBFB1A4C74CD843CED618200C85BDCC7EA19858465833B4566E9761B7E72CAA87355C7AB61CD95A7BBCA5CE8846AA3B102FBE7296AB0DB9EA5C46AD12B"
+        );
+        let decrypted = eapi_decrypt(&hex::decode(&form.params).unwrap()).unwrap();
+        assert!(decrypted.starts_with("/api/song/lyric-36cd479b6b5-"));
+        assert!(eapi_decrypt(b"not a multiple of 16").is_err());
+    }
+
+    #[test]
+    fn linuxapi_matches_reference() {
+        let envelope = r#"{"method":"POST","url":"https://music.163.com/api/song/lyric","params":{"id":"347230"}}"#;
+        assert_eq!(
+            linuxapi_encrypt(envelope).unwrap().eparams,
+            "A0D9583F4C5FF68DE851D2893A49DE98FAFB24399F27B4F7E74C64B6FC49A965CFA972FA5EA3D6247CD6247C8198CB876BA9315A2F4B205B4E87A713A33C1C5DAA7B445EDD95E98E7565DEA65206007E51B21BDDC87A055E521CE03A43841DED"
+        );
+    }
+
+    #[test]
+    fn md5_sign_sorts_keys() {
+        let form: BTreeMap<String, String> = [("c", "3"), ("a", "1"), ("b", "x y")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(
+            md5_sign(&form, "secret").unwrap(),
+            "db236131500cd6e425ad95f9aa559a84"
+        );
+    }
+
+    #[test]
+    fn oversized_inputs_are_rejected() {
+        let big = "x".repeat(MAX_INPUT_BYTES + 1);
+        assert!(weapi_encrypt(&big).is_err());
+        assert!(eapi_encrypt("/api", &big).is_err());
+        assert!(linuxapi_encrypt(&big).is_err());
+        assert!(eapi_decrypt(big.as_bytes()).is_err());
+    }
+}
@@ -1,16 +1,52 @@
-// 定义一个通用的类型，用于指定分块后的子数组类型
-pub type ChunkedArray<T> = Vec<Vec<T>>;
+use serde_json::Value;
 
 /**
  * 将输入数组按照指定的大小进行分块。
  *
+ * 最后一块可能少于 `size` 个元素；空数组返回空结果。
+ * `size` 为 0 时无法分块，返回错误（而不是 panic）。
+ *
  * @param input - 需要被分块的原始数组。
- * @param size - 每个块应该包含的元素数量。
+ * @param size - 每个块应该包含的元素数量，必须大于 0。
  * @returns 一个包含按指定大小分组的子数组的新数组。
  */
-pub fn chunk<T>(input: &[T], size: usize) -> ChunkedArray<T>
+pub fn chunk<T>(input: &[T], size: usize) -> Result<Vec<Vec<T>>, String>
 where
     T: Clone,
 {
-    input.chunks(size).map(|chunk| chunk.to_vec()).collect()
+    if size == 0 {
+        return Err("Chunk size must be greater than 0".into());
+    }
+    Ok(input.chunks(size).map(|chunk| chunk.to_vec()).collect())
+}
+
+/// 供前端调用的分块命令，元素为任意 JSON 值。
+#[tauri::command]
+pub fn get_chunk(input: Vec<Value>, size: usize) -> Result<Vec<Vec<Value>>, String> {
+    chunk(&input, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_split_evenly_and_keep_the_remainder() {
+        assert_eq!(
+            chunk(&[1, 2, 3, 4], 2).unwrap(),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        assert_eq!(
+            chunk(&[1, 2, 3, 4, 5], 2).unwrap(),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+        assert_eq!(chunk(&[1, 2], 10).unwrap(), vec![vec![1, 2]]);
+        assert!(chunk::<i32>(&[], 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn zero_size_is_an_error() {
+        assert!(chunk(&[1, 2, 3], 0).is_err());
+        assert!(chunk::<i32>(&[], 0).is_err());
+    }
 }
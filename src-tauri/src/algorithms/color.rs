@@ -0,0 +1,313 @@
+//! Theme colors from album art.
+//!
+//! The image is shrunk to at most 64×64 and its opaque pixels are split by
+//! median cut: the box of pixels with the widest spread (weighted by how
+//! many pixels it holds) is halved along its widest channel until there
+//! are as many boxes as colors wanted. Each box's average is one palette
+//! color. This runs natively because the webview cannot read pixels of
+//! covers served without CORS headers.
+//!
+//! Palettes are memoized by the MD5 of the image bytes, so the same cover
+//! under different URLs is only quantized once.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use md5::{Digest, Md5};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::cover_cache::{self, CoverCacheError};
+use crate::covers::MAX_INPUT_BYTES;
+
+const SAMPLE_EDGE: u32 = 64;
+const DEFAULT_COUNT: usize = 5;
+const MAX_COUNT: usize = 16;
+/// WCAG AA contrast for normal text.
+const MIN_CONTRAST: f64 = 4.5;
+/// Palettes kept at most; the memo starts over when it is full.
+const MEMO_CAPACITY: usize = 256;
+
+/// MD5 of the image bytes and the color count asked for.
+type MemoKey = ([u8; 16], usize);
+
+static MEMO: LazyLock<Mutex<HashMap<MemoKey, ColorPalette>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum ColorError {
+    /// Neither an http(s) URL nor a readable file.
+    InvalidSource(String),
+    Download(String),
+    /// Not a decodable image, or one without opaque pixels.
+    InvalidImage(String),
+    Io(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSource(source) => write!(f, "Invalid image source '{}'", source),
+            Self::Download(message) => write!(f, "Image download failed: {}", message),
+            Self::InvalidImage(message) => write!(f, "Invalid image: {}", message),
+            Self::Io(message) => write!(f, "Failed to read image: {}", message),
+        }
+    }
+}
+
+impl From<CoverCacheError> for ColorError {
+    fn from(e: CoverCacheError) -> Self {
+        match e {
+            CoverCacheError::InvalidUrl(url) => Self::InvalidSource(url),
+            CoverCacheError::Download(message) => Self::Download(message),
+            CoverCacheError::InvalidImage(message) => Self::InvalidImage(message),
+            CoverCacheError::Io(message) => Self::Io(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteColor {
+    pub rgb: [u8; 3],
+    /// Pixels of the 64×64 sample in this color's box.
+    pub population: u32,
+    /// Closer to black than to white, i.e. white text reads better on it.
+    pub is_dark: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorPalette {
+    /// Most common first.
+    pub colors: Vec<PaletteColor>,
+    /// The dominant color.
+    pub accent: [u8; 3],
+    /// The most common palette color readable on `accent`, or white or
+    /// black when none is.
+    pub foreground: [u8; 3],
+    pub contrast: f64,
+}
+
+/// WCAG relative luminance.
+fn luminance(rgb: [u8; 3]) -> f64 {
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(rgb[0]) + 0.7152 * linear(rgb[1]) + 0.0722 * linear(rgb[2])
+}
+
+fn contrast(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// The channel with the widest spread in `pixels`, and that spread.
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, 0), |(min, max), p| {
+                (min.min(p[channel]), max.max(p[channel]))
+            });
+            (channel, max.saturating_sub(min))
+        })
+        .max_by_key(|&(_, spread)| spread)
+        .unwrap_or((0, 0))
+}
+
+fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<PaletteColor> {
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .map(|(i, pixels)| (i, widest_channel(pixels), pixels.len()))
+            .filter(|&(_, (_, spread), _)| spread > 0)
+            .max_by_key(|&(_, (_, spread), len)| u64::from(spread) * len as u64);
+        let Some((i, (channel, _), _)) = widest else {
+            break;
+        };
+        let mut lower = boxes.swap_remove(i);
+        lower.sort_unstable_by_key(|p| p[channel]);
+        // Cut at the median, moved to a change in value so that pixels of
+        // one color never end up in both halves.
+        let median = lower[lower.len() / 2][channel];
+        let mut at = lower.partition_point(|p| p[channel] < median);
+        if at == 0 {
+            at = lower.partition_point(|p| p[channel] <= median);
+        }
+        let upper = lower.split_off(at);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+    let mut colors: Vec<PaletteColor> = boxes
+        .into_iter()
+        .filter(|pixels| !pixels.is_empty())
+        .map(|pixels| {
+            let mut sum = [0u64; 3];
+            for p in &pixels {
+                for (total, &c) in sum.iter_mut().zip(p) {
+                    *total += u64::from(c);
+                }
+            }
+            let n = pixels.len() as u64;
+            let rgb = sum.map(|total| ((total + n / 2) / n) as u8);
+            PaletteColor {
+                rgb,
+                population: pixels.len() as u32,
+                is_dark: contrast(rgb, [255; 3]) > contrast(rgb, [0; 3]),
+            }
+        })
+        .collect();
+    colors.sort_by_key(|color| Reverse(color.population));
+    colors
+}
+
+fn palette(bytes: &[u8], count: usize) -> Result<ColorPalette, ColorError> {
+    let sample = cover_cache::decode(bytes)?
+        .thumbnail(SAMPLE_EDGE, SAMPLE_EDGE)
+        .to_rgba8();
+    let pixels: Vec<[u8; 3]> = sample
+        .pixels()
+        .filter(|p| p[3] >= 128)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() {
+        return Err(ColorError::InvalidImage("no opaque pixels".into()));
+    }
+    let colors = median_cut(pixels, count);
+    let accent = colors[0].rgb;
+    let foreground = colors[1..]
+        .iter()
+        .map(|color| color.rgb)
+        .find(|&rgb| contrast(rgb, accent) >= MIN_CONTRAST)
+        .unwrap_or(if colors[0].is_dark { [255; 3] } else { [0; 3] });
+    Ok(ColorPalette {
+        contrast: contrast(accent, foreground),
+        colors,
+        accent,
+        foreground,
+    })
+}
+
+fn extract(bytes: &[u8], count: usize) -> Result<ColorPalette, ColorError> {
+    let key = (Md5::digest(bytes).into(), count);
+    if let Some(palette) = MEMO.lock().get(&key) {
+        return Ok(palette.clone());
+    }
+    let palette = palette(bytes, count)?;
+    let mut memo = MEMO.lock();
+    if memo.len() >= MEMO_CAPACITY {
+        memo.clear();
+    }
+    memo.insert(key, palette.clone());
+    Ok(palette)
+}
+
+/// A local image path, checked against the folders the frontend may have
+/// the backend read (see `file_manager`). Mobile apps can only reach their
+/// own files anyway.
+fn local_path(app: &AppHandle, source: &str) -> Result<PathBuf, ColorError> {
+    #[cfg(not(mobile))]
+    {
+        crate::desktop::file_manager::resolve_readable_path(app, std::path::Path::new(source))
+            .map_err(|e| ColorError::InvalidSource(e.to_string()))
+    }
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Ok(PathBuf::from(source))
+    }
+}
+
+/// Palette of the image at `source`, an http(s) URL (fetched through the
+/// cover cache) or a local path. `count` defaults to 5 and is clamped to
+/// 1..=16; flat images can yield fewer colors.
+#[tauri::command]
+pub async fn extract_colors(
+    app: AppHandle,
+    source: String,
+    count: Option<usize>,
+) -> Result<ColorPalette, ColorError> {
+    let count = count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT);
+    let path = if source.starts_with("http://") || source.starts_with("https://") {
+        cover_cache::cached_cover(&app, &source, None).await?
+    } else {
+        local_path(&app, &source)?
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let len = std::fs::metadata(&path)
+            .map_err(|_| ColorError::InvalidSource(source))?
+            .len();
+        if len > MAX_INPUT_BYTES as u64 {
+            return Err(ColorError::InvalidImage("larger than 20 MB".into()));
+        }
+        let bytes = std::fs::read(&path).map_err(|e| ColorError::Io(e.to_string()))?;
+        extract(&bytes, count)
+    })
+    .await
+    .map_err(|e| ColorError::Io(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    use super::*;
+
+    fn png(image: &RgbImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn dominant_color_leads_with_a_readable_foreground() {
+        // Three quarters dark red, one quarter pale yellow.
+        let image = RgbImage::from_fn(128, 128, |x, _| {
+            if x < 96 {
+                Rgb([120, 10, 20])
+            } else {
+                Rgb([250, 240, 180])
+            }
+        });
+        let palette = extract(&png(&image), 3).unwrap();
+        assert_eq!(palette.colors.len(), 2);
+        assert_eq!(palette.accent, [120, 10, 20]);
+        assert!(palette.colors[0].is_dark);
+        assert_eq!(
+            palette.colors[0].population,
+            3 * palette.colors[1].population
+        );
+        assert_eq!(palette.foreground, [250, 240, 180]);
+        assert!(palette.contrast >= MIN_CONTRAST);
+    }
+
+    #[test]
+    fn low_contrast_palettes_fall_back_to_white_or_black() {
+        let image = RgbImage::from_fn(32, 32, |x, y| {
+            Rgb([200, 200 + ((x + y) % 2) as u8 * 20, 210])
+        });
+        let palette = extract(&png(&image), 2).unwrap();
+        assert_eq!(palette.foreground, [0; 3]);
+        assert!(palette.contrast >= MIN_CONTRAST);
+        assert!(matches!(
+            extract(b"\x89PNG\r\n\x1a\nbroken", 2),
+            Err(ColorError::InvalidImage(_))
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+//! Crossfade / gapless transition planning.
+//!
+//! Produces paired gain envelopes for the outgoing and incoming track plus the
+//! timing needed to schedule the incoming track, with leading/trailing silence
+//! already trimmed. Curve math matches `AutoMix/curves.ts` so native and JS
+//! transitions sound the same.
+
+use std::f32::consts::FRAC_PI_2;
+
+use serde::{Deserialize, Serialize};
+
+/// Sample spacing for envelope points.
+const POINT_SPACING_MS: u32 = 50;
+const MAX_SEGMENTS: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrossfadeCurve {
+    Linear,
+    #[default]
+    EqualPower,
+    SCurve,
+}
+
+impl CrossfadeCurve {
+    /// Gains `(outgoing, incoming)` at `progress` in `0.0..=1.0`.
+    pub fn gains(self, progress: f32) -> (f32, f32) {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => (1.0 - t, t),
+            Self::EqualPower => {
+                let angle = t * FRAC_PI_2;
+                (angle.cos(), angle.sin())
+            }
+            Self::SCurve => {
+                // Smootherstep mapped onto the equal-power angle.
+                let s = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+                let angle = s * FRAC_PI_2;
+                (angle.cos(), angle.sin())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GainPoint {
+    /// Milliseconds since the incoming track started.
+    pub time_ms: u32,
+    pub gain: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossfadePlan {
+    /// False when the transition is a plain gapless cut.
+    pub crossfade: bool,
+    /// Effective fade length after clamping to the available audio.
+    pub fade_ms: u32,
+    /// Start the incoming track this many ms before the outgoing track's file end.
+    pub start_before_end_ms: u32,
+    /// Seek the incoming track to this offset when it starts (lead silence skipped).
+    pub incoming_offset_ms: u32,
+    pub fade_out: Vec<GainPoint>,
+    pub fade_in: Vec<GainPoint>,
+}
+
+/// Full transition description as sent by the frontend.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionRequest {
+    pub duration_ms: u32,
+    #[serde(default)]
+    pub curve: CrossfadeCurve,
+    #[serde(default)]
+    pub out_track_tail_silence_ms: u32,
+    #[serde(default)]
+    pub in_track_lead_silence_ms: u32,
+    /// Total length of the outgoing track, when known.
+    #[serde(default)]
+    pub out_track_duration_ms: Option<u32>,
+    /// Total length of the incoming track, when known.
+    #[serde(default)]
+    pub in_track_duration_ms: Option<u32>,
+    /// Consecutive tracks from a gapless album: never fade or trim.
+    #[serde(default)]
+    pub gapless_album: bool,
+}
+
+/// Plan a crossfade of `duration_ms` with the given silence trims.
+///
+/// Silence is removed before the fade is placed: the incoming track starts
+/// `duration_ms + out_track_tail_silence_ms` before the outgoing file ends,
+/// seeked past its own lead silence, so both fades cover audible material.
+pub fn plan(
+    duration_ms: u32,
+    curve: CrossfadeCurve,
+    out_track_tail_silence_ms: u32,
+    in_track_lead_silence_ms: u32,
+) -> CrossfadePlan {
+    let fade_ms = duration_ms;
+    let (fade_out, fade_in) = envelopes(fade_ms, curve);
+    CrossfadePlan {
+        crossfade: fade_ms > 0,
+        fade_ms,
+        start_before_end_ms: fade_ms.saturating_add(out_track_tail_silence_ms),
+        incoming_offset_ms: in_track_lead_silence_ms,
+        fade_out,
+        fade_in,
+    }
+}
+
+/// Plan a transition, handling gapless albums and tracks too short for the
+/// requested fade.
+///
+/// The fade is clamped to the audible part of the outgoing track and to half
+/// of the audible part of the incoming track, so a very short incoming track
+/// is never still fading in when it should already be fading out.
+pub fn plan_transition(request: &TransitionRequest) -> CrossfadePlan {
+    if request.gapless_album {
+        return plan(0, request.curve, 0, 0);
+    }
+
+    let mut fade_ms = request.duration_ms;
+    let mut tail = request.out_track_tail_silence_ms;
+    let mut lead = request.in_track_lead_silence_ms;
+
+    if let Some(total) = request.out_track_duration_ms {
+        tail = tail.min(total);
+        fade_ms = fade_ms.min(total - tail);
+    }
+    if let Some(total) = request.in_track_duration_ms {
+        lead = lead.min(total);
+        fade_ms = fade_ms.min((total - lead) / 2);
+    }
+
+    plan(fade_ms, request.curve, tail, lead)
+}
+
+fn envelopes(fade_ms: u32, curve: CrossfadeCurve) -> (Vec<GainPoint>, Vec<GainPoint>) {
+    if fade_ms == 0 {
+        // Hard cut: outgoing is already silent, incoming plays at unity.
+        let point = |gain| vec![GainPoint { time_ms: 0, gain }];
+        return (point(0.0), point(1.0));
+    }
+
+    let segments = (fade_ms / POINT_SPACING_MS).clamp(1, MAX_SEGMENTS);
+    let mut fade_out = Vec::with_capacity(segments as usize + 1);
+    let mut fade_in = Vec::with_capacity(segments as usize + 1);
+    for i in 0..=segments {
+        let progress = i as f32 / segments as f32;
+        let time_ms = (fade_ms as u64 * i as u64 / segments as u64) as u32;
+        let (out_gain, in_gain) = curve.gains(progress);
+        fade_out.push(GainPoint {
+            time_ms,
+            gain: out_gain,
+        });
+        fade_in.push(GainPoint {
+            time_ms,
+            gain: in_gain,
+        });
+    }
+    (fade_out, fade_in)
+}
+
+/// Compute crossfade envelopes and scheduling for a track transition.
+#[tauri::command]
+pub fn plan_crossfade(request: TransitionRequest) -> CrossfadePlan {
+    plan_transition(&request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [CrossfadeCurve; 3] = [
+        CrossfadeCurve::Linear,
+        CrossfadeCurve::EqualPower,
+        CrossfadeCurve::SCurve,
+    ];
+
+    fn request(duration_ms: u32) -> TransitionRequest {
+        TransitionRequest {
+            duration_ms,
+            curve: CrossfadeCurve::EqualPower,
+            out_track_tail_silence_ms: 0,
+            in_track_lead_silence_ms: 0,
+            out_track_duration_ms: None,
+            in_track_duration_ms: None,
+            gapless_album: false,
+        }
+    }
+
+    #[test]
+    fn envelopes_are_monotonic_with_fixed_endpoints() {
+        for curve in CURVES {
+            let plan = plan(3000, curve, 0, 0);
+            assert_eq!(plan.fade_out.len(), plan.fade_in.len());
+            for pair in plan.fade_out.windows(2) {
+                assert!(pair[1].time_ms > pair[0].time_ms);
+                assert!(
+                    pair[1].gain <= pair[0].gain + 1e-6,
+                    "{curve:?} fade-out rises"
+                );
+            }
+            for pair in plan.fade_in.windows(2) {
+                assert!(
+                    pair[1].gain + 1e-6 >= pair[0].gain,
+                    "{curve:?} fade-in falls"
+                );
+            }
+            assert!((plan.fade_out[0].gain - 1.0).abs() < 1e-6);
+            assert!(plan.fade_out.last().unwrap().gain.abs() < 1e-6);
+            assert!(plan.fade_in[0].gain.abs() < 1e-6);
+            assert!((plan.fade_in.last().unwrap().gain - 1.0).abs() < 1e-6);
+            assert_eq!(plan.fade_out.last().unwrap().time_ms, 3000);
+        }
+    }
+
+    #[test]
+    fn equal_power_curves_keep_constant_power() {
+        for curve in [CrossfadeCurve::EqualPower, CrossfadeCurve::SCurve] {
+            let plan = plan(4000, curve, 0, 0);
+            for (out, inc) in plan.fade_out.iter().zip(&plan.fade_in) {
+                let power = out.gain * out.gain + inc.gain * inc.gain;
+                assert!((power - 1.0).abs() < 1e-5, "{curve:?} power {power}");
+            }
+        }
+    }
+
+    #[test]
+    fn linear_curve_keeps_constant_amplitude() {
+        let plan = plan(1000, CrossfadeCurve::Linear, 0, 0);
+        for (out, inc) in plan.fade_out.iter().zip(&plan.fade_in) {
+            assert!((out.gain + inc.gain - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn silence_trims_shift_the_schedule() {
+        let plan = plan(2000, CrossfadeCurve::Linear, 1500, 700);
+        assert_eq!(plan.start_before_end_ms, 3500);
+        assert_eq!(plan.incoming_offset_ms, 700);
+    }
+
+    #[test]
+    fn fade_is_clamped_to_short_tracks() {
+        let mut req = request(8000);
+        req.out_track_duration_ms = Some(5000);
+        req.out_track_tail_silence_ms = 1000;
+        assert_eq!(plan_transition(&req).fade_ms, 4000);
+
+        let mut req = request(8000);
+        req.in_track_duration_ms = Some(6000);
+        req.in_track_lead_silence_ms = 2000;
+        assert_eq!(plan_transition(&req).fade_ms, 2000);
+
+        let mut req = request(8000);
+        req.out_track_duration_ms = Some(500);
+        req.out_track_tail_silence_ms = 900;
+        let plan = plan_transition(&req);
+        assert_eq!(plan.fade_ms, 0);
+        assert!(!plan.crossfade);
+        assert_eq!(plan.start_before_end_ms, 500);
+    }
+
+    #[test]
+    fn gapless_albums_never_fade_or_trim() {
+        let mut req = request(5000);
+        req.gapless_album = true;
+        req.out_track_tail_silence_ms = 800;
+        req.in_track_lead_silence_ms = 300;
+        let plan = plan_transition(&req);
+        assert!(!plan.crossfade);
+        assert_eq!(plan.start_before_end_ms, 0);
+        assert_eq!(plan.incoming_offset_ms, 0);
+        assert_eq!(plan.fade_in[0].gain, 1.0);
+    }
+}
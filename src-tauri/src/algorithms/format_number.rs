@@ -1,19 +1,167 @@
-pub fn format_number(num: f64, language_data: &str) -> String {
-    if num == 0.0 || num < 10000.0 {
-        num.to_string()
-    } else if num < 100000000.0 {
-        let num_string = format!("{:.1}", num / 10000.0);
-        if num_string.ends_with(".0") {
-            format!("{}{}", &num_string[..num_string.len() - 2], language_data)
-        } else {
-            format!("{}{}", num_string, language_data)
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// 大数字的写法，由前端传入的语言标识解析而来。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum NumberStyle {
+    /// 万 / 亿，保留一位小数：`12.3万`
+    Chinese,
+    /// K / M / B，保留一位小数：`12.3K`
+    English,
+    /// 只加千位分隔符：`12,345`
+    Grouped,
+}
+
+impl FromStr for NumberStyle {
+    type Err = String;
+
+    /// 接受 `zh` / `zh-CN` 之类的语言标签，也接受 `chinese`、`english`、
+    /// `grouped`（或 `raw`）这几个名字，大小写不敏感。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let language = lower.split(['-', '_']).next().unwrap_or_default();
+        match (lower.as_str(), language) {
+            ("chinese", _) | (_, "zh") => Ok(Self::Chinese),
+            ("english", _) | (_, "en") => Ok(Self::English),
+            ("grouped" | "raw", _) => Ok(Self::Grouped),
+            _ => Err(format!("Unknown number style '{}'", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for NumberStyle {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// 按 `units` 的进位写出非负数；四舍五入后够进位的（如 9999.99万）写成下一级。
+fn abbreviate(num: f64, base: f64, units: &[&str]) -> String {
+    if num < base {
+        return num.to_string();
+    }
+    let mut scale = base;
+    let mut unit = 0;
+    loop {
+        let rounded = (num / scale * 10.0).round() / 10.0;
+        if rounded < base || unit + 1 == units.len() {
+            let text = format!("{:.1}", rounded);
+            let text = text.strip_suffix(".0").unwrap_or(&text);
+            return format!("{}{}", text, units[unit]);
+        }
+        scale *= base;
+        unit += 1;
+    }
+}
+
+/// 整数部分每三位加一个逗号，小数部分原样保留。
+fn group_thousands(num: f64) -> String {
+    let text = num.to_string();
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text.as_str(), None),
+    };
+    let mut out = String::with_capacity(text.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push(',');
         }
+        out.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        out.push('.');
+        out.push_str(fraction);
+    }
+    out
+}
+
+/**
+ * 将数字格式化为便于阅读的字符串。
+ *
+ * 中文在 1 万以下原样输出，以上写成 `万` / `亿`；英文在 1000 以下原样输出，
+ * 以上写成 `K` / `M` / `B`；`Grouped` 只加千位分隔符。负数保留负号。
+ *
+ * @param num - 需要格式化的数字，NaN 与无穷大返回错误。
+ * @param style - 写法。
+ */
+pub fn format_number(num: f64, style: NumberStyle) -> Result<String, String> {
+    if !num.is_finite() {
+        return Err(format!("Cannot format {}", num));
+    }
+    let text = match style {
+        NumberStyle::Chinese => abbreviate(num.abs(), 1e4, &["万", "亿"]),
+        NumberStyle::English => abbreviate(num.abs(), 1e3, &["K", "M", "B"]),
+        NumberStyle::Grouped => group_thousands(num.abs()),
+    };
+    Ok(if num < 0.0 {
+        format!("-{}", text)
     } else {
-        let num_string = format!("{:.1}", num / 100000000.0);
-        if num_string.ends_with(".0") {
-            format!("{}{}", &num_string[..num_string.len() - 2], language_data)
-        } else {
-            format!("{}{}", num_string, language_data)
+        text
+    })
+}
+
+pub mod commands {
+    use super::NumberStyle;
+
+    /// `language_data` 为语言标签或写法名，见 [`NumberStyle`]。
+    #[tauri::command]
+    pub fn format_number(num: f64, language_data: NumberStyle) -> Result<String, String> {
+        super::format_number(num, language_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_by_style() {
+        use NumberStyle::*;
+        let cases: &[(f64, NumberStyle, &str)] = &[
+            (0.0, Chinese, "0"),
+            (-0.0, Chinese, "0"),
+            (12.5, Chinese, "12.5"),
+            (9_999.0, Chinese, "9999"),
+            (10_000.0, Chinese, "1万"),
+            (12_345.0, Chinese, "1.2万"),
+            (-12_345.0, Chinese, "-1.2万"),
+            (99_994_999.0, Chinese, "9999.5万"),
+            (99_999_999.0, Chinese, "1亿"),
+            (123_456_789.0, Chinese, "1.2亿"),
+            (1e13, Chinese, "100000亿"),
+            (999.0, English, "999"),
+            (1_000.0, English, "1K"),
+            (999_960.0, English, "1M"),
+            (2_500_000.0, English, "2.5M"),
+            (-7_250_000_000.0, English, "-7.3B"),
+            (1e13, English, "10000B"),
+            (999.0, Grouped, "999"),
+            (1_000.0, Grouped, "1,000"),
+            (-1_234_567.5, Grouped, "-1,234,567.5"),
+            (100_000.0, Grouped, "100,000"),
+        ];
+        for &(num, style, expected) in cases {
+            assert_eq!(
+                format_number(num, style).unwrap(),
+                expected,
+                "{} as {:?}",
+                num,
+                style
+            );
         }
+        assert!(format_number(f64::NAN, Chinese).is_err());
+        assert!(format_number(f64::NEG_INFINITY, Grouped).is_err());
+    }
+
+    #[test]
+    fn styles_parse_from_language_tags() {
+        assert_eq!("zh-CN".parse(), Ok(NumberStyle::Chinese));
+        assert_eq!("en_US".parse(), Ok(NumberStyle::English));
+        assert_eq!("Raw".parse(), Ok(NumberStyle::Grouped));
+        assert!("万".parse::<NumberStyle>().is_err());
     }
 }
@@ -1,2 +1,8 @@
+pub mod apicrypto;
 pub mod chunk;
+pub mod color;
+pub mod crossfade;
 pub mod format_number;
+pub mod ncm;
+pub mod qr;
+pub mod search;
@@ -0,0 +1,547 @@
+//! NetEase Cloud Music `.ncm` containers.
+//!
+//! Layout, all lengths little-endian `u32`:
+//!
+//! - `CTENFDAM` magic and two unused bytes.
+//! - Key chunk: XOR `0x64`, AES-128-ECB with the core key, then
+//!   `neteasecloudmusic` and the per-file RC4 key.
+//! - Metadata chunk (may be empty): XOR `0x63`, `163 key(Don't modify):`,
+//!   base64, AES-128-ECB with the meta key, then `music:` and JSON.
+//! - CRC and five unused bytes, the cover frame length, the image length
+//!   and the image; the frame may be padded past the image.
+//! - The audio, XORed with a keystream from the RC4 key schedule. The
+//!   keystream repeats every 256 bytes, so it is worked out once.
+//!
+//! [`read_header`] parses everything up to the audio and [`decrypt_audio`]
+//! streams the rest into any writer, a file or a `Vec<u8>`.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use aes::Aes128;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyInit};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const NCM_DECRYPT_PROGRESS_EVENT: &str = "ncm-decrypt-progress";
+
+const MAGIC: &[u8; 8] = b"CTENFDAM";
+const CORE_KEY: &[u8; 16] = b"hzHRAmso5kInbaxW";
+const META_KEY: &[u8; 16] = b"#14ljk_!\\]&0U<'(";
+const KEY_XOR: u8 = 0x64;
+const META_XOR: u8 = 0x63;
+const KEY_PREFIX: &[u8] = b"neteasecloudmusic";
+const META_PREFIX: &[u8] = b"163 key(Don't modify):";
+/// Chunks above this are not plausible and would only waste memory.
+const MAX_CHUNK_BYTES: u32 = 16 * 1024 * 1024;
+const BUFFER_BYTES: usize = 64 * 1024;
+/// Files smaller than this decrypt too fast for progress to matter.
+const PROGRESS_MIN_BYTES: u64 = 8 * 1024 * 1024;
+const PROGRESS_EVERY_BYTES: u64 = 1024 * 1024;
+const OUTPUT_DIR: &str = "ncm";
+const EXTENSION: &str = "ncm";
+
+/// Numbers the temp files, so two conversions of the same stem (or the same
+/// file twice) never write into one.
+static PART_SEQ: AtomicU64 = AtomicU64::new(0);
+
+type Aes128EcbDec = ecb::Decryptor<Aes128>;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum NcmError {
+    /// The file does not start with the NCM magic.
+    NotNcm(String),
+    /// The file ends inside the header.
+    Truncated(String),
+    /// A chunk does not decrypt or parse.
+    Corrupt(String),
+    Io(String),
+}
+
+impl fmt::Display for NcmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotNcm(message) => write!(f, "Not an NCM file: {}", message),
+            Self::Truncated(message) => write!(f, "Truncated NCM file: {}", message),
+            Self::Corrupt(message) => write!(f, "Corrupt NCM file: {}", message),
+            Self::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<std::io::Error> for NcmError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+/// The song fields of the metadata chunk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NcmMetadata {
+    pub music_id: Option<u64>,
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    /// `mp3` or `flac`, as the file declares it.
+    pub format: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub bitrate: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMetadata {
+    #[serde(default)]
+    music_id: Value,
+    #[serde(default)]
+    music_name: String,
+    /// `[[name, id], ...]`.
+    #[serde(default)]
+    artist: Vec<Vec<Value>>,
+    #[serde(default)]
+    album: String,
+    format: Option<String>,
+    duration: Option<u64>,
+    bitrate: Option<u64>,
+}
+
+impl From<RawMetadata> for NcmMetadata {
+    fn from(raw: RawMetadata) -> Self {
+        // Older files carry the id as a string.
+        let music_id = match raw.music_id {
+            Value::Number(id) => id.as_u64(),
+            Value::String(id) => id.parse().ok(),
+            _ => None,
+        };
+        Self {
+            music_id,
+            title: raw.music_name,
+            artists: raw
+                .artist
+                .into_iter()
+                .filter_map(|entry| entry.into_iter().next()?.as_str().map(str::to_string))
+                .collect(),
+            album: raw.album,
+            // Ends up as a file extension.
+            format: raw.format.filter(|format| {
+                !format.is_empty() && format.bytes().all(|byte| byte.is_ascii_alphanumeric())
+            }),
+            duration_ms: raw.duration,
+            bitrate: raw.bitrate,
+        }
+    }
+}
+
+/// Everything before the audio.
+pub struct NcmHeader {
+    keystream: [u8; 256],
+    pub metadata: Option<NcmMetadata>,
+    pub cover: Option<Vec<u8>>,
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8], what: &str) -> Result<(), NcmError> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => NcmError::Truncated(format!("ends in the {}", what)),
+        _ => e.into(),
+    })
+}
+
+fn read_u32<R: Read>(reader: &mut R, what: &str) -> Result<u32, NcmError> {
+    let mut bytes = [0u8; 4];
+    read_exact(reader, &mut bytes, what)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_chunk<R: Read>(reader: &mut R, what: &str) -> Result<Vec<u8>, NcmError> {
+    let len = read_u32(reader, what)?;
+    if len > MAX_CHUNK_BYTES {
+        return Err(NcmError::Corrupt(format!(
+            "{} length {} is implausible",
+            what, len
+        )));
+    }
+    let mut chunk = vec![0u8; len as usize];
+    read_exact(reader, &mut chunk, what)?;
+    Ok(chunk)
+}
+
+fn skip<R: Read>(reader: &mut R, len: u64, what: &str) -> Result<(), NcmError> {
+    let skipped = std::io::copy(&mut reader.take(len), &mut std::io::sink())?;
+    if skipped < len {
+        return Err(NcmError::Truncated(format!("ends in the {}", what)));
+    }
+    Ok(())
+}
+
+fn aes_decrypt(key: &[u8; 16], data: &[u8], what: &str) -> Result<Vec<u8>, NcmError> {
+    Aes128EcbDec::new(key.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map_err(|_| NcmError::Corrupt(format!("{} does not decrypt", what)))
+}
+
+fn strip<'a>(data: &'a [u8], prefix: &[u8], what: &str) -> Result<&'a [u8], NcmError> {
+    data.strip_prefix(prefix).ok_or_else(|| {
+        NcmError::Corrupt(format!(
+            "{} has no '{}' prefix",
+            what,
+            String::from_utf8_lossy(prefix)
+        ))
+    })
+}
+
+/// The 256-byte keystream the audio is XORed with, repeated from offset 0.
+fn keystream(key: &[u8]) -> [u8; 256] {
+    let mut sbox: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let mut last = 0u8;
+    for i in 0..256 {
+        let c = sbox[i].wrapping_add(last).wrapping_add(key[i % key.len()]);
+        sbox.swap(i, c as usize);
+        last = c;
+    }
+    std::array::from_fn(|i| {
+        let j = (i + 1) & 0xff;
+        let a = sbox[j];
+        let b = sbox[(a as usize + j) & 0xff];
+        sbox[a.wrapping_add(b) as usize]
+    })
+}
+
+fn parse_metadata(chunk: &mut [u8]) -> Result<NcmMetadata, NcmError> {
+    chunk.iter_mut().for_each(|byte| *byte ^= META_XOR);
+    let encoded = strip(chunk, META_PREFIX, "metadata")?;
+    let encrypted = BASE64
+        .decode(encoded)
+        .map_err(|e| NcmError::Corrupt(format!("metadata is not base64: {}", e)))?;
+    let plain = aes_decrypt(META_KEY, &encrypted, "metadata")?;
+    // `music:{...}`, or `dj:{...}` with the song under `mainMusic`.
+    let split = plain
+        .iter()
+        .position(|&byte| byte == b':')
+        .ok_or_else(|| NcmError::Corrupt("metadata has no type prefix".into()))?;
+    let mut json: Value = serde_json::from_slice(&plain[split + 1..])
+        .map_err(|e| NcmError::Corrupt(format!("metadata is not JSON: {}", e)))?;
+    if &plain[..split] == b"dj" {
+        json = json["mainMusic"].take();
+    }
+    let raw: RawMetadata = serde_json::from_value(json)
+        .map_err(|e| NcmError::Corrupt(format!("unexpected metadata: {}", e)))?;
+    Ok(raw.into())
+}
+
+/// Read an NCM header, leaving `reader` at the first audio byte.
+pub fn read_header<R: Read>(reader: &mut R) -> Result<NcmHeader, NcmError> {
+    let mut magic = [0u8; 8];
+    if reader.read_exact(&mut magic).is_err() || &magic != MAGIC {
+        return Err(NcmError::NotNcm("missing CTENFDAM magic".into()));
+    }
+    skip(reader, 2, "header")?;
+
+    let mut key = read_chunk(reader, "key chunk")?;
+    key.iter_mut().for_each(|byte| *byte ^= KEY_XOR);
+    let key = aes_decrypt(CORE_KEY, &key, "key chunk")?;
+    let key = strip(&key, KEY_PREFIX, "key chunk")?;
+    if key.is_empty() {
+        return Err(NcmError::Corrupt("key chunk holds no key".into()));
+    }
+    let keystream = keystream(key);
+
+    let mut meta = read_chunk(reader, "metadata")?;
+    let metadata = if meta.is_empty() {
+        None
+    } else {
+        Some(parse_metadata(&mut meta)?)
+    };
+
+    // CRC32 of the key and five unused bytes.
+    skip(reader, 9, "header")?;
+    let frame_len = read_u32(reader, "cover")?;
+    let image_len = read_u32(reader, "cover")?;
+    if frame_len > MAX_CHUNK_BYTES || image_len > frame_len {
+        return Err(NcmError::Corrupt(format!(
+            "cover of {} bytes in a {} byte frame",
+            image_len, frame_len
+        )));
+    }
+    let mut cover = vec![0u8; image_len as usize];
+    read_exact(reader, &mut cover, "cover")?;
+    skip(
+        reader,
+        u64::from(frame_len.saturating_sub(image_len)),
+        "cover",
+    )?;
+
+    Ok(NcmHeader {
+        keystream,
+        metadata,
+        cover: (!cover.is_empty()).then_some(cover),
+    })
+}
+
+/// Decrypt the audio after [`read_header`] from `reader` into `writer`.
+/// `on_progress` gets the audio bytes written so far after every buffer.
+/// Returns the total.
+pub fn decrypt_audio<R: Read, W: Write>(
+    header: &NcmHeader,
+    reader: &mut R,
+    writer: &mut W,
+    mut on_progress: impl FnMut(u64),
+) -> Result<u64, NcmError> {
+    let mut buf = vec![0u8; BUFFER_BYTES];
+    let mut done = 0u64;
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for (offset, byte) in buf[..read].iter_mut().enumerate() {
+            *byte ^= header.keystream[(done as usize + offset) & 0xff];
+        }
+        writer.write_all(&buf[..read])?;
+        done += read as u64;
+        on_progress(done);
+    }
+    Ok(done)
+}
+
+/// `flac` or `mp3` from the first audio bytes.
+fn sniff_format(audio: &[u8]) -> Option<&'static str> {
+    if audio.starts_with(b"fLaC") {
+        Some("flac")
+    } else if audio.starts_with(b"ID3")
+        || (audio.len() >= 2 && audio[0] == 0xff && audio[1] & 0xe0 == 0xe0)
+    {
+        Some("mp3")
+    } else {
+        None
+    }
+}
+
+fn image_extension(image: &[u8]) -> &'static str {
+    if image.starts_with(b"\x89PNG") {
+        "png"
+    } else {
+        "jpg"
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NcmDecryptProgress {
+    pub path: String,
+    pub bytes_done: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NcmDecrypted {
+    pub audio_path: PathBuf,
+    pub format: String,
+    pub metadata: Option<NcmMetadata>,
+    pub cover_path: Option<PathBuf>,
+}
+
+/// Whether `path` has the `.ncm` extension.
+pub fn is_ncm_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+fn decrypt_file(
+    source: &Path,
+    out_dir: &Path,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<NcmDecrypted, NcmError> {
+    let file = File::open(source)?;
+    let total_bytes = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let header = read_header(&mut reader)?;
+
+    std::fs::create_dir_all(out_dir)?;
+    let stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "track".into());
+    let seq = PART_SEQ.fetch_add(1, Ordering::Relaxed);
+    let part = out_dir.join(format!("{}.{}.part", stem, seq));
+    let mut output = BufWriter::new(File::create(&part)?);
+    let result = decrypt_audio(&header, &mut reader, &mut output, |done| {
+        on_progress(done, total_bytes)
+    })
+    .and_then(|written| {
+        output.flush()?;
+        Ok(written)
+    });
+    drop(output);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&part);
+        return Err(e);
+    }
+
+    let declared = header
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.format.clone());
+    let format = match declared {
+        Some(format) => format,
+        None => {
+            let mut head = Vec::new();
+            File::open(&part)?.take(16).read_to_end(&mut head)?;
+            sniff_format(&head).unwrap_or("mp3").to_string()
+        }
+    };
+    let audio_path = out_dir.join(format!("{}.{}", stem, format));
+    std::fs::rename(&part, &audio_path)?;
+
+    let cover_path = match &header.cover {
+        Some(image) => {
+            let path = out_dir.join(format!("{}.{}", stem, image_extension(image)));
+            std::fs::write(&path, image)?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    Ok(NcmDecrypted {
+        audio_path,
+        format,
+        metadata: header.metadata,
+        cover_path,
+    })
+}
+
+/// Decrypt the `.ncm` at `path` into the app cache. Files over 8 MiB report
+/// progress through `ncm-decrypt-progress`.
+#[tauri::command]
+pub async fn decrypt_ncm(app: AppHandle, path: String) -> Result<NcmDecrypted, NcmError> {
+    let out_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| NcmError::Io(e.to_string()))?
+        .join(OUTPUT_DIR);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut reported = 0u64;
+        let decrypted = decrypt_file(Path::new(&path), &out_dir, |bytes_done, total_bytes| {
+            if total_bytes < PROGRESS_MIN_BYTES || bytes_done - reported < PROGRESS_EVERY_BYTES {
+                return;
+            }
+            reported = bytes_done;
+            let _ = app.emit(
+                NCM_DECRYPT_PROGRESS_EVENT,
+                NcmDecryptProgress {
+                    path: path.clone(),
+                    bytes_done,
+                    total_bytes,
+                },
+            );
+        })?;
+        info!("Decrypted NCM to {}", decrypted.audio_path.display());
+        Ok(decrypted)
+    })
+    .await
+    .map_err(|e| NcmError::Io(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cbc::cipher::BlockEncryptMut;
+
+    type Aes128EcbEnc = ecb::Encryptor<Aes128>;
+
+    const AUDIO: &[u8] =
+        b"fLaC\0\0\0\x22 synthetic audio payload, long enough to wrap the keystream \
+        more than once when repeated";
+
+    fn chunk(data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u32).to_le_bytes().to_vec();
+        out.extend_from_slice(data);
+        out
+    }
+
+    /// An NCM file built the way the client writes one.
+    fn fixture(audio: &[u8], cover: &[u8]) -> Vec<u8> {
+        let key = [KEY_PREFIX, b"0123456789abcdefghijklmnopqrstuvwxyz"].concat();
+        let mut key = Aes128EcbEnc::new(CORE_KEY.into()).encrypt_padded_vec_mut::<Pkcs7>(&key);
+        key.iter_mut().for_each(|byte| *byte ^= KEY_XOR);
+
+        let json = r#"music:{"musicId":1859245776,"musicName":"Song","artist":[["A",1],["B",2]],"album":"Album","format":"flac","duration":1000,"bitrate":999000}"#;
+        let meta =
+            Aes128EcbEnc::new(META_KEY.into()).encrypt_padded_vec_mut::<Pkcs7>(json.as_bytes());
+        let mut meta = [META_PREFIX, BASE64.encode(meta).as_bytes()].concat();
+        meta.iter_mut().for_each(|byte| *byte ^= META_XOR);
+
+        let stream = keystream(b"0123456789abcdefghijklmnopqrstuvwxyz");
+        let mut file = MAGIC.to_vec();
+        file.extend_from_slice(&[0; 2]);
+        file.extend(chunk(&key));
+        file.extend(chunk(&meta));
+        file.extend_from_slice(&[0; 9]);
+        // A cover frame with two bytes of padding.
+        file.extend_from_slice(&(cover.len() as u32 + 2).to_le_bytes());
+        file.extend(chunk(cover));
+        file.extend_from_slice(&[0; 2]);
+        file.extend(
+            audio
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| byte ^ stream[i & 0xff]),
+        );
+        file
+    }
+
+    #[test]
+    fn synthetic_file_decrypts_to_audio_metadata_and_cover() {
+        let audio = AUDIO.repeat(5);
+        let data = fixture(&audio, b"\xff\xd8\xff cover");
+        let mut reader = data.as_slice();
+        let header = read_header(&mut reader).unwrap();
+        let metadata = header.metadata.clone().unwrap();
+        assert_eq!(metadata.music_id, Some(1859245776));
+        assert_eq!(metadata.title, "Song");
+        assert_eq!(metadata.artists, ["A", "B"]);
+        assert_eq!(metadata.format.as_deref(), Some("flac"));
+        assert_eq!(header.cover.as_deref(), Some(&b"\xff\xd8\xff cover"[..]));
+
+        let mut out = Vec::new();
+        let written = decrypt_audio(&header, &mut reader, &mut out, |_| {}).unwrap();
+        assert_eq!(written, audio.len() as u64);
+        assert_eq!(out, audio);
+        assert_eq!(sniff_format(&out), Some("flac"));
+    }
+
+    #[test]
+    fn truncated_and_corrupt_files_are_told_apart() {
+        let data = fixture(AUDIO, b"");
+        assert!(matches!(
+            read_header(&mut &b"ID3\x03"[..]),
+            Err(NcmError::NotNcm(_))
+        ));
+        for len in [9, 14, 40, data.len() - AUDIO.len() - 1] {
+            assert!(
+                matches!(read_header(&mut &data[..len]), Err(NcmError::Truncated(_))),
+                "cut at {}",
+                len
+            );
+        }
+        let mut corrupt = data.clone();
+        corrupt[20] ^= 0xff;
+        assert!(matches!(
+            read_header(&mut corrupt.as_slice()),
+            Err(NcmError::Corrupt(_))
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+//! QR codes for the login-by-QR flow, rendered to PNG.
+//!
+//! The code is drawn module by module at a whole number of pixels per
+//! module, with the standard four-module quiet zone, and centered on a
+//! canvas of the requested size. A logo can be laid over the middle; it
+//! covers a fifth of the code's width, so the error correction level is
+//! raised to at least `Q` to keep the code readable.
+//!
+//! Results are memoized per input for the lifetime of the process: the
+//! login screen redraws the same code on every re-render.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, ImageReader, Limits, Rgba, RgbaImage};
+use parking_lot::Mutex;
+use qrcode::{Color, EcLevel, QrCode};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SIZE_PX: u32 = 256;
+const MIN_SIZE_PX: u32 = 64;
+const MAX_SIZE_PX: u32 = 2048;
+const QUIET_ZONE: u32 = 4;
+const MAX_LOGO_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_LOGO_EDGE: u32 = 4096;
+/// Memoized codes kept at most; the memo starts over when it is full.
+const MEMO_CAPACITY: usize = 64;
+
+static MEMO: LazyLock<Mutex<HashMap<QrRequest, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum QrError {
+    /// The text does not fit in a QR code at this error correction level.
+    TooLong(String),
+    InvalidColor(String),
+    /// The logo is missing or not a decodable image.
+    InvalidLogo(String),
+    Encode(String),
+}
+
+impl fmt::Display for QrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong(message) => write!(f, "Text too long for a QR code: {}", message),
+            Self::InvalidColor(color) => write!(f, "Invalid color '{}'", color),
+            Self::InvalidLogo(message) => write!(f, "Invalid QR logo: {}", message),
+            Self::Encode(message) => write!(f, "Failed to encode QR code: {}", message),
+        }
+    }
+}
+
+/// Error correction level, from `L` (7% recoverable) to `H` (30%).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub enum QrEcLevel {
+    L,
+    #[default]
+    M,
+    Q,
+    H,
+}
+
+impl From<QrEcLevel> for EcLevel {
+    fn from(level: QrEcLevel) -> Self {
+        match level {
+            QrEcLevel::L => EcLevel::L,
+            QrEcLevel::M => EcLevel::M,
+            QrEcLevel::Q => EcLevel::Q,
+            QrEcLevel::H => EcLevel::H,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct QrRequest {
+    text: String,
+    size_px: u32,
+    dark: [u8; 4],
+    light: [u8; 4],
+    ec_level: QrEcLevel,
+    logo: Option<PathBuf>,
+}
+
+/// `#rgb`, `#rrggbb` or `#rrggbbaa`.
+fn parse_color(color: &str) -> Result<[u8; 4], QrError> {
+    let invalid = || QrError::InvalidColor(color.to_string());
+    let digits = color.strip_prefix('#').ok_or_else(invalid)?;
+    if !digits.is_ascii() {
+        return Err(invalid());
+    }
+    let expanded: String = match digits.len() {
+        3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => digits.to_string(),
+        _ => return Err(invalid()),
+    };
+    let bytes = hex::decode(expanded).map_err(|_| invalid())?;
+    Ok([
+        bytes[0],
+        bytes[1],
+        bytes[2],
+        bytes.get(3).copied().unwrap_or(255),
+    ])
+}
+
+fn load_logo(path: &Path) -> Result<RgbaImage, QrError> {
+    let invalid = |e: &dyn fmt::Display| QrError::InvalidLogo(format!("{}: {}", path.display(), e));
+    let len = std::fs::metadata(path).map_err(|e| invalid(&e))?.len();
+    if len > MAX_LOGO_BYTES {
+        return Err(invalid(&"larger than 10 MB"));
+    }
+    let mut reader = ImageReader::open(path)
+        .and_then(ImageReader::with_guessed_format)
+        .map_err(|e| invalid(&e))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_LOGO_EDGE);
+    limits.max_image_height = Some(MAX_LOGO_EDGE);
+    reader.limits(limits);
+    Ok(reader.decode().map_err(|e| invalid(&e))?.into_rgba8())
+}
+
+fn render(request: &QrRequest) -> Result<RgbaImage, QrError> {
+    let ec_level = match request.logo {
+        Some(_) => request.ec_level.max(QrEcLevel::Q),
+        None => request.ec_level,
+    };
+    let code = QrCode::with_error_correction_level(&request.text, ec_level.into())
+        .map_err(|e| QrError::TooLong(e.to_string()))?;
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+
+    let span = modules + 2 * QUIET_ZONE;
+    let scale = (request.size_px / span).max(1);
+    let canvas_px = request.size_px.max(span * scale);
+    let offset = (canvas_px - span * scale) / 2 + QUIET_ZONE * scale;
+    let mut image = RgbaImage::from_pixel(canvas_px, canvas_px, Rgba(request.light));
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x = offset + (index as u32 % modules) * scale;
+        let y = offset + (index as u32 / modules) * scale;
+        for dy in 0..scale {
+            for dx in 0..scale {
+                image.put_pixel(x + dx, y + dy, Rgba(request.dark));
+            }
+        }
+    }
+
+    if let Some(path) = &request.logo {
+        let side = (modules * scale / 5).max(1);
+        let logo = imageops::resize(&load_logo(path)?, side, side, FilterType::Lanczos3);
+        // A light backing square keeps the logo's edges off the modules.
+        let backing = side + 2 * scale;
+        let start = (canvas_px - backing) / 2;
+        for y in start..start + backing {
+            for x in start..start + backing {
+                image.put_pixel(x, y, Rgba(request.light));
+            }
+        }
+        let at = i64::from((canvas_px - side) / 2);
+        imageops::overlay(&mut image, &logo, at, at);
+    }
+    Ok(image)
+}
+
+fn data_url(image: &RgbaImage) -> Result<String, QrError> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| QrError::Encode(e.to_string()))?;
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(png)))
+}
+
+fn generate(request: QrRequest) -> Result<String, QrError> {
+    if let Some(url) = MEMO.lock().get(&request) {
+        return Ok(url.clone());
+    }
+    let url = data_url(&render(&request)?)?;
+    let mut memo = MEMO.lock();
+    if memo.len() >= MEMO_CAPACITY {
+        memo.clear();
+    }
+    memo.insert(request, url.clone());
+    Ok(url)
+}
+
+/// Render `text` as a QR code PNG and return it as a `data:` URL.
+/// `size_px` defaults to 256 and is clamped to 64..=2048; a code with more
+/// modules than that comes out larger. Colors default to black on white.
+#[tauri::command]
+pub async fn generate_qr(
+    text: String,
+    size_px: Option<u32>,
+    dark_color: Option<String>,
+    light_color: Option<String>,
+    ec_level: Option<QrEcLevel>,
+    logo_path: Option<String>,
+) -> Result<String, QrError> {
+    let request = QrRequest {
+        text,
+        size_px: size_px
+            .unwrap_or(DEFAULT_SIZE_PX)
+            .clamp(MIN_SIZE_PX, MAX_SIZE_PX),
+        dark: parse_color(dark_color.as_deref().unwrap_or("#000000"))?,
+        light: parse_color(light_color.as_deref().unwrap_or("#ffffff"))?,
+        ec_level: ec_level.unwrap_or_default(),
+        logo: logo_path.map(PathBuf::from),
+    };
+    tauri::async_runtime::spawn_blocking(move || generate(request))
+        .await
+        .map_err(|e| QrError::Encode(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(text: &str) -> QrRequest {
+        QrRequest {
+            text: text.to_string(),
+            size_px: 200,
+            dark: [0, 0, 0, 255],
+            light: [255, 255, 255, 255],
+            ec_level: QrEcLevel::M,
+            logo: None,
+        }
+    }
+
+    #[test]
+    fn code_is_centered_with_a_quiet_zone() {
+        let text = "https://music.163.com/login?codekey=abc";
+        let image = render(&request(text)).unwrap();
+        assert_eq!(image.dimensions(), (200, 200));
+        let span = QrCode::new(text).unwrap().width() as u32 + 2 * QUIET_ZONE;
+        let scale = 200 / span;
+        let margin = (200 - span * scale) / 2 + QUIET_ZONE * scale;
+        assert_eq!(image.get_pixel(margin - 1, margin - 1).0, [255; 4]);
+        // Every code starts with a finder pattern's dark corner.
+        assert_eq!(image.get_pixel(margin, margin).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn colors_parse_and_results_are_memoized() {
+        assert_eq!(parse_color("#0f8").unwrap(), [0, 255, 136, 255]);
+        assert_eq!(parse_color("#11223380").unwrap(), [0x11, 0x22, 0x33, 0x80]);
+        assert!(parse_color("112233").is_err());
+        assert!(parse_color("#12345").is_err());
+
+        let first = generate(request("memo")).unwrap();
+        assert!(first.starts_with("data:image/png;base64,"));
+        assert!(MEMO.lock().contains_key(&request("memo")));
+        assert_eq!(generate(request("memo")).unwrap(), first);
+        let mut missing_logo = request("memo");
+        missing_logo.logo = Some(PathBuf::from("/nonexistent/logo.png"));
+        assert!(matches!(
+            generate(missing_logo),
+            Err(QrError::InvalidLogo(_))
+        ));
+    }
+}
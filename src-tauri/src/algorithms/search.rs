@@ -0,0 +1,415 @@
+//! Fuzzy search over local song, artist and playlist names.
+//!
+//! Each candidate is matched three ways at once: as written, as full pinyin
+//! (`周杰伦` → `zhoujielun`) and as pinyin initials (`zjl`), and ranked by
+//! the best of the three SkimV2 scores. Matches on the pinyin forms are
+//! mapped back to the Chinese characters they came from, so the returned
+//! ranges always point into the original text.
+//!
+//! Characters with several readings get the crate's most common one unless
+//! the word they are part of is in [`WORD_READINGS`]; `音乐` is `yinyue`,
+//! not `yinle`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::Mutex;
+use pinyin::ToPinyin;
+use serde::Serialize;
+
+const DEFAULT_LIMIT: usize = 100;
+/// Candidates whose pinyin forms are remembered; the memo starts over when
+/// a search would take it past this.
+const FORMS_CAPACITY: usize = 100_000;
+
+/// Readings for common words whose characters are usually read otherwise.
+const WORD_READINGS: &[(&str, &[&str])] = &[
+    ("音乐", &["yin", "yue"]),
+    ("乐队", &["yue", "dui"]),
+    ("乐团", &["yue", "tuan"]),
+    ("乐器", &["yue", "qi"]),
+    ("乐章", &["yue", "zhang"]),
+    ("乐曲", &["yue", "qu"]),
+    ("乐坛", &["yue", "tan"]),
+    ("快乐", &["kuai", "le"]),
+    ("重庆", &["chong", "qing"]),
+    ("重来", &["chong", "lai"]),
+    ("重逢", &["chong", "feng"]),
+    ("重新", &["chong", "xin"]),
+    ("重复", &["chong", "fu"]),
+    ("长大", &["zhang", "da"]),
+    ("成长", &["cheng", "zhang"]),
+    ("长安", &["chang", "an"]),
+    ("长城", &["chang", "cheng"]),
+    ("长江", &["chang", "jiang"]),
+    ("银行", &["yin", "hang"]),
+    ("一行", &["yi", "hang"]),
+    ("曾经", &["ceng", "jing"]),
+    ("睡觉", &["shui", "jiao"]),
+    ("朝阳", &["zhao", "yang"]),
+    ("朝夕", &["zhao", "xi"]),
+    ("了解", &["liao", "jie"]),
+    ("还是", &["hai", "shi"]),
+    ("还有", &["hai", "you"]),
+    ("还给", &["huan", "gei"]),
+    ("传说", &["chuan", "shuo"]),
+    ("传记", &["zhuan", "ji"]),
+    ("和声", &["he", "sheng"]),
+    ("少年", &["shao", "nian"]),
+    ("好听", &["hao", "ting"]),
+    ("爱好", &["ai", "hao"]),
+    ("调皮", &["tiao", "pi"]),
+    ("单曲", &["dan", "qu"]),
+    ("都市", &["du", "shi"]),
+    ("首都", &["shou", "du"]),
+    ("角色", &["jue", "se"]),
+    ("的士", &["di", "shi"]),
+    ("目的", &["mu", "di"]),
+];
+
+/// A word's characters and their readings.
+type WordReading = (Vec<char>, &'static [&'static str]);
+
+/// [`WORD_READINGS`] keyed by first character.
+static READINGS_BY_FIRST: LazyLock<HashMap<char, Vec<WordReading>>> = LazyLock::new(|| {
+    let mut map: HashMap<char, Vec<_>> = HashMap::new();
+    for (word, readings) in WORD_READINGS {
+        let chars: Vec<char> = word.chars().collect();
+        map.entry(chars[0]).or_default().push((chars, *readings));
+    }
+    map
+});
+
+/// Pinyin forms of recently searched candidates, `None` for text without
+/// any readings. The search box sends the same list on every keystroke,
+/// and converting it again would cost more than matching it.
+static FORMS: LazyLock<Mutex<HashMap<String, Option<Arc<Forms>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static MATCHER: LazyLock<SkimMatcherV2> = LazyLock::new(|| SkimMatcherV2::default().ignore_case());
+
+/// Pinyin forms of a piece of text. Characters without a reading are kept
+/// as they are, lowercased.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PinyinText {
+    /// Syllables without tones or separators: `zhoujielun`.
+    pub full: String,
+    /// First letter of each syllable: `zjl`.
+    pub initials: String,
+}
+
+/// One ranked candidate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    /// Position in the candidate list.
+    pub index: usize,
+    pub score: i64,
+    /// Matched `[start, end)` spans of the candidate in UTF-16 code units,
+    /// as JavaScript string methods count them.
+    pub matched_ranges: Vec<[usize; 2]>,
+}
+
+/// The reading of each character, `None` for characters without one.
+fn readings(chars: &[char]) -> Vec<Option<&'static str>> {
+    let mut out = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let word = READINGS_BY_FIRST.get(&chars[i]).and_then(|words| {
+            words
+                .iter()
+                .find(|(word, _)| chars[i..].starts_with(word))
+                .map(|(_, readings)| *readings)
+        });
+        match word {
+            Some(readings) => {
+                out.extend(readings.iter().map(|&reading| Some(reading)));
+                i += readings.len();
+            }
+            None => {
+                out.push(chars[i].to_pinyin().map(|pinyin| pinyin.plain()));
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Text to match against, with the index of the original character each
+/// of its characters came from.
+struct Target {
+    text: String,
+    source: Vec<usize>,
+}
+
+impl Target {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            text: String::with_capacity(capacity),
+            source: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Append a reading, which is already lowercase ASCII.
+    fn push_reading(&mut self, reading: &str, source: usize) {
+        self.text.push_str(reading);
+        self.source
+            .extend(std::iter::repeat(source).take(reading.len()));
+    }
+
+    fn push_char(&mut self, c: char, source: usize) {
+        for c in c.to_lowercase() {
+            self.text.push(c);
+            self.source.push(source);
+        }
+    }
+}
+
+/// Full-pinyin and initials match targets of a candidate.
+struct Forms {
+    full: Target,
+    initials: Target,
+}
+
+impl Forms {
+    fn get(&self, form: Form) -> Option<&Target> {
+        match form {
+            Form::Original => None,
+            Form::Full => Some(&self.full),
+            Form::Initials => Some(&self.initials),
+        }
+    }
+}
+
+/// The pinyin forms of `text`, or `None` when nothing in it has a reading.
+fn pinyin_forms(text: &str) -> Option<Forms> {
+    let chars: Vec<char> = text.chars().collect();
+    let readings = readings(&chars);
+    if readings.iter().all(Option::is_none) {
+        return None;
+    }
+    let mut full = Target::with_capacity(chars.len() * 4);
+    let mut initials = Target::with_capacity(chars.len());
+    for (i, (&c, reading)) in chars.iter().zip(&readings).enumerate() {
+        match reading {
+            Some(reading) => {
+                full.push_reading(reading, i);
+                initials.push_reading(&reading[..1], i);
+            }
+            None => {
+                full.push_char(c, i);
+                initials.push_char(c, i);
+            }
+        }
+    }
+    Some(Forms { full, initials })
+}
+
+/// The pinyin forms of each candidate, `None` for ASCII text and text
+/// without readings. Remembered forms are copied out and new ones worked
+/// out without holding the lock, so concurrent searches only wait for the
+/// lookups, never for each other's matching.
+fn candidate_forms<S: AsRef<str>>(candidates: &[S]) -> Vec<Option<Arc<Forms>>> {
+    let mut forms: Vec<Option<Option<Arc<Forms>>>> = {
+        let memo = FORMS.lock();
+        candidates
+            .iter()
+            .map(|candidate| {
+                let candidate = candidate.as_ref();
+                if candidate.is_ascii() {
+                    Some(None)
+                } else {
+                    memo.get(candidate).cloned()
+                }
+            })
+            .collect()
+    };
+    let mut computed = Vec::new();
+    for (slot, candidate) in forms.iter_mut().zip(candidates) {
+        if slot.is_none() {
+            let candidate = candidate.as_ref();
+            let value = pinyin_forms(candidate).map(Arc::new);
+            computed.push((candidate.to_string(), value.clone()));
+            *slot = Some(value);
+        }
+    }
+    if !computed.is_empty() {
+        let mut memo = FORMS.lock();
+        if memo.len() + computed.len() > FORMS_CAPACITY {
+            memo.clear();
+        }
+        memo.extend(computed);
+    }
+    forms.into_iter().map(Option::flatten).collect()
+}
+
+/// Convert `text` to toneless pinyin, reading common polyphonic words as
+/// listed in [`WORD_READINGS`].
+pub fn to_pinyin(text: &str) -> PinyinText {
+    match pinyin_forms(text) {
+        Some(Forms { full, initials }) => PinyinText {
+            full: full.text,
+            initials: initials.text,
+        },
+        None => PinyinText {
+            full: text.to_lowercase(),
+            initials: text.to_lowercase(),
+        },
+    }
+}
+
+/// Sorted character indices as UTF-16 `[start, end)` ranges of `text`.
+fn utf16_ranges(text: &str, mut indices: Vec<usize>) -> Vec<[usize; 2]> {
+    indices.sort_unstable();
+    indices.dedup();
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut offset = 0;
+    for c in text.chars() {
+        offsets.push(offset);
+        offset += c.len_utf16();
+    }
+    offsets.push(offset);
+
+    let mut ranges: Vec<[usize; 2]> = Vec::new();
+    for index in indices {
+        let (start, end) = (offsets[index], offsets[index + 1]);
+        match ranges.last_mut() {
+            Some(last) if last[1] == start => last[1] = end,
+            _ => ranges.push([start, end]),
+        }
+    }
+    ranges
+}
+
+/// Which form of a candidate matched best.
+#[derive(Clone, Copy)]
+enum Form {
+    Original,
+    Full,
+    Initials,
+}
+
+/// Rank `candidates` against `query` and return at most `limit` matches,
+/// best first; equal scores keep the candidates' order. An empty query
+/// matches everything with a score of zero.
+pub fn fuzzy_match<S: AsRef<str>>(query: &str, candidates: &[S], limit: usize) -> Vec<SearchMatch> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..candidates.len().min(limit))
+            .map(|index| SearchMatch {
+                index,
+                score: 0,
+                matched_ranges: Vec::new(),
+            })
+            .collect();
+    }
+    // Pinyin forms have no spaces, so "zhou jie lun" is matched as one word.
+    let compact: String = query.split_whitespace().collect();
+    let try_pinyin = compact.is_ascii();
+
+    let forms = if try_pinyin {
+        candidate_forms(candidates)
+    } else {
+        Vec::new()
+    };
+    let forms_of = |index: usize| forms.get(index).and_then(Option::as_deref);
+    let mut ranked: Vec<(i64, usize, Form)> = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        let candidate = candidate.as_ref();
+        let mut best = MATCHER
+            .fuzzy_match(candidate, query)
+            .map(|score| (score, Form::Original));
+        if let Some(forms) = forms_of(index) {
+            for form in [Form::Full, Form::Initials] {
+                let Some(target) = forms.get(form) else {
+                    continue;
+                };
+                if let Some(score) = MATCHER.fuzzy_match(&target.text, &compact) {
+                    if best.map_or(true, |(best, _)| score > best) {
+                        best = Some((score, form));
+                    }
+                }
+            }
+        }
+        if let Some((score, form)) = best {
+            ranked.push((score, index, form));
+        }
+    }
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    ranked.truncate(limit);
+
+    // Match positions are only worth working out for what is returned.
+    ranked
+        .into_iter()
+        .map(|(score, index, form)| {
+            let candidate = candidates[index].as_ref();
+            let target = forms_of(index).and_then(|forms| forms.get(form));
+            let indices = match target {
+                Some(target) => MATCHER
+                    .fuzzy_indices(&target.text, &compact)
+                    .map(|(_, i)| i.into_iter().map(|i| target.source[i]).collect()),
+                None => MATCHER.fuzzy_indices(candidate, query).map(|(_, i)| i),
+            };
+            SearchMatch {
+                index,
+                score,
+                matched_ranges: utf16_ranges(candidate, indices.unwrap_or_default()),
+            }
+        })
+        .collect()
+}
+
+/// Rank `candidates` (song titles, artist names, …) against `query`,
+/// matching Chinese text by its pinyin as well. `limit` defaults to 100.
+#[tauri::command]
+pub async fn search_rank(
+    query: String,
+    candidates: Vec<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchMatch>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        fuzzy_match(&query, &candidates, limit.unwrap_or(DEFAULT_LIMIT))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinyin_uses_word_readings() {
+        assert_eq!(
+            to_pinyin("周杰伦 Jay"),
+            PinyinText {
+                full: "zhoujielun jay".into(),
+                initials: "zjl jay".into(),
+            }
+        );
+        assert_eq!(to_pinyin("音乐").full, "yinyue");
+        assert_eq!(to_pinyin("重庆森林").initials, "cqsl");
+    }
+
+    #[test]
+    fn pinyin_matches_highlight_the_original_characters() {
+        let candidates = ["陈奕迅", "周杰伦 - 晴天", "Zhou Shen", "🎵周杰伦"];
+        let matches = fuzzy_match("zhoujielun", &candidates, 10);
+        let indices: Vec<usize> = matches.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![1, 3]);
+        assert_eq!(matches[0].matched_ranges, vec![[0, 3]]);
+        // The emoji is two UTF-16 code units.
+        assert_eq!(matches[1].matched_ranges, vec![[2, 5]]);
+
+        let matches = fuzzy_match("zjl", &candidates, 10);
+        assert_eq!(matches[0].index, 1);
+        assert_eq!(matches[0].matched_ranges, vec![[0, 3]]);
+
+        let matches = fuzzy_match("zhou", &candidates, 1);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(fuzzy_match(" ", &candidates, 2).len(), 2);
+        assert!(fuzzy_match("xyz", &candidates, 10).is_empty());
+    }
+}
@@ -0,0 +1,602 @@
+//! Native HTTP for API calls, with a persistent cookie jar.
+//!
+//! The webview's `fetch` can neither set `Cookie` nor read `Set-Cookie`, so
+//! logged-in API calls go through [`api_fetch`] instead. Requests share the
+//! client and retry / circuit policy of [`crate::http_util`]; when
+//! `use_cookies` is set the matching cookies from the jar are attached and
+//! the `Set-Cookie` headers are stored back. Redirects are followed here
+//! rather than by the client, so the cookies a redirect sets (a login
+//! bouncing through another host, say) are kept and sent on the next hop.
+//!
+//! The jar lives in `cookies.bin` under the app data dir, AES-256-GCM
+//! encrypted with a random key kept next to it in `cookies.key`. That keeps
+//! the login token out of backups and log bundles that pick up the data
+//! file alone; it is no defense against something that can read the whole
+//! directory. Like a browser, only cookies with an expiry are persisted.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cookie_store::{Cookie, CookieExpiration, CookieStore, RawCookie};
+use log::{info, warn};
+use parking_lot::Mutex;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_http::reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE,
+    LOCATION, PROXY_AUTHORIZATION, SET_COOKIE,
+};
+use tauri_plugin_http::reqwest::{Method, StatusCode, Url};
+
+use crate::http_util::{self, HttpError};
+use crate::settings::SettingsStore;
+
+const COOKIE_FILE: &str = "cookies.bin";
+const KEY_FILE: &str = "cookies.key";
+const FILE_MAGIC: &[u8; 4] = b"GMCK";
+const NONCE_LEN: usize = 12;
+/// Lifetime of a cookie added through `cookies_set`.
+const SET_COOKIE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+/// Same limit as the client's default redirect policy.
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum ApiClientError {
+    /// Bad URL, method, header or domain.
+    InvalidRequest(String),
+    /// Transport failure or an open circuit; HTTP error statuses are not
+    /// errors and come back as responses.
+    Request(String),
+    /// The cookie was rejected for its domain.
+    Cookie(String),
+    Io(String),
+}
+
+impl fmt::Display for ApiClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRequest(message) => write!(f, "Invalid request: {}", message),
+            Self::Request(message) => write!(f, "Request failed: {}", message),
+            Self::Cookie(message) => write!(f, "Cookie rejected: {}", message),
+            Self::Io(message) => write!(f, "Failed to save cookies: {}", message),
+        }
+    }
+}
+
+impl From<HttpError> for ApiClientError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::InvalidUrl(url) => Self::InvalidRequest(format!("invalid URL '{}'", url)),
+            e => Self::Request(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse {
+    pub status: u16,
+    /// Lower-case names; repeated headers are joined with `, `.
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    /// `body` is base64 because the response is not UTF-8 text.
+    pub base64: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieInfo {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    /// Milliseconds since the epoch; `None` for session cookies.
+    pub expires_ms: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+/// Managed cookie jar.
+pub struct ApiClient {
+    jar: Mutex<CookieStore>,
+    /// `None` when the app data dir is unavailable; cookies then only last
+    /// for the session.
+    files: Option<JarFiles>,
+    /// Held while a snapshot is written, so saves land in order.
+    saving: Mutex<()>,
+}
+
+struct JarFiles {
+    path: PathBuf,
+    key: [u8; 32],
+}
+
+impl ApiClient {
+    /// Load the jar from the app data dir, starting empty if it is missing
+    /// or cannot be decrypted.
+    pub fn load(app: &AppHandle) -> Self {
+        let files = match app.path().app_data_dir() {
+            Ok(dir) => match load_or_create_key(&dir.join(KEY_FILE)) {
+                Ok(key) => Some(JarFiles {
+                    path: dir.join(COOKIE_FILE),
+                    key,
+                }),
+                Err(e) => {
+                    warn!(
+                        "Failed to set up the cookie key, cookies will not persist: {}",
+                        e
+                    );
+                    None
+                }
+            },
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, cookies will not persist: {}",
+                    e
+                );
+                None
+            }
+        };
+        let jar = files
+            .as_ref()
+            .and_then(|files| match std::fs::read(&files.path) {
+                Ok(data) => match open_jar(&files.key, &data) {
+                    Ok(jar) => Some(jar),
+                    Err(e) => {
+                        warn!("Discarding unreadable cookie jar: {}", e);
+                        None
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    warn!("Failed to read cookie jar: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            jar: Mutex::new(jar),
+            files,
+            saving: Mutex::new(()),
+        }
+    }
+
+    fn save(&self) -> Result<(), ApiClientError> {
+        let Some(files) = &self.files else {
+            return Ok(());
+        };
+        let _saving = self.saving.lock();
+        let data = seal_jar(&files.key, &self.jar.lock())?;
+        crate::settings::write_atomic(&files.path, &data)
+            .map_err(|e| ApiClientError::Io(e.to_string()))
+    }
+
+    /// The `Cookie` header value for `url`, if any cookie matches.
+    fn cookie_header(&self, url: &Url) -> Option<String> {
+        let jar = self.jar.lock();
+        let pairs: Vec<String> = jar
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        (!pairs.is_empty()).then(|| pairs.join("; "))
+    }
+
+    /// Store the `Set-Cookie` headers of a response from `url`. Returns
+    /// whether any were present.
+    fn store_response(&self, url: &Url, headers: &HeaderMap) -> bool {
+        let mut jar = self.jar.lock();
+        let mut stored = false;
+        for value in headers.get_all(SET_COOKIE) {
+            stored = true;
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            if let Err(e) = jar.parse(value, url) {
+                warn!(
+                    "Ignoring cookie from {}: {}",
+                    url.host_str().unwrap_or(""),
+                    e
+                );
+            }
+        }
+        stored
+    }
+}
+
+fn load_or_create_key(path: &Path) -> std::io::Result<[u8; 32]> {
+    match std::fs::read(path) {
+        Ok(bytes) if bytes.len() == 32 => {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&bytes);
+            return Ok(key);
+        }
+        Ok(_) => warn!("Cookie key has the wrong length, replacing it"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    crate::settings::write_atomic(path, &key)?;
+    info!("Created a new cookie key");
+    Ok(key)
+}
+
+/// Serialize the persistent cookies of `jar` and encrypt them as
+/// `magic | nonce | ciphertext`.
+fn seal_jar(key: &[u8; 32], jar: &CookieStore) -> Result<Vec<u8>, ApiClientError> {
+    let mut json = Vec::new();
+    cookie_store::serde::json::save(jar, &mut json)
+        .map_err(|e| ApiClientError::Io(e.to_string()))?;
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = Aes256Gcm::new(key.into());
+    let sealed = cipher
+        .encrypt(Nonce::from_slice(&nonce), json.as_slice())
+        .map_err(|_| ApiClientError::Io("encryption failed".into()))?;
+    let mut data = Vec::with_capacity(FILE_MAGIC.len() + NONCE_LEN + sealed.len());
+    data.extend_from_slice(FILE_MAGIC);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&sealed);
+    Ok(data)
+}
+
+fn open_jar(key: &[u8; 32], data: &[u8]) -> Result<CookieStore, String> {
+    let rest = data
+        .strip_prefix(FILE_MAGIC.as_slice())
+        .filter(|rest| rest.len() >= NONCE_LEN)
+        .ok_or("not a cookie jar")?;
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let json = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), sealed)
+        .map_err(|_| "wrong key or corrupted file")?;
+    cookie_store::serde::json::load(Cursor::new(json)).map_err(|e| e.to_string())
+}
+
+/// Whether a response with this `Content-Type` may be returned as text.
+/// Without one the body is sniffed as UTF-8.
+fn is_text_type(content_type: Option<&str>) -> bool {
+    let Some(content_type) = content_type else {
+        return true;
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime.as_str(),
+            "application/json"
+                | "application/javascript"
+                | "application/xml"
+                | "application/x-www-form-urlencoded"
+        )
+}
+
+/// The body as text when it is declared textual and valid UTF-8, else as
+/// base64. Returns the body and whether it is base64.
+fn encode_body(content_type: Option<&str>, bytes: Vec<u8>) -> (String, bool) {
+    let bytes = if is_text_type(content_type) {
+        match String::from_utf8(bytes) {
+            Ok(text) => return (text, false),
+            Err(e) => e.into_bytes(),
+        }
+    } else {
+        bytes
+    };
+    (STANDARD.encode(bytes), true)
+}
+
+/// `https://<domain>/`, for matching cookies against a bare domain.
+fn domain_url(domain: &str) -> Result<Url, ApiClientError> {
+    let host = domain.trim().trim_start_matches('.');
+    Url::parse(&format!("https://{}/", host))
+        .ok()
+        .filter(|url| {
+            url.host_str()
+                .is_some_and(|h| h == host.to_ascii_lowercase())
+        })
+        .ok_or_else(|| ApiClientError::InvalidRequest(format!("invalid domain '{}'", domain)))
+}
+
+fn cookie_info(cookie: &Cookie<'_>) -> CookieInfo {
+    CookieInfo {
+        name: cookie.name().to_string(),
+        value: cookie.value().to_string(),
+        domain: cookie.domain.as_cow().unwrap_or_default().into_owned(),
+        path: String::from(cookie.path.clone()),
+        expires_ms: match &cookie.expires {
+            CookieExpiration::AtUtc(at) => Some(at.unix_timestamp() * 1000),
+            CookieExpiration::SessionEnd => None,
+        },
+        secure: cookie.secure().unwrap_or(false),
+        http_only: cookie.http_only().unwrap_or(false),
+    }
+}
+
+/// Where a response with `status` sends a `method` request next, if it is
+/// a redirect to follow. 301/302/303 turn anything but GET/HEAD into a GET
+/// without a body, as browsers do; 307/308 repeat the request.
+fn next_hop(
+    status: StatusCode,
+    method: &Method,
+    url: &Url,
+    location: Option<&str>,
+) -> Option<(Url, Method)> {
+    let method = match status {
+        StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER
+            if *method != Method::GET && *method != Method::HEAD =>
+        {
+            Method::GET
+        }
+        StatusCode::MOVED_PERMANENTLY
+        | StatusCode::FOUND
+        | StatusCode::SEE_OTHER
+        | StatusCode::TEMPORARY_REDIRECT
+        | StatusCode::PERMANENT_REDIRECT => method.clone(),
+        _ => return None,
+    };
+    Some((url.join(location?).ok()?, method))
+}
+
+/// Send an HTTP request from the backend. `timeout_ms` overrides the
+/// per-attempt timeout of the HTTP policy.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn api_fetch(
+    app: AppHandle,
+    client: State<'_, ApiClient>,
+    store: State<'_, SettingsStore>,
+    url: String,
+    method: Option<String>,
+    headers: Option<BTreeMap<String, String>>,
+    mut body: Option<String>,
+    use_cookies: bool,
+    timeout_ms: Option<u64>,
+) -> Result<ApiResponse, ApiClientError> {
+    let mut url = Url::parse(&url)
+        .map_err(|e| ApiClientError::InvalidRequest(format!("invalid URL '{}': {}", url, e)))?;
+    let mut method = match method {
+        Some(method) => Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| ApiClientError::InvalidRequest(format!("invalid method '{}'", method)))?,
+        None => Method::GET,
+    };
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers.unwrap_or_default() {
+        let header = HeaderName::from_bytes(name.as_bytes())
+            .ok()
+            .zip(HeaderValue::from_str(&value).ok())
+            .ok_or_else(|| ApiClientError::InvalidRequest(format!("invalid header '{}'", name)))?;
+        header_map.append(header.0, header.1);
+    }
+    let mut policy = store.read(|s| s.http.clone());
+    if let Some(timeout_ms) = timeout_ms {
+        policy.timeout_ms = timeout_ms.clamp(1_000, 300_000);
+    }
+
+    let mut stored = false;
+    let mut redirects = 0;
+    let response = loop {
+        let mut hop_headers = header_map.clone();
+        if use_cookies {
+            if let Some(cookies) = client.cookie_header(&url) {
+                let merged = match hop_headers.get(COOKIE).and_then(|v| v.to_str().ok()) {
+                    Some(own) => format!("{}; {}", own, cookies),
+                    None => cookies,
+                };
+                let value = HeaderValue::from_str(&merged)
+                    .map_err(|_| ApiClientError::InvalidRequest("invalid cookie header".into()))?;
+                hop_headers.insert(COOKIE, value);
+            }
+        }
+        let mut request = http_util::client()
+            .request(method.clone(), url.clone())
+            .headers(hop_headers);
+        if let Some(body) = &body {
+            request = request.body(body.clone());
+        }
+        let request = request
+            .build()
+            .map_err(|e| ApiClientError::InvalidRequest(e.to_string()))?;
+        let response = http_util::fetch_hop_with_policy(request, &policy).await?;
+        if use_cookies {
+            stored |= client.store_response(response.url(), response.headers());
+        }
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok());
+        let Some((next, next_method)) = next_hop(response.status(), &method, &url, location)
+            .filter(|_| redirects < MAX_REDIRECTS)
+        else {
+            break response;
+        };
+        redirects += 1;
+        if next_method != method {
+            body = None;
+            header_map.remove(CONTENT_TYPE);
+            header_map.remove(CONTENT_LENGTH);
+        }
+        // Credentials the caller set are for the original origin only.
+        if next.origin() != url.origin() {
+            for name in [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION] {
+                header_map.remove(name);
+            }
+        }
+        (url, method) = (next, next_method);
+    };
+    if stored {
+        // The response does not wait for the jar to reach disk.
+        let save = save_on_blocking_pool(app);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = save.await {
+                warn!("{}", e);
+            }
+        });
+    }
+    let status = response.status().as_u16();
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| ApiClientError::Request(e.to_string()))?;
+    let (body, base64) = encode_body(
+        headers.get(CONTENT_TYPE.as_str()).map(String::as_str),
+        bytes.to_vec(),
+    );
+    Ok(ApiResponse {
+        status,
+        headers,
+        body,
+        base64,
+    })
+}
+
+/// Seal and write the jar on the blocking pool; encryption and the file
+/// write would otherwise hold up an async worker.
+async fn save_on_blocking_pool(app: AppHandle) -> Result<(), ApiClientError> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<ApiClient>().save())
+        .await
+        .map_err(|e| ApiClientError::Io(e.to_string()))?
+}
+
+/// Unexpired cookies that would be sent to `domain`.
+#[tauri::command]
+pub fn cookies_get(
+    client: State<'_, ApiClient>,
+    domain: String,
+) -> Result<Vec<CookieInfo>, ApiClientError> {
+    let url = domain_url(&domain)?;
+    let jar = client.jar.lock();
+    Ok(jar
+        .iter_unexpired()
+        .filter(|cookie| cookie.domain.matches(&url))
+        .map(cookie_info)
+        .collect())
+}
+
+/// Add or replace a cookie for `domain` and its subdomains, path `/`,
+/// valid for a year.
+#[tauri::command]
+pub async fn cookies_set(
+    app: AppHandle,
+    client: State<'_, ApiClient>,
+    domain: String,
+    name: String,
+    value: String,
+) -> Result<(), ApiClientError> {
+    let url = domain_url(&domain)?;
+    let raw = RawCookie::build((name, value))
+        .domain(url.host_str().unwrap_or_default().to_string())
+        .path("/")
+        .build();
+    let mut cookie = Cookie::try_from_raw_cookie(&raw, &url)
+        .map_err(|e| ApiClientError::Cookie(e.to_string()))?;
+    cookie.expires = CookieExpiration::from(SET_COOKIE_MAX_AGE_SECS);
+    client
+        .jar
+        .lock()
+        .insert(cookie.into_owned(), &url)
+        .map_err(|e| ApiClientError::Cookie(e.to_string()))?;
+    save_on_blocking_pool(app).await
+}
+
+#[tauri::command]
+pub async fn cookies_clear(
+    app: AppHandle,
+    client: State<'_, ApiClient>,
+) -> Result<(), ApiClientError> {
+    client.jar.lock().clear();
+    info!("Cookie jar cleared");
+    save_on_blocking_pool(app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jar_round_trips_persistent_cookies_only() {
+        let url = Url::parse("https://music.163.com/api/login").unwrap();
+        let mut jar = CookieStore::default();
+        jar.parse("MUSIC_U=abc; Domain=163.com; Path=/; Max-Age=3600", &url)
+            .unwrap();
+        jar.parse("__csrf=xyz; Path=/", &url).unwrap();
+
+        let key = [7u8; 32];
+        let data = seal_jar(&key, &jar).unwrap();
+        assert!(!data.windows(3).any(|w| w == b"abc"));
+        let loaded = open_jar(&key, &data).unwrap();
+        let names: Vec<&str> = loaded.iter_unexpired().map(|c| c.name()).collect();
+        assert_eq!(names, vec!["MUSIC_U"]);
+        assert!(open_jar(&[8u8; 32], &data).is_err());
+        assert!(open_jar(&key, b"GMCK").is_err());
+    }
+
+    #[test]
+    fn redirects_keep_or_downgrade_the_method() {
+        let url = Url::parse("https://music.163.com/api/login").unwrap();
+        let hop = |status, method: &Method, location| {
+            next_hop(status, method, &url, location).map(|(url, method)| (url.to_string(), method))
+        };
+        assert_eq!(
+            hop(StatusCode::FOUND, &Method::POST, Some("/done")),
+            Some(("https://music.163.com/done".into(), Method::GET))
+        );
+        assert_eq!(
+            hop(
+                StatusCode::TEMPORARY_REDIRECT,
+                &Method::POST,
+                Some("https://interface.music.163.com/api/login")
+            ),
+            Some((
+                "https://interface.music.163.com/api/login".into(),
+                Method::POST
+            ))
+        );
+        assert_eq!(
+            hop(StatusCode::SEE_OTHER, &Method::HEAD, Some("next")),
+            Some(("https://music.163.com/api/next".into(), Method::HEAD))
+        );
+        assert_eq!(hop(StatusCode::FOUND, &Method::GET, None), None);
+        assert_eq!(hop(StatusCode::NOT_MODIFIED, &Method::GET, Some("/")), None);
+    }
+
+    #[test]
+    fn bodies_are_text_only_when_textual_and_utf8() {
+        let json = br#"{"code":200}"#.to_vec();
+        assert_eq!(
+            encode_body(Some("application/json;charset=UTF-8"), json.clone()),
+            (r#"{"code":200}"#.to_string(), false)
+        );
+        assert!(!encode_body(None, json).1);
+        assert_eq!(
+            encode_body(Some("image/png"), vec![0x89, b'P']),
+            ("iVA=".to_string(), true)
+        );
+        assert!(encode_body(Some("text/plain"), vec![0xff, 0xfe]).1);
+    }
+}
@@ -0,0 +1,708 @@
+//! API response cache and play history in SQLite.
+//!
+//! localStorage tops out around 5 MB and goes whenever the webview storage
+//! is cleared, so API responses worth keeping are stored here instead, in
+//! the `cache` table with an optional expiry and ETag. Next to it,
+//! `play_history` is the one record of what was played when and for how
+//! long; the listening report export (`history`) reads it too.
+//! `lyric_offsets` holds the per-track lyric offsets migrated from the old
+//! offsets file (see `first_run`).
+//!
+//! Reads use their own connection and never wait for the writer. Writes
+//! are sent over a channel to one writer thread, which applies whatever has
+//! queued up in a single transaction, so concurrent commands never race
+//! each other for the write lock and fail with `SQLITE_BUSY`. When the
+//! writer starts it purges expired rows and hands the freed pages back to
+//! the file system.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+pub const CACHE_DB_FILE: &str = "cache.sqlite3";
+/// Upper bound on a single `history_query` call.
+const MAX_HISTORY_ROWS: usize = 5_000;
+/// Writes applied in one transaction at most.
+const MAX_BATCH: usize = 256;
+/// Expired entries with an ETag are kept this long for revalidation.
+const STALE_KEEP_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS cache (
+        key TEXT PRIMARY KEY,
+        value BLOB NOT NULL,
+        etag TEXT,
+        expires_at INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS cache_expires_at ON cache (expires_at);
+    CREATE TABLE IF NOT EXISTS play_history (
+        id INTEGER PRIMARY KEY,
+        song_id TEXT NOT NULL,
+        played_at INTEGER NOT NULL,
+        duration_listened INTEGER NOT NULL,
+        track TEXT NOT NULL DEFAULT '',
+        artist TEXT NOT NULL DEFAULT '',
+        album TEXT NOT NULL DEFAULT '',
+        source TEXT NOT NULL DEFAULT ''
+    );
+    CREATE INDEX IF NOT EXISTS play_history_played_at ON play_history (played_at);
+    CREATE TABLE IF NOT EXISTS lyric_offsets (
+        track_id TEXT PRIMARY KEY,
+        offset_ms INTEGER NOT NULL
+    );
+";
+
+/// A cached response. Expired entries are only returned when they carry an
+/// ETag, so the caller can revalidate instead of refetching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub value: String,
+    pub etag: Option<String>,
+    /// Milliseconds since the epoch; `None` never expires.
+    pub expires_at: Option<i64>,
+    pub expired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayRecord {
+    pub song_id: String,
+    /// Milliseconds since the epoch.
+    pub played_at: i64,
+    /// Milliseconds actually listened.
+    pub duration_listened: i64,
+    #[serde(flatten)]
+    pub details: PlayDetails,
+}
+
+/// What was played, for the listening report. Names are kept as they were
+/// at play time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlayDetails {
+    pub track: String,
+    pub artist: String,
+    pub album: String,
+    /// Where the track came from: "local", "netease", ...
+    pub source: String,
+}
+
+/// Inclusive `played_at` bounds; `None` is open-ended.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlayRange {
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub cache_rows: u64,
+    pub history_rows: u64,
+    /// Database plus WAL; 0 when kept in memory.
+    pub file_bytes: u64,
+}
+
+enum Write {
+    Set {
+        key: String,
+        value: String,
+        etag: Option<String>,
+        expires_at: Option<i64>,
+    },
+    Evict {
+        prefix: String,
+    },
+    AddPlay(PlayRecord),
+    Purge {
+        now: i64,
+    },
+}
+
+struct Job {
+    write: Write,
+    /// Rows changed, or the error; `None` for fire-and-forget writes.
+    reply: Option<oneshot::Sender<Result<usize, String>>>,
+}
+
+/// Where the connections point: a file, or a private shared-cache memory
+/// database when the app data dir is unavailable.
+#[derive(Clone)]
+enum Location {
+    File(PathBuf),
+    Memory(String),
+}
+
+impl Location {
+    fn memory() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        Self::Memory(format!(
+            "file:gmplayer-cache-{}-{}?mode=memory&cache=shared",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    fn connect(&self) -> Result<Connection, String> {
+        let conn = self.open()?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        Ok(conn)
+    }
+
+    fn open(&self) -> Result<Connection, String> {
+        Ok(match self {
+            Self::File(path) => {
+                let conn = Connection::open(path).map_err(|e| e.to_string())?;
+                // Only takes effect on a new file, before the first table.
+                conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")
+                    .map_err(|e| e.to_string())?;
+                conn.pragma_update(None, "journal_mode", "WAL")
+                    .map_err(|e| e.to_string())?;
+                conn
+            }
+            Self::Memory(uri) => {
+                let conn = Connection::open_with_flags(
+                    uri,
+                    OpenFlags::SQLITE_OPEN_READ_WRITE
+                        | OpenFlags::SQLITE_OPEN_CREATE
+                        | OpenFlags::SQLITE_OPEN_URI,
+                )
+                .map_err(|e| e.to_string())?;
+                // Shared-cache readers would otherwise fail with
+                // `SQLITE_LOCKED` while the writer holds a table.
+                conn.pragma_update(None, "read_uncommitted", true)
+                    .map_err(|e| e.to_string())?;
+                conn
+            }
+        })
+    }
+}
+
+/// Managed SQLite-backed cache and play history.
+pub struct CacheStore {
+    /// Lookups only. In WAL mode a reader sees the last commit and does not
+    /// wait for a write batch in progress.
+    read: Mutex<Connection>,
+    /// The writer thread locks this per batch.
+    write: Arc<Mutex<Connection>>,
+    jobs: mpsc::Sender<Job>,
+    location: Location,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl CacheStore {
+    /// Open (or create) the cache database in the app data dir and start
+    /// the writer, which first purges expired rows. Falls back to memory
+    /// so the commands keep working; fails only if that does too.
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => {
+                let _ = std::fs::create_dir_all(&dir);
+                Some(dir.join(CACHE_DB_FILE))
+            }
+            Err(e) => {
+                warn!("Failed to resolve app data dir for the cache store: {}", e);
+                None
+            }
+        };
+        let opened = match &path {
+            Some(path) => Self::open(Location::File(path.clone())),
+            None => Err("no app data dir".to_string()),
+        };
+        let store = match opened {
+            Ok(store) => store,
+            Err(e) => {
+                warn!("Cache store unavailable, caching in memory: {}", e);
+                Self::open(Location::memory())?
+            }
+        };
+        store.send(Write::Purge { now: now_ms() }, None);
+        Ok(store)
+    }
+
+    fn open(location: Location) -> Result<Self, String> {
+        // The writer connects first so the schema exists for the reader.
+        let write = Arc::new(Mutex::new(location.connect()?));
+        let read = Mutex::new(location.connect()?);
+        let (jobs, queue) = mpsc::channel();
+        let writer = write.clone();
+        std::thread::Builder::new()
+            .name("cache-writer".into())
+            .spawn(move || run_writer(&writer, &queue))
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            read,
+            write,
+            jobs,
+            location,
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match &self.location {
+            Location::File(path) => Some(path),
+            Location::Memory(_) => None,
+        }
+    }
+
+    /// A separate connection for long reads (the history export), so they
+    /// hold up neither lookups nor writes.
+    pub(crate) fn reader(&self) -> Result<Connection, String> {
+        self.location.open()
+    }
+
+    /// Close the database file while `f` runs (so it can be replaced or
+    /// moved), then reopen it. Reads and the writer wait meanwhile.
+    pub fn with_file_released<T>(&self, f: impl FnOnce() -> T) -> Result<T, String> {
+        let path = self.path().ok_or("Cache store has no file")?;
+        let mut write = self.write.lock();
+        let mut read = self.read.lock();
+        let placeholder = Location::memory();
+        *write = placeholder.connect()?;
+        *read = placeholder.connect()?;
+        let result = f();
+        let location = Location::File(path.to_path_buf());
+        *write = location.connect()?;
+        *read = location.connect()?;
+        Ok(result)
+    }
+
+    fn send(&self, write: Write, reply: Option<oneshot::Sender<Result<usize, String>>>) {
+        if self.jobs.send(Job { write, reply }).is_err() {
+            warn!("Cache writer has stopped; write dropped");
+        }
+    }
+
+    /// Purge expired rows and wait for it. The writer works in order, so
+    /// everything queued before has landed once this returns.
+    pub fn flush(&self) -> Result<usize, String> {
+        tauri::async_runtime::block_on(self.write(Write::Purge { now: now_ms() }))
+    }
+
+    async fn write(&self, write: Write) -> Result<usize, String> {
+        let (reply, done) = oneshot::channel();
+        self.send(write, Some(reply));
+        done.await
+            .map_err(|_| "Cache writer has stopped".to_string())?
+    }
+
+    fn get(&self, key: &str, now: i64) -> Result<Option<CacheEntry>, String> {
+        let row = self
+            .read
+            .lock()
+            .query_row(
+                "SELECT value, etag, expires_at FROM cache WHERE key = ?1",
+                [key],
+                |row| {
+                    Ok((
+                        row.get::<_, Vec<u8>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        Ok(row.and_then(|(value, etag, expires_at)| {
+            let expired = expires_at.is_some_and(|at| at <= now);
+            (!expired || etag.is_some()).then(|| CacheEntry {
+                value: String::from_utf8_lossy(&value).into_owned(),
+                etag,
+                expires_at,
+                expired,
+            })
+        }))
+    }
+
+    fn history(&self, range: PlayRange, limit: usize) -> Result<Vec<PlayRecord>, String> {
+        let read = self.read.lock();
+        let mut stmt = read
+            .prepare_cached(
+                "SELECT song_id, played_at, duration_listened, track, artist, album, source
+                 FROM play_history
+                 WHERE played_at >= ?1 AND played_at <= ?2
+                 ORDER BY played_at DESC, id DESC LIMIT ?3",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![
+                    range.from_ms.unwrap_or(i64::MIN),
+                    range.to_ms.unwrap_or(i64::MAX),
+                    limit.min(MAX_HISTORY_ROWS) as i64
+                ],
+                |row| {
+                    Ok(PlayRecord {
+                        song_id: row.get(0)?,
+                        played_at: row.get(1)?,
+                        duration_listened: row.get(2)?,
+                        details: PlayDetails {
+                            track: row.get(3)?,
+                            artist: row.get(4)?,
+                            album: row.get(5)?,
+                            source: row.get(6)?,
+                        },
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+
+    fn stats(&self) -> Result<CacheStats, String> {
+        let read = self.read.lock();
+        let count = |table: &str| {
+            read.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| count as u64)
+            .map_err(|e| e.to_string())
+        };
+        let file_bytes = self.path().map_or(0, |path| {
+            ["", "-wal"]
+                .iter()
+                .filter_map(|suffix| {
+                    let mut name = path.as_os_str().to_owned();
+                    name.push(suffix);
+                    std::fs::metadata(PathBuf::from(name)).ok()
+                })
+                .map(|meta| meta.len())
+                .sum()
+        });
+        Ok(CacheStats {
+            cache_rows: count("cache")?,
+            history_rows: count("play_history")?,
+            file_bytes,
+        })
+    }
+}
+
+/// Write lyric offsets into the database at `path`, creating it if needed.
+/// Runs before the store is opened, so it uses a connection of its own.
+/// Offsets already in the table are kept. Returns how many were added.
+pub(crate) fn import_lyric_offsets(
+    path: &Path,
+    offsets: &[(String, i64)],
+) -> Result<usize, String> {
+    let mut conn = Location::File(path.to_path_buf()).connect()?;
+    insert_lyric_offsets(&mut conn, offsets).map_err(|e| e.to_string())
+}
+
+fn insert_lyric_offsets(
+    conn: &mut Connection,
+    offsets: &[(String, i64)],
+) -> rusqlite::Result<usize> {
+    let tx = conn.transaction()?;
+    let mut added = 0;
+    {
+        let mut insert = tx
+            .prepare("INSERT OR IGNORE INTO lyric_offsets (track_id, offset_ms) VALUES (?1, ?2)")?;
+        for (track_id, offset_ms) in offsets {
+            added += insert.execute(params![track_id, offset_ms])?;
+        }
+    }
+    tx.commit()?;
+    Ok(added)
+}
+
+fn apply(conn: &Connection, write: &Write) -> rusqlite::Result<usize> {
+    match write {
+        Write::Set {
+            key,
+            value,
+            etag,
+            expires_at,
+        } => conn.execute(
+            "INSERT OR REPLACE INTO cache (key, value, etag, expires_at) VALUES (?1, ?2, ?3, ?4)",
+            params![key, value.as_bytes(), etag, expires_at],
+        ),
+        Write::Evict { prefix } => conn.execute(
+            "DELETE FROM cache WHERE substr(key, 1, length(?1)) = ?1",
+            [prefix],
+        ),
+        Write::AddPlay(record) => conn.execute(
+            "INSERT INTO play_history
+             (song_id, played_at, duration_listened, track, artist, album, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                record.song_id,
+                record.played_at,
+                record.duration_listened,
+                record.details.track,
+                record.details.artist,
+                record.details.album,
+                record.details.source
+            ],
+        ),
+        Write::Purge { now } => conn.execute(
+            "DELETE FROM cache WHERE expires_at <= ?1 AND (etag IS NULL OR expires_at <= ?2)",
+            params![now, now - STALE_KEEP_MS],
+        ),
+    }
+}
+
+/// Apply queued writes until every sender is gone. Each batch is one
+/// transaction; a failing write is rolled back on its own (through a
+/// savepoint) without taking the rest of the batch with it.
+fn run_writer(conn: &Mutex<Connection>, queue: &mpsc::Receiver<Job>) {
+    while let Ok(first) = queue.recv() {
+        let mut batch = vec![first];
+        batch.extend(queue.try_iter().take(MAX_BATCH - 1));
+
+        let mut guard = conn.lock();
+        let mut results = Vec::with_capacity(batch.len());
+        let committed = guard
+            .transaction()
+            .and_then(|mut tx| {
+                for job in &batch {
+                    let result = tx.savepoint().and_then(|mut sp| {
+                        let changed = apply(&sp, &job.write)?;
+                        sp.commit()?;
+                        Ok(changed)
+                    });
+                    results.push(result.map_err(|e| e.to_string()));
+                }
+                tx.commit()
+            })
+            .map_err(|e| e.to_string());
+        if let Err(e) = &committed {
+            warn!("Cache write batch of {} failed: {}", batch.len(), e);
+        }
+
+        let mut purged = None;
+        for (job, result) in batch.into_iter().zip(results) {
+            let result = committed.clone().and(result);
+            if let (Write::Purge { .. }, Ok(rows)) = (&job.write, &result) {
+                purged = Some(*rows);
+            }
+            if let Some(reply) = job.reply {
+                let _ = reply.send(result);
+            }
+        }
+        if let Some(rows) = purged {
+            info!("Cache store purged {} expired entries", rows);
+            if let Err(e) = guard.execute_batch("PRAGMA incremental_vacuum;") {
+                warn!("Cache store vacuum failed: {}", e);
+            }
+        }
+    }
+}
+
+fn unavailable() -> String {
+    "Cache store unavailable".to_string()
+}
+
+/// Run a lookup on the blocking pool; SQLite calls must not hold up the
+/// async runtime or the main thread.
+async fn read<T: Send + 'static>(
+    app: AppHandle,
+    f: impl FnOnce(&CacheStore) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.try_state::<CacheStore>().ok_or_else(unavailable)?;
+        f(&store)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn write(app: &AppHandle, write: Write) -> Result<usize, String> {
+    let store = app.try_state::<CacheStore>().ok_or_else(unavailable)?;
+    store.write(write).await
+}
+
+/// The cached value for `key`, or `None` if missing or expired without an
+/// ETag.
+#[tauri::command]
+pub async fn cache_get(app: AppHandle, key: String) -> Result<Option<CacheEntry>, String> {
+    read(app, move |store| store.get(&key, now_ms())).await
+}
+
+/// Store `value` under `key`. Without `ttl_secs` the entry never expires.
+#[tauri::command]
+pub async fn cache_set(
+    app: AppHandle,
+    key: String,
+    value: String,
+    ttl_secs: Option<u64>,
+    etag: Option<String>,
+) -> Result<(), String> {
+    let expires_at = ttl_secs.map(|ttl| now_ms().saturating_add(ttl as i64 * 1000));
+    write(
+        &app,
+        Write::Set {
+            key,
+            value,
+            etag,
+            expires_at,
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Drop every entry whose key starts with `prefix` (all of them for an
+/// empty prefix). Returns how many went.
+#[tauri::command]
+pub async fn cache_evict(app: AppHandle, prefix: String) -> Result<usize, String> {
+    write(&app, Write::Evict { prefix }).await
+}
+
+/// Record one play. `details` names the track for the listening report.
+#[tauri::command]
+pub async fn history_add(
+    app: AppHandle,
+    song_id: String,
+    played_at: i64,
+    duration_listened: i64,
+    details: Option<PlayDetails>,
+) -> Result<(), String> {
+    write(
+        &app,
+        Write::AddPlay(PlayRecord {
+            song_id,
+            played_at,
+            duration_listened,
+            details: details.unwrap_or_default(),
+        }),
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Plays within `range`, newest first.
+#[tauri::command]
+pub async fn history_query(
+    app: AppHandle,
+    range: PlayRange,
+    limit: usize,
+) -> Result<Vec<PlayRecord>, String> {
+    read(app, move |store| store.history(range, limit)).await
+}
+
+#[tauri::command]
+pub async fn cache_stats(app: AppHandle) -> Result<CacheStats, String> {
+    read(app, CacheStore::stats).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_store() -> CacheStore {
+        CacheStore::open(Location::memory()).unwrap()
+    }
+
+    fn set(key: &str, etag: Option<&str>, expires_at: Option<i64>) -> Write {
+        Write::Set {
+            key: key.into(),
+            value: format!("{{\"key\":\"{}\"}}", key),
+            etag: etag.map(str::to_string),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn expired_entries_are_hidden_unless_they_can_be_revalidated() {
+        let store = memory_store();
+        tauri::async_runtime::block_on(async {
+            store.write(set("song/1", None, Some(1_000))).await.unwrap();
+            store
+                .write(set("song/2", Some("\"v2\""), Some(1_000)))
+                .await
+                .unwrap();
+            store.write(set("album/1", None, None)).await.unwrap();
+        });
+        assert!(store.get("song/1", 500).unwrap().is_some());
+        assert!(store.get("song/1", 1_000).unwrap().is_none());
+        let stale = store.get("song/2", 2_000).unwrap().unwrap();
+        assert!(stale.expired);
+        assert_eq!(stale.value, r#"{"key":"song/2"}"#);
+
+        let purged = tauri::async_runtime::block_on(store.write(Write::Purge { now: 2_000 }));
+        assert_eq!(purged, Ok(1));
+        let evicted = tauri::async_runtime::block_on(store.write(Write::Evict {
+            prefix: "song/".into(),
+        }));
+        assert_eq!(evicted, Ok(1));
+        assert_eq!(store.stats().unwrap().cache_rows, 1);
+    }
+
+    #[test]
+    fn reads_do_not_wait_for_a_write_batch() {
+        let store = memory_store();
+        tauri::async_runtime::block_on(store.write(set("album/1", None, None))).unwrap();
+        let _batch = store.write.lock();
+        assert!(store.get("album/1", 0).unwrap().is_some());
+        assert_eq!(store.stats().unwrap().cache_rows, 1);
+    }
+
+    #[test]
+    fn concurrent_writes_are_all_applied() {
+        let store = Arc::new(memory_store());
+        let writers: Vec<_> = (0..8)
+            .map(|thread| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    for play in 0..50 {
+                        let record = PlayRecord {
+                            song_id: format!("{}", thread),
+                            played_at: thread * 1_000 + play,
+                            duration_listened: 30_000,
+                            details: PlayDetails::default(),
+                        };
+                        tauri::async_runtime::block_on(store.write(Write::AddPlay(record)))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        writers
+            .into_iter()
+            .for_each(|writer| writer.join().unwrap());
+
+        assert_eq!(store.stats().unwrap().history_rows, 400);
+        let range = PlayRange {
+            from_ms: Some(7_000),
+            to_ms: None,
+        };
+        let latest = store.history(range, 3).unwrap();
+        assert_eq!(
+            latest.iter().map(|play| play.played_at).collect::<Vec<_>>(),
+            [7_049, 7_048, 7_047]
+        );
+    }
+
+    #[test]
+    fn imported_lyric_offsets_keep_existing_rows() {
+        let mut conn = Location::memory().connect().unwrap();
+        let offsets = [("1".to_string(), 500), ("2".to_string(), -250)];
+        assert_eq!(insert_lyric_offsets(&mut conn, &offsets).unwrap(), 2);
+        let again = [("2".to_string(), 1_000), ("3".to_string(), 0)];
+        assert_eq!(insert_lyric_offsets(&mut conn, &again).unwrap(), 1);
+        let offset: i64 = conn
+            .query_row(
+                "SELECT offset_ms FROM lyric_offsets WHERE track_id = '2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(offset, -250);
+    }
+}
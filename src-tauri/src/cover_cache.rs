@@ -0,0 +1,505 @@
+//! Downloaded album covers, shared by every window.
+//!
+//! The main window, mini player, tray popup and desktop lyrics all show the
+//! same covers; instead of each webview downloading them, [`get_cover`]
+//! fetches a URL once, optionally crops and scales it to a square, and
+//! keeps the file in `covers/` under the app cache dir, named by the URL
+//! hash and size. The returned path goes through `convertFileSrc`, and the
+//! system media controls can hand the same file to the OS.
+//!
+//! Custom covers live in the app data dir, outside the asset protocol's
+//! scope, so [`get_cover`] serves a copy of them from the same folder.
+//!
+//! Requests for a file that is already being fetched wait for that fetch.
+//! Files are evicted least recently used first once the folder grows past
+//! the configured size; the last use is the file's modification time, so
+//! the order survives restarts. A file handed out within the last
+//! [`IN_USE_GRACE`] is never evicted, so a webview still loading it does
+//! not find it gone.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use log::{info, warn};
+use md5::{Digest, Md5};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::OnceCell;
+
+use crate::covers::{CustomCover, CustomCoverState, MAX_INPUT_BYTES};
+use crate::http_util::{self, HttpError};
+use crate::settings::SettingsStore;
+
+const CACHE_DIR: &str = "covers";
+/// Requested sizes are clamped to this edge.
+const MAX_SIZE: u32 = 2048;
+const MAX_SOURCE_EDGE: u32 = 12_000;
+const JPEG_QUALITY: u8 = 90;
+/// How long a file returned by [`get_cover`] is kept from eviction.
+const IN_USE_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CoverCacheError {
+    InvalidUrl(String),
+    /// Network failure or a non-success status.
+    Download(String),
+    /// The response is not a decodable PNG or JPEG.
+    InvalidImage(String),
+    Io(String),
+}
+
+impl fmt::Display for CoverCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl(url) => write!(f, "Invalid cover URL '{}'", url),
+            Self::Download(message) => write!(f, "Cover download failed: {}", message),
+            Self::InvalidImage(message) => write!(f, "Invalid cover image: {}", message),
+            Self::Io(message) => write!(f, "Failed to cache cover: {}", message),
+        }
+    }
+}
+
+impl From<std::io::Error> for CoverCacheError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<HttpError> for CoverCacheError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::InvalidUrl(url) => Self::InvalidUrl(url),
+            e => Self::Download(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CachedFile {
+    bytes: u64,
+    used: SystemTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverCacheStats {
+    pub files: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+}
+
+type Fetch = Arc<OnceCell<Result<PathBuf, CoverCacheError>>>;
+
+/// Managed cover cache.
+pub struct CoverCache {
+    dir: Option<PathBuf>,
+    /// Cached files by name.
+    files: Mutex<HashMap<String, CachedFile>>,
+    in_flight: Mutex<HashMap<String, Fetch>>,
+}
+
+impl CoverCache {
+    /// Index the files left by earlier runs.
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = match app.path().app_cache_dir() {
+            Ok(dir) => Some(dir.join(CACHE_DIR)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app cache dir, covers will not be cached: {}",
+                    e
+                );
+                None
+            }
+        };
+        let files = dir.as_deref().map(scan).unwrap_or_default();
+        Self {
+            dir,
+            files: Mutex::new(files),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn dir(&self) -> Result<&Path, CoverCacheError> {
+        self.dir
+            .as_deref()
+            .ok_or_else(|| CoverCacheError::Io("app cache dir unavailable".into()))
+    }
+
+    /// The cached file for `name`, marked as just used.
+    fn hit(&self, dir: &Path, name: &str) -> Option<PathBuf> {
+        let mut files = self.files.lock();
+        let file = files.get_mut(name)?;
+        let path = dir.join(name);
+        let now = SystemTime::now();
+        let touched = std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|handle| handle.set_modified(now));
+        if let Err(e) = touched {
+            // Deleted behind our back; fetch it again.
+            if e.kind() == std::io::ErrorKind::NotFound {
+                files.remove(name);
+                return None;
+            }
+        }
+        file.used = now;
+        Some(path)
+    }
+
+    fn insert(&self, dir: &Path, name: String, bytes: u64, max_bytes: u64) {
+        let mut files = self.files.lock();
+        files.insert(
+            name.clone(),
+            CachedFile {
+                bytes,
+                used: SystemTime::now(),
+            },
+        );
+        evict(dir, &mut files, max_bytes);
+    }
+
+    fn stats(&self, max_bytes: u64) -> CoverCacheStats {
+        let files = self.files.lock();
+        CoverCacheStats {
+            files: files.len(),
+            bytes: files.values().map(|file| file.bytes).sum(),
+            max_bytes,
+        }
+    }
+}
+
+/// Delete files until the cache fits `max_bytes`, sparing any used within
+/// [`IN_USE_GRACE`]. Runs under the same lock as [`CoverCache::hit`], so a
+/// path being handed out is never deleted underneath it.
+fn evict(dir: &Path, files: &mut HashMap<String, CachedFile>, max_bytes: u64) {
+    let in_use_since = SystemTime::now() - IN_USE_GRACE;
+    for victim in eviction_order(files, max_bytes, in_use_since) {
+        files.remove(&victim);
+        if let Err(e) = std::fs::remove_file(dir.join(&victim)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to evict cover {}: {}", victim, e);
+            }
+        }
+    }
+}
+
+/// Every cached file with its size and last use.
+fn scan(dir: &Path) -> HashMap<String, CachedFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(|meta| meta.is_file())?;
+            let name = entry.file_name().into_string().ok()?;
+            // Leftovers of an interrupted write.
+            if name.ends_with(".tmp") {
+                let _ = std::fs::remove_file(entry.path());
+                return None;
+            }
+            let file = CachedFile {
+                bytes: meta.len(),
+                used: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            };
+            Some((name, file))
+        })
+        .collect()
+}
+
+/// Files to delete, least recently used first, to get under `max_bytes`.
+/// Files used at or after `in_use_since` are kept even if that leaves the
+/// cache over its limit for a while.
+fn eviction_order(
+    files: &HashMap<String, CachedFile>,
+    max_bytes: u64,
+    in_use_since: SystemTime,
+) -> Vec<String> {
+    let mut total: u64 = files.values().map(|file| file.bytes).sum();
+    let mut by_age: Vec<(&String, &CachedFile)> = files
+        .iter()
+        .filter(|(_, file)| file.used < in_use_since)
+        .collect();
+    by_age.sort_by_key(|(name, file)| (file.used, *name));
+    let mut victims = Vec::new();
+    for (name, file) in by_age {
+        if total <= max_bytes {
+            break;
+        }
+        total -= file.bytes;
+        victims.push(name.clone());
+    }
+    victims
+}
+
+/// `<md5 of url>-<size or "orig">.<ext>`.
+fn file_name(url: &str, size: Option<u32>, ext: &str) -> String {
+    let size = size.map_or_else(|| "orig".to_string(), |size| size.to_string());
+    format!(
+        "{}-{}.{}",
+        hex::encode(Md5::digest(url.as_bytes())),
+        size,
+        ext
+    )
+}
+
+fn max_bytes(app: &AppHandle) -> u64 {
+    app.try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.cover_cache.clone()))
+        .unwrap_or_default()
+        .max_bytes()
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<DynamicImage, CoverCacheError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| CoverCacheError::InvalidImage(e.to_string()))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_EDGE);
+    limits.max_image_height = Some(MAX_SOURCE_EDGE);
+    reader.limits(limits);
+    reader
+        .decode()
+        .map_err(|e| CoverCacheError::InvalidImage(e.to_string()))
+}
+
+/// The bytes to store and their extension: the download as-is without a
+/// size, or a `size`-pixel square JPEG cropped from its centre.
+fn prepare(bytes: Vec<u8>, size: Option<u32>) -> Result<(Vec<u8>, &'static str), CoverCacheError> {
+    let Some(size) = size else {
+        let ext = match image::guess_format(&bytes) {
+            Ok(ImageFormat::Png) => "png",
+            Ok(ImageFormat::Jpeg) => "jpg",
+            _ => return Err(CoverCacheError::InvalidImage("not a PNG or JPEG".into())),
+        };
+        return Ok((bytes, ext));
+    };
+    let image = decode(&bytes)?.resize_to_fill(size, size, FilterType::Lanczos3);
+    let image = DynamicImage::ImageRgb8(image.to_rgb8());
+    let mut out = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))
+        .map_err(|e| CoverCacheError::Io(e.to_string()))?;
+    Ok((out, "jpg"))
+}
+
+async fn download(app: &AppHandle, url: &str) -> Result<Vec<u8>, CoverCacheError> {
+    let request = http_util::client()
+        .get(url)
+        .build()
+        .map_err(|_| CoverCacheError::InvalidUrl(url.to_string()))?;
+    let policy = app
+        .try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.http.clone()))
+        .unwrap_or_default();
+    let response = http_util::fetch_with_policy(request, &policy).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CoverCacheError::Download(format!("HTTP {}", status)));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_INPUT_BYTES as u64)
+    {
+        return Err(CoverCacheError::InvalidImage("larger than 20 MB".into()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| CoverCacheError::Download(e.to_string()))?;
+    if bytes.len() > MAX_INPUT_BYTES {
+        return Err(CoverCacheError::InvalidImage("larger than 20 MB".into()));
+    }
+    Ok(bytes.to_vec())
+}
+
+async fn fetch(app: &AppHandle, url: &str, size: Option<u32>) -> Result<PathBuf, CoverCacheError> {
+    let cache = app.state::<CoverCache>();
+    let dir = cache.dir()?.to_path_buf();
+    let bytes = download(app, url).await?;
+    let (data, ext) = tauri::async_runtime::spawn_blocking(move || prepare(bytes, size))
+        .await
+        .map_err(|e| CoverCacheError::Io(e.to_string()))??;
+
+    let name = file_name(url, size, ext);
+    store(app, &dir, name, &data).await
+}
+
+/// Write `data` into the cache as `name`, through a temp file so readers
+/// never see half of it.
+async fn store(
+    app: &AppHandle,
+    dir: &Path,
+    name: String,
+    data: &[u8],
+) -> Result<PathBuf, CoverCacheError> {
+    tokio::fs::create_dir_all(dir).await?;
+    let path = dir.join(&name);
+    let tmp = dir.join(format!("{}.tmp", name));
+    tokio::fs::write(&tmp, data).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    app.state::<CoverCache>()
+        .insert(dir, name, data.len() as u64, max_bytes(app));
+    Ok(path)
+}
+
+/// A custom cover, copied into the cache folder so the asset
+/// protocol can serve it. The copy is named after the source and its last
+/// update, so replacing the custom cover yields a new file.
+async fn cached_custom(app: &AppHandle, cover: &CustomCover) -> Result<PathBuf, CoverCacheError> {
+    let cache = app.state::<CoverCache>();
+    let dir = cache.dir()?.to_path_buf();
+    let source = format!("{}@{}", cover.path.display(), cover.updated_ms);
+    let name = format!("custom-{}", file_name(&source, None, "jpg"));
+    if let Some(path) = cache.hit(&dir, &name) {
+        return Ok(path);
+    }
+    let data = tokio::fs::read(&cover.path).await?;
+    store(app, &dir, name, &data).await
+}
+
+/// Local path of the cover at `url`, squared to `size` pixels if given,
+/// downloading it first when it is not cached. Rust callers (the system
+/// media controls) use this directly.
+pub async fn cached_cover(
+    app: &AppHandle,
+    url: &str,
+    size: Option<u32>,
+) -> Result<PathBuf, CoverCacheError> {
+    let size = size.map(|size| size.clamp(1, MAX_SIZE));
+    let cache = app.state::<CoverCache>();
+    let dir = cache.dir()?;
+    for ext in ["jpg", "png"] {
+        if let Some(path) = cache.hit(dir, &file_name(url, size, ext)) {
+            return Ok(path);
+        }
+    }
+
+    let key = file_name(url, size, "");
+    let fetch_cell = cache
+        .in_flight
+        .lock()
+        .entry(key.clone())
+        .or_default()
+        .clone();
+    let result = fetch_cell
+        .get_or_init(|| fetch(app, url, size))
+        .await
+        .clone();
+    let mut in_flight = cache.in_flight.lock();
+    if in_flight
+        .get(&key)
+        .is_some_and(|current| Arc::ptr_eq(current, &fetch_cell))
+    {
+        in_flight.remove(&key);
+    }
+    result
+}
+
+/// Delete files until the cache fits the configured size again.
+pub fn trim(app: &AppHandle) {
+    let Some(cache) = app.try_state::<CoverCache>() else {
+        return;
+    };
+    let Ok(dir) = cache.dir() else {
+        return;
+    };
+    evict(dir, &mut cache.files.lock(), max_bytes(app));
+}
+
+/// Cached cover for `url` as a local file path. A custom cover set for
+/// `scope_id` wins over the download.
+#[tauri::command]
+pub async fn get_cover(
+    app: AppHandle,
+    custom: State<'_, CustomCoverState>,
+    url: String,
+    size: Option<u32>,
+    scope_id: Option<String>,
+) -> Result<PathBuf, CoverCacheError> {
+    if let Some(cover) = scope_id.and_then(|scope_id| custom.resolve(&scope_id)) {
+        return cached_custom(&app, &cover).await;
+    }
+    cached_cover(&app, &url, size).await
+}
+
+/// Delete every cached cover. Returns the bytes freed.
+#[tauri::command]
+pub fn clear_cover_cache(cache: State<'_, CoverCache>) -> Result<u64, CoverCacheError> {
+    let dir = cache.dir()?;
+    let mut files = cache.files.lock();
+    let mut freed = 0;
+    for (name, file) in files.drain() {
+        match std::fs::remove_file(dir.join(&name)) {
+            Ok(()) => freed += file.bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to delete cover {}: {}", name, e),
+        }
+    }
+    info!("Cover cache cleared, {} bytes freed", freed);
+    Ok(freed)
+}
+
+#[tauri::command]
+pub fn cover_cache_stats(app: AppHandle, cache: State<'_, CoverCache>) -> CoverCacheStats {
+    cache.stats(max_bytes(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_files_go_first() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let files: HashMap<String, CachedFile> = [("a", 40, 3), ("b", 30, 1), ("c", 50, 2)]
+            .into_iter()
+            .map(|(name, bytes, used)| {
+                let file = CachedFile {
+                    bytes,
+                    used: at(used),
+                };
+                (name.to_string(), file)
+            })
+            .collect();
+        let order = |max_bytes| eviction_order(&files, max_bytes, at(10));
+        assert!(order(120).is_empty());
+        assert_eq!(order(100), ["b"]);
+        assert_eq!(order(40), ["b", "c"]);
+        assert_eq!(order(0), ["b", "c", "a"]);
+        // Files handed out recently stay, even over the limit.
+        assert_eq!(eviction_order(&files, 0, at(3)), ["b", "c"]);
+    }
+
+    #[test]
+    fn sized_covers_are_square_jpegs() {
+        let mut png = Vec::new();
+        image::RgbaImage::new(300, 200)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let (original, ext) = prepare(png.clone(), None).unwrap();
+        assert_eq!((original.len(), ext), (png.len(), "png"));
+
+        let (jpeg, ext) = prepare(png, Some(64)).unwrap();
+        assert_eq!(ext, "jpg");
+        let image = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!((image.width(), image.height()), (64, 64));
+        assert!(matches!(
+            prepare(b"<html>".to_vec(), None),
+            Err(CoverCacheError::InvalidImage(_))
+        ));
+        assert_ne!(
+            file_name("https://p1.music.126.net/a.jpg", Some(64), "jpg"),
+            file_name("https://p1.music.126.net/a.jpg", None, "jpg")
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! User-chosen cover overrides for tracks and albums.
+//!
+//! Images are validated, downscaled to at most [`MAX_COVER_EDGE`] px and
+//! re-encoded as JPEG into `covers/` under the app data dir, next to a JSON
+//! index (`covers/index.json`) keyed by scope id (a track or album id).
+//! The folder lives outside the cache dir, so cache eviction never touches
+//! overrides; they are included in the settings backup export instead.
+//!
+//! The index is a file in that folder rather than a table in one of the
+//! SQLite stores: those hold the queue and the API cache, and a corrupt one
+//! is reset by [`crate::storage`], which must not take user-made overrides
+//! with it. Keeping index and images together also keeps the folder
+//! self-contained.
+//!
+//! Decoding and re-encoding an upload takes long enough to stall the UI, so
+//! the commands that touch images or files run on the blocking pool.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader, Limits};
+use log::warn;
+use md5::{Digest, Md5};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::settings::write_atomic;
+
+const COVERS_DIR: &str = "covers";
+const INDEX_FILE: &str = "index.json";
+/// Uploads above this are rejected before decoding.
+pub const MAX_INPUT_BYTES: usize = 20 * 1024 * 1024;
+/// Source images above this edge length are rejected as absurd.
+const MAX_SOURCE_EDGE: u32 = 12_000;
+const MIN_SOURCE_EDGE: u32 = 32;
+/// Stored covers are downscaled to fit this box.
+pub const MAX_COVER_EDGE: u32 = 1200;
+const JPEG_QUALITY: u8 = 90;
+const MAX_SCOPE_ID_LEN: usize = 256;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CoverError {
+    /// Not a decodable image, or too small to be a cover.
+    InvalidImage(String),
+    /// Input file or pixel dimensions exceed the limits.
+    TooLarge(String),
+    InvalidScope(String),
+    Io(String),
+}
+
+impl fmt::Display for CoverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidImage(message) => write!(f, "Invalid cover image: {}", message),
+            Self::TooLarge(message) => write!(f, "Cover image too large: {}", message),
+            Self::InvalidScope(scope) => write!(f, "Invalid cover scope '{}'", scope),
+            Self::Io(message) => write!(f, "Failed to store cover: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoverEntry {
+    file: String,
+    width: u32,
+    height: u32,
+    updated_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCover {
+    pub scope_id: String,
+    pub path: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    pub updated_ms: u64,
+}
+
+/// A cover as stored in the settings backup.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCoverBackup {
+    pub scope_id: String,
+    pub mime_type: &'static str,
+    pub data_base64: String,
+}
+
+/// Managed custom cover state.
+pub struct CustomCoverState {
+    dir: Option<PathBuf>,
+    index: Mutex<BTreeMap<String, CoverEntry>>,
+}
+
+impl CustomCoverState {
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(COVERS_DIR)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, custom covers are disabled: {}",
+                    e
+                );
+                None
+            }
+        };
+        let index = dir
+            .as_deref()
+            .and_then(|dir| read_index(&dir.join(INDEX_FILE)))
+            .unwrap_or_default();
+        Self {
+            dir,
+            index: Mutex::new(index),
+        }
+    }
+
+    fn dir(&self) -> Result<&Path, CoverError> {
+        self.dir
+            .as_deref()
+            .ok_or_else(|| CoverError::Io("app data dir unavailable".into()))
+    }
+
+    fn to_cover(&self, scope_id: &str, entry: &CoverEntry) -> Option<CustomCover> {
+        Some(CustomCover {
+            scope_id: scope_id.to_string(),
+            path: self.dir.as_deref()?.join(&entry.file),
+            width: entry.width,
+            height: entry.height,
+            updated_ms: entry.updated_ms,
+        })
+    }
+
+    /// Override for `scope_id`, if any. The cover cache checks this before
+    /// fetching a remote cover.
+    pub fn resolve(&self, scope_id: &str) -> Option<CustomCover> {
+        let index = self.index.lock();
+        let entry = index.get(scope_id)?;
+        self.to_cover(scope_id, entry)
+    }
+
+    /// Every override with its image data, for the settings backup.
+    pub fn backup_entries(&self) -> Vec<CustomCoverBackup> {
+        let Some(dir) = self.dir.as_deref() else {
+            return Vec::new();
+        };
+        let engine = base64::engine::general_purpose::STANDARD;
+        self.index
+            .lock()
+            .iter()
+            .filter_map(|(scope_id, entry)| {
+                let bytes = std::fs::read(dir.join(&entry.file))
+                    .map_err(|e| warn!("Skipping cover '{}' in backup: {}", scope_id, e))
+                    .ok()?;
+                Some(CustomCoverBackup {
+                    scope_id: scope_id.clone(),
+                    mime_type: "image/jpeg",
+                    data_base64: engine.encode(bytes),
+                })
+            })
+            .collect()
+    }
+
+    fn persist(&self, dir: &Path, index: &BTreeMap<String, CoverEntry>) -> Result<(), CoverError> {
+        let json = serde_json::to_vec(index).map_err(|e| CoverError::Io(e.to_string()))?;
+        write_atomic(&dir.join(INDEX_FILE), &json).map_err(|e| CoverError::Io(e.to_string()))
+    }
+}
+
+fn read_index(path: &Path) -> Option<BTreeMap<String, CoverEntry>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(index) => Some(index),
+        Err(e) => {
+            warn!("Ignoring malformed {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn check_scope_id(scope_id: &str) -> Result<(), CoverError> {
+    if scope_id.trim().is_empty() || scope_id.len() > MAX_SCOPE_ID_LEN {
+        return Err(CoverError::InvalidScope(scope_id.to_string()));
+    }
+    Ok(())
+}
+
+/// Scope ids are arbitrary strings; file names are their hash.
+fn file_name(scope_id: &str) -> String {
+    format!("{}.jpg", hex::encode(Md5::digest(scope_id.as_bytes())))
+}
+
+/// Validate, downscale and re-encode an uploaded image as JPEG.
+fn process_image(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), CoverError> {
+    if bytes.len() > MAX_INPUT_BYTES {
+        return Err(CoverError::TooLarge(format!(
+            "{} bytes (max {})",
+            bytes.len(),
+            MAX_INPUT_BYTES
+        )));
+    }
+    let (width, height) = limited_reader(bytes)?
+        .into_dimensions()
+        .map_err(|e| classify_decode_error(&e))?;
+    if width < MIN_SOURCE_EDGE || height < MIN_SOURCE_EDGE {
+        return Err(CoverError::InvalidImage(format!(
+            "{}x{} is smaller than {}px",
+            width, height, MIN_SOURCE_EDGE
+        )));
+    }
+
+    let mut image = limited_reader(bytes)?
+        .decode()
+        .map_err(|e| classify_decode_error(&e))?;
+    if width > MAX_COVER_EDGE || height > MAX_COVER_EDGE {
+        image = image.resize(MAX_COVER_EDGE, MAX_COVER_EDGE, FilterType::Lanczos3);
+    }
+    // JPEG has no alpha; flatten onto the RGB channels.
+    let image = DynamicImage::ImageRgb8(image.to_rgb8());
+
+    let mut out = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))
+        .map_err(|e| CoverError::Io(e.to_string()))?;
+    Ok((out, image.width(), image.height()))
+}
+
+fn limited_reader(bytes: &[u8]) -> Result<ImageReader<Cursor<&[u8]>>, CoverError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| CoverError::InvalidImage(e.to_string()))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_EDGE);
+    limits.max_image_height = Some(MAX_SOURCE_EDGE);
+    reader.limits(limits);
+    Ok(reader)
+}
+
+fn classify_decode_error(e: &image::ImageError) -> CoverError {
+    match e {
+        image::ImageError::Limits(_) => {
+            CoverError::TooLarge(format!("dimensions exceed {}px", MAX_SOURCE_EDGE))
+        }
+        e => CoverError::InvalidImage(e.to_string()),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+async fn on_blocking_pool<T: Send + 'static>(
+    app: AppHandle,
+    f: impl FnOnce(&CustomCoverState) -> Result<T, CoverError> + Send + 'static,
+) -> Result<T, CoverError> {
+    tauri::async_runtime::spawn_blocking(move || f(&app.state::<CustomCoverState>()))
+        .await
+        .map_err(|e| CoverError::Io(e.to_string()))?
+}
+
+/// Store `image_bytes` as the cover for a track or album.
+#[tauri::command]
+pub async fn set_custom_cover(
+    app: AppHandle,
+    scope_id: String,
+    image_bytes: Vec<u8>,
+) -> Result<CustomCover, CoverError> {
+    check_scope_id(&scope_id)?;
+    on_blocking_pool(app, move |state| {
+        store_cover(state, &scope_id, &image_bytes)
+    })
+    .await
+}
+
+fn store_cover(
+    state: &CustomCoverState,
+    scope_id: &str,
+    image_bytes: &[u8],
+) -> Result<CustomCover, CoverError> {
+    let (jpeg, width, height) = process_image(image_bytes)?;
+    let dir = state.dir()?;
+    let entry = CoverEntry {
+        file: file_name(scope_id),
+        width,
+        height,
+        updated_ms: now_ms(),
+    };
+    write_atomic(&dir.join(&entry.file), &jpeg).map_err(|e| CoverError::Io(e.to_string()))?;
+
+    let mut index = state.index.lock();
+    let mut next = index.clone();
+    next.insert(scope_id.to_string(), entry.clone());
+    state.persist(dir, &next)?;
+    *index = next;
+    state
+        .to_cover(scope_id, &entry)
+        .ok_or_else(|| CoverError::Io("app data dir unavailable".into()))
+}
+
+/// Remove the override for a track or album. Returns whether one existed.
+#[tauri::command]
+pub async fn clear_custom_cover(app: AppHandle, scope_id: String) -> Result<bool, CoverError> {
+    on_blocking_pool(app, move |state| remove_cover(state, &scope_id)).await
+}
+
+fn remove_cover(state: &CustomCoverState, scope_id: &str) -> Result<bool, CoverError> {
+    let dir = state.dir()?;
+    let mut index = state.index.lock();
+    let Some(entry) = index.get(scope_id).cloned() else {
+        return Ok(false);
+    };
+    let mut next = index.clone();
+    next.remove(scope_id);
+    state.persist(dir, &next)?;
+    *index = next;
+    if let Err(e) = std::fs::remove_file(dir.join(&entry.file)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to delete cover file {}: {}", entry.file, e);
+        }
+    }
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn get_custom_cover(
+    state: State<'_, CustomCoverState>,
+    scope_id: String,
+) -> Option<CustomCover> {
+    state.resolve(&scope_id)
+}
+
+#[tauri::command]
+pub fn list_custom_covers(state: State<'_, CustomCoverState>) -> Vec<CustomCover> {
+    let index = state.index.lock();
+    index
+        .iter()
+        .filter_map(|(scope_id, entry)| state.to_cover(scope_id, entry))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        RgbaImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut out), ImageFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn large_images_are_downscaled_to_jpeg() {
+        let (jpeg, width, height) = process_image(&png(2400, 1200)).unwrap();
+        assert_eq!((width, height), (MAX_COVER_EDGE, MAX_COVER_EDGE / 2));
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+    }
+
+    #[test]
+    fn invalid_and_tiny_images_are_rejected() {
+        assert!(matches!(
+            process_image(b"definitely not an image"),
+            Err(CoverError::InvalidImage(_))
+        ));
+        assert!(matches!(
+            process_image(&png(8, 8)),
+            Err(CoverError::InvalidImage(_))
+        ));
+        assert!(matches!(
+            process_image(&vec![0; MAX_INPUT_BYTES + 1]),
+            Err(CoverError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn scope_ids_map_to_stable_file_names() {
+        assert_eq!(file_name("album:42"), file_name("album:42"));
+        assert_ne!(file_name("album:42"), file_name("track:42"));
+        assert!(check_scope_id("").is_err());
+        assert!(check_scope_id("track:1").is_ok());
+    }
+}
@@ -0,0 +1,71 @@
+//! Trailing-edge debouncing for bursts of events.
+
+use std::time::{Duration, Instant};
+
+use log::warn;
+use parking_lot::Mutex;
+
+/// Runs work once a burst of events has been quiet for `delay`.
+/// Dragging fires `Moved` per frame and an EQ slider fires a command per
+/// step; the window-state saver, the lyric bounds writer, edge snapping and
+/// the settings saver only care about where things end up.
+pub(crate) struct Debounced {
+    name: &'static str,
+    delay: Duration,
+    /// `Some` while a worker thread is waiting.
+    deadline: Mutex<Option<Instant>>,
+}
+
+impl Debounced {
+    pub(crate) const fn new(name: &'static str, delay: Duration) -> Self {
+        Self {
+            name,
+            delay,
+            deadline: Mutex::new(None),
+        }
+    }
+
+    /// Push the deadline back, starting a worker that runs `work` once it
+    /// passes. While a worker is already waiting, `work` is dropped.
+    pub(crate) fn trigger(&'static self, work: impl FnOnce() + Send + 'static) {
+        let mut deadline = self.deadline.lock();
+        let waiting = deadline.is_some();
+        *deadline = Some(Instant::now() + self.delay);
+        if waiting {
+            return;
+        }
+        drop(deadline);
+
+        let spawned = std::thread::Builder::new()
+            .name(self.name.into())
+            .spawn(move || {
+                while let Some(at) = self.due() {
+                    std::thread::sleep(at.saturating_duration_since(Instant::now()));
+                }
+                work();
+            });
+        if let Err(e) = spawned {
+            *self.deadline.lock() = None;
+            warn!("Failed to spawn {}: {}", self.name, e);
+        }
+    }
+
+    /// The pending deadline, or `None` (and cleared) once it has passed.
+    fn due(&self) -> Option<Instant> {
+        let mut deadline = self.deadline.lock();
+        match *deadline {
+            Some(at) if at > Instant::now() => Some(at),
+            _ => {
+                *deadline = None;
+                None
+            }
+        }
+    }
+
+    /// Clear the deadline so the caller can do the work right away; returns
+    /// whether a run was pending. The waiting worker still runs its `work`
+    /// when it wakes, so that work must tolerate running twice.
+    pub(crate) fn take_pending(&self) -> bool {
+        self.deadline.lock().take().is_some()
+    }
+}
@@ -0,0 +1,356 @@
+//! Launch at login.
+//!
+//! Windows uses a value under the current user's `Run` key, macOS a
+//! LaunchAgent plist in `~/Library/LaunchAgents` and Linux a desktop entry
+//! in `~/.config/autostart`. With `start_hidden` the entry passes
+//! `--hidden`, which [`startup`](super::startup) turns into a tray-only
+//! launch. `get_autostart` always reads the entry back instead of trusting
+//! a saved setting, so removing it by hand (or through the OS settings) is
+//! reflected straight away.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::desktop::startup::HIDDEN_FLAG;
+
+/// What the OS will do at the next login.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    /// Launch with `--hidden`, leaving only the tray icon.
+    pub start_hidden: bool,
+}
+
+fn current_exe() -> Result<std::path::PathBuf, String> {
+    // An AppImage runs from a temporary mount; the login entry has to
+    // point at the image itself.
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(appimage.into());
+    }
+    std::env::current_exe().map_err(|e| format!("Failed to locate the executable: {}", e))
+}
+
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> Result<AutostartStatus, String> {
+    imp::read(&app)
+}
+
+/// Add or remove the login entry. Resolves to the entry as read back.
+#[tauri::command]
+pub fn set_autostart(
+    app: AppHandle,
+    enabled: bool,
+    start_hidden: bool,
+) -> Result<AutostartStatus, String> {
+    if enabled {
+        imp::write(&app, &current_exe()?, start_hidden)?;
+    } else {
+        imp::remove(&app)?;
+    }
+    imp::read(&app)
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::path::Path;
+
+    use tauri::AppHandle;
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+    };
+
+    use super::{AutostartStatus, HIDDEN_FLAG};
+
+    const RUN_KEY: PCWSTR = w!(r"Software\Microsoft\Windows\CurrentVersion\Run");
+
+    fn value_name(app: &AppHandle) -> HSTRING {
+        HSTRING::from(app.package_info().name.as_str())
+    }
+
+    pub(super) fn read(app: &AppHandle) -> Result<AutostartStatus, String> {
+        let name = value_name(app);
+        let mut size = 0u32;
+        // SAFETY: a size query with no buffer, then a read into a buffer of
+        // the size it reported.
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                RUN_KEY,
+                &name,
+                RRF_RT_REG_SZ,
+                None,
+                None,
+                Some(&mut size),
+            )
+        };
+        if status == ERROR_FILE_NOT_FOUND {
+            return Ok(AutostartStatus::default());
+        }
+        status.ok().map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+        unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                RUN_KEY,
+                &name,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .map_err(|e| e.to_string())?;
+        let command = String::from_utf16_lossy(&buffer);
+        let command = command.trim_end_matches('\0');
+        Ok(AutostartStatus {
+            enabled: true,
+            start_hidden: command.split_whitespace().any(|arg| arg == HIDDEN_FLAG),
+        })
+    }
+
+    pub(super) fn write(app: &AppHandle, exe: &Path, start_hidden: bool) -> Result<(), String> {
+        let mut command = format!("\"{}\"", exe.display());
+        if start_hidden {
+            command.push(' ');
+            command.push_str(HIDDEN_FLAG);
+        }
+        let data: Vec<u16> = command.encode_utf16().chain(Some(0)).collect();
+        // SAFETY: `data` is a NUL-terminated UTF-16 string of the given
+        // byte length.
+        unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                RUN_KEY,
+                &value_name(app),
+                REG_SZ.0,
+                Some(data.as_ptr().cast()),
+                (data.len() * 2) as u32,
+            )
+        }
+        .ok()
+        .map_err(|e| e.to_string())
+    }
+
+    pub(super) fn remove(app: &AppHandle) -> Result<(), String> {
+        // SAFETY: deletes one named value under the Run key.
+        let status = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, &value_name(app)) };
+        if status == ERROR_FILE_NOT_FOUND {
+            return Ok(());
+        }
+        status.ok().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::path::{Path, PathBuf};
+
+    use tauri::{AppHandle, Manager};
+
+    use super::{AutostartStatus, HIDDEN_FLAG};
+    use crate::settings::write_atomic;
+
+    fn plist_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let home = app.path().home_dir().map_err(|e| e.to_string())?;
+        Ok(home
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", app.config().identifier)))
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub(super) fn read(app: &AppHandle) -> Result<AutostartStatus, String> {
+        match std::fs::read_to_string(plist_path(app)?) {
+            Ok(plist) => Ok(AutostartStatus {
+                enabled: true,
+                start_hidden: plist.contains(&format!("<string>{}</string>", HIDDEN_FLAG)),
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AutostartStatus::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub(super) fn write(app: &AppHandle, exe: &Path, start_hidden: bool) -> Result<(), String> {
+        let path = plist_path(app)?;
+        let mut arguments = format!("<string>{}</string>", escape(&exe.to_string_lossy()));
+        if start_hidden {
+            arguments.push_str(&format!("\n        <string>{}</string>", HIDDEN_FLAG));
+        }
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        {}
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            escape(&app.config().identifier),
+            arguments
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        write_atomic(&path, plist.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub(super) fn remove(app: &AppHandle) -> Result<(), String> {
+        match std::fs::remove_file(plist_path(app)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::path::{Path, PathBuf};
+
+    use tauri::{AppHandle, Manager};
+
+    use super::{AutostartStatus, HIDDEN_FLAG};
+    use crate::settings::write_atomic;
+
+    fn entry_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let config = app.path().config_dir().map_err(|e| e.to_string())?;
+        Ok(config
+            .join("autostart")
+            .join(format!("{}.desktop", app.config().identifier)))
+    }
+
+    /// Quote an `Exec` argument as the desktop entry spec asks.
+    fn quote(arg: &str) -> String {
+        let mut quoted = String::with_capacity(arg.len() + 2);
+        quoted.push('"');
+        for c in arg.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    pub(super) fn desktop_entry(name: &str, exe: &Path, start_hidden: bool) -> String {
+        let mut exec = quote(&exe.to_string_lossy());
+        if start_hidden {
+            exec.push(' ');
+            exec.push_str(HIDDEN_FLAG);
+        }
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            name, exec
+        )
+    }
+
+    /// An entry switched off with `Hidden=true` or the GNOME key counts as
+    /// disabled, the same as a missing one.
+    pub(super) fn parse_entry(entry: &str) -> AutostartStatus {
+        let mut status = AutostartStatus {
+            enabled: true,
+            start_hidden: false,
+        };
+        for line in entry.lines() {
+            match line.trim().split_once('=') {
+                Some(("Hidden", "true")) | Some(("X-GNOME-Autostart-enabled", "false")) => {
+                    status.enabled = false;
+                }
+                Some(("Exec", exec)) => {
+                    status.start_hidden = exec.split_whitespace().any(|arg| arg == HIDDEN_FLAG);
+                }
+                _ => {}
+            }
+        }
+        status
+    }
+
+    pub(super) fn read(app: &AppHandle) -> Result<AutostartStatus, String> {
+        match std::fs::read_to_string(entry_path(app)?) {
+            Ok(entry) => Ok(parse_entry(&entry)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AutostartStatus::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub(super) fn write(app: &AppHandle, exe: &Path, start_hidden: bool) -> Result<(), String> {
+        let path = entry_path(app)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let entry = desktop_entry(&app.package_info().name, exe, start_hidden);
+        write_atomic(&path, entry.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub(super) fn remove(app: &AppHandle) -> Result<(), String> {
+        match std::fs::remove_file(entry_path(app)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use std::path::Path;
+
+    use tauri::AppHandle;
+
+    use super::AutostartStatus;
+
+    pub(super) fn read(_app: &AppHandle) -> Result<AutostartStatus, String> {
+        Ok(AutostartStatus::default())
+    }
+
+    pub(super) fn write(_app: &AppHandle, _exe: &Path, _start_hidden: bool) -> Result<(), String> {
+        Err("Autostart is not supported on this platform".into())
+    }
+
+    pub(super) fn remove(_app: &AppHandle) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::path::Path;
+
+    use super::imp::{desktop_entry, parse_entry};
+    use super::*;
+
+    #[test]
+    fn desktop_entries_round_trip_the_hidden_flag() {
+        let exe = Path::new("/opt/GM Player/gmplayer");
+        let entry = desktop_entry("GMPlayer", exe, true);
+        assert!(entry.contains("Exec=\"/opt/GM Player/gmplayer\" --hidden\n"));
+        assert_eq!(
+            parse_entry(&entry),
+            AutostartStatus {
+                enabled: true,
+                start_hidden: true
+            }
+        );
+        let entry = desktop_entry("GMPlayer", Path::new("/usr/bin/gm$player"), false);
+        assert!(entry.contains("Exec=\"/usr/bin/gm\\$player\"\n"));
+        assert!(!parse_entry(&entry).start_hidden);
+        let disabled = entry.replace("enabled=true", "enabled=false");
+        assert!(!parse_entry(&disabled).enabled);
+    }
+}
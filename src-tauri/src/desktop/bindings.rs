@@ -0,0 +1,998 @@
+//! Typed frontend bindings for the window, tray, payload and settings
+//! commands.
+//!
+//! [`registry!`] lists each command with the arguments the frontend sends
+//! and the value it resolves to, plus the events those modules emit. Every
+//! entry names the real `#[command]` function, so renaming or removing one
+//! breaks the build here instead of silently in the frontend, and a test
+//! compares each entry's arguments and resolved type with the function's
+//! signature. From that list, `gmplayer --dump-bindings [dir]` writes
+//! `bindings.json` (commands, events and JSON schemas) and `bindings.ts`
+//! (typed `invoke` wrappers and payload interfaces) into `src/utils/tauri`;
+//! a test fails when the checked-in files are stale.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::desktop::idle::{IdleChanged, SYSTEM_ACTIVE_EVENT, SYSTEM_IDLE_EVENT};
+use crate::desktop::local_files::{FilesOpened, FILES_OPENED_EVENT};
+use crate::desktop::notifications::{
+    NotificationAction, NotificationActionPressed, NOTIFICATION_ACTION_EVENT,
+};
+use crate::desktop::second_instance::{PendingOpen, SecondInstance, SECOND_INSTANCE_EVENT};
+use crate::desktop::system_volume::{SystemVolume, SYSTEM_VOLUME_CHANGED_EVENT};
+use crate::desktop::window::capture::{CaptureRegion, WindowCapture};
+use crate::desktop::window::commands::{ResizeEdge, WindowInfo, WindowState};
+use crate::desktop::window::config::{WindowConfig, WindowConfigOverrides};
+use crate::desktop::window::coords::PositioningConvention;
+use crate::desktop::window::desktop_lyrics::fullscreen::DESKTOP_LYRICS_AUTOHIDDEN_EVENT;
+use crate::desktop::window::desktop_lyrics::lock::{LyricsHover, DESKTOP_LYRICS_HOVER_EVENT};
+use crate::desktop::window::desktop_lyrics::mouse_through::HitRegion;
+use crate::desktop::window::desktop_lyrics::orientation::{
+    LyricsOrientation, OrientationClamped, DESKTOP_LYRICS_ORIENTATION_CLAMPED_EVENT,
+};
+use crate::desktop::window::desktop_lyrics::snap::{LyricsSnapped, DESKTOP_LYRICS_SNAPPED_EVENT};
+use crate::desktop::window::events::{WindowLifecycle, WINDOW_LIFECYCLE_EVENT};
+use crate::desktop::window::manager::{
+    MonitorAnchor, MonitorInfo, ScreenPoint, WindowUnresponsive, WindowVisibilityChanged,
+    WINDOW_UNRESPONSIVE_EVENT, WINDOW_VISIBILITY_CHANGED_EVENT,
+};
+use crate::desktop::window::payload::PayloadCacheStats;
+use crate::desktop::window::pin::{
+    WindowPinChanged, WINDOW_ALWAYS_ON_TOP_CHANGED_EVENT, WINDOW_PIN_CHANGED_EVENT,
+};
+use crate::desktop::window::platform::appearance::{
+    AccentColor, SystemTheme, ACCENT_COLOR_CHANGED_EVENT, SYSTEM_THEME_CHANGED_EVENT,
+};
+use crate::desktop::window::prewarm::{WindowPrewarmed, WINDOW_PREWARMED_EVENT};
+use crate::desktop::window::recently_closed::ClosedWindow;
+use crate::desktop::window::rpc::{WindowRpcRequest, WINDOW_RPC_REQUEST_EVENT};
+use crate::desktop::window::shared_state::{
+    SharedStateChanged, SharedValue, SHARED_STATE_CHANGED_EVENT,
+};
+use crate::desktop::window::taskbar_progress::TaskbarProgressState;
+use crate::desktop::window::titlebar::{
+    TitlebarStyle, TitlebarStyleChanged, TITLEBAR_STYLE_CHANGED_EVENT,
+};
+use crate::desktop::window::tray::{
+    TrayNowPlaying, TrayPlaybackState, TRAY_MENU_NEXT_TRACK_EVENT, TRAY_MENU_PLAY_PAUSE_EVENT,
+    TRAY_MENU_PREV_TRACK_EVENT,
+};
+use crate::desktop::window::zoom::{WindowZoomChanged, WINDOW_ZOOM_CHANGED_EVENT};
+use crate::desktop::{
+    autostart, file_picker, i18n, idle, local_files, notifications, power, safe_mode,
+    second_instance, startup, system_volume, window,
+};
+use crate::lyrics::session::{self as lyric_session, LyricLineChange, LYRIC_LINE_CHANGED_EVENT};
+use crate::lyrics::LyricLine;
+use crate::settings;
+use crate::settings::cover_cache::CoverCacheSettings;
+use crate::settings::downloads::DownloadSettings;
+use crate::settings::eq::{EqBand, EqPresetInfo, EqState, EQ_CHANGED_EVENT};
+use crate::settings::http::HttpPolicy;
+use crate::settings::notifications::NotificationSettings;
+use crate::settings::osd::OsdSettings;
+use crate::settings::paths::PathSettings;
+use crate::settings::power::PowerSettings;
+use crate::settings::tray::{TrayClickAction, TrayConfig, TrayMenuMode};
+use crate::settings::window::{CloseBehavior, LyricsSnap};
+use crate::shutdown::{self, AppWillQuit, APP_WILL_QUIT_EVENT};
+
+pub const DUMP_BINDINGS_FLAG: &str = "--dump-bindings";
+pub const MANIFEST_FILE: &str = "bindings.json";
+pub const TYPESCRIPT_FILE: &str = "bindings.ts";
+
+/// Where the checked-in bindings live.
+fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/utils/tauri")
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub commands: Vec<CommandSpec>,
+    pub events: Vec<EventSpec>,
+    pub definitions: BTreeMap<String, Schema>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSpec {
+    pub name: String,
+    pub args: Vec<ArgSpec>,
+    pub returns: Schema,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArgSpec {
+    /// The key the frontend passes to `invoke`.
+    pub name: String,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSpec {
+    pub name: String,
+    pub payload: Schema,
+}
+
+/// Tauri expects camelCase argument keys unless the command is declared
+/// with `rename_all = "snake_case"`.
+fn arg_key(name: &str, snake_case: bool) -> String {
+    if snake_case {
+        return name.to_string();
+    }
+    let mut key = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            key.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            key.push(c);
+        }
+    }
+    key
+}
+
+struct Builder {
+    gen: SchemaGenerator,
+    commands: Vec<CommandSpec>,
+    events: Vec<EventSpec>,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            gen: SchemaSettings::draft07().into_generator(),
+            commands: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    fn schema<T: ?Sized + JsonSchema>(&mut self) -> Schema {
+        self.gen.subschema_for::<T>()
+    }
+
+    fn command(
+        &mut self,
+        path: &str,
+        snake_case: bool,
+        args: Vec<(&str, Schema)>,
+        returns: Schema,
+    ) {
+        let name = path.rsplit("::").next().unwrap_or(path).trim().to_string();
+        let args = args
+            .into_iter()
+            .map(|(name, schema)| ArgSpec {
+                name: arg_key(name, snake_case),
+                schema,
+            })
+            .collect();
+        self.commands.push(CommandSpec {
+            name,
+            args,
+            returns,
+        });
+    }
+
+    fn event(&mut self, name: &str, payload: Schema) {
+        self.events.push(EventSpec {
+            name: name.to_string(),
+            payload,
+        });
+    }
+
+    fn finish(self) -> Manifest {
+        Manifest {
+            commands: self.commands,
+            events: self.events,
+            definitions: self
+                .gen
+                .definitions()
+                .iter()
+                .map(|(name, schema)| (name.clone(), schema.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// `path(arg: Type, ..) -> Resolved;` per command, where `Resolved` is the
+/// `Ok` type (`()` for none). `#[snake_case]` marks commands declared with
+/// `rename_all = "snake_case"`. `"event-name" => Payload;` per event.
+macro_rules! registry {
+    (
+        commands {
+            $( $(#[$case:ident])? $($seg:ident)::+ ( $($arg:ident : $ty:ty),* $(,)? ) -> $ret:ty; )*
+        }
+        events {
+            $( $event:expr => $payload:ty; )*
+        }
+    ) => {
+        /// Build the manifest for every registered command and event.
+        pub fn manifest() -> Manifest {
+            let mut builder = Builder::new();
+            $(
+                let _ = $($seg)::+;
+                let args = vec![$((stringify!($arg), builder.schema::<$ty>())),*];
+                let returns = builder.schema::<$ret>();
+                builder.command(
+                    stringify!($($seg)::+),
+                    false $(|| stringify!($case) == "snake_case")?,
+                    args,
+                    returns,
+                );
+            )*
+            $(
+                let payload = builder.schema::<$payload>();
+                builder.event($event, payload);
+            )*
+            builder.finish()
+        }
+
+        /// Each command as written here: path, arguments and resolved type.
+        #[cfg(test)]
+        fn declared_commands() -> Vec<DeclaredCommand> {
+            vec![$(DeclaredCommand {
+                path: stringify!($($seg)::+),
+                args: vec![$((stringify!($arg), stringify!($ty))),*],
+                returns: stringify!($ret),
+            }),*]
+        }
+    };
+}
+
+#[cfg(test)]
+struct DeclaredCommand {
+    path: &'static str,
+    args: Vec<(&'static str, &'static str)>,
+    returns: &'static str,
+}
+
+registry! {
+    commands {
+        // Window management
+        #[snake_case]
+        window::commands::create_window(label: String) -> ();
+        window::commands::create_window_with_overrides(label: String, overrides: WindowConfigOverrides) -> ();
+        window::commands::create_custom_window(config: WindowConfig) -> ();
+        window::presets::register_window_preset(config: WindowConfig) -> ();
+        window::presets::remove_window_preset(label: String) -> bool;
+        window::presets::list_window_presets() -> Vec<WindowConfig>;
+        window::commands::create_window_with_payload(label: String, payload: Value) -> ();
+        #[snake_case]
+        window::commands::show_window(label: String) -> ();
+        window::commands::hide_window(label: String) -> ();
+        window::commands::close_managed_window(label: String) -> ();
+        window::commands::toggle_window(label: String) -> ();
+        window::commands::focus_window(label: String) -> ();
+        window::commands::maximize_window(label: String) -> ();
+        window::commands::unmaximize_window(label: String) -> ();
+        window::commands::minimize_window(label: String) -> ();
+        window::commands::toggle_maximize(label: String) -> bool;
+        window::commands::get_window_state(label: String) -> WindowState;
+        window::commands::get_all_window_states() -> BTreeMap<String, WindowState>;
+        window::commands::list_windows() -> Vec<String>;
+        window::events::subscribe_window_events() -> Vec<String>;
+        window::commands::open_window_devtools(label: String) -> ();
+        window::commands::show_window_at_position(label: String, x: f64, y: f64) -> ();
+        window::commands::set_window_effect_color(label: String, r: u8, g: u8, b: u8, a: u8) -> ();
+        window::commands::set_ignore_cursor_events(label: String, ignore: bool) -> ();
+        window::commands::start_native_drag(label: String) -> ();
+        window::titlebar::get_titlebar_style(label: String) -> TitlebarStyle;
+        window::titlebar::set_titlebar_style(label: String, style: TitlebarStyle) -> ();
+        window::commands::start_native_resize(label: String, direction: ResizeEdge) -> ();
+        window::commands::resize_window(label: String, width: f64, height: f64) -> ();
+        window::commands::quit_app(force: Option<bool>) -> ();
+        window::commands::flush_window_state() -> ();
+        shutdown::quit_ack(label: String) -> ();
+        window::commands::get_cursor_position() -> (i32, i32);
+        window::commands::get_window_bounds(label: String) -> (i32, i32, u32, u32);
+        window::commands::ack_close_request() -> ();
+        window::commands::set_always_on_top(label: String, on_top: bool) -> ();
+        window::commands::get_always_on_top(label: String) -> bool;
+        window::commands::set_content_protected(label: String, enabled: bool) -> ();
+        window::commands::list_monitors() -> Vec<MonitorInfo>;
+        window::commands::move_window_to_monitor(label: String, monitor_name: String, anchor: MonitorAnchor) -> ScreenPoint;
+        window::commands::get_window_info(label: String) -> WindowInfo;
+        window::coords::show_window_at_logical_position(
+            label: String,
+            x: f64,
+            y: f64,
+            monitor: Option<String>,
+        ) -> ();
+        window::coords::set_window_logical_position(
+            label: String,
+            x: f64,
+            y: f64,
+            monitor: Option<String>,
+        ) -> ();
+        window::coords::get_positioning_conventions() -> Vec<PositioningConvention>;
+        window::coords::ensure_window_on_screen(label: String) -> bool;
+        window::prewarm::notify_main_window_ready() -> ();
+        window::first_paint::notify_first_paint(label: String) -> ();
+        window::zoom::set_webview_zoom(label: String, factor: f64) -> f64;
+        window::zoom::get_webview_zoom(label: String) -> f64;
+        window::opacity::set_window_opacity(label: String, opacity: f64) -> f64;
+        window::opacity::get_window_opacity(label: String) -> f64;
+        window::exclusive::set_exclusive_pair(label_a: String, label_b: String, enabled: bool) -> ();
+        window::exclusive::switch_to_mini_player() -> ();
+        window::recently_closed::reopen_last_closed_window() -> Option<String>;
+        window::recently_closed::list_recently_closed_windows() -> Vec<ClosedWindow>;
+        window::capture::capture_window(label: String, region: Option<CaptureRegion>) -> WindowCapture;
+        window::recovery::update_current_route(route: String) -> ();
+        window::recovery::recover_main_window() -> ();
+        window::recovery::restart_app() -> ();
+        startup::get_startup_mode() -> startup::StartupMode;
+        autostart::get_autostart() -> autostart::AutostartStatus;
+        autostart::set_autostart(enabled: bool, start_hidden: bool) -> autostart::AutostartStatus;
+        power::set_keep_awake(mode: power::KeepAwake) -> power::KeepAwake;
+        power::get_keep_awake() -> power::KeepAwake;
+        idle::get_system_idle_seconds() -> u64;
+        idle::set_idle_watch(threshold_secs: u64) -> ();
+        window::platform::appearance::get_system_theme() -> SystemTheme;
+        window::platform::appearance::get_accent_color() -> AccentColor;
+        i18n::set_backend_locale(tag: String) -> String;
+        i18n::get_backend_locale() -> String;
+        safe_mode::is_safe_mode() -> bool;
+        safe_mode::reset_window_state() -> ();
+        // Desktop lyrics
+        #[snake_case]
+        window::desktop_lyrics::commands::set_window_position(label: String, x: i32, y: i32) -> ();
+        #[snake_case]
+        window::desktop_lyrics::commands::start_mouse_through(label: String, regions: Vec<HitRegion>) -> ();
+        #[snake_case]
+        window::desktop_lyrics::commands::stop_mouse_through(label: String) -> ();
+        #[snake_case]
+        window::desktop_lyrics::commands::update_mouse_through_regions(
+            label: String,
+            regions: Vec<HitRegion>,
+        ) -> ();
+        #[snake_case]
+        window::desktop_lyrics::commands::set_lyrics_locked(locked: bool) -> ();
+        window::desktop_lyrics::commands::get_lyrics_orientation() -> LyricsOrientation;
+        window::desktop_lyrics::commands::set_lyrics_orientation(orientation: LyricsOrientation) -> ();
+        window::desktop_lyrics::commands::get_lyrics_autohide_fullscreen() -> bool;
+        window::desktop_lyrics::commands::set_lyrics_autohide_fullscreen(enabled: bool) -> ();
+        // Window payloads
+        window::commands::set_window_payload(label: String, payload: Value, ttl_secs: Option<u64>) -> ();
+        window::commands::take_window_payload(label: String) -> Option<Value>;
+        window::commands::peek_window_payload(label: String) -> Option<Value>;
+        window::commands::payload_cache_stats() -> PayloadCacheStats;
+        // Shared state
+        window::shared_state::state_set(key: String, value: Value, persistent: Option<bool>) -> u64;
+        window::shared_state::state_get(key: String) -> Option<SharedValue>;
+        window::shared_state::state_delete(key: String) -> bool;
+        window::shared_state::state_snapshot() -> BTreeMap<String, SharedValue>;
+        // Window requests
+        window::rpc::window_request(target_label: String, channel: String, payload: Value, timeout_ms: Option<u64>) -> Value;
+        window::rpc::window_respond(request_id: u64, payload: Value) -> ();
+        // Queued events
+        window::event_queue::emit_or_queue(label: String, event: String, payload: Value) -> ();
+        window::event_queue::window_ready(label: String) -> usize;
+        window::event_queue::clear_event_queue(label: String) -> usize;
+        // Hang watchdog
+        window::commands::window_heartbeat(label: String) -> ();
+        window::commands::set_watchdog_enabled(label: String, enabled: bool) -> ();
+        // Tray and OSD
+        window::tray::set_tray_tooltip(text: String) -> ();
+        window::tray::update_tray_now_playing(
+            title: String,
+            artist: String,
+            position_ms: u64,
+            duration_ms: u64,
+            next_title: Option<String>,
+            album: Option<String>,
+            playing: Option<bool>,
+        ) -> ();
+        window::tray::clear_tray_now_playing() -> ();
+        window::tray::set_tray_playback_state(state: TrayPlaybackState) -> ();
+        window::tray::update_tray_popup_layout(width: f64, height: f64) -> ();
+        window::tray::set_tray_menu_mode(mode: TrayMenuMode) -> TrayConfig;
+        window::tray::rebuild_tray_icon() -> ();
+        window::tray::dismiss_tray_popup() -> ();
+        window::taskbar_progress::set_taskbar_progress(state: TaskbarProgressState, value: f64) -> ();
+        window::platform::set_thumbbar_state(playing: bool) -> ();
+        window::panic::panic_hide(mute: bool) -> ();
+        window::panic::panic_restore() -> ();
+        second_instance::take_pending_open() -> Option<PendingOpen>;
+        local_files::take_opened_files() -> Option<FilesOpened>;
+        file_picker::pick_music_files(multiple: bool, extensions: Option<Vec<String>>) -> Option<Vec<PathBuf>>;
+        file_picker::pick_music_folder() -> Option<PathBuf>;
+        file_picker::pick_save_path(default_name: String, extension: String) -> Option<PathBuf>;
+        notifications::notify_now_playing(
+            title: String,
+            artist: String,
+            cover_path: Option<String>,
+            actions: Vec<NotificationAction>,
+        ) -> bool;
+        notifications::set_notifications_enabled(enabled: bool) -> NotificationSettings;
+        system_volume::get_system_volume() -> f64;
+        system_volume::set_system_volume(level: f64) -> f64;
+        system_volume::get_system_muted() -> bool;
+        system_volume::set_system_muted(muted: bool) -> ();
+        window::osd::show_osd(kind: String, payload: Value, duration_ms: Option<u64>) -> bool;
+        // Lyric session
+        #[snake_case]
+        lyric_session::load_lyric_session(track_id: String, lines: Vec<LyricLine>, offset_ms: Option<i64>) -> ();
+        #[snake_case]
+        lyric_session::set_lyric_offset(offset_ms: i64) -> ();
+        lyric_session::clear_lyric_session() -> ();
+        lyric_session::get_current_lyric_line() -> Option<LyricLineChange>;
+        window::osd::hide_osd() -> ();
+        // Settings
+        settings::export_settings_backup(dest_path: String) -> ();
+        safe_mode::reset_settings(section: String) -> ();
+        settings::eq::get_eq_state() -> EqState;
+        settings::eq::set_eq_band(index: usize, band: EqBand) -> EqState;
+        settings::eq::set_eq_bands(bands: Vec<EqBand>) -> EqState;
+        settings::eq::set_eq_band_count(count: usize) -> EqState;
+        settings::eq::set_eq_preamp(gain: f32) -> EqState;
+        settings::eq::set_eq_enabled(enabled: bool) -> EqState;
+        settings::eq::save_eq_preset(name: String) -> EqState;
+        settings::eq::load_eq_preset(name: String) -> EqState;
+        settings::eq::delete_eq_preset(name: String) -> EqState;
+        settings::eq::list_eq_presets() -> Vec<EqPresetInfo>;
+        settings::osd::get_osd_settings() -> OsdSettings;
+        settings::osd::set_osd_settings(settings: OsdSettings) -> OsdSettings;
+        settings::tray::get_tray_config() -> TrayConfig;
+        settings::tray::set_tray_left_click_action(action: TrayClickAction) -> TrayConfig;
+        settings::tray::set_tray_double_click_action(action: TrayClickAction) -> TrayConfig;
+        window::commands::get_close_behavior(label: Option<String>) -> CloseBehavior;
+        settings::window::set_close_behavior(label: Option<String>, behavior: CloseBehavior) -> ();
+        settings::window::get_prewarm_windows() -> bool;
+        settings::window::set_prewarm_windows(enabled: bool) -> ();
+        settings::window::get_session_restore_enabled() -> bool;
+        settings::window::set_session_restore_enabled(enabled: bool) -> ();
+        settings::window::get_lyrics_snap() -> LyricsSnap;
+        settings::window::set_lyrics_snap(enabled: bool, threshold_px: u32) -> LyricsSnap;
+        settings::window::get_quit_grace_ms() -> u64;
+        settings::window::set_quit_grace_ms(grace_ms: u64) -> u64;
+        settings::paths::get_path_settings() -> PathSettings;
+        settings::paths::set_path_settings(settings: PathSettings) -> PathSettings;
+        settings::cover_cache::get_cover_cache_settings() -> CoverCacheSettings;
+        settings::cover_cache::set_cover_cache_settings(settings: CoverCacheSettings) -> CoverCacheSettings;
+        settings::downloads::get_download_settings() -> DownloadSettings;
+        settings::downloads::set_download_settings(settings: DownloadSettings) -> DownloadSettings;
+        settings::power::get_power_settings() -> PowerSettings;
+        settings::power::set_power_settings(settings: PowerSettings) -> PowerSettings;
+        settings::notifications::get_notification_settings() -> NotificationSettings;
+        settings::http::get_http_policy() -> HttpPolicy;
+        settings::http::set_http_policy(policy: HttpPolicy) -> HttpPolicy;
+        settings::api_endpoints::get_api_endpoints() -> BTreeMap<String, String>;
+        settings::api_endpoints::set_api_endpoint(source: String, url: String) -> Option<String>;
+    }
+    events {
+        WINDOW_VISIBILITY_CHANGED_EVENT => WindowVisibilityChanged;
+        "main-window-visibility" => bool;
+        "main-close-requested" => ();
+        WINDOW_PIN_CHANGED_EVENT => WindowPinChanged;
+        WINDOW_ALWAYS_ON_TOP_CHANGED_EVENT => WindowPinChanged;
+        WINDOW_ZOOM_CHANGED_EVENT => WindowZoomChanged;
+        WINDOW_PREWARMED_EVENT => WindowPrewarmed;
+        WINDOW_LIFECYCLE_EVENT => WindowLifecycle;
+        SHARED_STATE_CHANGED_EVENT => SharedStateChanged;
+        WINDOW_RPC_REQUEST_EVENT => WindowRpcRequest;
+        WINDOW_UNRESPONSIVE_EVENT => WindowUnresponsive;
+        APP_WILL_QUIT_EVENT => AppWillQuit;
+        SYSTEM_IDLE_EVENT => IdleChanged;
+        SYSTEM_ACTIVE_EVENT => IdleChanged;
+        SYSTEM_THEME_CHANGED_EVENT => SystemTheme;
+        ACCENT_COLOR_CHANGED_EVENT => AccentColor;
+        DESKTOP_LYRICS_SNAPPED_EVENT => LyricsSnapped;
+        DESKTOP_LYRICS_HOVER_EVENT => LyricsHover;
+        TITLEBAR_STYLE_CHANGED_EVENT => TitlebarStyleChanged;
+        DESKTOP_LYRICS_AUTOHIDDEN_EVENT => bool;
+        DESKTOP_LYRICS_ORIENTATION_CLAMPED_EVENT => OrientationClamped;
+        "tray-popup-opened" => Option<TrayNowPlaying>;
+        TRAY_MENU_PLAY_PAUSE_EVENT => ();
+        TRAY_MENU_NEXT_TRACK_EVENT => ();
+        TRAY_MENU_PREV_TRACK_EVENT => ();
+        // Windows-only constants in `window::platform::windows_taskbar`.
+        "thumbbar-prev" => ();
+        "thumbbar-play-pause" => ();
+        "thumbbar-next" => ();
+        EQ_CHANGED_EVENT => EqState;
+        SECOND_INSTANCE_EVENT => SecondInstance;
+        FILES_OPENED_EVENT => FilesOpened;
+        NOTIFICATION_ACTION_EVENT => NotificationActionPressed;
+        SYSTEM_VOLUME_CHANGED_EVENT => SystemVolume;
+        LYRIC_LINE_CHANGED_EVENT => LyricLineChange;
+    }
+}
+
+fn doc_comment(schema: &SchemaObject, indent: &str) -> String {
+    let Some(description) = schema
+        .metadata
+        .as_ref()
+        .and_then(|m| m.description.as_ref())
+    else {
+        return String::new();
+    };
+    let lines: Vec<&str> = description.lines().collect();
+    if lines.len() == 1 {
+        return format!("{}/** {} */\n", indent, lines[0]);
+    }
+    let mut out = format!("{}/**\n", indent);
+    for line in lines {
+        out.push_str(&format!("{} * {}\n", indent, line).replace(" * \n", " *\n"));
+    }
+    out.push_str(&format!("{} */\n", indent));
+    out
+}
+
+fn union(members: impl IntoIterator<Item = String>) -> String {
+    let mut seen = BTreeSet::new();
+    let members: Vec<String> = members
+        .into_iter()
+        .filter(|m| seen.insert(m.clone()))
+        .collect();
+    match members.len() {
+        0 => "never".into(),
+        _ => members.join(" | "),
+    }
+}
+
+fn property_key(name: &str) -> String {
+    let is_ident = name.chars().enumerate().all(|(i, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+    });
+    if is_ident {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn ts_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".into(),
+        Schema::Bool(false) => "never".into(),
+        Schema::Object(object) => ts_object(object),
+    }
+}
+
+fn ts_object(schema: &SchemaObject) -> String {
+    if let Some(reference) = &schema.reference {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(value) = &schema.const_value {
+        return value.to_string();
+    }
+    if let Some(values) = &schema.enum_values {
+        return union(values.iter().map(Value::to_string));
+    }
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(members) = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref()) {
+            return union(members.iter().map(ts_type));
+        }
+        if let Some(members) = &subschemas.all_of {
+            return members.iter().map(ts_type).collect::<Vec<_>>().join(" & ");
+        }
+    }
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => ts_instance(**ty, schema),
+        Some(SingleOrVec::Vec(types)) => union(types.iter().map(|ty| ts_instance(*ty, schema))),
+        None if schema.object.is_some() => ts_instance(InstanceType::Object, schema),
+        None => "unknown".into(),
+    }
+}
+
+fn ts_instance(ty: InstanceType, schema: &SchemaObject) -> String {
+    match ty {
+        InstanceType::Null => "null".into(),
+        InstanceType::Boolean => "boolean".into(),
+        InstanceType::Integer | InstanceType::Number => "number".into(),
+        InstanceType::String => "string".into(),
+        InstanceType::Array => match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => {
+                let item = ts_type(item);
+                if item.contains(' ') {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+            Some(SingleOrVec::Vec(items)) => {
+                format!(
+                    "[{}]",
+                    items.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+                )
+            }
+            None => "unknown[]".into(),
+        },
+        InstanceType::Object => {
+            let Some(object) = &schema.object else {
+                return "Record<string, unknown>".into();
+            };
+            if object.properties.is_empty() {
+                let value = object
+                    .additional_properties
+                    .as_deref()
+                    .map_or_else(|| "unknown".into(), ts_type);
+                return format!("Record<string, {}>", value);
+            }
+            let fields: Vec<String> = object
+                .properties
+                .iter()
+                .map(|(name, field)| {
+                    let optional = if object.required.contains(name) {
+                        ""
+                    } else {
+                        "?"
+                    };
+                    format!("{}{}: {}", property_key(name), optional, ts_type(field))
+                })
+                .collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+    }
+}
+
+/// A named definition: an interface for plain objects, an alias otherwise.
+fn ts_definition(name: &str, schema: &Schema) -> String {
+    let Schema::Object(object) = schema else {
+        return format!("export type {} = {};\n", name, ts_type(schema));
+    };
+    let mut out = doc_comment(object, "");
+    let plain_object = object.subschemas.is_none()
+        && object.enum_values.is_none()
+        && object
+            .object
+            .as_ref()
+            .is_some_and(|o| !o.properties.is_empty());
+    if !plain_object {
+        out.push_str(&format!("export type {} = {};\n", name, ts_object(object)));
+        return out;
+    }
+    let fields = object.object.as_ref().expect("checked above");
+    out.push_str(&format!("export interface {} {{\n", name));
+    for (field, schema) in &fields.properties {
+        if let Schema::Object(field_object) = schema {
+            out.push_str(&doc_comment(field_object, "  "));
+        }
+        let optional = if fields.required.contains(field) {
+            ""
+        } else {
+            "?"
+        };
+        out.push_str(&format!(
+            "  {}{}: {};\n",
+            property_key(field),
+            optional,
+            ts_type(schema)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn camel_case(name: &str) -> String {
+    arg_key(name, false)
+}
+
+fn is_nullable(schema: &Schema) -> bool {
+    ts_type(schema).split(" | ").any(|member| member == "null")
+}
+
+fn ts_command(command: &CommandSpec) -> String {
+    // Trailing nullable arguments may be left out.
+    let optional_from = command
+        .args
+        .iter()
+        .rposition(|arg| !is_nullable(&arg.schema))
+        .map_or(0, |i| i + 1);
+    let params: Vec<String> = command
+        .args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let optional = if i >= optional_from { "?" } else { "" };
+            format!("{}{}: {}", arg.name, optional, ts_type(&arg.schema))
+        })
+        .collect();
+    let returns = match ts_type(&command.returns).as_str() {
+        "null" => "void".to_string(),
+        other => other.to_string(),
+    };
+    let keys: Vec<&str> = command.args.iter().map(|arg| arg.name.as_str()).collect();
+    let invoke_args = if keys.is_empty() {
+        String::new()
+    } else {
+        format!(", {{ {} }}", keys.join(", "))
+    };
+    format!(
+        "export function {}({}): Promise<{}> {{\n  return invoke<{}>(\"{}\"{});\n}}\n",
+        camel_case(&command.name),
+        params.join(", "),
+        returns,
+        returns,
+        command.name,
+        invoke_args
+    )
+}
+
+/// Render `bindings.ts`.
+pub fn typescript(manifest: &Manifest) -> String {
+    let mut out = String::from(
+        "// Generated by `gmplayer --dump-bindings` from src-tauri/src/desktop/bindings.rs.\n\
+         // Do not edit by hand.\n\n\
+         import { invoke } from \"@tauri-apps/api/core\";\n",
+    );
+    for (name, schema) in &manifest.definitions {
+        out.push('\n');
+        out.push_str(&ts_definition(name, schema));
+    }
+
+    out.push_str("\nexport const EVENTS = {\n");
+    for event in &manifest.events {
+        out.push_str(&format!(
+            "  {}: {:?},\n",
+            camel_case(&event.name.replace('-', "_")),
+            event.name
+        ));
+    }
+    out.push_str("} as const;\n\nexport interface EventPayloads {\n");
+    for event in &manifest.events {
+        out.push_str(&format!(
+            "  {:?}: {};\n",
+            event.name,
+            ts_type(&event.payload)
+        ));
+    }
+    out.push_str("}\n");
+
+    for command in &manifest.commands {
+        out.push('\n');
+        out.push_str(&ts_command(command));
+    }
+    out
+}
+
+/// Render `bindings.json`.
+pub fn manifest_json(manifest: &Manifest) -> String {
+    let mut json = serde_json::to_string_pretty(manifest).expect("manifest is serializable");
+    json.push('\n');
+    json
+}
+
+/// Write both files into `dir`.
+pub fn dump(dir: &Path) -> Result<(), String> {
+    let manifest = manifest();
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(MANIFEST_FILE), manifest_json(&manifest)).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(TYPESCRIPT_FILE), typescript(&manifest)).map_err(|e| e.to_string())
+}
+
+/// Handle `--dump-bindings [dir]`. Returns `true` when the flag was given
+/// and the app should exit instead of starting.
+pub fn dump_from_args(args: impl IntoIterator<Item = String>) -> bool {
+    let mut args = args.into_iter();
+    if !args.any(|arg| arg == DUMP_BINDINGS_FLAG) {
+        return false;
+    }
+    let dir = args
+        .next()
+        .filter(|arg| !arg.starts_with("--"))
+        .map_or_else(default_dir, PathBuf::from);
+    match dump(&dir) {
+        Ok(()) => println!(
+            "Wrote {} and {} to {}",
+            MANIFEST_FILE,
+            TYPESCRIPT_FILE,
+            dir.display()
+        ),
+        Err(e) => {
+            eprintln!("Failed to write bindings to {}: {}", dir.display(), e);
+            std::process::exit(1);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argument_keys_follow_tauri_casing() {
+        assert_eq!(arg_key("dest_path", false), "destPath");
+        assert_eq!(arg_key("label_a", false), "labelA");
+        assert_eq!(arg_key("dest_path", true), "dest_path");
+
+        let manifest = manifest();
+        let osd = manifest
+            .commands
+            .iter()
+            .find(|c| c.name == "show_osd")
+            .unwrap();
+        assert_eq!(
+            ts_command(osd),
+            "export function showOsd(kind: string, payload: unknown, durationMs?: number | null): Promise<boolean> {\n  return invoke<boolean>(\"show_osd\", { kind, payload, durationMs });\n}\n"
+        );
+    }
+
+    /// Arguments Tauri injects instead of reading from the `invoke` payload.
+    const INJECTED: [&str; 5] = ["AppHandle", "State", "Window", "WebviewWindow", "Webview"];
+
+    struct Signature {
+        args: Vec<(String, String)>,
+        returns: String,
+    }
+
+    /// Drop whitespace and module paths, so `tauri :: AppHandle` and
+    /// `AppHandle` compare equal. A returned `&'static str` resolves to a
+    /// `String` on the frontend.
+    fn normalize_type(ty: &str) -> String {
+        let mut out = String::new();
+        for c in ty.chars().filter(|c| !c.is_whitespace()) {
+            if c == ':' && out.ends_with(':') {
+                out.pop();
+                while out.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    out.pop();
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        match out.as_str() {
+            "&str" | "&'staticstr" => "String".to_string(),
+            _ => out,
+        }
+    }
+
+    /// Split `text` at commas outside brackets.
+    fn split_top_level(text: &str) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let (mut depth, mut start) = (0i32, 0);
+        for (i, c) in text.char_indices() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' if !text[..i].ends_with('-') => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(&text[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&text[start..]);
+        parts.into_iter().filter(|p| !p.trim().is_empty()).collect()
+    }
+
+    /// Parse the signature of the function starting at `fn name`.
+    fn parse_signature(source: &str) -> Signature {
+        let open = source.find('(').unwrap();
+        let mut depth = 0;
+        let close = source[open..]
+            .char_indices()
+            .find_map(|(i, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(open + i)
+            })
+            .unwrap();
+        let args = split_top_level(&source[open + 1..close])
+            .into_iter()
+            .filter_map(|param| {
+                let (name, ty) = param.split_once(':')?;
+                let ty = normalize_type(ty);
+                let base = ty.split('<').next().unwrap_or_default();
+                if INJECTED.contains(&base) {
+                    return None;
+                }
+                let name = name.trim().trim_start_matches("mut ").trim();
+                Some((name.to_string(), ty))
+            })
+            .collect();
+        let rest = &source[close + 1..];
+        let body = rest.find(['{', ';']).unwrap_or(rest.len());
+        let head = rest[..body].split(" where").next().unwrap_or_default();
+        let returns = match head.trim().strip_prefix("->") {
+            Some(ty) => {
+                let ty = normalize_type(ty);
+                match ty.strip_prefix("Result<") {
+                    Some(inner) => split_top_level(&inner[..inner.len() - 1])[0].to_string(),
+                    None => ty,
+                }
+            }
+            None => "()".to_string(),
+        };
+        Signature { args, returns }
+    }
+
+    /// Every `#[command]` function under `src`, by name.
+    fn command_signatures() -> BTreeMap<String, Signature> {
+        let mut signatures = BTreeMap::new();
+        let mut dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("src")];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if path.extension() != Some("rs".as_ref()) {
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path).unwrap();
+                let mut at = 0;
+                for line in source.split_inclusive('\n') {
+                    at += line.len();
+                    let line = line.trim_start();
+                    if !line.starts_with("#[command") && !line.starts_with("#[tauri::command") {
+                        continue;
+                    }
+                    let Some(fn_at) = source[at..].find("fn ") else {
+                        continue;
+                    };
+                    let item = &source[at + fn_at + 3..];
+                    let name_end = item.find(['(', '<']).unwrap();
+                    signatures.insert(item[..name_end].to_string(), parse_signature(item));
+                }
+            }
+        }
+        signatures
+    }
+
+    #[test]
+    fn registry_matches_command_signatures() {
+        let signatures = command_signatures();
+        let mut mismatches = Vec::new();
+        for declared in declared_commands() {
+            let name = declared.path.rsplit("::").next().unwrap().trim();
+            let Some(signature) = signatures.get(name) else {
+                mismatches.push(format!("{}: no #[command] fn", name));
+                continue;
+            };
+            let args: Vec<(String, String)> = declared
+                .args
+                .iter()
+                .map(|(name, ty)| (name.to_string(), normalize_type(ty)))
+                .collect();
+            if args != signature.args {
+                mismatches.push(format!("{}: {:?} != {:?}", name, args, signature.args));
+            }
+            let returns = normalize_type(declared.returns);
+            if returns != signature.returns {
+                mismatches.push(format!("{}: {} != {}", name, returns, signature.returns));
+            }
+        }
+        assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    }
+
+    #[test]
+    fn checked_in_bindings_are_up_to_date() {
+        let manifest = manifest();
+        let dir = default_dir();
+        for (file, expected) in [
+            (MANIFEST_FILE, manifest_json(&manifest)),
+            (TYPESCRIPT_FILE, typescript(&manifest)),
+        ] {
+            let actual = std::fs::read_to_string(dir.join(file)).unwrap_or_default();
+            assert!(
+                actual == expected,
+                "{} is stale; regenerate with `cargo run -- {}`",
+                file,
+                DUMP_BINDINGS_FLAG
+            );
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Casting to DLNA / UPnP media renderers (network speakers, TVs).
+//!
+//! Discovery is an SSDP M-SEARCH followed by a fetch of every responder's
+//! device description. Playback is handed off with AVTransport SOAP actions;
+//! the renderer then pulls `media_url` itself, so the URL has to be
+//! reachable from the LAN and loopback URLs are refused up front.
+//!
+//! Plenty of devices answer SSDP but fail or reject SOAP calls. Those
+//! failures come back as [`CastError::Rejected`] / [`CastError::Unreachable`]
+//! for the target in question and never affect the other targets.
+
+mod soap;
+mod ssdp;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State, Url};
+use tauri_plugin_http::reqwest::header::CONTENT_TYPE;
+
+use crate::http_util;
+use crate::settings::http::HttpPolicy;
+use crate::settings::SettingsStore;
+
+const DEFAULT_DISCOVERY_MS: u64 = 3_000;
+const MIN_DISCOVERY_MS: u64 = 1_000;
+const MAX_DISCOVERY_MS: u64 = 10_000;
+/// Renderers are on the LAN; anything slower than this is as good as gone.
+const LAN_TIMEOUT_MS: u64 = 5_000;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CastError {
+    /// No renderer with this id was found by the last discovery.
+    UnknownTarget(String),
+    /// The media URL cannot be played by a renderer.
+    InvalidMedia(String),
+    /// The renderer did not answer.
+    Unreachable(String),
+    /// The renderer answered with a SOAP fault or an HTTP error.
+    Rejected(String),
+    Failed(String),
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTarget(id) => write!(f, "Cast target '{}' not found", id),
+            Self::InvalidMedia(message) => write!(f, "Media cannot be cast: {}", message),
+            Self::Unreachable(message) => write!(f, "Cast target unreachable: {}", message),
+            Self::Rejected(message) => write!(f, "Cast target rejected the request: {}", message),
+            Self::Failed(message) => write!(f, "Cast failed: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastTarget {
+    /// Device UDN without the `uuid:` prefix.
+    pub id: String,
+    pub name: String,
+    pub model: Option<String>,
+    /// Device description URL.
+    pub location: String,
+    /// AVTransport control URL.
+    pub control_url: String,
+    /// RenderingControl control URL; `None` when volume cannot be set.
+    pub rendering_control_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastMetadata {
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub cover_url: Option<String>,
+    /// Defaults to `audio/mpeg`.
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CastAction {
+    Play,
+    Pause,
+    Stop,
+    Seek {
+        #[serde(rename = "positionMs")]
+        position_ms: u64,
+    },
+    SetVolume {
+        /// 0-100.
+        volume: u8,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastStatus {
+    /// AVTransport `CurrentTransportState`, e.g. `PLAYING`, `PAUSED_PLAYBACK`, `STOPPED`.
+    pub transport_state: String,
+    pub position_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub volume: Option<u8>,
+}
+
+/// Renderers seen by the last discoveries, keyed by id.
+#[derive(Default)]
+pub struct CastState {
+    targets: Mutex<HashMap<String, CastTarget>>,
+}
+
+impl CastState {
+    fn target(&self, id: &str) -> Result<CastTarget, CastError> {
+        self.targets
+            .lock()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| CastError::UnknownTarget(id.to_string()))
+    }
+}
+
+/// HTTP policy for renderer requests. SOAP actions are not idempotent and
+/// UPnP faults arrive as HTTP 500, so nothing is retried.
+fn lan_policy(app: &AppHandle) -> HttpPolicy {
+    let mut policy = app
+        .try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.http.clone()))
+        .unwrap_or_default();
+    policy.max_retries = 0;
+    policy.timeout_ms = policy.timeout_ms.min(LAN_TIMEOUT_MS);
+    policy
+}
+
+/// The renderer fetches the media itself, so the URL must be plain HTTP(S)
+/// on an address other than loopback.
+fn check_media_url(media_url: &str) -> Result<(), CastError> {
+    let url = Url::parse(media_url)
+        .map_err(|e| CastError::InvalidMedia(format!("{}: {}", media_url, e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(CastError::InvalidMedia(format!(
+            "unsupported scheme '{}'",
+            url.scheme()
+        )));
+    }
+    let host = url.host_str().unwrap_or_default();
+    let loopback = host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    if host.is_empty() || loopback {
+        return Err(CastError::InvalidMedia(format!(
+            "'{}' is not reachable from the renderer",
+            media_url
+        )));
+    }
+    Ok(())
+}
+
+async fn describe(response: ssdp::SsdpResponse, policy: &HttpPolicy) -> Option<CastTarget> {
+    let request = match http_util::client().get(&response.location).build() {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid SSDP location {}: {}", response.location, e);
+            return None;
+        }
+    };
+    let xml = match http_util::fetch_with_policy(request, policy).await {
+        Ok(r) if r.status().is_success() => r.text().await.ok()?,
+        Ok(r) => {
+            warn!(
+                "Device description {} returned HTTP {}",
+                response.location,
+                r.status()
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!(
+                "Failed to fetch device description {}: {}",
+                response.location, e
+            );
+            return None;
+        }
+    };
+    let device = soap::parse_description(&xml, &response.location)?;
+    Some(CastTarget {
+        id: device
+            .udn
+            .unwrap_or_else(|| response.device_id().to_string()),
+        name: device.name,
+        model: device.model,
+        location: response.location,
+        control_url: device.av_transport_url,
+        rendering_control_url: device.rendering_control_url,
+    })
+}
+
+async fn soap_call(
+    control_url: &str,
+    service: &str,
+    action: &str,
+    args: &[(&str, &str)],
+    policy: &HttpPolicy,
+) -> Result<String, CastError> {
+    let request = http_util::client()
+        .post(control_url)
+        .header(CONTENT_TYPE, "text/xml; charset=\"utf-8\"")
+        .header("SOAPACTION", format!("\"{}#{}\"", service, action))
+        .body(soap::envelope(service, action, args))
+        .build()
+        .map_err(|e| CastError::Failed(e.to_string()))?;
+    let response = http_util::fetch_with_policy(request, policy)
+        .await
+        .map_err(|e| CastError::Unreachable(e.to_string()))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| CastError::Unreachable(e.to_string()))?;
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(CastError::Rejected(
+            soap::fault_message(&body)
+                .unwrap_or_else(|| format!("{} returned HTTP {}", action, status)),
+        ))
+    }
+}
+
+async fn av_transport(
+    target: &CastTarget,
+    action: &str,
+    args: &[(&str, &str)],
+    policy: &HttpPolicy,
+) -> Result<String, CastError> {
+    let mut full = vec![("InstanceID", "0")];
+    full.extend_from_slice(args);
+    soap_call(
+        &target.control_url,
+        soap::AV_TRANSPORT,
+        action,
+        &full,
+        policy,
+    )
+    .await
+}
+
+async fn rendering_control(
+    target: &CastTarget,
+    action: &str,
+    args: &[(&str, &str)],
+    policy: &HttpPolicy,
+) -> Result<String, CastError> {
+    let url = target
+        .rendering_control_url
+        .as_deref()
+        .ok_or_else(|| CastError::Rejected(format!("'{}' has no volume control", target.name)))?;
+    let mut full = vec![("InstanceID", "0"), ("Channel", "Master")];
+    full.extend_from_slice(args);
+    soap_call(url, soap::RENDERING_CONTROL, action, &full, policy).await
+}
+
+/// Search the LAN for media renderers. Runs the SSDP search off the async
+/// runtime and fetches descriptions concurrently.
+#[tauri::command]
+pub async fn discover_cast_targets(
+    app: AppHandle,
+    state: State<'_, CastState>,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<CastTarget>, CastError> {
+    let timeout = Duration::from_millis(
+        timeout_ms
+            .unwrap_or(DEFAULT_DISCOVERY_MS)
+            .clamp(MIN_DISCOVERY_MS, MAX_DISCOVERY_MS),
+    );
+    let responses = tauri::async_runtime::spawn_blocking(move || ssdp::search(timeout))
+        .await
+        .map_err(|e| CastError::Failed(e.to_string()))?
+        .map_err(|e| CastError::Failed(format!("SSDP search failed: {}", e)))?;
+
+    let policy = lan_policy(&app);
+    let handles: Vec<_> = responses
+        .into_iter()
+        .map(|response| {
+            let policy = policy.clone();
+            tauri::async_runtime::spawn(async move { describe(response, &policy).await })
+        })
+        .collect();
+
+    let mut found: Vec<CastTarget> = Vec::new();
+    for handle in handles {
+        let Ok(Some(target)) = handle.await else {
+            continue;
+        };
+        // One device can answer from several locations (e.g. per interface).
+        if !found.iter().any(|t| t.id == target.id) {
+            found.push(target);
+        }
+    }
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut targets = state.targets.lock();
+    for target in &found {
+        targets.insert(target.id.clone(), target.clone());
+    }
+    Ok(found)
+}
+
+/// Hand `media_url` over to a renderer and start playback.
+#[tauri::command]
+pub async fn cast_to_target(
+    app: AppHandle,
+    state: State<'_, CastState>,
+    target_id: String,
+    media_url: String,
+    metadata: CastMetadata,
+) -> Result<(), CastError> {
+    check_media_url(&media_url)?;
+    let target = state.target(&target_id)?;
+    let policy = lan_policy(&app);
+    let didl = soap::didl_lite(
+        &media_url,
+        metadata.mime_type.as_deref().unwrap_or("audio/mpeg"),
+        &metadata.title,
+        metadata.artist.as_deref(),
+        metadata.album.as_deref(),
+        metadata.cover_url.as_deref(),
+    );
+    av_transport(
+        &target,
+        "SetAVTransportURI",
+        &[("CurrentURI", &media_url), ("CurrentURIMetaData", &didl)],
+        &policy,
+    )
+    .await?;
+    av_transport(&target, "Play", &[("Speed", "1")], &policy).await?;
+    Ok(())
+}
+
+/// Transport and volume control for a renderer that is already casting.
+#[tauri::command]
+pub async fn cast_control(
+    app: AppHandle,
+    state: State<'_, CastState>,
+    target_id: String,
+    action: CastAction,
+) -> Result<(), CastError> {
+    let target = state.target(&target_id)?;
+    let policy = lan_policy(&app);
+    match action {
+        CastAction::Play => av_transport(&target, "Play", &[("Speed", "1")], &policy).await,
+        CastAction::Pause => av_transport(&target, "Pause", &[], &policy).await,
+        CastAction::Stop => av_transport(&target, "Stop", &[], &policy).await,
+        CastAction::Seek { position_ms } => {
+            let position = soap::format_time(position_ms);
+            av_transport(
+                &target,
+                "Seek",
+                &[("Unit", "REL_TIME"), ("Target", &position)],
+                &policy,
+            )
+            .await
+        }
+        CastAction::SetVolume { volume } => {
+            let volume = volume.min(100).to_string();
+            rendering_control(&target, "SetVolume", &[("DesiredVolume", &volume)], &policy).await
+        }
+    }
+    .map(|_| ())
+}
+
+/// Poll a renderer's transport state, position and volume.
+#[tauri::command]
+pub async fn get_cast_state(
+    app: AppHandle,
+    state: State<'_, CastState>,
+    target_id: String,
+) -> Result<CastStatus, CastError> {
+    let target = state.target(&target_id)?;
+    let policy = lan_policy(&app);
+    let transport = av_transport(&target, "GetTransportInfo", &[], &policy).await?;
+    // Position and volume are best-effort; many renderers only half implement them.
+    let position = av_transport(&target, "GetPositionInfo", &[], &policy)
+        .await
+        .ok();
+    let volume = if target.rendering_control_url.is_some() {
+        rendering_control(&target, "GetVolume", &[], &policy)
+            .await
+            .ok()
+            .and_then(|xml| soap::tag_text(&xml, "CurrentVolume"))
+            .and_then(|v| v.parse::<u8>().ok())
+    } else {
+        None
+    };
+    Ok(CastStatus {
+        transport_state: soap::tag_text(&transport, "CurrentTransportState")
+            .unwrap_or_else(|| "UNKNOWN".into()),
+        position_ms: position
+            .as_deref()
+            .and_then(|xml| soap::tag_text(xml, "RelTime"))
+            .and_then(|t| soap::parse_time(&t)),
+        duration_ms: position
+            .as_deref()
+            .and_then(|xml| soap::tag_text(xml, "TrackDuration"))
+            .and_then(|t| soap::parse_time(&t)),
+        volume,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_url_must_be_reachable_from_the_lan() {
+        assert!(check_media_url("http://192.168.1.10:8080/stream/42").is_ok());
+        assert!(check_media_url("https://music.example.com/a.mp3").is_ok());
+        for url in [
+            "http://127.0.0.1:8080/stream/42",
+            "http://localhost/a.mp3",
+            "http://[::1]/a.mp3",
+            "file:///music/a.mp3",
+            "not a url",
+        ] {
+            assert!(
+                matches!(check_media_url(url), Err(CastError::InvalidMedia(_))),
+                "{url}"
+            );
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Minimal UPnP XML handling: device descriptions, SOAP envelopes and
+//! responses. Renderers only need a handful of leaf elements, so this
+//! scans for tags instead of pulling in a full XML parser.
+
+use tauri::Url;
+
+pub const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+pub const RENDERING_CONTROL: &str = "urn:schemas-upnp-org:service:RenderingControl:1";
+
+/// Text of the first `<tag>` (any namespace prefix) that has no child elements.
+pub fn tag_text(xml: &str, tag: &str) -> Option<String> {
+    let mut from = 0;
+    while let Some(rel) = xml[from..].find(tag) {
+        let start = from + rel;
+        from = start + tag.len();
+        let Some(open) = xml[..start].rfind('<') else {
+            continue;
+        };
+        let prefix = &xml[open + 1..start];
+        let prefix_ok = prefix.is_empty()
+            || (prefix.ends_with(':')
+                && prefix[..prefix.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+        let boundary_ok = matches!(xml[from..].chars().next(), Some('>' | ' ' | '/' | '\t'));
+        if !prefix_ok || !boundary_ok {
+            continue;
+        }
+        let gt = from + xml[from..].find('>')?;
+        if xml[..gt].ends_with('/') {
+            return Some(String::new());
+        }
+        let close = gt + 1 + xml[gt + 1..].find("</")?;
+        return Some(unescape(xml[gt + 1..close].trim()));
+    }
+    None
+}
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// What we need from a renderer's device description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceDescription {
+    pub udn: Option<String>,
+    pub name: String,
+    pub model: Option<String>,
+    pub av_transport_url: String,
+    pub rendering_control_url: Option<String>,
+}
+
+/// Parse a device description fetched from `location`. Control URLs are
+/// resolved against `URLBase` when present, otherwise against `location`.
+/// Returns `None` for devices without an AVTransport service.
+pub fn parse_description(xml: &str, location: &str) -> Option<DeviceDescription> {
+    let base = tag_text(xml, "URLBase")
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| location.to_string());
+    let base = Url::parse(&base).ok()?;
+
+    let mut av_transport_url = None;
+    let mut rendering_control_url = None;
+    for block in xml.split("<service>").skip(1) {
+        let block = block.split("</service>").next().unwrap_or(block);
+        let (Some(service_type), Some(control)) = (
+            tag_text(block, "serviceType"),
+            tag_text(block, "controlURL"),
+        ) else {
+            continue;
+        };
+        let Ok(url) = base.join(&control) else {
+            continue;
+        };
+        if service_type.starts_with("urn:schemas-upnp-org:service:AVTransport:") {
+            av_transport_url.get_or_insert(url.to_string());
+        } else if service_type.starts_with("urn:schemas-upnp-org:service:RenderingControl:") {
+            rendering_control_url.get_or_insert(url.to_string());
+        }
+    }
+
+    Some(DeviceDescription {
+        udn: tag_text(xml, "UDN").map(|udn| udn.trim_start_matches("uuid:").to_string()),
+        name: tag_text(xml, "friendlyName").unwrap_or_else(|| "Unknown renderer".into()),
+        model: tag_text(xml, "modelName").filter(|m| !m.is_empty()),
+        av_transport_url: av_transport_url?,
+        rendering_control_url,
+    })
+}
+
+pub fn envelope(service: &str, action: &str, args: &[(&str, &str)]) -> String {
+    let mut body = String::new();
+    for (name, value) in args {
+        body.push_str(&format!("<{0}>{1}</{0}>", name, escape(value)));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service}\">{body}</u:{action}></s:Body></s:Envelope>"
+    )
+}
+
+/// `UPnPError` details of a SOAP fault, e.g. "UPnP error 714: Illegal MIME-type".
+pub fn fault_message(xml: &str) -> Option<String> {
+    let code = tag_text(xml, "errorCode");
+    let description = tag_text(xml, "errorDescription").or_else(|| tag_text(xml, "faultstring"));
+    match (code, description) {
+        (Some(code), Some(description)) => Some(format!("UPnP error {}: {}", code, description)),
+        (Some(code), None) => Some(format!("UPnP error {}", code)),
+        (None, Some(description)) => Some(description),
+        (None, None) => None,
+    }
+}
+
+/// DIDL-Lite item describing the track, sent as `CurrentURIMetaData`.
+pub fn didl_lite(
+    media_url: &str,
+    mime_type: &str,
+    title: &str,
+    artist: Option<&str>,
+    album: Option<&str>,
+    cover_url: Option<&str>,
+) -> String {
+    let mut item = format!("<dc:title>{}</dc:title>", escape(title));
+    if let Some(artist) = artist {
+        item.push_str(&format!(
+            "<upnp:artist>{0}</upnp:artist><dc:creator>{0}</dc:creator>",
+            escape(artist)
+        ));
+    }
+    if let Some(album) = album {
+        item.push_str(&format!("<upnp:album>{}</upnp:album>", escape(album)));
+    }
+    if let Some(cover) = cover_url {
+        item.push_str(&format!(
+            "<upnp:albumArtURI>{}</upnp:albumArtURI>",
+            escape(cover)
+        ));
+    }
+    format!(
+        "<DIDL-Lite xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\">\
+         <item id=\"0\" parentID=\"-1\" restricted=\"1\">{item}\
+         <upnp:class>object.item.audioItem.musicTrack</upnp:class>\
+         <res protocolInfo=\"http-get:*:{mime}:*\">{url}</res></item></DIDL-Lite>",
+        mime = escape(mime_type),
+        url = escape(media_url),
+    )
+}
+
+/// `H:MM:SS` as used by `Seek` with `REL_TIME`.
+pub fn format_time(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Parse `H+:MM:SS[.F+]`; `NOT_IMPLEMENTED` and other junk give `None`.
+pub fn parse_time(text: &str) -> Option<u64> {
+    let mut parts = text.trim().split(':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some((hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <friendlyName>Living Room &amp; Kitchen</friendlyName>
+    <modelName>Speaker One</modelName>
+    <UDN>uuid:1234-abcd</UDN>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/RenderingControl/ctrl</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>AVTransport/ctrl?x=1&amp;y=2</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn description_yields_resolved_control_urls() {
+        let device = parse_description(DESCRIPTION, "http://10.0.0.5:8080/dev/desc.xml").unwrap();
+        assert_eq!(device.udn.as_deref(), Some("1234-abcd"));
+        assert_eq!(device.name, "Living Room & Kitchen");
+        assert_eq!(device.model.as_deref(), Some("Speaker One"));
+        assert_eq!(
+            device.av_transport_url,
+            "http://10.0.0.5:8080/dev/AVTransport/ctrl?x=1&y=2"
+        );
+        assert_eq!(
+            device.rendering_control_url.as_deref(),
+            Some("http://10.0.0.5:8080/RenderingControl/ctrl")
+        );
+    }
+
+    #[test]
+    fn description_without_av_transport_is_skipped() {
+        let xml = DESCRIPTION.replace("AVTransport:1", "ConnectionManager:1");
+        assert!(parse_description(&xml, "http://10.0.0.5/desc.xml").is_none());
+    }
+
+    #[test]
+    fn reads_prefixed_tags_and_faults() {
+        let response = "<s:Envelope><s:Body><s:Fault><faultstring>UPnPError</faultstring>\
+            <detail><UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\
+            <errorCode>714</errorCode><errorDescription>Illegal MIME-type</errorDescription>\
+            </UPnPError></detail></s:Fault></s:Body></s:Envelope>";
+        assert_eq!(
+            fault_message(response).as_deref(),
+            Some("UPnP error 714: Illegal MIME-type")
+        );
+        assert_eq!(
+            tag_text("<u:Res><Vol>30</Vol></u:Res>", "Vol").as_deref(),
+            Some("30")
+        );
+        // `<VolumeDB>` must not match a search for `Volume`.
+        assert_eq!(tag_text("<VolumeDB>1</VolumeDB>", "Volume"), None);
+    }
+
+    #[test]
+    fn envelope_escapes_arguments() {
+        let xml = envelope(AV_TRANSPORT, "Play", &[("Speed", "1<&>")]);
+        assert!(xml.contains("<u:Play xmlns:u=\"urn:schemas-upnp-org:service:AVTransport:1\">"));
+        assert!(xml.contains("<Speed>1&lt;&amp;&gt;</Speed>"));
+    }
+
+    #[test]
+    fn time_round_trips() {
+        assert_eq!(format_time(3_725_000), "1:02:05");
+        assert_eq!(parse_time("1:02:05"), Some(3_725_000));
+        assert_eq!(parse_time("00:00:07.250"), Some(7_250));
+        assert_eq!(parse_time("NOT_IMPLEMENTED"), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! SSDP (UPnP discovery) search for media renderers.
+
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+const SSDP_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::new(239, 255, 255, 250), 1900);
+const MEDIA_RENDERER: &str = "urn:schemas-upnp-org:device:MediaRenderer:1";
+
+/// One answer to an M-SEARCH.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsdpResponse {
+    /// URL of the device description XML.
+    pub location: String,
+    /// Unique service name; its `uuid:...` part identifies the device.
+    pub usn: String,
+}
+
+impl SsdpResponse {
+    /// Device identity shared by every service a device advertises.
+    pub fn device_id(&self) -> &str {
+        let id = self.usn.split("::").next().unwrap_or(&self.usn);
+        id.strip_prefix("uuid:").unwrap_or(id)
+    }
+}
+
+fn m_search(mx: u64) -> String {
+    format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: 239.255.255.250:1900\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         MX: {}\r\n\
+         ST: {}\r\n\r\n",
+        mx, MEDIA_RENDERER
+    )
+}
+
+/// Parse an SSDP response datagram. Header names are case-insensitive.
+pub fn parse_response(datagram: &str) -> Option<SsdpResponse> {
+    let mut lines = datagram.lines();
+    if !lines
+        .next()?
+        .to_ascii_uppercase()
+        .starts_with("HTTP/1.1 200")
+    {
+        return None;
+    }
+    let mut location = None;
+    let mut usn = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("location") {
+            location = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("usn") {
+            usn = Some(value.to_string());
+        }
+    }
+    let location = location.filter(|l| l.starts_with("http://"))?;
+    Some(SsdpResponse {
+        usn: usn.unwrap_or_else(|| location.clone()),
+        location,
+    })
+}
+
+/// Multicast an M-SEARCH and collect answers until `timeout` elapses.
+/// Blocking; devices answering more than once are reported once.
+pub fn search(timeout: Duration) -> std::io::Result<Vec<SsdpResponse>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_multicast_ttl_v4(2)?;
+    let mx = timeout.as_secs().clamp(1, 5);
+    let request = m_search(mx);
+    let target = SocketAddr::from(SSDP_ADDR);
+    // UDP is lossy; a second probe picks up devices that missed the first.
+    socket.send_to(request.as_bytes(), target)?;
+    socket.send_to(request.as_bytes(), target)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut seen = HashSet::new();
+    let mut responses = Vec::new();
+    let mut buf = [0u8; 2048];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let len = match socket.recv_from(&mut buf) {
+            Ok((len, _)) => len,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                break
+            }
+            Err(e) => return Err(e),
+        };
+        let Some(response) = parse_response(&String::from_utf8_lossy(&buf[..len])) else {
+            continue;
+        };
+        if seen.insert(response.device_id().to_string()) {
+            responses.push(response);
+        }
+    }
+    Ok(responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_renderer_response() {
+        let datagram = "HTTP/1.1 200 OK\r\n\
+            CACHE-CONTROL: max-age=1800\r\n\
+            Location: http://192.168.1.20:49152/description.xml\r\n\
+            ST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\
+            USN: uuid:5f9ec1b3-ed59-1900-4530-00a0dea1b2c3::urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n";
+        let response = parse_response(datagram).unwrap();
+        assert_eq!(
+            response.location,
+            "http://192.168.1.20:49152/description.xml"
+        );
+        assert_eq!(response.device_id(), "5f9ec1b3-ed59-1900-4530-00a0dea1b2c3");
+    }
+
+    #[test]
+    fn rejects_notifications_and_missing_location() {
+        assert!(parse_response("NOTIFY * HTTP/1.1\r\nLOCATION: http://x/\r\n").is_none());
+        assert!(parse_response("HTTP/1.1 200 OK\r\nUSN: uuid:abc\r\n").is_none());
+    }
+}
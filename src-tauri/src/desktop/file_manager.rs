@@ -0,0 +1,265 @@
+//! "Show in folder" for local, downloaded and exported files.
+//!
+//! The webview may only reveal paths under folders the user already gave the
+//! app (library folders, download dir, export dir), the user's home or the
+//! app data dir; anything else is rejected so these commands cannot be used
+//! to launch arbitrary paths. Files are only ever written below the first
+//! group, see [`resolve_output_path`], and only read from below the roots
+//! or the app cache dir, see [`resolve_readable_path`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "macos"))]
+use std::process::Command;
+
+use log::warn;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::library::LibraryState;
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum FileManagerError {
+    /// The path does not exist (deleted or on an unplugged drive).
+    NotFound(String),
+    /// The path is outside every allowed root.
+    NotAllowed(String),
+    /// The platform file manager could not be launched.
+    Failed(String),
+}
+
+impl fmt::Display for FileManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "File not found: {}", path),
+            Self::NotAllowed(path) => write!(f, "Path is not inside an allowed folder: {}", path),
+            Self::Failed(message) => write!(f, "Failed to open file manager: {}", message),
+        }
+    }
+}
+
+/// Folders the frontend may reveal files from.
+fn allowed_roots(app: &AppHandle) -> Vec<PathBuf> {
+    let mut roots = app
+        .try_state::<LibraryState>()
+        .map(|state| state.folders())
+        .unwrap_or_default();
+    if let Some(store) = app.try_state::<SettingsStore>() {
+        let (download, export) =
+            store.read(|s| (s.paths.download_dir.clone(), s.paths.export_dir.clone()));
+        match download {
+            Some(dir) => roots.push(dir),
+            None => roots.extend(app.path().download_dir().ok()),
+        }
+        roots.extend(export);
+    }
+    roots
+}
+
+/// Folders the frontend may reveal or open: the output roots plus the
+/// user's home and the app data dir.
+fn reveal_roots(app: &AppHandle) -> Vec<PathBuf> {
+    let mut roots = allowed_roots(app);
+    roots.extend(app.path().home_dir().ok());
+    roots.extend(app.path().app_data_dir().ok());
+    roots
+}
+
+/// Canonicalize `path` and check it sits under one of `roots`.
+fn resolve_allowed(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, FileManagerError> {
+    let display = path.display().to_string();
+    let canonical =
+        dunce::canonicalize(path).map_err(|_| FileManagerError::NotFound(display.clone()))?;
+    let allowed = roots
+        .iter()
+        .filter_map(|root| dunce::canonicalize(root).ok())
+        .any(|root| canonical.starts_with(root));
+    if allowed {
+        Ok(canonical)
+    } else {
+        Err(FileManagerError::NotAllowed(display))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(path: &Path) -> Result<(), FileManagerError> {
+    use std::os::windows::process::CommandExt;
+    // explorer parses its own command line, so pass `/select,"path"` verbatim.
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| FileManagerError::Failed(e.to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn open_dir(dir: &Path) -> Result<(), FileManagerError> {
+    Command::new("explorer")
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| FileManagerError::Failed(e.to_string()))
+}
+
+/// Finder through `NSWorkspace`, which needs no helper process.
+#[cfg(target_os = "macos")]
+mod workspace {
+    use std::path::Path;
+
+    use objc2::rc::autoreleasepool;
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::{NSArray, NSString, NSURL};
+
+    use super::FileManagerError;
+
+    /// Call `f` with the shared workspace and an `NSURL` for `path`.
+    fn with_url(
+        path: &Path,
+        f: impl FnOnce(&NSWorkspace, &NSURL) -> bool,
+    ) -> Result<(), FileManagerError> {
+        // Callers are not on a thread that drains an autorelease pool.
+        let ok = autoreleasepool(|_| {
+            let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+            f(&NSWorkspace::sharedWorkspace(), &url)
+        });
+        if ok {
+            Ok(())
+        } else {
+            Err(FileManagerError::Failed(format!(
+                "Finder refused '{}'",
+                path.display()
+            )))
+        }
+    }
+
+    pub(super) fn reveal(path: &Path) -> Result<(), FileManagerError> {
+        with_url(path, |workspace, url| {
+            workspace.activateFileViewerSelectingURLs(&NSArray::from_slice(&[url]));
+            true
+        })
+    }
+
+    pub(super) fn open_dir(dir: &Path) -> Result<(), FileManagerError> {
+        with_url(dir, |workspace, url| workspace.openURL(url))
+    }
+}
+
+#[cfg(target_os = "macos")]
+use workspace::{open_dir, reveal};
+
+#[cfg(target_os = "linux")]
+fn reveal(path: &Path) -> Result<(), FileManagerError> {
+    match show_items_over_dbus(path) {
+        Ok(()) => return Ok(()),
+        Err(e) => warn!(
+            "FileManager1.ShowItems failed, falling back to xdg-open: {}",
+            e
+        ),
+    }
+    open_dir(path.parent().unwrap_or(path))
+}
+
+#[cfg(target_os = "linux")]
+fn open_dir(dir: &Path) -> Result<(), FileManagerError> {
+    Command::new("xdg-open")
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| FileManagerError::Failed(e.to_string()))
+}
+
+/// `org.freedesktop.FileManager1.ShowItems`, which selects the file in the
+/// user's file manager (Nautilus, Dolphin, Nemo, ...).
+#[cfg(target_os = "linux")]
+fn show_items_over_dbus(path: &Path) -> Result<(), String> {
+    let uri = tauri::Url::from_file_path(path)
+        .map_err(|_| format!("Cannot build a file URI for '{}'", path.display()))?;
+    let session = zbus::blocking::Connection::session().map_err(|e| e.to_string())?;
+    session
+        .call_method(
+            Some("org.freedesktop.FileManager1"),
+            "/org/freedesktop/FileManager1",
+            Some("org.freedesktop.FileManager1"),
+            "ShowItems",
+            &(vec![uri.as_str()], ""),
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Validate an output file path: its parent folder must be an allowed root
+/// (or below one). The file itself does not have to exist yet.
+pub(crate) fn resolve_output_path(
+    app: &AppHandle,
+    path: &Path,
+) -> Result<PathBuf, FileManagerError> {
+    let display = path.display().to_string();
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(FileManagerError::NotAllowed(display));
+    };
+    Ok(resolve_allowed(parent, &allowed_roots(app))?.join(name))
+}
+
+/// Validate a file the backend is asked to read, such as a local cover.
+pub(crate) fn resolve_readable_path(
+    app: &AppHandle,
+    path: &Path,
+) -> Result<PathBuf, FileManagerError> {
+    let mut roots = reveal_roots(app);
+    roots.extend(app.path().app_cache_dir().ok());
+    resolve_allowed(path, &roots)
+}
+
+/// Select `path` in the platform file manager.
+#[tauri::command]
+pub fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), FileManagerError> {
+    let path = resolve_allowed(Path::new(&path), &reveal_roots(&app))?;
+    reveal(&path)
+}
+
+/// Open a folder in the platform file manager. For a file, the folder
+/// holding it is opened.
+#[tauri::command]
+pub fn open_path(app: AppHandle, path: String) -> Result<(), FileManagerError> {
+    let path = resolve_allowed(Path::new(&path), &reveal_roots(&app))?;
+    let dir = if path.is_dir() {
+        path.as_path()
+    } else {
+        path.parent().unwrap_or(&path)
+    };
+    open_dir(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_existing_paths_under_roots_are_allowed() {
+        let base = tempfile::tempdir().unwrap();
+        let root = base.path().join("music");
+        let outside = base.path().join("other");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(root.join("a.flac"), b"").unwrap();
+        std::fs::write(outside.join("b.flac"), b"").unwrap();
+        let roots = vec![root.clone()];
+
+        assert!(resolve_allowed(&root.join("a.flac"), &roots).is_ok());
+        assert!(matches!(
+            resolve_allowed(&root.join("missing.flac"), &roots),
+            Err(FileManagerError::NotFound(_))
+        ));
+        assert!(matches!(
+            resolve_allowed(&outside.join("b.flac"), &roots),
+            Err(FileManagerError::NotAllowed(_))
+        ));
+        // `..` cannot climb out of an allowed root.
+        assert!(matches!(
+            resolve_allowed(&root.join("../other/b.flac"), &roots),
+            Err(FileManagerError::NotAllowed(_))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+//! Native open and save dialogs that hand back real paths.
+//!
+//! `<input type="file">` yields blob URLs and cannot pick folders
+//! everywhere, while the library scanner and the export features need
+//! absolute paths. Each picker reopens in the directory it was last used
+//! in; those directories are kept in `file-pickers.json`.
+//!
+//! On Linux the dialog plugin is built against the XDG desktop portal
+//! rather than GTK, so the dialogs also work from inside a sandbox.
+
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+use tokio::sync::oneshot;
+
+use crate::library::AUDIO_EXTENSIONS;
+use crate::settings::write_atomic;
+
+const LAST_DIRS_FILE: &str = "file-pickers.json";
+
+#[derive(Debug, Clone, Copy)]
+enum Picker {
+    MusicFiles,
+    MusicFolder,
+    Save,
+}
+
+/// Last directory of each picker.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct LastDirs {
+    music_files: Option<PathBuf>,
+    music_folder: Option<PathBuf>,
+    save: Option<PathBuf>,
+}
+
+impl LastDirs {
+    fn slot(&mut self, picker: Picker) -> &mut Option<PathBuf> {
+        match picker {
+            Picker::MusicFiles => &mut self.music_files,
+            Picker::MusicFolder => &mut self.music_folder,
+            Picker::Save => &mut self.save,
+        }
+    }
+}
+
+/// Loaded from disk on first use.
+static LAST_DIRS: Mutex<Option<LastDirs>> = Mutex::new(None);
+
+fn last_dirs_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(LAST_DIRS_FILE))
+}
+
+fn with_last_dirs<T>(app: &AppHandle, f: impl FnOnce(&mut LastDirs) -> T) -> T {
+    let mut last = LAST_DIRS.lock();
+    let dirs = last.get_or_insert_with(|| {
+        last_dirs_path(app)
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    });
+    f(dirs)
+}
+
+/// Where `picker` should open: its last directory if that still exists.
+fn start_dir(app: &AppHandle, picker: Picker) -> Option<PathBuf> {
+    with_last_dirs(app, |dirs| dirs.slot(picker).clone()).filter(|dir| dir.is_dir())
+}
+
+fn remember(app: &AppHandle, picker: Picker, dir: &Path) {
+    let dirs = with_last_dirs(app, |dirs| {
+        *dirs.slot(picker) = Some(dir.to_path_buf());
+        dirs.clone()
+    });
+    let Some(path) = last_dirs_path(app) else {
+        return;
+    };
+    let result = serde_json::to_vec_pretty(&dirs)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| write_atomic(&path, &bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to save {}: {}", path.display(), e);
+    }
+}
+
+/// Requested extensions without dots, lowercase and deduplicated; the
+/// known audio extensions when none are given.
+fn audio_filter(extensions: Option<Vec<String>>) -> Vec<String> {
+    let mut filter: Vec<String> = Vec::new();
+    for ext in extensions.into_iter().flatten() {
+        let ext = ext.trim().trim_start_matches('.').to_ascii_lowercase();
+        if !ext.is_empty() && !filter.contains(&ext) {
+            filter.push(ext);
+        }
+    }
+    if filter.is_empty() {
+        filter = AUDIO_EXTENSIONS.iter().map(|ext| ext.to_string()).collect();
+    }
+    filter
+}
+
+/// `name` ending in `.extension`, appended unless it is already there.
+fn with_extension(name: &str, extension: &str) -> String {
+    let extension = extension.trim().trim_start_matches('.');
+    let name = name.trim();
+    let name = if name.is_empty() { "untitled" } else { name };
+    let has_it = Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+    if extension.is_empty() || has_it {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, extension)
+    }
+}
+
+fn builder(app: &AppHandle, picker: Picker) -> FileDialogBuilder<tauri::Wry> {
+    let builder = app.dialog().file();
+    match start_dir(app, picker) {
+        Some(dir) => builder.set_directory(dir),
+        None => builder,
+    }
+}
+
+fn into_path(path: FilePath) -> Result<PathBuf, String> {
+    path.into_path().map_err(|e| e.to_string())
+}
+
+/// Pick one or more audio files. `extensions` narrows the filter (without
+/// dots); by default every format the library reads is offered. Resolves
+/// to `null` when the dialog is dismissed.
+#[tauri::command]
+pub async fn pick_music_files(
+    app: AppHandle,
+    multiple: bool,
+    extensions: Option<Vec<String>>,
+) -> Result<Option<Vec<PathBuf>>, String> {
+    let filter = audio_filter(extensions);
+    let filter: Vec<&str> = filter.iter().map(String::as_str).collect();
+    let builder = builder(&app, Picker::MusicFiles).add_filter("Audio", &filter);
+    let (tx, rx) = oneshot::channel();
+    if multiple {
+        builder.pick_files(move |paths| {
+            let _ = tx.send(paths);
+        });
+    } else {
+        builder.pick_file(move |path| {
+            let _ = tx.send(path.map(|path| vec![path]));
+        });
+    }
+    let Some(picked) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    let paths = picked
+        .into_iter()
+        .map(into_path)
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(dir) = paths.first().and_then(|path| path.parent()) {
+        remember(&app, Picker::MusicFiles, dir);
+    }
+    Ok(Some(paths))
+}
+
+/// Pick a folder to add to the library. Resolves to `null` when dismissed.
+#[tauri::command]
+pub async fn pick_music_folder(app: AppHandle) -> Result<Option<PathBuf>, String> {
+    let (tx, rx) = oneshot::channel();
+    builder(&app, Picker::MusicFolder).pick_folder(move |path| {
+        let _ = tx.send(path);
+    });
+    let Some(picked) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    let folder = into_path(picked)?;
+    remember(&app, Picker::MusicFolder, &folder);
+    Ok(Some(folder))
+}
+
+/// Ask where to save a file, suggesting `default_name` and filtering on
+/// `extension`. The dialog asks before replacing an existing file itself.
+/// Resolves to `null` when dismissed.
+#[tauri::command]
+pub async fn pick_save_path(
+    app: AppHandle,
+    default_name: String,
+    extension: String,
+) -> Result<Option<PathBuf>, String> {
+    let name = with_extension(&default_name, &extension);
+    let extension = extension.trim().trim_start_matches('.').to_string();
+    let mut builder = builder(&app, Picker::Save).set_file_name(name);
+    if !extension.is_empty() {
+        builder = builder.add_filter(extension.to_uppercase(), &[extension.as_str()]);
+    }
+    let (tx, rx) = oneshot::channel();
+    builder.save_file(move |path| {
+        let _ = tx.send(path);
+    });
+    let Some(picked) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    let path = into_path(picked)?;
+    if let Some(dir) = path.parent() {
+        remember(&app, Picker::Save, dir);
+    }
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_default_to_audio_and_are_normalized() {
+        assert_eq!(audio_filter(None).len(), AUDIO_EXTENSIONS.len());
+        assert_eq!(
+            audio_filter(Some(vec![" ".into()])).len(),
+            AUDIO_EXTENSIONS.len()
+        );
+        assert_eq!(
+            audio_filter(Some(vec![".FLAC".into(), "flac".into(), "mp3".into()])),
+            ["flac", "mp3"]
+        );
+    }
+
+    #[test]
+    fn save_names_get_the_extension_once() {
+        assert_eq!(with_extension("Song", "lrc"), "Song.lrc");
+        assert_eq!(with_extension("Song.LRC", ".lrc"), "Song.LRC");
+        assert_eq!(with_extension("A. B", "m3u8"), "A. B.m3u8");
+        assert_eq!(with_extension("", "m3u8"), "untitled.m3u8");
+        assert_eq!(with_extension("notes", ""), "notes");
+    }
+}
@@ -0,0 +1,278 @@
+//! Strings the backend shows before (or without) the frontend: default window
+//! titles, the tray tooltip, the native tray menu and the close dialog of
+//! auxiliary windows.
+//!
+//! The locale starts from the OS and follows the app language once the
+//! frontend calls [`set_backend_locale`]. Lookups go through [`Text`], so a
+//! missing translation is a compile error rather than a runtime fallback.
+
+use std::sync::LazyLock;
+
+use log::info;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::desktop::window::tray;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    ZhCn,
+    ZhTw,
+    Ja,
+}
+
+impl Locale {
+    /// Parse a BCP 47 tag or POSIX locale (`zh_TW.UTF-8`). Unknown → `En`.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        let mut parts = tag.split('-');
+        match parts.next() {
+            Some("zh") => {
+                if parts.any(|part| matches!(part, "tw" | "hk" | "mo" | "hant")) {
+                    Locale::ZhTw
+                } else {
+                    Locale::ZhCn
+                }
+            }
+            Some("ja") => Locale::Ja,
+            _ => Locale::En,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+            Locale::ZhTw => "zh-TW",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    MainTitle,
+    MiniPlayerTitle,
+    DesktopLyricsTitle,
+    DesktopLyricsControlsTitle,
+    TaskbarLyricTitle,
+    SettingsTitle,
+    AboutTitle,
+    TrayPopupTitle,
+    OsdTitle,
+    TrayTooltip,
+    /// Prefix of the tooltip's next-track line, separator included.
+    TrayNextUp,
+    /// Follows the tooltip's progress while paused.
+    TrayPaused,
+    /// Disabled "Now Playing" item of the native menu when idle.
+    TrayMenuNotPlaying,
+    TrayMenuPlayPause,
+    TrayMenuPrevious,
+    TrayMenuNext,
+    /// Thumbnail toolbar tooltips; they name what a click does.
+    ThumbbarPlay,
+    ThumbbarPause,
+    TrayMenuShowLyrics,
+    TrayMenuShowMain,
+    TrayMenuQuit,
+    TrayMenuForceQuit,
+    /// Native close dialog of windows other than main.
+    CloseAskMessage,
+    CloseAskHide,
+    CloseAskClose,
+}
+
+impl Text {
+    /// Default title of a preset window, by label.
+    fn window_title(label: &str) -> Option<Self> {
+        Some(match label {
+            "main" => Text::MainTitle,
+            "mini-player" => Text::MiniPlayerTitle,
+            "desktop-lyrics" => Text::DesktopLyricsTitle,
+            "desktop-lyrics-controls" => Text::DesktopLyricsControlsTitle,
+            "taskbar-lyric" => Text::TaskbarLyricTitle,
+            "settings" => Text::SettingsTitle,
+            "about" => Text::AboutTitle,
+            "tray-popup" => Text::TrayPopupTitle,
+            "osd" => Text::OsdTitle,
+            _ => return None,
+        })
+    }
+
+    /// `[en, zh-CN, zh-TW, ja]`
+    fn strings(self) -> [&'static str; 4] {
+        match self {
+            Text::MainTitle | Text::TrayTooltip => ["GMPlayer"; 4],
+            Text::MiniPlayerTitle => ["Mini Player", "迷你播放器", "迷你播放器", "ミニプレーヤー"],
+            Text::DesktopLyricsTitle => {
+                ["Desktop Lyrics", "桌面歌词", "桌面歌詞", "デスクトップ歌詞"]
+            }
+            Text::DesktopLyricsControlsTitle => [
+                "Desktop Lyrics Controls",
+                "桌面歌词控制",
+                "桌面歌詞控制",
+                "デスクトップ歌詞コントロール",
+            ],
+            Text::TaskbarLyricTitle => [
+                "Taskbar Lyric",
+                "任务栏歌词",
+                "工作列歌詞",
+                "タスクバー歌詞",
+            ],
+            Text::SettingsTitle => ["Settings", "设置", "設定", "設定"],
+            Text::AboutTitle => ["About", "关于", "關於", "このアプリについて"],
+            Text::TrayPopupTitle => ["Tray Popup", "托盘菜单", "系統匣選單", "トレイメニュー"],
+            Text::OsdTitle => [
+                "GMPlayer OSD",
+                "GMPlayer 屏显",
+                "GMPlayer 螢幕顯示",
+                "GMPlayer OSD",
+            ],
+            Text::TrayNextUp => ["Next: ", "下一首：", "下一首：", "次の曲："],
+            Text::TrayPaused => ["Paused", "已暂停", "已暫停", "一時停止中"],
+            Text::TrayMenuNotPlaying => ["Not Playing", "未在播放", "未在播放", "再生していません"],
+            Text::TrayMenuPlayPause => ["Play/Pause", "播放/暂停", "播放/暫停", "再生/一時停止"],
+            Text::TrayMenuPrevious => ["Previous", "上一首", "上一首", "前の曲"],
+            Text::TrayMenuNext => ["Next", "下一首", "下一首", "次の曲"],
+            Text::ThumbbarPlay => ["Play", "播放", "播放", "再生"],
+            Text::ThumbbarPause => ["Pause", "暂停", "暫停", "一時停止"],
+            Text::TrayMenuShowLyrics => [
+                "Show Desktop Lyrics",
+                "显示桌面歌词",
+                "顯示桌面歌詞",
+                "デスクトップ歌詞を表示",
+            ],
+            Text::TrayMenuShowMain => [
+                "Show Main Window",
+                "显示主窗口",
+                "顯示主視窗",
+                "メインウィンドウを表示",
+            ],
+            Text::TrayMenuQuit => ["Quit", "退出", "結束", "終了"],
+            Text::TrayMenuForceQuit => ["Force Quit", "强制退出", "強制結束", "強制終了"],
+            Text::CloseAskMessage => [
+                "Hide this window or close it?",
+                "隐藏此窗口还是关闭它？",
+                "隱藏此視窗還是關閉它？",
+                "このウィンドウを隠しますか、それとも閉じますか？",
+            ],
+            Text::CloseAskHide => ["Hide", "隐藏", "隱藏", "隠す"],
+            Text::CloseAskClose => ["Close", "关闭", "關閉", "閉じる"],
+        }
+    }
+
+    pub fn in_locale(self, locale: Locale) -> &'static str {
+        let strings = self.strings();
+        match locale {
+            Locale::En => strings[0],
+            Locale::ZhCn => strings[1],
+            Locale::ZhTw => strings[2],
+            Locale::Ja => strings[3],
+        }
+    }
+}
+
+static LOCALE: LazyLock<Mutex<Locale>> = LazyLock::new(|| Mutex::new(detect_system_locale()));
+
+pub fn current() -> Locale {
+    *LOCALE.lock()
+}
+
+/// Look up `key` in the current locale.
+pub fn tr(key: Text) -> &'static str {
+    key.in_locale(current())
+}
+
+fn detect_system_locale() -> Locale {
+    let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX");
+    if let Some(tag) = from_env {
+        return Locale::from_tag(&tag);
+    }
+
+    #[cfg(windows)]
+    {
+        use windows::Win32::Globalization::GetUserDefaultLocaleName;
+
+        let mut buffer = [0u16; 85];
+        // SAFETY: the slice carries its own length.
+        let len = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+        if len > 1 {
+            return Locale::from_tag(&String::from_utf16_lossy(&buffer[..len as usize - 1]));
+        }
+    }
+
+    Locale::En
+}
+
+/// Switch the backend locale and retitle existing windows. Titles the
+/// frontend has changed from the old default are left alone.
+fn apply(app: &AppHandle, locale: Locale) {
+    let previous = std::mem::replace(&mut *LOCALE.lock(), locale);
+    if previous == locale {
+        return;
+    }
+    info!("Backend locale: {}", locale.tag());
+    for (label, window) in app.webview_windows() {
+        let Some(key) = Text::window_title(&label) else {
+            continue;
+        };
+        let untouched = window
+            .title()
+            .is_ok_and(|title| title == key.in_locale(previous));
+        if untouched {
+            let _ = window.set_title(key.in_locale(locale));
+        }
+    }
+    tray::refresh_locale(app);
+    #[cfg(target_os = "windows")]
+    crate::desktop::window::platform::windows_taskbar::refresh_locale(app);
+}
+
+/// Set the backend locale from the app language (`"zh-CN"`, `"en"`, …).
+/// Returns the tag actually used; unsupported languages fall back to `en`.
+#[tauri::command]
+pub fn set_backend_locale(app: AppHandle, tag: String) -> &'static str {
+    let locale = Locale::from_tag(&tag);
+    apply(&app, locale);
+    locale.tag()
+}
+
+#[tauri::command]
+pub fn get_backend_locale() -> &'static str {
+    current().tag()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_map_to_supported_locales() {
+        assert_eq!(Locale::from_tag("zh-CN"), Locale::ZhCn);
+        assert_eq!(Locale::from_tag("zh_TW.UTF-8"), Locale::ZhTw);
+        assert_eq!(Locale::from_tag("zh-Hant-HK"), Locale::ZhTw);
+        assert_eq!(Locale::from_tag("zh"), Locale::ZhCn);
+        assert_eq!(Locale::from_tag("ja-JP"), Locale::Ja);
+        assert_eq!(Locale::from_tag("fr-FR"), Locale::En);
+        assert_eq!(Locale::from_tag(""), Locale::En);
+    }
+
+    #[test]
+    fn every_preset_label_has_a_title() {
+        for label in ["main", "mini-player", "settings", "osd", "tray-popup"] {
+            assert!(Text::window_title(label).is_some(), "{}", label);
+        }
+        assert_eq!(Text::SettingsTitle.in_locale(Locale::ZhCn), "设置");
+        assert_eq!(Text::window_title("custom"), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! How long since the user last touched the keyboard or mouse, anywhere on
+//! the system.
+//!
+//! `set_idle_watch(threshold_secs)` starts one background thread that emits
+//! `system-idle` when the idle time crosses the threshold and
+//! `system-active` when input comes back; a threshold of 0 stops it. The
+//! thread sleeps until the earliest moment the threshold could be reached
+//! (at most 30 s), and only polls every second while the user is away so
+//! their return is noticed quickly.
+//!
+//! Windows reads `GetLastInputInfo`, macOS
+//! `CGEventSourceSecondsSinceLastEventType`, X11 the XScreenSaver
+//! extension. Wayland has no portable way in; GNOME's Mutter idle monitor
+//! is asked over D-Bus and other compositors report an error.
+
+use std::time::Duration;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub const SYSTEM_IDLE_EVENT: &str = "system-idle";
+pub const SYSTEM_ACTIVE_EVENT: &str = "system-active";
+
+const MAX_POLL: Duration = Duration::from_secs(30);
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
+/// Payload of `system-idle` and `system-active`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleChanged {
+    pub idle_secs: u64,
+}
+
+struct Watch {
+    /// 0 when nobody is watching.
+    threshold: Duration,
+    running: bool,
+}
+
+static WATCH: Mutex<Watch> = Mutex::new(Watch {
+    threshold: Duration::ZERO,
+    running: false,
+});
+
+/// Global input idle time.
+pub fn idle_time() -> Result<Duration, String> {
+    imp::idle_time()
+}
+
+/// How long to sleep before the next check.
+fn next_poll(threshold: Duration, idle: Duration, is_idle: bool) -> Duration {
+    if is_idle {
+        IDLE_POLL
+    } else {
+        threshold.saturating_sub(idle).clamp(IDLE_POLL, MAX_POLL)
+    }
+}
+
+fn run_watch(app: AppHandle) {
+    let mut is_idle = false;
+    loop {
+        let threshold = {
+            let mut watch = WATCH.lock();
+            if watch.threshold.is_zero() {
+                watch.running = false;
+                return;
+            }
+            watch.threshold
+        };
+        let idle = match idle_time() {
+            Ok(idle) => idle,
+            Err(e) => {
+                warn!("Idle watch stopped: {}", e);
+                WATCH.lock().running = false;
+                return;
+            }
+        };
+        let now_idle = idle >= threshold;
+        if now_idle != is_idle {
+            is_idle = now_idle;
+            let event = if is_idle {
+                SYSTEM_IDLE_EVENT
+            } else {
+                SYSTEM_ACTIVE_EVENT
+            };
+            let _ = app.emit(
+                event,
+                IdleChanged {
+                    idle_secs: idle.as_secs(),
+                },
+            );
+        }
+        std::thread::sleep(next_poll(threshold, idle, is_idle));
+    }
+}
+
+#[tauri::command]
+pub fn get_system_idle_seconds() -> Result<u64, String> {
+    idle_time().map(|idle| idle.as_secs())
+}
+
+/// Watch for the user going idle for `threshold_secs`; 0 stops watching.
+/// Fails when idle time cannot be read on this system.
+#[tauri::command]
+pub fn set_idle_watch(app: AppHandle, threshold_secs: u64) -> Result<(), String> {
+    if threshold_secs > 0 {
+        idle_time()?;
+    }
+    let mut watch = WATCH.lock();
+    watch.threshold = Duration::from_secs(threshold_secs);
+    if threshold_secs == 0 || watch.running {
+        return Ok(());
+    }
+    std::thread::Builder::new()
+        .name("idle-watch".into())
+        .spawn(move || run_watch(app))
+        .map_err(|e| format!("Failed to spawn idle watcher: {}", e))?;
+    watch.running = true;
+    info!("Watching for {} s of idle time", threshold_secs);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::time::Duration;
+
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub(super) fn idle_time() -> Result<Duration, String> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: `info` is a live LASTINPUTINFO with its size filled in.
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return Err("GetLastInputInfo failed".into());
+        }
+        // Both tick counts wrap after 49.7 days.
+        let now = unsafe { GetTickCount() };
+        Ok(Duration::from_millis(now.wrapping_sub(info.dwTime).into()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::time::Duration;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    const HID_SYSTEM_STATE: i32 = 1;
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    pub(super) fn idle_time() -> Result<Duration, String> {
+        // SAFETY: a plain query with no pointers.
+        let secs =
+            unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) };
+        Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::time::Duration;
+
+    use parking_lot::Mutex;
+    use x11_dl::xlib::{Display, Xlib};
+    use x11_dl::xss::Xss;
+
+    struct X11Connection {
+        xlib: Xlib,
+        xss: Xss,
+        display: *mut Display,
+    }
+
+    // SAFETY: the connection is only used while holding `X11`'s lock.
+    unsafe impl Send for X11Connection {}
+
+    static X11: Mutex<Option<X11Connection>> = Mutex::new(None);
+
+    fn is_wayland_session() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+    }
+
+    fn x11_idle_time() -> Result<Duration, String> {
+        let mut connection = X11.lock();
+        if connection.is_none() {
+            let xlib = Xlib::open().map_err(|e| format!("libX11 is unavailable: {}", e))?;
+            let xss = Xss::open().map_err(|e| format!("libXss is unavailable: {}", e))?;
+            // SAFETY: a null name opens the display named by $DISPLAY.
+            let display = unsafe { (xlib.XOpenDisplay)(std::ptr::null()) };
+            if display.is_null() {
+                return Err("cannot open the X display".into());
+            }
+            *connection = Some(X11Connection { xlib, xss, display });
+        }
+        let X11Connection { xlib, xss, display } = connection.as_ref().expect("opened above");
+        // SAFETY: `display` is a live connection; the info struct comes
+        // from Xss and is freed with XFree.
+        unsafe {
+            let info = (xss.XScreenSaverAllocInfo)();
+            if info.is_null() {
+                return Err("XScreenSaverAllocInfo failed".into());
+            }
+            let root = (xlib.XDefaultRootWindow)(*display);
+            let status = (xss.XScreenSaverQueryInfo)(*display, root, info);
+            // c_ulong is only u64 on 64-bit targets.
+            #[allow(clippy::unnecessary_cast)]
+            let idle_ms = (*info).idle as u64;
+            (xlib.XFree)(info.cast());
+            if status == 0 {
+                return Err("the X server has no screen saver extension".into());
+            }
+            Ok(Duration::from_millis(idle_ms))
+        }
+    }
+
+    /// GNOME on Wayland; other compositors only offer ext-idle-notify to
+    /// Wayland clients, which a webview app is not.
+    fn mutter_idle_time() -> Result<Duration, String> {
+        let millis: u64 = zbus::blocking::Connection::session()
+            .and_then(|session| {
+                session.call_method(
+                    Some("org.gnome.Mutter.IdleMonitor"),
+                    "/org/gnome/Mutter/IdleMonitor/Core",
+                    Some("org.gnome.Mutter.IdleMonitor"),
+                    "GetIdletime",
+                    &(),
+                )
+            })
+            .and_then(|reply| reply.body().deserialize())
+            .map_err(|e| format!("idle time is unavailable on this Wayland session: {}", e))?;
+        Ok(Duration::from_millis(millis))
+    }
+
+    pub(super) fn idle_time() -> Result<Duration, String> {
+        if is_wayland_session() {
+            mutter_idle_time()
+        } else {
+            x11_idle_time()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use std::time::Duration;
+
+    pub(super) fn idle_time() -> Result<Duration, String> {
+        Err("Idle time is not supported on this platform".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polls_sleep_until_the_threshold_could_be_reached() {
+        let two_hours = Duration::from_secs(7200);
+        assert_eq!(next_poll(two_hours, Duration::ZERO, false), MAX_POLL);
+        assert_eq!(
+            next_poll(two_hours, two_hours - Duration::from_secs(12), false),
+            Duration::from_secs(12)
+        );
+        assert_eq!(
+            next_poll(Duration::from_secs(60), Duration::from_secs(60), false),
+            IDLE_POLL
+        );
+        assert_eq!(next_poll(two_hours, two_hours * 2, true), IDLE_POLL);
+    }
+}
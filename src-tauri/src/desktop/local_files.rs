@@ -0,0 +1,186 @@
+//! Audio files opened from outside the app: command-line arguments (file
+//! associations pass the file that way, also to a second launch), files
+//! dropped on any window, and macOS "Open With".
+//!
+//! NetEase `.ncm` files are passed through as they are; the frontend
+//! decrypts them with `decrypt_ncm` before queueing them.
+//!
+//! Tags are read on a background thread and the files reach the frontend
+//! in one `files-opened` batch for it to enqueue. Until the frontend first
+//! calls `take_opened_files` it may not be listening yet, so batches are
+//! held back and handed over by that call instead.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::algorithms::ncm;
+use crate::desktop::second_instance;
+use crate::library;
+
+pub const FILES_OPENED_EVENT: &str = "files-opened";
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedFile {
+    pub path: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Payload of `files-opened`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesOpened {
+    pub files: Vec<OpenedFile>,
+    /// Dropped or passed paths that were not audio files.
+    pub ignored: usize,
+}
+
+/// Batches waiting for the frontend's first `take_opened_files`.
+static HELD: Mutex<FilesOpened> = Mutex::new(FilesOpened {
+    files: Vec::new(),
+    ignored: 0,
+});
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+/// Audio files in `paths`, with directories walked one level deep, and how
+/// many entries were skipped as not audio. Subdirectories of a dropped
+/// directory are neither opened nor counted.
+fn collect(paths: &[PathBuf]) -> (Vec<PathBuf>, usize) {
+    let mut files = Vec::new();
+    let mut ignored = 0;
+    let mut take = |path: PathBuf| {
+        if library::is_audio_file(&path) || ncm::is_ncm_file(&path) {
+            files.push(path);
+        } else {
+            ignored += 1;
+        }
+    };
+    for path in paths {
+        if !path.is_dir() {
+            take(path.clone());
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(path) else {
+            continue;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+            .map(|entry| entry.path())
+            .collect();
+        children.sort();
+        children.into_iter().for_each(&mut take);
+    }
+    (files, ignored)
+}
+
+fn deliver(app: &AppHandle, batch: FilesOpened) {
+    let mut held = HELD.lock();
+    if FRONTEND_READY.load(Ordering::Acquire) {
+        drop(held);
+        let _ = app.emit_to("main", FILES_OPENED_EVENT, batch);
+    } else {
+        held.files.extend(batch.files);
+        held.ignored += batch.ignored;
+    }
+}
+
+/// Read the tags of the audio files among `paths` and send them to the
+/// frontend. Returns at once; the work happens on a background thread.
+pub fn open(app: &AppHandle, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("local-files".into())
+        .spawn(move || {
+            let (files, ignored) = collect(&paths);
+            info!("Opening {} file(s), ignoring {}", files.len(), ignored);
+            let files = files
+                .into_iter()
+                .map(|path| {
+                    let tags = if ncm::is_ncm_file(&path) {
+                        library::TrackTags::default()
+                    } else {
+                        library::read_tags(&path)
+                    };
+                    OpenedFile {
+                        path: path.to_string_lossy().into_owned(),
+                        title: tags.title,
+                        artist: tags.artist,
+                        duration_ms: tags.duration_ms,
+                    }
+                })
+                .collect();
+            deliver(&app, FilesOpened { files, ignored });
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn local file reader: {}", e);
+    }
+}
+
+/// Existing paths among command-line `args`, relative ones taken from
+/// `cwd`. Flags, their values and `gmplayer://` links are not paths.
+fn paths_in_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .filter(|arg| !arg.starts_with('-') && !second_instance::is_app_link(arg))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Open the files among a launch's arguments (after the executable path).
+pub fn open_args(app: &AppHandle, args: &[String], cwd: &Path) {
+    open(app, paths_in_args(args, cwd));
+}
+
+/// Files opened so far that the frontend has not seen. From the first call
+/// on, later batches come as `files-opened` events instead.
+#[tauri::command]
+pub fn take_opened_files() -> Option<FilesOpened> {
+    let mut held = HELD.lock();
+    FRONTEND_READY.store(true, Ordering::Release);
+    let batch = std::mem::take(&mut *held);
+    (!batch.files.is_empty() || batch.ignored > 0).then_some(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_are_walked_one_level_and_other_files_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let album = dir.join("album");
+        std::fs::create_dir_all(album.join("scans")).unwrap();
+        for name in ["b.flac", "a.MP3", "c.ncm", "cover.jpg"] {
+            std::fs::write(album.join(name), b"").unwrap();
+        }
+        std::fs::write(album.join("scans").join("c.flac"), b"").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"").unwrap();
+
+        let (files, ignored) = collect(&[album.clone(), dir.join("notes.txt")]);
+        assert_eq!(
+            files,
+            vec![
+                album.join("a.MP3"),
+                album.join("b.flac"),
+                album.join("c.ncm")
+            ]
+        );
+        assert_eq!(ignored, 2);
+
+        let args = ["--window", "mini-player", "album", "gmplayer://song/1"].map(String::from);
+        assert_eq!(paths_in_args(&args, dir), vec![album.clone()]);
+    }
+}
@@ -0,0 +1,68 @@
+//! The system media actions the backend answers itself.
+//!
+//! MPRIS on Linux is served by the now-playing-controls plugin, which
+//! passes every action on as `now-playing-controls:media-action` for the
+//! frontend to play, pause or seek. `raise` and `quit` are about the app
+//! rather than playback, and a hidden or frozen webview could not answer
+//! them, so they are handled here: `raise` shows the main window and `quit`
+//! quits the same way `quit_app` does.
+
+use gmplayer_now_playing_controls::MEDIA_ACTION_EVENT;
+use log::{info, warn};
+use serde::Deserialize;
+use tauri::{AppHandle, Listener};
+
+use crate::desktop::window::manager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppAction {
+    Raise,
+    Quit,
+}
+
+#[derive(Deserialize)]
+struct MediaAction {
+    action: String,
+}
+
+/// The app-level action in a media action payload, if it is one.
+fn app_action(payload: &str) -> Option<AppAction> {
+    let MediaAction { action } = serde_json::from_str(payload).ok()?;
+    match action.as_str() {
+        "raise" => Some(AppAction::Raise),
+        "quit" => Some(AppAction::Quit),
+        _ => None,
+    }
+}
+
+/// Start answering `raise` and `quit`. Call once during setup.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen(MEDIA_ACTION_EVENT, move |event| {
+        match app_action(event.payload()) {
+            Some(AppAction::Raise) => {
+                if let Err(e) = manager::show_window(&handle, "main") {
+                    warn!("Failed to raise the main window for media controls: {}", e);
+                }
+            }
+            Some(AppAction::Quit) => {
+                info!("Quit requested by the system media controls");
+                crate::shutdown::quit(&handle, false);
+            }
+            None => {}
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_raise_and_quit_are_handled_by_the_backend() {
+        assert_eq!(app_action(r#"{"action":"raise"}"#), Some(AppAction::Raise));
+        assert_eq!(app_action(r#"{"action":"quit"}"#), Some(AppAction::Quit));
+        assert_eq!(app_action(r#"{"action":"seek","position":1200}"#), None);
+        assert_eq!(app_action("null"), None);
+    }
+}
@@ -1,7 +1,22 @@
 //! Desktop (Windows / macOS / Linux) backend: multi-window management, tray, desktop lyrics.
 
+pub mod autostart;
+pub mod bindings;
+pub mod cast;
+pub mod file_manager;
+pub mod file_picker;
+pub mod i18n;
+pub mod idle;
 #[cfg(target_os = "linux")]
 mod linux_graphics;
+pub mod local_files;
+mod media_controls;
+pub mod notifications;
+pub mod power;
+pub mod safe_mode;
+pub mod second_instance;
+pub mod startup;
+pub mod system_volume;
 pub mod window;
 
 use crate::desktop::window::config::WindowConfig;
@@ -9,32 +24,48 @@ use crate::desktop::window::config::WindowConfig;
 use crate::desktop::window::config::DEFAULT_ADDITIONAL_WINDOW_ARGS;
 use crate::desktop::window::desktop_lyrics::mouse_through::{HitRegionRegistry, MouseThroughState};
 use crate::desktop::window::manager as wm;
+use crate::desktop::window::payload::PayloadCache;
+use crate::desktop::window::pin::WindowPinState;
+use crate::library::{self, LibraryState};
+use crate::lyrics::session::LyricSessionState;
+use crate::settings::{self, SettingsStore};
 use crate::shared;
+use crate::tasks::Scheduler;
 use gmplayer_audio_backend::commands;
 use log::warn;
-use tauri::{Emitter, Manager, RunEvent, WindowEvent};
+use tauri::{DragDropEvent, Emitter, Manager, RunEvent, WindowEvent};
 #[cfg(target_os = "macos")]
 use tauri_plugin_decorum::WebviewWindowExt;
 use tauri_plugin_window_state::{AppHandleExt, StateFlags, WindowExt};
 
 /// State flags for window-state plugin — excludes VISIBLE so a previous
 /// hide-to-tray state is not restored as a hidden main window on launch.
-const WINDOW_STATE_FLAGS: StateFlags = StateFlags::SIZE
+pub(crate) const WINDOW_STATE_FLAGS: StateFlags = StateFlags::SIZE
     .union(StateFlags::POSITION)
     .union(StateFlags::MAXIMIZED)
     .union(StateFlags::FULLSCREEN)
     .union(StateFlags::DECORATIONS);
 
 pub fn run() {
+    if bindings::dump_from_args(std::env::args().skip(1)) {
+        return;
+    }
+
     #[cfg(target_os = "linux")]
     linux_graphics::configure_webkit_gtk_backend();
 
+    build_app()
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(handle_run_event);
+}
+
+/// The app's builder with every plugin, managed state, command and the
+/// setup hook registered, ready for a context.
+pub fn build_app() -> tauri::Builder<tauri::Wry> {
     let builder = tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _, _| {
-            let _ = app
-                .get_webview_window("main")
-                .expect("no main window")
-                .set_focus();
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            second_instance::handle(app, argv, cwd);
         }))
         .plugin(
             tauri_plugin_log::Builder::new()
@@ -46,7 +77,10 @@ pub fn run() {
                 .level_for("symphonia_core", tauri_plugin_log::log::LevelFilter::Info)
                 .build(),
         )
+        .plugin(crate::first_run::init())
+        .plugin(safe_mode::init())
         .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_decorum::init())
         .plugin(gmplayer_now_playing_controls::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -54,6 +88,7 @@ pub fn run() {
             tauri_plugin_window_state::Builder::new()
                 .with_state_flags(WINDOW_STATE_FLAGS)
                 .skip_initial_state("main")
+                .with_denylist(&[window::desktop_lyrics::bounds::LABEL])
                 .build(),
         );
 
@@ -62,46 +97,292 @@ pub fn run() {
         DEFAULT_ADDITIONAL_WINDOW_ARGS.to_owned(),
     )));
 
-    let app = builder
+    builder
         .manage(MouseThroughState::default())
         .manage(HitRegionRegistry::default())
+        .manage(LyricSessionState::default())
+        .manage(crate::playback_clock::PlaybackClock::default())
+        .manage(WindowPinState::default())
+        .manage(window::manager::WindowEffects::default())
+        .manage(window::manager::ContentProtection::default())
+        .manage(window::opacity::WindowOpacity::default())
+        .manage(window::exclusive::ExclusivePairs::default())
+        .manage(window::recently_closed::RecentlyClosedWindows::default())
+        .manage(window::rpc::WindowRpc::default())
+        .manage(cast::CastState::default())
+        // A page that starts (re)loading has dropped its listeners.
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Started {
+                window::event_queue::note_not_ready(webview.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             shared::detect_desktop,
             shared::desktop_environment,
             // Window management commands
             window::commands::create_window,
+            window::commands::create_window_with_overrides,
             window::commands::create_custom_window,
+            window::presets::register_window_preset,
+            window::presets::remove_window_preset,
+            window::presets::list_window_presets,
             window::commands::create_window_with_payload,
             window::commands::show_window,
             window::commands::hide_window,
             window::commands::close_managed_window,
             window::commands::toggle_window,
             window::commands::focus_window,
+            window::commands::maximize_window,
+            window::commands::unmaximize_window,
+            window::commands::minimize_window,
+            window::commands::toggle_maximize,
             window::commands::get_window_state,
+            window::commands::get_all_window_states,
             window::commands::list_windows,
+            window::events::subscribe_window_events,
             window::commands::open_window_devtools,
             window::commands::set_window_payload,
             window::commands::take_window_payload,
             window::commands::peek_window_payload,
+            window::commands::payload_cache_stats,
+            window::shared_state::state_set,
+            window::shared_state::state_get,
+            window::shared_state::state_delete,
+            window::shared_state::state_snapshot,
+            window::rpc::window_request,
+            window::rpc::window_respond,
+            window::event_queue::emit_or_queue,
+            window::event_queue::window_ready,
+            window::event_queue::clear_event_queue,
+            window::commands::window_heartbeat,
+            window::commands::set_watchdog_enabled,
+            window::commands::flush_window_state,
             window::commands::show_window_at_position,
+            window::prewarm::notify_main_window_ready,
+            i18n::set_backend_locale,
+            i18n::get_backend_locale,
+            window::first_paint::notify_first_paint,
+            window::zoom::set_webview_zoom,
+            window::zoom::get_webview_zoom,
+            window::opacity::set_window_opacity,
+            window::opacity::get_window_opacity,
+            safe_mode::is_safe_mode,
+            safe_mode::reset_window_state,
+            safe_mode::reset_settings,
+            window::coords::show_window_at_logical_position,
+            window::coords::set_window_logical_position,
+            window::coords::get_positioning_conventions,
+            window::coords::ensure_window_on_screen,
             window::commands::set_window_effect_color,
             window::commands::set_ignore_cursor_events,
+            window::commands::start_native_drag,
+            window::titlebar::get_titlebar_style,
+            window::titlebar::set_titlebar_style,
+            window::commands::start_native_resize,
             window::commands::resize_window,
             window::commands::quit_app,
             window::commands::get_cursor_position,
             window::commands::get_window_bounds,
+            window::commands::ack_close_request,
+            window::commands::set_always_on_top,
+            window::commands::get_always_on_top,
+            window::commands::set_content_protected,
+            window::commands::list_monitors,
+            window::commands::move_window_to_monitor,
+            window::commands::get_window_info,
+            window::exclusive::set_exclusive_pair,
+            window::exclusive::switch_to_mini_player,
+            window::recently_closed::reopen_last_closed_window,
+            window::recently_closed::list_recently_closed_windows,
+            window::capture::capture_window,
+            startup::get_startup_mode,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            power::set_keep_awake,
+            power::get_keep_awake,
+            idle::get_system_idle_seconds,
+            idle::set_idle_watch,
+            window::platform::appearance::get_system_theme,
+            window::platform::appearance::get_accent_color,
+            // Main window recovery
+            window::recovery::update_current_route,
+            window::recovery::recover_main_window,
+            window::recovery::restart_app,
             // Desktop lyrics commands
             window::desktop_lyrics::commands::set_window_position,
             window::desktop_lyrics::commands::start_mouse_through,
             window::desktop_lyrics::commands::stop_mouse_through,
             window::desktop_lyrics::commands::update_mouse_through_regions,
+            window::desktop_lyrics::commands::set_lyrics_locked,
+            window::desktop_lyrics::commands::get_lyrics_orientation,
+            window::desktop_lyrics::commands::set_lyrics_orientation,
+            window::desktop_lyrics::commands::get_lyrics_autohide_fullscreen,
+            window::desktop_lyrics::commands::set_lyrics_autohide_fullscreen,
             // Tray commands
             window::tray::set_tray_tooltip,
+            window::tray::update_tray_now_playing,
+            window::tray::clear_tray_now_playing,
+            window::tray::set_tray_playback_state,
             window::tray::update_tray_popup_layout,
+            window::tray::set_tray_menu_mode,
+            window::tray::rebuild_tray_icon,
+            window::tray::dismiss_tray_popup,
+            window::taskbar_progress::set_taskbar_progress,
+            window::platform::set_thumbbar_state,
+            window::panic::panic_hide,
+            window::panic::panic_restore,
+            second_instance::take_pending_open,
+            local_files::take_opened_files,
+            file_picker::pick_music_files,
+            file_picker::pick_music_folder,
+            file_picker::pick_save_path,
+            notifications::notify_now_playing,
+            notifications::set_notifications_enabled,
+            system_volume::get_system_volume,
+            system_volume::set_system_volume,
+            system_volume::get_system_muted,
+            system_volume::set_system_muted,
+            // Hotkey OSD
+            window::osd::show_osd,
+            window::osd::hide_osd,
+            // Settings store
+            settings::export_settings_backup,
+            settings::eq::get_eq_state,
+            settings::eq::set_eq_band,
+            settings::eq::set_eq_bands,
+            settings::eq::set_eq_band_count,
+            settings::eq::set_eq_preamp,
+            settings::eq::set_eq_enabled,
+            settings::eq::save_eq_preset,
+            settings::eq::load_eq_preset,
+            settings::eq::delete_eq_preset,
+            settings::eq::list_eq_presets,
+            settings::osd::get_osd_settings,
+            settings::osd::set_osd_settings,
+            settings::tray::get_tray_config,
+            settings::tray::set_tray_left_click_action,
+            settings::tray::set_tray_double_click_action,
+            window::commands::get_close_behavior,
+            settings::window::get_prewarm_windows,
+            settings::window::set_prewarm_windows,
+            settings::window::get_session_restore_enabled,
+            settings::window::set_session_restore_enabled,
+            settings::window::get_lyrics_snap,
+            settings::window::set_lyrics_snap,
+            settings::window::get_quit_grace_ms,
+            settings::window::set_quit_grace_ms,
+            crate::shutdown::quit_ack,
+            settings::window::set_close_behavior,
+            settings::paths::get_path_settings,
+            settings::paths::set_path_settings,
+            settings::power::get_power_settings,
+            settings::power::set_power_settings,
+            settings::notifications::get_notification_settings,
+            settings::http::get_http_policy,
+            settings::http::set_http_policy,
+            crate::http_util::get_http_stats,
+            crate::http_util::notify_network_changed,
+            crate::api_client::api_fetch,
+            crate::api_client::cookies_get,
+            crate::api_client::cookies_set,
+            crate::api_client::cookies_clear,
+            settings::api_endpoints::get_api_endpoints,
+            settings::api_endpoints::set_api_endpoint,
+            crate::endpoint_health::check_api_endpoint,
+            crate::endpoint_health::get_api_endpoint_health,
+            // Custom covers
+            crate::covers::set_custom_cover,
+            crate::covers::clear_custom_cover,
+            crate::covers::get_custom_cover,
+            crate::covers::list_custom_covers,
+            // Downloaded cover cache
+            crate::cover_cache::get_cover,
+            crate::cover_cache::clear_cover_cache,
+            crate::cover_cache::cover_cache_stats,
+            crate::algorithms::color::extract_colors,
+            settings::cover_cache::get_cover_cache_settings,
+            settings::cover_cache::set_cover_cache_settings,
+            // Music library
+            crate::library::list_library,
+            crate::library::get_library_folders,
+            crate::library::add_library_folder,
+            crate::library::remove_library_folder,
+            crate::library::rescan_library,
+            crate::library::pause_library_watcher,
+            crate::library::record_library_playback,
+            file_manager::reveal_in_file_manager,
+            file_manager::open_path,
+            // Playback history
+            crate::history::export_history,
+            crate::history::cancel_history_export,
+            // Offline downloads
+            crate::downloads::download_start,
+            crate::downloads::download_pause,
+            crate::downloads::download_resume,
+            crate::downloads::download_cancel,
+            crate::downloads::download_list,
+            settings::downloads::get_download_settings,
+            settings::downloads::set_download_settings,
+            crate::first_run::get_run_context,
+            // Maintenance scheduler
+            crate::tasks::scheduler::get_scheduler_state,
+            crate::tasks::scheduler::run_maintenance_now,
+            crate::tasks::scheduler::report_user_activity,
+            // LAN remote API tokens
+            crate::remote::create_remote_token,
+            crate::remote::revoke_remote_token,
+            crate::remote::list_remote_tokens,
+            // DLNA / UPnP casting
+            cast::discover_cast_targets,
+            cast::cast_to_target,
+            cast::cast_control,
+            cast::get_cast_state,
+            // Lyric session
+            crate::lyrics::parse_lyrics,
+            crate::lyrics::export::export_lrc,
+            crate::lyrics::session::load_lyric_session,
+            crate::lyrics::session::set_lyric_offset,
+            crate::lyrics::session::clear_lyric_session,
+            crate::lyrics::session::get_current_lyric_line,
+            crate::playback_clock::sync_playback_clock,
+            crate::playback_clock::get_estimated_position,
+            crate::queue_store::replace_queue,
+            crate::queue_store::apply_queue_diff,
+            crate::queue_store::get_queue_page,
+            crate::queue_store::get_queue_meta,
+            crate::storage::get_storage_health,
+            crate::storage::run_storage_maintenance,
+            crate::cache_store::cache_get,
+            crate::cache_store::cache_set,
+            crate::cache_store::cache_evict,
+            crate::cache_store::cache_stats,
+            crate::cache_store::history_add,
+            crate::cache_store::history_query,
+            // Crossfade planning
+            crate::algorithms::crossfade::plan_crossfade,
+            // Array chunking
+            crate::algorithms::chunk::get_chunk,
+            // Number formatting
+            crate::algorithms::format_number::commands::format_number,
+            // API request crypto
+            crate::algorithms::apicrypto::commands::weapi_encrypt,
+            crate::algorithms::apicrypto::commands::eapi_encrypt,
+            crate::algorithms::apicrypto::commands::linuxapi_encrypt,
+            crate::algorithms::apicrypto::commands::eapi_decrypt,
+            crate::algorithms::apicrypto::commands::md5_sign,
+            // Login QR codes
+            crate::algorithms::qr::generate_qr,
+            // Local library search
+            crate::algorithms::search::search_rank,
+            // NCM container decryption
+            crate::algorithms::ncm::decrypt_ncm,
             // AutoMix analysis (native Rust, shared by desktop/mobile)
             commands::audio_analyze_automix,
             commands::audio_analyze_automix_source,
             commands::audio_preheat,
+            // Output device capabilities
+            commands::get_device_capabilities,
+            commands::get_recommended_audio_config,
             // AMLL-style: single message command for all playback control
             commands::audio_send_msg,
             // Event stream subscription (Rust → frontend Tauri Channel)
@@ -112,96 +393,273 @@ pub fn run() {
             commands::audio_set_session,
             commands::audio_poll_events,
         ])
-        .setup(|app| {
-            let app_handle = app.handle().clone();
-            app.manage(commands::PlayerState::new(app_handle.clone()));
-
-            // Create the primary desktop window from the Rust-side preset.
-            // `tauri.conf.json` intentionally has no static windows so desktop
-            // and mobile entry points can own their platform-specific startup.
-            let mut main_config = WindowConfig::main();
-            // Create hidden, restore saved geometry, then show. Otherwise
-            // users see the default window size for one frame before the
-            // window-state plugin applies the saved size/position.
-            main_config.visible = false;
-            if let Err(e) = wm::create_window(&app_handle, &main_config) {
-                warn!("Failed to create main window: {}", e);
-            }
+        .setup(setup)
+}
 
-            #[allow(unused_variables)]
-            let Some(main_window) = app.get_webview_window("main") else {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "main window was not created",
-                )
-                .into());
-            };
+/// Manage the state that needs the app handle, then create the main window
+/// and tray.
+fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let app_handle = app.handle().clone();
+    // Parsed here rather than at the top of `run()` so warnings about
+    // bad arguments reach the log plugin.
+    let startup_mode = if safe_mode::is_active() {
+        startup::init(startup::StartupMode::Normal)
+    } else {
+        startup::init(startup::parse_args(std::env::args().skip(1)))
+    };
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            PayloadCache::init_persistence(dir.join("window-payloads.json"));
+            window::recovery::discard_stale_recovery_payload();
+        }
+        Err(e) => warn!("Failed to resolve app data dir for payloads: {}", e),
+    }
+    PayloadCache::start_sweeper();
+    window::manager::start_watchdog(&app_handle);
+    window::platform::appearance::start(&app_handle);
+    system_volume::start(&app_handle);
+    app.manage(SettingsStore::load(&app_handle));
+    window::presets::load(&app_handle);
+    app.manage(window::session::WindowSession::load(&app_handle));
+    app.manage(window::shared_state::SharedState::load(&app_handle));
+    app.manage(window::panic::PanicState::default());
+    app.manage(crate::covers::CustomCoverState::load(&app_handle));
+    app.manage(crate::cover_cache::CoverCache::load(&app_handle));
+    match crate::queue_store::QueueStore::load(&app_handle) {
+        Ok(store) => {
+            app.manage(store);
+        }
+        Err(e) => warn!("Queue store disabled: {}", e),
+    }
+    match crate::cache_store::CacheStore::load(&app_handle) {
+        Ok(store) => {
+            app.manage(store);
+        }
+        Err(e) => warn!("Cache store disabled: {}", e),
+    }
+    crate::shutdown::ShutdownHooks::register("keep-awake", |_| power::release_all());
+    crate::shutdown::ShutdownHooks::register("settings", |app| {
+        if let Some(store) = app.try_state::<SettingsStore>() {
+            store.flush();
+        }
+    });
+    crate::shutdown::ShutdownHooks::register("cache-store", |app| {
+        let Some(store) = app.try_state::<crate::cache_store::CacheStore>() else {
+            return;
+        };
+        if let Err(e) = store.flush() {
+            warn!("Failed to flush the cache store: {}", e);
+        }
+    });
+    app.manage(crate::api_client::ApiClient::load(&app_handle));
+    crate::storage::start_integrity_check(&app_handle);
+    app.manage(Scheduler::start(&app_handle));
+    crate::endpoint_health::start_periodic_checks(&app_handle);
+    app.manage(LibraryState::load(&app_handle));
+    app.manage(crate::history::HistoryState::default());
+    app.manage(crate::downloads::DownloadManager::load(&app_handle));
+    crate::downloads::pump(&app_handle);
+    crate::shutdown::ShutdownHooks::register("downloads", crate::downloads::save_for_exit);
+    crate::shutdown::ShutdownHooks::register("library", library::flush);
+    library::start_watcher(&app_handle);
+    app.manage(commands::PlayerState::new(app_handle.clone()));
+    commands::watch_device_format(app_handle.clone());
+    media_controls::start(&app_handle);
+    // Files from a file association or `gmplayer song.flac`.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    local_files::open_args(
+        &app_handle,
+        &args,
+        &std::env::current_dir().unwrap_or_default(),
+    );
 
-            // macOS-specific helpers that are not part of the generic window preset.
-            #[cfg(target_os = "macos")]
-            {
-                // NSWindowLevel: https://developer.apple.com/documentation/appkit/nswindowlevel
-                if let Err(e) = main_window.set_window_level(25) {
-                    warn!("Failed to set main window level: {}", e);
-                }
-            }
+    // Create the primary desktop window from the Rust-side preset.
+    // `tauri.conf.json` intentionally has no static windows so desktop
+    // and mobile entry points can own their platform-specific startup.
+    let mut main_config = WindowConfig::main();
+    // Create hidden, restore saved geometry, then show. Otherwise
+    // users see the default window size for one frame before the
+    // window-state plugin applies the saved size/position.
+    main_config.visible = false;
+    if let Err(e) = wm::create_window(&app_handle, &main_config) {
+        warn!("Failed to create main window: {}", e);
+    }
 
-            if let Err(e) = main_window.restore_state(WINDOW_STATE_FLAGS) {
-                warn!("Failed to restore main window state before show: {}", e);
+    #[allow(unused_variables)]
+    let Some(main_window) = app.get_webview_window("main") else {
+        return Err(
+            std::io::Error::new(std::io::ErrorKind::Other, "main window was not created").into(),
+        );
+    };
+
+    // macOS-specific helpers that are not part of the generic window preset.
+    #[cfg(target_os = "macos")]
+    {
+        // NSWindowLevel: https://developer.apple.com/documentation/appkit/nswindowlevel
+        if let Err(e) = main_window.set_window_level(25) {
+            warn!("Failed to set main window level: {}", e);
+        }
+    }
+
+    if safe_mode::is_active() {
+        // Created at the preset size by `create_window`; also skip
+        // the saved geometry, which may be what broke the last run.
+        let _ = main_window.center();
+    } else {
+        if let Err(e) = main_window.restore_state(WINDOW_STATE_FLAGS) {
+            warn!("Failed to restore main window state before show: {}", e);
+        }
+        if let Err(e) = window::coords::ensure_on_screen(&app_handle, "main") {
+            warn!("Failed to check the main window is on screen: {}", e);
+        }
+    }
+    match startup_mode {
+        startup::StartupMode::Normal => {
+            // With a window effect, `first_paint` shows it once the
+            // page has rendered.
+            if !window::first_paint::defer_show(&main_window, true) {
+                if let Err(e) = main_window.show() {
+                    warn!("Failed to show main window after state restore: {}", e);
+                } else {
+                    let _ = main_window.set_focus();
+                    let _ = app_handle.emit("main-window-visibility", true);
+                }
             }
-            if let Err(e) = main_window.show() {
-                warn!("Failed to show main window after state restore: {}", e);
-            } else {
-                let _ = main_window.set_focus();
-                let _ = app_handle.emit("main-window-visibility", true);
+        }
+        startup::StartupMode::Hidden => {
+            let _ = app_handle.emit("main-window-visibility", false);
+        }
+        startup::StartupMode::Window(label) => {
+            let _ = app_handle.emit("main-window-visibility", false);
+            let opened = window::presets::lookup(label)
+                .ok_or_else(|| format!("Unknown window preset '{}'", label))
+                .and_then(|config| wm::create_window(&app_handle, &config));
+            if let Err(e) = opened {
+                warn!(
+                    "Failed to open startup window '{}', showing main: {}",
+                    label, e
+                );
+                let _ = wm::show_window(&app_handle, "main");
             }
+        }
+    }
 
-            // Set up system tray
-            let handle = app.handle().clone();
-            if let Err(e) = window::tray::setup_tray(&handle) {
-                warn!("Failed to setup system tray: {}", e);
-            }
+    // A hidden or `--window` launch opens only what was asked for.
+    if !safe_mode::is_active() && !startup::was_started_hidden() {
+        window::session::restore(&app_handle);
+    }
 
-            // Pre-create tray popup (hidden) so it's loaded and ready on first right-click
-            let popup_config = WindowConfig::tray_popup();
-            if let Err(e) = wm::create_window(&handle, &popup_config) {
-                warn!("Failed to pre-create tray popup: {}", e);
-            }
+    // Set up system tray
+    let handle = app.handle().clone();
+    if let Err(e) = window::tray::setup_tray(&handle) {
+        warn!("Failed to setup system tray: {}", e);
+    }
+    #[cfg(target_os = "windows")]
+    window::taskbar_restart::start(&handle);
 
-            spawn_audio_preheat(app_handle);
+    // Pre-create tray popup (hidden) so it's loaded and ready on first right-click
+    if !safe_mode::is_active() {
+        let popup_config = WindowConfig::tray_popup();
+        if let Err(e) = wm::create_window(&handle, &popup_config) {
+            warn!("Failed to pre-create tray popup: {}", e);
+        }
+    }
 
-            Ok(())
-        })
-        .build(tauri::generate_context!())
-        .expect("error while building tauri application");
+    spawn_audio_preheat(app_handle);
+
+    Ok(())
+}
 
-    app.run(|app_handle, event| {
-        if let RunEvent::WindowEvent { label, event, .. } = &event {
-            // Handle desktop lyrics window events (moved/resized/destroyed)
-            window::desktop_lyrics::commands::handle_desktop_lyrics_event(app_handle, label, event);
+/// Window, file-open and exit events of the running app.
+pub fn handle_run_event(app_handle: &tauri::AppHandle, event: RunEvent) {
+    if let RunEvent::WindowEvent { label, event, .. } = &event {
+        // Handle desktop lyrics window events (moved/resized/destroyed)
+        window::desktop_lyrics::commands::handle_desktop_lyrics_event(app_handle, label, event);
+        window::events::handle(app_handle, label, event);
 
-            #[cfg(windows)]
-            if label == "main" && matches!(event, WindowEvent::Destroyed) {
-                gmplayer_taskbar_lyric::close_taskbar_lyric(app_handle.clone());
-            }
+        #[cfg(windows)]
+        if label == "main" && matches!(event, WindowEvent::Destroyed) {
+            gmplayer_taskbar_lyric::close_taskbar_lyric(app_handle.clone());
+        }
 
-            match (label.as_str(), event) {
-                // Main window close → save state, emit to frontend for close-behavior decision
-                ("main", WindowEvent::CloseRequested { api, .. }) => {
-                    api.prevent_close();
-                    let _ = app_handle.save_window_state(WINDOW_STATE_FLAGS);
-                    let _ = app_handle.emit("main-close-requested", ());
+        if matches!(event, WindowEvent::Resized(_) | WindowEvent::Focused(true)) {
+            wm::sync_minimized(app_handle, label);
+        }
+
+        match (label.as_str(), event) {
+            // Main window close → save state, emit to frontend for close-behavior decision
+            ("main", WindowEvent::CloseRequested { api, .. }) => {
+                api.prevent_close();
+                let _ = app_handle.save_window_state(WINDOW_STATE_FLAGS);
+                if let Err(e) = wm::request_close(app_handle, "main") {
+                    warn!("Failed to handle main window close: {}", e);
                 }
-                // Tray popup loses focus → hide it
-                ("tray-popup", WindowEvent::Focused(false)) => {
-                    if let Some(popup) = app_handle.get_webview_window("tray-popup") {
-                        let _ = popup.hide();
-                    }
+            }
+            // Keep geometry saved in case the app does not exit cleanly
+            ("main", WindowEvent::Moved(_) | WindowEvent::Resized(_)) => {
+                wm::schedule_state_save(app_handle);
+            }
+            // Backstops for OS appearance notifications; see `appearance`
+            (_, WindowEvent::ThemeChanged(_)) => {
+                window::platform::appearance::refresh_soon(app_handle);
+            }
+            ("main", WindowEvent::Focused(true)) => {
+                window::platform::appearance::main_focused(app_handle);
+            }
+            // Tray popup loses focus → hide it
+            ("tray-popup", WindowEvent::Focused(false)) => {
+                window::tray::on_popup_focus_lost(app_handle);
+            }
+            (_, WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. })) => {
+                local_files::open(app_handle, paths.clone());
+            }
+            // Hide or ask instead of closing, per the window's close behavior
+            (_, WindowEvent::CloseRequested { api, .. })
+                if wm::intercepts_close(app_handle, label) =>
+            {
+                api.prevent_close();
+                if let Err(e) = wm::request_close(app_handle, label) {
+                    warn!("Failed to handle close of '{}': {}", label, e);
+                }
+            }
+            // Snapshot bounds while the window still exists, for reopen
+            (_, WindowEvent::CloseRequested { .. }) => {
+                if let Some(window) = app_handle.get_webview_window(label) {
+                    window::recently_closed::note_closing(&window);
                 }
-                _ => {}
             }
+            (_, WindowEvent::Destroyed) => {
+                PayloadCache::discard_for_window(label);
+                window::event_queue::note_not_ready(label);
+                window::manager::forget_heartbeat(label);
+                window::manager::forget_minimized(label);
+                window::manager::forget_window_records(app_handle, label);
+                power::release(label);
+                window::recently_closed::on_destroyed(app_handle, label);
+                window::session::note_destroyed(app_handle, label);
+                window::tray::note_visibility(label, false);
+                // Last, so the cleanup above saw the old window.
+                let app = app_handle.clone();
+                let label = label.clone();
+                let _ = app_handle.run_on_main_thread(move || {
+                    window::manager::recreate_recovered(&app, &label);
+                });
+            }
+            _ => {}
         }
-    });
+    }
+    // "Open With" and file associations on macOS arrive as file URLs.
+    #[cfg(target_os = "macos")]
+    if let RunEvent::Opened { urls } = &event {
+        let paths = urls
+            .iter()
+            .filter_map(|url| url.to_file_path().ok())
+            .collect();
+        local_files::open(app_handle, paths);
+    }
+    if let RunEvent::ExitRequested { .. } = &event {
+        window::recently_closed::mark_shutting_down(app_handle);
+    }
 }
 
 fn spawn_audio_preheat(app_handle: tauri::AppHandle) {
@@ -0,0 +1,725 @@
+//! Now-playing notifications with playback buttons.
+//!
+//! `notify_now_playing` shows the track as a system notification while the
+//! main window is hidden or minimized. Each one replaces the previous
+//! instead of stacking up: Windows reuses the toast tag and group, macOS
+//! the request identifier, Linux the `replaces_id` of the last one. A press
+//! on one of the buttons emits `notification-action`; clicking the body
+//! brings the main window back.
+//!
+//! Windows shows a WinRT toast whose buttons launch
+//! `gmplayer://notification/<action>`. That works from the Action Center
+//! too, long after the toast's own process-bound handlers are gone: the
+//! launch becomes a second instance, which hands the link to [`activated`].
+//! The installer registers the `gmplayer://` scheme (the `deep-link`
+//! section of `tauri.conf.json`) and removes it on uninstall; a dev or
+//! portable build shows the buttons but leaves the scheme to the installed
+//! app.
+//! macOS goes through `UNUserNotificationCenter` with a small delegate
+//! class, and Linux talks to the notification daemon over D-Bus (the
+//! protocol libnotify speaks).
+
+use std::fmt;
+use std::path::PathBuf;
+
+use log::{debug, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::desktop::i18n::{self, Text};
+use crate::desktop::window::manager;
+use crate::settings::notifications::NotificationSettings;
+use crate::settings::SettingsStore;
+
+pub const NOTIFICATION_ACTION_EVENT: &str = "notification-action";
+
+/// Cover size handed to the notification, in pixels.
+const COVER_SIZE: u32 = 256;
+/// Prefix of the links toast buttons launch on Windows.
+const LINK_PREFIX: &str = "gmplayer://notification/";
+
+/// A button on the notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationAction {
+    Previous,
+    Play,
+    Pause,
+    Next,
+}
+
+impl NotificationAction {
+    /// Identifier passed through the platform and back.
+    fn id(self) -> &'static str {
+        match self {
+            Self::Previous => "previous",
+            Self::Play => "play",
+            Self::Pause => "pause",
+            Self::Next => "next",
+        }
+    }
+
+    fn parse(id: &str) -> Option<Self> {
+        [Self::Previous, Self::Play, Self::Pause, Self::Next]
+            .into_iter()
+            .find(|action| action.id() == id)
+    }
+
+    fn label(self) -> &'static str {
+        i18n::tr(match self {
+            Self::Previous => Text::TrayMenuPrevious,
+            Self::Play => Text::ThumbbarPlay,
+            Self::Pause => Text::ThumbbarPause,
+            Self::Next => Text::TrayMenuNext,
+        })
+    }
+}
+
+/// Payload of `notification-action`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationActionPressed {
+    pub action: NotificationAction,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum NotificationError {
+    /// No notification support on this platform or build.
+    Unsupported(String),
+    Failed(String),
+}
+
+impl fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(message) => write!(f, "{}", message),
+            Self::Failed(message) => write!(f, "Failed to show notification: {}", message),
+        }
+    }
+}
+
+/// What goes on screen.
+struct Toast {
+    title: String,
+    body: String,
+    image: Option<PathBuf>,
+    actions: Vec<NotificationAction>,
+}
+
+/// Escape text for toast XML and for notification bodies that may be
+/// rendered as markup.
+fn escape_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The action in a `gmplayer://notification/<action>` link.
+pub(crate) fn link_action(arg: &str) -> Option<&str> {
+    let prefix = arg.get(..LINK_PREFIX.len())?;
+    prefix
+        .eq_ignore_ascii_case(LINK_PREFIX)
+        .then(|| arg[LINK_PREFIX.len()..].trim_end_matches('/'))
+}
+
+/// Handle a press on a notification: a known button becomes an event,
+/// anything else (the body) shows the main window.
+pub(crate) fn activated(app: &AppHandle, id: &str) {
+    debug!("Notification activated: '{}'", id);
+    match NotificationAction::parse(id) {
+        Some(action) => {
+            let _ = app.emit(
+                NOTIFICATION_ACTION_EVENT,
+                NotificationActionPressed { action },
+            );
+        }
+        None => {
+            if let Err(e) = manager::focus_window(app, "main") {
+                warn!("Failed to show main window from notification: {}", e);
+            }
+        }
+    }
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsStore>()
+        .map_or(true, |store| store.read(|s| s.notifications.enabled))
+}
+
+/// The player is on screen, so a notification would only repeat it.
+fn main_window_showing(app: &AppHandle) -> bool {
+    app.get_webview_window("main").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+    })
+}
+
+/// Local cover file: `cover` is either a path already on disk or a URL that
+/// goes through the cover cache.
+async fn cover_file(app: &AppHandle, cover: &str) -> Option<PathBuf> {
+    if cover.starts_with("http://") || cover.starts_with("https://") {
+        match crate::cover_cache::cached_cover(app, cover, Some(COVER_SIZE)).await {
+            Ok(path) => Some(path),
+            Err(e) => {
+                warn!("Notification without cover: {}", e);
+                None
+            }
+        }
+    } else {
+        Some(PathBuf::from(cover)).filter(|path| path.is_file())
+    }
+}
+
+/// Show the current track with `actions` as buttons. Does nothing and
+/// resolves to `false` while notifications are turned off, the main window
+/// is showing, or the system blocks notifications from the app.
+#[tauri::command]
+pub async fn notify_now_playing(
+    app: AppHandle,
+    title: String,
+    artist: String,
+    cover_path: Option<String>,
+    actions: Vec<NotificationAction>,
+) -> Result<bool, NotificationError> {
+    if !enabled(&app) || main_window_showing(&app) {
+        return Ok(false);
+    }
+    let image = match cover_path.as_deref().filter(|cover| !cover.is_empty()) {
+        Some(cover) => cover_file(&app, cover).await,
+        None => None,
+    };
+    let toast = Toast {
+        title,
+        body: artist,
+        image,
+        actions,
+    };
+    tauri::async_runtime::spawn_blocking(move || imp::show(&app, toast))
+        .await
+        .map_err(|e| NotificationError::Failed(e.to_string()))?
+}
+
+/// Turn now-playing notifications on or off; turning them off also
+/// removes the one on screen.
+#[tauri::command]
+pub fn set_notifications_enabled(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<NotificationSettings, String> {
+    let settings = store.update(|s| {
+        s.notifications.enabled = enabled;
+        Ok(s.notifications.clone())
+    })?;
+    if !enabled {
+        imp::withdraw(&app);
+    }
+    Ok(settings)
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use parking_lot::Mutex;
+    use tauri::AppHandle;
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{
+        NotificationSetting, ToastNotification, ToastNotificationManager,
+    };
+
+    use super::{escape_markup, NotificationError, Toast, LINK_PREFIX};
+
+    const TAG: &str = "now-playing";
+    const GROUP: &str = "gmplayer";
+    /// Toasts from an unpackaged app need an AppUserModelID registered by a
+    /// Start menu shortcut. The installer's shortcut carries the bundle
+    /// identifier; dev builds have none and borrow PowerShell's.
+    const POWERSHELL_APP_ID: &str =
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+    fn app_id(app: &AppHandle) -> HSTRING {
+        if cfg!(debug_assertions) {
+            POWERSHELL_APP_ID.into()
+        } else {
+            app.config().identifier.as_str().into()
+        }
+    }
+
+    /// Title and body of the toast on screen.
+    static SHOWN: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+    fn failed(e: windows::core::Error) -> NotificationError {
+        NotificationError::Failed(e.message())
+    }
+
+    fn toast_xml(toast: &Toast) -> String {
+        let image = toast
+            .image
+            .as_deref()
+            .and_then(|path| tauri::Url::from_file_path(path).ok())
+            .map(|url| {
+                format!(
+                    r#"<image placement="appLogoOverride" src="{}"/>"#,
+                    escape_markup(url.as_str())
+                )
+            })
+            .unwrap_or_default();
+        let actions: String = toast
+            .actions
+            .iter()
+            .map(|action| {
+                format!(
+                    r#"<action content="{}" arguments="{}{}" activationType="protocol"/>"#,
+                    escape_markup(action.label()),
+                    LINK_PREFIX,
+                    action.id()
+                )
+            })
+            .collect();
+        format!(
+            concat!(
+                r#"<toast launch="{}open" activationType="protocol">"#,
+                r#"<visual><binding template="ToastGeneric">"#,
+                "<text>{}</text><text>{}</text>{}",
+                "</binding></visual>",
+                "<actions>{}</actions>",
+                r#"<audio silent="true"/>"#,
+                "</toast>"
+            ),
+            LINK_PREFIX,
+            escape_markup(&toast.title),
+            escape_markup(&toast.body),
+            image,
+            actions
+        )
+    }
+
+    pub(super) fn show(app: &AppHandle, toast: Toast) -> Result<bool, NotificationError> {
+        let notifier =
+            ToastNotificationManager::CreateToastNotifierWithId(&app_id(app)).map_err(failed)?;
+        if notifier.Setting().map_err(failed)? != NotificationSetting::Enabled {
+            return Ok(false);
+        }
+        let document = XmlDocument::new().map_err(failed)?;
+        document
+            .LoadXml(&HSTRING::from(toast_xml(&toast)))
+            .map_err(failed)?;
+        let notification = ToastNotification::CreateToastNotification(&document).map_err(failed)?;
+        notification.SetTag(&TAG.into()).map_err(failed)?;
+        notification.SetGroup(&GROUP.into()).map_err(failed)?;
+        // Same track, new buttons (play ↔ pause): update the toast in the
+        // Action Center without popping it up again.
+        let track = (toast.title.clone(), toast.body.clone());
+        let mut shown = SHOWN.lock();
+        notification
+            .SetSuppressPopup(shown.as_ref() == Some(&track))
+            .map_err(failed)?;
+        notifier.Show(&notification).map_err(failed)?;
+        *shown = Some(track);
+        Ok(true)
+    }
+
+    pub(super) fn withdraw(app: &AppHandle) {
+        SHOWN.lock().take();
+        let _ = ToastNotificationManager::History().and_then(|history| {
+            history.RemoveGroupedTagWithId(&TAG.into(), &GROUP.into(), &app_id(app))
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    use block2::{DynBlock, RcBlock};
+    use objc2::rc::{autoreleasepool, Retained};
+    use objc2::runtime::{Bool, NSObject, NSObjectProtocol, ProtocolObject};
+    use objc2::{define_class, msg_send, AnyThread};
+    use objc2_foundation::{ns_string, NSArray, NSBundle, NSError, NSSet, NSString, NSURL};
+    use objc2_user_notifications::{
+        UNAuthorizationOptions, UNMutableNotificationContent, UNNotification, UNNotificationAction,
+        UNNotificationActionOptions, UNNotificationAttachment, UNNotificationCategory,
+        UNNotificationCategoryOptions, UNNotificationDefaultActionIdentifier,
+        UNNotificationPresentationOptions, UNNotificationRequest, UNNotificationResponse,
+        UNUserNotificationCenter, UNUserNotificationCenterDelegate,
+    };
+    use parking_lot::Mutex;
+    use tauri::AppHandle;
+
+    use super::{NotificationAction, NotificationError, Toast};
+
+    const REQUEST_ID: &str = "now-playing";
+
+    define_class!(
+        // SAFETY: NSObject has no subclassing requirements, and the
+        // delegate has no `Drop`.
+        #[unsafe(super(NSObject))]
+        #[name = "GMPlayerNotificationDelegate"]
+        struct Delegate;
+
+        unsafe impl NSObjectProtocol for Delegate {}
+
+        unsafe impl UNUserNotificationCenterDelegate for Delegate {
+            #[unsafe(method(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:))]
+            fn did_receive(
+                &self,
+                _center: &UNUserNotificationCenter,
+                response: &UNNotificationResponse,
+                done: &DynBlock<dyn Fn()>,
+            ) {
+                let id = response.actionIdentifier();
+                if let Some(app) = APP.get() {
+                    // SAFETY: an immutable framework constant.
+                    let default = unsafe { UNNotificationDefaultActionIdentifier };
+                    let id = if id.isEqualToString(default) {
+                        String::new()
+                    } else {
+                        id.to_string()
+                    };
+                    super::activated(app, &id);
+                }
+                done.call(());
+            }
+
+            // Without it nothing is shown while the app is frontmost.
+            #[unsafe(method(userNotificationCenter:willPresentNotification:withCompletionHandler:))]
+            fn will_present(
+                &self,
+                _center: &UNUserNotificationCenter,
+                _notification: &UNNotification,
+                done: &DynBlock<dyn Fn(UNNotificationPresentationOptions)>,
+            ) {
+                done.call((UNNotificationPresentationOptions::Banner
+                    | UNNotificationPresentationOptions::List,));
+            }
+        }
+    );
+
+    impl Delegate {
+        fn new() -> Retained<Self> {
+            let this = Self::alloc().set_ivars(());
+            // SAFETY: NSObject's plain `init`.
+            unsafe { msg_send![super(this), init] }
+        }
+    }
+
+    struct Center {
+        /// Category identifiers registered so far; `setNotificationCategories:`
+        /// replaces the whole set, so every call passes all of them.
+        categories: Vec<Vec<NotificationAction>>,
+    }
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    static CENTER: Mutex<Option<Center>> = Mutex::new(None);
+
+    /// Delegate and authorization request, once.
+    fn setup() -> Result<Center, NotificationError> {
+        // `currentNotificationCenter` raises outside an app bundle.
+        if NSBundle::mainBundle().bundleIdentifier().is_none() {
+            return Err(NotificationError::Unsupported(
+                "Notifications need the bundled app".into(),
+            ));
+        }
+        let center = UNUserNotificationCenter::currentNotificationCenter();
+        let delegate = Delegate::new();
+        center.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+        // The center holds its delegate weakly; this one is never released.
+        std::mem::forget(delegate);
+
+        let ignore = RcBlock::new(|_granted: Bool, _error: *mut NSError| {});
+        center.requestAuthorizationWithOptions_completionHandler(
+            UNAuthorizationOptions::Alert,
+            &ignore,
+        );
+        Ok(Center {
+            categories: Vec::new(),
+        })
+    }
+
+    fn category_id(actions: &[NotificationAction]) -> String {
+        let ids: Vec<&str> = actions.iter().map(|action| action.id()).collect();
+        format!("now-playing:{}", ids.join(","))
+    }
+
+    /// Register the category for `actions` if it is new.
+    fn ensure_category(center: &mut Center, actions: &[NotificationAction]) {
+        if center.categories.iter().any(|known| known == actions) {
+            return;
+        }
+        center.categories.push(actions.to_vec());
+        let categories: Vec<Retained<UNNotificationCategory>> = center
+            .categories
+            .iter()
+            .map(|actions| {
+                let buttons: Vec<Retained<UNNotificationAction>> = actions
+                    .iter()
+                    .map(|action| {
+                        UNNotificationAction::actionWithIdentifier_title_options(
+                            &NSString::from_str(action.id()),
+                            &NSString::from_str(action.label()),
+                            UNNotificationActionOptions::empty(),
+                        )
+                    })
+                    .collect();
+                UNNotificationCategory::categoryWithIdentifier_actions_intentIdentifiers_options(
+                    &NSString::from_str(&category_id(actions)),
+                    &NSArray::from_retained_slice(&buttons),
+                    &NSArray::new(),
+                    UNNotificationCategoryOptions::empty(),
+                )
+            })
+            .collect();
+        UNUserNotificationCenter::currentNotificationCenter()
+            .setNotificationCategories(&NSSet::from_retained_slice(&categories));
+    }
+
+    /// Attachments are moved into the notification store, so hand over a
+    /// copy rather than the cached cover.
+    fn attachment(path: &Path) -> Option<Retained<UNNotificationAttachment>> {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("jpg");
+        let copy = std::env::temp_dir().join(format!(
+            "gmplayer-notification-{}.{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis()),
+            ext
+        ));
+        std::fs::copy(path, &copy).ok()?;
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&copy.to_string_lossy()));
+        // SAFETY: no options dictionary is passed.
+        unsafe {
+            UNNotificationAttachment::attachmentWithIdentifier_URL_options_error(
+                ns_string!("cover"),
+                &url,
+                None,
+            )
+        }
+        .ok()
+    }
+
+    pub(super) fn show(app: &AppHandle, toast: Toast) -> Result<bool, NotificationError> {
+        let _ = APP.set(app.clone());
+        let mut guard = CENTER.lock();
+        // The blocking pool drains no autorelease pool of its own.
+        autoreleasepool(|_| {
+            if guard.is_none() {
+                *guard = Some(setup()?);
+            }
+            let center = guard.as_mut().expect("set up above");
+            ensure_category(center, &toast.actions);
+
+            let content = UNMutableNotificationContent::new();
+            content.setTitle(&NSString::from_str(&toast.title));
+            content.setBody(&NSString::from_str(&toast.body));
+            content.setCategoryIdentifier(&NSString::from_str(&category_id(&toast.actions)));
+            content.setThreadIdentifier(ns_string!("now-playing"));
+            if let Some(attachment) = toast.image.as_deref().and_then(attachment) {
+                content.setAttachments(&NSArray::from_retained_slice(&[attachment]));
+            }
+            // The same identifier replaces the notification on screen.
+            let request = UNNotificationRequest::requestWithIdentifier_content_trigger(
+                &NSString::from_str(REQUEST_ID),
+                &content,
+                None,
+            );
+            UNUserNotificationCenter::currentNotificationCenter()
+                .addNotificationRequest_withCompletionHandler(&request, None);
+            Ok(true)
+        })
+    }
+
+    pub(super) fn withdraw(_app: &AppHandle) {
+        if CENTER.lock().is_none() {
+            return;
+        }
+        autoreleasepool(|_| {
+            let ids = NSArray::from_retained_slice(&[NSString::from_str(REQUEST_ID)]);
+            UNUserNotificationCenter::currentNotificationCenter()
+                .removeDeliveredNotificationsWithIdentifiers(&ids);
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::HashMap;
+
+    use log::warn;
+    use parking_lot::Mutex;
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::Value;
+
+    use super::{escape_markup, NotificationError, Toast};
+
+    const DEST: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const APP_NAME: &str = "GMPlayer";
+    /// Key of the action invoked by clicking the body.
+    const DEFAULT_ACTION: &str = "default";
+
+    struct Daemon {
+        connection: Connection,
+        /// Id of the notification on screen, 0 for none.
+        last_id: u32,
+    }
+
+    static DAEMON: Mutex<Option<Daemon>> = Mutex::new(None);
+
+    fn failed(e: zbus::Error) -> NotificationError {
+        NotificationError::Failed(e.to_string())
+    }
+
+    /// Forward `ActionInvoked` for our own notification.
+    fn listen(app: AppHandle, connection: Connection) -> zbus::Result<()> {
+        let proxy = Proxy::new(&connection, DEST, PATH, DEST)?;
+        for message in proxy.receive_signal("ActionInvoked")? {
+            let Ok((id, key)) = message.body().deserialize::<(u32, String)>() else {
+                continue;
+            };
+            let ours = DAEMON
+                .lock()
+                .as_ref()
+                .is_some_and(|daemon| daemon.last_id == id);
+            if ours {
+                let key = if key == DEFAULT_ACTION { "" } else { &key };
+                super::activated(&app, key);
+            }
+        }
+        Ok(())
+    }
+
+    fn connect(app: &AppHandle) -> Result<Daemon, NotificationError> {
+        let connection = Connection::session().map_err(failed)?;
+        let (app, listener) = (app.clone(), connection.clone());
+        std::thread::Builder::new()
+            .name("notification-actions".into())
+            .spawn(move || {
+                if let Err(e) = listen(app, listener) {
+                    warn!("Notification buttons will not respond: {}", e);
+                }
+            })
+            .map_err(|e| NotificationError::Failed(e.to_string()))?;
+        Ok(Daemon {
+            connection,
+            last_id: 0,
+        })
+    }
+
+    pub(super) fn show(app: &AppHandle, toast: Toast) -> Result<bool, NotificationError> {
+        let mut guard = DAEMON.lock();
+        if guard.is_none() {
+            *guard = Some(connect(app)?);
+        }
+        let daemon = guard.as_mut().expect("connected above");
+
+        let mut actions = vec![DEFAULT_ACTION, ""];
+        for action in &toast.actions {
+            actions.extend([action.id(), action.label()]);
+        }
+        let mut hints: HashMap<&str, Value> = HashMap::new();
+        hints.insert("category", "x-gnome.music".into());
+        hints.insert("suppress-sound", true.into());
+        let image = toast
+            .image
+            .as_deref()
+            .map(|path| path.to_string_lossy().into_owned());
+        if let Some(image) = image.as_deref() {
+            hints.insert("image-path", image.into());
+        }
+        let reply = daemon
+            .connection
+            .call_method(
+                Some(DEST),
+                PATH,
+                Some(DEST),
+                "Notify",
+                &(
+                    APP_NAME,
+                    daemon.last_id,
+                    "",
+                    toast.title.as_str(),
+                    escape_markup(&toast.body),
+                    actions,
+                    hints,
+                    -1i32,
+                ),
+            )
+            .map_err(failed)?;
+        daemon.last_id = reply.body().deserialize().map_err(failed)?;
+        Ok(true)
+    }
+
+    pub(super) fn withdraw(_app: &AppHandle) {
+        let mut guard = DAEMON.lock();
+        let Some(daemon) = guard.as_mut().filter(|daemon| daemon.last_id != 0) else {
+            return;
+        };
+        let id = std::mem::take(&mut daemon.last_id);
+        let _ =
+            daemon
+                .connection
+                .call_method(Some(DEST), PATH, Some(DEST), "CloseNotification", &id);
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use tauri::AppHandle;
+
+    use super::{NotificationError, Toast};
+
+    pub(super) fn show(_app: &AppHandle, _toast: Toast) -> Result<bool, NotificationError> {
+        Err(NotificationError::Unsupported(
+            "Notifications are not supported on this platform".into(),
+        ))
+    }
+
+    pub(super) fn withdraw(_app: &AppHandle) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_ids_round_trip() {
+        for action in [
+            NotificationAction::Previous,
+            NotificationAction::Play,
+            NotificationAction::Pause,
+            NotificationAction::Next,
+        ] {
+            assert_eq!(NotificationAction::parse(action.id()), Some(action));
+        }
+        assert_eq!(NotificationAction::parse(""), None);
+        assert_eq!(NotificationAction::parse("default"), None);
+
+        assert_eq!(link_action("GMPlayer://notification/pause/"), Some("pause"));
+        assert_eq!(link_action("gmplayer://notification/open"), Some("open"));
+        assert_eq!(link_action("gmplayer://song/1"), None);
+    }
+
+    #[test]
+    fn markup_is_escaped() {
+        assert_eq!(
+            escape_markup(r#"Tom & Jerry <"Live"> 'n'"#),
+            "Tom &amp; Jerry &lt;&quot;Live&quot;&gt; &apos;n&apos;"
+        );
+        assert_eq!(escape_markup("周杰伦"), "周杰伦");
+    }
+}
@@ -0,0 +1,418 @@
+//! Keep the machine awake during playback.
+//!
+//! Each caller (normally a window, keyed by its label) holds its own
+//! [`KeepAwake`] mode; the strongest mode held by anyone is what the OS is
+//! asked for, so one window switching to `off` does not cancel another's
+//! request. A single `power` thread owns the OS assertion, because
+//! `SetThreadExecutionState` only lasts as long as the thread that set it.
+//!
+//! - Windows: `SetThreadExecutionState`.
+//! - macOS: an IOKit power assertion.
+//! - Linux: `org.freedesktop.PowerManagement` (plus
+//!   `org.freedesktop.ScreenSaver` for `display`) on the session bus,
+//!   falling back to a logind inhibitor lock.
+//!
+//! Every hold is dropped when the app quits or the holder's window closes.
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::WebviewWindow;
+
+const REASON: &str = "Playing audio";
+
+/// How awake to keep the machine. Ordered from weakest to strongest.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum KeepAwake {
+    #[default]
+    Off,
+    /// Prevent idle sleep but let the display turn off.
+    System,
+    /// Also keep the display on, for the lyrics window.
+    Display,
+}
+
+static HOLDS: LazyLock<Mutex<HashMap<String, KeepAwake>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+enum Request {
+    Apply(KeepAwake),
+    /// Answered once every earlier request has been applied.
+    Sync(mpsc::Sender<()>),
+}
+
+static WORKER: OnceLock<Option<mpsc::Sender<Request>>> = OnceLock::new();
+
+/// Record `holder`'s mode and return the new effective mode if it changed.
+fn update(
+    holds: &mut HashMap<String, KeepAwake>,
+    holder: &str,
+    mode: KeepAwake,
+) -> Option<KeepAwake> {
+    let before = effective(holds);
+    if mode == KeepAwake::Off {
+        holds.remove(holder);
+    } else {
+        holds.insert(holder.to_string(), mode);
+    }
+    let after = effective(holds);
+    (after != before).then_some(after)
+}
+
+fn effective(holds: &HashMap<String, KeepAwake>) -> KeepAwake {
+    holds.values().copied().max().unwrap_or_default()
+}
+
+fn worker() -> Option<&'static mpsc::Sender<Request>> {
+    WORKER
+        .get_or_init(|| {
+            let (tx, rx) = mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name("power".into())
+                .spawn(move || run_worker(rx));
+            match spawned {
+                Ok(_) => Some(tx),
+                Err(e) => {
+                    warn!("Failed to spawn power thread: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+fn run_worker(rx: mpsc::Receiver<Request>) {
+    let mut current: Option<imp::Inhibitor> = None;
+    for request in rx {
+        match request {
+            Request::Apply(mode) => {
+                // Release first: on Windows the new state replaces the old
+                // one anyway, and elsewhere two assertions would overlap.
+                drop(current.take());
+                if mode != KeepAwake::Off {
+                    current = imp::Inhibitor::acquire(mode)
+                        .map_err(|e| warn!("Failed to keep the system awake: {}", e))
+                        .ok();
+                }
+                info!("Keep awake: {:?}", mode);
+            }
+            Request::Sync(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+fn send(request: Request) {
+    if let Some(worker) = worker() {
+        let _ = worker.send(request);
+    }
+}
+
+/// Set `holder`'s keep-awake mode; `Off` drops its hold.
+pub fn hold(holder: &str, mode: KeepAwake) {
+    let mut holds = HOLDS.lock();
+    if let Some(mode) = update(&mut holds, holder, mode) {
+        // Sent under the lock so concurrent changes reach the worker in
+        // the order they were made.
+        send(Request::Apply(mode));
+    }
+}
+
+/// Drop `holder`'s hold, e.g. when its window is destroyed.
+pub fn release(holder: &str) {
+    hold(holder, KeepAwake::Off);
+}
+
+/// Drop every hold and wait (briefly) for the assertion to be released.
+pub fn release_all() {
+    let mut holds = HOLDS.lock();
+    if holds.is_empty() {
+        return;
+    }
+    holds.clear();
+    send(Request::Apply(KeepAwake::Off));
+    drop(holds);
+    let (tx, rx) = mpsc::channel();
+    send(Request::Sync(tx));
+    let _ = rx.recv_timeout(Duration::from_secs(1));
+}
+
+/// Keep the machine awake for the calling window. Overlapping holds from
+/// several windows add up: the strongest mode wins.
+#[tauri::command]
+pub fn set_keep_awake(window: WebviewWindow, mode: KeepAwake) -> KeepAwake {
+    hold(window.label(), mode);
+    effective(&HOLDS.lock())
+}
+
+/// The mode currently requested from the OS.
+#[tauri::command]
+pub fn get_keep_awake() -> KeepAwake {
+    effective(&HOLDS.lock())
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+    };
+
+    use super::KeepAwake;
+
+    pub(super) struct Inhibitor;
+
+    impl Inhibitor {
+        pub(super) fn acquire(mode: KeepAwake) -> Result<Self, String> {
+            let mut flags = ES_CONTINUOUS | ES_SYSTEM_REQUIRED;
+            if mode == KeepAwake::Display {
+                flags |= ES_DISPLAY_REQUIRED;
+            }
+            // SAFETY: only changes this thread's execution state.
+            if unsafe { SetThreadExecutionState(flags) }.0 == 0 {
+                return Err("SetThreadExecutionState failed".into());
+            }
+            Ok(Self)
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            // SAFETY: as above; clears what `acquire` set on this thread.
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::{c_char, c_void, CString};
+
+    use super::{KeepAwake, REASON};
+
+    type CFStringRef = *const c_void;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: *const c_void,
+            text: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFRelease(object: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    const UTF8: u32 = 0x0800_0100;
+    const LEVEL_ON: u32 = 255;
+
+    fn cf_string(text: &str) -> CFStringRef {
+        let text = CString::new(text).unwrap_or_default();
+        // SAFETY: `text` is a NUL-terminated UTF-8 string.
+        unsafe { CFStringCreateWithCString(std::ptr::null(), text.as_ptr(), UTF8) }
+    }
+
+    pub(super) struct Inhibitor(u32);
+
+    impl Inhibitor {
+        pub(super) fn acquire(mode: KeepAwake) -> Result<Self, String> {
+            let kind = cf_string(match mode {
+                KeepAwake::Display => "PreventUserIdleDisplaySleep",
+                _ => "PreventUserIdleSystemSleep",
+            });
+            let name = cf_string(REASON);
+            let mut id = 0u32;
+            // SAFETY: both strings are live CFStrings, released right after.
+            let status = unsafe {
+                let status = IOPMAssertionCreateWithName(kind, LEVEL_ON, name, &mut id);
+                CFRelease(kind);
+                CFRelease(name);
+                status
+            };
+            if status != 0 {
+                return Err(format!("IOKit error {}", status));
+            }
+            Ok(Self(id))
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            // SAFETY: `self.0` came from a successful create.
+            unsafe { IOPMAssertionRelease(self.0) };
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use log::debug;
+    use zbus::blocking::Connection;
+    use zbus::zvariant::OwnedFd;
+
+    use super::{KeepAwake, REASON};
+
+    const APP: &str = "GMPlayer";
+
+    /// A session bus service with `Inhibit(app, reason) -> cookie` and
+    /// `UnInhibit(cookie)`.
+    struct Service {
+        name: &'static str,
+        path: &'static str,
+        interface: &'static str,
+    }
+
+    const POWER_MANAGEMENT: Service = Service {
+        name: "org.freedesktop.PowerManagement",
+        path: "/org/freedesktop/PowerManagement/Inhibit",
+        interface: "org.freedesktop.PowerManagement.Inhibit",
+    };
+    const SCREEN_SAVER: Service = Service {
+        name: "org.freedesktop.ScreenSaver",
+        path: "/org/freedesktop/ScreenSaver",
+        interface: "org.freedesktop.ScreenSaver",
+    };
+
+    fn inhibit(session: &Connection, service: &'static Service) -> Option<(&'static Service, u32)> {
+        let cookie = session
+            .call_method(
+                Some(service.name),
+                service.path,
+                Some(service.interface),
+                "Inhibit",
+                &(APP, REASON),
+            )
+            .and_then(|reply| reply.body().deserialize::<u32>());
+        match cookie {
+            Ok(cookie) => Some((service, cookie)),
+            Err(e) => {
+                debug!("{} inhibit failed: {}", service.name, e);
+                None
+            }
+        }
+    }
+
+    /// A logind lock lasts as long as its file descriptor stays open.
+    fn logind_inhibit(what: &str) -> zbus::Result<OwnedFd> {
+        Connection::system()?
+            .call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1",
+                Some("org.freedesktop.login1.Manager"),
+                "Inhibit",
+                &(what, APP, REASON, "block"),
+            )?
+            .body()
+            .deserialize::<OwnedFd>()
+    }
+
+    pub(super) struct Inhibitor {
+        session: Option<Connection>,
+        cookies: Vec<(&'static Service, u32)>,
+        _logind: Option<OwnedFd>,
+    }
+
+    impl Inhibitor {
+        pub(super) fn acquire(mode: KeepAwake) -> Result<Self, String> {
+            let session = Connection::session().ok();
+            let mut cookies = Vec::new();
+            if let Some(session) = &session {
+                cookies.extend(inhibit(session, &POWER_MANAGEMENT));
+                if mode == KeepAwake::Display {
+                    cookies.extend(inhibit(session, &SCREEN_SAVER));
+                }
+            }
+            let mut logind = None;
+            if cookies.is_empty() {
+                let what = match mode {
+                    KeepAwake::Display => "sleep:idle",
+                    _ => "sleep",
+                };
+                logind = Some(logind_inhibit(what).map_err(|e| e.to_string())?);
+            }
+            Ok(Self {
+                session,
+                cookies,
+                _logind: logind,
+            })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            let Some(session) = &self.session else {
+                return;
+            };
+            for (service, cookie) in self.cookies.drain(..) {
+                let released = session.call_method(
+                    Some(service.name),
+                    service.path,
+                    Some(service.interface),
+                    "UnInhibit",
+                    &(cookie,),
+                );
+                if let Err(e) = released {
+                    debug!("{} uninhibit failed: {}", service.name, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use super::KeepAwake;
+
+    pub(super) struct Inhibitor;
+
+    impl Inhibitor {
+        pub(super) fn acquire(_mode: KeepAwake) -> Result<Self, String> {
+            Err("Keeping the system awake is not supported on this platform".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_strongest_hold_wins_until_it_is_released() {
+        let mut holds = HashMap::new();
+        assert_eq!(
+            update(&mut holds, "main", KeepAwake::System),
+            Some(KeepAwake::System)
+        );
+        assert_eq!(
+            update(&mut holds, "desktop-lyrics", KeepAwake::Display),
+            Some(KeepAwake::Display)
+        );
+        // Main pausing does not undo the lyrics window's hold.
+        assert_eq!(update(&mut holds, "main", KeepAwake::Off), None);
+        assert_eq!(effective(&holds), KeepAwake::Display);
+        assert_eq!(
+            update(&mut holds, "desktop-lyrics", KeepAwake::Off),
+            Some(KeepAwake::Off)
+        );
+        assert!(holds.is_empty());
+    }
+}
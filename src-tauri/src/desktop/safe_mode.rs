@@ -0,0 +1,178 @@
+//! Safe mode: a launch that avoids everything a broken file could break.
+//!
+//! Entered with `--safe-mode`, or automatically when the two previous
+//! launches never reached a ready main window (the `run.unfinished_launches`
+//! count `first_run` keeps, cleared by `notify_main_window_ready`). In safe
+//! mode saved window geometry and persisted pin choices are ignored, the tray
+//! popup and pre-warmed windows are not created, and the main window opens
+//! centered at its default size. The frontend checks [`is_safe_mode`] to show
+//! a banner and to leave its keyboard shortcuts unregistered. The remote API
+//! has no server yet (see `crate::remote`), so there is nothing to switch off
+//! there.
+//!
+//! Runs as a plugin registered after `first_run` and ahead of
+//! `window-state`, so the decision is made before any window is created.
+
+use std::sync::OnceLock;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::first_run::RunContext;
+use crate::settings::{Settings, SettingsStore};
+
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+/// Consecutive unfinished launches before safe mode kicks in.
+const CRASHES_BEFORE_SAFE_MODE: u32 = 2;
+const WINDOW_STATE_FILE: &str = tauri_plugin_window_state::DEFAULT_FILENAME;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SafeModeReason {
+    Flag,
+    RepeatedCrashes,
+}
+
+static SAFE_MODE: OnceLock<Option<SafeModeReason>> = OnceLock::new();
+
+/// Whether this launch runs in safe mode. `false` until the plugin is set up.
+pub fn is_active() -> bool {
+    SAFE_MODE.get().copied().flatten().is_some()
+}
+
+fn decide(flag: bool, unfinished_launches: u32) -> Option<SafeModeReason> {
+    if flag {
+        Some(SafeModeReason::Flag)
+    } else if unfinished_launches >= CRASHES_BEFORE_SAFE_MODE {
+        Some(SafeModeReason::RepeatedCrashes)
+    } else {
+        None
+    }
+}
+
+/// The main window came up; this launch did not crash at startup.
+pub fn mark_startup_complete(app: &AppHandle) {
+    let Some(store) = app.try_state::<SettingsStore>() else {
+        return;
+    };
+    if store.read(|s| s.run.unfinished_launches) == 0 {
+        return;
+    }
+    if let Err(e) = store.update(|s| {
+        s.run.unfinished_launches = 0;
+        Ok(())
+    }) {
+        warn!("Failed to clear the unfinished launch count: {}", e);
+    }
+}
+
+pub fn init() -> TauriPlugin<Wry> {
+    PluginBuilder::new("safe-mode")
+        .setup(|app, _api| {
+            let flag = std::env::args().skip(1).any(|arg| arg == SAFE_MODE_FLAG);
+            let unfinished = app
+                .try_state::<RunContext>()
+                .map_or(0, |context| context.unfinished_launches);
+            let reason = *SAFE_MODE.get_or_init(|| decide(flag, unfinished));
+            match reason {
+                Some(SafeModeReason::Flag) => info!("Starting in safe mode ({})", SAFE_MODE_FLAG),
+                Some(SafeModeReason::RepeatedCrashes) => warn!(
+                    "Starting in safe mode: the last {} launches did not finish starting",
+                    unfinished
+                ),
+                None => {}
+            }
+            Ok(())
+        })
+        .build()
+}
+
+/// Relaunch without `--safe-mode`. Window state is deliberately not saved
+/// first, so a reset file stays reset.
+fn restart_normally(app: &AppHandle) -> ! {
+    let mut env = app.env();
+    env.args_os.retain(|arg| arg != SAFE_MODE_FLAG);
+    mark_startup_complete(app);
+    app.cleanup_before_exit();
+    tauri::process::restart(&env)
+}
+
+#[tauri::command]
+pub fn is_safe_mode() -> bool {
+    is_active()
+}
+
+/// Delete saved window geometry for every window and restart normally.
+#[tauri::command]
+pub fn reset_window_state(app: AppHandle) -> Result<(), String> {
+    let path = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?
+        .join(WINDOW_STATE_FILE);
+    match std::fs::remove_file(&path) {
+        Ok(()) => info!("Removed {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+    restart_normally(&app)
+}
+
+/// Reset one settings section (or `"all"`) to defaults and restart normally.
+/// Startup bookkeeping (`run`) is never reset; it would replay migrations.
+#[tauri::command]
+pub fn reset_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    section: String,
+) -> Result<(), String> {
+    store.update(|s| reset_section(s, &section))?;
+    info!("Reset settings section '{}'", section);
+    restart_normally(&app)
+}
+
+fn reset_section(settings: &mut Settings, section: &str) -> Result<(), String> {
+    match section {
+        "all" => {
+            *settings = Settings {
+                run: std::mem::take(&mut settings.run),
+                ..Settings::default()
+            }
+        }
+        "apiEndpoints" => settings.api_endpoints = Default::default(),
+        "eq" => settings.eq = Default::default(),
+        "http" => settings.http = Default::default(),
+        "notifications" => settings.notifications = Default::default(),
+        "osd" => settings.osd = Default::default(),
+        "paths" => settings.paths = Default::default(),
+        "power" => settings.power = Default::default(),
+        "remote" => settings.remote = Default::default(),
+        "window" => settings.window = Default::default(),
+        other => return Err(format!("Unknown settings section '{}'", other)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_mode_needs_the_flag_or_two_failed_launches() {
+        assert_eq!(decide(false, 0), None);
+        assert_eq!(decide(false, 1), None);
+        assert_eq!(decide(false, 2), Some(SafeModeReason::RepeatedCrashes));
+        assert_eq!(decide(true, 5), Some(SafeModeReason::Flag));
+    }
+
+    #[test]
+    fn unknown_sections_are_rejected() {
+        let mut settings = Settings::default();
+        settings.window.prewarm_windows = false;
+        reset_section(&mut settings, "window").unwrap();
+        assert!(settings.window.prewarm_windows);
+        assert!(reset_section(&mut settings, "bogus").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Hand a second launch over to the running instance.
+//!
+//! The single-instance plugin keeps the second process from starting and
+//! gives us its arguments and working directory. A
+//! `gmplayer://notification/...` link is a notification button and goes to
+//! [`notifications`]. Otherwise the main window is brought
+//! forward and told with `second-instance`. Audio files among the arguments
+//! go to [`local_files`] to be enqueued; the first audio file or
+//! `gmplayer://` link is parked under [`PENDING_OPEN_KEY`] in the payload
+//! cache until the frontend takes it with `take_pending_open`.
+
+use std::path::Path;
+
+use log::{info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::desktop::window::manager;
+use crate::desktop::window::payload::PayloadCache;
+use crate::desktop::{local_files, notifications};
+
+pub const SECOND_INSTANCE_EVENT: &str = "second-instance";
+pub const PENDING_OPEN_KEY: &str = "pending-open";
+const URL_SCHEME: &str = "gmplayer://";
+
+/// Payload of `second-instance`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstance {
+    /// Arguments after the executable path.
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PendingOpen {
+    /// Absolute path of an audio file.
+    File {
+        path: String,
+    },
+    Url {
+        url: String,
+    },
+}
+
+pub(crate) fn is_app_link(arg: &str) -> bool {
+    arg.get(..URL_SCHEME.len())
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case(URL_SCHEME))
+}
+
+/// The first audio file or `gmplayer://` link in `args`. Relative paths are
+/// taken from `cwd`, the second launch's working directory.
+fn pending_open(args: &[String], cwd: &Path) -> Option<PendingOpen> {
+    args.iter().find_map(|arg| {
+        if is_app_link(arg) {
+            return Some(PendingOpen::Url { url: arg.clone() });
+        }
+        if arg.starts_with('-') {
+            return None;
+        }
+        let path = cwd.join(arg);
+        (crate::library::is_audio_file(&path) && path.is_file()).then(|| PendingOpen::File {
+            path: path.to_string_lossy().into_owned(),
+        })
+    })
+}
+
+/// Single-instance callback: runs in the first instance for each later launch.
+pub fn handle(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let args: Vec<String> = argv.into_iter().skip(1).collect();
+    info!("Second instance launched with {:?}", args);
+    // A notification button pressed from the Action Center.
+    if let Some(action) = args.iter().find_map(|arg| notifications::link_action(arg)) {
+        notifications::activated(app, action);
+        return;
+    }
+    local_files::open_args(app, &args, Path::new(&cwd));
+    if let Some(open) = pending_open(&args, Path::new(&cwd)) {
+        match serde_json::to_value(&open) {
+            Ok(value) => PayloadCache::set(PENDING_OPEN_KEY, value),
+            Err(e) => warn!("Failed to park {:?}: {}", open, e),
+        }
+    }
+    // Focus also shows: main may never have been shown when the first
+    // instance was started with --hidden / --window.
+    if let Err(e) = manager::focus_window(app, "main") {
+        warn!("Failed to focus main window for second instance: {}", e);
+    }
+    let _ = app.emit_to("main", SECOND_INSTANCE_EVENT, SecondInstance { args, cwd });
+}
+
+/// Take the file or link a second launch asked to open, if any.
+#[tauri::command]
+pub fn take_pending_open() -> Option<PendingOpen> {
+    PayloadCache::take(PENDING_OPEN_KEY).and_then(|value| serde_json::from_value(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_links_and_existing_audio_files_but_not_flags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        std::fs::write(dir.join("song.FLAC"), b"").unwrap();
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            pending_open(&args(&["--hidden", "missing.mp3", "song.FLAC"]), dir),
+            Some(PendingOpen::File {
+                path: dir.join("song.FLAC").to_string_lossy().into_owned()
+            })
+        );
+        assert_eq!(
+            pending_open(&args(&["GMPlayer://song/42"]), dir),
+            Some(PendingOpen::Url {
+                url: "GMPlayer://song/42".into()
+            })
+        );
+        assert_eq!(pending_open(&args(&["notes.txt", "--window"]), dir), None);
+    }
+}
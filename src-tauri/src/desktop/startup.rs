@@ -0,0 +1,120 @@
+//! Command-line options that change which window appears at launch.
+//!
+//! `--hidden` keeps every window hidden (tray only); `--window <label>`
+//! opens a preset such as `mini-player` or `desktop-lyrics` instead of the
+//! main window. The main window is still created hidden in both cases
+//! because it owns the player; the tray and a second launch show it later.
+
+use std::sync::OnceLock;
+
+use log::warn;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::desktop::window::config::WindowConfig;
+
+pub const HIDDEN_FLAG: &str = "--hidden";
+/// Presets that need an anchor or a parent and cannot be opened on their own.
+const NON_STARTUP_LABELS: [&str; 3] = ["tray-popup", "osd", "desktop-lyrics-controls"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "mode", content = "label", rename_all = "camelCase")]
+pub enum StartupMode {
+    Normal,
+    Hidden,
+    /// A preset window opened in place of main.
+    Window(String),
+}
+
+static STARTUP_MODE: OnceLock<StartupMode> = OnceLock::new();
+
+/// Parse launch arguments (without the program name). Unknown arguments are
+/// ignored; they may belong to the webview or the single-instance plugin.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> StartupMode {
+    let mut hidden = false;
+    let mut window = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == HIDDEN_FLAG {
+            hidden = true;
+        } else if arg == "--window" {
+            match args.next() {
+                Some(label) => window = Some(label),
+                None => warn!("--window requires a window label"),
+            }
+        } else if let Some(label) = arg.strip_prefix("--window=") {
+            window = Some(label.to_string());
+        }
+    }
+
+    if hidden {
+        return StartupMode::Hidden;
+    }
+    match window {
+        Some(label) if label == "main" => StartupMode::Normal,
+        Some(label)
+            if WindowConfig::from_label(&label).is_some()
+                && !NON_STARTUP_LABELS.contains(&label.as_str()) =>
+        {
+            StartupMode::Window(label)
+        }
+        Some(label) => {
+            warn!("Ignoring --window '{}': not a startup window preset", label);
+            StartupMode::Normal
+        }
+        None => StartupMode::Normal,
+    }
+}
+
+/// Record the launch mode. Only the first call has an effect.
+pub fn init(mode: StartupMode) -> &'static StartupMode {
+    STARTUP_MODE.get_or_init(|| mode)
+}
+
+pub fn startup_mode() -> &'static StartupMode {
+    STARTUP_MODE.get_or_init(|| StartupMode::Normal)
+}
+
+/// Whether the main window was left hidden at launch.
+pub fn was_started_hidden() -> bool {
+    *startup_mode() != StartupMode::Normal
+}
+
+#[tauri::command]
+pub fn get_startup_mode() -> StartupMode {
+    startup_mode().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> StartupMode {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn window_flag_selects_a_preset() {
+        assert_eq!(parse(&[]), StartupMode::Normal);
+        assert_eq!(
+            parse(&["--window", "mini-player"]),
+            StartupMode::Window("mini-player".into())
+        );
+        assert_eq!(
+            parse(&["--window=desktop-lyrics"]),
+            StartupMode::Window("desktop-lyrics".into())
+        );
+        assert_eq!(parse(&["--window", "main"]), StartupMode::Normal);
+    }
+
+    #[test]
+    fn hidden_wins_and_invalid_labels_fall_back() {
+        assert_eq!(
+            parse(&["--window", "mini-player", "--hidden"]),
+            StartupMode::Hidden
+        );
+        assert_eq!(parse(&["--window", "nope"]), StartupMode::Normal);
+        assert_eq!(parse(&["--window", "tray-popup"]), StartupMode::Normal);
+        assert_eq!(parse(&["--window"]), StartupMode::Normal);
+    }
+}
@@ -0,0 +1,753 @@
+//! Volume and mute state of the system's default output device.
+//!
+//! This is the device the OS mixer controls, not GMPlayer's own volume:
+//! muting it silences every app. Windows goes through the default render
+//! endpoint's `IAudioEndpointVolume`, macOS through the default output
+//! device's CoreAudio properties, and Linux through PulseAudio's `pactl`
+//! (PipeWire answers it too). Other platforms report `Unsupported` and
+//! callers carry on without it.
+//!
+//! Changes made anywhere, the OS mixer included, are announced with
+//! `system-volume-changed`, at most every 50 ms so a dragged slider does
+//! not flood the webviews.
+
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use log::info;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub const SYSTEM_VOLUME_CHANGED_EVENT: &str = "system-volume-changed";
+
+const MIN_EVENT_GAP: Duration = Duration::from_millis(50);
+
+/// Payload of `system-volume-changed`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemVolume {
+    /// 0.0–1.0.
+    pub level: f64,
+    pub muted: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum VolumeError {
+    /// Not implemented for this platform or sound server.
+    Unsupported(String),
+    Failed(String),
+}
+
+impl fmt::Display for VolumeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(message) => write!(f, "{}", message),
+            Self::Failed(message) => write!(f, "System volume error: {}", message),
+        }
+    }
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+struct Throttle {
+    last: Option<Instant>,
+    /// An event is already on its way and will read the latest state.
+    pending: bool,
+}
+
+static THROTTLE: Mutex<Throttle> = Mutex::new(Throttle {
+    last: None,
+    pending: false,
+});
+
+/// Whether the default output device is muted.
+pub fn output_muted() -> Result<bool, VolumeError> {
+    imp::muted()
+}
+
+/// Mute or unmute the default output device.
+pub fn set_output_muted(muted: bool) -> Result<(), VolumeError> {
+    imp::set_muted(muted)
+}
+
+/// Start announcing changes. Call once from setup.
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    if let Err(e) = imp::watch() {
+        info!("System volume changes will not be reported: {}", e);
+    }
+}
+
+/// How long an event must wait to stay `MIN_EVENT_GAP` after the last one.
+fn hold_back(last: Option<Instant>, now: Instant) -> Duration {
+    last.map_or(Duration::ZERO, |last| {
+        MIN_EVENT_GAP.saturating_sub(now.saturating_duration_since(last))
+    })
+}
+
+/// The platform saw a change; emit it, or schedule it if one just went out.
+fn note_changed() {
+    let wait = {
+        let mut throttle = THROTTLE.lock();
+        if throttle.pending {
+            return;
+        }
+        throttle.pending = true;
+        hold_back(throttle.last, Instant::now())
+    };
+    if wait.is_zero() {
+        emit_current();
+    } else {
+        std::thread::spawn(move || {
+            std::thread::sleep(wait);
+            emit_current();
+        });
+    }
+}
+
+fn emit_current() {
+    {
+        let mut throttle = THROTTLE.lock();
+        throttle.pending = false;
+        throttle.last = Some(Instant::now());
+    }
+    let (Some(app), Ok(level), Ok(muted)) = (APP.get(), imp::volume(), imp::muted()) else {
+        return;
+    };
+    let _ = app.emit(SYSTEM_VOLUME_CHANGED_EVENT, SystemVolume { level, muted });
+}
+
+/// Run a platform call off the async runtime; on Linux each one spawns
+/// `pactl`.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, VolumeError> + Send + 'static,
+) -> Result<T, VolumeError> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| VolumeError::Failed(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn get_system_volume() -> Result<f64, VolumeError> {
+    blocking(imp::volume).await
+}
+
+/// Set the output volume, clamped to 0.0–1.0. Resolves to the value set.
+#[tauri::command]
+pub async fn set_system_volume(level: f64) -> Result<f64, VolumeError> {
+    let level = if level.is_nan() {
+        return Err(VolumeError::Failed("volume is not a number".into()));
+    } else {
+        level.clamp(0.0, 1.0)
+    };
+    blocking(move || imp::set_volume(level)).await?;
+    Ok(level)
+}
+
+#[tauri::command]
+pub async fn get_system_muted() -> Result<bool, VolumeError> {
+    blocking(imp::muted).await
+}
+
+#[tauri::command]
+pub async fn set_system_muted(muted: bool) -> Result<(), VolumeError> {
+    blocking(move || imp::set_muted(muted)).await
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::core::implement;
+    use windows::Win32::Media::Audio::Endpoints::{
+        IAudioEndpointVolume, IAudioEndpointVolumeCallback, IAudioEndpointVolumeCallback_Impl,
+    };
+    use windows::Win32::Media::Audio::{
+        eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator, AUDIO_VOLUME_NOTIFICATION_DATA,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT,
+        COINIT_APARTMENTTHREADED, COINIT_MULTITHREADED,
+    };
+
+    use super::VolumeError;
+
+    fn failed(e: windows::core::Error) -> VolumeError {
+        VolumeError::Failed(e.to_string())
+    }
+
+    /// COM on the current thread, uninitialized again on drop. Interfaces
+    /// must be released before it goes.
+    struct Apartment {
+        entered: bool,
+    }
+
+    impl Apartment {
+        fn enter(model: COINIT) -> Self {
+            // SAFETY: plain COM call. `S_FALSE` (the thread already had COM)
+            // also counts as entered and needs its own `CoUninitialize`.
+            let entered = unsafe { CoInitializeEx(None, model) }.is_ok();
+            Self { entered }
+        }
+    }
+
+    impl Drop for Apartment {
+        fn drop(&mut self) {
+            if self.entered {
+                // SAFETY: balances the successful `CoInitializeEx` in `enter`.
+                unsafe { CoUninitialize() };
+            }
+        }
+    }
+
+    fn endpoint_volume() -> Result<IAudioEndpointVolume, VolumeError> {
+        // SAFETY: plain COM calls on a thread with an apartment.
+        unsafe {
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(failed)?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(failed)?;
+            device
+                .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+                .map_err(failed)
+        }
+    }
+
+    /// Run `f` on the default endpoint from a blocking-pool thread, which
+    /// enters an apartment for the call and leaves it afterwards.
+    fn with_endpoint<T>(
+        f: impl FnOnce(&IAudioEndpointVolume) -> windows::core::Result<T>,
+    ) -> Result<T, VolumeError> {
+        let _com = Apartment::enter(COINIT_APARTMENTTHREADED);
+        let volume = endpoint_volume()?;
+        f(&volume).map_err(failed)
+    }
+
+    pub(super) fn volume() -> Result<f64, VolumeError> {
+        // SAFETY: `volume` is a live endpoint interface.
+        with_endpoint(|volume| unsafe { volume.GetMasterVolumeLevelScalar() }).map(f64::from)
+    }
+
+    pub(super) fn set_volume(level: f64) -> Result<(), VolumeError> {
+        // SAFETY: as above; a null event context is allowed.
+        with_endpoint(|volume| unsafe {
+            volume.SetMasterVolumeLevelScalar(level as f32, std::ptr::null())
+        })
+    }
+
+    pub(super) fn muted() -> Result<bool, VolumeError> {
+        // SAFETY: as above.
+        with_endpoint(|volume| unsafe { volume.GetMute() }).map(|muted| muted.as_bool())
+    }
+
+    pub(super) fn set_muted(muted: bool) -> Result<(), VolumeError> {
+        // SAFETY: as above.
+        with_endpoint(|volume| unsafe { volume.SetMute(muted, std::ptr::null()) })
+    }
+
+    #[implement(IAudioEndpointVolumeCallback)]
+    struct VolumeCallback;
+
+    impl IAudioEndpointVolumeCallback_Impl for VolumeCallback_Impl {
+        fn OnNotify(
+            &self,
+            _data: *mut AUDIO_VOLUME_NOTIFICATION_DATA,
+        ) -> windows::core::Result<()> {
+            super::note_changed();
+            Ok(())
+        }
+    }
+
+    /// Register for endpoint notifications on a thread that keeps the
+    /// endpoint and the callback alive. A later switch of the default
+    /// device is not followed.
+    pub(super) fn watch() -> Result<(), VolumeError> {
+        std::thread::Builder::new()
+            .name("volume-watch".into())
+            .spawn(|| {
+                // This thread joins the MTA, where the endpoint calls the
+                // callback from its own threads. Left only if registering fails.
+                let _com = Apartment::enter(COINIT_MULTITHREADED);
+                let registered = endpoint_volume().and_then(|volume| {
+                    let callback: IAudioEndpointVolumeCallback = VolumeCallback.into();
+                    // SAFETY: both interfaces stay alive while this thread parks.
+                    unsafe { volume.RegisterControlChangeNotify(&callback) }.map_err(failed)?;
+                    Ok((volume, callback))
+                });
+                match registered {
+                    Ok(_alive) => loop {
+                        std::thread::park();
+                    },
+                    Err(e) => log::info!("System volume changes will not be reported: {}", e),
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| VolumeError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::c_void;
+
+    use parking_lot::Mutex;
+
+    use super::VolumeError;
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    type Listener = extern "C" fn(u32, u32, *const PropertyAddress, *mut c_void) -> i32;
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+        fn AudioObjectSetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: u32,
+            data: *const c_void,
+        ) -> i32;
+        fn AudioObjectAddPropertyListener(
+            object: u32,
+            address: *const PropertyAddress,
+            listener: Listener,
+            client_data: *mut c_void,
+        ) -> i32;
+        fn AudioObjectRemovePropertyListener(
+            object: u32,
+            address: *const PropertyAddress,
+            listener: Listener,
+            client_data: *mut c_void,
+        ) -> i32;
+    }
+
+    // The device's "virtual main volume" is only served through the
+    // (deprecated but working) AudioHardwareService calls.
+    #[link(name = "AudioToolbox", kind = "framework")]
+    extern "C" {
+        fn AudioHardwareServiceGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+        fn AudioHardwareServiceSetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: u32,
+            data: *const c_void,
+        ) -> i32;
+        fn AudioHardwareServiceAddPropertyListener(
+            object: u32,
+            address: *const PropertyAddress,
+            listener: Listener,
+            client_data: *mut c_void,
+        ) -> i32;
+        fn AudioHardwareServiceRemovePropertyListener(
+            object: u32,
+            address: *const PropertyAddress,
+            listener: Listener,
+            client_data: *mut c_void,
+        ) -> i32;
+    }
+
+    const SYSTEM_OBJECT: u32 = 1;
+    const ELEMENT_MAIN: u32 = 0;
+    const DEFAULT_OUTPUT_DEVICE: u32 = u32::from_be_bytes(*b"dOut");
+    const SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+    const SCOPE_OUTPUT: u32 = u32::from_be_bytes(*b"outp");
+    const MUTE: u32 = u32::from_be_bytes(*b"mute");
+    const VIRTUAL_MAIN_VOLUME: u32 = u32::from_be_bytes(*b"vmvc");
+
+    const DEFAULT_OUTPUT_ADDRESS: PropertyAddress = PropertyAddress {
+        selector: DEFAULT_OUTPUT_DEVICE,
+        scope: SCOPE_GLOBAL,
+        element: ELEMENT_MAIN,
+    };
+
+    const MUTE_ADDRESS: PropertyAddress = PropertyAddress {
+        selector: MUTE,
+        scope: SCOPE_OUTPUT,
+        element: ELEMENT_MAIN,
+    };
+
+    const VOLUME_ADDRESS: PropertyAddress = PropertyAddress {
+        selector: VIRTUAL_MAIN_VOLUME,
+        scope: SCOPE_OUTPUT,
+        element: ELEMENT_MAIN,
+    };
+
+    /// Device the volume and mute listeners are on, 0 for none.
+    static WATCHED_DEVICE: Mutex<u32> = Mutex::new(0);
+
+    fn check(status: i32) -> Result<(), VolumeError> {
+        match status {
+            0 => Ok(()),
+            status => Err(VolumeError::Failed(format!("CoreAudio error {}", status))),
+        }
+    }
+
+    fn default_output_device() -> Result<u32, VolumeError> {
+        let mut device = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: `device` and `size` describe a live u32 buffer.
+        check(unsafe {
+            AudioObjectGetPropertyData(
+                SYSTEM_OBJECT,
+                &DEFAULT_OUTPUT_ADDRESS,
+                0,
+                std::ptr::null(),
+                &mut size,
+                (&mut device as *mut u32).cast(),
+            )
+        })?;
+        match device {
+            0 => Err(VolumeError::Failed("No default output device".into())),
+            device => Ok(device),
+        }
+    }
+
+    pub(super) fn volume() -> Result<f64, VolumeError> {
+        let device = default_output_device()?;
+        let mut level = 0f32;
+        let mut size = std::mem::size_of::<f32>() as u32;
+        // SAFETY: `level` and `size` describe a live f32 buffer.
+        check(unsafe {
+            AudioHardwareServiceGetPropertyData(
+                device,
+                &VOLUME_ADDRESS,
+                0,
+                std::ptr::null(),
+                &mut size,
+                (&mut level as *mut f32).cast(),
+            )
+        })?;
+        Ok(f64::from(level))
+    }
+
+    pub(super) fn set_volume(level: f64) -> Result<(), VolumeError> {
+        let device = default_output_device()?;
+        let value = level as f32;
+        // SAFETY: `value` is a live f32 for the duration of the call.
+        check(unsafe {
+            AudioHardwareServiceSetPropertyData(
+                device,
+                &VOLUME_ADDRESS,
+                0,
+                std::ptr::null(),
+                std::mem::size_of::<f32>() as u32,
+                (&value as *const f32).cast(),
+            )
+        })
+    }
+
+    pub(super) fn muted() -> Result<bool, VolumeError> {
+        let device = default_output_device()?;
+        let mut muted = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: as in `default_output_device`.
+        check(unsafe {
+            AudioObjectGetPropertyData(
+                device,
+                &MUTE_ADDRESS,
+                0,
+                std::ptr::null(),
+                &mut size,
+                (&mut muted as *mut u32).cast(),
+            )
+        })?;
+        Ok(muted != 0)
+    }
+
+    pub(super) fn set_muted(muted: bool) -> Result<(), VolumeError> {
+        let device = default_output_device()?;
+        let value = u32::from(muted);
+        // SAFETY: `value` is a live u32 for the duration of the call.
+        check(unsafe {
+            AudioObjectSetPropertyData(
+                device,
+                &MUTE_ADDRESS,
+                0,
+                std::ptr::null(),
+                std::mem::size_of::<u32>() as u32,
+                (&value as *const u32).cast(),
+            )
+        })
+    }
+
+    extern "C" fn changed(_: u32, _: u32, _: *const PropertyAddress, _: *mut c_void) -> i32 {
+        super::note_changed();
+        0
+    }
+
+    extern "C" fn device_changed(_: u32, _: u32, _: *const PropertyAddress, _: *mut c_void) -> i32 {
+        follow_default_device();
+        super::note_changed();
+        0
+    }
+
+    /// Move the volume and mute listeners to the current default device.
+    fn follow_default_device() {
+        let Ok(device) = default_output_device() else {
+            return;
+        };
+        let mut watched = WATCHED_DEVICE.lock();
+        if *watched == device {
+            return;
+        }
+        // SAFETY: listeners are added and removed with the same function
+        // and address; the callbacks touch no client data.
+        unsafe {
+            if *watched != 0 {
+                AudioHardwareServiceRemovePropertyListener(
+                    *watched,
+                    &VOLUME_ADDRESS,
+                    changed,
+                    std::ptr::null_mut(),
+                );
+                AudioObjectRemovePropertyListener(
+                    *watched,
+                    &MUTE_ADDRESS,
+                    changed,
+                    std::ptr::null_mut(),
+                );
+            }
+            AudioHardwareServiceAddPropertyListener(
+                device,
+                &VOLUME_ADDRESS,
+                changed,
+                std::ptr::null_mut(),
+            );
+            AudioObjectAddPropertyListener(device, &MUTE_ADDRESS, changed, std::ptr::null_mut());
+        }
+        *watched = device;
+    }
+
+    pub(super) fn watch() -> Result<(), VolumeError> {
+        // SAFETY: see `follow_default_device`.
+        check(unsafe {
+            AudioObjectAddPropertyListener(
+                SYSTEM_OBJECT,
+                &DEFAULT_OUTPUT_ADDRESS,
+                device_changed,
+                std::ptr::null_mut(),
+            )
+        })?;
+        follow_default_device();
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::io::{BufRead, BufReader, ErrorKind};
+    use std::process::{Command, Stdio};
+
+    use super::VolumeError;
+
+    const DEFAULT_SINK: &str = "@DEFAULT_SINK@";
+    /// PulseAudio's 100%.
+    const VOLUME_NORM: f64 = 65536.0;
+
+    /// `pactl` with its output in English: the parsers below match its
+    /// untranslated words (`Mute: yes`, `Event 'change'`).
+    fn command() -> Command {
+        let mut command = Command::new("pactl");
+        command.env("LC_ALL", "C");
+        command
+    }
+
+    fn pactl(args: &[&str]) -> Result<String, VolumeError> {
+        let output = command().args(args).output().map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                VolumeError::Unsupported("System volume needs PulseAudio's pactl".into())
+            } else {
+                VolumeError::Failed(e.to_string())
+            }
+        })?;
+        if !output.status.success() {
+            return Err(VolumeError::Failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Average channel volume from `pactl get-sink-volume`, e.g.
+    /// `Volume: front-left: 32768 /  50% / -18.06 dB,   front-right: ...`.
+    pub(super) fn parse_volume(output: &str) -> Option<f64> {
+        let first = output.lines().next()?;
+        let raw: Vec<f64> = first
+            .split(',')
+            .filter_map(|channel| {
+                let value = channel.rsplit(':').next()?;
+                value.split('/').next()?.trim().parse().ok()
+            })
+            .collect();
+        if raw.is_empty() {
+            return None;
+        }
+        let average = raw.iter().sum::<f64>() / raw.len() as f64;
+        Some((average / VOLUME_NORM).clamp(0.0, 1.0))
+    }
+
+    /// `Mute: yes` / `Mute: no` from `pactl get-sink-mute`.
+    pub(super) fn parse_muted(output: &str) -> Option<bool> {
+        match output.trim().strip_prefix("Mute:")?.trim() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// `pactl subscribe` lines that can mean a new volume or mute state:
+    /// any sink change, or the server switching the default sink.
+    pub(super) fn is_volume_event(line: &str) -> bool {
+        line.starts_with("Event 'change' on sink #") || line.starts_with("Event 'change' on server")
+    }
+
+    fn unreadable(output: &str) -> VolumeError {
+        VolumeError::Failed(format!("unexpected pactl output: {}", output.trim()))
+    }
+
+    pub(super) fn volume() -> Result<f64, VolumeError> {
+        let output = pactl(&["get-sink-volume", DEFAULT_SINK])?;
+        parse_volume(&output).ok_or_else(|| unreadable(&output))
+    }
+
+    pub(super) fn set_volume(level: f64) -> Result<(), VolumeError> {
+        let raw = ((level * VOLUME_NORM).round() as u32).to_string();
+        pactl(&["set-sink-volume", DEFAULT_SINK, &raw]).map(|_| ())
+    }
+
+    pub(super) fn muted() -> Result<bool, VolumeError> {
+        let output = pactl(&["get-sink-mute", DEFAULT_SINK])?;
+        parse_muted(&output).ok_or_else(|| unreadable(&output))
+    }
+
+    pub(super) fn set_muted(muted: bool) -> Result<(), VolumeError> {
+        let value = if muted { "1" } else { "0" };
+        pactl(&["set-sink-mute", DEFAULT_SINK, value]).map(|_| ())
+    }
+
+    /// Follow `pactl subscribe` on a background thread. The child exits on
+    /// its own once we are gone and its output has nowhere to go.
+    pub(super) fn watch() -> Result<(), VolumeError> {
+        volume()?;
+        let mut child = command()
+            .arg("subscribe")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| VolumeError::Failed(e.to_string()))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        std::thread::Builder::new()
+            .name("volume-watch".into())
+            .spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if is_volume_event(&line) {
+                        super::note_changed();
+                    }
+                }
+                let _ = child.wait();
+            })
+            .map(|_| ())
+            .map_err(|e| VolumeError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use super::VolumeError;
+
+    fn unsupported<T>() -> Result<T, VolumeError> {
+        Err(VolumeError::Unsupported(
+            "System volume is not supported on this platform".into(),
+        ))
+    }
+
+    pub(super) fn volume() -> Result<f64, VolumeError> {
+        unsupported()
+    }
+
+    pub(super) fn set_volume(_level: f64) -> Result<(), VolumeError> {
+        unsupported()
+    }
+
+    pub(super) fn muted() -> Result<bool, VolumeError> {
+        unsupported()
+    }
+
+    pub(super) fn set_muted(_muted: bool) -> Result<(), VolumeError> {
+        unsupported()
+    }
+
+    pub(super) fn watch() -> Result<(), VolumeError> {
+        unsupported()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_held_back_to_the_minimum_gap() {
+        let now = Instant::now();
+        assert_eq!(hold_back(None, now), Duration::ZERO);
+        assert_eq!(hold_back(Some(now), now), MIN_EVENT_GAP);
+        assert_eq!(
+            hold_back(Some(now), now + Duration::from_millis(20)),
+            Duration::from_millis(30)
+        );
+        assert_eq!(
+            hold_back(Some(now), now + MIN_EVENT_GAP * 3),
+            Duration::ZERO
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pactl_output_is_parsed() {
+        let volume = "Volume: front-left: 32768 /  50% / -18.06 dB,   \
+                      front-right: 49152 /  75% / -7.50 dB\n        balance 0.20\n";
+        assert_eq!(imp::parse_volume(volume), Some(0.625));
+        assert_eq!(
+            imp::parse_volume("Volume: mono: 98304 / 150% / 10.57 dB\n"),
+            Some(1.0)
+        );
+        assert_eq!(imp::parse_volume("garbage"), None);
+
+        assert_eq!(imp::parse_muted("Mute: yes\n"), Some(true));
+        assert_eq!(imp::parse_muted("Mute: no\n"), Some(false));
+        assert_eq!(imp::parse_muted("Failure"), None);
+
+        assert!(imp::is_volume_event("Event 'change' on sink #56"));
+        assert!(imp::is_volume_event("Event 'change' on server #-1"));
+        assert!(!imp::is_volume_event("Event 'change' on sink-input #12"));
+        assert!(!imp::is_volume_event("Event 'new' on client #90"));
+    }
+}
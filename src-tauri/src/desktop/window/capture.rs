@@ -0,0 +1,556 @@
+//! Window snapshot capture ("share as image" for the now-playing card).
+//!
+//! - Windows: `PrintWindow` with `PW_RENDERFULLCONTENT`, which renders the
+//!   window's own content and therefore works while it is partially covered.
+//! - macOS: `CGWindowListCreateImage` restricted to the window, which also
+//!   ignores whatever is stacked on top of it.
+//! - Linux/X11: `XGetImage` on the window. Without a compositor, parts
+//!   covered by other windows come back as whatever covers them.
+//! - Linux/Wayland: unsupported. Wayland does not let a client read back its
+//!   own composited surface outside the screencast portal.
+//!
+//! Windows with content protection enabled are never captured. Reading the
+//! pixels, encoding the PNG and writing a large one to disk all happen on the
+//! blocking pool.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+/// Captures larger than this are written to a temp file instead of being
+/// sent back over IPC.
+pub const INLINE_CAPTURE_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// Labels whose content protection was turned on at runtime.
+static CONTENT_PROTECTED: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Record a window's content protection state so captures can refuse it.
+pub fn note_content_protection(label: &str, protected: bool) {
+    let mut labels = CONTENT_PROTECTED.lock();
+    if protected {
+        labels.insert(label.to_string());
+    } else {
+        labels.remove(label);
+    }
+}
+
+/// Crop rectangle in logical pixels, relative to the window's client area.
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PixelRect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WindowCapture {
+    /// PNG bytes returned inline.
+    Png {
+        width: u32,
+        height: u32,
+        bytes: Vec<u8>,
+    },
+    /// PNG written to a temp file because it exceeded [`INLINE_CAPTURE_MAX_BYTES`].
+    File {
+        width: u32,
+        height: u32,
+        path: PathBuf,
+    },
+}
+
+/// Tightly packed RGBA pixels.
+struct RgbaImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+/// Convert a logical region to physical pixels, clamped to the image.
+/// Returns `None` when nothing of the region is left.
+fn physical_region(
+    region: &CaptureRegion,
+    scale_factor: f64,
+    image_width: u32,
+    image_height: u32,
+) -> Option<PixelRect> {
+    let values = [
+        region.x,
+        region.y,
+        region.width,
+        region.height,
+        scale_factor,
+    ];
+    if values.iter().any(|v| !v.is_finite()) || scale_factor <= 0.0 {
+        return None;
+    }
+    let left = (region.x * scale_factor).round().max(0.0);
+    let top = (region.y * scale_factor).round().max(0.0);
+    let right = ((region.x + region.width) * scale_factor)
+        .round()
+        .min(image_width as f64);
+    let bottom = ((region.y + region.height) * scale_factor)
+        .round()
+        .min(image_height as f64);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some(PixelRect {
+        x: left as u32,
+        y: top as u32,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+    })
+}
+
+fn crop(image: &RgbaImage, rect: PixelRect) -> RgbaImage {
+    let row_bytes = image.width as usize * 4;
+    let mut pixels = Vec::with_capacity(rect.width as usize * rect.height as usize * 4);
+    for row in rect.y..rect.y + rect.height {
+        let start = row as usize * row_bytes + rect.x as usize * 4;
+        pixels.extend_from_slice(&image.pixels[start..start + rect.width as usize * 4]);
+    }
+    RgbaImage {
+        width: rect.width,
+        height: rect.height,
+        pixels,
+    }
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(&image.pixels)
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Convert a bottom-up or top-down BGRA buffer with row padding to RGBA.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn bgra_to_rgba(data: &[u8], width: u32, height: u32, stride: usize) -> RgbaImage {
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in 0..height as usize {
+        let line = &data[row * stride..row * stride + width as usize * 4];
+        for px in line.chunks_exact(4) {
+            pixels.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+    }
+    RgbaImage {
+        width,
+        height,
+        pixels,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_content_protected(window: &WebviewWindow) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{GetWindowDisplayAffinity, WDA_NONE};
+
+    let Ok(hwnd) = window.hwnd() else {
+        return false;
+    };
+    let mut affinity = WDA_NONE.0;
+    // SAFETY: valid HWND from tauri and a valid out pointer.
+    let ok = unsafe { GetWindowDisplayAffinity(HWND(hwnd.0 as _), &mut affinity) }.is_ok();
+    ok && affinity != WDA_NONE.0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_content_protected(_window: &WebviewWindow) -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn capture_pixels(window: &WebviewWindow) -> Result<RgbaImage, String> {
+    use std::ffi::c_void;
+    use std::ptr::null_mut;
+
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, GetDC, ReleaseDC,
+        SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS, PW_CLIENTONLY};
+    use windows::Win32::UI::WindowsAndMessaging::PW_RENDERFULLCONTENT;
+
+    let hwnd = HWND(window.hwnd().map_err(|e| e.to_string())?.0 as _);
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    if size.width == 0 || size.height == 0 {
+        return Err("Window has no visible area".into());
+    }
+
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: size.width as i32,
+            // Negative height: top-down rows.
+            biHeight: -(size.height as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // SAFETY: every GDI object created here is released before returning,
+    // and `bits` is only read while the DIB section is alive.
+    unsafe {
+        let screen_dc = GetDC(Some(hwnd));
+        let mem_dc = CreateCompatibleDC(Some(screen_dc));
+        let mut bits: *mut c_void = null_mut();
+        let result = match CreateDIBSection(Some(mem_dc), &info, DIB_RGB_COLORS, &mut bits, None, 0)
+        {
+            Ok(bitmap) if !bits.is_null() => {
+                let previous = SelectObject(mem_dc, bitmap.into());
+                let flags = PRINT_WINDOW_FLAGS(PW_CLIENTONLY.0 | PW_RENDERFULLCONTENT);
+                let result = if PrintWindow(hwnd, mem_dc, flags).as_bool() {
+                    let stride = size.width as usize * 4;
+                    let data = std::slice::from_raw_parts(
+                        bits as *const u8,
+                        stride * size.height as usize,
+                    );
+                    let mut image = bgra_to_rgba(data, size.width, size.height, stride);
+                    // GDI leaves alpha at 0 for opaque content.
+                    for px in image.pixels.chunks_exact_mut(4) {
+                        px[3] = 255;
+                    }
+                    Ok(image)
+                } else {
+                    Err("PrintWindow failed".to_string())
+                };
+                SelectObject(mem_dc, previous);
+                let _ = DeleteObject(bitmap.into());
+                result
+            }
+            Ok(bitmap) => {
+                let _ = DeleteObject(bitmap.into());
+                Err("CreateDIBSection failed".to_string())
+            }
+            Err(e) => Err(format!("CreateDIBSection failed: {e}")),
+        };
+        let _ = DeleteDC(mem_dc);
+        ReleaseDC(Some(hwnd), screen_dc);
+        result
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn capture_pixels(window: &WebviewWindow) -> Result<RgbaImage, String> {
+    use core_graphics::geometry::CGRect;
+    use core_graphics::window::{
+        create_image, kCGWindowImageBestResolution, kCGWindowImageBoundsIgnoreFraming,
+        kCGWindowListOptionIncludingWindow,
+    };
+    use objc2_app_kit::NSWindowSharingType;
+
+    use crate::desktop::window::platform::macos;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        static CGRectNull: CGRect;
+    }
+
+    let ns_window = macos::ns_window(window).map_err(|e| e.to_string())?;
+    if ns_window.sharingType() == NSWindowSharingType::None {
+        return Err("Window content is protected".into());
+    }
+    let window_number = ns_window.windowNumber();
+
+    // SAFETY: reading an immutable CoreGraphics constant.
+    let bounds = unsafe { CGRectNull };
+    let image = create_image(
+        bounds,
+        kCGWindowListOptionIncludingWindow,
+        window_number as u32,
+        kCGWindowImageBoundsIgnoreFraming | kCGWindowImageBestResolution,
+    )
+    .ok_or_else(|| "CGWindowListCreateImage failed (screen recording permission?)".to_string())?;
+    if image.bits_per_pixel() != 32 {
+        return Err(format!(
+            "Unsupported capture pixel format ({} bpp)",
+            image.bits_per_pixel()
+        ));
+    }
+    let data = image.data();
+    Ok(bgra_to_rgba(
+        data.bytes(),
+        image.width() as u32,
+        image.height() as u32,
+        image.bytes_per_row(),
+    ))
+}
+
+/// Convert 32 bpp pixels laid out by channel masks (as in an `XImage`) to
+/// RGBA. With `alpha`, the bits outside the color masks carry alpha.
+#[cfg(target_os = "linux")]
+fn masked_to_rgba(
+    data: &[u8],
+    (width, height, stride): (u32, u32, usize),
+    [red, green, blue]: [u32; 3],
+    big_endian: bool,
+    alpha: bool,
+) -> RgbaImage {
+    let channel = |pixel: u32, mask: u32| match mask {
+        0 => 0,
+        mask => ((pixel & mask) >> mask.trailing_zeros()) as u8,
+    };
+    let alpha_mask = !(red | green | blue);
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in 0..height as usize {
+        let line = &data[row * stride..row * stride + width as usize * 4];
+        for px in line.chunks_exact(4) {
+            let bytes = [px[0], px[1], px[2], px[3]];
+            let pixel = if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            };
+            pixels.extend_from_slice(&[
+                channel(pixel, red),
+                channel(pixel, green),
+                channel(pixel, blue),
+                if alpha {
+                    channel(pixel, alpha_mask)
+                } else {
+                    255
+                },
+            ]);
+        }
+    }
+    RgbaImage {
+        width,
+        height,
+        pixels,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn capture_pixels(window: &WebviewWindow) -> Result<RgbaImage, String> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    use x11_dl::xlib::{self, Xlib};
+
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"));
+    if wayland {
+        return Err("Window capture is not supported on Wayland".into());
+    }
+    let xid = match window.window_handle().map(|handle| handle.as_raw()) {
+        Ok(RawWindowHandle::Xlib(handle)) => handle.window,
+        Ok(_) => return Err("Window is not an X11 window".into()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let xlib = Xlib::open().map_err(|e| format!("libX11 is unavailable: {}", e))?;
+    // SAFETY: a null name opens the display named by $DISPLAY; the
+    // connection and the image are released before returning, and the image
+    // data is only read while the image is alive.
+    unsafe {
+        let display = (xlib.XOpenDisplay)(std::ptr::null());
+        if display.is_null() {
+            return Err("Cannot open the X display".into());
+        }
+        let result = (|| {
+            let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+            if (xlib.XGetWindowAttributes)(display, xid, &mut attributes) == 0 {
+                return Err("XGetWindowAttributes failed".to_string());
+            }
+            if attributes.map_state != xlib::IsViewable {
+                return Err("Window is not visible".to_string());
+            }
+            let (width, height) = (attributes.width as u32, attributes.height as u32);
+            if width == 0 || height == 0 {
+                return Err("Window has no visible area".to_string());
+            }
+            let image = (xlib.XGetImage)(display, xid, 0, 0, width, height, !0, xlib::ZPixmap);
+            if image.is_null() {
+                return Err("XGetImage failed".to_string());
+            }
+            let ximage = &*image;
+            let result = if ximage.bits_per_pixel == 32 {
+                let stride = ximage.bytes_per_line as usize;
+                let data = std::slice::from_raw_parts(
+                    ximage.data as *const u8,
+                    stride * ximage.height as usize,
+                );
+                Ok(masked_to_rgba(
+                    data,
+                    (ximage.width as u32, ximage.height as u32, stride),
+                    [
+                        ximage.red_mask as u32,
+                        ximage.green_mask as u32,
+                        ximage.blue_mask as u32,
+                    ],
+                    ximage.byte_order == xlib::MSBFirst,
+                    ximage.depth == 32,
+                ))
+            } else {
+                Err(format!(
+                    "Unsupported capture pixel format ({} bpp)",
+                    ximage.bits_per_pixel
+                ))
+            };
+            (xlib.XDestroyImage)(image);
+            result
+        })();
+        (xlib.XCloseDisplay)(display);
+        result
+    }
+}
+
+/// Capture a window's content as PNG, optionally cropped to a logical region.
+#[tauri::command]
+pub async fn capture_window(
+    app: AppHandle,
+    label: String,
+    region: Option<CaptureRegion>,
+) -> Result<WindowCapture, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    if CONTENT_PROTECTED.lock().contains(&label) || is_content_protected(&window) {
+        return Err(format!("Window '{}' has content protection enabled", label));
+    }
+
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || capture(&window, &label, region, scale))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn capture(
+    window: &WebviewWindow,
+    label: &str,
+    region: Option<CaptureRegion>,
+    scale: f64,
+) -> Result<WindowCapture, String> {
+    let mut image = capture_pixels(window)?;
+    if let Some(region) = region {
+        let rect = physical_region(&region, scale, image.width, image.height)
+            .ok_or_else(|| "Capture region is outside the window".to_string())?;
+        image = crop(&image, rect);
+    }
+
+    let bytes = encode_png(&image)?;
+    if bytes.len() <= INLINE_CAPTURE_MAX_BYTES {
+        return Ok(WindowCapture::Png {
+            width: image.width,
+            height: image.height,
+            bytes,
+        });
+    }
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("gmplayer-capture-{}-{}.png", label, stamp));
+    std::fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+    Ok(WindowCapture::File {
+        width: image.width,
+        height: image.height,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: f64, y: f64, width: f64, height: f64) -> CaptureRegion {
+        CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn logical_region_is_scaled_and_clamped() {
+        let rect = physical_region(&region(10.0, 20.0, 100.0, 50.0), 1.5, 1000, 1000).unwrap();
+        assert_eq!(
+            rect,
+            PixelRect {
+                x: 15,
+                y: 30,
+                width: 150,
+                height: 75
+            }
+        );
+
+        let clamped = physical_region(&region(-10.0, 90.0, 50.0, 50.0), 2.0, 100, 200).unwrap();
+        assert_eq!(
+            clamped,
+            PixelRect {
+                x: 0,
+                y: 180,
+                width: 80,
+                height: 20
+            }
+        );
+
+        assert!(physical_region(&region(200.0, 0.0, 10.0, 10.0), 1.0, 100, 100).is_none());
+        assert!(physical_region(&region(0.0, 0.0, f64::NAN, 10.0), 1.0, 100, 100).is_none());
+    }
+
+    #[test]
+    fn crop_and_encode_png() {
+        // 3x2 image where each pixel's red channel is its index.
+        let image = RgbaImage {
+            width: 3,
+            height: 2,
+            pixels: (0..6u8).flat_map(|i| [i, 0, 0, 255]).collect(),
+        };
+        let cropped = crop(
+            &image,
+            PixelRect {
+                x: 1,
+                y: 1,
+                width: 2,
+                height: 1,
+            },
+        );
+        assert_eq!(cropped.pixels, vec![4, 0, 0, 255, 5, 0, 0, 255]);
+
+        let png = encode_png(&cropped).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn x11_pixels_follow_the_channel_masks() {
+        let masks = [0x00ff_0000, 0x0000_ff00, 0x0000_00ff];
+        // One 24-bit pixel per row, one row padded to 8 bytes.
+        let little = [0x30, 0x20, 0x10, 0x00, 0xee, 0xee, 0xee, 0xee];
+        let image = masked_to_rgba(&little, (1, 1, 8), masks, false, false);
+        assert_eq!(image.pixels, vec![0x10, 0x20, 0x30, 255]);
+
+        let big = [0x80, 0x10, 0x20, 0x30];
+        let image = masked_to_rgba(&big, (1, 1, 4), masks, true, true);
+        assert_eq!(image.pixels, vec![0x10, 0x20, 0x30, 0x80]);
+    }
+}
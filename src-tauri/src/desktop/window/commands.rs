@@ -1,31 +1,132 @@
-use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{command, AppHandle, Manager};
-use tauri_plugin_window_state::{AppHandleExt, StateFlags};
+use tauri::{command, AppHandle, Manager, WebviewWindow};
+use tauri_runtime::ResizeDirection;
 
-use crate::desktop::window::config::WindowConfig;
-use crate::desktop::window::manager;
-use crate::desktop::window::payload::PayloadCache;
+use crate::desktop::window::config::{WindowConfig, WindowConfigOverrides};
+use crate::desktop::window::coords;
+use crate::desktop::window::manager::{self, MonitorAnchor, MonitorInfo, ScreenPoint};
+use crate::desktop::window::payload::{PayloadCache, PayloadCacheStats};
+use crate::desktop::window::pin;
+use crate::desktop::window::presets;
+use crate::desktop::window::recently_closed;
+use crate::settings::window::CloseBehavior;
 
-#[derive(Debug, Serialize)]
+/// Every flag is `false` when the window does not exist.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowState {
     pub exists: bool,
     pub visible: bool,
+    pub focused: bool,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    /// Hidden from screen capture; see `set_content_protected`.
+    pub content_protected: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum WindowError {
+    /// No window has this label.
+    NotFound(String),
+    /// The platform rejected the request.
+    Failed(String),
+    /// The platform has no such feature.
+    Unsupported(String),
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(label) => write!(f, "Window '{}' not found", label),
+            Self::Failed(message) => write!(f, "Window operation failed: {}", message),
+            Self::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<tauri::Error> for WindowError {
+    fn from(e: tauri::Error) -> Self {
+        Self::Failed(e.to_string())
+    }
+}
+
+fn find_window(app: &AppHandle, label: &str) -> Result<WebviewWindow, WindowError> {
+    app.get_webview_window(label)
+        .ok_or_else(|| WindowError::NotFound(label.to_string()))
+}
+
+/// Edge or corner grabbed by `start_native_resize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResizeEdge {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl From<ResizeEdge> for ResizeDirection {
+    fn from(edge: ResizeEdge) -> Self {
+        match edge {
+            ResizeEdge::North => Self::North,
+            ResizeEdge::NorthEast => Self::NorthEast,
+            ResizeEdge::East => Self::East,
+            ResizeEdge::SouthEast => Self::SouthEast,
+            ResizeEdge::South => Self::South,
+            ResizeEdge::SouthWest => Self::SouthWest,
+            ResizeEdge::West => Self::West,
+            ResizeEdge::NorthWest => Self::NorthWest,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    pub label: String,
+    pub visible: bool,
+    pub always_on_top: bool,
 }
 
 /// Create a window from a preset label (e.g. "settings", "mini-player").
 #[tauri::command(rename_all = "snake_case")]
 pub async fn create_window(app: AppHandle, label: String) -> Result<(), String> {
-    let config = WindowConfig::from_label(&label)
-        .ok_or_else(|| format!("No preset found for label '{}'", label))?;
+    let config =
+        presets::lookup(&label).ok_or_else(|| format!("No preset found for label '{}'", label))?;
+    manager::create_window(&app, &config)
+}
+
+/// Create a window from a preset label with some fields changed, e.g. a
+/// different initial size. Single-instance handling follows the merged config.
+#[command]
+pub async fn create_window_with_overrides(
+    app: AppHandle,
+    label: String,
+    overrides: WindowConfigOverrides,
+) -> Result<(), String> {
+    let config = presets::lookup(&label)
+        .ok_or_else(|| format!("No preset found for label '{}'", label))?
+        .merge(overrides);
     manager::create_window(&app, &config)
 }
 
 /// Create a window with a fully custom configuration.
 #[command]
 pub async fn create_custom_window(app: AppHandle, config: WindowConfig) -> Result<(), String> {
-    manager::create_window(&app, &config)
+    manager::create_window(&app, &config)?;
+    recently_closed::remember_custom_config(&app, &config);
+    Ok(())
 }
 
 /// Create a window from a preset label, with an attached payload.
@@ -36,8 +137,8 @@ pub async fn create_window_with_payload(
     payload: Value,
 ) -> Result<(), String> {
     PayloadCache::set(&label, payload);
-    let config = WindowConfig::from_label(&label)
-        .ok_or_else(|| format!("No preset found for label '{}'", label))?;
+    let config =
+        presets::lookup(&label).ok_or_else(|| format!("No preset found for label '{}'", label))?;
     manager::create_window(&app, &config)
 }
 
@@ -59,6 +160,12 @@ pub async fn close_managed_window(app: AppHandle, label: String) -> Result<(), S
     manager::close_window(&app, &label)
 }
 
+/// Close behavior of a window; `main` when no label is given.
+#[command]
+pub fn get_close_behavior(app: AppHandle, label: Option<String>) -> CloseBehavior {
+    manager::close_behavior(&app, label.as_deref().unwrap_or("main"))
+}
+
 /// Toggle window visibility.
 #[command]
 pub async fn toggle_window(app: AppHandle, label: String) -> Result<(), String> {
@@ -71,16 +178,22 @@ pub async fn focus_window(app: AppHandle, label: String) -> Result<(), String> {
     manager::focus_window(&app, &label)
 }
 
-/// Get the state (exists, visible) of a window.
+/// Get the state (exists, visible, focused, ...) of a window.
 #[command]
 pub async fn get_window_state(app: AppHandle, label: String) -> Result<WindowState, String> {
-    let exists = manager::window_exists(&app, &label);
-    let visible = if exists {
-        manager::is_window_visible(&app, &label)?
-    } else {
-        false
-    };
-    Ok(WindowState { exists, visible })
+    Ok(manager::window_state(&app, &label))
+}
+
+/// States of all open windows, keyed by label.
+#[command]
+pub async fn get_all_window_states(app: AppHandle) -> BTreeMap<String, WindowState> {
+    manager::list_windows(&app)
+        .into_iter()
+        .map(|label| {
+            let state = manager::window_state(&app, &label);
+            (label, state)
+        })
+        .collect()
 }
 
 /// List all open window labels.
@@ -95,10 +208,18 @@ pub async fn open_window_devtools(app: AppHandle, label: String) -> Result<(), S
     manager::open_window_devtools(&app, &label)
 }
 
-/// Store a payload in the cache for a window label.
+/// Store a payload in the cache for a window label. With `ttl_secs` the
+/// payload is dropped after that long if no window takes it.
 #[command]
-pub async fn set_window_payload(label: String, payload: Value) -> Result<(), String> {
-    PayloadCache::set(&label, payload);
+pub async fn set_window_payload(
+    label: String,
+    payload: Value,
+    ttl_secs: Option<u64>,
+) -> Result<(), String> {
+    match ttl_secs {
+        Some(ttl_secs) => PayloadCache::set_with_ttl(&label, payload, ttl_secs),
+        None => PayloadCache::set(&label, payload),
+    }
     Ok(())
 }
 
@@ -114,6 +235,36 @@ pub async fn peek_window_payload(label: String) -> Option<Value> {
     PayloadCache::peek(&label)
 }
 
+/// Number and size of the payloads waiting to be taken.
+#[command]
+pub async fn payload_cache_stats() -> PayloadCacheStats {
+    PayloadCache::stats()
+}
+
+/// Maximize a window by label.
+#[command]
+pub async fn maximize_window(app: AppHandle, label: String) -> Result<(), String> {
+    manager::maximize_window(&app, &label)
+}
+
+/// Restore a maximized window.
+#[command]
+pub async fn unmaximize_window(app: AppHandle, label: String) -> Result<(), String> {
+    manager::unmaximize_window(&app, &label)
+}
+
+/// Minimize a window by label.
+#[command]
+pub async fn minimize_window(app: AppHandle, label: String) -> Result<(), String> {
+    manager::minimize_window(&app, &label)
+}
+
+/// Maximize or restore a window; resolves to the new maximized state.
+#[command]
+pub async fn toggle_maximize(app: AppHandle, label: String) -> Result<bool, String> {
+    manager::toggle_maximize(&app, &label)
+}
+
 /// Show a window at a specific screen position (physical pixels).
 #[command]
 pub async fn show_window_at_position(
@@ -122,10 +273,12 @@ pub async fn show_window_at_position(
     x: f64,
     y: f64,
 ) -> Result<(), String> {
+    coords::check_physical_call(&app, "show_window_at_position", &label, x, y);
     manager::show_window_at_position(&app, &label, x, y)
 }
 
 /// Update the native window effect tint color (e.g. Acrylic on Windows).
+/// Fails if the window was not created with an effect.
 #[command]
 pub async fn set_window_effect_color(
     app: AppHandle,
@@ -148,6 +301,30 @@ pub async fn set_ignore_cursor_events(
     manager::set_ignore_cursor_events(&app, &label, ignore)
 }
 
+/// Move a frameless window with the system drag loop. Call from a
+/// `pointerdown` handler instead of relying on `-webkit-app-region`, which
+/// WebKitGTK ignores and which eats clicks on buttons inside the region.
+#[command]
+pub async fn start_native_drag(app: AppHandle, label: String) -> Result<(), WindowError> {
+    find_window(&app, &label)?.start_dragging()?;
+    Ok(())
+}
+
+/// Resize a frameless window from the given edge or corner with the system
+/// resize loop, e.g. from the desktop-lyrics window's own resize handles.
+#[command]
+pub async fn start_native_resize(
+    app: AppHandle,
+    label: String,
+    direction: ResizeEdge,
+) -> Result<(), WindowError> {
+    find_window(&app, &label)?
+        .as_ref()
+        .window()
+        .start_resize_dragging(direction.into())?;
+    Ok(())
+}
+
 /// Resize a window to a logical size.
 #[command]
 pub async fn resize_window(
@@ -159,61 +336,48 @@ pub async fn resize_window(
     manager::resize_window(&app, &label, width, height)
 }
 
-/// Quit the application after saving window state.
+/// Quit the application: windows get `app-will-quit` and a grace period to
+/// ack it (skipped with `force`), then window state is saved.
 /// Excludes VISIBLE flag so a previous hide-to-tray state is not restored as a
 /// hidden main window on next launch.
 #[command]
-pub async fn quit_app(app: AppHandle) -> Result<(), String> {
-    let flags = StateFlags::SIZE
-        | StateFlags::POSITION
-        | StateFlags::MAXIMIZED
-        | StateFlags::FULLSCREEN
-        | StateFlags::DECORATIONS;
-    let _ = app.save_window_state(flags);
-    app.exit(0);
+pub async fn quit_app(app: AppHandle, force: Option<bool>) -> Result<(), String> {
+    crate::shutdown::quit(&app, force.unwrap_or(false));
     Ok(())
 }
 
-/// Get the current screen cursor position (physical pixels).
+/// Acknowledge a `main-close-requested` event so the fallback watchdog
+/// does not hide the main window on the frontend's behalf.
 #[command]
-pub fn get_cursor_position() -> Result<(i32, i32), String> {
-    #[cfg(target_os = "windows")]
-    {
-        use std::mem::MaybeUninit;
-        #[repr(C)]
-        struct POINT {
-            x: i32,
-            y: i32,
-        }
-        extern "system" {
-            fn GetCursorPos(lp_point: *mut POINT) -> i32;
-        }
-        unsafe {
-            let mut pt = MaybeUninit::<POINT>::uninit();
-            if GetCursorPos(pt.as_mut_ptr()) != 0 {
-                let pt = pt.assume_init();
-                Ok((pt.x, pt.y))
-            } else {
-                Err("GetCursorPos failed".into())
-            }
-        }
-    }
+pub fn ack_close_request() {
+    manager::ack_close_request();
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        use core_graphics::event::CGEvent;
-        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
-        let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
-            .map_err(|_| "Failed to create CGEventSource".to_string())?;
-        let event = CGEvent::new(source).map_err(|_| "Failed to create CGEvent".to_string())?;
-        let loc = event.location();
-        Ok((loc.x as i32, loc.y as i32))
-    }
+/// Save window geometry right away, e.g. before something that might
+/// crash the app.
+#[command]
+pub fn flush_window_state(app: AppHandle) -> Result<(), String> {
+    manager::flush_window_state(&app)
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        Err("get_cursor_position is not yet supported on Linux".into())
-    }
+/// Tell the hang watchdog the window `label` is alive. Windows send this
+/// every few seconds once their page is up.
+#[command]
+pub fn window_heartbeat(label: String) {
+    manager::heartbeat(&label);
+}
+
+/// Exempt a window from the hang watchdog, e.g. while it runs something
+/// long and blocking on purpose, or put it back under watch.
+#[command]
+pub fn set_watchdog_enabled(label: String, enabled: bool) {
+    manager::set_watchdog_enabled(&label, enabled);
+}
+
+/// Get the current screen cursor position (physical pixels; points on macOS).
+#[command]
+pub fn get_cursor_position(app: AppHandle) -> Result<(i32, i32), String> {
+    manager::cursor_position(&app)
 }
 
 /// Get a window's outer position and size (physical pixels).
@@ -229,3 +393,68 @@ pub async fn get_window_bounds(
     let size = window.outer_size().map_err(|e| e.to_string())?;
     Ok((pos.x, pos.y, size.width, size.height))
 }
+
+/// Pin or unpin a window (always on top).
+#[command]
+pub async fn set_always_on_top(app: AppHandle, label: String, on_top: bool) -> Result<(), String> {
+    manager::set_always_on_top(&app, &label, on_top)
+}
+
+/// Effective always-on-top value of a window; for a closed preset window,
+/// the value it will be created with.
+#[command]
+pub async fn get_always_on_top(app: AppHandle, label: String) -> Result<bool, String> {
+    pin::current(&app, &label)
+}
+
+/// Exclude a window from screenshots, recordings and screen sharing.
+#[command]
+pub async fn set_content_protected(
+    app: AppHandle,
+    label: String,
+    enabled: bool,
+) -> Result<(), WindowError> {
+    let window = find_window(&app, &label)?;
+    if !manager::CONTENT_PROTECTION_SUPPORTED {
+        return Err(WindowError::Unsupported(
+            "Content protection is not supported on this platform".into(),
+        ));
+    }
+    window.set_content_protected(enabled)?;
+    if let Some(protection) = app.try_state::<manager::ContentProtection>() {
+        protection.record(&label, enabled);
+    }
+    Ok(())
+}
+
+/// Describe an open window, including its tracked always-on-top state.
+#[command]
+pub async fn get_window_info(app: AppHandle, label: String) -> Result<WindowInfo, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let always_on_top = pin::current(&app, &label)?;
+    Ok(WindowInfo {
+        visible: window.is_visible().unwrap_or(false),
+        always_on_top,
+        label,
+    })
+}
+
+/// Connected monitors in physical pixels.
+#[command]
+pub async fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    manager::list_monitors(&app)
+}
+
+/// Move a window to another monitor at `anchor`, keeping its apparent size.
+/// Returns the final physical position for the caller to persist.
+#[command]
+pub async fn move_window_to_monitor(
+    app: AppHandle,
+    label: String,
+    monitor_name: String,
+    anchor: MonitorAnchor,
+) -> Result<ScreenPoint, String> {
+    manager::move_window_to_monitor(&app, &label, &monitor_name, anchor)
+}
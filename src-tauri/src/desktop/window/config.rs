@@ -1,5 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::desktop::i18n::{self, Text};
+
 #[cfg(all(target_os = "windows", debug_assertions))]
 pub const DEFAULT_ADDITIONAL_WINDOW_ARGS: &str = "--enable-gpu-rasterization --enable-zero-copy --ignore-gpu-blocklist --use-gl=angle --disable-features=VaapiVideoDecoder,UseChromeOSDirectVideoDecoder,msWebOOUI,msPdfOOUI --enable-threaded-compositing --num-raster-threads=4 --remote-debugging-port=9222";
 
@@ -8,6 +11,8 @@ pub const DEFAULT_ADDITIONAL_WINDOW_ARGS: &str = "--enable-gpu-rasterization --e
 
 pub const TRAY_POPUP_WIDTH: f64 = 260.0;
 pub const TRAY_POPUP_BASE_HEIGHT: f64 = 334.0;
+pub const OSD_WIDTH: f64 = 320.0;
+pub const OSD_HEIGHT: f64 = 88.0;
 
 /// Known window labels with preset configurations.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -21,6 +26,7 @@ pub enum WindowLabel {
     Settings,
     About,
     TrayPopup,
+    Osd,
     Custom(String),
 }
 
@@ -36,6 +42,7 @@ impl WindowLabel {
             Self::Settings => "settings",
             Self::About => "about",
             Self::TrayPopup => "tray-popup",
+            Self::Osd => "osd",
             Self::Custom(s) => s.as_str(),
         }
     }
@@ -51,13 +58,14 @@ impl WindowLabel {
             "settings" => Self::Settings,
             "about" => Self::About,
             "tray-popup" => Self::TrayPopup,
+            "osd" => Self::Osd,
             other => Self::Custom(other.to_string()),
         }
     }
 }
 
 /// Configuration for creating a window.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowConfig {
     pub label: String,
@@ -87,19 +95,23 @@ pub struct WindowConfig {
     pub center: bool,
     #[serde(default = "default_true")]
     pub visible: bool,
+    /// If false, the window never takes keyboard focus (overlays, OSD).
+    #[serde(default = "default_true")]
+    pub focusable: bool,
     /// If true, reuse existing window instead of creating a duplicate.
     #[serde(default)]
     pub single_instance: bool,
     /// If true, close button hides the window instead of destroying it.
     #[serde(default)]
     pub closeable_to_tray: bool,
-    /// If true, apply decorum overlay titlebar.
+    /// If true, apply decorum overlay titlebar (macOS and Windows).
     #[serde(default)]
     pub use_overlay_titlebar: bool,
     /// macOS traffic lights inset (x, y). Only used on macOS.
     #[serde(default)]
     pub traffic_lights_inset: Option<(f32, f32)>,
-    /// Native window effect to apply (e.g. "acrylic"). Platform-specific.
+    /// Native window effect: "acrylic", "mica", "mica_dark", "tabbed", "blur"
+    /// or "vibrancy[:material]" (macOS). Platform-specific.
     #[serde(default)]
     pub window_effect: Option<String>,
     /// Whether to show a native window shadow. Defaults to false for transparent windows.
@@ -111,6 +123,48 @@ pub struct WindowConfig {
     /// Parent window label (for child windows)
     #[serde(default)]
     pub parent_label: Option<String>,
+    /// Create hidden for later use: effects and positioning are deferred
+    /// until the window is first shown (see `prewarm.rs`).
+    #[serde(default)]
+    pub prewarm: bool,
+    /// Keep the window out of screenshots, recordings and screen sharing.
+    /// Windows and macOS only.
+    #[serde(default)]
+    pub content_protected: bool,
+}
+
+/// Fields to change on a preset before it is opened; anything left out
+/// keeps the preset's value. The label is fixed by the preset. Fields that
+/// are optional in `WindowConfig` can be set here but not cleared.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowConfigOverrides {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub min_width: Option<f64>,
+    pub min_height: Option<f64>,
+    pub max_width: Option<f64>,
+    pub max_height: Option<f64>,
+    pub resizable: Option<bool>,
+    pub decorations: Option<bool>,
+    pub transparent: Option<bool>,
+    pub always_on_top: Option<bool>,
+    pub skip_taskbar: Option<bool>,
+    pub center: Option<bool>,
+    pub visible: Option<bool>,
+    pub focusable: Option<bool>,
+    pub single_instance: Option<bool>,
+    pub closeable_to_tray: Option<bool>,
+    pub use_overlay_titlebar: Option<bool>,
+    pub traffic_lights_inset: Option<(f32, f32)>,
+    pub window_effect: Option<String>,
+    pub shadow: Option<bool>,
+    pub additional_args: Option<String>,
+    pub parent_label: Option<String>,
+    pub prewarm: Option<bool>,
+    pub content_protected: Option<bool>,
 }
 
 fn default_true() -> bool {
@@ -132,7 +186,7 @@ impl WindowConfig {
     pub fn main() -> Self {
         Self {
             label: "main".into(),
-            title: "GMPlayer".into(),
+            title: i18n::tr(Text::MainTitle).into(),
             url: "/".into(),
             width: 881.0,
             height: 653.0,
@@ -147,14 +201,17 @@ impl WindowConfig {
             skip_taskbar: false,
             center: false,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: true,
             use_overlay_titlebar: true,
             traffic_lights_inset: Some((12.0, 16.0)),
-            window_effect: Some("acrylic".into()),
+            window_effect: Some("mica".into()),
             shadow: true,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -162,7 +219,7 @@ impl WindowConfig {
     pub fn mini_player() -> Self {
         Self {
             label: "mini-player".into(),
-            title: "Mini Player".into(),
+            title: i18n::tr(Text::MiniPlayerTitle).into(),
             url: "/slave.html#/mini-player".into(),
             width: 350.0,
             height: 80.0,
@@ -177,6 +234,7 @@ impl WindowConfig {
             skip_taskbar: true,
             center: false,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: false,
@@ -185,6 +243,8 @@ impl WindowConfig {
             shadow: true,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -192,7 +252,7 @@ impl WindowConfig {
     pub fn desktop_lyrics() -> Self {
         Self {
             label: "desktop-lyrics".into(),
-            title: "Desktop Lyrics".into(),
+            title: i18n::tr(Text::DesktopLyricsTitle).into(),
             url: "/slave.html#/desktop-lyrics".into(),
             width: 800.0,
             height: 120.0,
@@ -207,6 +267,7 @@ impl WindowConfig {
             skip_taskbar: true,
             center: false,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: false,
@@ -215,6 +276,8 @@ impl WindowConfig {
             shadow: false,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -222,7 +285,7 @@ impl WindowConfig {
     pub fn desktop_lyrics_controls() -> Self {
         Self {
             label: "desktop-lyrics-controls".into(),
-            title: "Desktop Lyrics Controls".into(),
+            title: i18n::tr(Text::DesktopLyricsControlsTitle).into(),
             url: "/slave.html#/desktop-lyrics-controls".into(),
             width: 220.0,
             height: 40.0,
@@ -237,6 +300,7 @@ impl WindowConfig {
             skip_taskbar: true,
             center: false,
             visible: false,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: false,
@@ -245,6 +309,8 @@ impl WindowConfig {
             shadow: false,
             additional_args: default_additional_window_args(),
             parent_label: Some("desktop-lyrics".into()),
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -258,7 +324,7 @@ impl WindowConfig {
     pub fn taskbar_lyric() -> Self {
         Self {
             label: "taskbar-lyric".into(),
-            title: "Taskbar Lyric".into(),
+            title: i18n::tr(Text::TaskbarLyricTitle).into(),
             url: "/slave.html#/taskbar-lyric".into(),
             width: 320.0,
             height: 48.0,
@@ -273,6 +339,7 @@ impl WindowConfig {
             skip_taskbar: false,
             center: false,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: false,
@@ -281,6 +348,8 @@ impl WindowConfig {
             shadow: false,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -288,7 +357,7 @@ impl WindowConfig {
     pub fn settings() -> Self {
         Self {
             label: "settings".into(),
-            title: "Settings".into(),
+            title: i18n::tr(Text::SettingsTitle).into(),
             url: "/slave.html#/settings".into(),
             width: 860.0,
             height: 620.0,
@@ -303,6 +372,7 @@ impl WindowConfig {
             skip_taskbar: false,
             center: true,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: true,
@@ -311,6 +381,8 @@ impl WindowConfig {
             shadow: true,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -318,7 +390,7 @@ impl WindowConfig {
     pub fn about() -> Self {
         Self {
             label: "about".into(),
-            title: "About".into(),
+            title: i18n::tr(Text::AboutTitle).into(),
             url: "/about".into(),
             width: 400.0,
             height: 350.0,
@@ -333,14 +405,17 @@ impl WindowConfig {
             skip_taskbar: false,
             center: true,
             visible: true,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
-            use_overlay_titlebar: true,
+            use_overlay_titlebar: cfg!(target_os = "macos"),
             traffic_lights_inset: Some((12.0, 16.0)),
             window_effect: None,
             shadow: true,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -349,7 +424,7 @@ impl WindowConfig {
     pub fn tray_popup() -> Self {
         Self {
             label: "tray-popup".into(),
-            title: "Tray Popup".into(),
+            title: i18n::tr(Text::TrayPopupTitle).into(),
             url: "/slave.html#/tray-popup".into(),
             width: TRAY_POPUP_WIDTH,
             height: TRAY_POPUP_BASE_HEIGHT,
@@ -364,6 +439,7 @@ impl WindowConfig {
             skip_taskbar: true,
             center: false,
             visible: false,
+            focusable: true,
             single_instance: true,
             closeable_to_tray: false,
             use_overlay_titlebar: false,
@@ -372,6 +448,43 @@ impl WindowConfig {
             shadow: true,
             additional_args: default_additional_window_args(),
             parent_label: None,
+            prewarm: false,
+            content_protected: false,
+        }
+    }
+
+    /// OSD preset — transient volume / track-change overlay shown by hotkeys.
+    /// Never focusable and click-through so it cannot interrupt whatever the
+    /// user is doing; see `window::osd` for the show/hide lifecycle.
+    pub fn osd() -> Self {
+        Self {
+            label: "osd".into(),
+            title: i18n::tr(Text::OsdTitle).into(),
+            url: "/slave.html#/osd".into(),
+            width: OSD_WIDTH,
+            height: OSD_HEIGHT,
+            min_width: None,
+            min_height: None,
+            max_width: None,
+            max_height: None,
+            resizable: false,
+            decorations: false,
+            transparent: true,
+            always_on_top: true,
+            skip_taskbar: true,
+            center: false,
+            visible: false,
+            focusable: false,
+            single_instance: true,
+            closeable_to_tray: false,
+            use_overlay_titlebar: false,
+            traffic_lights_inset: None,
+            window_effect: None,
+            shadow: false,
+            additional_args: default_additional_window_args(),
+            parent_label: None,
+            prewarm: false,
+            content_protected: false,
         }
     }
 
@@ -387,10 +500,33 @@ impl WindowConfig {
             "settings" => Some(Self::settings()),
             "about" => Some(Self::about()),
             "tray-popup" => Some(Self::tray_popup()),
+            "osd" => Some(Self::osd()),
             _ => None,
         }
     }
 
+    /// Apply the fields set in `overrides`, keeping the rest.
+    pub fn merge(mut self, overrides: WindowConfigOverrides) -> Self {
+        macro_rules! set {
+            ($($field:ident),* ; $($optional:ident),*) => {
+                $(if let Some(value) = overrides.$field {
+                    self.$field = value;
+                })*
+                $(if let Some(value) = overrides.$optional {
+                    self.$optional = Some(value);
+                })*
+            };
+        }
+        set!(
+            title, url, width, height, resizable, decorations, transparent,
+            always_on_top, skip_taskbar, center, visible, focusable, single_instance,
+            closeable_to_tray, use_overlay_titlebar, shadow, prewarm, content_protected;
+            min_width, min_height, max_width, max_height, traffic_lights_inset,
+            window_effect, additional_args, parent_label
+        );
+        self
+    }
+
     /// Effective browser args for WebView2-backed windows.
     ///
     /// WebView2 requires every webview that shares the same user data folder
@@ -407,3 +543,83 @@ impl WindowConfig {
         self.additional_args.as_deref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_overrides_keep_the_preset() {
+        let preset = WindowConfig::settings();
+        let merged = preset.clone().merge(WindowConfigOverrides::default());
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::to_value(&preset).unwrap()
+        );
+    }
+
+    #[test]
+    fn every_override_is_applied() {
+        let preset = WindowConfig::desktop_lyrics();
+        let overrides: WindowConfigOverrides = serde_json::from_value(serde_json::json!({
+            "title": "T",
+            "url": "/u",
+            "width": 10.0,
+            "height": 20.0,
+            "minWidth": 1.0,
+            "minHeight": 2.0,
+            "maxWidth": 30.0,
+            "maxHeight": 40.0,
+            "resizable": !preset.resizable,
+            "decorations": !preset.decorations,
+            "transparent": !preset.transparent,
+            "alwaysOnTop": !preset.always_on_top,
+            "skipTaskbar": !preset.skip_taskbar,
+            "center": !preset.center,
+            "visible": !preset.visible,
+            "focusable": !preset.focusable,
+            "singleInstance": !preset.single_instance,
+            "closeableToTray": !preset.closeable_to_tray,
+            "useOverlayTitlebar": !preset.use_overlay_titlebar,
+            "trafficLightsInset": [3.0, 4.0],
+            "windowEffect": "blur",
+            "shadow": !preset.shadow,
+            "additionalArgs": "--x",
+            "parentLabel": "main",
+            "prewarm": !preset.prewarm,
+            "contentProtected": !preset.content_protected,
+        }))
+        .unwrap();
+        let merged = preset.clone().merge(overrides);
+
+        assert_eq!(merged.label, preset.label);
+        assert_eq!((merged.title.as_str(), merged.url.as_str()), ("T", "/u"));
+        assert_eq!((merged.width, merged.height), (10.0, 20.0));
+        assert_eq!(
+            (merged.min_width, merged.min_height),
+            (Some(1.0), Some(2.0))
+        );
+        assert_eq!(
+            (merged.max_width, merged.max_height),
+            (Some(30.0), Some(40.0))
+        );
+        assert_eq!(merged.resizable, !preset.resizable);
+        assert_eq!(merged.decorations, !preset.decorations);
+        assert_eq!(merged.transparent, !preset.transparent);
+        assert_eq!(merged.always_on_top, !preset.always_on_top);
+        assert_eq!(merged.skip_taskbar, !preset.skip_taskbar);
+        assert_eq!(merged.center, !preset.center);
+        assert_eq!(merged.visible, !preset.visible);
+        assert_eq!(merged.focusable, !preset.focusable);
+        assert_eq!(merged.single_instance, !preset.single_instance);
+        assert_eq!(merged.closeable_to_tray, !preset.closeable_to_tray);
+        assert_eq!(merged.use_overlay_titlebar, !preset.use_overlay_titlebar);
+        assert_eq!(merged.traffic_lights_inset, Some((3.0, 4.0)));
+        assert_eq!(merged.window_effect.as_deref(), Some("blur"));
+        assert_eq!(merged.shadow, !preset.shadow);
+        assert_eq!(merged.additional_args.as_deref(), Some("--x"));
+        assert_eq!(merged.parent_label.as_deref(), Some("main"));
+        assert_eq!(merged.prewarm, !preset.prewarm);
+        assert_eq!(merged.content_protected, !preset.content_protected);
+    }
+}
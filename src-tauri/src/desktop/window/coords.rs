@@ -0,0 +1,354 @@
+//! Coordinate spaces of the positioning commands.
+//!
+//! Older commands take physical pixels, which forces the frontend to scale by
+//! `devicePixelRatio` — the ratio of the window's *current* monitor, which is
+//! wrong as soon as the target is on another monitor. The logical variants
+//! here resolve the target monitor first and convert with its scale factor.
+
+use log::{debug, info};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewWindow};
+
+use crate::desktop::window::manager::{self, MonitorArea, ScreenRect};
+use crate::settings::window::DEFAULT_MIN_VISIBLE_FRACTION;
+use crate::settings::SettingsStore;
+
+/// Physical coordinates below this on a HiDPI monitor are probably logical.
+const SUSPICIOUS_PHYSICAL_COORD: f64 = 100.0;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CoordinateUnits {
+    Physical,
+    Logical,
+    /// macOS reports points (logical), other platforms physical pixels.
+    PlatformDependent,
+}
+
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PositioningConvention {
+    pub command: &'static str,
+    pub input: Option<CoordinateUnits>,
+    pub output: Option<CoordinateUnits>,
+    pub note: &'static str,
+}
+
+/// Pick the monitor for a logical point: the hinted one when it exists,
+/// else the one containing the point, else the first (primary-first) entry.
+fn pick_monitor<'a>(
+    monitors: &'a [MonitorArea],
+    hint: Option<&str>,
+    x: f64,
+    y: f64,
+) -> Option<&'a MonitorArea> {
+    hint.and_then(|hint| monitors.iter().find(|m| m.name.as_deref() == Some(hint)))
+        .or_else(|| monitors.iter().find(|m| m.contains_logical(x, y)))
+        .or_else(|| monitors.first())
+}
+
+/// Share of a physical rectangle that lies on any monitor, from 0 to 1.
+/// Monitors do not overlap, so the per-monitor intersections just add up.
+fn visible_fraction(monitors: &[MonitorArea], x: f64, y: f64, width: f64, height: f64) -> f64 {
+    if width <= 0.0 || height <= 0.0 {
+        return 1.0;
+    }
+    let visible: f64 = monitors
+        .iter()
+        .map(|m| {
+            let ScreenRect {
+                x: left,
+                y: top,
+                width: w,
+                height: h,
+            } = m.rect;
+            let (left, top, w, h) = (left as f64, top as f64, w as f64, h as f64);
+            let w = (x + width).min(left + w) - x.max(left);
+            let h = (y + height).min(top + h) - y.max(top);
+            w.max(0.0) * h.max(0.0)
+        })
+        .sum();
+    (visible / (width * height)).min(1.0)
+}
+
+fn looks_logical(x: f64, y: f64, scale_factor: f64) -> bool {
+    scale_factor > 1.0 && x.abs() < SUSPICIOUS_PHYSICAL_COORD && y.abs() < SUSPICIOUS_PHYSICAL_COORD
+}
+
+/// Monitors with the primary one first. `"current"` as a hint means the
+/// window's current monitor.
+fn monitor_areas(window: &WebviewWindow, hint: Option<&str>) -> Vec<MonitorArea> {
+    let mut preferred = match hint {
+        Some("current") => window.current_monitor().ok().flatten(),
+        _ => None,
+    }
+    .or_else(|| window.primary_monitor().ok().flatten())
+    .map(|m| vec![MonitorArea::bounds(&m)])
+    .unwrap_or_default();
+    let first_name = preferred.first().and_then(|m| m.name.clone());
+    preferred.extend(
+        window
+            .available_monitors()
+            .unwrap_or_default()
+            .iter()
+            .map(MonitorArea::bounds)
+            .filter(|m| first_name.is_none() || m.name != first_name),
+    );
+    preferred
+}
+
+/// Convert logical coordinates to physical ones using the target monitor's
+/// scale factor (the window's own one if no monitor is known).
+fn to_physical(
+    window: &WebviewWindow,
+    x: f64,
+    y: f64,
+    monitor: Option<&str>,
+) -> Result<PhysicalPosition<i32>, String> {
+    if !x.is_finite() || !y.is_finite() {
+        return Err("Position must be finite".into());
+    }
+    let monitors = monitor_areas(window, monitor);
+    let hint = monitor.filter(|hint| *hint != "current");
+    let scale_factor = match pick_monitor(&monitors, hint, x, y) {
+        Some(area) => area.scale_factor,
+        None => window.scale_factor().map_err(|e| e.to_string())?,
+    };
+    Ok(PhysicalPosition::new(
+        (x * scale_factor).round() as i32,
+        (y * scale_factor).round() as i32,
+    ))
+}
+
+/// Log (at debug level) physical-coordinate calls whose values look like
+/// logical ones. Nothing is rejected; this only helps find callers that
+/// forgot to scale.
+pub(crate) fn check_physical_call(app: &AppHandle, command: &str, label: &str, x: f64, y: f64) {
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let scale_factor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .map(|m| m.scale_factor())
+        .unwrap_or(1.0);
+    if looks_logical(x, y, scale_factor) {
+        debug!(
+            "{}('{}', {}, {}) takes physical pixels but the values look logical (scale {}); \
+             consider the logical variant",
+            command, label, x, y, scale_factor
+        );
+    }
+}
+
+/// Show a window at a logical position. `monitor` is a monitor name or
+/// `"current"`; without it the monitor containing the point is used.
+#[tauri::command]
+pub async fn show_window_at_logical_position(
+    app: AppHandle,
+    label: String,
+    x: f64,
+    y: f64,
+    monitor: Option<String>,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let position = to_physical(&window, x, y, monitor.as_deref())?;
+    manager::show_window_at_position(&app, &label, position.x as f64, position.y as f64)
+}
+
+/// Move a window to a logical position without showing it.
+#[tauri::command]
+pub async fn set_window_logical_position(
+    app: AppHandle,
+    label: String,
+    x: f64,
+    y: f64,
+    monitor: Option<String>,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let position = to_physical(&window, x, y, monitor.as_deref())?;
+    window.set_position(position).map_err(|e| e.to_string())
+}
+
+/// Move a window to the center of the primary monitor's work area when less
+/// than the configured share of it is on any monitor, e.g. because it was
+/// last closed on a display that is now unplugged. Returns whether it moved.
+pub(crate) fn ensure_on_screen(app: &AppHandle, label: &str) -> Result<bool, String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(false);
+    }
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(MonitorArea::bounds)
+        .collect();
+    if monitors.is_empty() {
+        return Ok(false);
+    }
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let (width, height) = (size.width as f64, size.height as f64);
+    let visible = visible_fraction(
+        &monitors,
+        position.x as f64,
+        position.y as f64,
+        width,
+        height,
+    );
+    let threshold = app
+        .try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.window.min_visible_fraction))
+        .unwrap_or(DEFAULT_MIN_VISIBLE_FRACTION);
+    if visible >= threshold {
+        return Ok(false);
+    }
+
+    let Some(primary) = window.primary_monitor().map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+    let area = primary.work_area();
+    let x = area.position.x as f64 + ((area.size.width as f64 - width) / 2.0).max(0.0);
+    let y = area.position.y as f64 + ((area.size.height as f64 - height) / 2.0).max(0.0);
+    info!(
+        "Window '{}' was {:.0}% on screen, moving it onto the primary monitor",
+        label,
+        visible * 100.0
+    );
+    window
+        .set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32))
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Bring a window back onto a monitor if it is (mostly) off-screen, e.g.
+/// after a display was disconnected. Returns whether it was moved.
+#[tauri::command]
+pub async fn ensure_window_on_screen(app: AppHandle, label: String) -> Result<bool, String> {
+    ensure_on_screen(&app, &label)
+}
+
+/// Units taken and returned by every positioning command.
+#[tauri::command]
+pub fn get_positioning_conventions() -> Vec<PositioningConvention> {
+    use CoordinateUnits::*;
+    let entry = |command, input, output, note| PositioningConvention {
+        command,
+        input,
+        output,
+        note,
+    };
+    vec![
+        entry(
+            "show_window_at_position",
+            Some(Physical),
+            None,
+            "Virtual-screen pixels.",
+        ),
+        entry(
+            "set_window_position",
+            Some(Physical),
+            None,
+            "Virtual-screen pixels.",
+        ),
+        entry(
+            "show_window_at_logical_position",
+            Some(Logical),
+            None,
+            "Scaled with the hinted or containing monitor's factor.",
+        ),
+        entry(
+            "set_window_logical_position",
+            Some(Logical),
+            None,
+            "Scaled with the hinted or containing monitor's factor.",
+        ),
+        entry("resize_window", Some(Logical), None, "Size only."),
+        entry(
+            "update_tray_popup_layout",
+            Some(Logical),
+            None,
+            "Size only.",
+        ),
+        entry(
+            "get_window_bounds",
+            None,
+            Some(Physical),
+            "Outer position and size.",
+        ),
+        entry(
+            "get_cursor_position",
+            None,
+            Some(PlatformDependent),
+            "Points on macOS, physical pixels elsewhere.",
+        ),
+        entry(
+            "move_window_to_monitor",
+            None,
+            Some(Physical),
+            "Returns the final outer position.",
+        ),
+        entry(
+            "capture_window",
+            Some(Logical),
+            Some(Physical),
+            "Region in, PNG pixels out.",
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(name: &str, x: i32, width: u32, scale_factor: f64) -> MonitorArea {
+        MonitorArea {
+            name: Some(name.into()),
+            rect: ScreenRect {
+                x,
+                y: 0,
+                width,
+                height: (1080.0 * scale_factor) as u32,
+            },
+            scale_factor,
+        }
+    }
+
+    #[test]
+    fn monitor_is_picked_by_hint_then_point() {
+        // 1920x1080 at 1.0, then a 4K panel at 2.0 to its right.
+        let monitors = [area("A", 0, 1920, 1.0), area("B", 1920, 3840, 2.0)];
+        let pick = |hint, x| pick_monitor(&monitors, hint, x, 10.0).unwrap().scale_factor;
+        assert_eq!(pick(None, 100.0), 1.0);
+        assert_eq!(pick(None, 2000.0), 2.0); // B spans 960..2880 logical
+        assert_eq!(pick(Some("B"), 100.0), 2.0);
+        assert_eq!(pick(Some("missing"), 5000.0), 1.0);
+    }
+
+    #[test]
+    fn visible_fraction_counts_every_monitor() {
+        let monitors = [area("A", 0, 1920, 1.0), area("B", 1920, 1920, 1.0)];
+        // Straddling both monitors is fully visible.
+        assert_eq!(visible_fraction(&monitors, 1820.0, 0.0, 200.0, 100.0), 1.0);
+        // Half hanging off the right edge of B.
+        assert_eq!(visible_fraction(&monitors, 3740.0, 0.0, 200.0, 100.0), 0.5);
+        // Left behind on an unplugged third monitor.
+        assert_eq!(visible_fraction(&monitors, 4000.0, 0.0, 200.0, 100.0), 0.0);
+    }
+
+    #[test]
+    fn small_physical_values_look_logical_only_on_hidpi() {
+        assert!(looks_logical(40.0, 60.0, 1.5));
+        assert!(!looks_logical(40.0, 60.0, 1.0));
+        assert!(!looks_logical(40.0, 600.0, 2.0));
+    }
+}
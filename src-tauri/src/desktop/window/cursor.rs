@@ -0,0 +1,243 @@
+//! Screen cursor position on Linux.
+//!
+//! On X11 the pointer is read with `XQueryPointer`; libX11 is loaded at
+//! runtime, so a system without it still starts. Wayland never exposes the
+//! global pointer (tao reports `(0, 0)` there), so the fallback is the last
+//! pointer motion seen over one of our windows, offset by that window's
+//! outer position. That is only as accurate as the position the compositor
+//! reports for the window.
+
+use std::collections::HashMap;
+use std::os::raw::{c_int, c_uint, c_ulong};
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use gtk::prelude::*;
+use log::warn;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use x11_dl::xlib::{self, Display, Xlib};
+
+/// Last pointer motion over a window, in logical pixels relative to it.
+#[derive(Debug, Clone, Copy)]
+struct Motion {
+    x: f64,
+    y: f64,
+    at: Instant,
+}
+
+static LAST_MOTION: LazyLock<Mutex<HashMap<String, Motion>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A display connection kept open between calls; dragging queries the
+/// pointer on every frame.
+struct X11Connection {
+    xlib: Xlib,
+    display: *mut Display,
+}
+
+// SAFETY: the connection is only used while holding `X11`'s lock.
+unsafe impl Send for X11Connection {}
+
+static X11: Mutex<Option<X11Connection>> = Mutex::new(None);
+
+fn is_wayland_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+}
+
+/// Root-relative pointer position and the button/modifier mask.
+fn query_x11_pointer() -> Result<((i32, i32), c_uint), String> {
+    let mut connection = X11.lock();
+    if connection.is_none() {
+        let xlib = Xlib::open().map_err(|e| format!("libX11 is unavailable: {}", e))?;
+        // SAFETY: a null name opens the display named by $DISPLAY.
+        let display = unsafe { (xlib.XOpenDisplay)(std::ptr::null()) };
+        if display.is_null() {
+            return Err("cannot open the X display".into());
+        }
+        *connection = Some(X11Connection { xlib, display });
+    }
+    let X11Connection { xlib, display } = connection.as_ref().expect("opened above");
+
+    let (mut root_return, mut child_return): (c_ulong, c_ulong) = (0, 0);
+    let (mut root_x, mut root_y, mut win_x, mut win_y): (c_int, c_int, c_int, c_int) = (0, 0, 0, 0);
+    let mut mask: c_uint = 0;
+    // SAFETY: `display` is a live connection and every out-pointer is valid.
+    let same_screen = unsafe {
+        let root = (xlib.XDefaultRootWindow)(*display);
+        (xlib.XQueryPointer)(
+            *display,
+            root,
+            &mut root_return,
+            &mut child_return,
+            &mut root_x,
+            &mut root_y,
+            &mut win_x,
+            &mut win_y,
+            &mut mask,
+        )
+    };
+    if same_screen == 0 {
+        return Err("the pointer is on another X screen".into());
+    }
+    Ok(((root_x, root_y), mask))
+}
+
+/// Pick the focused window's motion, else the most recent one.
+fn pick_motion<'a>(
+    motions: &HashMap<String, Motion>,
+    windows: &'a [(String, bool)],
+) -> Option<(&'a str, Motion)> {
+    let known = windows
+        .iter()
+        .filter_map(|(label, focused)| Some((label.as_str(), *focused, *motions.get(label)?)));
+    known
+        .max_by_key(|(_, focused, motion)| (*focused, motion.at))
+        .map(|(label, _, motion)| (label, motion))
+}
+
+fn from_last_motion(app: &AppHandle) -> Option<(i32, i32)> {
+    let windows: Vec<(String, bool)> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| {
+            let focused = window.is_focused().unwrap_or(false);
+            (label, focused)
+        })
+        .collect();
+    let (label, motion) = {
+        let motions = LAST_MOTION.lock();
+        let (label, motion) = pick_motion(&motions, &windows)?;
+        (label.to_string(), motion)
+    };
+    let window = app.get_webview_window(&label)?;
+    let origin = window.outer_position().ok()?;
+    let scale = window.scale_factor().ok()?;
+    Some((
+        origin.x + (motion.x * scale).round() as i32,
+        origin.y + (motion.y * scale).round() as i32,
+    ))
+}
+
+/// Screen cursor position in physical pixels.
+pub fn cursor_position(app: &AppHandle) -> Result<(i32, i32), String> {
+    let x11_error = if is_wayland_session() {
+        None
+    } else {
+        match query_x11_pointer() {
+            Ok((position, _)) => return Ok(position),
+            Err(e) => Some(e),
+        }
+    };
+    from_last_motion(app).ok_or_else(|| match x11_error {
+        Some(e) => format!("Cursor position unavailable: {}", e),
+        None => "Cursor position unavailable: Wayland only reports it once the pointer \
+                 has moved over one of the app's windows"
+            .into(),
+    })
+}
+
+/// Whether a mouse button is held, from the X11 pointer mask. `None` on
+/// Wayland, which keeps button state to the focused client.
+pub fn button_down() -> Option<bool> {
+    if is_wayland_session() {
+        return None;
+    }
+    let buttons = xlib::Button1Mask | xlib::Button2Mask | xlib::Button3Mask;
+    query_x11_pointer()
+        .ok()
+        .map(|(_, mask)| mask & buttons != 0)
+}
+
+/// Record pointer motion over `window` for the Wayland fallback. No-op on X11.
+pub(crate) fn track(window: &WebviewWindow) {
+    if !is_wayland_session() {
+        return;
+    }
+    let handle = window.clone();
+    let result = window.run_on_main_thread(move || {
+        let gtk_window = match handle.gtk_window() {
+            Ok(gtk_window) => gtk_window,
+            Err(e) => {
+                warn!("No GTK window for '{}': {}", handle.label(), e);
+                return;
+            }
+        };
+        let label = handle.label().to_string();
+        gtk_window.add_events(gtk::gdk::EventMask::POINTER_MOTION_MASK);
+        gtk_window.connect_motion_notify_event(move |_, event| {
+            let (x, y) = event.position();
+            LAST_MOTION.lock().insert(
+                label.clone(),
+                Motion {
+                    x,
+                    y,
+                    at: Instant::now(),
+                },
+            );
+            gtk::glib::Propagation::Proceed
+        });
+    });
+    if let Err(e) = result {
+        warn!("Failed to track pointer motion: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn focused_window_motion_wins_over_newer_ones() {
+        let earlier = Instant::now();
+        let later = earlier + Duration::from_millis(10);
+        let motions = HashMap::from([
+            (
+                "main".to_string(),
+                Motion {
+                    x: 1.0,
+                    y: 2.0,
+                    at: earlier,
+                },
+            ),
+            (
+                "desktop-lyrics".to_string(),
+                Motion {
+                    x: 3.0,
+                    y: 4.0,
+                    at: later,
+                },
+            ),
+        ]);
+        let windows = vec![
+            ("main".to_string(), true),
+            ("desktop-lyrics".to_string(), false),
+        ];
+        assert_eq!(
+            pick_motion(&motions, &windows).map(|(l, _)| l),
+            Some("main")
+        );
+
+        let unfocused = vec![
+            ("main".to_string(), false),
+            ("desktop-lyrics".to_string(), false),
+        ];
+        assert_eq!(
+            pick_motion(&motions, &unfocused).map(|(l, _)| l),
+            Some("desktop-lyrics")
+        );
+    }
+
+    /// Needs an X server, e.g. `xvfb-run cargo test x11_pointer`; skipped
+    /// when `$DISPLAY` is unset.
+    #[test]
+    fn x11_pointer_is_on_the_root_window() {
+        if std::env::var_os("DISPLAY").is_none() {
+            return;
+        }
+        let ((x, y), _) = query_x11_pointer().expect("XQueryPointer");
+        assert!(x >= 0 && y >= 0);
+    }
+}
@@ -0,0 +1,255 @@
+//! Saved position and size of the desktop-lyrics window.
+//!
+//! The window-state plugin skips this window (see the denylist in
+//! `desktop::run`): the overlay sits wherever the user dragged it, usually
+//! low on a specific monitor, and must come back there without a flash at the
+//! preset position. Moves and resizes are written after a quiet period, in
+//! physical pixels together with the monitor name. On the next launch the
+//! bounds go straight into the window builder; if that monitor is gone they
+//! are clamped into the work area of the primary one. The window's
+//! orientation and opacity are kept in the same file.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::orientation::{self, LyricsOrientation};
+use crate::debounce::Debounced;
+use crate::desktop::window::manager::MonitorArea;
+use crate::desktop::window::opacity;
+use crate::settings::write_atomic;
+
+pub const LABEL: &str = "desktop-lyrics";
+const BOUNDS_FILE: &str = "desktop-lyrics-bounds.json";
+/// Dragging fires `Moved` per frame; write once the window has settled.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub monitor_name: Option<String>,
+    #[serde(default)]
+    pub orientation: LyricsOrientation,
+    #[serde(default = "opaque")]
+    pub opacity: f64,
+}
+
+fn opaque() -> f64 {
+    1.0
+}
+
+static WRITER: Debounced = Debounced::new("desktop-lyrics-bounds", DEBOUNCE);
+
+fn bounds_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(BOUNDS_FILE))
+}
+
+pub(crate) fn load(app: &AppHandle) -> Option<SavedBounds> {
+    let path = bounds_path(app)?;
+    let bytes = std::fs::read(&path).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(bounds) => Some(bounds),
+        Err(e) => {
+            warn!("Ignoring unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn save(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(LABEL) else {
+        return Ok(());
+    };
+    // A minimized window reports a parking position far off-screen.
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let monitor_name = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    let bounds = SavedBounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        monitor_name,
+        orientation: orientation::current(app),
+        opacity: opacity::current(app, LABEL),
+    };
+    let path = bounds_path(app).ok_or("app data dir unavailable")?;
+    let bytes = serde_json::to_vec_pretty(&bounds).map_err(|e| e.to_string())?;
+    write_atomic(&path, &bytes).map_err(|e| e.to_string())?;
+    debug!("Saved desktop-lyrics bounds {:?}", bounds);
+    Ok(())
+}
+
+/// Schedule a write after a move or resize. Called from the window event
+/// handler, so it only pushes the deadline back and never blocks.
+pub(crate) fn note_changed(app: &AppHandle) {
+    let app = app.clone();
+    WRITER.trigger(move || {
+        if let Err(e) = save(&app) {
+            warn!("Failed to save desktop-lyrics bounds: {}", e);
+        }
+    });
+}
+
+/// Where to put `saved` given the monitors present now, in physical pixels,
+/// plus the scale factor of the monitor it lands on.
+///
+/// Bounds on a monitor that still exists are kept as saved. Otherwise they
+/// are moved onto `fallback` (the primary monitor), shrunk if needed, so the
+/// whole window is inside its work area.
+fn resolve(
+    saved: &SavedBounds,
+    monitors: &[MonitorArea],
+    fallback: Option<&MonitorArea>,
+) -> Option<(SavedBounds, f64)> {
+    let home = monitors.iter().find(|m| match &saved.monitor_name {
+        Some(name) => m.name.as_deref() == Some(name.as_str()),
+        None => m.rect.contains(saved.x, saved.y),
+    });
+    if let Some(home) = home {
+        return Some((saved.clone(), home.scale_factor));
+    }
+    let fallback = fallback.or_else(|| monitors.first())?;
+    let area = fallback.rect;
+    let width = saved.width.min(area.width);
+    let height = saved.height.min(area.height);
+    let x = saved.x.clamp(area.x, area.x + (area.width - width) as i32);
+    let y = saved
+        .y
+        .clamp(area.y, area.y + (area.height - height) as i32);
+    let bounds = SavedBounds {
+        x,
+        y,
+        width,
+        height,
+        monitor_name: fallback.name.clone(),
+        orientation: saved.orientation,
+        opacity: saved.opacity,
+    };
+    Some((bounds, fallback.scale_factor))
+}
+
+/// Saved bounds for a new desktop-lyrics window as logical
+/// `(x, y, width, height)`, ready for the window builder.
+pub(crate) fn restored_logical(app: &AppHandle) -> Option<(f64, f64, f64, f64)> {
+    let saved = load(app)?;
+    let monitors: Vec<MonitorArea> = app
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(MonitorArea::work_area)
+        .collect();
+    let primary = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| MonitorArea::work_area(&monitor));
+    let (bounds, scale) = resolve(&saved, &monitors, primary.as_ref())?;
+    if bounds != saved {
+        debug!(
+            "Saved desktop-lyrics monitor {:?} is gone, clamped to {:?}",
+            saved.monitor_name, bounds.monitor_name
+        );
+    }
+    Some((
+        bounds.x as f64 / scale,
+        bounds.y as f64 / scale,
+        bounds.width as f64 / scale,
+        bounds.height as f64 / scale,
+    ))
+}
+
+/// Write a pending change now; the window is about to close and the
+/// debounced writer would find it gone.
+pub(crate) fn flush(app: &AppHandle) {
+    if WRITER.take_pending() {
+        if let Err(e) = save(app) {
+            warn!("Failed to save desktop-lyrics bounds: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::desktop::window::manager::ScreenRect;
+
+    fn monitor(name: &str, x: i32, width: u32, scale: f64) -> MonitorArea {
+        MonitorArea {
+            name: Some(name.into()),
+            rect: ScreenRect {
+                x,
+                y: 0,
+                width,
+                height: 1040,
+            },
+            scale_factor: scale,
+        }
+    }
+
+    fn bounds(x: i32, y: i32, monitor_name: Option<&str>) -> SavedBounds {
+        SavedBounds {
+            x,
+            y,
+            width: 800,
+            height: 120,
+            monitor_name: monitor_name.map(Into::into),
+            orientation: LyricsOrientation::Horizontal,
+            opacity: 1.0,
+        }
+    }
+
+    #[test]
+    fn bounds_on_a_present_monitor_are_kept() {
+        let monitors = [
+            monitor("DP-1", 0, 1920, 1.0),
+            monitor("HDMI-1", 1920, 2560, 1.5),
+        ];
+        let saved = bounds(2400, 900, Some("HDMI-1"));
+        assert_eq!(
+            resolve(&saved, &monitors, monitors.first()),
+            Some((saved.clone(), 1.5))
+        );
+
+        let unnamed = bounds(100, 900, None);
+        assert_eq!(
+            resolve(&unnamed, &monitors, monitors.first()),
+            Some((unnamed.clone(), 1.0))
+        );
+    }
+
+    #[test]
+    fn bounds_from_a_missing_monitor_are_clamped_to_the_fallback() {
+        let monitors = [monitor("DP-1", 0, 1920, 1.0)];
+        let saved = bounds(2400, 1000, Some("HDMI-1"));
+        let (clamped, scale) = resolve(&saved, &monitors, monitors.first()).unwrap();
+        assert_eq!(scale, 1.0);
+        assert_eq!((clamped.x, clamped.y), (1120, 920));
+        assert_eq!(clamped.monitor_name.as_deref(), Some("DP-1"));
+
+        let wide = SavedBounds {
+            width: 4000,
+            ..saved
+        };
+        let (clamped, _) = resolve(&wide, &monitors, None).unwrap();
+        assert_eq!((clamped.x, clamped.width), (0, 1920));
+        assert_eq!(resolve(&wide, &[], None), None);
+    }
+}
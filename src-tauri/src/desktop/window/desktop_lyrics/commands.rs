@@ -1,6 +1,8 @@
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use super::mouse_through::HitRegion;
+use super::orientation::LyricsOrientation;
+use crate::settings::SettingsStore;
 
 /// Set window position to specific physical coordinates.
 #[tauri::command(rename_all = "snake_case")]
@@ -10,6 +12,13 @@ pub async fn set_window_position(
     x: i32,
     y: i32,
 ) -> Result<(), String> {
+    crate::desktop::window::coords::check_physical_call(
+        &app,
+        "set_window_position",
+        &label,
+        x as f64,
+        y as f64,
+    );
     let window = app
         .get_webview_window(&label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
@@ -49,6 +58,50 @@ pub async fn update_mouse_through_regions(
     super::mouse_through::update_hit_regions(&app, &label, regions)
 }
 
+/// Lock the desktop-lyrics window (click-through, except for an unlock
+/// strip on hover) or unlock it.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_lyrics_locked(app: AppHandle, locked: bool) -> Result<(), String> {
+    super::lock::set_locked(&app, locked)
+}
+
+#[tauri::command]
+pub fn get_lyrics_orientation(app: AppHandle) -> LyricsOrientation {
+    super::orientation::current(&app)
+}
+
+/// Turn the desktop-lyrics window on its side (or back), swapping its size
+/// limits and keeping the edges nearest the screen sides in place. Emits
+/// `desktop-lyrics-orientation-clamped` if it had to shrink to fit.
+#[tauri::command]
+pub async fn set_lyrics_orientation(
+    app: AppHandle,
+    orientation: LyricsOrientation,
+) -> Result<(), String> {
+    super::orientation::set(&app, orientation)
+}
+
+#[tauri::command]
+pub fn get_lyrics_autohide_fullscreen(store: State<'_, SettingsStore>) -> bool {
+    store.read(|s| s.window.lyrics_autohide_fullscreen)
+}
+
+/// Hide desktop lyrics while a fullscreen app is focused on their monitor
+/// (Windows and macOS). Turning it off brings back auto-hidden lyrics.
+#[tauri::command]
+pub fn set_lyrics_autohide_fullscreen(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    store.update(|s| {
+        s.window.lyrics_autohide_fullscreen = enabled;
+        Ok(())
+    })?;
+    super::fullscreen::start(&app);
+    Ok(())
+}
+
 /// Emit events when desktop lyrics window moves or resizes.
 /// Call this from the main event loop (app.run() closure).
 pub fn handle_desktop_lyrics_event(app: &AppHandle, label: &str, event: &tauri::WindowEvent) {
@@ -59,10 +112,18 @@ pub fn handle_desktop_lyrics_event(app: &AppHandle, label: &str, event: &tauri::
     match event {
         tauri::WindowEvent::Moved(position) => {
             let _ = app.emit("desktop-lyrics-moved", (position.x, position.y));
+            super::bounds::note_changed(app);
+            super::snap::note_moved(app);
         }
         tauri::WindowEvent::Resized(size) => {
-            let _ = app.emit("desktop-lyrics-resized", (size.width, size.height));
+            let orientation = super::orientation::current(app);
+            let _ = app.emit(
+                "desktop-lyrics-resized",
+                (size.width, size.height, orientation),
+            );
+            super::bounds::note_changed(app);
         }
+        tauri::WindowEvent::CloseRequested { .. } => super::bounds::flush(app),
         _ => {}
     }
 }
@@ -0,0 +1,265 @@
+//! Hide the desktop-lyrics overlay while a fullscreen app is in front.
+//!
+//! The overlay is always on top, so it otherwise sits over games and
+//! fullscreen video. While `window.lyricsAutohideFullscreen` is on and the
+//! lyrics window exists, a watcher checks the foreground window twice a
+//! second. When that window covers the whole monitor the lyrics are on, the
+//! lyrics are hidden; they come back once it leaves fullscreen or loses
+//! focus. Each change is announced with `desktop-lyrics-autohidden`.
+//!
+//! - Windows: `GetForegroundWindow`, compared against its monitor's rect.
+//!   Captioned windows and the desktop itself never count.
+//! - macOS: the frontmost normal-layer window from `CGWindowListCopyWindowInfo`.
+//! - Linux: not detected; the watcher never starts.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::{debug, warn};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use super::bounds::LABEL;
+use crate::desktop::window::manager::ScreenRect;
+use crate::settings::SettingsStore;
+
+pub const DESKTOP_LYRICS_AUTOHIDDEN_EVENT: &str = "desktop-lyrics-autohidden";
+
+const INTERVAL: Duration = Duration::from_millis(500);
+const SUPPORTED: bool = cfg!(any(target_os = "windows", target_os = "macos"));
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// The lyrics are hidden by the watcher, not by the user.
+static AUTOHIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether `window` covers all of `monitor`.
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+fn covers(window: ScreenRect, monitor: ScreenRect) -> bool {
+    window.x <= monitor.x
+        && window.y <= monitor.y
+        && window.x + window.width as i32 >= monitor.x + monitor.width as i32
+        && window.y + window.height as i32 >= monitor.y + monitor.height as i32
+}
+
+#[cfg(target_os = "windows")]
+fn foreground_fullscreen(lyrics: &WebviewWindow, monitor: ScreenRect) -> bool {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetClassNameW, GetForegroundWindow, GetShellWindow, GetWindowLongW, GetWindowRect,
+        GWL_STYLE, WS_CAPTION,
+    };
+
+    // SAFETY: plain user32 queries on a window handle that may have gone
+    // away meanwhile, which they report as failure.
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() || hwnd == GetShellWindow() {
+            return false;
+        }
+        if lyrics
+            .hwnd()
+            .is_ok_and(|own| own.0 as isize == hwnd.0 as isize)
+        {
+            return false;
+        }
+        if GetWindowLongW(hwnd, GWL_STYLE) as u32 & WS_CAPTION.0 == WS_CAPTION.0 {
+            return false;
+        }
+        let mut class = [0u16; 16];
+        let len = GetClassNameW(hwnd, &mut class);
+        let class = String::from_utf16_lossy(&class[..len.max(0) as usize]);
+        if class == "Progman" || class == "WorkerW" {
+            return false;
+        }
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let window = ScreenRect {
+            x: rect.left,
+            y: rect.top,
+            width: (rect.right - rect.left).max(0) as u32,
+            height: (rect.bottom - rect.top).max(0) as u32,
+        };
+        covers(window, monitor)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn foreground_fullscreen(lyrics: &WebviewWindow, monitor: ScreenRect) -> bool {
+    use core_graphics::geometry::CGRect;
+    use std::ffi::c_void;
+
+    type CFTypeRef = *const c_void;
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFArrayGetCount(array: CFTypeRef) -> isize;
+        fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+        fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+        fn CFNumberGetValue(number: CFTypeRef, kind: isize, value: *mut c_void) -> bool;
+        fn CFRelease(cf: CFTypeRef);
+    }
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        static kCGWindowLayer: CFTypeRef;
+        static kCGWindowBounds: CFTypeRef;
+        fn CGWindowListCopyWindowInfo(option: u32, relative_to: u32) -> CFTypeRef;
+        fn CGRectMakeWithDictionaryRepresentation(dict: CFTypeRef, rect: *mut CGRect) -> bool;
+    }
+    const ON_SCREEN_ONLY: u32 = 1 << 0;
+    const EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+    const CF_NUMBER_SINT32: isize = 3;
+
+    // Window bounds are in points; bring the monitor into the same space.
+    let Ok(scale) = lyrics.scale_factor() else {
+        return false;
+    };
+    let monitor = ScreenRect {
+        x: (monitor.x as f64 / scale).round() as i32,
+        y: (monitor.y as f64 / scale).round() as i32,
+        width: (monitor.width as f64 / scale).round() as u32,
+        height: (monitor.height as f64 / scale).round() as u32,
+    };
+
+    // SAFETY: the array is released before returning; dictionary values are
+    // borrowed from it and not used afterwards.
+    unsafe {
+        let list = CGWindowListCopyWindowInfo(ON_SCREEN_ONLY | EXCLUDE_DESKTOP_ELEMENTS, 0);
+        if list.is_null() {
+            return false;
+        }
+        let mut fullscreen = false;
+        // Front to back; the first normal-layer window is the active app's.
+        for index in 0..CFArrayGetCount(list) {
+            let info = CFArrayGetValueAtIndex(list, index);
+            let mut layer: i32 = -1;
+            let number = CFDictionaryGetValue(info, kCGWindowLayer);
+            if number.is_null()
+                || !CFNumberGetValue(number, CF_NUMBER_SINT32, &mut layer as *mut i32 as _)
+                || layer != 0
+            {
+                continue;
+            }
+            let mut rect = CGRect::default();
+            let bounds = CFDictionaryGetValue(info, kCGWindowBounds);
+            if !bounds.is_null() && CGRectMakeWithDictionaryRepresentation(bounds, &mut rect) {
+                let window = ScreenRect {
+                    x: rect.origin.x.round() as i32,
+                    y: rect.origin.y.round() as i32,
+                    width: rect.size.width.round() as u32,
+                    height: rect.size.height.round() as u32,
+                };
+                fullscreen = covers(window, monitor);
+            }
+            break;
+        }
+        CFRelease(list);
+        fullscreen
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn foreground_fullscreen(_lyrics: &WebviewWindow, _monitor: ScreenRect) -> bool {
+    false
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsStore>()
+        .is_some_and(|store| store.read(|s| s.window.lyrics_autohide_fullscreen))
+}
+
+/// Whether a fullscreen window is in front on the lyrics window's monitor.
+fn fullscreen_in_front(window: &WebviewWindow) -> Result<bool, String> {
+    let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+    let rect = ScreenRect {
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+    };
+    Ok(foreground_fullscreen(window, rect))
+}
+
+fn set_autohidden(app: &AppHandle, window: &WebviewWindow, hidden: bool) {
+    let result = if hidden { window.hide() } else { window.show() };
+    if let Err(e) = result {
+        warn!("Failed to toggle desktop lyrics for fullscreen: {}", e);
+        return;
+    }
+    AUTOHIDDEN.store(hidden, Ordering::Relaxed);
+    let _ = app.emit(DESKTOP_LYRICS_AUTOHIDDEN_EVENT, hidden);
+}
+
+fn watch(app: AppHandle) {
+    loop {
+        let Some(window) = app.get_webview_window(LABEL) else {
+            AUTOHIDDEN.store(false, Ordering::Relaxed);
+            break;
+        };
+        if !enabled(&app) {
+            if AUTOHIDDEN.load(Ordering::Relaxed) {
+                set_autohidden(&app, &window, false);
+            }
+            break;
+        }
+        let hidden = AUTOHIDDEN.load(Ordering::Relaxed);
+        match fullscreen_in_front(&window) {
+            // Only hide what the user can see, and only show what we hid.
+            Ok(true) if !hidden && window.is_visible().unwrap_or(false) => {
+                set_autohidden(&app, &window, true);
+            }
+            Ok(false) if hidden => set_autohidden(&app, &window, false),
+            Ok(_) => {}
+            Err(e) => debug!("Fullscreen check failed: {}", e),
+        }
+        std::thread::sleep(INTERVAL);
+    }
+    RUNNING.store(false, Ordering::Release);
+    debug!("Desktop lyrics fullscreen watcher stopped");
+}
+
+/// Start the watcher if the feature is on and the lyrics window exists.
+/// Called when the window is created and when the setting is turned on.
+pub(crate) fn start(app: &AppHandle) {
+    if !SUPPORTED || !enabled(app) || app.get_webview_window(LABEL).is_none() {
+        return;
+    }
+    if RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("desktop-lyrics-fullscreen".into())
+        .spawn(move || watch(app));
+    if let Err(e) = spawned {
+        RUNNING.store(false, Ordering::Release);
+        warn!("Failed to spawn desktop lyrics fullscreen watcher: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_windows_covering_the_monitor_count_as_fullscreen() {
+        let monitor = ScreenRect {
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1440,
+        };
+        let window = |x, y, width, height| ScreenRect {
+            x,
+            y,
+            width,
+            height,
+        };
+        assert!(covers(window(1920, 0, 2560, 1440), monitor));
+        // Exclusive-fullscreen games may overhang the edges.
+        assert!(covers(window(1912, -8, 2576, 1456), monitor));
+        assert!(!covers(window(1920, 0, 2560, 1400), monitor));
+        assert!(!covers(window(0, 0, 1920, 1080), monitor));
+    }
+}
@@ -0,0 +1,222 @@
+//! Lock mode: the desktop-lyrics window ignores the cursor, except for a
+//! thin unlock strip along the edge holding its header: the top, or the
+//! right edge when the lyrics are vertical.
+//!
+//! A click-through window never sees the pointer, so the frontend cannot
+//! tell when to show its unlock button. While locked, a poller thread reads
+//! the native cursor position, emits `desktop-lyrics-hover` as the cursor
+//! enters and leaves the window, and lets clicks through to the webview only
+//! while the cursor is over the strip. It polls at 10 Hz near the window and
+//! 2 Hz otherwise, and exits once the window is gone or unlocked.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use super::bounds::LABEL;
+use super::orientation::{self, LyricsOrientation};
+use crate::desktop::window::manager::{self, ScreenRect};
+
+pub const DESKTOP_LYRICS_HOVER_EVENT: &str = "desktop-lyrics-hover";
+
+/// Logical height of the strip that accepts clicks while locked; covers
+/// the frontend's unlock header.
+const UNLOCK_STRIP_HEIGHT: f64 = 40.0;
+/// Logical distance from the window within which polling stays fast.
+const NEAR_DISTANCE: f64 = 160.0;
+const NEAR_INTERVAL: Duration = Duration::from_millis(100);
+const FAR_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Stop flag of the running poller, if any.
+static POLLER: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+/// Payload of `desktop-lyrics-hover`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LyricsHover {
+    Enter,
+    Leave,
+}
+
+/// Where the cursor is relative to the locked window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Proximity {
+    Far,
+    Near,
+    Over,
+    OverStrip,
+}
+
+impl Proximity {
+    fn hovering(self) -> bool {
+        matches!(self, Self::Over | Self::OverStrip)
+    }
+
+    fn interval(self) -> Duration {
+        match self {
+            Self::Far => FAR_INTERVAL,
+            _ => NEAR_INTERVAL,
+        }
+    }
+}
+
+/// Classify `cursor` against the window's outer rect. `strip` and `near`
+/// are in the same units as the rect; the strip runs along the top, or the
+/// right edge when `vertical`.
+fn proximity(
+    cursor: (i32, i32),
+    window: ScreenRect,
+    strip: i32,
+    near: i32,
+    vertical: bool,
+) -> Proximity {
+    let (x, y) = cursor;
+    let right = window.x + window.width as i32;
+    let bottom = window.y + window.height as i32;
+    let dx = (window.x - x).max(x - right + 1).max(0);
+    let dy = (window.y - y).max(y - bottom + 1).max(0);
+    if dx == 0 && dy == 0 {
+        let on_strip = if vertical {
+            x >= right - strip
+        } else {
+            y < window.y + strip
+        };
+        if on_strip {
+            Proximity::OverStrip
+        } else {
+            Proximity::Over
+        }
+    } else if dx.max(dy) <= near {
+        Proximity::Near
+    } else {
+        Proximity::Far
+    }
+}
+
+fn current(app: &AppHandle, window: &WebviewWindow) -> Result<Proximity, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let cursor = manager::cursor_position(app)?;
+    // The native cursor is in points on macOS, where the window's physical
+    // rect is its point rect times its own scale; compare in points there
+    // so a cursor on a monitor with another scale still lines up.
+    let unit = if cfg!(target_os = "macos") {
+        scale
+    } else {
+        1.0
+    };
+    let to_unit = |v: f64| (v / unit).round() as i32;
+    let rect = ScreenRect {
+        x: to_unit(position.x as f64),
+        y: to_unit(position.y as f64),
+        width: to_unit(size.width as f64) as u32,
+        height: to_unit(size.height as f64) as u32,
+    };
+    Ok(proximity(
+        cursor,
+        rect,
+        to_unit(UNLOCK_STRIP_HEIGHT * scale),
+        to_unit(NEAR_DISTANCE * scale),
+        orientation::current(app) == LyricsOrientation::Vertical,
+    ))
+}
+
+fn poll(app: AppHandle, stop: Arc<AtomicBool>) {
+    let mut last = Proximity::Far;
+    while !stop.load(Ordering::Relaxed) {
+        let Some(window) = app.get_webview_window(LABEL) else {
+            break;
+        };
+        let now = match current(&app, &window) {
+            Ok(now) => now,
+            Err(e) => {
+                debug!("Desktop lyrics lock poll failed: {}", e);
+                last
+            }
+        };
+        if now.hovering() != last.hovering() {
+            let hover = if now.hovering() {
+                LyricsHover::Enter
+            } else {
+                LyricsHover::Leave
+            };
+            let _ = app.emit(DESKTOP_LYRICS_HOVER_EVENT, hover);
+        }
+        if (now == Proximity::OverStrip) != (last == Proximity::OverStrip) {
+            // Under the lock, so an unlock that lands mid-poll cannot be
+            // overridden by a stale toggle.
+            let _poller = POLLER.lock();
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            if let Err(e) = window.set_ignore_cursor_events(now != Proximity::OverStrip) {
+                warn!("Failed to toggle desktop lyrics click-through: {}", e);
+            }
+        }
+        last = now;
+        std::thread::sleep(now.interval());
+    }
+    debug!("Desktop lyrics lock poller stopped");
+}
+
+/// Lock or unlock the desktop-lyrics window.
+pub fn set_locked(app: &AppHandle, locked: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window(LABEL)
+        .ok_or_else(|| format!("Window '{}' not found", LABEL))?;
+    let mut poller = POLLER.lock();
+    if let Some(stop) = poller.take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+    window
+        .set_ignore_cursor_events(locked)
+        .map_err(|e| e.to_string())?;
+    if !locked {
+        return Ok(());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let app = app.clone();
+    let flag = stop.clone();
+    std::thread::Builder::new()
+        .name("desktop-lyrics-lock".into())
+        .spawn(move || poll(app, flag))
+        .map_err(|e| e.to_string())?;
+    *poller = Some(stop);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: ScreenRect = ScreenRect {
+        x: 100,
+        y: 800,
+        width: 800,
+        height: 120,
+    };
+
+    #[test]
+    fn cursor_is_classified_against_the_window() {
+        let at = |x, y| proximity((x, y), WINDOW, 36, 160, false);
+        assert_eq!(at(500, 810), Proximity::OverStrip);
+        assert_eq!(at(500, 836), Proximity::Over);
+        assert_eq!(at(899, 919), Proximity::Over);
+        assert_eq!(at(900, 850), Proximity::Near);
+        assert_eq!(at(50, 700), Proximity::Near);
+        assert_eq!(at(500, 400), Proximity::Far);
+        assert_eq!(at(500, 400).interval(), FAR_INTERVAL);
+
+        let vertical = |x, y| proximity((x, y), WINDOW, 36, 160, true);
+        assert_eq!(vertical(880, 900), Proximity::OverStrip);
+        assert_eq!(vertical(500, 810), Proximity::Over);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod bounds;
 pub mod commands;
+pub mod fullscreen;
+pub mod lock;
 pub mod mouse_through;
+pub mod orientation;
+pub mod snap;
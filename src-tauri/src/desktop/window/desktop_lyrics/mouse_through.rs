@@ -15,6 +15,7 @@ use std::sync::{mpsc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
@@ -38,7 +39,7 @@ impl Default for MouseThroughState {
 /// A hit region defined by the frontend (logical coordinates relative to the
 /// webview client area). The backend uses these to decide whether the cursor
 /// is over an interactive element.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HitRegion {
     pub id: String,
     pub x: f64,
@@ -0,0 +1,235 @@
+//! Horizontal or vertical desktop lyrics.
+//!
+//! A vertical window is the preset turned on its side: width and height,
+//! and their min/max limits, trade places. The orientation is kept in the
+//! bounds file next to the saved geometry, so a vertical window comes back
+//! vertical with its limits already swapped before it is built.
+
+use std::borrow::Cow;
+
+use log::warn;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize};
+
+use super::bounds::{self, LABEL};
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager::{self, ScreenRect};
+
+/// Emitted when the rotated window had to be shrunk to fit its monitor.
+pub const DESKTOP_LYRICS_ORIENTATION_CLAMPED_EVENT: &str = "desktop-lyrics-orientation-clamped";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LyricsOrientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// Payload of `desktop-lyrics-orientation-clamped`, in physical pixels.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrientationClamped {
+    pub orientation: LyricsOrientation,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `None` until first read from the bounds file.
+static CURRENT: Mutex<Option<LyricsOrientation>> = Mutex::new(None);
+
+/// The current orientation, read from the bounds file on first use.
+pub(crate) fn current(app: &AppHandle) -> LyricsOrientation {
+    *CURRENT
+        .lock()
+        .get_or_insert_with(|| bounds::load(app).map(|b| b.orientation).unwrap_or_default())
+}
+
+fn rotated(config: &WindowConfig) -> WindowConfig {
+    WindowConfig {
+        width: config.height,
+        height: config.width,
+        min_width: config.min_height,
+        min_height: config.min_width,
+        max_width: config.max_height,
+        max_height: config.max_width,
+        ..config.clone()
+    }
+}
+
+/// `config` as the window should be built: rotated for vertical lyrics.
+pub(crate) fn oriented<'a>(app: &AppHandle, config: &'a WindowConfig) -> Cow<'a, WindowConfig> {
+    if config.label == LABEL && current(app) == LyricsOrientation::Vertical {
+        Cow::Owned(rotated(config))
+    } else {
+        Cow::Borrowed(config)
+    }
+}
+
+/// The rotated rect of `window` inside `area`, and whether it had to shrink.
+///
+/// The edges nearest the work area's sides stay put: a bar along the bottom
+/// of the screen grows upwards, one on the right grows to the left.
+fn rotate(window: ScreenRect, area: ScreenRect) -> (ScreenRect, bool) {
+    let width = window.height.min(area.width);
+    let height = window.width.min(area.height);
+    let clamped = (width, height) != (window.height, window.width);
+
+    let center_x = window.x + window.width as i32 / 2;
+    let center_y = window.y + window.height as i32 / 2;
+    let x = if center_x < area.x + area.width as i32 / 2 {
+        window.x
+    } else {
+        window.x + window.width as i32 - width as i32
+    };
+    let y = if center_y < area.y + area.height as i32 / 2 {
+        window.y
+    } else {
+        window.y + window.height as i32 - height as i32
+    };
+    let rect = ScreenRect {
+        x: x.clamp(area.x, area.x + (area.width - width) as i32),
+        y: y.clamp(area.y, area.y + (area.height - height) as i32),
+        width,
+        height,
+    };
+    (rect, clamped)
+}
+
+pub(crate) fn set(app: &AppHandle, orientation: LyricsOrientation) -> Result<(), String> {
+    let window = app
+        .get_webview_window(LABEL)
+        .ok_or_else(|| format!("Window '{}' not found", LABEL))?;
+    if current(app) == orientation {
+        return Ok(());
+    }
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("Desktop lyrics are not on any monitor")?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let work_area = monitor.work_area();
+    let (rect, clamped) = rotate(
+        ScreenRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+        ScreenRect {
+            x: work_area.position.x,
+            y: work_area.position.y,
+            width: work_area.size.width,
+            height: work_area.size.height,
+        },
+    );
+
+    // Set before resizing so `desktop-lyrics-resized` carries it.
+    *CURRENT.lock() = Some(orientation);
+    let preset = WindowConfig::desktop_lyrics();
+    let limits = match orientation {
+        LyricsOrientation::Horizontal => preset,
+        LyricsOrientation::Vertical => rotated(&preset),
+    };
+    // Drop the old limits first or they would clamp the new size.
+    window
+        .set_min_size(None::<LogicalSize<f64>>)
+        .map_err(|e| e.to_string())?;
+    window
+        .set_max_size(None::<LogicalSize<f64>>)
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(PhysicalSize::new(rect.width, rect.height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(rect.x, rect.y))
+        .map_err(|e| e.to_string())?;
+    manager::apply_runtime_size_constraints(&window, &limits)?;
+    bounds::note_changed(app);
+
+    if clamped {
+        warn!(
+            "Rotated desktop lyrics do not fit on {:?}, shrunk to {}x{}",
+            monitor.name(),
+            rect.width,
+            rect.height
+        );
+        let _ = app.emit(
+            DESKTOP_LYRICS_ORIENTATION_CLAMPED_EVENT,
+            OrientationClamped {
+                orientation,
+                width: rect.width,
+                height: rect.height,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: ScreenRect = ScreenRect {
+        x: 0,
+        y: 0,
+        width: 3440,
+        height: 1400,
+    };
+
+    #[test]
+    fn rotation_keeps_the_edges_nearest_the_screen_sides() {
+        // A bar low on the right half keeps its right and bottom edges.
+        let bar = ScreenRect {
+            x: 2400,
+            y: 1200,
+            width: 800,
+            height: 120,
+        };
+        let (rect, clamped) = rotate(bar, AREA);
+        assert!(!clamped);
+        assert_eq!(
+            (rect.x, rect.y, rect.width, rect.height),
+            (3080, 520, 120, 800)
+        );
+
+        // And back again from the top-left.
+        let column = ScreenRect {
+            x: 20,
+            y: 40,
+            width: 120,
+            height: 800,
+        };
+        let (rect, _) = rotate(column, AREA);
+        assert_eq!(
+            (rect.x, rect.y, rect.width, rect.height),
+            (20, 40, 800, 120)
+        );
+    }
+
+    #[test]
+    fn a_rotated_window_too_tall_for_the_monitor_is_clamped() {
+        let wide = ScreenRect {
+            x: 100,
+            y: 1200,
+            width: 1800,
+            height: 120,
+        };
+        let (rect, clamped) = rotate(wide, AREA);
+        assert!(clamped);
+        assert_eq!((rect.y, rect.height), (0, 1400));
+    }
+
+    #[test]
+    fn vertical_presets_swap_their_limits() {
+        let config = rotated(&WindowConfig::desktop_lyrics());
+        assert_eq!((config.width, config.height), (120.0, 800.0));
+        assert_eq!(
+            (config.min_width, config.min_height),
+            (Some(60.0), Some(400.0))
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Snap the desktop-lyrics window to work-area edges and the horizontal
+//! center once a drag settles.
+//!
+//! Guides are taken from the work area of the monitor under the window's
+//! center, so a snapped window never ends up under the taskbar. Snapping
+//! only runs after `Moved` has been quiet for [`SETTLE`]; acting on every
+//! frame would pull the window out from under the cursor.
+
+use std::time::Duration;
+
+use log::warn;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition};
+
+use super::bounds;
+use crate::debounce::Debounced;
+use crate::desktop::window::manager::ScreenRect;
+use crate::settings::window::LyricsSnap;
+use crate::settings::SettingsStore;
+
+pub const DESKTOP_LYRICS_SNAPPED_EVENT: &str = "desktop-lyrics-snapped";
+
+const SETTLE: Duration = Duration::from_millis(150);
+
+static SNAPPER: Debounced = Debounced::new("desktop-lyrics-snap", SETTLE);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapEdge {
+    Left,
+    Center,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Payload of `desktop-lyrics-snapped`; `x`/`y` are the new physical position.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricsSnapped {
+    pub edges: Vec<SnapEdge>,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// The closest guide within `threshold` on one axis.
+fn nearest(position: i32, guides: &[(i32, SnapEdge)], threshold: i32) -> Option<(i32, SnapEdge)> {
+    guides
+        .iter()
+        .copied()
+        .filter(|(guide, _)| (guide - position).abs() <= threshold)
+        .min_by_key(|(guide, _)| (guide - position).abs())
+}
+
+/// Where `window` snaps inside `area`, if it is near any guide and not on
+/// it already. All values are physical pixels.
+fn snap_position(window: ScreenRect, area: ScreenRect, threshold: i32) -> Option<LyricsSnapped> {
+    let (w, h) = (window.width as i32, window.height as i32);
+    let horizontal = [
+        (area.x, SnapEdge::Left),
+        (area.x + (area.width as i32 - w) / 2, SnapEdge::Center),
+        (area.x + area.width as i32 - w, SnapEdge::Right),
+    ];
+    let vertical = [
+        (area.y, SnapEdge::Top),
+        (area.y + area.height as i32 - h, SnapEdge::Bottom),
+    ];
+    let x = nearest(window.x, &horizontal, threshold);
+    let y = nearest(window.y, &vertical, threshold);
+
+    let target = (
+        x.map_or(window.x, |(x, _)| x),
+        y.map_or(window.y, |(y, _)| y),
+    );
+    if target == (window.x, window.y) {
+        return None;
+    }
+    Some(LyricsSnapped {
+        edges: y.into_iter().chain(x).map(|(_, edge)| edge).collect(),
+        x: target.0,
+        y: target.1,
+    })
+}
+
+fn settings(app: &AppHandle) -> LyricsSnap {
+    app.try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.window.lyrics_snap))
+        .unwrap_or_default()
+}
+
+fn snap(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(bounds::LABEL) else {
+        return Ok(());
+    };
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let center = (
+        position.x as f64 + size.width as f64 / 2.0,
+        position.y as f64 + size.height as f64 / 2.0,
+    );
+    let Some(monitor) = window
+        .monitor_from_point(center.0, center.1)
+        .map_err(|e| e.to_string())?
+        .or(window.current_monitor().map_err(|e| e.to_string())?)
+    else {
+        return Ok(());
+    };
+    let work_area = monitor.work_area();
+    let threshold = (settings(app).threshold_px as f64 * monitor.scale_factor()).round() as i32;
+    let snapped = snap_position(
+        ScreenRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+        ScreenRect {
+            x: work_area.position.x,
+            y: work_area.position.y,
+            width: work_area.size.width,
+            height: work_area.size.height,
+        },
+        threshold,
+    );
+    let Some(snapped) = snapped else {
+        return Ok(());
+    };
+    window
+        .set_position(PhysicalPosition::new(snapped.x, snapped.y))
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(DESKTOP_LYRICS_SNAPPED_EVENT, snapped);
+    Ok(())
+}
+
+/// Snap once the window has stopped moving. Called on every `Moved`.
+pub(crate) fn note_moved(app: &AppHandle) {
+    if !settings(app).enabled {
+        return;
+    }
+    let app = app.clone();
+    SNAPPER.trigger(move || {
+        if let Err(e) = snap(&app) {
+            warn!("Failed to snap desktop lyrics: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: ScreenRect = ScreenRect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1040,
+    };
+
+    fn window(x: i32, y: i32) -> ScreenRect {
+        ScreenRect {
+            x,
+            y,
+            width: 800,
+            height: 120,
+        }
+    }
+
+    #[test]
+    fn near_bottom_center_snaps_to_both_guides() {
+        let snapped = snap_position(window(575, 905), AREA, 24).unwrap();
+        assert_eq!((snapped.x, snapped.y), (560, 920));
+        assert_eq!(snapped.edges, [SnapEdge::Bottom, SnapEdge::Center]);
+
+        // Each axis snaps on its own, and only within the threshold.
+        let snapped = snap_position(window(10, 400), AREA, 24).unwrap();
+        assert_eq!(
+            (snapped.x, snapped.y, snapped.edges),
+            (0, 400, vec![SnapEdge::Left])
+        );
+        assert_eq!(snap_position(window(300, 400), AREA, 24), None);
+    }
+
+    #[test]
+    fn a_window_already_on_a_guide_is_left_alone() {
+        assert_eq!(snap_position(window(560, 920), AREA, 24), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! Hold events for windows that cannot receive them yet.
+//!
+//! An event emitted to a window whose page has not registered its
+//! listeners is simply lost. `emit_or_queue` emits straight away to a
+//! window that has called `window_ready`, and otherwise buffers the event
+//! under the window's label. The buffer is flushed, in order, when the
+//! window calls `window_ready`. A window counts as not ready again from
+//! the moment it is (re)created, starts loading a page, or is destroyed,
+//! so events sent across a reload or a reopen are held rather than lost.
+//!
+//! Each label keeps at most [`MAX_QUEUED`] events; the oldest go first.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+
+use log::{debug, warn};
+use parking_lot::Mutex;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+const MAX_QUEUED: usize = 100;
+
+#[derive(Debug, Default)]
+struct LabelQueue {
+    ready: bool,
+    events: VecDeque<(String, Value)>,
+}
+
+impl LabelQueue {
+    fn push(&mut self, event: String, payload: Value) {
+        if self.events.len() == MAX_QUEUED {
+            self.events.pop_front();
+        }
+        self.events.push_back((event, payload));
+    }
+}
+
+static QUEUES: LazyLock<Mutex<HashMap<String, LabelQueue>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The window `label` stops receiving events directly until it calls
+/// `window_ready` again. Its queue is kept.
+pub(crate) fn note_not_ready(label: &str) {
+    if let Some(queue) = QUEUES.lock().get_mut(label) {
+        queue.ready = false;
+    }
+}
+
+/// Mark `label` ready and hand its held events to `emit`, oldest first.
+/// Emits happen under the lock, so an `emit_or_queue` racing with the
+/// flush cannot overtake queued events.
+fn drain_ready(label: &str, mut emit: impl FnMut(String, Value)) -> usize {
+    let mut queues = QUEUES.lock();
+    let queue = queues.entry(label.to_string()).or_default();
+    queue.ready = true;
+    let count = queue.events.len();
+    for (event, payload) in queue.events.drain(..) {
+        emit(event, payload);
+    }
+    count
+}
+
+/// Emit to `label` now if it is ready, else hold the event until it is.
+#[tauri::command]
+pub fn emit_or_queue(app: AppHandle, label: String, event: String, payload: Value) {
+    let mut queues = QUEUES.lock();
+    let queue = queues.entry(label.clone()).or_default();
+    if !queue.ready {
+        queue.push(event, payload);
+    } else if let Err(e) = app.emit_to(label.as_str(), &event, payload) {
+        warn!("Failed to emit '{}' to '{}': {}", event, label, e);
+    }
+}
+
+/// Called by a window once its listeners are registered: marks it ready
+/// and delivers what was held for it. Returns how many events that was.
+#[tauri::command]
+pub fn window_ready(app: AppHandle, label: String) -> usize {
+    let count = drain_ready(&label, |event, payload| {
+        if let Err(e) = app.emit_to(label.as_str(), &event, payload) {
+            warn!("Failed to emit queued '{}' to '{}': {}", event, label, e);
+        }
+    });
+    if count > 0 {
+        debug!("Delivered {} queued event(s) to '{}'", count, label);
+    }
+    count
+}
+
+/// Drop the events held for `label`. Returns how many there were.
+#[tauri::command]
+pub fn clear_event_queue(label: String) -> usize {
+    QUEUES
+        .lock()
+        .get_mut(&label)
+        .map_or(0, |queue| std::mem::take(&mut queue.events).len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn queue_for_test(label: &str, count: usize) {
+        let mut queues = QUEUES.lock();
+        let queue = queues.entry(label.to_string()).or_default();
+        for n in 0..count {
+            queue.push(format!("event-{}", n), json!(n));
+        }
+    }
+
+    #[test]
+    fn queues_drop_the_oldest_and_flush_in_order() {
+        queue_for_test("test-queue-cap", MAX_QUEUED + 5);
+        let mut events = Vec::new();
+        let count = drain_ready("test-queue-cap", |event, payload| {
+            events.push((event, payload))
+        });
+        assert_eq!(count, MAX_QUEUED);
+        assert_eq!(events.len(), MAX_QUEUED);
+        assert_eq!(events[0], ("event-5".to_string(), json!(5)));
+        assert_eq!(events.last().unwrap().1, json!(MAX_QUEUED + 4));
+        assert!(QUEUES.lock()["test-queue-cap"].ready);
+
+        note_not_ready("test-queue-cap");
+        queue_for_test("test-queue-cap", 3);
+        assert!(!QUEUES.lock()["test-queue-cap"].ready);
+        assert_eq!(clear_event_queue("test-queue-cap".into()), 3);
+        assert_eq!(clear_event_queue("test-queue-missing".into()), 0);
+    }
+}
@@ -0,0 +1,129 @@
+//! One `window-lifecycle` event stream for every window.
+//!
+//! The run-event handler keeps its per-label special cases; this module
+//! only reports, so the frontend can track the settings window or mini
+//! player (e.g. for a "Windows" menu) without a dedicated event per label.
+//! Creation is reported by `manager::create_window`, everything else from
+//! `RunEvent::WindowEvent`.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, WindowEvent};
+
+use crate::desktop::window::manager;
+
+pub const WINDOW_LIFECYCLE_EVENT: &str = "window-lifecycle";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LifecycleKind {
+    Created,
+    Destroyed,
+    Focused,
+    Blurred,
+    Moved,
+    Resized,
+    CloseRequested,
+}
+
+/// Physical outer position for `moved`, physical inner size for `resized`.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum LifecycleData {
+    Position { x: i32, y: i32 },
+    Size { width: u32, height: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLifecycle {
+    pub label: String,
+    pub kind: LifecycleKind,
+    pub data: Option<LifecycleData>,
+}
+
+/// The lifecycle entry for a window event, if it is one we report.
+fn from_window_event(label: &str, event: &WindowEvent) -> Option<WindowLifecycle> {
+    let (kind, data) = match event {
+        WindowEvent::Destroyed => (LifecycleKind::Destroyed, None),
+        WindowEvent::Focused(true) => (LifecycleKind::Focused, None),
+        WindowEvent::Focused(false) => (LifecycleKind::Blurred, None),
+        WindowEvent::Moved(position) => (
+            LifecycleKind::Moved,
+            Some(LifecycleData::Position {
+                x: position.x,
+                y: position.y,
+            }),
+        ),
+        WindowEvent::Resized(size) => (
+            LifecycleKind::Resized,
+            Some(LifecycleData::Size {
+                width: size.width,
+                height: size.height,
+            }),
+        ),
+        WindowEvent::CloseRequested { .. } => (LifecycleKind::CloseRequested, None),
+        _ => return None,
+    };
+    Some(WindowLifecycle {
+        label: label.to_string(),
+        kind,
+        data,
+    })
+}
+
+/// Forward a window event; called from the `RunEvent::WindowEvent` branch.
+pub(crate) fn handle(app: &AppHandle, label: &str, event: &WindowEvent) {
+    if let Some(lifecycle) = from_window_event(label, event) {
+        let _ = app.emit(WINDOW_LIFECYCLE_EVENT, lifecycle);
+    }
+}
+
+/// Report a window that `manager::create_window` just built.
+pub(crate) fn note_created(app: &AppHandle, label: &str) {
+    let _ = app.emit(
+        WINDOW_LIFECYCLE_EVENT,
+        WindowLifecycle {
+            label: label.to_string(),
+            kind: LifecycleKind::Created,
+            data: None,
+        },
+    );
+}
+
+/// Labels of the windows open right now. Call before listening to
+/// `window-lifecycle` to seed the list; later changes arrive as events.
+#[tauri::command]
+pub async fn subscribe_window_events(app: AppHandle) -> Vec<String> {
+    manager::list_windows(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::{PhysicalPosition, Theme};
+
+    #[test]
+    fn window_events_map_to_lifecycle_kinds() {
+        let kind = |event: WindowEvent| from_window_event("settings", &event).map(|l| l.kind);
+        assert_eq!(
+            kind(WindowEvent::Focused(true)),
+            Some(LifecycleKind::Focused)
+        );
+        assert_eq!(
+            kind(WindowEvent::Focused(false)),
+            Some(LifecycleKind::Blurred)
+        );
+        assert_eq!(kind(WindowEvent::Destroyed), Some(LifecycleKind::Destroyed));
+        assert_eq!(kind(WindowEvent::ThemeChanged(Theme::Dark)), None);
+
+        let moved = from_window_event(
+            "settings",
+            &WindowEvent::Moved(PhysicalPosition::new(10, -20)),
+        );
+        assert_eq!(
+            serde_json::to_value(moved).unwrap(),
+            serde_json::json!({ "label": "settings", "kind": "moved", "data": { "x": 10, "y": -20 } })
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Window pairs that are never visible at the same time.
+//!
+//! With a pair such as main / mini-player registered, showing one window
+//! hides its counterpart first. This lives in the backend so show paths that
+//! never touch the frontend (tray click, second launch) respect it too.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::warn;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager;
+
+/// Managed set of exclusive pairs.
+#[derive(Default)]
+pub struct ExclusivePairs {
+    pairs: Mutex<Vec<(String, String)>>,
+    suppressed: AtomicUsize,
+}
+
+/// While alive, showing a window does not hide its counterpart. Used by
+/// handoffs that manage both windows themselves.
+pub struct ExclusionSuppressed<'a>(&'a ExclusivePairs);
+
+impl Drop for ExclusionSuppressed<'_> {
+    fn drop(&mut self) {
+        self.0.suppressed.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ExclusivePairs {
+    pub fn set(&self, label_a: &str, label_b: &str, enabled: bool) {
+        let mut pairs = self.pairs.lock();
+        pairs.retain(|(a, b)| !is_pair(a, b, label_a, label_b));
+        if enabled {
+            pairs.push((label_a.to_string(), label_b.to_string()));
+        }
+    }
+
+    pub fn counterparts(&self, label: &str) -> Vec<String> {
+        self.pairs
+            .lock()
+            .iter()
+            .filter_map(|(a, b)| match label {
+                l if l == a => Some(b.clone()),
+                l if l == b => Some(a.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn suppress(&self) -> ExclusionSuppressed<'_> {
+        self.suppressed.fetch_add(1, Ordering::SeqCst);
+        ExclusionSuppressed(self)
+    }
+
+    fn is_suppressed(&self) -> bool {
+        self.suppressed.load(Ordering::SeqCst) > 0
+    }
+}
+
+fn is_pair(a: &str, b: &str, x: &str, y: &str) -> bool {
+    (a == x && b == y) || (a == y && b == x)
+}
+
+/// Hide every visible counterpart of `label`. Called right before `label`
+/// is shown, so the hide events go out before the show event.
+pub(crate) fn hide_counterparts(app: &AppHandle, label: &str) {
+    let Some(state) = app.try_state::<ExclusivePairs>() else {
+        return;
+    };
+    if state.is_suppressed() {
+        return;
+    }
+    for other in state.counterparts(label) {
+        let visible = app
+            .get_webview_window(&other)
+            .is_some_and(|w| w.is_visible().unwrap_or(false));
+        if visible {
+            if let Err(e) = manager::hide_window(app, &other) {
+                warn!(
+                    "Failed to hide '{}' before showing '{}': {}",
+                    other, label, e
+                );
+            }
+        }
+    }
+}
+
+/// Couple or decouple two windows. Disabling leaves current visibility as is.
+#[tauri::command]
+pub fn set_exclusive_pair(
+    state: State<'_, ExclusivePairs>,
+    label_a: String,
+    label_b: String,
+    enabled: bool,
+) -> Result<(), String> {
+    if label_a.is_empty() || label_b.is_empty() || label_a == label_b {
+        return Err("An exclusive pair needs two different window labels".into());
+    }
+    state.set(&label_a, &label_b, enabled);
+    Ok(())
+}
+
+/// Hand over from main to the mini player: show (or create) the mini
+/// player first, then hide main, so there is no moment with neither on
+/// screen. The pair coupling is suppressed meanwhile, since it would hide
+/// main before the mini player appears.
+#[tauri::command]
+pub async fn switch_to_mini_player(app: AppHandle) -> Result<(), String> {
+    let pairs = app.state::<ExclusivePairs>();
+    let _suppressed = pairs.suppress();
+    manager::create_window(&app, &WindowConfig::mini_player())?;
+    if app.get_webview_window("main").is_some() {
+        manager::hide_window(&app, "main")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_are_symmetric_and_removable() {
+        let pairs = ExclusivePairs::default();
+        pairs.set("main", "mini-player", true);
+        pairs.set("mini-player", "main", true);
+        assert_eq!(pairs.counterparts("main"), vec!["mini-player"]);
+        assert_eq!(pairs.counterparts("mini-player"), vec!["main"]);
+        assert!(pairs.counterparts("settings").is_empty());
+        pairs.set("mini-player", "main", false);
+        assert!(pairs.counterparts("main").is_empty());
+    }
+
+    #[test]
+    fn suppression_is_scoped() {
+        let pairs = ExclusivePairs::default();
+        {
+            let _outer = pairs.suppress();
+            let _inner = pairs.suppress();
+            assert!(pairs.is_suppressed());
+        }
+        assert!(!pairs.is_suppressed());
+    }
+}
@@ -0,0 +1,180 @@
+//! Keep effect-bearing windows hidden until their webview has painted.
+//!
+//! Acrylic/mica windows are created invisible, get their effects, and are
+//! only shown once the page calls [`notify_first_paint`]; otherwise the
+//! first frame is an opaque white rectangle. Show requests that arrive in
+//! between are recorded and replayed on paint. A timeout reveals the window
+//! anyway so a stuck page can never leave it hidden for good.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use log::{debug, warn};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager;
+
+/// Upper bound on how long a window may wait for its first paint.
+const FIRST_PAINT_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaintState {
+    /// Hidden until painted. `show`/`focus` are what to do once it has.
+    Waiting {
+        generation: u64,
+        show: bool,
+        focus: bool,
+    },
+    Painted,
+}
+
+#[derive(Debug, Default)]
+struct PaintGates {
+    states: HashMap<String, PaintState>,
+    next_generation: u64,
+}
+
+impl PaintGates {
+    fn begin(&mut self, label: &str, show: bool, focus: bool) -> u64 {
+        self.next_generation += 1;
+        let generation = self.next_generation;
+        self.states.insert(
+            label.to_string(),
+            PaintState::Waiting {
+                generation,
+                show,
+                focus,
+            },
+        );
+        generation
+    }
+
+    /// Record a show request; `false` when the window is not waiting and
+    /// should simply be shown now.
+    fn defer_show(&mut self, label: &str, focus: bool) -> bool {
+        match self.states.get_mut(label) {
+            Some(PaintState::Waiting {
+                show,
+                focus: wants_focus,
+                ..
+            }) => {
+                *show = true;
+                *wants_focus |= focus;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Leave the waiting state. A timeout passes its generation so it cannot
+    /// reveal a newer window that reused the label. Returns `(show, focus)`.
+    fn finish(&mut self, label: &str, timeout_generation: Option<u64>) -> Option<(bool, bool)> {
+        let state = self.states.get_mut(label)?;
+        match *state {
+            PaintState::Waiting {
+                generation,
+                show,
+                focus,
+            } if timeout_generation.map_or(true, |g| g == generation) => {
+                *state = PaintState::Painted;
+                Some((show, focus))
+            }
+            _ => None,
+        }
+    }
+}
+
+static GATES: LazyLock<Mutex<PaintGates>> = LazyLock::new(|| Mutex::new(PaintGates::default()));
+
+/// Windows with a native effect flash white if shown before painting.
+/// Pre-warmed windows are hidden anyway and apply effects later.
+pub(crate) fn needs_gate(config: &WindowConfig) -> bool {
+    config.window_effect.is_some() && !config.prewarm
+}
+
+/// Start waiting for `window`'s first paint; it is shown afterwards if
+/// `show` (or a later show request) asks for it.
+pub(crate) fn begin(window: &WebviewWindow, show: bool, focus: bool) {
+    let label = window.label().to_string();
+    let generation = GATES.lock().begin(&label, show, focus);
+    let app = window.app_handle().clone();
+    let spawned = std::thread::Builder::new()
+        .name("first-paint-timeout".into())
+        .spawn(move || {
+            std::thread::sleep(FIRST_PAINT_TIMEOUT);
+            if reveal(&app, &label, Some(generation)) {
+                warn!(
+                    "Window '{}' did not report its first paint within {:?}",
+                    label, FIRST_PAINT_TIMEOUT
+                );
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn first-paint timeout: {}", e);
+        reveal(window.app_handle(), window.label(), None);
+    }
+}
+
+/// Called by show paths before showing. Returns `true` when the window has
+/// not painted yet; it will then be shown (and focused) on first paint.
+pub(crate) fn defer_show(window: &WebviewWindow, focus: bool) -> bool {
+    GATES.lock().defer_show(window.label(), focus)
+}
+
+/// Returns whether the window was still waiting.
+fn reveal(app: &AppHandle, label: &str, timeout_generation: Option<u64>) -> bool {
+    let Some((show, focus)) = GATES.lock().finish(label, timeout_generation) else {
+        return false;
+    };
+    if !show {
+        return true;
+    }
+    if let Some(window) = app.get_webview_window(label) {
+        if let Err(e) = window.show() {
+            warn!("Failed to show '{}' after first paint: {}", label, e);
+            return true;
+        }
+        if focus {
+            let _ = window.set_focus();
+        }
+        manager::emit_visibility(app, label, true);
+    }
+    true
+}
+
+/// The page in `label` has rendered its first frame.
+#[tauri::command]
+pub fn notify_first_paint(app: AppHandle, label: String) {
+    if reveal(&app, &label, None) {
+        debug!("Window '{}' painted, revealing", label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn show_requests_wait_for_paint() {
+        let mut gates = PaintGates::default();
+        gates.begin("popup", false, false);
+        assert!(gates.defer_show("popup", true));
+        assert_eq!(gates.finish("popup", None), Some((true, true)));
+        // Painted: later shows go straight through, repeats are no-ops.
+        assert!(!gates.defer_show("popup", true));
+        assert_eq!(gates.finish("popup", None), None);
+        assert!(!gates.defer_show("unknown", false));
+    }
+
+    #[test]
+    fn stale_timeout_does_not_reveal_a_recreated_window() {
+        let mut gates = PaintGates::default();
+        let old = gates.begin("mini-player", true, true);
+        let new = gates.begin("mini-player", true, false);
+        assert_eq!(gates.finish("mini-player", Some(old)), None);
+        assert_eq!(gates.finish("mini-player", Some(new)), Some((true, false)));
+    }
+}
@@ -1,23 +1,173 @@
+use crate::debounce::Debounced;
+use crate::desktop::safe_mode;
+use crate::desktop::window::commands::WindowState;
 use crate::desktop::window::config::WindowConfig;
-use log::info;
+use crate::desktop::window::coords;
+use crate::desktop::window::desktop_lyrics;
+use crate::desktop::window::event_queue;
+use crate::desktop::window::events;
+use crate::desktop::window::exclusive;
+use crate::desktop::window::first_paint;
+use crate::desktop::window::opacity;
+use crate::desktop::window::pin::{
+    self, WindowPinChanged, WINDOW_ALWAYS_ON_TOP_CHANGED_EVENT, WINDOW_PIN_CHANGED_EVENT,
+};
+use crate::desktop::window::platform::appearance::{self, SystemTheme};
+use crate::desktop::window::presets;
+use crate::desktop::window::prewarm;
+use crate::desktop::window::recently_closed;
+use crate::desktop::window::recovery;
+use crate::desktop::window::session;
+use crate::desktop::window::titlebar;
+use crate::desktop::window::tray;
+use crate::desktop::window::zoom;
+use crate::desktop::WINDOW_STATE_FLAGS;
+use crate::settings::window::CloseBehavior;
+use crate::settings::SettingsStore;
+use log::{info, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::webview::PageLoadEvent;
 use tauri::window::EffectsBuilder;
 #[cfg(target_os = "windows")]
 use tauri::window::{Color, Effect};
 #[cfg(target_os = "macos")]
 use tauri::window::{Effect, EffectState};
 use tauri::{
-    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, WebviewUrl, WebviewWindow,
-    WebviewWindowBuilder,
+    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
 };
 
 #[cfg(target_os = "macos")]
 use tauri_plugin_decorum::WebviewWindowExt;
+use tauri_plugin_window_state::AppHandleExt;
+
+/// How long the frontend has to acknowledge `main-close-requested` before
+/// the backend hides the main window itself.
+const CLOSE_ACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How often watched windows call `window_heartbeat`.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// Beats a visible window may miss before it counts as hung.
+const MISSED_BEATS: u32 = 3;
+/// Quiet time after the last move or resize before main's geometry is saved.
+const STATE_SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Saves triggered by moves and resizes are at least this far apart.
+const STATE_SAVE_MIN_INTERVAL: Duration = Duration::from_secs(5);
+/// A watchdog tick this late means the machine slept rather than a webview
+/// hanging, and every window gets a fresh grace period.
+const RESUME_TOLERANCE: Duration = Duration::from_secs(30);
+
+pub const WINDOW_VISIBILITY_CHANGED_EVENT: &str = "window-visibility-changed";
+pub const WINDOW_UNRESPONSIVE_EVENT: &str = "window-unresponsive";
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowVisibilityChanged {
+    pub label: String,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowUnresponsive {
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    last: Instant,
+    enabled: bool,
+    /// The last recovery was a reload and no beat has come since.
+    reloaded: bool,
+}
+
+/// Windows that have sent a heartbeat, by label.
+static HEARTBEATS: LazyLock<Mutex<HashMap<String, Heartbeat>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// What a hung window is rebuilt from.
+type Recreate = (WindowConfig, Option<PhysicalPosition<i32>>);
+
+/// Hung windows destroyed for a rebuild. Destroying only queues the close,
+/// so they are recreated from their `Destroyed` event once the old window
+/// is really gone.
+static PENDING_RECREATE: LazyLock<Mutex<HashMap<String, Recreate>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static STATE_SAVER: Debounced = Debounced::new("window-state-save", STATE_SAVE_DEBOUNCE);
+/// When the debounced saver last wrote; held while it waits out the interval.
+static LAST_STATE_SAVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Windows last seen minimized by [`sync_minimized`].
+static MINIMIZED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+static CLOSE_REQUEST_SEQ: AtomicU64 = AtomicU64::new(0);
+static CLOSE_REQUEST_ACKED: AtomicU64 = AtomicU64::new(0);
+
+/// Managed effect name of every window built by `create_window`, so the
+/// tint can be changed for custom windows too, not only presets.
+#[derive(Default)]
+pub struct WindowEffects(Mutex<HashMap<String, String>>);
+
+impl WindowEffects {
+    fn record(&self, label: &str, effect: Option<&str>) {
+        let mut effects = self.0.lock();
+        match effect {
+            Some(effect) => effects.insert(label.to_string(), effect.to_string()),
+            None => effects.remove(label),
+        };
+    }
+
+    /// The effect `label` was created with, else its preset's.
+    fn lookup(&self, label: &str) -> Option<String> {
+        self.0
+            .lock()
+            .get(label)
+            .cloned()
+            .or_else(|| presets::lookup(label)?.window_effect)
+    }
+}
+
+/// Whether `set_content_protected` does anything here. Tauri accepts the
+/// call on Linux too but nothing hides the window from capture there.
+pub const CONTENT_PROTECTION_SUPPORTED: bool =
+    cfg!(any(target_os = "windows", target_os = "macos"));
+
+/// Managed set of windows excluded from screen capture. The OS offers no
+/// way to read the flag back, so it is tracked here.
+#[derive(Default)]
+pub struct ContentProtection(Mutex<HashSet<String>>);
+
+impl ContentProtection {
+    pub(crate) fn record(&self, label: &str, protected: bool) {
+        let mut labels = self.0.lock();
+        if protected {
+            labels.insert(label.to_string());
+        } else {
+            labels.remove(label);
+        }
+    }
+
+    pub fn is_protected(&self, label: &str) -> bool {
+        self.0.lock().contains(label)
+    }
+}
+
+/// Windows created at a saved position other than main.
+const RESTORED_LABELS: [&str; 2] = ["mini-player", desktop_lyrics::bounds::LABEL];
 
 /// Create or focus a window from a `WindowConfig`.
 ///
 /// If `config.single_instance` is true and a window with the same label already
 /// exists, it is shown and focused instead of creating a duplicate.
 pub fn create_window(app: &AppHandle, config: &WindowConfig) -> Result<(), String> {
+    let config = &*desktop_lyrics::orientation::oriented(app, config);
     let label = &config.label;
 
     // Single-instance check: focus existing window if it exists
@@ -25,16 +175,31 @@ pub fn create_window(app: &AppHandle, config: &WindowConfig) -> Result<(), Strin
         if let Some(existing) = app.get_webview_window(label) {
             info!("Window '{}' already exists, focusing", label);
             apply_runtime_size_constraints(&existing, config)?;
+            prewarm::activate(&existing);
+            session::note_opened(app, label);
+            exclusive::hide_counterparts(app, label);
+            if first_paint::defer_show(&existing, config.focusable) {
+                return Ok(());
+            }
             existing.show().map_err(|e| e.to_string())?;
             if existing.is_minimized().unwrap_or(false) {
                 existing.unminimize().map_err(|e| e.to_string())?;
             }
-            existing.set_focus().map_err(|e| e.to_string())?;
+            if config.focusable {
+                existing.set_focus().map_err(|e| e.to_string())?;
+            }
             return Ok(());
         }
     }
 
-    info!("Creating window '{}'", label);
+    info!(
+        "Creating window '{}'{}",
+        label,
+        if config.prewarm { " (pre-warm)" } else { "" }
+    );
+    let visible = config.visible && !config.prewarm;
+    // Effect windows stay hidden until painted; `first_paint` shows them.
+    let gated = first_paint::needs_gate(config);
 
     let url = WebviewUrl::App(config.url.clone().into());
     let mut builder = WebviewWindowBuilder::new(app, label, url)
@@ -48,16 +213,37 @@ pub fn create_window(app: &AppHandle, config: &WindowConfig) -> Result<(), Strin
         builder = builder.transparent(config.transparent);
     }
 
+    let always_on_top = pin::effective_always_on_top(app, config);
     let mut builder = builder
-        .always_on_top(config.always_on_top)
+        .always_on_top(always_on_top)
         .skip_taskbar(config.skip_taskbar)
-        .visible(config.visible)
-        .shadow(config.shadow);
+        .visible(visible && !gated)
+        .focusable(config.focusable)
+        .shadow(config.shadow)
+        .content_protected(config.content_protected && CONTENT_PROTECTION_SUPPORTED);
+
+    if !config.focusable {
+        builder = builder.focused(false);
+    }
 
-    if config.center {
+    // Zoom only sticks once the page exists; re-apply on every load.
+    builder = builder.on_page_load(|window, payload| {
+        if payload.event() == PageLoadEvent::Finished {
+            zoom::apply_saved(&window);
+        }
+    });
+
+    if config.center && !config.prewarm {
         builder = builder.center();
     }
 
+    // Desktop lyrics keep their own bounds; set them before the first show.
+    if label == desktop_lyrics::bounds::LABEL && !safe_mode::is_active() {
+        if let Some((x, y, width, height)) = desktop_lyrics::bounds::restored_logical(app) {
+            builder = builder.position(x, y).inner_size(width, height);
+        }
+    }
+
     #[cfg(target_os = "windows")]
     if let Some(args) = config.effective_additional_args() {
         let args = args.trim();
@@ -94,44 +280,107 @@ pub fn create_window(app: &AppHandle, config: &WindowConfig) -> Result<(), Strin
         }
     }
 
+    if visible {
+        exclusive::hide_counterparts(app, label);
+    }
     let _window = builder.build().map_err(|e| e.to_string())?;
     apply_runtime_size_constraints(&_window, config)?;
 
+    // Some platforms drop the builder flag for undecorated/transparent
+    // windows, so apply the resolved pin state again once built.
+    let _ = _window.set_always_on_top(always_on_top);
+    pin::record_created(app, label, always_on_top);
+    if let Some(effects) = app.try_state::<WindowEffects>() {
+        effects.record(label, config.window_effect.as_deref());
+    }
+    if config.content_protected && !CONTENT_PROTECTION_SUPPORTED {
+        warn!(
+            "Window '{}' asked for content protection, which this platform lacks",
+            label
+        );
+    }
+    if let Some(protection) = app.try_state::<ContentProtection>() {
+        protection.record(
+            label,
+            config.content_protected && CONTENT_PROTECTION_SUPPORTED,
+        );
+    }
+    events::note_created(app, label);
+    // Events for the new page wait in the queue until it calls `window_ready`.
+    event_queue::note_not_ready(label);
+    if !config.prewarm {
+        session::note_opened(app, label);
+    }
+    if label == desktop_lyrics::bounds::LABEL {
+        desktop_lyrics::fullscreen::start(app);
+        opacity::restore_saved(&_window);
+    }
+    #[cfg(target_os = "linux")]
+    crate::desktop::window::cursor::track(&_window);
+    #[cfg(target_os = "windows")]
+    if label == "main" {
+        crate::desktop::window::platform::windows_taskbar::attach(app, &_window);
+    }
+
+    // Safe mode: undo whatever geometry the window-state plugin restored.
+    if safe_mode::is_active() {
+        let _ = _window.set_size(LogicalSize::new(config.width, config.height));
+        let _ = _window.center();
+    } else if RESTORED_LABELS.contains(&label.as_str()) {
+        // The saved monitor may be gone; main is checked after its own
+        // deferred restore in `setup`.
+        if let Err(e) = coords::ensure_on_screen(app, label) {
+            warn!("Failed to check '{}' is on screen: {}", label, e);
+        }
+    }
+
+    if config.prewarm {
+        prewarm::register(&_window);
+    } else {
+        apply_appearance(&_window, config)?;
+    }
+    if gated {
+        first_paint::begin(&_window, visible, config.focusable);
+    }
+
+    info!("Window '{}' created successfully", label);
+    Ok(())
+}
+
+/// Native effects and titlebar tweaks that follow window creation. Split out
+/// so pre-warmed windows can get them when they are first shown.
+pub(crate) fn apply_appearance(
+    window: &WebviewWindow,
+    config: &WindowConfig,
+) -> Result<(), String> {
     // Apply native window effects (acrylic, mica, etc.) if configured.
     // Uses set_effects() on the built window because WebviewWindowBuilder
     // does not reliably pass effects to the underlying WindowBuilder.
     if let Some(ref effect_name) = config.window_effect {
         if let Some(effects) = build_window_effects(effect_name) {
-            let _ = _window.set_effects(effects);
+            let _ = window.set_effects(effects);
         }
     }
 
-    // Apply decorum overlay titlebar (macOS only — Windows/Linux use DOM-based titlebar)
-    #[cfg(target_os = "macos")]
-    if config.use_overlay_titlebar {
-        _window
-            .create_overlay_titlebar()
-            .map_err(|e| e.to_string())?;
-    }
+    titlebar::apply(window, config);
 
     // macOS-specific: traffic lights and transparency
     #[cfg(target_os = "macos")]
     {
         if let Some((x, y)) = config.traffic_lights_inset {
-            _window
+            window
                 .set_traffic_lights_inset(x, y)
                 .map_err(|e| e.to_string())?;
         }
         if config.transparent {
-            _window.make_transparent().map_err(|e| e.to_string())?;
+            window.make_transparent().map_err(|e| e.to_string())?;
         }
     }
 
-    info!("Window '{}' created successfully", label);
     Ok(())
 }
 
-fn apply_runtime_size_constraints(
+pub(crate) fn apply_runtime_size_constraints(
     window: &WebviewWindow,
     config: &WindowConfig,
 ) -> Result<(), String> {
@@ -161,12 +410,32 @@ pub fn show_window(app: &AppHandle, label: &str) -> Result<(), String> {
     let window = app
         .get_webview_window(label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
+    prewarm::activate(&window);
+    exclusive::hide_counterparts(app, label);
+    if first_paint::defer_show(&window, true) {
+        return Ok(());
+    }
     window.show().map_err(|e| e.to_string())?;
     window.set_focus().map_err(|e| e.to_string())?;
+    emit_visibility(app, label, true);
+    Ok(())
+}
+
+/// Announce a visibility change. `main-window-visibility` predates the
+/// generic event and is kept for existing listeners.
+pub(crate) fn emit_visibility(app: &AppHandle, label: &str, visible: bool) {
+    tray::note_visibility(label, visible);
+    session::note_visibility(app, label, visible);
     if label == "main" {
-        let _ = app.emit("main-window-visibility", true);
+        let _ = app.emit("main-window-visibility", visible);
     }
-    Ok(())
+    let _ = app.emit(
+        WINDOW_VISIBILITY_CHANGED_EVENT,
+        WindowVisibilityChanged {
+            label: label.to_string(),
+            visible,
+        },
+    );
 }
 
 /// Hide a window by label.
@@ -175,29 +444,349 @@ pub fn hide_window(app: &AppHandle, label: &str) -> Result<(), String> {
         .get_webview_window(label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
     window.hide().map_err(|e| e.to_string())?;
-    if label == "main" {
-        let _ = app.emit("main-window-visibility", false);
-    }
+    emit_visibility(app, label, false);
     Ok(())
 }
 
-/// Close a window by label.
-/// If the window's preset has `closeable_to_tray`, it is hidden instead of destroyed.
+/// Close a window by label, following its close behavior (see
+/// [`close_behavior`]).
 pub fn close_window(app: &AppHandle, label: &str) -> Result<(), String> {
-    // Check if this window should hide-to-tray instead of closing
-    if let Some(preset) = WindowConfig::from_label(label) {
-        if preset.closeable_to_tray {
-            info!("Window '{}' is closeable-to-tray, hiding instead", label);
-            return hide_window(app, label);
+    request_close(app, label)
+}
+
+/// What closing `label` does: the saved choice, else hide for presets with
+/// `closeable_to_tray`, else close.
+pub fn close_behavior(app: &AppHandle, label: &str) -> CloseBehavior {
+    let hides_by_default = presets::lookup(label).is_some_and(|p| p.closeable_to_tray);
+    app.try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.window.clone()))
+        .unwrap_or_default()
+        .close_behavior_for(label, hides_by_default)
+}
+
+/// Whether the native close of `label` has to be intercepted, i.e. its
+/// close behavior is anything but simply closing it.
+pub fn intercepts_close(app: &AppHandle, label: &str) -> bool {
+    label == "main" || close_behavior(app, label) != CloseBehavior::Exit
+}
+
+/// Handle a close of a window according to its close behavior. Shared by
+/// the native close button and `close_managed_window`.
+pub fn request_close(app: &AppHandle, label: &str) -> Result<(), String> {
+    match close_behavior(app, label) {
+        CloseBehavior::Hide => {
+            info!("Window '{}' hides on close", label);
+            hide_window(app, label)
+        }
+        CloseBehavior::Exit if label == "main" => {
+            crate::shutdown::quit(app, false);
+            Ok(())
+        }
+        CloseBehavior::Exit => destroy_window(app, label),
+        CloseBehavior::Ask if label == "main" => {
+            ask_close(app, label);
+            Ok(())
         }
+        CloseBehavior::Ask => ask_close_natively(app, label),
     }
+}
 
+fn destroy_window(app: &AppHandle, label: &str) -> Result<(), String> {
     let window = app
         .get_webview_window(label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
+    recently_closed::note_closing(&window);
     window.destroy().map_err(|e| e.to_string())
 }
 
+/// Ask whether to hide or close a window other than main. Those windows
+/// have no dialog of their own, so the question is a native one.
+fn ask_close_natively(app: &AppHandle, label: &str) -> Result<(), String> {
+    use crate::desktop::i18n::{tr, Text};
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let title = window.title().unwrap_or_default();
+    let handle = app.clone();
+    let label = label.to_string();
+    app.dialog()
+        .message(tr(Text::CloseAskMessage))
+        .title(title)
+        .parent(&window)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            tr(Text::CloseAskHide).into(),
+            tr(Text::CloseAskClose).into(),
+        ))
+        .show(move |hide| {
+            let result = if hide {
+                hide_window(&handle, &label)
+            } else {
+                destroy_window(&handle, &label)
+            };
+            if let Err(e) = result {
+                warn!("Failed to close '{}' as asked: {}", label, e);
+            }
+        });
+    Ok(())
+}
+
+/// Let the frontend show its close dialog. If it does not acknowledge the
+/// request in time (busy or hung webview), hide the window so the close
+/// button never silently does nothing.
+fn ask_close(app: &AppHandle, label: &str) {
+    let seq = CLOSE_REQUEST_SEQ.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = app.emit("main-close-requested", ());
+
+    let app = app.clone();
+    let label = label.to_string();
+    let spawned = std::thread::Builder::new()
+        .name("close-ack-watchdog".into())
+        .spawn(move || {
+            std::thread::sleep(CLOSE_ACK_TIMEOUT);
+            if CLOSE_REQUEST_ACKED.load(Ordering::SeqCst) < seq {
+                warn!("Close request was not acknowledged, hiding '{}'", label);
+                let _ = hide_window(&app, &label);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn close watchdog: {}", e);
+    }
+}
+
+/// Mark every outstanding close request as handled by the frontend.
+pub fn ack_close_request() {
+    CLOSE_REQUEST_ACKED.fetch_max(CLOSE_REQUEST_SEQ.load(Ordering::SeqCst), Ordering::SeqCst);
+}
+
+/// Last step of a quit: persist window geometry, then exit. Quitting
+/// goes through `shutdown::quit`, which gives windows and hooks their
+/// turn first.
+pub fn exit_app(app: &AppHandle) {
+    recently_closed::mark_shutting_down(app);
+    let _ = app.save_window_state(WINDOW_STATE_FLAGS);
+    app.exit(0);
+}
+
+/// Record a heartbeat from `label`; its first one puts it under watch.
+pub fn heartbeat(label: &str) {
+    let mut beats = HEARTBEATS.lock();
+    let beat = beats.entry(label.to_string()).or_insert(Heartbeat {
+        last: Instant::now(),
+        enabled: true,
+        reloaded: false,
+    });
+    beat.last = Instant::now();
+    beat.reloaded = false;
+}
+
+/// Turn the hang watchdog off or back on for `label`.
+pub fn set_watchdog_enabled(label: &str, enabled: bool) {
+    let mut beats = HEARTBEATS.lock();
+    let beat = beats.entry(label.to_string()).or_insert(Heartbeat {
+        last: Instant::now(),
+        enabled,
+        reloaded: false,
+    });
+    if enabled && !beat.enabled {
+        beat.last = Instant::now();
+    }
+    beat.enabled = enabled;
+}
+
+/// Stop watching a destroyed window; a recreated one starts over with its
+/// first beat.
+pub(crate) fn forget_heartbeat(label: &str) {
+    HEARTBEATS.lock().remove(label);
+}
+
+/// Labels of enabled windows that have missed [`MISSED_BEATS`] beats at
+/// `now`. Windows `watchable` rejects (hidden or minimized, and so
+/// throttled on purpose) get their clock restarted instead, so showing
+/// them again does not count the hidden time against them. Reported
+/// windows also restart their clock, giving the recovery time to load.
+fn hung_windows(
+    beats: &mut HashMap<String, Heartbeat>,
+    now: Instant,
+    watchable: impl Fn(&str) -> bool,
+) -> Vec<(String, bool)> {
+    let limit = HEARTBEAT_INTERVAL * MISSED_BEATS;
+    let mut hung = Vec::new();
+    for (label, beat) in beats.iter_mut() {
+        if !beat.enabled || now.saturating_duration_since(beat.last) < limit {
+            continue;
+        }
+        if watchable(label) {
+            hung.push((label.clone(), beat.reloaded));
+            beat.reloaded = true;
+        }
+        beat.last = now;
+    }
+    hung
+}
+
+/// Reload a hung webview; if a reload already failed to bring it back,
+/// destroy the window so [`recreate_recovered`] rebuilds it from its
+/// preset at the same position. Main goes through [`recovery`] instead,
+/// so the new page can pick up the session.
+fn recover_hung_window(app: &AppHandle, label: &str, reloaded_before: bool) {
+    warn!(
+        "Window '{}' missed {} heartbeats, {}",
+        label,
+        MISSED_BEATS,
+        if reloaded_before {
+            "recreating it"
+        } else {
+            "reloading it"
+        }
+    );
+    let _ = app.emit(
+        WINDOW_UNRESPONSIVE_EVENT,
+        WindowUnresponsive {
+            label: label.to_string(),
+        },
+    );
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    if !reloaded_before {
+        if label == "main" {
+            // A reload loses the frontend state just like a rebuild.
+            recovery::collect_recovery_payload(app);
+        }
+        match window.reload() {
+            Ok(()) => return,
+            Err(e) => warn!("Failed to reload '{}': {}", label, e),
+        }
+    }
+    if label == "main" {
+        if let Err(e) = recovery::recreate_main_window(app) {
+            warn!("Failed to recreate hung main window: {}", e);
+        }
+        return;
+    }
+    let Some(config) = presets::lookup(label) else {
+        warn!("No preset to recreate '{}' from", label);
+        return;
+    };
+    let position = window.outer_position().ok();
+    if let Err(e) = destroy_for_recreate(&window, config, position) {
+        warn!("Failed to destroy hung window '{}': {}", label, e);
+    }
+}
+
+/// Destroy `window` and rebuild it from `config` (at `position`, if given)
+/// once it is really gone; see [`recreate_recovered`].
+pub(crate) fn destroy_for_recreate(
+    window: &WebviewWindow,
+    config: WindowConfig,
+    position: Option<PhysicalPosition<i32>>,
+) -> Result<(), String> {
+    let label = window.label().to_string();
+    PENDING_RECREATE
+        .lock()
+        .insert(label.clone(), (config, position));
+    window.destroy().map_err(|e| {
+        PENDING_RECREATE.lock().remove(&label);
+        e.to_string()
+    })
+}
+
+/// Run-loop hook for `WindowEvent::Destroyed`, after the per-window
+/// cleanup: rebuild the window if a hang recovery destroyed it.
+pub(crate) fn recreate_recovered(app: &AppHandle, label: &str) {
+    let Some((config, position)) = PENDING_RECREATE.lock().remove(label) else {
+        return;
+    };
+    let recreated = create_window(app, &config).and_then(|()| {
+        let window = app
+            .get_webview_window(label)
+            .ok_or_else(|| format!("Window '{}' not found", label))?;
+        if let Some(position) = position {
+            window.set_position(position).map_err(|e| e.to_string())?;
+        }
+        if label == "main" {
+            recovery::show_recreated_main(app, &window)?;
+        }
+        Ok(())
+    });
+    match recreated {
+        Ok(()) => info!("Window '{}' recreated", label),
+        Err(e) => warn!("Failed to recreate window '{}': {}", label, e),
+    }
+}
+
+/// Check heartbeats on a background thread for the life of the app.
+pub fn start_watchdog(app: &AppHandle) {
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("webview-watchdog".into())
+        .spawn(move || {
+            let mut last_tick = Instant::now();
+            loop {
+                std::thread::sleep(HEARTBEAT_INTERVAL);
+                let now = Instant::now();
+                let slept = now.saturating_duration_since(last_tick)
+                    > HEARTBEAT_INTERVAL + RESUME_TOLERANCE;
+                last_tick = now;
+                if slept {
+                    info!("Resumed from sleep, restarting heartbeat clocks");
+                    for beat in HEARTBEATS.lock().values_mut() {
+                        beat.last = now;
+                    }
+                    continue;
+                }
+                let hung = hung_windows(&mut HEARTBEATS.lock(), now, |label| {
+                    app.get_webview_window(label).is_some_and(|window| {
+                        window.is_visible().unwrap_or(false)
+                            && !window.is_minimized().unwrap_or(false)
+                    })
+                });
+                for (label, reloaded_before) in hung {
+                    let app = app.clone();
+                    let _ = app.clone().run_on_main_thread(move || {
+                        recover_hung_window(&app, &label, reloaded_before);
+                    });
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn webview watchdog: {}", e);
+    }
+}
+
+/// Persist window geometry now. Skipped while main is minimized, since
+/// Windows then reports it parked at -32000.
+pub fn flush_window_state(app: &AppHandle) -> Result<(), String> {
+    STATE_SAVER.take_pending();
+    let minimized = app
+        .get_webview_window("main")
+        .is_some_and(|main| main.is_minimized().unwrap_or(false));
+    if minimized {
+        return Ok(());
+    }
+    app.save_window_state(WINDOW_STATE_FLAGS)
+        .map_err(|e| e.to_string())
+}
+
+/// Save window geometry once main has stopped moving or resizing, so a
+/// crash or kill does not lose the session's layout. Bursts coalesce into
+/// one write, and writes are spaced by [`STATE_SAVE_MIN_INTERVAL`].
+pub fn schedule_state_save(app: &AppHandle) {
+    let app = app.clone();
+    STATE_SAVER.trigger(move || {
+        let mut last = LAST_STATE_SAVE.lock();
+        if let Some(last) = *last {
+            std::thread::sleep(STATE_SAVE_MIN_INTERVAL.saturating_sub(last.elapsed()));
+        }
+        if let Err(e) = flush_window_state(&app) {
+            warn!("Failed to save window state: {}", e);
+        }
+        *last = Some(Instant::now());
+    });
+}
+
 /// Toggle visibility of a window by label.
 pub fn toggle_window(app: &AppHandle, label: &str) -> Result<(), String> {
     let window = app
@@ -207,11 +796,14 @@ pub fn toggle_window(app: &AppHandle, label: &str) -> Result<(), String> {
     let is_visible = window.is_visible().map_err(|e| e.to_string())?;
     if is_visible {
         window.hide().map_err(|e| e.to_string())?;
-        if label == "main" {
-            let _ = app.emit("main-window-visibility", false);
-        }
+        emit_visibility(app, label, false);
         Ok(())
     } else {
+        prewarm::activate(&window);
+        exclusive::hide_counterparts(app, label);
+        if first_paint::defer_show(&window, true) {
+            return Ok(());
+        }
         window.show().map_err(|e| e.to_string())?;
         // Only unminimize if actually minimized — calling unminimize on a
         // hidden-but-not-minimized window can reset its size on Windows.
@@ -219,9 +811,7 @@ pub fn toggle_window(app: &AppHandle, label: &str) -> Result<(), String> {
             window.unminimize().map_err(|e| e.to_string())?;
         }
         window.set_focus().map_err(|e| e.to_string())?;
-        if label == "main" {
-            let _ = app.emit("main-window-visibility", true);
-        }
+        emit_visibility(app, label, true);
         Ok(())
     }
 }
@@ -231,14 +821,113 @@ pub fn focus_window(app: &AppHandle, label: &str) -> Result<(), String> {
     let window = app
         .get_webview_window(label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
+    prewarm::activate(&window);
+    exclusive::hide_counterparts(app, label);
+    if first_paint::defer_show(&window, true) {
+        return Ok(());
+    }
     window.show().map_err(|e| e.to_string())?;
     if window.is_minimized().unwrap_or(false) {
         window.unminimize().map_err(|e| e.to_string())?;
     }
     window.set_focus().map_err(|e| e.to_string())?;
-    if label == "main" {
-        let _ = app.emit("main-window-visibility", true);
+    emit_visibility(app, label, true);
+    Ok(())
+}
+
+/// Maximize a window by label.
+pub fn maximize_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    window.maximize().map_err(|e| e.to_string())
+}
+
+/// Restore a maximized window to its previous size.
+pub fn unmaximize_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    window.unmaximize().map_err(|e| e.to_string())
+}
+
+/// Maximize or restore a window. Returns whether it is now maximized.
+pub fn toggle_maximize(app: &AppHandle, label: &str) -> Result<bool, String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    if window.is_maximized().map_err(|e| e.to_string())? {
+        window.unmaximize().map_err(|e| e.to_string())?;
+        Ok(false)
+    } else {
+        window.maximize().map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
+
+/// Minimize a window by label. A minimized window counts as hidden, like
+/// one hidden to the tray, until it is restored.
+pub fn minimize_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    window.minimize().map_err(|e| e.to_string())?;
+    sync_minimized(app, label);
+    Ok(())
+}
+
+/// Announce minimizing and restoring through [`emit_visibility`]. Runs
+/// after `minimize_window` and on resize and focus events, which also
+/// catch the title bar button and restores from the taskbar or dock.
+pub(crate) fn sync_minimized(app: &AppHandle, label: &str) {
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let minimized = window.is_minimized().unwrap_or(false);
+    let changed = {
+        let mut seen = MINIMIZED.lock();
+        if minimized {
+            seen.insert(label.to_string())
+        } else {
+            seen.remove(label)
+        }
+    };
+    if changed {
+        emit_visibility(app, label, !minimized);
     }
+}
+
+pub(crate) fn forget_minimized(label: &str) {
+    MINIMIZED.lock().remove(label);
+}
+
+/// Drop the effect and capture-protection records of a destroyed window.
+pub(crate) fn forget_window_records(app: &AppHandle, label: &str) {
+    if let Some(effects) = app.try_state::<WindowEffects>() {
+        effects.record(label, None);
+    }
+    if let Some(protection) = app.try_state::<ContentProtection>() {
+        protection.record(label, false);
+    }
+}
+
+/// Pin or unpin a window at runtime. Counts as an explicit user choice:
+/// it overrides preset defaults for the rest of the session and is
+/// persisted for the main window and mini player.
+pub fn set_always_on_top(app: &AppHandle, label: &str, on_top: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    window
+        .set_always_on_top(on_top)
+        .map_err(|e| e.to_string())?;
+    pin::record_user_choice(app, label, on_top)?;
+    let changed = WindowPinChanged {
+        label: label.to_string(),
+        always_on_top: on_top,
+    };
+    let _ = app.emit(WINDOW_PIN_CHANGED_EVENT, changed.clone());
+    let _ = app.emit(WINDOW_ALWAYS_ON_TOP_CHANGED_EVENT, changed);
     Ok(())
 }
 
@@ -255,6 +944,24 @@ pub fn is_window_visible(app: &AppHandle, label: &str) -> Result<bool, String> {
     window.is_visible().map_err(|e| e.to_string())
 }
 
+/// Snapshot a window's flags; all `false` if it does not exist.
+pub fn window_state(app: &AppHandle, label: &str) -> WindowState {
+    let Some(window) = app.get_webview_window(label) else {
+        return WindowState::default();
+    };
+    WindowState {
+        exists: true,
+        visible: window.is_visible().unwrap_or(false),
+        focused: window.is_focused().unwrap_or(false),
+        minimized: window.is_minimized().unwrap_or(false),
+        maximized: window.is_maximized().unwrap_or(false),
+        fullscreen: window.is_fullscreen().unwrap_or(false),
+        content_protected: app
+            .try_state::<ContentProtection>()
+            .is_some_and(|protection| protection.is_protected(label)),
+    }
+}
+
 /// List all open window labels.
 pub fn list_windows(app: &AppHandle) -> Vec<String> {
     app.webview_windows().keys().cloned().collect()
@@ -287,10 +994,82 @@ pub fn show_window_at_position(app: &AppHandle, label: &str, x: f64, y: f64) ->
     window
         .set_position(PhysicalPosition::new(x as i32, y as i32))
         .map_err(|e| e.to_string())?;
+    // First open of the pre-created tray popup: shown once it has painted.
+    if first_paint::defer_show(&window, true) {
+        return Ok(());
+    }
     window.show().map_err(|e| e.to_string())?;
     window.set_focus().map_err(|e| e.to_string())
 }
 
+/// Screen cursor position: physical pixels, except points on macOS.
+pub fn cursor_position(app: &AppHandle) -> Result<(i32, i32), String> {
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::POINT;
+        use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+        let mut pt = POINT::default();
+        // SAFETY: `pt` is a live out-parameter.
+        unsafe { GetCursorPos(&mut pt) }.map_err(|e| format!("GetCursorPos failed: {}", e))?;
+        Ok((pt.x, pt.y))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use core_graphics::event::CGEvent;
+        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+        let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
+            .map_err(|_| "Failed to create CGEventSource".to_string())?;
+        let event = CGEvent::new(source).map_err(|_| "Failed to create CGEvent".to_string())?;
+        let loc = event.location();
+        Ok((loc.x as i32, loc.y as i32))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::desktop::window::cursor::cursor_position(app)
+    }
+}
+
+/// Whether any of the three main mouse buttons is held down. `None` where
+/// the platform will not say (Wayland).
+pub(crate) fn mouse_button_down() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            GetAsyncKeyState, VK_LBUTTON, VK_MBUTTON, VK_RBUTTON,
+        };
+
+        // SAFETY: a plain key-state query. The high bit means "down".
+        let down = [VK_LBUTTON, VK_RBUTTON, VK_MBUTTON]
+            .into_iter()
+            .any(|key| unsafe { GetAsyncKeyState(key.0.into()) } < 0);
+        Some(down)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        const COMBINED_SESSION_STATE: i32 = 0;
+        #[link(name = "CoreGraphics", kind = "framework")]
+        extern "C" {
+            fn CGEventSourceButtonState(state: i32, button: u32) -> bool;
+        }
+        // SAFETY: a plain button-state query.
+        let down = (0..3)
+            .any(|button| unsafe { CGEventSourceButtonState(COMBINED_SESSION_STATE, button) });
+        Some(down)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::desktop::window::cursor::button_down()
+    }
+}
+
 /// Set whether a window ignores cursor events (click-through).
 pub fn set_ignore_cursor_events(app: &AppHandle, label: &str, ignore: bool) -> Result<(), String> {
     let window = app
@@ -322,12 +1101,383 @@ pub fn set_window_position(app: &AppHandle, label: &str, x: i32, y: i32) -> Resu
         .map_err(|e| e.to_string())
 }
 
+/// Gap kept between an edge-anchored window and the work-area edge, in
+/// logical pixels of the target monitor.
+const MONITOR_EDGE_MARGIN: f64 = 48.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ScreenPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ScreenSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    /// A monitor's work area: its bounds minus taskbars and docks.
+    pub fn work_area(monitor: &tauri::Monitor) -> Self {
+        let area = monitor.work_area();
+        Self {
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+}
+
+/// A monitor's rectangle (physical) and scale, detached from the handle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorArea {
+    pub name: Option<String>,
+    pub rect: ScreenRect,
+    pub scale_factor: f64,
+}
+
+impl MonitorArea {
+    /// The monitor's full bounds.
+    pub fn bounds(monitor: &tauri::Monitor) -> Self {
+        let (position, size) = (monitor.position(), monitor.size());
+        Self::new(
+            monitor,
+            ScreenRect {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            },
+        )
+    }
+
+    /// The monitor's work area.
+    pub fn work_area(monitor: &tauri::Monitor) -> Self {
+        Self::new(monitor, ScreenRect::work_area(monitor))
+    }
+
+    fn new(monitor: &tauri::Monitor, rect: ScreenRect) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            rect,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+
+    /// Whether a logical point lies on this monitor.
+    pub fn contains_logical(&self, x: f64, y: f64) -> bool {
+        let scale = self.scale_factor;
+        let ScreenRect {
+            x: left,
+            y: top,
+            width,
+            height,
+        } = self.rect;
+        let (left, top) = (left as f64 / scale, top as f64 / scale);
+        let (width, height) = (width as f64 / scale, height as f64 / scale);
+        x >= left && x < left + width && y >= top && y < top + height
+    }
+}
+
+/// A connected monitor, in physical pixels.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub position: ScreenPoint,
+    pub size: ScreenSize,
+    pub work_area: ScreenRect,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+/// Where `move_window_to_monitor` puts the window on the target monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum MonitorAnchor {
+    Center,
+    BottomCenter,
+    TopCenter,
+    /// Same relative spot in the work area as on the current monitor.
+    Remember,
+}
+
+pub fn list_monitors(app: &AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|m| (m.name().cloned(), *m.position()));
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .iter()
+        .map(|m| MonitorInfo {
+            name: m.name().cloned(),
+            position: ScreenPoint {
+                x: m.position().x,
+                y: m.position().y,
+            },
+            size: ScreenSize {
+                width: m.size().width,
+                height: m.size().height,
+            },
+            work_area: ScreenRect::work_area(m),
+            scale_factor: m.scale_factor(),
+            is_primary: primary.as_ref().is_some_and(|(name, position)| {
+                name.as_ref() == m.name() && position == m.position()
+            }),
+        })
+        .collect())
+}
+
+/// Bounds for a window moved from `from` (scale `from_scale`) to the work
+/// area `to` (scale `to_scale`). The size is rescaled so the window keeps
+/// its apparent size, then clamped to the target work area.
+fn place_on_monitor(
+    window: ScreenRect,
+    from: ScreenRect,
+    from_scale: f64,
+    to: ScreenRect,
+    to_scale: f64,
+    anchor: MonitorAnchor,
+) -> ScreenRect {
+    let ratio = to_scale / from_scale;
+    let width = ((window.width as f64 * ratio).round() as u32).min(to.width);
+    let height = ((window.height as f64 * ratio).round() as u32).min(to.height);
+    let free_x = (to.width - width) as f64;
+    let free_y = (to.height - height) as f64;
+    let margin = (MONITOR_EDGE_MARGIN * to_scale).min(free_y);
+
+    let (dx, dy) = match anchor {
+        MonitorAnchor::Center => (free_x / 2.0, free_y / 2.0),
+        MonitorAnchor::BottomCenter => (free_x / 2.0, free_y - margin),
+        MonitorAnchor::TopCenter => (free_x / 2.0, margin),
+        MonitorAnchor::Remember => {
+            // Fraction of the free space left of / above the window before.
+            let fraction = |offset: i32, free: i64| {
+                if free <= 0 {
+                    0.5
+                } else {
+                    (offset as f64 / free as f64).clamp(0.0, 1.0)
+                }
+            };
+            let fx = fraction(window.x - from.x, from.width as i64 - window.width as i64);
+            let fy = fraction(window.y - from.y, from.height as i64 - window.height as i64);
+            (free_x * fx, free_y * fy)
+        }
+    };
+    ScreenRect {
+        x: to.x + dx.round() as i32,
+        y: to.y + dy.round() as i32,
+        width,
+        height,
+    }
+}
+
+/// Top-left corner that keeps a `width` x `height` window placed at
+/// `(x, y)` inside `area`, moving it as little as possible. A window larger
+/// than the area is pinned to the area's top-left.
+pub(crate) fn clamp_to_monitor(
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    area: ScreenRect,
+) -> (f64, f64) {
+    let (left, top) = (area.x as f64, area.y as f64);
+    let right = left + area.width as f64;
+    let bottom = top + area.height as f64;
+    (
+        x.clamp(left, (right - width).max(left)),
+        y.clamp(top, (bottom - height).max(top)),
+    )
+}
+
+/// Move a window onto the monitor named `monitor_name`, keeping its
+/// apparent size across mixed-DPI displays. Returns the new physical
+/// position.
+pub fn move_window_to_monitor(
+    app: &AppHandle,
+    label: &str,
+    monitor_name: &str,
+    anchor: MonitorAnchor,
+) -> Result<ScreenPoint, String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let target = window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|m| m.name().map(String::as_str) == Some(monitor_name))
+        .ok_or_else(|| format!("Monitor '{}' not found", monitor_name))?;
+    let current = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .or_else(|| window.primary_monitor().ok().flatten())
+        .ok_or("No monitor found for the window")?;
+
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let inner = window.inner_size().map_err(|e| e.to_string())?;
+    let placed = place_on_monitor(
+        ScreenRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+        ScreenRect::work_area(&current),
+        current.scale_factor(),
+        ScreenRect::work_area(&target),
+        target.scale_factor(),
+        anchor,
+    );
+
+    window
+        .set_position(PhysicalPosition::new(placed.x, placed.y))
+        .map_err(|e| e.to_string())?;
+    // Crossing monitors may already have rescaled the window; set the size
+    // explicitly so it ends up the same either way.
+    let ratio = target.scale_factor() / current.scale_factor();
+    let frame_w = size.width.saturating_sub(inner.width) as f64 * ratio;
+    let frame_h = size.height.saturating_sub(inner.height) as f64 * ratio;
+    window
+        .set_size(PhysicalSize::new(
+            (placed.width as f64 - frame_w).round().max(1.0) as u32,
+            (placed.height as f64 - frame_h).round().max(1.0) as u32,
+        ))
+        .map_err(|e| e.to_string())?;
+    Ok(ScreenPoint {
+        x: placed.x,
+        y: placed.y,
+    })
+}
+
+/// Effect names a preset can use in `window_effect`. Windows materials map
+/// to the HUD look on macOS, and `vibrancy[:material]` (macOS only) picks an
+/// `NSVisualEffectView` material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NamedEffect {
+    Acrylic,
+    Mica,
+    MicaDark,
+    Tabbed,
+    Blur,
+    Vibrancy(VibrancyMaterial),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+enum VibrancyMaterial {
+    UnderWindow,
+    Sidebar,
+    Menu,
+    Popover,
+    Hud,
+    Titlebar,
+    Content,
+}
+
+impl NamedEffect {
+    fn parse(name: &str) -> Option<Self> {
+        let (base, material) = match name.split_once(':') {
+            Some((base, material)) => (base, Some(material)),
+            None => (name, None),
+        };
+        let effect = match (base, material) {
+            ("acrylic", None) => Self::Acrylic,
+            ("mica", None) => Self::Mica,
+            ("mica_dark", None) => Self::MicaDark,
+            ("tabbed", None) => Self::Tabbed,
+            ("blur", None) => Self::Blur,
+            ("vibrancy", material) => Self::Vibrancy(match material.unwrap_or("under-window") {
+                "under-window" => VibrancyMaterial::UnderWindow,
+                "sidebar" => VibrancyMaterial::Sidebar,
+                "menu" => VibrancyMaterial::Menu,
+                "popover" => VibrancyMaterial::Popover,
+                "hud" => VibrancyMaterial::Hud,
+                "titlebar" => VibrancyMaterial::Titlebar,
+                "content" => VibrancyMaterial::Content,
+                _ => return None,
+            }),
+            _ => return None,
+        };
+        Some(effect)
+    }
+
+    /// Whether the effect is drawn with a tint (`set_window_effect_color`).
+    /// Only Windows' acrylic and blur are; mica on Windows 10 becomes blur.
+    fn takes_tint(self) -> bool {
+        match self {
+            Self::Acrylic | Self::Blur => cfg!(target_os = "windows"),
+            Self::Mica | Self::MicaDark | Self::Tabbed => {
+                cfg!(target_os = "windows") && !supports_mica()
+            }
+            Self::Vibrancy(_) => false,
+        }
+    }
+}
+
+/// Mica and tabbed need Windows 11 (build 22000); older builds get blur,
+/// which unlike acrylic does not lag while the window is dragged.
+#[cfg(target_os = "windows")]
+fn supports_mica() -> bool {
+    use std::sync::LazyLock;
+
+    use windows::Wdk::System::SystemServices::RtlGetVersion;
+    use windows::Win32::System::SystemInformation::OSVERSIONINFOW;
+
+    static SUPPORTED: LazyLock<bool> = LazyLock::new(|| {
+        let mut info = OSVERSIONINFOW {
+            dwOSVersionInfoSize: std::mem::size_of::<OSVERSIONINFOW>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `info` is a correctly sized OSVERSIONINFOW.
+        let status = unsafe { RtlGetVersion(&mut info) };
+        status.is_ok() && info.dwBuildNumber >= 22000
+    });
+    *SUPPORTED
+}
+
+#[cfg(not(target_os = "windows"))]
+fn supports_mica() -> bool {
+    false
+}
+
+/// Tint for effects that take one until a window sets its own, matched
+/// to the OS theme.
+fn default_tint() -> (u8, u8, u8, u8) {
+    match appearance::cached_theme() {
+        SystemTheme::Dark => (30, 30, 30, 200),
+        SystemTheme::Light => (243, 243, 243, 200),
+    }
+}
+
 /// Build platform-specific window effects config from a named effect.
 fn build_window_effects(effect: &str) -> Option<tauri::utils::config::WindowEffectsConfig> {
-    build_window_effects_with_color(effect, 30, 30, 30, 200)
+    let (r, g, b, a) = default_tint();
+    build_window_effects_with_color(effect, r, g, b, a)
 }
 
 /// Build platform-specific window effects config with a custom tint color.
+/// The tint is ignored by effects that do not take one.
 fn build_window_effects_with_color(
     effect: &str,
     r: u8,
@@ -335,24 +1485,57 @@ fn build_window_effects_with_color(
     b: u8,
     a: u8,
 ) -> Option<tauri::utils::config::WindowEffectsConfig> {
-    match effect {
-        "acrylic" => {
-            let mut builder = EffectsBuilder::new();
-            #[cfg(target_os = "windows")]
-            {
-                builder = builder.effect(Effect::Acrylic).color(Color(r, g, b, a));
+    let Some(named) = NamedEffect::parse(effect) else {
+        warn!("Unknown window effect '{}', ignoring", effect);
+        return None;
+    };
+    #[cfg(not(target_os = "windows"))]
+    let _ = (r, g, b, a);
+
+    let mut builder = EffectsBuilder::new();
+    #[cfg(target_os = "windows")]
+    {
+        let native = match named {
+            NamedEffect::Acrylic => Effect::Acrylic,
+            NamedEffect::Blur => Effect::Blur,
+            NamedEffect::Mica | NamedEffect::MicaDark | NamedEffect::Tabbed if !supports_mica() => {
+                Effect::Blur
             }
-            #[cfg(target_os = "macos")]
-            {
-                builder = builder
-                    .effect(Effect::HudWindow)
-                    .state(EffectState::Active)
-                    .radius(12.0);
+            NamedEffect::Mica => Effect::Mica,
+            NamedEffect::MicaDark => Effect::MicaDark,
+            NamedEffect::Tabbed => Effect::Tabbed,
+            NamedEffect::Vibrancy(_) => {
+                log::debug!("Window effect '{}' is macOS-only", effect);
+                return None;
             }
-            Some(builder.build())
+        };
+        builder = builder.effect(native);
+        if named.takes_tint() {
+            builder = builder.color(Color(r, g, b, a));
         }
-        _ => None,
     }
+    #[cfg(target_os = "macos")]
+    {
+        let native = match named {
+            NamedEffect::Vibrancy(material) => match material {
+                VibrancyMaterial::UnderWindow => Effect::UnderWindowBackground,
+                VibrancyMaterial::Sidebar => Effect::Sidebar,
+                VibrancyMaterial::Menu => Effect::Menu,
+                VibrancyMaterial::Popover => Effect::Popover,
+                VibrancyMaterial::Hud => Effect::HudWindow,
+                VibrancyMaterial::Titlebar => Effect::Titlebar,
+                VibrancyMaterial::Content => Effect::ContentBackground,
+            },
+            _ => Effect::HudWindow,
+        };
+        builder = builder
+            .effect(native)
+            .state(EffectState::Active)
+            .radius(12.0);
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let _ = named;
+    Some(builder.build())
 }
 
 /// Update the tray popup's window effect tint color.
@@ -368,13 +1551,170 @@ pub fn set_window_effect_color(
         .get_webview_window(label)
         .ok_or_else(|| format!("Window '{}' not found", label))?;
 
-    // Look up the preset to find the effect name
-    if let Some(preset) = WindowConfig::from_label(label) {
-        if let Some(ref effect_name) = preset.window_effect {
-            if let Some(effects) = build_window_effects_with_color(effect_name, r, g, b, a) {
-                window.set_effects(effects).map_err(|e| e.to_string())?;
-            }
-        }
+    let effect_name = match app.try_state::<WindowEffects>() {
+        Some(effects) => effects.lookup(label),
+        None => presets::lookup(label).and_then(|preset| preset.window_effect),
+    }
+    .ok_or_else(|| format!("Window '{}' has no window effect to tint", label))?;
+    if !NamedEffect::parse(&effect_name).is_some_and(NamedEffect::takes_tint) {
+        return Ok(());
+    }
+    if let Some(effects) = build_window_effects_with_color(&effect_name, r, g, b, a) {
+        window.set_effects(effects).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
+
+/// Put the theme's default tint back on every open window whose effect
+/// takes one. Windows that tint themselves (the tray popup) redo theirs on
+/// `system-theme-changed`.
+pub fn retint_window_effects(app: &AppHandle) {
+    let (r, g, b, a) = default_tint();
+    for label in app.webview_windows().into_keys() {
+        let tinted = app
+            .try_state::<WindowEffects>()
+            .and_then(|effects| effects.lookup(&label))
+            .and_then(|effect| NamedEffect::parse(&effect))
+            .is_some_and(NamedEffect::takes_tint);
+        if !tinted {
+            continue;
+        }
+        if let Err(e) = set_window_effect_color(app, &label, r, g, b, a) {
+            warn!("Failed to re-tint '{}': {}", label, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> ScreenRect {
+        ScreenRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn moving_to_a_hidpi_monitor_keeps_the_apparent_size() {
+        let from = rect(0, 0, 1920, 1040);
+        let to = rect(1920, 0, 3840, 2080);
+        let window = rect(560, 900, 800, 120);
+
+        let placed = place_on_monitor(window, from, 1.0, to, 2.0, MonitorAnchor::BottomCenter);
+        assert_eq!((placed.width, placed.height), (1600, 240));
+        assert_eq!(placed.x, 1920 + (3840 - 1600) / 2);
+        assert_eq!(placed.y, 2080 - 240 - 96);
+
+        let centered = place_on_monitor(window, from, 1.0, to, 2.0, MonitorAnchor::Center);
+        assert_eq!(centered.y, (2080 - 240) / 2);
+    }
+
+    #[test]
+    fn clamping_pulls_overflowing_windows_back_onto_the_monitor() {
+        let area = rect(1920, 0, 1920, 1080);
+        let clamp = |x, y| clamp_to_monitor(x, y, 300.0, 400.0, area);
+        assert_eq!(clamp(2000.0, 100.0), (2000.0, 100.0));
+        assert_eq!(clamp(1800.0, 100.0), (1920.0, 100.0), "left");
+        assert_eq!(clamp(3700.0, 100.0), (3540.0, 100.0), "right");
+        assert_eq!(clamp(2000.0, -50.0), (2000.0, 0.0), "top");
+        assert_eq!(clamp(2000.0, 900.0), (2000.0, 680.0), "bottom");
+        // Too big to fit: the top-left edges win.
+        assert_eq!(
+            clamp_to_monitor(2500.0, 500.0, 2400.0, 1200.0, area),
+            (1920.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn effect_names_parse_with_optional_vibrancy_material() {
+        assert_eq!(NamedEffect::parse("acrylic"), Some(NamedEffect::Acrylic));
+        assert_eq!(NamedEffect::parse("mica"), Some(NamedEffect::Mica));
+        assert_eq!(NamedEffect::parse("mica_dark"), Some(NamedEffect::MicaDark));
+        assert_eq!(NamedEffect::parse("tabbed"), Some(NamedEffect::Tabbed));
+        assert_eq!(NamedEffect::parse("blur"), Some(NamedEffect::Blur));
+        assert_eq!(
+            NamedEffect::parse("vibrancy"),
+            Some(NamedEffect::Vibrancy(VibrancyMaterial::UnderWindow))
+        );
+        assert_eq!(
+            NamedEffect::parse("vibrancy:sidebar"),
+            Some(NamedEffect::Vibrancy(VibrancyMaterial::Sidebar))
+        );
+        assert_eq!(NamedEffect::parse("vibrancy:glass"), None);
+        assert_eq!(NamedEffect::parse("mica:dark"), None);
+        assert_eq!(NamedEffect::parse("Mica"), None);
+        assert_eq!(NamedEffect::parse(""), None);
+    }
+
+    #[test]
+    fn custom_windows_keep_their_effect_for_tinting() {
+        let effects = WindowEffects::default();
+        effects.record("visualizer", Some("acrylic"));
+        assert_eq!(effects.lookup("visualizer").as_deref(), Some("acrylic"));
+        assert_eq!(effects.lookup("unknown"), None);
+
+        // Presets resolve without being recorded; a recorded config wins.
+        assert_eq!(effects.lookup("tray-popup").as_deref(), Some("acrylic"));
+        effects.record("main", None);
+        assert_eq!(
+            effects.lookup("main"),
+            WindowConfig::main().window_effect,
+            "removing falls back to the preset"
+        );
+        effects.record("main", Some("blur"));
+        assert_eq!(effects.lookup("main").as_deref(), Some("blur"));
+    }
+
+    #[test]
+    fn only_visible_enabled_windows_count_as_hung() {
+        let start = Instant::now();
+        let beat = |enabled| Heartbeat {
+            last: start,
+            enabled,
+            reloaded: false,
+        };
+        let mut beats = HashMap::from([
+            ("desktop-lyrics".to_string(), beat(true)),
+            ("mini-player".to_string(), beat(true)),
+            ("settings".to_string(), beat(false)),
+        ]);
+        let visible = |label: &str| label != "mini-player";
+
+        let early = start + HEARTBEAT_INTERVAL * (MISSED_BEATS - 1);
+        assert!(hung_windows(&mut beats, early, visible).is_empty());
+
+        let late = start + HEARTBEAT_INTERVAL * MISSED_BEATS;
+        assert_eq!(
+            hung_windows(&mut beats, late, visible),
+            vec![("desktop-lyrics".to_string(), false)]
+        );
+        // The hidden window's clock restarted rather than running on.
+        assert_eq!(beats["mini-player"].last, late);
+
+        // Still silent after the reload: recreate next time.
+        let later = late + HEARTBEAT_INTERVAL * MISSED_BEATS;
+        assert_eq!(
+            hung_windows(&mut beats, later, visible),
+            vec![("desktop-lyrics".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn remember_keeps_the_relative_spot() {
+        let from = rect(0, 0, 1000, 1000);
+        let to = rect(-2000, 0, 2000, 1000);
+        // All the way right, a quarter of the way down.
+        let window = rect(900, 225, 100, 100);
+        let placed = place_on_monitor(window, from, 1.0, to, 1.0, MonitorAnchor::Remember);
+        assert_eq!((placed.x, placed.y), (-100, 225));
+
+        // Larger than the target work area: shrunk to fit.
+        let wide = rect(0, 0, 3000, 100);
+        let placed = place_on_monitor(wide, from, 1.0, to, 1.0, MonitorAnchor::Remember);
+        assert_eq!((placed.x, placed.width), (-2000, 2000));
+    }
+}
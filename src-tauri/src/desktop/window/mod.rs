@@ -1,6 +1,31 @@
+pub mod capture;
 pub mod commands;
 pub mod config;
+pub mod coords;
+#[cfg(target_os = "linux")]
+pub mod cursor;
 pub mod desktop_lyrics;
+pub mod event_queue;
+pub mod events;
+pub mod exclusive;
+pub mod first_paint;
 pub mod manager;
+pub mod opacity;
+pub mod osd;
+pub mod panic;
 pub mod payload;
+pub mod pin;
+pub mod platform;
+pub mod presets;
+pub mod prewarm;
+pub mod recently_closed;
+pub mod recovery;
+pub mod rpc;
+pub mod session;
+pub mod shared_state;
+pub mod taskbar_progress;
+#[cfg(target_os = "windows")]
+pub mod taskbar_restart;
+pub mod titlebar;
 pub mod tray;
+pub mod zoom;
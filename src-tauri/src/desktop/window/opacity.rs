@@ -0,0 +1,190 @@
+//! Whole-window opacity, set natively.
+//!
+//! Fading a window from CSS also fades the acrylic/vibrancy behind the page
+//! and leaves the native shadow at full strength; asking the OS instead
+//! fades everything together. Windows makes the window layered and sets
+//! its alpha, macOS sets `NSWindow.alphaValue`. WebKitGTK offers nothing
+//! equivalent, so Linux reports `Unsupported`.
+//!
+//! The last value per label is kept in [`WindowOpacity`]. The desktop
+//! lyrics window also writes it to its bounds file and gets it back when it
+//! is created again.
+
+use std::collections::HashMap;
+
+use log::warn;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::desktop::window::commands::WindowError;
+use crate::desktop::window::desktop_lyrics::bounds;
+
+pub const MIN_OPACITY: f64 = 0.1;
+
+/// Managed opacity per window label. Missing means fully opaque.
+#[derive(Default)]
+pub struct WindowOpacity(Mutex<HashMap<String, f64>>);
+
+impl WindowOpacity {
+    fn record(&self, label: &str, opacity: f64) {
+        let mut values = self.0.lock();
+        if opacity >= 1.0 {
+            values.remove(label);
+        } else {
+            values.insert(label.to_string(), opacity);
+        }
+    }
+
+    fn get(&self, label: &str) -> f64 {
+        self.0.lock().get(label).copied().unwrap_or(1.0)
+    }
+}
+
+/// Clamp into `MIN_OPACITY..=1.0`; NaN counts as opaque.
+fn clamp(opacity: f64) -> f64 {
+    if opacity.is_nan() {
+        1.0
+    } else {
+        opacity.clamp(MIN_OPACITY, 1.0)
+    }
+}
+
+/// The opacity last set for `label`, 1.0 if none.
+pub(crate) fn current(app: &AppHandle, label: &str) -> f64 {
+    app.try_state::<WindowOpacity>()
+        .map_or(1.0, |state| state.get(label))
+}
+
+/// Fade `window` natively and remember the value.
+pub(crate) fn apply(window: &WebviewWindow, opacity: f64) -> Result<f64, WindowError> {
+    let opacity = clamp(opacity);
+    imp::set(window, opacity)?;
+    if let Some(state) = window.try_state::<WindowOpacity>() {
+        state.record(window.label(), opacity);
+    }
+    Ok(opacity)
+}
+
+/// Put the saved opacity back on a newly created desktop-lyrics window.
+pub(crate) fn restore_saved(window: &WebviewWindow) {
+    if window.label() != bounds::LABEL {
+        return;
+    }
+    let Some(saved) = bounds::load(window.app_handle()).map(|b| b.opacity) else {
+        return;
+    };
+    if saved < 1.0 {
+        if let Err(e) = apply(window, saved) {
+            warn!("Failed to restore desktop-lyrics opacity: {}", e);
+        }
+    }
+}
+
+/// Set a window's opacity, clamped to 0.1–1.0. Resolves to the value used.
+#[tauri::command]
+pub fn set_window_opacity(app: AppHandle, label: String, opacity: f64) -> Result<f64, WindowError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| WindowError::NotFound(label.clone()))?;
+    let opacity = apply(&window, opacity)?;
+    if label == bounds::LABEL {
+        bounds::note_changed(&app);
+    }
+    Ok(opacity)
+}
+
+#[tauri::command]
+pub fn get_window_opacity(
+    app: AppHandle,
+    state: State<'_, WindowOpacity>,
+    label: String,
+) -> Result<f64, WindowError> {
+    if app.get_webview_window(&label).is_none() {
+        return Err(WindowError::NotFound(label));
+    }
+    Ok(state.get(&label))
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use tauri::WebviewWindow;
+    use windows::Win32::Foundation::{COLORREF, HWND};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    use super::WindowError;
+
+    pub(super) fn set(window: &WebviewWindow, opacity: f64) -> Result<(), WindowError> {
+        let hwnd = HWND(window.hwnd()?.0 as _);
+        let layered = WS_EX_LAYERED.0 as isize;
+        // SAFETY: `hwnd` belongs to a live window of this process.
+        unsafe {
+            let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            if opacity >= 1.0 {
+                // Layered windows cost a redirection surface; drop the
+                // style once the window is opaque again.
+                if style & layered != 0 {
+                    SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style & !layered);
+                }
+                return Ok(());
+            }
+            if style & layered == 0 {
+                SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | layered);
+            }
+            let alpha = (opacity * 255.0).round() as u8;
+            SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+                .map_err(|e| WindowError::Failed(e.to_string()))
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use tauri::WebviewWindow;
+
+    use super::WindowError;
+    use crate::desktop::window::platform::macos;
+
+    pub(super) fn set(window: &WebviewWindow, opacity: f64) -> Result<(), WindowError> {
+        macos::with_ns_window(window, move |ns_window| ns_window.setAlphaValue(opacity))?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod imp {
+    use tauri::WebviewWindow;
+
+    use super::WindowError;
+
+    pub(super) fn set(_window: &WebviewWindow, opacity: f64) -> Result<(), WindowError> {
+        if opacity >= 1.0 {
+            return Ok(());
+        }
+        Err(WindowError::Unsupported(
+            "Window opacity is not supported on this platform".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_clamped_and_opaque_windows_are_forgotten() {
+        assert_eq!(clamp(0.0), MIN_OPACITY);
+        assert_eq!(clamp(1.5), 1.0);
+        assert_eq!(clamp(f64::NAN), 1.0);
+        assert_eq!(clamp(0.45), 0.45);
+
+        let state = WindowOpacity::default();
+        state.record("desktop-lyrics", 0.4);
+        assert_eq!(state.get("desktop-lyrics"), 0.4);
+        state.record("desktop-lyrics", 1.0);
+        assert!(state.0.lock().is_empty());
+        assert_eq!(state.get("mini-player"), 1.0);
+    }
+}
@@ -0,0 +1,272 @@
+//! Hotkey OSD: a small overlay that briefly shows volume / track changes while
+//! the main window is hidden.
+//!
+//! The window is created once (hidden, non-focusable, click-through) and then
+//! reused. Every `show_osd` call bumps a generation counter; the auto-hide
+//! timer only acts if no newer call arrived in the meantime, so rapid volume
+//! steps update the content in place instead of flickering.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, State, WebviewWindow};
+
+use crate::desktop::window::config::{WindowConfig, OSD_HEIGHT, OSD_WIDTH};
+use crate::desktop::window::event_queue;
+use crate::desktop::window::manager as wm;
+use crate::settings::osd::{clamp_duration, OsdCorner};
+use crate::settings::SettingsStore;
+
+const OSD_LABEL: &str = "osd";
+const OSD_UPDATE_EVENT: &str = "osd-update";
+const OSD_FADE_OUT_EVENT: &str = "osd-fade-out";
+/// Must match the CSS fade-out transition in the OSD view.
+const OSD_FADE_MS: u64 = 200;
+
+static OSD_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OsdUpdate {
+    kind: String,
+    payload: Value,
+    duration_ms: u64,
+}
+
+/// Physical-pixel rectangle (work area of a monitor).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Area {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Show (or refresh) the OSD with `payload` for `duration_ms`.
+///
+/// Returns `false` when the OSD was suppressed: disabled in settings, or a
+/// fullscreen app is in front and the user has not opted in.
+#[tauri::command]
+pub async fn show_osd(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    kind: String,
+    payload: Value,
+    duration_ms: Option<u64>,
+) -> Result<bool, String> {
+    let settings = store.read(|s| s.osd.clone());
+    if !settings.enabled {
+        return Ok(false);
+    }
+    if !settings.show_over_fullscreen && foreground_is_fullscreen() {
+        return Ok(false);
+    }
+
+    if app.get_webview_window(OSD_LABEL).is_none() {
+        wm::create_window(&app, &WindowConfig::osd())?;
+    }
+    let window = app
+        .get_webview_window(OSD_LABEL)
+        .ok_or_else(|| format!("Window '{}' not found", OSD_LABEL))?;
+
+    let duration_ms = clamp_duration(duration_ms.unwrap_or(settings.duration_ms));
+    let generation = OSD_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    // A freshly created OSD has no listener until its page calls
+    // `window_ready`; the update is held for it until then.
+    let update = serde_json::to_value(OsdUpdate {
+        kind,
+        payload,
+        duration_ms,
+    })
+    .map_err(|e| e.to_string())?;
+    event_queue::emit_or_queue(
+        app.clone(),
+        OSD_LABEL.into(),
+        OSD_UPDATE_EVENT.into(),
+        update,
+    );
+
+    // Only reposition on first show; moving a visible OSD between updates
+    // would make it jump if the cursor crossed to another monitor.
+    if !window.is_visible().unwrap_or(false) {
+        if let Some(area) = target_work_area(&app) {
+            let scale = window.scale_factor().unwrap_or(1.0);
+            let (x, y) = osd_position(
+                area,
+                OSD_WIDTH * scale,
+                OSD_HEIGHT * scale,
+                settings.corner,
+                settings.margin * scale,
+            );
+            window
+                .set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32))
+                .map_err(|e| e.to_string())?;
+        }
+        prepare_overlay(&window);
+        window.show().map_err(|e| e.to_string())?;
+    }
+
+    schedule_hide(app, generation, duration_ms);
+    Ok(true)
+}
+
+/// Hide the OSD immediately, cancelling any pending auto-hide.
+#[tauri::command]
+pub fn hide_osd(app: AppHandle) -> Result<(), String> {
+    OSD_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window(OSD_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn schedule_hide(app: AppHandle, generation: u64, duration_ms: u64) {
+    let is_current = move || OSD_GENERATION.load(Ordering::SeqCst) == generation;
+    let spawned = std::thread::Builder::new()
+        .name("osd-auto-hide".into())
+        .spawn(move || {
+            std::thread::sleep(Duration::from_millis(duration_ms));
+            if !is_current() {
+                return;
+            }
+            let _ = app.emit_to(OSD_LABEL, OSD_FADE_OUT_EVENT, ());
+            std::thread::sleep(Duration::from_millis(OSD_FADE_MS));
+            if !is_current() {
+                return;
+            }
+            if let Some(window) = app.get_webview_window(OSD_LABEL) {
+                let _ = window.hide();
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn OSD auto-hide timer: {}", e);
+    }
+}
+
+/// Click-through and Alt-Tab exclusion. Re-applied on every show because
+/// some platforms reset the cursor-event flag when a window is hidden.
+fn prepare_overlay(window: &WebviewWindow) {
+    if let Err(e) = window.set_ignore_cursor_events(true) {
+        warn!("Failed to make OSD click-through: {}", e);
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Ok(hwnd) = window.hwnd() {
+        hide_from_alt_tab(windows::Win32::Foundation::HWND(hwnd.0 as _));
+    }
+}
+
+/// Work area of the monitor under the cursor, falling back to the primary one.
+fn target_work_area(app: &AppHandle) -> Option<Area> {
+    let monitor = app
+        .cursor_position()
+        .ok()
+        .and_then(|pos| app.monitor_from_point(pos.x, pos.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten())?;
+    let work_area = monitor.work_area();
+    Some(Area {
+        x: work_area.position.x as f64,
+        y: work_area.position.y as f64,
+        width: work_area.size.width as f64,
+        height: work_area.size.height as f64,
+    })
+}
+
+/// Top-left position for a `width` x `height` OSD anchored at `corner`.
+fn osd_position(area: Area, width: f64, height: f64, corner: OsdCorner, margin: f64) -> (f64, f64) {
+    let left = area.x + margin;
+    let right = area.x + area.width - width - margin;
+    let center = area.x + (area.width - width) / 2.0;
+    let top = area.y + margin;
+    let bottom = area.y + area.height - height - margin;
+
+    let (x, y) = match corner {
+        OsdCorner::TopLeft => (left, top),
+        OsdCorner::TopCenter => (center, top),
+        OsdCorner::TopRight => (right, top),
+        OsdCorner::BottomLeft => (left, bottom),
+        OsdCorner::BottomCenter => (center, bottom),
+        OsdCorner::BottomRight => (right, bottom),
+    };
+
+    // Keep the OSD on-screen even when the margin is larger than the space left.
+    (
+        x.clamp(area.x, (area.x + area.width - width).max(area.x)),
+        y.clamp(area.y, (area.y + area.height - height).max(area.y)),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn hide_from_alt_tab(hwnd: windows::Win32::Foundation::HWND) {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetWindowLongPtrW, GWL_EXSTYLE, WS_EX_APPWINDOW, WS_EX_NOACTIVATE,
+        WS_EX_TOOLWINDOW,
+    };
+
+    let add = (WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE).0 as isize;
+    let remove = WS_EX_APPWINDOW.0 as isize;
+    // SAFETY: `hwnd` belongs to the live OSD window.
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let next = (style | add) & !remove;
+        if next != style {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, next);
+        }
+    }
+}
+
+/// Whether a fullscreen app (game, presentation, video) owns the foreground.
+#[cfg(target_os = "windows")]
+fn foreground_is_fullscreen() -> bool {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    // These states mean "don't interrupt".
+    // SAFETY: no arguments; the state comes back by value.
+    unsafe { SHQueryUserNotificationState() }.is_ok_and(|state| {
+        matches!(
+            state,
+            QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE
+        )
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn foreground_is_fullscreen() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Area = Area {
+        x: 1920.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1040.0,
+    };
+
+    #[test]
+    fn corners_respect_margin_within_work_area() {
+        let pos = |corner| osd_position(AREA, 320.0, 88.0, corner, 24.0);
+        assert_eq!(pos(OsdCorner::TopLeft), (1944.0, 24.0));
+        assert_eq!(pos(OsdCorner::TopRight), (3496.0, 24.0));
+        assert_eq!(pos(OsdCorner::BottomLeft), (1944.0, 928.0));
+        assert_eq!(pos(OsdCorner::BottomCenter), (2720.0, 928.0));
+    }
+
+    #[test]
+    fn oversized_margin_is_clamped_on_screen() {
+        let (x, y) = osd_position(AREA, 320.0, 88.0, OsdCorner::BottomRight, 5000.0);
+        assert_eq!((x, y), (1920.0, 0.0));
+        let (x, y) = osd_position(AREA, 4000.0, 88.0, OsdCorner::TopRight, 0.0);
+        assert_eq!((x, y), (1920.0, 0.0));
+    }
+}
@@ -0,0 +1,140 @@
+//! Boss key: hide every window, optionally mute, and undo it exactly.
+//!
+//! The snapshot of which windows were up is taken and kept here rather
+//! than in the frontend: a hidden main webview is throttled and may not
+//! run again until it is shown, which is the one thing it would need to do.
+//! The tray popup is left alone; it dismisses itself.
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::desktop::system_volume;
+use crate::desktop::window::{manager, tray};
+
+const TRAY_POPUP_LABEL: &str = "tray-popup";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hidden {
+    /// Windows that were visible, in the order they get shown again.
+    labels: Vec<String>,
+    /// Mute state before `panic_hide` muted the output; `None` if it did not.
+    muted_before: Option<bool>,
+}
+
+/// What `panic_hide` took down, until `panic_restore` puts it back.
+#[derive(Default)]
+pub struct PanicState {
+    hidden: Mutex<Option<Hidden>>,
+}
+
+/// Main goes last so it ends up focused on top of the rest.
+fn restore_order(mut labels: Vec<String>) -> Vec<String> {
+    if let Some(index) = labels.iter().position(|label| label == "main") {
+        let main = labels.remove(index);
+        labels.push(main);
+    }
+    labels
+}
+
+/// Hide every visible window except the tray popup and, with `mute`, mute
+/// the system output. The tray tooltip drops the track until
+/// `panic_restore`. Does nothing if already hidden.
+#[tauri::command]
+pub fn panic_hide(app: AppHandle, state: State<'_, PanicState>, mute: bool) -> Result<(), String> {
+    let mut hidden = state.hidden.lock();
+    if hidden.is_some() {
+        return Ok(());
+    }
+
+    let mut labels = Vec::new();
+    for label in manager::list_windows(&app) {
+        if label == TRAY_POPUP_LABEL {
+            continue;
+        }
+        let visible = app
+            .get_webview_window(&label)
+            .is_some_and(|window| window.is_visible().unwrap_or(false));
+        if !visible {
+            continue;
+        }
+        match manager::hide_window(&app, &label) {
+            Ok(()) => labels.push(label),
+            Err(e) => warn!("Boss key could not hide '{}': {}", label, e),
+        }
+    }
+
+    let muted_before = if mute {
+        match system_volume::output_muted()
+            .and_then(|was| system_volume::set_output_muted(true).map(|()| was))
+        {
+            Ok(was) => Some(was),
+            Err(e) => {
+                warn!("Boss key could not mute the output: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Err(e) = tray::set_disguised(&app, true) {
+        warn!("Boss key could not reset the tray tooltip: {}", e);
+    }
+    info!("Boss key: hid {} window(s)", labels.len());
+    *hidden = Some(Hidden {
+        labels: restore_order(labels),
+        muted_before,
+    });
+    Ok(())
+}
+
+/// Show exactly the windows `panic_hide` hid and put the mute state back.
+/// Windows closed in the meantime are skipped. Does nothing if not hidden.
+#[tauri::command]
+pub fn panic_restore(app: AppHandle, state: State<'_, PanicState>) -> Result<(), String> {
+    let Some(hidden) = state.hidden.lock().take() else {
+        return Ok(());
+    };
+
+    if let Some(muted) = hidden.muted_before {
+        if let Err(e) = system_volume::set_output_muted(muted) {
+            warn!("Boss key could not restore the output mute state: {}", e);
+        }
+    }
+    if let Err(e) = tray::set_disguised(&app, false) {
+        warn!("Boss key could not restore the tray tooltip: {}", e);
+    }
+    for label in &hidden.labels {
+        let Some(window) = app.get_webview_window(label) else {
+            continue;
+        };
+        if let Err(e) = window.show() {
+            warn!("Boss key could not show '{}': {}", label, e);
+            continue;
+        }
+        manager::emit_visibility(&app, label, true);
+    }
+    if hidden.labels.iter().any(|label| label == "main") {
+        if let Some(main) = app.get_webview_window("main") {
+            let _ = main.set_focus();
+        }
+    }
+    info!("Boss key: restored {} window(s)", hidden.labels.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn main_is_restored_last() {
+        let labels = ["main", "desktop-lyrics", "mini-player"].map(String::from);
+        assert_eq!(
+            restore_order(labels.to_vec()),
+            ["desktop-lyrics", "mini-player", "main"].map(String::from)
+        );
+        assert!(restore_order(Vec::new()).is_empty());
+    }
+}
@@ -1,34 +1,222 @@
+use log::warn;
 use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::path::PathBuf;
+use std::sync::{LazyLock, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::settings::write_atomic;
+
+/// How often expired payloads are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Entry {
+    value: Value,
+    /// Mirrored to disk so it survives an app restart.
+    persistent: bool,
+    /// After this the payload counts as gone.
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadCacheStats {
+    pub entries: usize,
+    /// Size of the payloads as compact JSON.
+    pub bytes: usize,
+}
 
 /// Global one-shot payload cache for inter-window data passing.
 /// A creating window can store a payload before opening a new window,
 /// and the new window takes (consumes) it on initialization.
-static PAYLOAD_CACHE: LazyLock<Mutex<HashMap<String, Value>>> =
+static PAYLOAD_CACHE: LazyLock<Mutex<HashMap<String, Entry>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// File backing persistent payloads; unset until `init_persistence`.
+static PERSIST_PATH: OnceLock<PathBuf> = OnceLock::new();
+
 pub struct PayloadCache;
 
 impl PayloadCache {
+    /// Load persistent payloads left by a previous run from `path` and keep
+    /// mirroring them there. Call once during setup.
+    pub fn init_persistence(path: PathBuf) {
+        let loaded = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<HashMap<String, Value>>(&bytes).ok())
+            .unwrap_or_default();
+        if PERSIST_PATH.set(path).is_err() {
+            return;
+        }
+        let mut cache = PAYLOAD_CACHE.lock();
+        for (label, value) in loaded {
+            cache.entry(label).or_insert(Entry {
+                value,
+                persistent: true,
+                expires: None,
+            });
+        }
+    }
+
     /// Store a payload for a window label. Overwrites any existing payload.
     pub fn set(label: &str, value: Value) {
-        PAYLOAD_CACHE.lock().insert(label.to_string(), value);
+        Self::insert(label, value, false, None);
+    }
+
+    /// Like `set`, but the payload is dropped `ttl_secs` seconds from now
+    /// if nothing has taken it by then.
+    pub fn set_with_ttl(label: &str, value: Value, ttl_secs: u64) {
+        let expires = Instant::now().checked_add(Duration::from_secs(ttl_secs));
+        Self::insert(label, value, false, expires);
     }
 
-    /// Take (consume) the payload for a window label. Returns None if no payload exists.
+    /// Like `set`, but the payload also survives a restart until taken.
+    pub fn set_persistent(label: &str, value: Value) {
+        Self::insert(label, value, true, None);
+    }
+
+    /// Take (consume) the payload for a window label. Returns None if no
+    /// payload exists or it has expired.
     pub fn take(label: &str) -> Option<Value> {
-        PAYLOAD_CACHE.lock().remove(label)
+        let mut cache = PAYLOAD_CACHE.lock();
+        let entry = cache.remove(label)?;
+        if entry.persistent {
+            persist(&cache);
+        }
+        (!entry.expired(Instant::now())).then_some(entry.value)
     }
 
-    /// Peek at the payload without consuming it.
+    /// Peek at the payload without consuming it. Peeking does not extend
+    /// the payload's lifetime.
     pub fn peek(label: &str) -> Option<Value> {
-        PAYLOAD_CACHE.lock().get(label).cloned()
+        PAYLOAD_CACHE
+            .lock()
+            .get(label)
+            .filter(|entry| !entry.expired(Instant::now()))
+            .map(|entry| entry.value.clone())
+    }
+
+    /// Drop the payload left for a window that has been destroyed without
+    /// taking it. Persistent payloads are meant to outlive the window and
+    /// stay.
+    pub fn discard_for_window(label: &str) {
+        let mut cache = PAYLOAD_CACHE.lock();
+        if cache.get(label).is_some_and(|entry| !entry.persistent) {
+            cache.remove(label);
+        }
+    }
+
+    /// Drop expired payloads. Returns how many were dropped.
+    pub fn sweep_expired() -> usize {
+        let now = Instant::now();
+        let mut cache = PAYLOAD_CACHE.lock();
+        let before = cache.len();
+        cache.retain(|_, entry| !entry.expired(now));
+        before - cache.len()
+    }
+
+    /// Sweep expired payloads every minute on a background thread.
+    pub fn start_sweeper() {
+        let spawned = std::thread::Builder::new()
+            .name("payload-sweep".into())
+            .spawn(|| loop {
+                std::thread::sleep(SWEEP_INTERVAL);
+                Self::sweep_expired();
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to spawn payload sweeper: {}", e);
+        }
+    }
+
+    pub fn stats() -> PayloadCacheStats {
+        let cache = PAYLOAD_CACHE.lock();
+        let bytes = cache
+            .values()
+            .map(|entry| serde_json::to_vec(&entry.value).map_or(0, |json| json.len()))
+            .sum();
+        PayloadCacheStats {
+            entries: cache.len(),
+            bytes,
+        }
     }
 
     /// Clear all cached payloads.
     pub fn clear() {
-        PAYLOAD_CACHE.lock().clear();
+        let mut cache = PAYLOAD_CACHE.lock();
+        let had_persistent = cache.values().any(|entry| entry.persistent);
+        cache.clear();
+        if had_persistent {
+            persist(&cache);
+        }
+    }
+
+    fn insert(label: &str, value: Value, persistent: bool, expires: Option<Instant>) {
+        let mut cache = PAYLOAD_CACHE.lock();
+        let previous = cache.insert(
+            label.to_string(),
+            Entry {
+                value,
+                persistent,
+                expires,
+            },
+        );
+        if persistent || previous.is_some_and(|entry| entry.persistent) {
+            persist(&cache);
+        }
+    }
+}
+
+fn persist(cache: &HashMap<String, Entry>) {
+    let Some(path) = PERSIST_PATH.get() else {
+        return;
+    };
+    let persistent: HashMap<&str, &Value> = cache
+        .iter()
+        .filter(|(_, entry)| entry.persistent)
+        .map(|(label, entry)| (label.as_str(), &entry.value))
+        .collect();
+    let result = if persistent.is_empty() {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    } else {
+        serde_json::to_vec(&persistent)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| write_atomic(path, &bytes).map_err(|e| e.to_string()))
+    };
+    if let Err(e) = result {
+        warn!("Failed to persist window payloads: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn expired_and_destroyed_window_payloads_are_dropped() {
+        PayloadCache::set_with_ttl("test-ttl-expired", json!([1, 2, 3]), 0);
+        PayloadCache::set_with_ttl("test-ttl-live", json!({"id": 1}), 3600);
+        assert_eq!(PayloadCache::peek("test-ttl-expired"), None);
+        assert_eq!(PayloadCache::take("test-ttl-expired"), None);
+        assert_eq!(PayloadCache::peek("test-ttl-live"), Some(json!({"id": 1})));
+
+        PayloadCache::set_with_ttl("test-ttl-swept", json!(null), 0);
+        assert!(PayloadCache::sweep_expired() >= 1);
+        assert!(!PAYLOAD_CACHE.lock().contains_key("test-ttl-swept"));
+
+        PayloadCache::discard_for_window("test-ttl-live");
+        assert_eq!(PayloadCache::take("test-ttl-live"), None);
     }
 }
@@ -0,0 +1,153 @@
+//! Always-on-top ("pin") tracking.
+//!
+//! Records the current always-on-top value of every managed window so it can
+//! be queried, persists the user's choice for the windows listed in
+//! [`PERSISTED_PIN_LABELS`], and decides which value a window gets when it is
+//! (re)created.
+
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::desktop::safe_mode;
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::presets;
+use crate::settings::SettingsStore;
+
+/// Windows whose pin state survives a restart.
+pub const PERSISTED_PIN_LABELS: &[&str] = &["main", "mini-player"];
+
+pub const WINDOW_PIN_CHANGED_EVENT: &str = "window-pin-changed";
+/// Same payload as [`WINDOW_PIN_CHANGED_EVENT`], under the name used by the
+/// settings UI.
+pub const WINDOW_ALWAYS_ON_TOP_CHANGED_EVENT: &str = "window-always-on-top-changed";
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPinChanged {
+    pub label: String,
+    pub always_on_top: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PinEntry {
+    on_top: bool,
+    /// Set through `set_always_on_top` during this session.
+    user_set: bool,
+}
+
+/// Managed per-label always-on-top values.
+#[derive(Default)]
+pub struct WindowPinState {
+    entries: Mutex<HashMap<String, PinEntry>>,
+}
+
+impl WindowPinState {
+    pub fn get(&self, label: &str) -> Option<bool> {
+        self.entries.lock().get(label).map(|entry| entry.on_top)
+    }
+
+    fn session_override(&self, label: &str) -> Option<bool> {
+        self.entries
+            .lock()
+            .get(label)
+            .filter(|entry| entry.user_set)
+            .map(|entry| entry.on_top)
+    }
+
+    fn record(&self, label: &str, on_top: bool, user_set: bool) {
+        let mut entries = self.entries.lock();
+        let entry = entries
+            .entry(label.to_string())
+            .or_insert(PinEntry { on_top, user_set });
+        entry.on_top = on_top;
+        entry.user_set |= user_set;
+    }
+}
+
+/// Value a window should get when it is created: this session's explicit
+/// choice first, then the persisted choice (main / mini-player only), then
+/// the preset. Presets that force always-on-top (desktop lyrics) therefore
+/// only lose to an explicit toggle made in the current session.
+pub fn effective_always_on_top(app: &AppHandle, config: &WindowConfig) -> bool {
+    let label = config.label.as_str();
+    if let Some(on_top) = app
+        .try_state::<WindowPinState>()
+        .and_then(|state| state.session_override(label))
+    {
+        return on_top;
+    }
+    // Safe mode ignores saved choices; a bad one must not be sticky.
+    if PERSISTED_PIN_LABELS.contains(&label) && !safe_mode::is_active() {
+        if let Some(on_top) = app
+            .try_state::<SettingsStore>()
+            .and_then(|store| store.read(|s| s.window.pinned.get(label).copied()))
+        {
+            return on_top;
+        }
+    }
+    config.always_on_top
+}
+
+/// Current value for `label`: the tracked value of an open window, else what
+/// the window would be created with. Tauri has no always-on-top getter on
+/// every platform, so the tracked value wins over asking the window.
+pub fn current(app: &AppHandle, label: &str) -> Result<bool, String> {
+    if let Some(on_top) = app
+        .try_state::<WindowPinState>()
+        .and_then(|state| state.get(label))
+    {
+        return Ok(on_top);
+    }
+    if let Some(window) = app.get_webview_window(label) {
+        return Ok(window.is_always_on_top().unwrap_or(false));
+    }
+    presets::lookup(label)
+        .map(|config| effective_always_on_top(app, &config))
+        .ok_or_else(|| format!("Window '{}' not found", label))
+}
+
+/// Remember the value a window was created with (not a user override).
+pub fn record_created(app: &AppHandle, label: &str, on_top: bool) {
+    if let Some(state) = app.try_state::<WindowPinState>() {
+        state.record(label, on_top, false);
+    }
+}
+
+/// Remember a user toggle and persist it where applicable.
+pub fn record_user_choice(app: &AppHandle, label: &str, on_top: bool) -> Result<(), String> {
+    if let Some(state) = app.try_state::<WindowPinState>() {
+        state.record(label, on_top, true);
+    }
+    if PERSISTED_PIN_LABELS.contains(&label) {
+        if let Some(store) = app.try_state::<SettingsStore>() {
+            store.update(|s| {
+                s.window.pinned.insert(label.to_string(), on_top);
+                Ok(())
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_choice_sticks_across_recreation_records() {
+        let state = WindowPinState::default();
+        state.record("desktop-lyrics", true, false);
+        assert_eq!(state.get("desktop-lyrics"), Some(true));
+        assert_eq!(state.session_override("desktop-lyrics"), None);
+
+        state.record("desktop-lyrics", false, true);
+        // Re-creating the window records the applied value again, which
+        // must not clear the user's override.
+        state.record("desktop-lyrics", false, false);
+        assert_eq!(state.session_override("desktop-lyrics"), Some(false));
+    }
+}
@@ -0,0 +1,435 @@
+//! OS light/dark theme and accent color.
+//!
+//! [`start`] records the current appearance and subscribes to whatever
+//! change notification the platform has: the `Personalize` and `DWM`
+//! registry keys on Windows, the desktop portal's `SettingChanged` signal
+//! on Linux. tao's `ThemeChanged` is a backstop everywhere. macOS has no
+//! notification we can take without an Objective-C observer, so there (and
+//! wherever the watcher is not running) main-window focus also refreshes,
+//! which is when someone coming back from System Settings would look.
+//!
+//! Every trigger goes through [`refresh_soon`], which waits for a burst to
+//! settle: tao sends `ThemeChanged` to each window, and one change touches
+//! several registry values. [`refresh`] then emits `system-theme-changed` /
+//! `accent-color-changed` only for what actually changed, and re-tints
+//! windows whose effect takes a tint when the theme flips.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::{debug, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::debounce::Debounced;
+use crate::desktop::window::manager;
+
+pub const SYSTEM_THEME_CHANGED_EVENT: &str = "system-theme-changed";
+pub const ACCENT_COLOR_CHANGED_EVENT: &str = "accent-color-changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SystemTheme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct AccentColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Windows' stock blue, for systems that do not report an accent.
+const DEFAULT_ACCENT: AccentColor = AccentColor {
+    r: 0,
+    g: 120,
+    b: 215,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Appearance {
+    theme: SystemTheme,
+    accent: AccentColor,
+}
+
+/// Last appearance seen by [`refresh`]; None until [`start`].
+static LAST: Mutex<Option<Appearance>> = Mutex::new(None);
+
+const REFRESH_SETTLE: Duration = Duration::from_millis(250);
+static REFRESHER: Debounced = Debounced::new("appearance-refresh", REFRESH_SETTLE);
+
+/// Whether the platform watcher is reporting changes.
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+pub fn system_theme() -> SystemTheme {
+    imp::theme().unwrap_or_else(|e| {
+        debug!("System theme unavailable, assuming light: {}", e);
+        SystemTheme::Light
+    })
+}
+
+pub fn accent_color() -> AccentColor {
+    imp::accent().unwrap_or_else(|e| {
+        debug!("Accent color unavailable, using the default: {}", e);
+        DEFAULT_ACCENT
+    })
+}
+
+/// The theme as of the last refresh, without asking the OS. Dark before
+/// [`start`], matching the tint windows were created with until now.
+pub fn cached_theme() -> SystemTheme {
+    LAST.lock().map_or(SystemTheme::Dark, |last| last.theme)
+}
+
+fn read() -> Appearance {
+    Appearance {
+        theme: system_theme(),
+        accent: accent_color(),
+    }
+}
+
+/// Store `now` and return the theme and accent that differ from before.
+fn record(now: Appearance) -> (Option<SystemTheme>, Option<AccentColor>) {
+    let Some(before) = LAST.lock().replace(now) else {
+        return (None, None);
+    };
+    (
+        (before.theme != now.theme).then_some(now.theme),
+        (before.accent != now.accent).then_some(now.accent),
+    )
+}
+
+/// Re-read the appearance and tell the windows what changed.
+pub fn refresh(app: &AppHandle) {
+    let (theme, accent) = record(read());
+    if let Some(theme) = theme {
+        manager::retint_window_effects(app);
+        let _ = app.emit(SYSTEM_THEME_CHANGED_EVENT, theme);
+    }
+    if let Some(accent) = accent {
+        let _ = app.emit(ACCENT_COLOR_CHANGED_EVENT, accent);
+    }
+}
+
+/// [`refresh`] once a burst of triggers has settled, off the calling
+/// thread; the portal and registry reads can block briefly and the callers
+/// are on the event loop.
+pub fn refresh_soon(app: &AppHandle) {
+    let app = app.clone();
+    REFRESHER.trigger(move || refresh(&app));
+}
+
+/// The main window gained focus. Refreshes only where no watcher reports
+/// changes, so focusing the window costs nothing on Windows and Linux.
+pub fn main_focused(app: &AppHandle) {
+    if !WATCHING.load(Ordering::Relaxed) {
+        refresh_soon(app);
+    }
+}
+
+/// Called by a watcher that stopped; focus refreshes take over.
+fn watch_stopped() {
+    WATCHING.store(false, Ordering::Relaxed);
+}
+
+/// Record the current appearance and start listening for changes.
+pub fn start(app: &AppHandle) {
+    record(read());
+    match imp::watch(app) {
+        Ok(watching) => WATCHING.store(watching, Ordering::Relaxed),
+        Err(e) => warn!("Not watching for appearance changes: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn get_system_theme() -> SystemTheme {
+    system_theme()
+}
+
+#[tauri::command]
+pub fn get_accent_color() -> AccentColor {
+    accent_color()
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use log::warn;
+    use tauri::AppHandle;
+    use windows::core::{w, BOOL, PCWSTR};
+    use windows::Win32::Graphics::Dwm::DwmGetColorizationColor;
+    use windows::Win32::System::Registry::{
+        RegGetValueW, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER, KEY_NOTIFY,
+        REG_NOTIFY_CHANGE_LAST_SET, RRF_RT_REG_DWORD,
+    };
+
+    use super::{AccentColor, SystemTheme};
+
+    const PERSONALIZE_KEY: PCWSTR =
+        w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize");
+    const DWM_KEY: PCWSTR = w!(r"Software\Microsoft\Windows\DWM");
+
+    pub(super) fn theme() -> Result<SystemTheme, String> {
+        let mut light = 1u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: `light` and `size` describe a live DWORD buffer.
+        unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PERSONALIZE_KEY,
+                w!("AppsUseLightTheme"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some((&mut light as *mut u32).cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .map_err(|e| e.to_string())?;
+        Ok(if light == 0 {
+            SystemTheme::Dark
+        } else {
+            SystemTheme::Light
+        })
+    }
+
+    pub(super) fn accent() -> Result<AccentColor, String> {
+        let mut argb = 0u32;
+        let mut opaque = BOOL::default();
+        // SAFETY: both out-pointers are live locals.
+        unsafe { DwmGetColorizationColor(&mut argb, &mut opaque) }.map_err(|e| e.to_string())?;
+        let [_, r, g, b] = argb.to_be_bytes();
+        Ok(AccentColor { r, g, b })
+    }
+
+    /// Block on `subkey` changing and refresh, until the wait fails.
+    fn watch_key(app: &AppHandle, subkey: PCWSTR) -> Result<(), String> {
+        let mut key = HKEY::default();
+        // SAFETY: opens a key for change notifications only. It stays open
+        // for the life of the process.
+        unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, subkey, None, KEY_NOTIFY, &mut key) }
+            .ok()
+            .map_err(|e| e.to_string())?;
+        // HKEY is a raw handle; only the value crosses to the thread.
+        let raw = key.0 as isize;
+        let app = app.clone();
+        std::thread::Builder::new()
+            .name("appearance-watch".into())
+            .spawn(move || loop {
+                let key = HKEY(raw as _);
+                // SAFETY: `key` is open; a synchronous wait needs no event.
+                let status = unsafe {
+                    RegNotifyChangeKeyValue(key, false, REG_NOTIFY_CHANGE_LAST_SET, None, false)
+                };
+                if let Err(e) = status.ok() {
+                    warn!("Stopped watching appearance changes: {}", e);
+                    super::watch_stopped();
+                    return;
+                }
+                super::refresh_soon(&app);
+            })
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub(super) fn watch(app: &AppHandle) -> Result<bool, String> {
+        watch_key(app, PERSONALIZE_KEY)?;
+        watch_key(app, DWM_KEY)?;
+        Ok(true)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use objc2::rc::autoreleasepool;
+    use objc2_app_kit::{NSColor, NSColorSpace};
+    use objc2_foundation::{ns_string, NSUserDefaults};
+    use tauri::AppHandle;
+
+    use super::{AccentColor, SystemTheme};
+
+    // The callers are not on a thread that drains an autorelease pool, so
+    // each read brings its own.
+
+    pub(super) fn theme() -> Result<SystemTheme, String> {
+        let style = autoreleasepool(|_| {
+            NSUserDefaults::standardUserDefaults()
+                .stringForKey(ns_string!("AppleInterfaceStyle"))
+                .map(|style| style.to_string())
+        });
+        // Unset means light mode.
+        Ok(if style.as_deref() == Some("Dark") {
+            SystemTheme::Dark
+        } else {
+            SystemTheme::Light
+        })
+    }
+
+    pub(super) fn accent() -> Result<AccentColor, String> {
+        autoreleasepool(|_| {
+            // `controlAccentColor` is dynamic; read it in sRGB.
+            let srgb = NSColor::controlAccentColor()
+                .colorUsingColorSpace(&NSColorSpace::sRGBColorSpace())
+                .ok_or("accent color has no sRGB form")?;
+            let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            Ok(AccentColor {
+                r: channel(srgb.redComponent()),
+                g: channel(srgb.greenComponent()),
+                b: channel(srgb.blueComponent()),
+            })
+        })
+    }
+
+    pub(super) fn watch(_app: &AppHandle) -> Result<bool, String> {
+        // Covered by ThemeChanged and main-window focus; see the module docs.
+        Ok(false)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use log::warn;
+    use parking_lot::Mutex;
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedValue;
+
+    use super::{AccentColor, SystemTheme};
+
+    const PORTAL: &str = "org.freedesktop.portal.Desktop";
+    const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+    const SETTINGS: &str = "org.freedesktop.portal.Settings";
+    const APPEARANCE: &str = "org.freedesktop.appearance";
+
+    /// One session bus connection for every read and the watcher.
+    fn session() -> zbus::Result<Connection> {
+        static SESSION: Mutex<Option<Connection>> = Mutex::new(None);
+        let mut session = SESSION.lock();
+        if let Some(connection) = session.as_ref() {
+            return Ok(connection.clone());
+        }
+        let connection = Connection::session()?;
+        *session = Some(connection.clone());
+        Ok(connection)
+    }
+
+    fn read_setting(key: &str) -> Result<OwnedValue, String> {
+        session()
+            .and_then(|session| {
+                session.call_method(
+                    Some(PORTAL),
+                    PORTAL_PATH,
+                    Some(SETTINGS),
+                    "ReadOne",
+                    &(APPEARANCE, key),
+                )
+            })
+            .and_then(|reply| reply.body().deserialize::<OwnedValue>())
+            .map_err(|e| e.to_string())
+    }
+
+    pub(super) fn theme() -> Result<SystemTheme, String> {
+        // 0: no preference, 1: dark, 2: light.
+        let scheme = u32::try_from(read_setting("color-scheme")?).map_err(|e| e.to_string())?;
+        Ok(if scheme == 1 {
+            SystemTheme::Dark
+        } else {
+            SystemTheme::Light
+        })
+    }
+
+    pub(super) fn accent() -> Result<AccentColor, String> {
+        let (r, g, b) = <(f64, f64, f64)>::try_from(read_setting("accent-color")?)
+            .map_err(|e| e.to_string())?;
+        // Out-of-range components mean "unset".
+        if ![r, g, b].iter().all(|c| (0.0..=1.0).contains(c)) {
+            return Err("no accent color set".into());
+        }
+        let channel = |c: f64| (c * 255.0).round() as u8;
+        Ok(AccentColor {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        })
+    }
+
+    pub(super) fn watch(app: &AppHandle) -> Result<bool, String> {
+        let session = session().map_err(|e| e.to_string())?;
+        let app = app.clone();
+        std::thread::Builder::new()
+            .name("appearance-watch".into())
+            .spawn(move || {
+                let signals = Proxy::new(&session, PORTAL, PORTAL_PATH, SETTINGS)
+                    .and_then(|proxy| proxy.receive_signal("SettingChanged"));
+                let signals = match signals {
+                    Ok(signals) => signals,
+                    Err(e) => {
+                        warn!("Not watching the desktop portal: {}", e);
+                        super::watch_stopped();
+                        return;
+                    }
+                };
+                for message in signals {
+                    let namespace = message
+                        .body()
+                        .deserialize::<(String, String, OwnedValue)>()
+                        .map(|(namespace, ..)| namespace);
+                    if namespace.is_ok_and(|namespace| namespace == APPEARANCE) {
+                        super::refresh_soon(&app);
+                    }
+                }
+                super::watch_stopped();
+            })
+            .map(|_| true)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    use tauri::AppHandle;
+
+    use super::{AccentColor, SystemTheme};
+
+    pub(super) fn theme() -> Result<SystemTheme, String> {
+        Err("not supported on this platform".into())
+    }
+
+    pub(super) fn accent() -> Result<AccentColor, String> {
+        Err("not supported on this platform".into())
+    }
+
+    pub(super) fn watch(_app: &AppHandle) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_what_changed_is_reported() {
+        let dark = Appearance {
+            theme: SystemTheme::Dark,
+            accent: DEFAULT_ACCENT,
+        };
+        assert_eq!(record(dark), (None, None));
+        assert_eq!(cached_theme(), SystemTheme::Dark);
+        assert_eq!(record(dark), (None, None));
+        let pink = AccentColor {
+            r: 227,
+            g: 0,
+            b: 140,
+        };
+        let light = Appearance {
+            theme: SystemTheme::Light,
+            accent: pink,
+        };
+        assert_eq!(record(light), (Some(SystemTheme::Light), Some(pink)));
+        assert_eq!(cached_theme(), SystemTheme::Light);
+    }
+}
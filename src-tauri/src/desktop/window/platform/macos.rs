@@ -0,0 +1,27 @@
+//! AppKit access shared by the window modules, through objc2.
+
+use objc2_app_kit::NSWindow;
+use tauri::WebviewWindow;
+
+/// `window`'s `NSWindow`, for getters that are fine off the main thread.
+pub(crate) fn ns_window(window: &WebviewWindow) -> tauri::Result<&NSWindow> {
+    let ns_window = window.ns_window()?;
+    // SAFETY: tauri hands out the window's own NSWindow, which lives at
+    // least as long as `window`.
+    Ok(unsafe { &*(ns_window as *const NSWindow) })
+}
+
+/// Run `f` with `window`'s `NSWindow` on the main thread, where AppKit
+/// must be called.
+pub(crate) fn with_ns_window<F>(window: &WebviewWindow, f: F) -> tauri::Result<()>
+where
+    F: FnOnce(&NSWindow) + Send + 'static,
+{
+    let ns_window = window.ns_window()? as usize;
+    window.run_on_main_thread(move || {
+        // SAFETY: tauri hands out the window's own NSWindow, which lives as
+        // long as the window; the closure runs on the main thread.
+        let ns_window = unsafe { &*(ns_window as *const NSWindow) };
+        f(ns_window);
+    })
+}
@@ -0,0 +1,29 @@
+//! Native integrations that differ per platform: the OS appearance, the
+//! taskbar extras only Windows has, and shared AppKit access on macOS.
+
+use tauri::AppHandle;
+
+pub mod appearance;
+
+#[cfg(target_os = "macos")]
+pub(crate) mod macos;
+
+#[cfg(target_os = "windows")]
+pub mod windows_taskbar;
+
+#[cfg(target_os = "windows")]
+fn set_playing(app: &AppHandle, playing: bool) -> Result<(), String> {
+    windows_taskbar::set_thumbbar_state(app, playing)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_playing(_app: &AppHandle, _playing: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Show play or pause on the middle thumbnail toolbar button. Windows only;
+/// elsewhere there is no thumbnail toolbar and this does nothing.
+#[tauri::command]
+pub fn set_thumbbar_state(app: AppHandle, playing: bool) -> Result<(), String> {
+    set_playing(&app, playing)
+}
@@ -0,0 +1,295 @@
+//! Previous / play-pause / next buttons in the main window's taskbar
+//! thumbnail.
+//!
+//! The taskbar only takes buttons once it has made the window's button,
+//! which it announces with the registered `TaskbarButtonCreated` message:
+//! on first show, whenever the window comes back from the tray, and after
+//! Explorer restarts. The main window is subclassed to add them on that
+//! message and to turn their `WM_COMMAND` clicks into `thumbbar-*` events.
+//! A recreated main window is a new HWND, so `create_window` attaches again.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use log::{debug, warn};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use windows::core::w;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+use windows::Win32::UI::Shell::{
+    DefSubclassProc, ITaskbarList3, SetWindowSubclass, TaskbarList, THBF_ENABLED, THBN_CLICKED,
+    THB_FLAGS, THB_ICON, THB_TOOLTIP, THUMBBUTTON,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    ChangeWindowMessageFilterEx, CreateIcon, GetSystemMetrics, IsWindowVisible,
+    RegisterWindowMessageW, HICON, MSGFLT_ALLOW, SM_CXSMICON, WM_COMMAND,
+};
+
+use crate::desktop::i18n::{self, Text};
+
+pub const THUMBBAR_PREV_EVENT: &str = "thumbbar-prev";
+pub const THUMBBAR_PLAY_PAUSE_EVENT: &str = "thumbbar-play-pause";
+pub const THUMBBAR_NEXT_EVENT: &str = "thumbbar-next";
+
+/// Any value unique among the main window's subclasses; decorum has its own.
+const SUBCLASS_ID: usize = 0x7468_6d62;
+const BUTTON_PREV: u32 = 1;
+const BUTTON_PLAY_PAUSE: u32 = 2;
+const BUTTON_NEXT: u32 = 3;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static BUTTON_CREATED: OnceLock<u32> = OnceLock::new();
+/// Raw `HICON`s, one per [`Glyph`], created on first use and kept for the
+/// life of the process.
+static ICONS: OnceLock<[isize; 4]> = OnceLock::new();
+static PLAYING: AtomicBool = AtomicBool::new(false);
+
+/// In [`Glyph::ALL`] order, which indexes [`ICONS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Glyph {
+    Previous,
+    Play,
+    Pause,
+    Next,
+}
+
+impl Glyph {
+    const ALL: [Glyph; 4] = [Glyph::Previous, Glyph::Play, Glyph::Pause, Glyph::Next];
+
+    /// Whether the point `(u, v)`, both in 0..1 across the icon, is inked.
+    fn covers(self, u: f64, v: f64) -> bool {
+        // A right-pointing triangle from `left` to `tip`, 0.6 tall.
+        let triangle =
+            |left: f64, tip: f64| u >= left && (v - 0.5).abs() <= 0.3 * (tip - u) / (tip - left);
+        let bar = |left: f64, right: f64| (left..=right).contains(&u) && (0.2..=0.8).contains(&v);
+        match self {
+            Glyph::Play => triangle(0.3, 0.78),
+            Glyph::Pause => bar(0.28, 0.43) || bar(0.57, 0.72),
+            Glyph::Next => triangle(0.25, 0.65) || bar(0.65, 0.75),
+            Glyph::Previous => Glyph::Next.covers(1.0 - u, v),
+        }
+    }
+
+    /// White on transparent, as top-down 32bpp BGRA rows.
+    fn pixels(self, size: usize) -> Vec<u8> {
+        let mut pixels = vec![0u8; size * size * 4];
+        for y in 0..size {
+            for x in 0..size {
+                let u = (x as f64 + 0.5) / size as f64;
+                let v = (y as f64 + 0.5) / size as f64;
+                if self.covers(u, v) {
+                    let i = (y * size + x) * 4;
+                    pixels[i..i + 4].copy_from_slice(&[0xff; 4]);
+                }
+            }
+        }
+        pixels
+    }
+
+    fn icon(self) -> HICON {
+        let icons = ICONS.get_or_init(|| {
+            // SAFETY: both masks are sized for a `size`-pixel square icon.
+            unsafe {
+                let size = GetSystemMetrics(SM_CXSMICON).max(16);
+                // Rows of the 1bpp AND mask are padded to 16 bits; with an
+                // all-clear mask the alpha channel decides.
+                let mask = vec![0u8; (size as usize).div_ceil(16) * 2 * size as usize];
+                Glyph::ALL.map(|glyph| {
+                    let pixels = glyph.pixels(size as usize);
+                    CreateIcon(None, size, size, 1, 32, mask.as_ptr(), pixels.as_ptr())
+                        .map(|icon| icon.0 as isize)
+                        .unwrap_or_else(|e| {
+                            warn!("Failed to draw thumbnail button {:?}: {}", glyph, e);
+                            0
+                        })
+                })
+            }
+        });
+        HICON(icons[self as usize] as _)
+    }
+}
+
+fn button(id: u32, glyph: Glyph, tip: &str) -> THUMBBUTTON {
+    let mut button = THUMBBUTTON {
+        dwMask: THB_ICON | THB_TOOLTIP | THB_FLAGS,
+        iId: id,
+        hIcon: glyph.icon(),
+        dwFlags: THBF_ENABLED,
+        ..Default::default()
+    };
+    // Leave the last slot for the terminating NUL.
+    let room = button.szTip.len() - 1;
+    for (slot, unit) in button.szTip[..room].iter_mut().zip(tip.encode_utf16()) {
+        *slot = unit;
+    }
+    button
+}
+
+fn play_pause_button() -> THUMBBUTTON {
+    // The button shows what a click does.
+    if PLAYING.load(Ordering::Acquire) {
+        button(
+            BUTTON_PLAY_PAUSE,
+            Glyph::Pause,
+            i18n::tr(Text::ThumbbarPause),
+        )
+    } else {
+        button(BUTTON_PLAY_PAUSE, Glyph::Play, i18n::tr(Text::ThumbbarPlay))
+    }
+}
+
+fn buttons() -> [THUMBBUTTON; 3] {
+    [
+        button(
+            BUTTON_PREV,
+            Glyph::Previous,
+            i18n::tr(Text::TrayMenuPrevious),
+        ),
+        play_pause_button(),
+        button(BUTTON_NEXT, Glyph::Next, i18n::tr(Text::TrayMenuNext)),
+    ]
+}
+
+fn taskbar() -> windows::core::Result<ITaskbarList3> {
+    // SAFETY: called on the main thread, where the event loop has set up COM.
+    unsafe {
+        let taskbar: ITaskbarList3 = CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)?;
+        taskbar.HrInit()?;
+        Ok(taskbar)
+    }
+}
+
+/// Put the buttons on the current taskbar button. The taskbar refuses a
+/// second add on the same button, which happens when the window's own
+/// `TaskbarButtonCreated` follows an add from [`attach`]; update then.
+fn add_buttons(hwnd: HWND) -> windows::core::Result<()> {
+    let taskbar = taskbar()?;
+    let buttons = buttons();
+    // SAFETY: `hwnd` is the live main window and `buttons` outlives the call.
+    unsafe {
+        taskbar
+            .ThumbBarAddButtons(hwnd, &buttons)
+            .or_else(|_| taskbar.ThumbBarUpdateButtons(hwnd, &buttons))
+    }
+}
+
+unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    if BUTTON_CREATED.get() == Some(&msg) {
+        if let Err(e) = add_buttons(hwnd) {
+            warn!("Failed to add thumbnail toolbar buttons: {}", e);
+        }
+    } else if msg == WM_COMMAND && (wparam.0 >> 16) & 0xffff == THBN_CLICKED as usize {
+        let event = match (wparam.0 & 0xffff) as u32 {
+            BUTTON_PREV => Some(THUMBBAR_PREV_EVENT),
+            BUTTON_PLAY_PAUSE => Some(THUMBBAR_PLAY_PAUSE_EVENT),
+            BUTTON_NEXT => Some(THUMBBAR_NEXT_EVENT),
+            _ => None,
+        };
+        if let (Some(event), Some(app)) = (event, APP.get()) {
+            let _ = app.emit_to("main", event, ());
+            return LRESULT(0);
+        }
+    }
+    DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+/// Subclass a newly created main window. Call for every main window; the
+/// buttons follow it from then on.
+pub(crate) fn attach(app: &AppHandle, window: &WebviewWindow) {
+    let _ = APP.set(app.clone());
+    let hwnd = match window.hwnd() {
+        Ok(hwnd) => hwnd.0 as isize,
+        Err(e) => {
+            warn!("No HWND for the thumbnail toolbar: {}", e);
+            return;
+        }
+    };
+    // A window can only be subclassed from the thread that owns it.
+    let queued = window.run_on_main_thread(move || {
+        let hwnd = HWND(hwnd as _);
+        // SAFETY: runs on the window's own thread while it is alive.
+        unsafe {
+            let message =
+                *BUTTON_CREATED.get_or_init(|| RegisterWindowMessageW(w!("TaskbarButtonCreated")));
+            // Explorer's messages are otherwise dropped when running elevated.
+            let _ = ChangeWindowMessageFilterEx(hwnd, message, MSGFLT_ALLOW, None);
+            let _ = ChangeWindowMessageFilterEx(hwnd, WM_COMMAND, MSGFLT_ALLOW, None);
+            if !SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0).as_bool() {
+                warn!("Failed to subclass the main window for thumbnail buttons");
+                return;
+            }
+            // Already on the taskbar: its `TaskbarButtonCreated` has passed.
+            if IsWindowVisible(hwnd).as_bool() {
+                if let Err(e) = add_buttons(hwnd) {
+                    warn!("Failed to add thumbnail toolbar buttons: {}", e);
+                }
+            }
+        }
+    });
+    if let Err(e) = queued {
+        warn!("Failed to queue thumbnail toolbar setup: {}", e);
+    }
+}
+
+/// Rewrite all three buttons, e.g. after a locale change.
+fn update(app: &AppHandle, buttons: fn() -> Vec<THUMBBUTTON>) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as isize;
+    window
+        .run_on_main_thread(move || {
+            let result = taskbar().and_then(|taskbar| {
+                // SAFETY: on the main window's thread; the slice outlives the call.
+                unsafe { taskbar.ThumbBarUpdateButtons(HWND(hwnd as _), &buttons()) }
+            });
+            // Fails until the buttons exist; they are added in the new
+            // state when they do.
+            if let Err(e) = result {
+                debug!("Thumbnail toolbar not updated: {}", e);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Swap the middle button between play and pause.
+pub fn set_thumbbar_state(app: &AppHandle, playing: bool) -> Result<(), String> {
+    if PLAYING.swap(playing, Ordering::AcqRel) == playing {
+        return Ok(());
+    }
+    update(app, || vec![play_pause_button()])
+}
+
+/// Retranslate the button tooltips.
+pub(crate) fn refresh_locale(app: &AppHandle) {
+    if let Err(e) = update(app, || buttons().to_vec()) {
+        warn!("Failed to retranslate thumbnail buttons: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previous_mirrors_next_and_play_and_pause_differ() {
+        let size = 16;
+        let next = Glyph::Next.pixels(size);
+        let previous = Glyph::Previous.pixels(size);
+        let mirrored_at = |x: usize, y: usize| (y * size + (size - 1 - x)) * 4 + 3;
+        for y in 0..size {
+            for x in 0..size {
+                assert_eq!(previous[(y * size + x) * 4 + 3], next[mirrored_at(x, y)]);
+            }
+        }
+        assert!(next.iter().any(|&byte| byte != 0));
+        assert_ne!(Glyph::Play.pixels(size), Glyph::Pause.pixels(size));
+    }
+}
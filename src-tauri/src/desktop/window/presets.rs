@@ -0,0 +1,213 @@
+//! Window presets, built-in and user-defined.
+//!
+//! Plugins (a visualizer window, say) can register their own labelled
+//! presets instead of passing a full config on every open. They live in
+//! `presets.json` in the app config dir as an array of `WindowConfig`s,
+//! loaded once during setup. Built-in labels are reserved: a user preset
+//! cannot replace `main` or `settings`.
+//!
+//! Call [`lookup`] wherever a label is resolved to its preset.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use log::{info, warn};
+use parking_lot::RwLock;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::desktop::window::config::WindowConfig;
+use crate::settings::write_atomic;
+
+const PRESETS_FILE: &str = "presets.json";
+
+#[derive(Default)]
+struct Registry {
+    /// Unset until `load`; changes before that are kept in memory only.
+    path: Option<PathBuf>,
+    presets: BTreeMap<String, WindowConfig>,
+}
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(Default::default);
+
+/// The preset for `label`: the built-in one, else a registered one.
+pub fn lookup(label: &str) -> Option<WindowConfig> {
+    WindowConfig::from_label(label).or_else(|| REGISTRY.read().presets.get(label).cloned())
+}
+
+fn is_builtin(label: &str) -> bool {
+    WindowConfig::from_label(label).is_some()
+}
+
+/// Reject presets the window manager could not open sensibly.
+fn validate(config: &WindowConfig) -> Result<(), String> {
+    let label = &config.label;
+    if label.is_empty()
+        || !label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':'))
+    {
+        return Err(format!(
+            "Invalid window label '{}': use letters, digits, '-', '_', '/' or ':'",
+            label
+        ));
+    }
+    if is_builtin(label) {
+        return Err(format!("'{}' is a built-in window preset", label));
+    }
+    let sizes = [Some(config.width), Some(config.height)]
+        .into_iter()
+        .chain([
+            config.min_width,
+            config.min_height,
+            config.max_width,
+            config.max_height,
+        ])
+        .flatten();
+    for size in sizes {
+        if !size.is_finite() || size <= 0.0 {
+            return Err(format!("Preset '{}' has an invalid size {}", label, size));
+        }
+    }
+    if !config.url.starts_with('/') {
+        return Err(format!(
+            "Preset '{}' must load an app route starting with '/'",
+            label
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the presets file, skipping (and logging) entries that do not
+/// deserialize as a `WindowConfig` or fail validation.
+fn parse(bytes: &[u8]) -> Result<BTreeMap<String, WindowConfig>, String> {
+    let entries: Vec<Value> = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    let mut presets = BTreeMap::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let config = serde_json::from_value::<WindowConfig>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(|config| validate(&config).map(|()| config));
+        match config {
+            Ok(config) => {
+                presets.insert(config.label.clone(), config);
+            }
+            Err(e) => warn!("Skipping window preset #{}: {}", index, e),
+        }
+    }
+    Ok(presets)
+}
+
+fn persist(path: Option<&Path>, presets: &BTreeMap<String, WindowConfig>) -> Result<(), String> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let list: Vec<&WindowConfig> = presets.values().collect();
+    let json = serde_json::to_vec_pretty(&list).map_err(|e| e.to_string())?;
+    write_atomic(path, &json).map_err(|e| format!("Failed to save window presets: {}", e))
+}
+
+/// Load user presets from the app config dir. Call once during setup.
+pub fn load(app: &AppHandle) {
+    let path = match app.path().app_config_dir() {
+        Ok(dir) => dir.join(PRESETS_FILE),
+        Err(e) => {
+            warn!(
+                "Failed to resolve app config dir, window presets will not persist: {}",
+                e
+            );
+            return;
+        }
+    };
+    let presets = match std::fs::read(&path) {
+        Ok(bytes) => parse(&bytes).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable {}: {}", path.display(), e);
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    };
+    if !presets.is_empty() {
+        info!("Loaded {} user window preset(s)", presets.len());
+    }
+    let mut registry = REGISTRY.write();
+    registry.presets.extend(presets);
+    registry.path = Some(path);
+}
+
+/// Add or replace a user preset and save the file. Async so the write
+/// stays off the main thread.
+#[tauri::command]
+pub async fn register_window_preset(config: WindowConfig) -> Result<(), String> {
+    validate(&config)?;
+    let mut registry = REGISTRY.write();
+    let mut next = registry.presets.clone();
+    next.insert(config.label.clone(), config);
+    persist(registry.path.as_deref(), &next)?;
+    registry.presets = next;
+    Ok(())
+}
+
+/// Remove a user preset. Returns whether it existed. Open windows created
+/// from it stay open.
+#[tauri::command]
+pub async fn remove_window_preset(label: String) -> Result<bool, String> {
+    let mut registry = REGISTRY.write();
+    if !registry.presets.contains_key(&label) {
+        return Ok(false);
+    }
+    let mut next = registry.presets.clone();
+    next.remove(&label);
+    persist(registry.path.as_deref(), &next)?;
+    registry.presets = next;
+    Ok(true)
+}
+
+/// User-registered presets; built-ins are not included.
+#[tauri::command]
+pub fn list_window_presets() -> Vec<WindowConfig> {
+    REGISTRY.read().presets.values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(label: &str) -> Value {
+        serde_json::json!({
+            "label": label,
+            "title": "Visualizer",
+            "url": "/slave.html#/visualizer",
+            "width": 640.0,
+            "height": 360.0,
+        })
+    }
+
+    #[test]
+    fn invalid_and_reserved_presets_are_skipped() {
+        let mut bad_size = preset("tiny");
+        bad_size["width"] = serde_json::json!(0.0);
+        let mut remote = preset("remote");
+        remote["url"] = serde_json::json!("https://example.com");
+        let file = serde_json::to_vec(&serde_json::json!([
+            preset("visualizer"),
+            preset("settings"),
+            preset("bad label"),
+            bad_size,
+            remote,
+            { "label": "incomplete" },
+        ]))
+        .unwrap();
+
+        let presets = parse(&file).unwrap();
+        assert_eq!(presets.keys().collect::<Vec<_>>(), ["visualizer"]);
+        assert!(presets["visualizer"].resizable, "serde defaults apply");
+        assert!(parse(b"{}").is_err());
+    }
+
+    #[test]
+    fn builtins_win_over_registered_presets() {
+        let config: WindowConfig = serde_json::from_value(preset("about")).unwrap();
+        assert!(tauri::async_runtime::block_on(register_window_preset(config)).is_err());
+        assert_eq!(lookup("about").unwrap().url, WindowConfig::about().url);
+    }
+}
@@ -0,0 +1,210 @@
+//! Hidden pre-creation of windows that are slow to open cold.
+//!
+//! Once the main window reports ready, the settings window and mini player
+//! are created hidden with `prewarm` set: no effects, no centering. Their JS
+//! gets `window-prewarmed` (`paused: true`) and idles. The first show path
+//! that reaches one of them calls [`activate`], which applies the deferred
+//! appearance and un-pauses it.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::desktop::safe_mode;
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager;
+use crate::desktop::window::presets;
+use crate::settings::SettingsStore;
+
+pub const WINDOW_PREWARMED_EVENT: &str = "window-prewarmed";
+
+const PREWARM_LABELS: [&str; 2] = ["settings", "mini-player"];
+/// Give the main window's first paint and playback restore a head start.
+const PREWARM_DELAY: Duration = Duration::from_secs(3);
+/// Delay between windows so two webviews never spin up at once.
+const PREWARM_STAGGER: Duration = Duration::from_secs(2);
+/// Each webview costs 50–100 MB; below this the memory matters more than
+/// the faster first open.
+const MIN_TOTAL_MEMORY: u64 = 4 * 1024 * 1024 * 1024;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// Labels created by pre-warm and not shown since.
+static PREWARMED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPrewarmed {
+    pub label: String,
+    /// `true` while hidden and pre-warmed; `false` once really shown.
+    pub paused: bool,
+}
+
+/// Track a freshly built pre-warm window and tell its JS to idle.
+pub(crate) fn register(window: &WebviewWindow) {
+    let label = window.label().to_string();
+    PREWARMED.lock().insert(label.clone());
+    emit(window, label, true);
+}
+
+/// Turn a pre-warmed window into a normal one before it is shown. No-op for
+/// windows that were not pre-warmed or were already activated.
+pub(crate) fn activate(window: &WebviewWindow) {
+    let label = window.label().to_string();
+    if !PREWARMED.lock().remove(&label) {
+        return;
+    }
+    if let Some(config) = presets::lookup(&label) {
+        if let Err(e) = manager::apply_appearance(window, &config) {
+            warn!(
+                "Failed to apply appearance to pre-warmed '{}': {}",
+                label, e
+            );
+        }
+        if config.center {
+            let _ = window.center();
+        }
+    }
+    emit(window, label, false);
+}
+
+/// Pre-warmed windows have not been used yet, so idle-window reclaiming
+/// must leave them alone.
+pub fn is_prewarmed(label: &str) -> bool {
+    PREWARMED.lock().contains(label)
+}
+
+fn emit(window: &WebviewWindow, label: String, paused: bool) {
+    let target = label.clone();
+    let _ = window.app_handle().emit_to(
+        target.as_str(),
+        WINDOW_PREWARMED_EVENT,
+        WindowPrewarmed { label, paused },
+    );
+}
+
+fn prewarm_enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsStore>()
+        .map_or(true, |store| store.read(|s| s.window.prewarm_windows))
+}
+
+/// Called by the main window once it has rendered. Ends the startup-crash
+/// watch and starts the pre-warm at most once per run.
+#[tauri::command]
+pub fn notify_main_window_ready(app: AppHandle) {
+    safe_mode::mark_startup_complete(&app);
+    if safe_mode::is_active() || STARTED.swap(true, Ordering::SeqCst) || !prewarm_enabled(&app) {
+        return;
+    }
+    if let Some(total) = total_memory_bytes() {
+        if total < MIN_TOTAL_MEMORY {
+            info!(
+                "Skipping window pre-warm: {} MiB RAM",
+                total / (1024 * 1024)
+            );
+            return;
+        }
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("window-prewarm".into())
+        .spawn(move || {
+            std::thread::sleep(PREWARM_DELAY);
+            for (i, label) in PREWARM_LABELS.into_iter().enumerate() {
+                if i > 0 {
+                    std::thread::sleep(PREWARM_STAGGER);
+                }
+                // Re-checked per window so toggling the setting off cancels
+                // whatever has not been created yet.
+                if !prewarm_enabled(&app) || app.get_webview_window(label).is_some() {
+                    continue;
+                }
+                let Some(mut config) = WindowConfig::from_label(label) else {
+                    continue;
+                };
+                config.prewarm = true;
+                if let Err(e) = manager::create_window(&app, &config) {
+                    warn!("Failed to pre-warm '{}': {}", label, e);
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn window pre-warm: {}", e);
+    }
+}
+
+fn total_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+        let mut status = MEMORYSTATUSEX {
+            dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `status` is a properly sized MEMORYSTATUSEX with `dwLength` set.
+        let ok = unsafe { GlobalMemoryStatusEx(&mut status) }.is_ok();
+        ok.then_some(status.ullTotalPhys)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::ffi::{c_char, c_int, c_void};
+        extern "C" {
+            fn sysctlbyname(
+                name: *const c_char,
+                oldp: *mut c_void,
+                oldlenp: *mut usize,
+                newp: *mut c_void,
+                newlen: usize,
+            ) -> c_int;
+        }
+        let mut total: u64 = 0;
+        let mut len = std::mem::size_of::<u64>();
+        // SAFETY: the name is NUL-terminated and `total`/`len` describe a
+        // valid u64 buffer.
+        let rc = unsafe {
+            sysctlbyname(
+                c"hw.memsize".as_ptr(),
+                &mut total as *mut u64 as *mut c_void,
+                &mut len,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        (rc == 0).then_some(total)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|info| parse_mem_total(&info))
+    }
+}
+
+/// `MemTotal:  16318712 kB` → bytes.
+#[cfg(any(target_os = "linux", test))]
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meminfo_total_is_parsed() {
+        let info = "MemTotal:       16318712 kB\nMemFree:         1234 kB\n";
+        assert_eq!(parse_mem_total(info), Some(16_318_712 * 1024));
+        assert_eq!(parse_mem_total("MemFree: 1 kB"), None);
+    }
+}
@@ -0,0 +1,233 @@
+//! Recently closed windows, for "reopen closed window" (Ctrl+Shift+T).
+//!
+//! Bounds have to be captured while the window still exists, so
+//! [`note_closing`] snapshots it on the close request and [`on_destroyed`]
+//! moves the snapshot onto a bounded stack once the window is really gone.
+//! Nothing is recorded while the app is shutting down.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, LogicalPosition, Manager, State, WebviewWindow};
+
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager;
+use crate::desktop::window::presets;
+use crate::desktop::window::prewarm;
+
+pub const MAX_RECENTLY_CLOSED: usize = 10;
+/// Windows that are never "closed" from the user's point of view.
+const UNTRACKED_LABELS: [&str; 4] = ["main", "tray-popup", "osd", "desktop-lyrics-controls"];
+
+/// Logical outer position and inner size.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedWindow {
+    pub label: String,
+    pub bounds: Option<WindowBounds>,
+    pub visible: bool,
+    pub closed_ms: u64,
+}
+
+/// Managed recently-closed stack (newest last).
+#[derive(Default)]
+pub struct RecentlyClosedWindows {
+    stack: Mutex<VecDeque<ClosedWindow>>,
+    pending: Mutex<HashMap<String, ClosedWindow>>,
+    /// Configs of windows opened through `create_custom_window`, so they can
+    /// be rebuilt without a preset.
+    custom_configs: Mutex<HashMap<String, WindowConfig>>,
+    shutting_down: AtomicBool,
+}
+
+impl RecentlyClosedWindows {
+    fn push(&self, entry: ClosedWindow) {
+        let mut stack = self.stack.lock();
+        stack.retain(|e| e.label != entry.label);
+        stack.push_back(entry);
+        while stack.len() > MAX_RECENTLY_CLOSED {
+            stack.pop_front();
+        }
+    }
+
+    fn config_for(&self, label: &str) -> Option<WindowConfig> {
+        presets::lookup(label).or_else(|| self.custom_configs.lock().get(label).cloned())
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn snapshot(window: &WebviewWindow) -> ClosedWindow {
+    let bounds = (|| {
+        let scale = window.scale_factor().ok()?;
+        let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+        let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+        Some(WindowBounds {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    })();
+    ClosedWindow {
+        label: window.label().to_string(),
+        bounds,
+        visible: window.is_visible().unwrap_or(true),
+        closed_ms: now_ms(),
+    }
+}
+
+/// Remember the config of a window created from a frontend-supplied config.
+pub fn remember_custom_config(app: &AppHandle, config: &WindowConfig) {
+    if presets::lookup(&config.label).is_some() {
+        return;
+    }
+    if let Some(state) = app.try_state::<RecentlyClosedWindows>() {
+        state
+            .custom_configs
+            .lock()
+            .insert(config.label.clone(), config.clone());
+    }
+}
+
+/// Stop recording closes; called when the app starts quitting.
+pub fn mark_shutting_down(app: &AppHandle) {
+    if let Some(state) = app.try_state::<RecentlyClosedWindows>() {
+        state.shutting_down.store(true, Ordering::SeqCst);
+    }
+}
+
+pub fn is_shutting_down(app: &AppHandle) -> bool {
+    app.try_state::<RecentlyClosedWindows>()
+        .is_some_and(|state| state.shutting_down.load(Ordering::SeqCst))
+}
+
+/// Snapshot a window that is about to be closed by the user.
+pub fn note_closing(window: &WebviewWindow) {
+    let label = window.label();
+    if UNTRACKED_LABELS.contains(&label) {
+        return;
+    }
+    let Some(state) = window.app_handle().try_state::<RecentlyClosedWindows>() else {
+        return;
+    };
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return;
+    }
+    state
+        .pending
+        .lock()
+        .insert(label.to_string(), snapshot(window));
+}
+
+/// Destroy a window without recording it, for closes the user did not ask
+/// for (e.g. reclaiming idle windows). Pre-warmed windows that were never
+/// shown are kept; reclaiming them would undo the pre-warm.
+pub fn destroy_untracked(window: &WebviewWindow) -> Result<(), String> {
+    if prewarm::is_prewarmed(window.label()) {
+        return Ok(());
+    }
+    if let Some(state) = window.app_handle().try_state::<RecentlyClosedWindows>() {
+        state.pending.lock().remove(window.label());
+    }
+    window.destroy().map_err(|e| e.to_string())
+}
+
+/// Run-loop hook for `WindowEvent::Destroyed`.
+pub fn on_destroyed(app: &AppHandle, label: &str) {
+    let Some(state) = app.try_state::<RecentlyClosedWindows>() else {
+        return;
+    };
+    let Some(entry) = state.pending.lock().remove(label) else {
+        return;
+    };
+    if !state.shutting_down.load(Ordering::SeqCst) {
+        state.push(entry);
+    }
+}
+
+#[tauri::command]
+pub fn list_recently_closed_windows(state: State<'_, RecentlyClosedWindows>) -> Vec<ClosedWindow> {
+    state.stack.lock().iter().rev().cloned().collect()
+}
+
+/// Recreate the most recently closed window at its last bounds. Returns its
+/// label, or `None` when nothing is left to reopen.
+#[tauri::command]
+pub async fn reopen_last_closed_window(app: AppHandle) -> Result<Option<String>, String> {
+    let state = app.state::<RecentlyClosedWindows>();
+    loop {
+        let Some(entry) = state.stack.lock().pop_back() else {
+            return Ok(None);
+        };
+        if app.get_webview_window(&entry.label).is_some() {
+            // Already open again through some other path.
+            manager::show_window(&app, &entry.label)?;
+            return Ok(Some(entry.label));
+        }
+        let Some(mut config) = state.config_for(&entry.label) else {
+            warn!("No config to reopen window '{}'", entry.label);
+            continue;
+        };
+        config.visible = entry.visible;
+        if let Some(bounds) = entry.bounds {
+            config.width = bounds.width;
+            config.height = bounds.height;
+            config.center = false;
+        }
+        manager::create_window(&app, &config)?;
+        if let (Some(bounds), Some(window)) = (entry.bounds, app.get_webview_window(&entry.label)) {
+            if let Err(e) = window.set_position(LogicalPosition::new(bounds.x, bounds.y)) {
+                warn!("Failed to restore position of '{}': {}", entry.label, e);
+            }
+        }
+        return Ok(Some(entry.label));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(label: &str) -> ClosedWindow {
+        ClosedWindow {
+            label: label.into(),
+            bounds: None,
+            visible: true,
+            closed_ms: 0,
+        }
+    }
+
+    #[test]
+    fn stack_is_bounded_and_deduplicated() {
+        let state = RecentlyClosedWindows::default();
+        for i in 0..12 {
+            state.push(closed(&format!("w{}", i)));
+        }
+        state.push(closed("w5"));
+        let stack = state.stack.lock();
+        assert_eq!(stack.len(), MAX_RECENTLY_CLOSED);
+        assert_eq!(stack.front().unwrap().label, "w2");
+        assert_eq!(stack.back().unwrap().label, "w5");
+        assert_eq!(stack.iter().filter(|e| e.label == "w5").count(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+//! Main window recovery.
+//!
+//! When the hang watchdog reloads or recreates the main webview, or the
+//! whole app restarts (`restart_app`), the frontend loses its state even
+//! though the backend still knows most of it. Before tearing the webview
+//! down we store a [`RecoveryPayload`] for "main" as a persistent payload;
+//! the frontend takes it with `take_window_payload("main")` while booting.
+
+use gmplayer_audio_backend::commands::{PlaybackSnapshot, PlayerState};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use tauri_plugin_window_state::{AppHandleExt, WindowExt};
+
+use crate::desktop::window::config::WindowConfig;
+use crate::desktop::window::manager;
+use crate::desktop::window::payload::PayloadCache;
+use crate::desktop::window::recently_closed;
+use crate::desktop::WINDOW_STATE_FLAGS;
+use crate::lyrics::session::LyricSessionState;
+use crate::queue_store::QueueStore;
+
+/// Bump whenever the payload shape changes incompatibly.
+pub const RECOVERY_PAYLOAD_VERSION: u32 = 2;
+pub const RECOVERY_PAYLOAD_KIND: &str = "recovery";
+
+/// Last route reported by the main window.
+static CURRENT_ROUTE: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricOffset {
+    pub track_id: String,
+    pub offset_ms: i64,
+}
+
+/// Identifies the persisted queue without carrying its items; the frontend
+/// re-reads them with `get_queue_page` if the hash matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSnapshot {
+    pub hash: String,
+    pub current_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryPayload {
+    /// Always [`RECOVERY_PAYLOAD_KIND`]; tells it apart from other "main" payloads.
+    pub kind: &'static str,
+    pub version: u32,
+    pub app_version: &'static str,
+    pub route: Option<String>,
+    /// Native player state; the queue itself stays in the player and is
+    /// re-read through `SyncStatus`.
+    pub playback: Option<PlaybackSnapshot>,
+    pub volume: Option<f64>,
+    pub lyric_offset: Option<LyricOffset>,
+    pub queue: Option<QueueSnapshot>,
+}
+
+/// Whether `payload` is a recovery payload written by this exact build.
+/// Snapshots from other versions are dropped instead of half-applied.
+pub fn is_current_recovery_payload(payload: &Value) -> bool {
+    payload.get("kind").and_then(Value::as_str) == Some(RECOVERY_PAYLOAD_KIND)
+        && payload.get("version").and_then(Value::as_u64) == Some(RECOVERY_PAYLOAD_VERSION as u64)
+        && payload.get("appVersion").and_then(Value::as_str) == Some(env!("CARGO_PKG_VERSION"))
+}
+
+/// Snapshot what the backend knows about the frontend session and store it
+/// as a persistent payload for "main".
+pub fn collect_recovery_payload(app: &AppHandle) {
+    let playback = app
+        .try_state::<PlayerState>()
+        .and_then(|state| state.snapshot());
+    let lyric_offset = app
+        .try_state::<LyricSessionState>()
+        .and_then(|state| state.offset())
+        .map(|(track_id, offset_ms)| LyricOffset {
+            track_id,
+            offset_ms,
+        });
+    let queue = app
+        .try_state::<QueueStore>()
+        .and_then(|store| store.meta().ok())
+        .filter(|meta| meta.length > 0)
+        .map(|meta| QueueSnapshot {
+            hash: meta.hash,
+            current_index: meta.current_index,
+        });
+    let payload = RecoveryPayload {
+        kind: RECOVERY_PAYLOAD_KIND,
+        version: RECOVERY_PAYLOAD_VERSION,
+        app_version: env!("CARGO_PKG_VERSION"),
+        route: CURRENT_ROUTE.lock().clone(),
+        volume: playback.as_ref().map(|p| p.volume),
+        playback,
+        lyric_offset,
+        queue,
+    };
+    match serde_json::to_value(&payload) {
+        Ok(value) => PayloadCache::set_persistent("main", value),
+        Err(e) => warn!("Failed to serialize recovery payload: {}", e),
+    }
+}
+
+/// Drop a leftover "main" recovery payload written by another build.
+/// Call after `PayloadCache::init_persistence`.
+pub fn discard_stale_recovery_payload() {
+    let Some(payload) = PayloadCache::peek("main") else {
+        return;
+    };
+    let is_recovery = payload.get("kind").and_then(Value::as_str) == Some(RECOVERY_PAYLOAD_KIND);
+    if is_recovery && !is_current_recovery_payload(&payload) {
+        info!("Discarding recovery payload from another app version");
+        PayloadCache::take("main");
+    }
+}
+
+/// Destroy and recreate the main webview, handing the session over via the
+/// recovery payload. Used by the webview hang watchdog and
+/// `recover_main_window`; the new window is built once the old one is gone.
+pub fn recreate_main_window(app: &AppHandle) -> Result<(), String> {
+    collect_recovery_payload(app);
+    let mut config = WindowConfig::main();
+    config.visible = false;
+    match app.get_webview_window("main") {
+        Some(window) => {
+            let _ = app.save_window_state(WINDOW_STATE_FLAGS);
+            manager::destroy_for_recreate(&window, config, None)
+        }
+        None => {
+            manager::create_window(app, &config)?;
+            let window = app
+                .get_webview_window("main")
+                .ok_or_else(|| "Window 'main' not found".to_string())?;
+            show_recreated_main(app, &window)
+        }
+    }
+}
+
+/// Put a rebuilt main window back where the old one was and show it.
+pub(crate) fn show_recreated_main(app: &AppHandle, window: &WebviewWindow) -> Result<(), String> {
+    if let Err(e) = window.restore_state(WINDOW_STATE_FLAGS) {
+        warn!(
+            "Failed to restore main window state after recreation: {}",
+            e
+        );
+    }
+    window.show().map_err(|e| e.to_string())?;
+    let _ = window.set_focus();
+    let _ = app.emit("main-window-visibility", true);
+    Ok(())
+}
+
+/// Record the main window's current route (pushed periodically by the frontend).
+#[tauri::command]
+pub fn update_current_route(route: String) {
+    *CURRENT_ROUTE.lock() = Some(route);
+}
+
+/// Recreate the main webview without restarting the process.
+#[tauri::command]
+pub fn recover_main_window(app: AppHandle) -> Result<(), String> {
+    recreate_main_window(&app)
+}
+
+/// Restart the app, carrying the session over in the recovery payload.
+#[tauri::command]
+pub fn restart_app(app: AppHandle) {
+    recently_closed::mark_shutting_down(&app);
+    collect_recovery_payload(&app);
+    let _ = app.save_window_state(WINDOW_STATE_FLAGS);
+    app.restart();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn only_payloads_from_this_build_are_current() {
+        let current = json!({
+            "kind": RECOVERY_PAYLOAD_KIND,
+            "version": RECOVERY_PAYLOAD_VERSION,
+            "appVersion": env!("CARGO_PKG_VERSION"),
+        });
+        assert!(is_current_recovery_payload(&current));
+
+        let mut old_app = current.clone();
+        old_app["appVersion"] = json!("0.0.0-old");
+        assert!(!is_current_recovery_payload(&old_app));
+
+        let mut old_shape = current.clone();
+        old_shape["version"] = json!(RECOVERY_PAYLOAD_VERSION + 1);
+        assert!(!is_current_recovery_payload(&old_shape));
+
+        assert!(!is_current_recovery_payload(&json!({ "songs": [] })));
+    }
+}
@@ -0,0 +1,214 @@
+//! Request/response between windows.
+//!
+//! `window_request` emits `window-rpc-request` to the target window with a
+//! fresh id and waits for that window to call `window_respond` with the
+//! same id. A request that is not answered in time fails with `Timeout`.
+//! Hidden and minimized webviews are throttled and may not run their
+//! listeners until shown again, so requests to them fail straight away
+//! with `NotFound` instead of waiting out the timeout.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+use tokio::sync::oneshot;
+
+pub const WINDOW_RPC_REQUEST_EVENT: &str = "window-rpc-request";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum RpcError {
+    /// No such window, or one too throttled to answer.
+    NotFound(String),
+    Timeout(String),
+    /// `window_respond` for a request that already finished or never was.
+    UnknownRequest(String),
+    Emit(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(label) => write!(f, "Window '{}' cannot take requests", label),
+            Self::Timeout(message) | Self::Emit(message) => write!(f, "{}", message),
+            Self::UnknownRequest(id) => write!(f, "No pending request {}", id),
+        }
+    }
+}
+
+/// Payload of `window-rpc-request`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowRpcRequest {
+    pub request_id: u64,
+    /// Label of the window waiting for the answer.
+    pub source: String,
+    pub channel: String,
+    pub payload: Value,
+}
+
+/// Managed table of requests waiting for an answer.
+#[derive(Default)]
+pub struct WindowRpc {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+}
+
+/// Removes its request from the table when the caller stops waiting, be
+/// it answered, timed out or cancelled.
+struct Pending<'a> {
+    rpc: &'a WindowRpc,
+    id: u64,
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        self.rpc.pending.lock().remove(&self.id);
+    }
+}
+
+impl WindowRpc {
+    fn open(&self) -> (Pending<'_>, oneshot::Receiver<Value>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+        (Pending { rpc: self, id }, rx)
+    }
+
+    /// Hand `payload` to the caller waiting on `id`. False when nobody is.
+    fn resolve(&self, id: u64, payload: Value) -> bool {
+        let waiting = self.pending.lock().remove(&id);
+        waiting.is_some_and(|tx| tx.send(payload).is_ok())
+    }
+
+    async fn wait(
+        rx: oneshot::Receiver<Value>,
+        id: u64,
+        timeout: Duration,
+    ) -> Result<Value, RpcError> {
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(payload)) => Ok(payload),
+            Ok(Err(_)) | Err(_) => Err(RpcError::Timeout(format!(
+                "Request {} got no answer within {} ms",
+                id,
+                timeout.as_millis()
+            ))),
+        }
+    }
+
+    #[cfg(test)]
+    fn pending_count(&self) -> usize {
+        self.pending.lock().len()
+    }
+}
+
+/// Visible and not minimized, so its listeners run promptly.
+fn can_answer(window: &WebviewWindow) -> bool {
+    window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(true)
+}
+
+/// Ask `target_label` for something on `channel` and wait for its answer.
+/// `timeout_ms` defaults to 5 s and is capped at 60 s.
+#[tauri::command]
+pub async fn window_request(
+    app: AppHandle,
+    window: WebviewWindow,
+    rpc: State<'_, WindowRpc>,
+    target_label: String,
+    channel: String,
+    payload: Value,
+    timeout_ms: Option<u64>,
+) -> Result<Value, RpcError> {
+    let target = app
+        .get_webview_window(&target_label)
+        .filter(can_answer)
+        .ok_or_else(|| RpcError::NotFound(target_label.clone()))?;
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT)
+        .min(MAX_TIMEOUT);
+    let (pending, rx) = rpc.open();
+    let request = WindowRpcRequest {
+        request_id: pending.id,
+        source: window.label().to_string(),
+        channel,
+        payload,
+    };
+    target
+        .emit_to(target.label(), WINDOW_RPC_REQUEST_EVENT, request)
+        .map_err(|e| RpcError::Emit(e.to_string()))?;
+    WindowRpc::wait(rx, pending.id, timeout).await
+}
+
+/// Answer the request `request_id` with `payload`.
+#[tauri::command]
+pub fn window_respond(
+    rpc: State<'_, WindowRpc>,
+    request_id: u64,
+    payload: Value,
+) -> Result<(), RpcError> {
+    if rpc.resolve(request_id, payload) {
+        Ok(())
+    } else {
+        Err(RpcError::UnknownRequest(request_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn concurrent_requests_get_their_own_answers() {
+        let rpc = Arc::new(WindowRpc::default());
+        let handles: Vec<_> = (0..100)
+            .map(|n| {
+                let rpc = rpc.clone();
+                tauri::async_runtime::spawn(async move {
+                    let (pending, rx) = rpc.open();
+                    let id = pending.id;
+                    // Answer from another task, as a window would.
+                    let responder = rpc.clone();
+                    tauri::async_runtime::spawn(async move {
+                        assert!(responder.resolve(id, json!(n)));
+                    });
+                    let answer = WindowRpc::wait(rx, id, Duration::from_secs(5)).await;
+                    drop(pending);
+                    (id, answer.unwrap())
+                })
+            })
+            .collect();
+        let mut ids = HashSet::new();
+        for (n, handle) in handles.into_iter().enumerate() {
+            let (id, answer) = tauri::async_runtime::block_on(handle).unwrap();
+            assert!(ids.insert(id), "request id {} reused", id);
+            assert_eq!(answer, json!(n));
+        }
+        assert_eq!(rpc.pending_count(), 0);
+    }
+
+    #[test]
+    fn unanswered_requests_time_out_and_are_dropped() {
+        let rpc = WindowRpc::default();
+        let result = tauri::async_runtime::block_on(async {
+            let (pending, rx) = rpc.open();
+            WindowRpc::wait(rx, pending.id, Duration::from_millis(10)).await
+        });
+        assert!(matches!(result, Err(RpcError::Timeout(_))));
+        assert_eq!(rpc.pending_count(), 0);
+        assert!(!rpc.resolve(1, Value::Null));
+    }
+}
@@ -0,0 +1,170 @@
+//! Reopen the auxiliary windows that were open when the app last exited.
+//!
+//! The set of open preset windows is mirrored to `session.json` in the app
+//! data dir on every create, show, hide and destroy. Minimizing keeps a
+//! window in the set, and destroys during shutdown are not recorded, so the
+//! file still lists what was on screen at exit. On the next
+//! launch `setup` recreates those windows hidden and lets `first_paint`
+//! reveal them, unless `window.restoreSession` is off.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::desktop::window::{first_paint, manager, presets, recently_closed};
+use crate::settings::{write_atomic, SettingsStore};
+
+const SESSION_FILE: &str = "session.json";
+/// Restored by their own startup logic, or meaningless on their own.
+const SKIPPED_LABELS: [&str; 4] = ["main", "tray-popup", "osd", "desktop-lyrics-controls"];
+
+/// Managed record of the open auxiliary windows.
+pub struct WindowSession {
+    path: Option<PathBuf>,
+    /// Labels open at the end of the previous run.
+    previous: Vec<String>,
+    open: Mutex<BTreeSet<String>>,
+}
+
+impl WindowSession {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(SESSION_FILE)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, window session is not saved: {}",
+                    e
+                );
+                None
+            }
+        };
+        let previous = path
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            previous,
+            open: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Apply `f` to the open set and write it out if it changed.
+    fn update(&self, f: impl FnOnce(&mut BTreeSet<String>) -> bool) {
+        let mut open = self.open.lock();
+        if !f(&mut open) {
+            return;
+        }
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        let written = serde_json::to_vec(&*open)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_atomic(path, &json).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            warn!("Failed to save window session: {}", e);
+        }
+    }
+}
+
+fn is_tracked(label: &str) -> bool {
+    !SKIPPED_LABELS.contains(&label)
+}
+
+/// Record a window that was opened; `create_window` calls this for every
+/// window except pre-warmed ones, which are not open yet.
+pub(crate) fn note_opened(app: &AppHandle, label: &str) {
+    if !is_tracked(label) {
+        return;
+    }
+    if let Some(session) = app.try_state::<WindowSession>() {
+        session.update(|open| open.insert(label.to_string()));
+    }
+}
+
+/// Run-loop hook for `WindowEvent::Destroyed`.
+pub(crate) fn note_destroyed(app: &AppHandle, label: &str) {
+    if recently_closed::is_shutting_down(app) {
+        return;
+    }
+    if let Some(session) = app.try_state::<WindowSession>() {
+        session.update(|open| open.remove(label));
+    }
+}
+
+/// Follow a window being shown or hidden, so a window the user hid is not
+/// restored. [`manager::emit_visibility`] calls this; a minimized window
+/// still counts as open.
+pub(crate) fn note_visibility(app: &AppHandle, label: &str, visible: bool) {
+    if visible {
+        note_opened(app, label);
+        return;
+    }
+    let minimized = app
+        .get_webview_window(label)
+        .is_some_and(|window| window.is_minimized().unwrap_or(false));
+    if !minimized {
+        note_destroyed(app, label);
+    }
+}
+
+/// Labels from the previous run that can be reopened now.
+fn restorable(previous: &[String], is_preset: impl Fn(&str) -> bool) -> Vec<&str> {
+    previous
+        .iter()
+        .map(String::as_str)
+        .filter(|label| is_tracked(label) && is_preset(label))
+        .collect()
+}
+
+/// Recreate the windows open at the last exit. Call from `setup` after main.
+pub fn restore(app: &AppHandle) {
+    let enabled = app
+        .try_state::<SettingsStore>()
+        .map_or(true, |store| store.read(|s| s.window.restore_session));
+    let Some(session) = app.try_state::<WindowSession>() else {
+        return;
+    };
+    if !enabled {
+        return;
+    }
+    for label in restorable(&session.previous, |label| presets::lookup(label).is_some()) {
+        if app.get_webview_window(label).is_some() {
+            continue;
+        }
+        let Some(mut config) = presets::lookup(label) else {
+            continue;
+        };
+        info!("Restoring window '{}' from the last session", label);
+        // Hidden until painted, without taking focus from main.
+        config.visible = false;
+        if let Err(e) = manager::create_window(app, &config) {
+            warn!("Failed to restore window '{}': {}", label, e);
+            continue;
+        }
+        if let Some(window) = app.get_webview_window(label) {
+            if !first_paint::defer_show(&window, false) {
+                first_paint::begin(&window, true, false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_tracked_presets_are_restored() {
+        let previous: Vec<String> = ["desktop-lyrics", "main", "tray-popup", "gone", "settings"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let restored = restorable(&previous, |label| label != "gone");
+        assert_eq!(restored, ["desktop-lyrics", "settings"]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Key/value state shared by every window.
+//!
+//! Any window can write a key with `state_set`; the new value goes out to
+//! all windows as `shared-state-changed`. Each key carries a revision that
+//! goes up on every write (and on delete), so a window that subscribed late
+//! can compare what it holds against `state_get` or `state_snapshot` and
+//! tell whether it missed an update. Keys written with `persistent` are
+//! mirrored to `shared-state.json` in the app data dir and come back on the
+//! next launch with their revision.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use log::warn;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::settings::write_atomic;
+
+pub const SHARED_STATE_CHANGED_EVENT: &str = "shared-state-changed";
+const STATE_FILE: &str = "shared-state.json";
+
+/// A key's value and the revision it was written at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SharedValue {
+    pub value: Value,
+    pub revision: u64,
+}
+
+/// Payload of `shared-state-changed`. `value` is null when the key was
+/// deleted.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SharedStateChanged {
+    pub key: String,
+    pub value: Value,
+    pub revision: u64,
+}
+
+struct Entry {
+    /// None once deleted; the entry stays so the revision keeps counting.
+    value: Option<Value>,
+    revision: u64,
+    persistent: bool,
+}
+
+/// Managed store behind the `state_*` commands.
+pub struct SharedState {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl SharedState {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(STATE_FILE)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, shared state is not saved: {}",
+                    e
+                );
+                None
+            }
+        };
+        let saved: HashMap<String, SharedValue> = path
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let entries = saved
+            .into_iter()
+            .map(|(key, saved)| {
+                let entry = Entry {
+                    value: Some(saved.value),
+                    revision: saved.revision,
+                    persistent: true,
+                };
+                (key, entry)
+            })
+            .collect();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Write `value` under `key` and return its new revision. `persistent`
+    /// of None keeps whatever the key was written with before.
+    pub fn set(&self, key: &str, value: Value, persistent: Option<bool>) -> u64 {
+        let mut entries = self.entries.lock();
+        let entry = entries.entry(key.to_string()).or_insert(Entry {
+            value: None,
+            revision: 0,
+            persistent: false,
+        });
+        let was_persistent = entry.persistent;
+        entry.value = Some(value);
+        entry.revision += 1;
+        entry.persistent = persistent.unwrap_or(was_persistent);
+        let revision = entry.revision;
+        if was_persistent || entry.persistent {
+            self.persist(&entries);
+        }
+        revision
+    }
+
+    pub fn get(&self, key: &str) -> Option<SharedValue> {
+        let entries = self.entries.lock();
+        let entry = entries.get(key)?;
+        Some(SharedValue {
+            value: entry.value.clone()?,
+            revision: entry.revision,
+        })
+    }
+
+    /// Remove `key`. Returns the revision of the deletion, or None when
+    /// there was nothing to delete.
+    pub fn delete(&self, key: &str) -> Option<u64> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(key)?;
+        entry.value.take()?;
+        entry.revision += 1;
+        let revision = entry.revision;
+        if std::mem::take(&mut entry.persistent) {
+            self.persist(&entries);
+        }
+        Some(revision)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, SharedValue> {
+        self.entries
+            .lock()
+            .iter()
+            .filter_map(|(key, entry)| {
+                let value = entry.value.clone()?;
+                Some((
+                    key.clone(),
+                    SharedValue {
+                        value,
+                        revision: entry.revision,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn persist(&self, entries: &HashMap<String, Entry>) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        let persistent: HashMap<&str, SharedValue> = entries
+            .iter()
+            .filter(|(_, entry)| entry.persistent)
+            .filter_map(|(key, entry)| {
+                let value = entry.value.clone()?;
+                Some((
+                    key.as_str(),
+                    SharedValue {
+                        value,
+                        revision: entry.revision,
+                    },
+                ))
+            })
+            .collect();
+        let written = serde_json::to_vec(&persistent)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_atomic(path, &json).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            warn!("Failed to save shared state: {}", e);
+        }
+    }
+}
+
+fn broadcast(app: &AppHandle, key: String, value: Value, revision: u64) {
+    let _ = app.emit(
+        SHARED_STATE_CHANGED_EVENT,
+        SharedStateChanged {
+            key,
+            value,
+            revision,
+        },
+    );
+}
+
+/// Write a shared key and tell every window. `persistent` keeps the key
+/// across restarts; leave it out to keep the key's current setting.
+/// Resolves to the key's new revision.
+#[tauri::command]
+pub fn state_set(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    key: String,
+    value: Value,
+    persistent: Option<bool>,
+) -> u64 {
+    let revision = state.set(&key, value.clone(), persistent);
+    broadcast(&app, key, value, revision);
+    revision
+}
+
+#[tauri::command]
+pub fn state_get(state: State<'_, SharedState>, key: String) -> Option<SharedValue> {
+    state.get(&key)
+}
+
+/// Delete a shared key. Windows are told with a null value.
+#[tauri::command]
+pub fn state_delete(app: AppHandle, state: State<'_, SharedState>, key: String) -> bool {
+    match state.delete(&key) {
+        Some(revision) => {
+            broadcast(&app, key, Value::Null, revision);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Every shared key with its value and revision.
+#[tauri::command]
+pub fn state_snapshot(state: State<'_, SharedState>) -> BTreeMap<String, SharedValue> {
+    state.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn revisions_survive_deletes_and_persistent_keys_are_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE);
+        let state = SharedState {
+            path: Some(path.clone()),
+            entries: Mutex::new(HashMap::new()),
+        };
+        assert_eq!(state.set("volume", json!(0.5), Some(true)), 1);
+        assert_eq!(state.set("volume", json!(0.7), None), 2);
+        assert_eq!(state.set("scratch", json!("x"), None), 1);
+        assert_eq!(state.delete("scratch"), Some(2));
+        assert_eq!(state.delete("scratch"), None);
+        assert_eq!(state.get("scratch"), None);
+        assert_eq!(state.set("scratch", json!("y"), None), 3);
+        assert_eq!(state.snapshot().len(), 2);
+
+        let saved: HashMap<String, SharedValue> =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(
+            saved,
+            HashMap::from([(
+                "volume".to_string(),
+                SharedValue {
+                    value: json!(0.7),
+                    revision: 2
+                }
+            )])
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Playback progress on the main window's taskbar button.
+//!
+//! Windows draws it over the taskbar button and macOS on the Dock icon. On
+//! Linux, docks that follow the Unity launcher API (Plank, Dash to Dock,
+//! KDE's task manager) draw it on the launcher entry of our `.desktop`
+//! file, which Tauri names after the product. The frontend calls this once a second; the bar moves in whole percent at
+//! most once a second, and state changes go through immediately.
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskbarProgressState {
+    /// No bar.
+    None,
+    Normal,
+    Paused,
+    Indeterminate,
+}
+
+impl From<TaskbarProgressState> for ProgressBarStatus {
+    fn from(state: TaskbarProgressState) -> Self {
+        match state {
+            TaskbarProgressState::None => ProgressBarStatus::None,
+            TaskbarProgressState::Normal => ProgressBarStatus::Normal,
+            TaskbarProgressState::Paused => ProgressBarStatus::Paused,
+            TaskbarProgressState::Indeterminate => ProgressBarStatus::Indeterminate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shown {
+    state: TaskbarProgressState,
+    percent: u64,
+    at: Instant,
+}
+
+/// What the taskbar shows now; `None` until first set or while main is hidden.
+static SHOWN: Mutex<Option<Shown>> = Mutex::new(None);
+
+fn percent(value: f64) -> u64 {
+    if value.is_finite() {
+        (value.clamp(0.0, 1.0) * 100.0).round() as u64
+    } else {
+        0
+    }
+}
+
+/// Whether `state` at `percent` should replace what is shown at `now`.
+fn should_apply(
+    shown: Option<Shown>,
+    state: TaskbarProgressState,
+    percent: u64,
+    now: Instant,
+) -> bool {
+    let Some(shown) = shown else {
+        return true;
+    };
+    if shown.state != state {
+        return true;
+    }
+    shown.percent != percent && now.duration_since(shown.at) >= MIN_INTERVAL
+}
+
+/// Show playback progress (`value` from 0.0 to 1.0) on the main window's
+/// taskbar button. A no-op while the main window is hidden to the tray.
+#[tauri::command]
+pub fn set_taskbar_progress(
+    app: AppHandle,
+    state: TaskbarProgressState,
+    value: f64,
+) -> Result<(), String> {
+    let mut shown = SHOWN.lock();
+    let Some(window) = app
+        .get_webview_window("main")
+        .filter(|window| window.is_visible().unwrap_or(false))
+    else {
+        // The taskbar button goes with the window; start over once it is back.
+        *shown = None;
+        return Ok(());
+    };
+    let percent = percent(value);
+    let now = Instant::now();
+    if !should_apply(*shown, state, percent, now) {
+        return Ok(());
+    }
+    window
+        .set_progress_bar(ProgressBarState {
+            status: Some(state.into()),
+            progress: Some(percent),
+        })
+        .map_err(|e| e.to_string())?;
+    *shown = Some(Shown {
+        state,
+        percent,
+        at: now,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_moves_at_most_once_a_second_but_state_changes_apply_at_once() {
+        let start = Instant::now();
+        let shown = Some(Shown {
+            state: TaskbarProgressState::Normal,
+            percent: 40,
+            at: start,
+        });
+        let soon = start + Duration::from_millis(300);
+        let later = start + MIN_INTERVAL;
+        assert!(!should_apply(shown, TaskbarProgressState::Normal, 41, soon));
+        assert!(should_apply(shown, TaskbarProgressState::Normal, 41, later));
+        assert!(!should_apply(
+            shown,
+            TaskbarProgressState::Normal,
+            40,
+            later
+        ));
+        assert!(should_apply(shown, TaskbarProgressState::Paused, 40, soon));
+        assert!(should_apply(None, TaskbarProgressState::Normal, 40, soon));
+        assert_eq!(
+            (percent(0.404), percent(2.0), percent(f64::NAN)),
+            (40, 100, 0)
+        );
+    }
+}
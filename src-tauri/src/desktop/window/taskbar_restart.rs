@@ -0,0 +1,146 @@
+//! Rebuild the tray icon after Explorer restarts (Windows).
+//!
+//! A fresh taskbar broadcasts the registered `TaskbarCreated` message to
+//! every top-level window. Message-only windows never receive broadcasts,
+//! so a thread here owns an invisible top-level window that does nothing
+//! but wait for it. tray-icon re-adds its own icon on the same message, but
+//! an icon it added while the notification area was still coming up stays
+//! lost; rebuilding covers that and restores the badge, tooltip and menu.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use log::{info, warn};
+use tauri::AppHandle;
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    ChangeWindowMessageFilterEx, CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW,
+    RegisterClassW, RegisterWindowMessageW, TranslateMessage, MSG, MSGFLT_ALLOW, WINDOW_EX_STYLE,
+    WINDOW_STYLE, WNDCLASSW,
+};
+
+use super::tray;
+
+/// Explorer broadcasts before its notification area takes icons reliably.
+const SETTLE_DELAY: Duration = Duration::from_millis(1500);
+const CLASS_NAME: PCWSTR = w!("GMPlayerTaskbarRestartWatcher");
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static TASKBAR_CREATED: OnceLock<u32> = OnceLock::new();
+/// A rebuild is already waiting out [`SETTLE_DELAY`].
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if TASKBAR_CREATED.get() == Some(&msg) {
+        if let Some(app) = APP.get() {
+            schedule_rebuild(app);
+        }
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Rebuild once Explorer has settled; broadcasts in quick succession
+/// collapse into one rebuild.
+fn schedule_rebuild(app: &AppHandle) {
+    if PENDING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("tray-rebuild".into())
+        .spawn(move || {
+            std::thread::sleep(SETTLE_DELAY);
+            PENDING.store(false, Ordering::Release);
+            info!("Taskbar restarted, rebuilding the tray icon");
+            let handle = app.clone();
+            let queued = app.run_on_main_thread(move || {
+                if let Err(e) = tray::rebuild_tray(&handle) {
+                    warn!("Failed to rebuild tray icon: {}", e);
+                }
+            });
+            if let Err(e) = queued {
+                warn!("Failed to queue tray rebuild: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        PENDING.store(false, Ordering::Release);
+        warn!("Failed to spawn tray rebuild: {}", e);
+    }
+}
+
+fn watch() -> Result<(), String> {
+    // SAFETY: the class and window live for the rest of the process on this
+    // thread; the names are static, NUL-terminated wide strings.
+    unsafe {
+        let message = RegisterWindowMessageW(w!("TaskbarCreated"));
+        if message == 0 {
+            return Err("RegisterWindowMessageW failed".into());
+        }
+        let _ = TASKBAR_CREATED.set(message);
+
+        let instance: HINSTANCE = GetModuleHandleW(PCWSTR::null())
+            .map_err(|e| e.to_string())?
+            .into();
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance,
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 {
+            return Err("RegisterClassW failed".into());
+        }
+        // Top-level but never shown: a message-only window would miss the
+        // broadcast.
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            CLASS_NAME,
+            CLASS_NAME,
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance),
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+        // Let the broadcast through UIPI when running elevated.
+        let _ = ChangeWindowMessageFilterEx(hwnd, message, MSGFLT_ALLOW, None);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    Ok(())
+}
+
+/// Start listening for `TaskbarCreated`. Call once, after the tray is set up.
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("taskbar-restart".into())
+        .spawn(|| {
+            if let Err(e) = watch() {
+                warn!("Taskbar restart watcher stopped: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn taskbar restart watcher: {}", e);
+    }
+}
@@ -0,0 +1,141 @@
+//! Decorum overlay titlebars and switching them at runtime.
+//!
+//! With `use_overlay_titlebar`, decorum keeps the native caption buttons
+//! over the webview: traffic lights on macOS, and on Windows buttons that
+//! still open the Windows 11 snap-layout flyout from maximize. Linux has no
+//! overlay titlebar and always draws the DOM one. If decorum fails, the
+//! window keeps going with the DOM titlebar.
+//!
+//! Switching to `hidden` removes whatever decorum put there: the caption
+//! buttons it injects into the page on Windows, and the traffic lights on
+//! macOS. The DOM `WindowControls` show whenever a window has neither
+//! (`titlebar-style-changed` tells them when that changes), so a hidden
+//! window always keeps a way to be closed.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use log::warn;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use tauri_plugin_decorum::WebviewWindowExt;
+
+use crate::desktop::window::config::WindowConfig;
+
+pub const TITLEBAR_STYLE_CHANGED_EVENT: &str = "titlebar-style-changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TitlebarStyle {
+    /// Native caption buttons drawn over the webview by decorum.
+    Overlay,
+    /// No native titlebar; the frontend draws its own.
+    #[default]
+    Hidden,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TitlebarStyleChanged {
+    pub label: String,
+    pub style: TitlebarStyle,
+}
+
+static STYLES: LazyLock<Mutex<HashMap<String, TitlebarStyle>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn create_overlay(window: &WebviewWindow) -> Result<(), String> {
+    window
+        .create_overlay_titlebar()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn create_overlay(_window: &WebviewWindow) -> Result<(), String> {
+    Err("overlay titlebars are not supported on this platform".into())
+}
+
+/// Marker decorum puts on the caption buttons it injects on Windows.
+#[cfg(target_os = "windows")]
+const DECORUM_CONTROLS_SELECTOR: &str = "[data-tauri-decorum-tb]";
+
+/// Drop the overlay titlebar and any native one.
+fn remove_overlay(window: &WebviewWindow) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    window
+        .eval(&format!(
+            "document.querySelectorAll('{}').forEach((el) => el.remove());",
+            DECORUM_CONTROLS_SELECTOR
+        ))
+        .map_err(|e| e.to_string())?;
+    window.set_decorations(false).map_err(|e| e.to_string())
+}
+
+/// Apply the preset's titlebar to a new window. Never fails: a window
+/// decorum cannot handle keeps the DOM titlebar.
+pub(crate) fn apply(window: &WebviewWindow, config: &WindowConfig) {
+    let mut style = TitlebarStyle::Hidden;
+    if config.use_overlay_titlebar && cfg!(any(target_os = "macos", target_os = "windows")) {
+        match create_overlay(window) {
+            Ok(()) => style = TitlebarStyle::Overlay,
+            Err(e) => warn!(
+                "Overlay titlebar failed for '{}', using the DOM titlebar: {}",
+                window.label(),
+                e
+            ),
+        }
+    }
+    STYLES.lock().insert(window.label().to_string(), style);
+}
+
+/// Current titlebar style of a window; `hidden` for unknown labels.
+#[tauri::command]
+pub fn get_titlebar_style(label: String) -> TitlebarStyle {
+    STYLES.lock().get(&label).copied().unwrap_or_default()
+}
+
+/// Switch a window between the decorum overlay titlebar and none at all.
+#[tauri::command]
+pub async fn set_titlebar_style(
+    app: AppHandle,
+    label: String,
+    style: TitlebarStyle,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    match style {
+        TitlebarStyle::Overlay => {
+            // The traffic lights went with the decorations when hidden.
+            #[cfg(target_os = "macos")]
+            window.set_decorations(true).map_err(|e| e.to_string())?;
+            create_overlay(&window)?
+        }
+        TitlebarStyle::Hidden => remove_overlay(&window)?,
+    }
+    STYLES.lock().insert(label.clone(), style);
+    let _ = app.emit(
+        TITLEBAR_STYLE_CHANGED_EVENT,
+        TitlebarStyleChanged { label, style },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_windows_report_the_dom_titlebar() {
+        assert_eq!(get_titlebar_style("nope".into()), TitlebarStyle::Hidden);
+        assert_eq!(
+            serde_json::from_str::<TitlebarStyle>("\"overlay\"").unwrap(),
+            TitlebarStyle::Overlay
+        );
+    }
+}
@@ -1,21 +1,139 @@
+use image::{Rgba, RgbaImage};
 use log::{info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::image::Image;
-use tauri::tray::{MouseButton, TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, Rect, WebviewWindow};
+use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{
+    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, Rect, State, WebviewWindow, Wry,
+};
 
+use crate::desktop::i18n::{self, Text};
 use crate::desktop::window::config::{WindowConfig, TRAY_POPUP_BASE_HEIGHT, TRAY_POPUP_WIDTH};
-use crate::desktop::window::manager as wm;
+use crate::desktop::window::manager::{self as wm, ScreenRect};
+use crate::desktop::window::presets;
+use crate::settings::tray::{TrayClickAction, TrayConfig, TrayMenuMode};
+use crate::settings::SettingsStore;
 
 const TRAY_ID: &str = "main";
+const LYRICS_LABEL: &str = "desktop-lyrics";
+
+/// Sent to the main window by the native tray menu's playback items.
+pub const TRAY_MENU_PLAY_PAUSE_EVENT: &str = "tray-menu-play-pause";
+pub const TRAY_MENU_NEXT_TRACK_EVENT: &str = "tray-menu-next-track";
+pub const TRAY_MENU_PREV_TRACK_EVENT: &str = "tray-menu-prev-track";
+
+// Menu item ids. Tray menu handlers see every menu event in the app, so
+// these are prefixed to stay clear of other menus.
+const MENU_PLAY_PAUSE: &str = "tray-menu-play-pause";
+const MENU_PREVIOUS: &str = "tray-menu-previous";
+const MENU_NEXT: &str = "tray-menu-next";
+const MENU_SHOW_LYRICS: &str = "tray-menu-show-lyrics";
+const MENU_SHOW_MAIN: &str = "tray-menu-show-main";
+const MENU_QUIT: &str = "tray-menu-quit";
+const MENU_FORCE_QUIT: &str = "tray-menu-force-quit";
 const TRAY_POPUP_MIN_WIDTH: f64 = 220.0;
 const TRAY_POPUP_MAX_WIDTH: f64 = 420.0;
 const TRAY_POPUP_MIN_HEIGHT: f64 = 260.0;
 const TRAY_POPUP_MAX_HEIGHT: f64 = 560.0;
 const TRAY_POPUP_GAP: f64 = 8.0;
+/// Focus loss this soon after showing is the show racing the tray click,
+/// not the user leaving.
+const TRAY_POPUP_FOCUS_GRACE: Duration = Duration::from_millis(200);
+/// A tray click this soon after a dismissal is the release of the press
+/// that dismissed it, and does not reopen the popup.
+const TRAY_POPUP_REOPEN_GUARD: Duration = Duration::from_millis(300);
+/// Outside-click polling while the popup is open. A click shorter than
+/// this can slip through; focus loss still catches most of those.
+const TRAY_POPUP_WATCH_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the watchdog waits for a deferred first show.
+const TRAY_POPUP_SHOW_TIMEOUT: Duration = Duration::from_secs(2);
+/// Progress-only refreshes closer together than this are dropped; rewriting
+/// the tooltip while it is open makes it flicker.
+const TOOLTIP_MIN_INTERVAL: Duration = Duration::from_secs(5);
+/// `NOTIFYICONDATAW::szTip` holds 128 UTF-16 units including the NUL.
+#[cfg(windows)]
+const TOOLTIP_MAX_UNITS: usize = 127;
+#[cfg(not(windows))]
+const TOOLTIP_MAX_UNITS: usize = 255;
 
 static TRAY_POPUP_SIZE: OnceLock<Mutex<PopupSize>> = OnceLock::new();
 static TRAY_POPUP_ANCHOR: OnceLock<Mutex<Option<PhysicalRect>>> = OnceLock::new();
+static TRAY_TOOLTIP: OnceLock<Mutex<TooltipState>> = OnceLock::new();
+/// Composited tray icons per playback state, built on first use.
+static TRAY_ICONS: OnceLock<Mutex<HashMap<TrayPlaybackState, Image<'static>>>> = OnceLock::new();
+/// Last state passed to `set_tray_playback_state`, so a rebuilt icon keeps
+/// its badge.
+static TRAY_PLAYBACK: Mutex<TrayPlaybackState> = Mutex::new(TrayPlaybackState::Stopped);
+/// Items of the native menu that change after it is built, while there is one.
+static TRAY_MENU: Mutex<Option<TrayMenuItems>> = Mutex::new(None);
+static TRAY_POPUP_SHOWN_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static TRAY_POPUP_DISMISSED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static TRAY_POPUP_WATCHING: AtomicBool = AtomicBool::new(false);
+/// Set by the boss key: the tooltip shows only the app name while updates
+/// keep being recorded for when it is lifted.
+static TRAY_DISGUISED: AtomicBool = AtomicBool::new(false);
+/// Now-playing item text is cut to this many UTF-16 units.
+const MENU_NOW_PLAYING_MAX_UNITS: usize = 64;
+
+/// Badge diameter as a share of the icon's shorter side.
+const BADGE_SCALE: f32 = 0.6;
+const BADGE_FILL: Rgba<u8> = Rgba([32, 32, 32, 255]);
+const BADGE_GLYPH: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// What the tray icon badge shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayPlaybackState {
+    Playing,
+    Paused,
+    /// The plain app icon.
+    Stopped,
+}
+
+/// The last `update_tray_now_playing` payload. Sent with
+/// `tray-popup-opened`, and used to fill a rebuilt tray.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayNowPlaying {
+    pub title: String,
+    pub artist: String,
+    pub album: Option<String>,
+    pub position_ms: u64,
+    pub duration_ms: u64,
+    pub next_title: Option<String>,
+    pub playing: bool,
+}
+
+impl TrayNowPlaying {
+    /// Equal apart from the position: a change bypasses the throttle.
+    fn same_track(&self, other: &Self) -> bool {
+        Self {
+            position_ms: other.position_ms,
+            ..self.clone()
+        } == *other
+    }
+}
+
+struct TrayMenuItems {
+    now_playing: MenuItem<Wry>,
+    lyrics: CheckMenuItem<Wry>,
+}
+
+#[derive(Debug, Default)]
+struct TooltipState {
+    now_playing: Option<TrayNowPlaying>,
+    last_update: Option<Instant>,
+    /// Set once the shell refused a multi-line tooltip.
+    single_line_only: bool,
+    /// Last tooltip shown, restored when the icon is rebuilt.
+    text: Option<String>,
+}
 
 #[derive(Debug, Clone, Copy)]
 struct PopupSize {
@@ -48,52 +166,573 @@ enum ScreenEdge {
     Right,
 }
 
-/// Set up the system tray icon (no native menu — right-click shows a WebviewWindow popup).
+/// Set up the system tray icon. In popup mode right-click shows a
+/// WebviewWindow popup; in native mode the icon carries a native menu.
+/// The badge and tooltip from before a rebuild are kept.
 pub fn setup_tray(app: &AppHandle) -> Result<(), String> {
-    // Load tray icon from bundled icons
-    let icon = app.default_window_icon().cloned().unwrap_or_else(|| {
-        warn!("No default window icon found, using empty icon");
+    let playback = TRAY_PLAYBACK
+        .lock()
+        .map(|state| *state)
+        .unwrap_or(TrayPlaybackState::Stopped);
+    let icon = playback_icon(app, playback).unwrap_or_else(|e| {
+        warn!("No tray icon ({}), using empty icon", e);
         Image::new(&[], 0, 0)
     });
+    let tooltip = tooltip_state()
+        .lock()
+        .ok()
+        .and_then(|state| state.text.clone())
+        .filter(|_| !TRAY_DISGUISED.load(Ordering::Acquire))
+        .unwrap_or_else(|| i18n::tr(Text::TrayTooltip).to_string());
+    let menu_mode = tray_config(app).menu_mode;
 
-    TrayIconBuilder::with_id(TRAY_ID)
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
-        .tooltip("GMPlayer")
+        // Template icons follow the light/dark menu bar on macOS.
+        .icon_as_template(cfg!(target_os = "macos"))
+        .tooltip(tooltip);
+    if menu_mode == TrayMenuMode::Native {
+        let menu = build_menu(app)?;
+        builder = builder
+            .menu(&menu)
+            // Left click keeps its configured action; the menu is on the right.
+            .show_menu_on_left_click(false)
+            .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    } else if let Ok(mut items) = TRAY_MENU.lock() {
+        *items = None;
+    }
+
+    builder
         .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click { button, rect, .. } = event {
-                let app = tray.app_handle();
-                match button {
+            let app = tray.app_handle();
+            match event {
+                // Click fires on press and release; act once, on release.
+                TrayIconEvent::Click {
+                    button,
+                    button_state: MouseButtonState::Up,
+                    rect,
+                    ..
+                } => match button {
                     MouseButton::Left => {
-                        // Always show main window (never toggle)
-                        if let Err(e) = wm::show_window(app, "main") {
-                            warn!("Failed to show main window: {}", e);
-                        }
+                        run_click_action(app, tray_config(app).left_click_action, &rect);
                     }
-                    MouseButton::Right => {
+                    // In native mode the OS opens the menu itself.
+                    MouseButton::Right if tray_config(app).menu_mode == TrayMenuMode::Popup => {
                         if let Err(e) = show_tray_popup(app, &rect) {
                             warn!("Failed to show tray popup: {}", e);
                         }
                     }
                     _ => {}
-                }
+                },
+                TrayIconEvent::DoubleClick {
+                    button: MouseButton::Left,
+                    rect,
+                    ..
+                } => run_click_action(app, tray_config(app).double_click_action, &rect),
+                _ => {}
             }
         })
         .build(app)
         .map_err(|e| e.to_string())?;
 
-    info!("System tray initialized (popup mode)");
+    info!("System tray initialized ({:?} menu)", menu_mode);
     Ok(())
 }
 
+fn build_menu(app: &AppHandle) -> Result<Menu<Wry>, String> {
+    let cached = tooltip_state()
+        .lock()
+        .ok()
+        .and_then(|state| state.now_playing.clone());
+    let now_playing = MenuItem::new(
+        app,
+        menu_now_playing_text(cached.as_ref()),
+        false,
+        None::<&str>,
+    )
+    .map_err(|e| e.to_string())?;
+    let lyrics_shown = wm::is_window_visible(app, LYRICS_LABEL).unwrap_or(false);
+    let lyrics = CheckMenuItem::with_id(
+        app,
+        MENU_SHOW_LYRICS,
+        i18n::tr(Text::TrayMenuShowLyrics),
+        true,
+        lyrics_shown,
+        None::<&str>,
+    )
+    .map_err(|e| e.to_string())?;
+    let menu = MenuBuilder::new(app)
+        .item(&now_playing)
+        .separator()
+        .text(MENU_PLAY_PAUSE, i18n::tr(Text::TrayMenuPlayPause))
+        .text(MENU_PREVIOUS, i18n::tr(Text::TrayMenuPrevious))
+        .text(MENU_NEXT, i18n::tr(Text::TrayMenuNext))
+        .separator()
+        .item(&lyrics)
+        .text(MENU_SHOW_MAIN, i18n::tr(Text::TrayMenuShowMain))
+        .separator()
+        .text(MENU_QUIT, i18n::tr(Text::TrayMenuQuit))
+        .text(MENU_FORCE_QUIT, i18n::tr(Text::TrayMenuForceQuit))
+        .build()
+        .map_err(|e| e.to_string())?;
+    if let Ok(mut items) = TRAY_MENU.lock() {
+        *items = Some(TrayMenuItems {
+            now_playing,
+            lyrics,
+        });
+    }
+    Ok(menu)
+}
+
+fn menu_now_playing_text(now_playing: Option<&TrayNowPlaying>) -> String {
+    match now_playing {
+        Some(now_playing) => truncate_tooltip(
+            &heading(&now_playing.title, &now_playing.artist),
+            MENU_NOW_PLAYING_MAX_UNITS,
+        ),
+        None => i18n::tr(Text::TrayMenuNotPlaying).to_string(),
+    }
+}
+
+/// Retitle the native menu's "Now Playing" item, if there is a native menu.
+fn set_menu_now_playing(now_playing: Option<&TrayNowPlaying>) {
+    if let Ok(items) = TRAY_MENU.lock() {
+        if let Some(items) = items.as_ref() {
+            let _ = items
+                .now_playing
+                .set_text(menu_now_playing_text(now_playing));
+        }
+    }
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    let result = match id {
+        MENU_PLAY_PAUSE => app
+            .emit_to("main", TRAY_MENU_PLAY_PAUSE_EVENT, ())
+            .map_err(|e| e.to_string()),
+        MENU_PREVIOUS => app
+            .emit_to("main", TRAY_MENU_PREV_TRACK_EVENT, ())
+            .map_err(|e| e.to_string()),
+        MENU_NEXT => app
+            .emit_to("main", TRAY_MENU_NEXT_TRACK_EVENT, ())
+            .map_err(|e| e.to_string()),
+        MENU_SHOW_LYRICS => toggle_desktop_lyrics(app),
+        MENU_SHOW_MAIN => wm::show_window(app, "main"),
+        MENU_QUIT => {
+            crate::shutdown::quit(app, false);
+            Ok(())
+        }
+        // Skips waiting on windows, for when one of them is stuck.
+        MENU_FORCE_QUIT => {
+            crate::shutdown::quit(app, true);
+            Ok(())
+        }
+        _ => return,
+    };
+    if let Err(e) = result {
+        warn!("Tray menu item '{}' failed: {}", id, e);
+    }
+}
+
+fn toggle_desktop_lyrics(app: &AppHandle) -> Result<(), String> {
+    let shown = wm::is_window_visible(app, LYRICS_LABEL).unwrap_or(false);
+    let result = if wm::window_exists(app, LYRICS_LABEL) {
+        wm::toggle_window(app, LYRICS_LABEL)
+    } else {
+        let config = presets::lookup(LYRICS_LABEL).ok_or("No desktop lyrics preset")?;
+        wm::create_window(app, &config)
+    };
+    // The item toggled itself on click; put it back if nothing happened.
+    note_visibility(LYRICS_LABEL, if result.is_ok() { !shown } else { shown });
+    result
+}
+
+/// Keep the native menu's "Show Desktop Lyrics" check in step with the
+/// window, however it was shown or hidden.
+pub(crate) fn note_visibility(label: &str, visible: bool) {
+    if label != LYRICS_LABEL {
+        return;
+    }
+    if let Ok(items) = TRAY_MENU.lock() {
+        if let Some(items) = items.as_ref() {
+            let _ = items.lyrics.set_checked(visible);
+        }
+    }
+}
+
+/// Switch between the webview popup and a native tray menu. The tray icon
+/// is rebuilt, keeping its badge and tooltip.
+#[tauri::command]
+pub fn set_tray_menu_mode(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    mode: TrayMenuMode,
+) -> Result<TrayConfig, String> {
+    let previous = store.read(|s| s.tray.menu_mode);
+    let config = store.update(|s| {
+        s.tray.menu_mode = mode;
+        Ok(s.tray)
+    })?;
+    if previous != mode {
+        rebuild_tray(&app)?;
+    }
+    Ok(config)
+}
+
+/// Drop the tray icon, stale or not, and build it again from the cached
+/// badge, tooltip and now-playing state.
+pub fn rebuild_tray(app: &AppHandle) -> Result<(), String> {
+    app.remove_tray_by_id(TRAY_ID);
+    setup_tray(app)
+}
+
+/// Rebuild the tray icon by hand, for when it has gone missing from the
+/// notification area.
+#[tauri::command]
+pub fn rebuild_tray_icon(app: AppHandle) -> Result<(), String> {
+    rebuild_tray(&app)
+}
+
+fn tray_config(app: &AppHandle) -> TrayConfig {
+    app.try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.tray))
+        .unwrap_or_default()
+}
+
+fn run_click_action(app: &AppHandle, action: TrayClickAction, rect: &Rect) {
+    let result = match action {
+        TrayClickAction::ShowMain => wm::show_window(app, "main"),
+        TrayClickAction::ToggleMain => wm::toggle_window(app, "main"),
+        TrayClickAction::PlayPause => app
+            .emit_to("main", "tray-play-pause", ())
+            .map_err(|e| e.to_string()),
+        TrayClickAction::ShowPopup => show_tray_popup(app, rect),
+        TrayClickAction::Nothing => Ok(()),
+    };
+    if let Err(e) = result {
+        warn!("Tray click action {:?} failed: {}", action, e);
+    }
+}
+
 /// Update the tray icon tooltip (e.g., "Song Name - Artist").
-/// Call this from JS when the playing song changes.
+/// Kept for older callers; prefer `update_tray_now_playing`.
 #[tauri::command]
 pub fn set_tray_tooltip(app: AppHandle, text: String) -> Result<(), String> {
-    if let Some(tray) = app.tray_by_id(TRAY_ID) {
-        tray.set_tooltip(Some(&text)).map_err(|e| e.to_string())
+    let text = text.trim();
+    if text.is_empty() || text == i18n::tr(Text::TrayTooltip) {
+        return clear_tray_now_playing(app);
+    }
+    reset_tooltip_state();
+    apply_tooltip(&app, &truncate_tooltip(text, TOOLTIP_MAX_UNITS))
+}
+
+/// Show the current track, album, elapsed/total time and the next track in
+/// the tray tooltip, and the track in the native menu's "Now Playing" item.
+/// Track and play/pause changes apply immediately; progress-only updates
+/// are throttled to one per [`TOOLTIP_MIN_INTERVAL`], though the cached
+/// payload always takes the latest position. An empty title clears it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn update_tray_now_playing(
+    app: AppHandle,
+    title: String,
+    artist: String,
+    position_ms: u64,
+    duration_ms: u64,
+    next_title: Option<String>,
+    album: Option<String>,
+    playing: Option<bool>,
+) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return clear_tray_now_playing(app);
+    }
+    let non_empty = |text: Option<String>| {
+        text.map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let update = TrayNowPlaying {
+        title: title.to_string(),
+        artist: artist.trim().to_string(),
+        album: non_empty(album),
+        position_ms,
+        duration_ms,
+        next_title: non_empty(next_title),
+        // Callers from before `playing` only updated during playback.
+        playing: playing.unwrap_or(true),
+    };
+
+    let (single_line_only, track_changed) = {
+        let Ok(mut state) = tooltip_state().lock() else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let track_changed = !state
+            .now_playing
+            .as_ref()
+            .is_some_and(|last| last.same_track(&update));
+        let throttled = !track_changed
+            && state
+                .last_update
+                .is_some_and(|last| now.duration_since(last) < TOOLTIP_MIN_INTERVAL);
+        state.now_playing = Some(update.clone());
+        if throttled {
+            return Ok(());
+        }
+        state.last_update = Some(now);
+        (state.single_line_only, track_changed)
+    };
+    if track_changed {
+        set_menu_now_playing(Some(&update));
+    }
+
+    let now_playing = NowPlaying {
+        title: &update.title,
+        artist: &update.artist,
+        album: update.album.as_deref(),
+        position_ms,
+        duration_ms,
+        next_title: update.next_title.as_deref(),
+        next_label: i18n::tr(Text::TrayNextUp),
+        paused_label: (!update.playing).then(|| i18n::tr(Text::TrayPaused)),
+    };
+    if !single_line_only {
+        let text = format_tooltip(&now_playing, true);
+        match apply_tooltip(&app, &text) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                warn!("Multi-line tray tooltip rejected, using single line: {}", e);
+                if let Ok(mut state) = tooltip_state().lock() {
+                    state.single_line_only = true;
+                }
+            }
+        }
+    }
+    apply_tooltip(&app, &format_tooltip(&now_playing, false))
+}
+
+/// Reset the tooltip to the plain app name (playback stopped).
+#[tauri::command]
+pub fn clear_tray_now_playing(app: AppHandle) -> Result<(), String> {
+    reset_tooltip_state();
+    set_menu_now_playing(None);
+    apply_tooltip(&app, i18n::tr(Text::TrayTooltip))
+}
+
+/// Swap the tray icon for one with a play or pause badge, or back to the
+/// plain icon when stopped.
+#[tauri::command]
+pub fn set_tray_playback_state(app: AppHandle, state: TrayPlaybackState) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+    let icon = playback_icon(&app, state)?;
+    tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
+    if let Ok(mut current) = TRAY_PLAYBACK.lock() {
+        *current = state;
+    }
+    tray.set_icon_as_template(cfg!(target_os = "macos"))
+        .map_err(|e| e.to_string())
+}
+
+fn playback_icon(app: &AppHandle, state: TrayPlaybackState) -> Result<Image<'static>, String> {
+    let cache = TRAY_ICONS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = cache.lock().map_err(|e| e.to_string())?;
+    if let Some(icon) = cache.get(&state) {
+        return Ok(icon.clone());
+    }
+    let base = app
+        .default_window_icon()
+        .ok_or("No default window icon to badge")?;
+    let base = RgbaImage::from_raw(base.width(), base.height(), base.rgba().to_vec())
+        .ok_or("Default window icon is not RGBA")?;
+    // A template icon is drawn from alpha alone, so the glyph is cut out of
+    // the badge there instead of painted on it.
+    let badged = with_badge(base, state, cfg!(target_os = "macos"));
+    let (width, height) = badged.dimensions();
+    let icon = Image::new_owned(badged.into_raw(), width, height);
+    cache.insert(state, icon.clone());
+    Ok(icon)
+}
+
+/// Draw the playback badge into the bottom-right corner of `icon`.
+fn with_badge(mut icon: RgbaImage, state: TrayPlaybackState, cutout: bool) -> RgbaImage {
+    if state == TrayPlaybackState::Stopped {
+        return icon;
+    }
+    let (width, height) = icon.dimensions();
+    let diameter = (width.min(height) as f32 * BADGE_SCALE).round() as u32;
+    let r = diameter as f32 / 2.0;
+    let (cx, cy) = (width as f32 - r, height as f32 - r);
+    let glyph = if cutout {
+        Rgba([0, 0, 0, 0])
+    } else {
+        BADGE_GLYPH
+    };
+
+    for y in height - diameter..height {
+        for x in width - diameter..width {
+            // Pixel center relative to the badge center, in radii.
+            let dx = (x as f32 + 0.5 - cx) / r;
+            let dy = (y as f32 + 0.5 - cy) / r;
+            if dx * dx + dy * dy > 1.0 {
+                continue;
+            }
+            let in_glyph = match state {
+                // Triangle from (-0.3, ±0.45) to (0.5, 0).
+                TrayPlaybackState::Playing => dx >= -0.3 && dy.abs() <= 0.45 * (0.5 - dx) / 0.8,
+                TrayPlaybackState::Paused => dy.abs() <= 0.45 && (0.1..=0.35).contains(&dx.abs()),
+                TrayPlaybackState::Stopped => false,
+            };
+            icon.put_pixel(x, y, if in_glyph { glyph } else { BADGE_FILL });
+        }
+    }
+    icon
+}
+
+/// Redraw the tooltip after a backend locale change: the idle text right
+/// away, a now-playing tooltip on its next (unthrottled) update. A native
+/// menu is rebuilt in the new language.
+pub(crate) fn refresh_locale(app: &AppHandle) {
+    if tray_config(app).menu_mode == TrayMenuMode::Native {
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            match build_menu(app) {
+                Ok(menu) => {
+                    let _ = tray.set_menu(Some(menu));
+                }
+                Err(e) => warn!("Failed to rebuild tray menu: {}", e),
+            }
+        }
+    }
+
+    let idle = {
+        let Ok(mut state) = tooltip_state().lock() else {
+            return;
+        };
+        state.last_update = None;
+        state.now_playing.is_none()
+    };
+    if idle {
+        let _ = apply_tooltip(app, i18n::tr(Text::TrayTooltip));
+    }
+}
+
+struct NowPlaying<'a> {
+    title: &'a str,
+    artist: &'a str,
+    album: Option<&'a str>,
+    position_ms: u64,
+    duration_ms: u64,
+    next_title: Option<&'a str>,
+    next_label: &'a str,
+    /// Shown after the progress while paused.
+    paused_label: Option<&'a str>,
+}
+
+fn tooltip_state() -> &'static Mutex<TooltipState> {
+    TRAY_TOOLTIP.get_or_init(|| Mutex::new(TooltipState::default()))
+}
+
+fn reset_tooltip_state() {
+    if let Ok(mut state) = tooltip_state().lock() {
+        state.now_playing = None;
+        state.last_update = None;
+    }
+}
+
+fn apply_tooltip(app: &AppHandle, text: &str) -> Result<(), String> {
+    if !TRAY_DISGUISED.load(Ordering::Acquire) {
+        let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+        tray.set_tooltip(Some(text)).map_err(|e| e.to_string())?;
+    }
+    if let Ok(mut state) = tooltip_state().lock() {
+        state.text = Some(text.to_string());
+    }
+    Ok(())
+}
+
+/// Show only the app name in the tooltip (boss key), or go back to the
+/// last tooltip set.
+pub(crate) fn set_disguised(app: &AppHandle, disguised: bool) -> Result<(), String> {
+    TRAY_DISGUISED.store(disguised, Ordering::Release);
+    let text = tooltip_state()
+        .lock()
+        .ok()
+        .and_then(|state| state.text.clone())
+        .filter(|_| !disguised)
+        .unwrap_or_else(|| i18n::tr(Text::TrayTooltip).to_string());
+    let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+    tray.set_tooltip(Some(text)).map_err(|e| e.to_string())
+}
+
+fn heading(title: &str, artist: &str) -> String {
+    if artist.is_empty() {
+        title.to_string()
+    } else {
+        format!("{} - {}", title, artist)
+    }
+}
+
+/// "Title - Artist", then the album, "1:23 / 4:05" and "Next: …" on their
+/// own lines. The single-line form keeps only the first line. The title
+/// line gets at most half the budget and the album a quarter, so the
+/// progress line survives long names.
+fn format_tooltip(now_playing: &NowPlaying, multiline: bool) -> String {
+    let heading = heading(now_playing.title, now_playing.artist);
+    if !multiline {
+        return truncate_tooltip(&heading, TOOLTIP_MAX_UNITS);
+    }
+
+    let mut lines = vec![truncate_tooltip(&heading, TOOLTIP_MAX_UNITS / 2)];
+    if let Some(album) = now_playing.album {
+        lines.push(truncate_tooltip(album, TOOLTIP_MAX_UNITS / 4));
+    }
+    let mut progress = Vec::new();
+    if now_playing.duration_ms > 0 {
+        progress.push(format!(
+            "{} / {}",
+            format_clock(now_playing.position_ms.min(now_playing.duration_ms)),
+            format_clock(now_playing.duration_ms)
+        ));
+    }
+    progress.extend(now_playing.paused_label.map(str::to_string));
+    if !progress.is_empty() {
+        lines.push(progress.join(" · "));
+    }
+    if let Some(next) = now_playing.next_title {
+        lines.push(format!("{}{}", now_playing.next_label, next));
+    }
+    truncate_tooltip(&lines.join("\n"), TOOLTIP_MAX_UNITS)
+}
+
+fn format_clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
     } else {
-        Err("Tray icon not found".into())
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Cut `text` to at most `max_units` UTF-16 code units (the unit the
+/// Windows limit is expressed in), ending with an ellipsis when shortened.
+/// Never splits a character.
+fn truncate_tooltip(text: &str, max_units: usize) -> String {
+    if text.encode_utf16().count() <= max_units {
+        return text.to_string();
     }
+    let budget = max_units.saturating_sub(1);
+    let mut used = 0;
+    let mut out = String::new();
+    for ch in text.chars() {
+        used += ch.len_utf16();
+        if used > budget {
+            break;
+        }
+        out.push(ch);
+    }
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    out.push('…');
+    out
 }
 
 /// Update the tray popup size from the rendered Web UI and keep it anchored to the tray icon.
@@ -121,6 +760,16 @@ pub fn update_tray_popup_layout(app: AppHandle, width: f64, height: f64) -> Resu
 /// The popup is pre-created (hidden) during app setup. If it somehow doesn't
 /// exist yet, it is created lazily here as a fallback.
 fn show_tray_popup(app: &AppHandle, rect: &Rect) -> Result<(), String> {
+    let just_dismissed = TRAY_POPUP_DISMISSED_AT
+        .lock()
+        .map(|mut at| {
+            at.take()
+                .is_some_and(|at| at.elapsed() < TRAY_POPUP_REOPEN_GUARD)
+        })
+        .unwrap_or(false);
+    if just_dismissed {
+        return Ok(());
+    }
     let config = WindowConfig::tray_popup();
 
     // Fallback: create the popup if it doesn't exist yet
@@ -142,7 +791,11 @@ fn show_tray_popup(app: &AppHandle, rect: &Rect) -> Result<(), String> {
         .and_then(|w| w.scale_factor().ok())
         .unwrap_or(1.0);
 
-    let anchor = rect_to_physical(rect, scale_factor);
+    let anchor = if tray_rect_missing(rect) {
+        cursor_anchor(app, scale_factor).unwrap_or_else(|| rect_to_physical(rect, scale_factor))
+    } else {
+        rect_to_physical(rect, scale_factor)
+    };
     remember_anchor(anchor);
 
     let (x, y) = if let Some(ref popup_win) = popup {
@@ -155,13 +808,124 @@ fn show_tray_popup(app: &AppHandle, rect: &Rect) -> Result<(), String> {
     };
 
     wm::show_window_at_position(app, "tray-popup", x, y)?;
+    if let Ok(mut shown_at) = TRAY_POPUP_SHOWN_AT.lock() {
+        *shown_at = Some(Instant::now());
+    }
+    watch_popup(app);
 
-    // Notify the popup to request fresh player state
-    let _ = app.emit("tray-popup-opened", ());
+    // Hand the popup the cached track right away; it then reads the full
+    // player state from `state_snapshot`, without waking the main window.
+    let now_playing = tooltip_state()
+        .lock()
+        .ok()
+        .and_then(|state| state.now_playing.clone());
+    let _ = app.emit("tray-popup-opened", now_playing);
 
     Ok(())
 }
 
+/// Hide the tray popup if it is open. The popup calls this on Escape.
+#[tauri::command]
+pub fn dismiss_tray_popup(app: AppHandle) -> Result<(), String> {
+    dismiss_popup(&app)
+}
+
+fn dismiss_popup(app: &AppHandle) -> Result<(), String> {
+    let Some(popup) = app.get_webview_window("tray-popup") else {
+        return Ok(());
+    };
+    if !popup.is_visible().unwrap_or(false) {
+        return Ok(());
+    }
+    popup.hide().map_err(|e| e.to_string())?;
+    if let Ok(mut dismissed_at) = TRAY_POPUP_DISMISSED_AT.lock() {
+        *dismissed_at = Some(Instant::now());
+    }
+    Ok(())
+}
+
+/// `Focused(false)` on the popup. Ignored right after showing, when focus
+/// can still be settling between the tray and the popup.
+pub(crate) fn on_popup_focus_lost(app: &AppHandle) {
+    let settling = TRAY_POPUP_SHOWN_AT
+        .lock()
+        .ok()
+        .and_then(|at| *at)
+        .is_some_and(|at| at.elapsed() < TRAY_POPUP_FOCUS_GRACE);
+    if settling {
+        return;
+    }
+    if let Err(e) = dismiss_popup(app) {
+        warn!("Failed to hide tray popup: {}", e);
+    }
+}
+
+/// Hide the popup on a click outside it, for when it never got focus and
+/// so will never lose it. Runs until the popup is hidden.
+fn watch_popup(app: &AppHandle) {
+    if wm::mouse_button_down().is_none() || TRAY_POPUP_WATCHING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("tray-popup-watch".into())
+        .spawn(move || {
+            let started = Instant::now();
+            let mut seen = false;
+            // A button already down belongs to the click that opened it.
+            let mut was_down = true;
+            loop {
+                std::thread::sleep(TRAY_POPUP_WATCH_INTERVAL);
+                let Some(popup) = app.get_webview_window("tray-popup") else {
+                    break;
+                };
+                if !popup.is_visible().unwrap_or(false) {
+                    if seen || started.elapsed() > TRAY_POPUP_SHOW_TIMEOUT {
+                        break;
+                    }
+                    continue;
+                }
+                seen = true;
+                let down = wm::mouse_button_down().unwrap_or(false);
+                if down && !was_down && !cursor_over(&app, &popup) {
+                    if let Err(e) = dismiss_popup(&app) {
+                        warn!("Failed to hide tray popup: {}", e);
+                    }
+                    break;
+                }
+                was_down = down;
+            }
+            TRAY_POPUP_WATCHING.store(false, Ordering::Release);
+        });
+    if let Err(e) = spawned {
+        TRAY_POPUP_WATCHING.store(false, Ordering::Release);
+        warn!("Failed to spawn tray popup watchdog: {}", e);
+    }
+}
+
+/// Whether the cursor is over `popup`. Unknown counts as over, so a failed
+/// lookup never dismisses.
+fn cursor_over(app: &AppHandle, popup: &WebviewWindow) -> bool {
+    let (Ok(position), Ok(size), Ok((x, y))) = (
+        popup.outer_position(),
+        popup.outer_size(),
+        wm::cursor_position(app),
+    ) else {
+        return true;
+    };
+    // The native cursor is in points on macOS.
+    let scale = if cfg!(target_os = "macos") {
+        popup.scale_factor().unwrap_or(1.0)
+    } else {
+        1.0
+    };
+    let (x, y) = ((x as f64 * scale) as i32, (y as f64 * scale) as i32);
+    x >= position.x
+        && y >= position.y
+        && x < position.x + size.width as i32
+        && y < position.y + size.height as i32
+}
+
 fn popup_size_state() -> &'static Mutex<PopupSize> {
     TRAY_POPUP_SIZE.get_or_init(|| {
         Mutex::new(PopupSize {
@@ -233,6 +997,37 @@ fn rect_to_physical(rect: &Rect, scale_factor: f64) -> PhysicalRect {
     }
 }
 
+/// KDE and several appindicator hosts report an empty tray rect, or one at
+/// the origin, which would put the popup in the top-left corner.
+fn tray_rect_missing(rect: &Rect) -> bool {
+    let (x, y) = match &rect.position {
+        tauri::Position::Physical(pos) => (pos.x as f64, pos.y as f64),
+        tauri::Position::Logical(pos) => (pos.x, pos.y),
+    };
+    let (width, height) = match &rect.size {
+        tauri::Size::Physical(size) => (size.width as f64, size.height as f64),
+        tauri::Size::Logical(size) => (size.width, size.height),
+    };
+    width <= 0.0 || height <= 0.0 || (cfg!(target_os = "linux") && x == 0.0 && y == 0.0)
+}
+
+/// A one-pixel anchor under the cursor, for trays that report no rect.
+fn cursor_anchor(app: &AppHandle, scale_factor: f64) -> Option<PhysicalRect> {
+    let (x, y) = wm::cursor_position(app).ok()?;
+    // The native cursor is in points on macOS.
+    let scale = if cfg!(target_os = "macos") {
+        scale_factor
+    } else {
+        1.0
+    };
+    Some(PhysicalRect {
+        x: x as f64 * scale,
+        y: y as f64 * scale,
+        width: 1.0,
+        height: 1.0,
+    })
+}
+
 fn position_popup_window(
     popup: &WebviewWindow,
     anchor: PhysicalRect,
@@ -266,7 +1061,7 @@ fn calculate_popup_position(
     let anchor_center_x = anchor.x + anchor.width / 2.0;
     let anchor_center_y = anchor.y + anchor.height / 2.0;
 
-    let (mut x, mut y) = match edge {
+    let (x, y) = match edge {
         ScreenEdge::Top => (
             anchor_center_x - popup_width / 2.0,
             anchor.y + anchor.height + gap,
@@ -285,16 +1080,13 @@ fn calculate_popup_position(
         ),
     };
 
-    x = x.clamp(
-        monitor.left,
-        (monitor.right - popup_width).max(monitor.left),
-    );
-    y = y.clamp(
-        monitor.top,
-        (monitor.bottom - popup_height).max(monitor.top),
-    );
-
-    (x, y)
+    let area = ScreenRect {
+        x: monitor.left as i32,
+        y: monitor.top as i32,
+        width: (monitor.right - monitor.left) as u32,
+        height: (monitor.bottom - monitor.top) as u32,
+    };
+    wm::clamp_to_monitor(x, y, popup_width, popup_height, area)
 }
 
 fn target_monitor_bounds(popup: &WebviewWindow, anchor: PhysicalRect) -> Option<MonitorBounds> {
@@ -375,3 +1167,101 @@ fn nearest_screen_edge(anchor: PhysicalRect, monitor: MonitorBounds) -> ScreenEd
 
     edge
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncation_counts_utf16_units() {
+        assert_eq!(truncate_tooltip("short", 10), "short");
+        assert_eq!(truncate_tooltip("abcdef ghij", 8), "abcdef…");
+        // Each emoji is a surrogate pair and must not be split.
+        let cut = truncate_tooltip("🎵🎵🎵🎵", 6);
+        assert_eq!(cut, "🎵🎵…");
+        assert!(cut.encode_utf16().count() <= 6);
+    }
+
+    #[test]
+    fn playback_badge_is_drawn_in_the_corner() {
+        let base = RgbaImage::from_pixel(32, 32, Rgba([200, 0, 0, 255]));
+        let playing = with_badge(base.clone(), TrayPlaybackState::Playing, false);
+        // Badge center (just left of it, inside the triangle) is the glyph.
+        assert_eq!(*playing.get_pixel(21, 22), BADGE_GLYPH);
+        assert_eq!(*playing.get_pixel(0, 0), Rgba([200, 0, 0, 255]));
+
+        // Between the pause bars is badge fill; cut-out glyphs are clear.
+        let paused = with_badge(base.clone(), TrayPlaybackState::Paused, true);
+        assert_eq!(*paused.get_pixel(22, 22), BADGE_FILL);
+        assert_eq!(paused.get_pixel(19, 22)[3], 0);
+
+        assert_eq!(
+            with_badge(base.clone(), TrayPlaybackState::Stopped, false),
+            base
+        );
+    }
+
+    #[test]
+    fn tooltip_lines() {
+        let now_playing = NowPlaying {
+            title: "Song",
+            artist: "Artist",
+            album: None,
+            position_ms: 83_000,
+            duration_ms: 245_000,
+            next_title: Some("Other"),
+            next_label: "Next: ",
+            paused_label: None,
+        };
+        assert_eq!(
+            format_tooltip(&now_playing, true),
+            "Song - Artist\n1:23 / 4:05\nNext: Other"
+        );
+        assert_eq!(format_tooltip(&now_playing, false), "Song - Artist");
+
+        let long_title = "x".repeat(400);
+        let text = format_tooltip(
+            &NowPlaying {
+                title: &long_title,
+                ..now_playing
+            },
+            true,
+        );
+        assert!(text.encode_utf16().count() <= TOOLTIP_MAX_UNITS);
+        assert!(text.contains("1:23 / 4:05"));
+
+        let paused = NowPlaying {
+            album: Some("Album"),
+            next_title: None,
+            paused_label: Some("Paused"),
+            ..now_playing
+        };
+        assert_eq!(
+            format_tooltip(&paused, true),
+            "Song - Artist\nAlbum\n1:23 / 4:05 · Paused"
+        );
+    }
+
+    #[test]
+    fn only_position_changes_count_as_the_same_track() {
+        let playing = TrayNowPlaying {
+            title: "Song".into(),
+            artist: "Artist".into(),
+            album: None,
+            position_ms: 1_000,
+            duration_ms: 245_000,
+            next_title: None,
+            playing: true,
+        };
+        let later = TrayNowPlaying {
+            position_ms: 9_000,
+            ..playing.clone()
+        };
+        let paused = TrayNowPlaying {
+            playing: false,
+            ..later.clone()
+        };
+        assert!(playing.same_track(&later));
+        assert!(!later.same_track(&paused));
+    }
+}
@@ -0,0 +1,116 @@
+//! Per-window webview zoom (Ctrl+= / Ctrl+- like a browser).
+//!
+//! The factor is saved per label in the window settings and re-applied each
+//! time the window's page finishes loading. Windows whose layout depends on
+//! an exact pixel size are excluded.
+
+use log::warn;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+pub const WINDOW_ZOOM_CHANGED_EVENT: &str = "window-zoom-changed";
+
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 2.0;
+const ZOOM_STEP: f64 = 0.05;
+/// Sized to their content; zooming would clip or overflow them.
+const EXCLUDED_LABELS: [&str; 2] = ["desktop-lyrics", "tray-popup"];
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowZoomChanged {
+    pub label: String,
+    pub factor: f64,
+}
+
+/// Clamp to the supported range and snap to the step.
+fn normalize(factor: f64) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err("Zoom factor must be finite".into());
+    }
+    let snapped = (factor.clamp(MIN_ZOOM, MAX_ZOOM) / ZOOM_STEP).round() * ZOOM_STEP;
+    // Trim float noise such as 1.1500000000000001.
+    Ok((snapped * 100.0).round() / 100.0)
+}
+
+fn check_label(label: &str) -> Result<(), String> {
+    if EXCLUDED_LABELS.contains(&label) {
+        Err(format!("Zoom is not supported for '{}'", label))
+    } else {
+        Ok(())
+    }
+}
+
+fn saved_zoom(app: &AppHandle, label: &str) -> f64 {
+    app.try_state::<SettingsStore>()
+        .and_then(|store| store.read(|s| s.window.zoom.get(label).copied()))
+        .unwrap_or(1.0)
+}
+
+/// Re-apply the saved factor; called when the window's page has loaded.
+pub(crate) fn apply_saved(window: &WebviewWindow) {
+    let label = window.label();
+    if check_label(label).is_err() {
+        return;
+    }
+    let factor = saved_zoom(window.app_handle(), label);
+    if factor != 1.0 {
+        if let Err(e) = window.set_zoom(factor) {
+            warn!("Failed to restore zoom for '{}': {}", label, e);
+        }
+    }
+}
+
+/// Zoom a window's webview. Returns the factor actually applied.
+#[tauri::command]
+pub fn set_webview_zoom(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    label: String,
+    factor: f64,
+) -> Result<f64, String> {
+    check_label(&label)?;
+    let factor = normalize(factor)?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+    store.update(|s| {
+        if factor == 1.0 {
+            s.window.zoom.remove(&label);
+        } else {
+            s.window.zoom.insert(label.clone(), factor);
+        }
+        Ok(())
+    })?;
+    let _ = app.emit(
+        WINDOW_ZOOM_CHANGED_EVENT,
+        WindowZoomChanged { label, factor },
+    );
+    Ok(factor)
+}
+
+#[tauri::command]
+pub fn get_webview_zoom(app: AppHandle, label: String) -> Result<f64, String> {
+    check_label(&label)?;
+    Ok(saved_zoom(&app, &label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factors_are_clamped_and_snapped() {
+        assert_eq!(normalize(1.0), Ok(1.0));
+        assert_eq!(normalize(1.13), Ok(1.15));
+        assert_eq!(normalize(0.1), Ok(0.5));
+        assert_eq!(normalize(3.0), Ok(2.0));
+        assert!(normalize(f64::NAN).is_err());
+        assert!(check_label("tray-popup").is_err());
+        assert!(check_label("main").is_ok());
+    }
+}
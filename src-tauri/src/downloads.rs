@@ -0,0 +1,772 @@
+//! Song downloads for offline playback.
+//!
+//! Downloads run in the backend, so they keep going while the main window
+//! is hidden and its webview throttled. Each task writes to `<dest>.part`
+//! and asks for the rest with a `Range` request when resumed; the file is
+//! renamed into place once its size (or the MD5 the caller supplied)
+//! checks out. At most `max_concurrent` tasks run at once and the rest wait
+//! in order of creation.
+//!
+//! The task list is kept in `downloads.json` under the app data dir,
+//! written by a background thread after every state change. Tasks that were running when the app
+//! exited are queued again on the next start and pick up from the size of
+//! their `.part` file.
+//!
+//! Requests go through the shared client but not `fetch_with_policy`: its
+//! per-attempt timeout covers the whole body, which a song download would
+//! outlast. Instead a task fails once the server has sent nothing for
+//! [`IDLE_TIMEOUT`], and pausing or cancelling interrupts it even while it
+//! waits.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::future::Future;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use md5::{Digest, Md5};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_http::reqwest::header::{HeaderMap, CONTENT_RANGE, RANGE};
+use tauri_plugin_http::reqwest::StatusCode;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+
+use crate::desktop::file_manager::{self, FileManagerError};
+use crate::http_util;
+use crate::settings::{write_atomic, SettingsStore};
+
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+const TASKS_FILE: &str = "downloads.json";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+/// A response (or the next chunk of one) that takes longer fails the task.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long quitting waits for the task list to be written.
+const SHUTDOWN_SAVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a running task should do after its current chunk.
+const CONTROL_RUN: u8 = 0;
+const CONTROL_PAUSE: u8 = 1;
+const CONTROL_CANCEL: u8 = 2;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum DownloadError {
+    /// Destination folder is outside the allowed roots or missing.
+    InvalidDestination(String),
+    /// No task with that id.
+    NotFound(String),
+    /// An unfinished task already downloads to that path.
+    Duplicate(String),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDestination(message) => write!(f, "Invalid destination: {}", message),
+            Self::NotFound(id) => write!(f, "No download task {}", id),
+            Self::Duplicate(path) => write!(f, "Already downloading to {}", path),
+        }
+    }
+}
+
+impl From<FileManagerError> for DownloadError {
+    fn from(e: FileManagerError) -> Self {
+        Self::InvalidDestination(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    /// Only in the last event of a cancelled task, which is then dropped.
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadTask {
+    pub id: u64,
+    pub url: String,
+    pub dest_path: PathBuf,
+    /// Sent with every request for this task, e.g. a `Referer`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Expected MD5 of the finished file, hex.
+    pub md5: Option<String>,
+    pub status: DownloadStatus,
+    pub bytes: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+    pub created_ms: u64,
+}
+
+/// Payload of `download-progress`: sent at most every 500 ms per task while
+/// it runs, and once on every status change.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub task_id: u64,
+    pub status: DownloadStatus,
+    pub bytes: u64,
+    pub total: Option<u64>,
+    /// Bytes per second since the previous event.
+    pub speed: u64,
+}
+
+enum Outcome {
+    Completed { bytes: u64, total: Option<u64> },
+    Paused { bytes: u64, total: Option<u64> },
+    Cancelled,
+    Failed(String),
+}
+
+/// Pause and cancel requests for a running task.
+struct Control {
+    state: AtomicU8,
+    /// Wakes the task if it is waiting on the network.
+    changed: Notify,
+}
+
+impl Control {
+    fn new() -> Self {
+        Self {
+            state: AtomicU8::new(CONTROL_RUN),
+            changed: Notify::new(),
+        }
+    }
+
+    fn get(&self) -> u8 {
+        self.state.load(Ordering::Acquire)
+    }
+
+    fn request(&self, state: u8) {
+        self.state.store(state, Ordering::Release);
+        self.changed.notify_one();
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    tasks: BTreeMap<u64, DownloadTask>,
+    /// One per running task.
+    controls: HashMap<u64, Arc<Control>>,
+    next_id: u64,
+}
+
+enum Save {
+    Tasks(Vec<u8>),
+    /// Answered once everything sent before it is written.
+    Flush(mpsc::Sender<()>),
+}
+
+/// Managed download queue.
+pub struct DownloadManager {
+    /// Task list snapshots for the writer thread; `None` when the app data
+    /// dir is unavailable.
+    saves: Option<mpsc::Sender<Save>>,
+    inner: Mutex<Inner>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+fn part_len(dest: &Path) -> u64 {
+    std::fs::metadata(part_path(dest)).map_or(0, |meta| meta.len())
+}
+
+impl DownloadManager {
+    /// Load the task list. Tasks cut off by the last exit are queued again;
+    /// call [`pump`] once the manager is managed to start them.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(TASKS_FILE)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, downloads will not persist: {}",
+                    e
+                );
+                None
+            }
+        };
+        let tasks: Vec<DownloadTask> = path
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| {
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| warn!("Ignoring malformed {}: {}", TASKS_FILE, e))
+                    .ok()
+            })
+            .unwrap_or_default();
+        let mut inner = Inner::default();
+        for mut task in tasks {
+            if task.status == DownloadStatus::Running {
+                task.status = DownloadStatus::Queued;
+            }
+            if task.status != DownloadStatus::Completed {
+                task.bytes = part_len(&task.dest_path);
+            }
+            inner.next_id = inner.next_id.max(task.id + 1);
+            inner.tasks.insert(task.id, task);
+        }
+        let saves = path.and_then(|path| {
+            let (saves, queue) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("downloads-writer".into())
+                .spawn(move || run_writer(&path, &queue))
+                .map_err(|e| warn!("Downloads will not persist: {}", e))
+                .ok()
+                .map(|_| saves)
+        });
+        Self {
+            saves,
+            inner: Mutex::new(inner),
+        }
+    }
+
+    /// Hand a snapshot of the task list to the writer thread.
+    fn persist(&self, inner: &Inner) {
+        let Some(saves) = &self.saves else {
+            return;
+        };
+        let tasks: Vec<&DownloadTask> = inner.tasks.values().collect();
+        match serde_json::to_vec(&tasks) {
+            Ok(json) => {
+                let _ = saves.send(Save::Tasks(json));
+            }
+            Err(e) => warn!("Failed to save the download list: {}", e),
+        }
+    }
+
+    fn task(&self, id: u64) -> Option<DownloadTask> {
+        self.inner.lock().tasks.get(&id).cloned()
+    }
+
+    fn update(&self, id: u64, bytes: u64, total: Option<u64>) {
+        if let Some(task) = self.inner.lock().tasks.get_mut(&id) {
+            task.bytes = bytes;
+            task.total = total;
+        }
+    }
+}
+
+/// Write task list snapshots as they come; when several are waiting only
+/// the newest is written.
+fn run_writer(path: &Path, queue: &mpsc::Receiver<Save>) {
+    let mut flushes = Vec::new();
+    while let Ok(save) = queue.recv() {
+        let mut latest = None;
+        for save in std::iter::once(save).chain(queue.try_iter()) {
+            match save {
+                Save::Tasks(json) => latest = Some(json),
+                Save::Flush(done) => flushes.push(done),
+            }
+        }
+        if let Some(json) = latest {
+            if let Err(e) = write_atomic(path, &json) {
+                warn!("Failed to save the download list: {}", e);
+            }
+        }
+        for done in flushes.drain(..) {
+            let _ = done.send(());
+        }
+    }
+}
+
+/// Shutdown hook: save the task list with the latest byte counts and wait
+/// for it to be written. Running tasks are queued again on the next start
+/// and continue from their `.part` files.
+pub fn save_for_exit(app: &AppHandle) {
+    let Some(manager) = app.try_state::<DownloadManager>() else {
+        return;
+    };
+    let Some(saves) = &manager.saves else {
+        return;
+    };
+    manager.persist(&manager.inner.lock());
+    let (done, written) = mpsc::channel();
+    if saves.send(Save::Flush(done)).is_ok() && written.recv_timeout(SHUTDOWN_SAVE_TIMEOUT).is_err()
+    {
+        warn!("Download list not saved before exit");
+    }
+}
+
+fn emit(app: &AppHandle, task: &DownloadTask, speed: u64) {
+    let _ = app.emit(
+        DOWNLOAD_PROGRESS_EVENT,
+        DownloadProgress {
+            task_id: task.id,
+            status: task.status,
+            bytes: task.bytes,
+            total: task.total,
+            speed,
+        },
+    );
+}
+
+/// Throttles progress events and works out the speed between them.
+struct Meter {
+    at: Instant,
+    bytes: u64,
+}
+
+impl Meter {
+    fn new(bytes: u64, now: Instant) -> Self {
+        Self { at: now, bytes }
+    }
+
+    /// The speed in bytes per second, if an event is due.
+    fn tick(&mut self, bytes: u64, now: Instant) -> Option<u64> {
+        let elapsed = now.duration_since(self.at);
+        if elapsed < PROGRESS_INTERVAL {
+            return None;
+        }
+        let speed = (bytes.saturating_sub(self.bytes) as f64 / elapsed.as_secs_f64()) as u64;
+        *self = Self::new(bytes, now);
+        Some(speed)
+    }
+}
+
+/// The full length from a `Content-Range: bytes a-b/len` header.
+fn content_range_total(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+fn file_md5(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Check a finished `.part` file against the expected length and MD5.
+fn verify(part: &Path, total: Option<u64>, md5: Option<&str>) -> Result<(), String> {
+    let len = std::fs::metadata(part).map_err(|e| e.to_string())?.len();
+    if let Some(total) = total.filter(|&total| total != len) {
+        return Err(format!(
+            "Size mismatch: expected {} bytes, got {}",
+            total, len
+        ));
+    }
+    if let Some(expected) = md5 {
+        let actual = file_md5(part).map_err(|e| e.to_string())?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(format!(
+                "MD5 mismatch: expected {}, got {}",
+                expected, actual
+            ));
+        }
+    }
+    Ok(())
+}
+
+enum Step<T> {
+    Ready(T),
+    /// Paused or cancelled while waiting.
+    Interrupted(u8),
+}
+
+/// Await a network call unless the task is paused or cancelled first.
+/// Fails once it has waited [`IDLE_TIMEOUT`].
+async fn interruptible<T>(
+    control: &Control,
+    future: impl Future<Output = tauri_plugin_http::reqwest::Result<T>>,
+) -> Result<Step<T>, String> {
+    let future = tokio::time::timeout(IDLE_TIMEOUT, future);
+    tokio::pin!(future);
+    loop {
+        let state = control.get();
+        if state != CONTROL_RUN {
+            return Ok(Step::Interrupted(state));
+        }
+        tokio::select! {
+            _ = control.changed.notified() => {}
+            result = &mut future => {
+                return match result {
+                    Ok(result) => result.map(Step::Ready).map_err(|e| e.to_string()),
+                    Err(_) => Err(format!(
+                        "No data from the server for {} s",
+                        IDLE_TIMEOUT.as_secs()
+                    )),
+                };
+            }
+        }
+    }
+}
+
+async fn file_len(path: &Path) -> u64 {
+    tokio::fs::metadata(path).await.map_or(0, |meta| meta.len())
+}
+
+async fn transfer(
+    app: &AppHandle,
+    task: &DownloadTask,
+    control: &Control,
+) -> Result<Outcome, String> {
+    let manager = app.state::<DownloadManager>();
+    let part = part_path(&task.dest_path);
+    let mut bytes = file_len(&part).await;
+    let stopped = |state: u8, bytes: u64, total: Option<u64>| match state {
+        CONTROL_CANCEL => Outcome::Cancelled,
+        _ => Outcome::Paused { bytes, total },
+    };
+
+    let mut request = http_util::client().get(&task.url);
+    for (name, value) in &task.headers {
+        request = request.header(name, value);
+    }
+    if bytes > 0 {
+        request = request.header(RANGE, format!("bytes={}-", bytes));
+    }
+    let mut response = match interruptible(control, request.send()).await? {
+        Step::Ready(response) => response,
+        Step::Interrupted(state) => return Ok(stopped(state, bytes, task.total)),
+    };
+    let total = match response.status() {
+        StatusCode::PARTIAL_CONTENT => content_range_total(response.headers())
+            .or_else(|| response.content_length().map(|len| len + bytes)),
+        // The `.part` file already holds everything.
+        StatusCode::RANGE_NOT_SATISFIABLE if bytes > 0 => {
+            content_range_total(response.headers()).or(Some(bytes))
+        }
+        // The server ignored the range; start over.
+        status if status.is_success() => {
+            bytes = 0;
+            response.content_length()
+        }
+        status => return Err(format!("HTTP {}", status)),
+    };
+
+    if response.status() != StatusCode::RANGE_NOT_SATISFIABLE {
+        let mut file = if bytes == 0 {
+            tokio::fs::File::create(&part).await
+        } else {
+            tokio::fs::OpenOptions::new().append(true).open(&part).await
+        }
+        .map_err(|e| e.to_string())?;
+        let mut meter = Meter::new(bytes, Instant::now());
+        manager.update(task.id, bytes, total);
+        loop {
+            let chunk = match interruptible(control, response.chunk()).await? {
+                Step::Ready(Some(chunk)) => chunk,
+                Step::Ready(None) => break,
+                Step::Interrupted(state) => {
+                    file.flush().await.map_err(|e| e.to_string())?;
+                    return Ok(stopped(state, bytes, total));
+                }
+            };
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            bytes += chunk.len() as u64;
+            if let Some(speed) = meter.tick(bytes, Instant::now()) {
+                manager.update(task.id, bytes, total);
+                if let Some(task) = manager.task(task.id) {
+                    emit(app, &task, speed);
+                }
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+    }
+
+    let md5 = task.md5.clone();
+    let checked = part.clone();
+    let verified =
+        tauri::async_runtime::spawn_blocking(move || verify(&checked, total, md5.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Err(e) = verified {
+        // Corrupt data would only be appended to on retry.
+        let _ = tokio::fs::remove_file(&part).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&part, &task.dest_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Outcome::Completed {
+        bytes: tokio::fs::metadata(&task.dest_path)
+            .await
+            .map_or(bytes, |meta| meta.len()),
+        total,
+    })
+}
+
+fn finish(app: &AppHandle, id: u64, outcome: Outcome) {
+    let manager = app.state::<DownloadManager>();
+    let mut inner = manager.inner.lock();
+    inner.controls.remove(&id);
+    let Some(task) = inner.tasks.get_mut(&id) else {
+        return;
+    };
+    match outcome {
+        Outcome::Completed { bytes, total } => {
+            info!("Download {} finished: {}", id, task.dest_path.display());
+            task.status = DownloadStatus::Completed;
+            task.bytes = bytes;
+            task.total = total.or(Some(bytes));
+        }
+        Outcome::Paused { bytes, total } => {
+            task.status = DownloadStatus::Paused;
+            task.bytes = bytes;
+            task.total = total;
+        }
+        Outcome::Failed(error) => {
+            warn!("Download {} failed: {}", id, error);
+            task.status = DownloadStatus::Failed;
+            task.bytes = part_len(&task.dest_path);
+            task.error = Some(error);
+        }
+        Outcome::Cancelled => task.status = DownloadStatus::Cancelled,
+    }
+    emit(app, task, 0);
+    if task.status == DownloadStatus::Cancelled {
+        let _ = std::fs::remove_file(part_path(&task.dest_path));
+        inner.tasks.remove(&id);
+    }
+    manager.persist(&inner);
+    drop(inner);
+    pump(app);
+}
+
+/// Start queued tasks while fewer than `max_concurrent` are running.
+pub fn pump(app: &AppHandle) {
+    let Some(manager) = app.try_state::<DownloadManager>() else {
+        return;
+    };
+    let limit = app
+        .try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.downloads.max_concurrent))
+        .unwrap_or(3);
+    let mut inner = manager.inner.lock();
+    let running = inner.controls.len();
+    let next: Vec<u64> = inner
+        .tasks
+        .values()
+        .filter(|task| task.status == DownloadStatus::Queued)
+        .map(|task| task.id)
+        .take(limit.saturating_sub(running))
+        .collect();
+    if next.is_empty() {
+        return;
+    }
+    for id in next {
+        let control = Arc::new(Control::new());
+        inner.controls.insert(id, control.clone());
+        let Some(task) = inner.tasks.get_mut(&id) else {
+            continue;
+        };
+        task.status = DownloadStatus::Running;
+        task.error = None;
+        emit(app, task, 0);
+        let task = task.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let outcome = transfer(&app, &task, &control)
+                .await
+                .unwrap_or_else(Outcome::Failed);
+            finish(&app, task.id, outcome);
+        });
+    }
+    manager.persist(&inner);
+}
+
+/// Queue a download of `url` to `dest_path`, which must be inside the
+/// download or export folder. Returns the task id.
+#[tauri::command]
+pub fn download_start(
+    app: AppHandle,
+    manager: State<'_, DownloadManager>,
+    url: String,
+    dest_path: String,
+    headers: Option<BTreeMap<String, String>>,
+    md5: Option<String>,
+) -> Result<u64, DownloadError> {
+    let dest = file_manager::resolve_output_path(&app, Path::new(&dest_path))?;
+    let mut inner = manager.inner.lock();
+    // Two tasks would append to the same `.part` file.
+    let taken = inner
+        .tasks
+        .values()
+        .any(|task| task.dest_path == dest && task.status != DownloadStatus::Completed);
+    if taken {
+        return Err(DownloadError::Duplicate(dest.display().to_string()));
+    }
+    let id = inner.next_id;
+    inner.next_id += 1;
+    inner.tasks.insert(
+        id,
+        DownloadTask {
+            id,
+            url,
+            bytes: part_len(&dest),
+            dest_path: dest,
+            headers: headers.unwrap_or_default(),
+            md5,
+            status: DownloadStatus::Queued,
+            total: None,
+            error: None,
+            created_ms: now_ms(),
+        },
+    );
+    manager.persist(&inner);
+    drop(inner);
+    pump(&app);
+    Ok(id)
+}
+
+/// Pause a queued or running task, keeping what it has downloaded.
+#[tauri::command]
+pub fn download_pause(
+    manager: State<'_, DownloadManager>,
+    task_id: u64,
+) -> Result<(), DownloadError> {
+    let mut inner = manager.inner.lock();
+    if let Some(control) = inner.controls.get(&task_id) {
+        control.request(CONTROL_PAUSE);
+        return Ok(());
+    }
+    let task = inner
+        .tasks
+        .get_mut(&task_id)
+        .ok_or_else(|| DownloadError::NotFound(task_id.to_string()))?;
+    if task.status == DownloadStatus::Queued {
+        task.status = DownloadStatus::Paused;
+        manager.persist(&inner);
+    }
+    Ok(())
+}
+
+/// Queue a paused or failed task again; it continues from its `.part` file.
+#[tauri::command]
+pub fn download_resume(
+    app: AppHandle,
+    manager: State<'_, DownloadManager>,
+    task_id: u64,
+) -> Result<(), DownloadError> {
+    let mut inner = manager.inner.lock();
+    let task = inner
+        .tasks
+        .get_mut(&task_id)
+        .ok_or_else(|| DownloadError::NotFound(task_id.to_string()))?;
+    if matches!(task.status, DownloadStatus::Paused | DownloadStatus::Failed) {
+        task.status = DownloadStatus::Queued;
+        task.error = None;
+        manager.persist(&inner);
+    }
+    drop(inner);
+    pump(&app);
+    Ok(())
+}
+
+/// Stop a task and delete its partial file; a finished task is only taken
+/// off the list, its file stays.
+#[tauri::command]
+pub fn download_cancel(
+    manager: State<'_, DownloadManager>,
+    task_id: u64,
+) -> Result<(), DownloadError> {
+    let mut inner = manager.inner.lock();
+    if let Some(control) = inner.controls.get(&task_id) {
+        control.request(CONTROL_CANCEL);
+        return Ok(());
+    }
+    let task = inner
+        .tasks
+        .remove(&task_id)
+        .ok_or_else(|| DownloadError::NotFound(task_id.to_string()))?;
+    if task.status != DownloadStatus::Completed {
+        if let Err(e) = std::fs::remove_file(part_path(&task.dest_path)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to delete partial download {}: {}", task_id, e);
+            }
+        }
+    }
+    manager.persist(&inner);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn download_list(manager: State<'_, DownloadManager>) -> Vec<DownloadTask> {
+    manager.inner.lock().tasks.values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri_plugin_http::reqwest::header::HeaderValue;
+
+    #[test]
+    fn progress_is_throttled_and_speed_measured_between_events() {
+        let start = Instant::now();
+        let mut meter = Meter::new(1_000, start);
+        assert_eq!(meter.tick(50_000, start + Duration::from_millis(200)), None);
+        assert_eq!(
+            meter.tick(501_000, start + Duration::from_millis(500)),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            meter.tick(600_000, start + Duration::from_millis(900)),
+            None
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_RANGE,
+            HeaderValue::from_static("bytes 100-999/1000"),
+        );
+        assert_eq!(content_range_total(&headers), Some(1000));
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes */*"));
+        assert_eq!(content_range_total(&headers), None);
+    }
+
+    #[test]
+    fn a_task_waiting_on_the_network_stops_when_paused() {
+        let control = Control::new();
+        let stalled = std::future::pending::<tauri_plugin_http::reqwest::Result<()>>();
+        let (step, ()) = tauri::async_runtime::block_on(async {
+            tokio::join!(interruptible(&control, stalled), async {
+                control.request(CONTROL_PAUSE)
+            })
+        });
+        assert!(matches!(step, Ok(Step::Interrupted(CONTROL_PAUSE))));
+    }
+
+    #[test]
+    fn finished_files_are_checked_for_size_and_md5() {
+        let dir = tempfile::tempdir().unwrap();
+        let part = part_path(&dir.path().join("song.flac"));
+        std::fs::write(&part, b"hello").unwrap();
+
+        assert!(verify(&part, Some(5), None).is_ok());
+        assert!(verify(&part, Some(6), None).is_err());
+        assert!(verify(&part, None, Some("5D41402ABC4B2A76B9719D911017C592")).is_ok());
+        assert!(verify(&part, Some(5), Some("00000000000000000000000000000000")).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Health probes for the user-configured API endpoints.
+//!
+//! A probe is a single GET of the endpoint's base URL through
+//! [`crate::http_util`], without retries: the point is to report what the
+//! user would see right now. Results are cached per source and every change
+//! of a source's health is broadcast as `api-endpoint-health-changed`.
+//!
+//! On desktop the endpoints are re-probed periodically as maintenance work
+//! (so the scheduler can hold it back while the user is busy or on battery),
+//! skipped while offline, and re-probed when connectivity returns.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::http_util;
+use crate::settings::http::HttpPolicy;
+use crate::settings::SettingsStore;
+
+pub const API_ENDPOINT_HEALTH_CHANGED_EVENT: &str = "api-endpoint-health-changed";
+const PROBE_TIMEOUT_MS: u64 = 5_000;
+/// Response headers checked, in order, for a server version.
+const VERSION_HEADERS: [&str; 3] = ["x-api-version", "x-version", "api-version"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiEndpointHealth {
+    pub source: String,
+    pub url: String,
+    /// Reachable and not answering with a 5xx.
+    pub healthy: bool,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    pub error: Option<String>,
+    pub checked_ms: u64,
+}
+
+static HEALTH: LazyLock<Mutex<HashMap<String, ApiEndpointHealth>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn probe_policy(app: &AppHandle) -> HttpPolicy {
+    let mut policy = app
+        .try_state::<SettingsStore>()
+        .map(|store| store.read(|s| s.http.clone()))
+        .unwrap_or_default();
+    policy.max_retries = 0;
+    policy.timeout_ms = policy.timeout_ms.min(PROBE_TIMEOUT_MS);
+    policy
+}
+
+async fn probe(source: &str, url: &str, policy: &HttpPolicy) -> ApiEndpointHealth {
+    let mut health = ApiEndpointHealth {
+        source: source.to_string(),
+        url: url.to_string(),
+        healthy: false,
+        status: None,
+        latency_ms: None,
+        version: None,
+        error: None,
+        checked_ms: now_ms(),
+    };
+    let request = match http_util::client().get(url).build() {
+        Ok(request) => request,
+        Err(e) => {
+            health.error = Some(e.to_string());
+            return health;
+        }
+    };
+    let started = Instant::now();
+    match http_util::fetch_with_policy(request, policy).await {
+        Ok(response) => {
+            let status = response.status();
+            health.healthy = !status.is_server_error();
+            health.status = Some(status.as_u16());
+            health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            health.version = VERSION_HEADERS.iter().find_map(|name| {
+                let value = response.headers().get(*name)?.to_str().ok()?;
+                Some(value.trim().to_string())
+            });
+        }
+        Err(e) => health.error = Some(e.to_string()),
+    }
+    health
+}
+
+/// Cache `health` and announce it when the source's state flipped (or it
+/// was never checked before).
+fn record(app: &AppHandle, health: ApiEndpointHealth) {
+    let changed = HEALTH
+        .lock()
+        .insert(health.source.clone(), health.clone())
+        .map_or(true, |previous| {
+            previous.healthy != health.healthy || previous.url != health.url
+        });
+    if changed {
+        let _ = app.emit(API_ENDPOINT_HEALTH_CHANGED_EVENT, health);
+    }
+}
+
+/// Drop the cached result for a source whose URL changed or was removed.
+pub(crate) fn forget(source: &str) {
+    HEALTH.lock().remove(source);
+}
+
+fn configured(app: &AppHandle) -> Vec<(String, String)> {
+    app.try_state::<SettingsStore>()
+        .map(|store| {
+            store.read(|s| {
+                s.api_endpoints
+                    .0
+                    .iter()
+                    .map(|(source, url)| (source.clone(), url.clone()))
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Probe every configured endpoint concurrently.
+pub async fn check_all(app: &AppHandle) {
+    let policy = probe_policy(app);
+    let probes = configured(app)
+        .into_iter()
+        .map(|(source, url)| {
+            let policy = policy.clone();
+            tauri::async_runtime::spawn(async move { probe(&source, &url, &policy).await })
+        })
+        .collect::<Vec<_>>();
+    for handle in probes {
+        if let Ok(health) = handle.await {
+            record(app, health);
+        }
+    }
+}
+
+/// Probe `source`'s endpoint now.
+#[tauri::command]
+pub async fn check_api_endpoint(
+    app: AppHandle,
+    source: String,
+) -> Result<ApiEndpointHealth, String> {
+    let url = configured(&app)
+        .into_iter()
+        .find_map(|(s, url)| (s == source).then_some(url))
+        .ok_or_else(|| format!("No API endpoint configured for '{}'", source))?;
+    let health = probe(&source, &url, &probe_policy(&app)).await;
+    record(&app, health.clone());
+    Ok(health)
+}
+
+/// Last known health of every probed source.
+#[tauri::command]
+pub fn get_api_endpoint_health() -> Vec<ApiEndpointHealth> {
+    let mut all: Vec<_> = HEALTH.lock().values().cloned().collect();
+    all.sort_by(|a, b| a.source.cmp(&b.source));
+    all
+}
+
+#[cfg(not(mobile))]
+mod periodic {
+    use std::time::Duration;
+
+    use log::warn;
+    use tauri::{AppHandle, Listener, Manager};
+
+    use crate::http_util::{self, NETWORK_CHANGED_EVENT};
+    use crate::tasks::{Scheduler, TaskClass};
+
+    const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+    fn schedule(app: &AppHandle) {
+        if !http_util::is_online() {
+            return;
+        }
+        let Some(scheduler) = app.try_state::<Scheduler>() else {
+            return;
+        };
+        let app = app.clone();
+        scheduler.submit("api-endpoint-health", TaskClass::Maintenance, move |_| {
+            tauri::async_runtime::block_on(super::check_all(&app));
+        });
+    }
+
+    pub fn start(app: &AppHandle) {
+        let listener = app.clone();
+        app.listen(NETWORK_CHANGED_EVENT, move |event| {
+            if serde_json::from_str::<bool>(event.payload()).unwrap_or(false) {
+                schedule(&listener);
+            }
+        });
+
+        let app = app.clone();
+        let spawned = std::thread::Builder::new()
+            .name("api-endpoint-health".into())
+            .spawn(move || loop {
+                schedule(&app);
+                std::thread::sleep(CHECK_INTERVAL);
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to spawn API endpoint health checks: {}", e);
+        }
+    }
+}
+
+/// Start periodic probing. Needs the [`crate::tasks::Scheduler`] to be managed.
+#[cfg(not(mobile))]
+pub fn start_periodic_checks(app: &AppHandle) {
+    periodic::start(app);
+}
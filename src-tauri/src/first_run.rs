@@ -0,0 +1,503 @@
+//! First-run detection and one-shot migrations from older installs.
+//!
+//! Runs as a small plugin registered ahead of `window-state`, so every file
+//! is migrated before that plugin or [`SettingsStore`](crate::settings::SettingsStore)
+//! reads it. Each migration has a stable id and runs until it succeeds once;
+//! the files it changes are copied to `migration-backup/<from-version>/`
+//! first. A failing migration is logged and skipped and shows up in
+//! [`RunContext::failed_migrations`] so the frontend can warn the user —
+//! startup itself never fails because of it.
+
+use std::path::Path;
+
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::cache_store::{self, CACHE_DB_FILE};
+use crate::settings::eq::{bands_from_gains, EqBand};
+use crate::settings::window::CloseBehavior;
+use crate::settings::{write_atomic, Settings, SETTINGS_FILE};
+
+const BACKUP_DIR: &str = "migration-backup";
+/// Version reported for installs that predate version tracking.
+const LEGACY_VERSION: &str = "legacy";
+/// The old `settingData` localStorage store, as a JSON file in the config dir.
+const LEGACY_SETTINGS_FILE: &str = "settingData.json";
+/// The old per-track lyric offsets, `{ "<track id>": <offset ms> }`, in the
+/// config dir.
+const LEGACY_LYRIC_OFFSETS_FILE: &str = "lyricOffsets.json";
+#[cfg(not(mobile))]
+const WINDOW_STATE_FILE: &str = tauri_plugin_window_state::DEFAULT_FILENAME;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationFailure {
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunContext {
+    /// Nothing from a previous install was found; show onboarding.
+    pub first_run: bool,
+    /// Version of the previous launch when it differs from this one.
+    pub upgraded_from: Option<String>,
+    /// At least one migration changed something during this launch.
+    pub migration_performed: bool,
+    pub failed_migrations: Vec<MigrationFailure>,
+    /// Previous launches in a row that never reached a ready main window.
+    #[serde(skip)]
+    pub unfinished_launches: u32,
+}
+
+/// Inputs a migration step works with. `from` names the backup folder.
+struct MigrationEnv<'a> {
+    config_dir: &'a Path,
+    /// `None` when the app data dir cannot be resolved.
+    data_dir: Option<&'a Path>,
+    from: &'a str,
+}
+
+impl MigrationEnv<'_> {
+    /// Copy `file` into the backup folder before it is modified.
+    fn backup(&self, file: &Path) -> Result<(), String> {
+        let dir = self.config_dir.join(BACKUP_DIR).join(self.from);
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let name = file
+            .file_name()
+            .ok_or_else(|| format!("Cannot back up {}", file.display()))?;
+        std::fs::copy(file, dir.join(name))
+            .map(|_| ())
+            .map_err(|e| format!("Failed to back up {}: {}", file.display(), e))
+    }
+}
+
+/// Returns whether anything was migrated. Steps must only touch `settings`
+/// on success; on error the caller discards their changes.
+type MigrationFn = fn(&MigrationEnv, &mut Settings) -> Result<bool, String>;
+
+const MIGRATIONS: &[(&str, MigrationFn)] = &[
+    ("legacy-settings", migrate_legacy_settings),
+    #[cfg(not(mobile))]
+    ("window-state-prev-position", migrate_window_state),
+    ("lyric-offsets-sqlite", migrate_lyric_offsets),
+];
+
+/// Old `settingData` store → `eq` and `window` settings sections.
+fn migrate_legacy_settings(env: &MigrationEnv, settings: &mut Settings) -> Result<bool, String> {
+    let path = env.config_dir.join(LEGACY_SETTINGS_FILE);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.to_string()),
+    };
+    let legacy: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Malformed {}: {}", LEGACY_SETTINGS_FILE, e))?;
+    env.backup(&path)?;
+    Ok(apply_legacy_settings(&legacy, settings))
+}
+
+fn apply_legacy_settings(legacy: &Value, settings: &mut Settings) -> bool {
+    // Either the store state itself or a localStorage dump keyed by store name,
+    // where the value may still be a JSON string.
+    let parsed;
+    let legacy = match legacy.get("settingData") {
+        Some(Value::String(raw)) => {
+            parsed = serde_json::from_str::<Value>(raw).unwrap_or(Value::Null);
+            &parsed
+        }
+        Some(inner) => inner,
+        None => legacy,
+    };
+
+    let mut changed = false;
+    if let Some(enabled) = legacy.get("dspEqEnabled").and_then(Value::as_bool) {
+        settings.eq.enabled = enabled;
+        changed = true;
+    }
+    if let Some(preamp) = legacy.get("dspEqPreampDb").and_then(Value::as_f64) {
+        settings.eq.preamp_db = preamp as f32;
+        changed = true;
+    }
+    if let Some(preset) = legacy.get("dspEqPreset").and_then(Value::as_str) {
+        settings.eq.preset = preset.to_string();
+        changed = true;
+    }
+    // Full frontend bands, or at least their gains on the default layout.
+    let bands = legacy.get("dspEqBands").and_then(|value| {
+        serde_json::from_value::<Vec<EqBand>>(value.clone())
+            .ok()
+            .or_else(|| {
+                let gains: Option<Vec<f32>> = value
+                    .as_array()?
+                    .iter()
+                    .map(|band| band.get("gainDb").and_then(Value::as_f64).map(|g| g as f32))
+                    .collect();
+                bands_from_gains(&gains?)
+            })
+    });
+    if let Some(bands) = bands.filter(|b| !b.is_empty()) {
+        settings.eq.bands = bands;
+        changed = true;
+    }
+    if let Some(Ok(behavior)) = legacy
+        .get("closeBehavior")
+        .map(|v| serde_json::from_value::<CloseBehavior>(v.clone()))
+    {
+        settings.window.close_behavior = behavior;
+        changed = true;
+    }
+    changed
+}
+
+/// Window-state files from older plugin versions lack `prev_x` / `prev_y`
+/// (and may store sizes as floats), which makes the current plugin discard
+/// the whole file.
+#[cfg(not(mobile))]
+fn migrate_window_state(env: &MigrationEnv, _settings: &mut Settings) -> Result<bool, String> {
+    let path = env.config_dir.join(WINDOW_STATE_FILE);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut states: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Malformed {}: {}", WINDOW_STATE_FILE, e))?;
+    if !upgrade_window_states(&mut states) {
+        return Ok(false);
+    }
+    env.backup(&path)?;
+    let json = serde_json::to_vec_pretty(&states).map_err(|e| e.to_string())?;
+    write_atomic(&path, &json).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(any(not(mobile), test))]
+fn upgrade_window_states(states: &mut Value) -> bool {
+    let Some(states) = states.as_object_mut() else {
+        return false;
+    };
+    let mut changed = false;
+    for state in states.values_mut().filter_map(Value::as_object_mut) {
+        for (key, unsigned) in [
+            ("width", true),
+            ("height", true),
+            ("x", false),
+            ("y", false),
+        ] {
+            let Some(value) = state.get(key) else {
+                continue;
+            };
+            if value.is_f64() {
+                let rounded = value.as_f64().unwrap_or_default().round();
+                state.insert(
+                    key.into(),
+                    if unsigned {
+                        Value::from(rounded.max(0.0) as u32)
+                    } else {
+                        Value::from(rounded as i32)
+                    },
+                );
+                changed = true;
+            }
+        }
+        for (prev, current) in [("prev_x", "x"), ("prev_y", "y")] {
+            if !state.contains_key(prev) {
+                let value = state.get(current).cloned().unwrap_or(Value::from(0));
+                state.insert(prev.into(), value);
+                changed = true;
+            }
+        }
+        for (key, default) in [
+            ("maximized", false),
+            ("visible", true),
+            ("decorated", true),
+            ("fullscreen", false),
+        ] {
+            if !state.contains_key(key) {
+                state.insert(key.into(), Value::from(default));
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Old lyric offsets file → `lyric_offsets` table of the cache database.
+/// The file itself is left in place.
+fn migrate_lyric_offsets(env: &MigrationEnv, _settings: &mut Settings) -> Result<bool, String> {
+    let path = env.config_dir.join(LEGACY_LYRIC_OFFSETS_FILE);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.to_string()),
+    };
+    let legacy: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Malformed {}: {}", LEGACY_LYRIC_OFFSETS_FILE, e))?;
+    let offsets = legacy_lyric_offsets(&legacy);
+    if offsets.is_empty() {
+        return Ok(false);
+    }
+    let data_dir = env
+        .data_dir
+        .ok_or("No app data dir for the lyric offsets")?;
+    env.backup(&path)?;
+    std::fs::create_dir_all(data_dir).map_err(|e| e.to_string())?;
+    let added = cache_store::import_lyric_offsets(&data_dir.join(CACHE_DB_FILE), &offsets)?;
+    Ok(added > 0)
+}
+
+/// Track id → offset pairs, from the file itself or a localStorage dump
+/// keyed `lyricOffsets` whose value may still be a JSON string. Entries
+/// that are not a number of milliseconds are dropped.
+fn legacy_lyric_offsets(legacy: &Value) -> Vec<(String, i64)> {
+    let parsed;
+    let legacy = match legacy.get("lyricOffsets") {
+        Some(Value::String(raw)) => {
+            parsed = serde_json::from_str::<Value>(raw).unwrap_or(Value::Null);
+            &parsed
+        }
+        Some(inner) => inner,
+        None => legacy,
+    };
+    let Some(offsets) = legacy.as_object() else {
+        return Vec::new();
+    };
+    offsets
+        .iter()
+        .filter_map(|(track_id, offset)| {
+            let offset = offset.as_f64().filter(|o| o.is_finite())?;
+            Some((track_id.clone(), offset.round() as i64))
+        })
+        .collect()
+}
+
+fn run_context(
+    previous: Option<&str>,
+    current: &str,
+    settings_existed: bool,
+    migration_performed: bool,
+) -> RunContext {
+    let upgraded_from = match previous {
+        Some(previous) if previous != current => Some(previous.to_string()),
+        Some(_) => None,
+        None if settings_existed || migration_performed => Some(LEGACY_VERSION.to_string()),
+        None => None,
+    };
+    RunContext {
+        first_run: previous.is_none() && !settings_existed && !migration_performed,
+        upgraded_from,
+        migration_performed,
+        failed_migrations: Vec::new(),
+        unfinished_launches: 0,
+    }
+}
+
+fn prepare(app: &AppHandle) -> RunContext {
+    let config_dir = match app.path().app_config_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!(
+                "Failed to resolve app config dir, skipping migrations: {}",
+                e
+            );
+            return RunContext::default();
+        }
+    };
+    let current = app.package_info().version.to_string();
+    let settings_path = config_dir.join(SETTINGS_FILE);
+    let settings_existed = settings_path.exists();
+
+    let mut settings = if settings_existed {
+        let parsed = std::fs::read(&settings_path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                serde_json::from_slice::<Settings>(&bytes).map_err(|e| e.to_string())
+            });
+        match parsed {
+            Ok(settings) => settings,
+            Err(e) => {
+                // Never overwrite a settings file we could not read.
+                warn!(
+                    "Skipping migrations, {} is unreadable: {}",
+                    SETTINGS_FILE, e
+                );
+                let mut context = run_context(None, &current, true, false);
+                context.failed_migrations.push(MigrationFailure {
+                    id: "read-settings".into(),
+                    error: e,
+                });
+                return context;
+            }
+        }
+    } else {
+        Settings::default()
+    };
+
+    let previous = settings.run.last_version.clone();
+    let data_dir = app.path().app_data_dir().ok();
+    let env = MigrationEnv {
+        config_dir: &config_dir,
+        data_dir: data_dir.as_deref(),
+        from: previous.as_deref().unwrap_or(LEGACY_VERSION),
+    };
+    let mut performed = false;
+    let mut failures = Vec::new();
+    for (id, migrate) in MIGRATIONS {
+        if settings.run.completed_migrations.contains(*id) {
+            continue;
+        }
+        let mut next = settings.clone();
+        match migrate(&env, &mut next) {
+            Ok(changed) => {
+                if changed {
+                    info!("Migration '{}' applied", id);
+                }
+                performed |= changed;
+                settings = next;
+                settings.run.completed_migrations.insert(id.to_string());
+            }
+            Err(error) => {
+                warn!("Migration '{}' failed, skipping: {}", id, error);
+                failures.push(MigrationFailure {
+                    id: id.to_string(),
+                    error,
+                });
+            }
+        }
+    }
+
+    if performed && settings_existed {
+        if let Err(e) = env.backup(&settings_path) {
+            warn!("{}", e);
+        }
+    }
+    settings.run.last_version = Some(current.clone());
+    let unfinished_launches = settings.run.unfinished_launches;
+    // Cleared again once the main window is ready; see `safe_mode`.
+    #[cfg(not(mobile))]
+    {
+        settings.run.unfinished_launches = unfinished_launches.saturating_add(1);
+    }
+    let result = serde_json::to_vec_pretty(&settings)
+        .map_err(|e| e.to_string())
+        .and_then(|json| write_atomic(&settings_path, &json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to record run version: {}", e);
+    }
+
+    let mut context = run_context(previous.as_deref(), &current, settings_existed, performed);
+    context.failed_migrations = failures;
+    context.unfinished_launches = unfinished_launches;
+    if let Some(from) = &context.upgraded_from {
+        info!("Upgraded from {} to {}", from, current);
+    }
+    context
+}
+
+/// Register before `tauri_plugin_window_state` and `safe_mode`.
+pub fn init() -> TauriPlugin<Wry> {
+    PluginBuilder::new("first-run")
+        .setup(|app, _api| {
+            let context = prepare(app);
+            app.manage(context);
+            Ok(())
+        })
+        .build()
+}
+
+#[tauri::command]
+pub fn get_run_context(context: State<'_, RunContext>) -> RunContext {
+    context.inner().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn run_context_distinguishes_fresh_installs_and_upgrades() {
+        let fresh = run_context(None, "0.2.0", false, false);
+        assert!(fresh.first_run);
+        assert_eq!(fresh.upgraded_from, None);
+
+        let legacy = run_context(None, "0.2.0", true, false);
+        assert!(!legacy.first_run);
+        assert_eq!(legacy.upgraded_from.as_deref(), Some(LEGACY_VERSION));
+
+        let upgrade = run_context(Some("0.1.1"), "0.2.0", true, false);
+        assert_eq!(upgrade.upgraded_from.as_deref(), Some("0.1.1"));
+        assert!(run_context(Some("0.2.0"), "0.2.0", true, false)
+            .upgraded_from
+            .is_none());
+    }
+
+    #[test]
+    fn legacy_settings_map_onto_sections() {
+        let store = json!({
+            "dspEqEnabled": false,
+            "dspEqPreampDb": -3.0,
+            "dspEqPreset": "rock",
+            "dspEqBands": [
+                { "enabled": true, "filterType": "lowShelf", "frequency": 31, "gainDb": 1.5, "q": 1.414 },
+                { "enabled": false, "filterType": "peaking", "frequency": 80, "gainDb": -2.0, "q": 2.0 }
+            ],
+            "closeBehavior": "tray",
+            "theme": "dark"
+        });
+        let dump = json!({ "settingData": store.to_string() });
+
+        for legacy in [store, dump] {
+            let mut settings = Settings::default();
+            assert!(apply_legacy_settings(&legacy, &mut settings));
+            assert!(!settings.eq.enabled);
+            assert_eq!(settings.eq.preamp_db, -3.0);
+            assert_eq!(settings.eq.preset, "rock");
+            let gains: Vec<f32> = settings.eq.bands.iter().map(|b| b.gain_db).collect();
+            assert_eq!(gains, vec![1.5, -2.0]);
+            assert_eq!(settings.eq.bands[1].frequency, 80.0);
+            assert!(!settings.eq.bands[1].enabled);
+            assert_eq!(settings.window.close_behavior, CloseBehavior::Hide);
+        }
+        assert!(!apply_legacy_settings(
+            &json!({ "theme": "dark" }),
+            &mut Settings::default()
+        ));
+    }
+
+    #[test]
+    fn legacy_lyric_offsets_accept_the_file_and_a_dump() {
+        let file = json!({ "1": 500, "2": -250.4, "3": "fast" });
+        let dump = json!({ "lyricOffsets": file.to_string() });
+        for legacy in [file, dump] {
+            let mut offsets = legacy_lyric_offsets(&legacy);
+            offsets.sort();
+            assert_eq!(offsets, vec![("1".into(), 500), ("2".into(), -250)]);
+        }
+        assert!(legacy_lyric_offsets(&json!([1, 2])).is_empty());
+    }
+
+    #[test]
+    fn old_window_states_gain_missing_fields() {
+        let mut states = json!({
+            "main": { "width": 1280.4, "height": 800.0, "x": 10, "y": 20, "maximized": true },
+            "mini-player": {
+                "width": 300, "height": 120, "x": 0, "y": 0, "prev_x": 0, "prev_y": 0,
+                "maximized": false, "visible": true, "decorated": true, "fullscreen": false
+            }
+        });
+        assert!(upgrade_window_states(&mut states));
+        let main = &states["main"];
+        assert_eq!(main["width"], json!(1280));
+        assert_eq!(main["prev_x"], json!(10));
+        assert_eq!(main["prev_y"], json!(20));
+        assert_eq!(main["maximized"], json!(true));
+        assert_eq!(main["decorated"], json!(true));
+        assert!(!upgrade_window_states(&mut states));
+    }
+}
@@ -0,0 +1,416 @@
+//! The listening report export.
+//!
+//! Plays are recorded in the cache store's `play_history` table (see
+//! [`crate::cache_store::history_add`]). [`export_history`] steps through
+//! the matching rows on a connection of its own and writes CSV or JSON to
+//! the destination, so even very long histories are never held in memory
+//! at once.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::oneshot;
+
+use crate::cache_store::CacheStore;
+use crate::desktop::file_manager::{self, FileManagerError};
+use crate::tasks::{Scheduler, TaskClass, TaskContext};
+
+/// Scheduler task name; its progress counts rows written.
+pub const HISTORY_EXPORT_TASK: &str = "history-export";
+/// Rows written between progress reports and cancellation checks.
+const PROGRESS_EVERY: u64 = 2_000;
+
+/// One exported play.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    /// Unix ms at which playback started.
+    pub played_at_ms: i64,
+    pub track: String,
+    pub artist: String,
+    pub album: String,
+    /// Where the track came from: "local", "netease", ...
+    pub source: String,
+    /// How long it was actually listened to.
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+/// Inclusive `played_at_ms` bounds; `None` is open-ended.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryRange {
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+}
+
+impl HistoryRange {
+    fn bounds(&self) -> (i64, i64) {
+        (
+            self.from_ms.unwrap_or(i64::MIN),
+            self.to_ms.unwrap_or(i64::MAX),
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum HistoryExportError {
+    /// Destination folder is outside the allowed roots or missing.
+    InvalidDestination(String),
+    /// Another export is still running.
+    Busy(String),
+    Cancelled(String),
+    Io(String),
+}
+
+impl fmt::Display for HistoryExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDestination(message) => write!(f, "Invalid destination: {}", message),
+            Self::Busy(message) | Self::Cancelled(message) | Self::Io(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for HistoryExportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for HistoryExportError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<FileManagerError> for HistoryExportError {
+    fn from(e: FileManagerError) -> Self {
+        Self::InvalidDestination(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportSummary {
+    pub path: PathBuf,
+    pub rows_written: u64,
+}
+
+/// Managed export state.
+#[derive(Default)]
+pub struct HistoryState {
+    exporting: AtomicBool,
+    cancel: AtomicBool,
+}
+
+/// Clears the `exporting` flag however the export ends.
+struct ExportGuard<'a>(&'a HistoryState);
+
+impl Drop for ExportGuard<'_> {
+    fn drop(&mut self) {
+        self.0.exporting.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Replaces names with salted hashes. The salt is random per export, so the
+/// same track matches within one report but not across reports.
+struct Anonymizer {
+    salt: [u8; 16],
+}
+
+impl Anonymizer {
+    fn new() -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self { salt }
+    }
+
+    fn apply(&self, value: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+        hasher.update(value.as_bytes());
+        hex::encode(&hasher.finalize()[..8])
+    }
+}
+
+const CSV_HEADER: &str = "timestamp,track,artist,album,source,duration_ms";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+struct ExportOptions<'a> {
+    format: HistoryFormat,
+    range: HistoryRange,
+    anonymizer: Option<&'a Anonymizer>,
+}
+
+const COUNT_SQL: &str =
+    "SELECT COUNT(*) FROM play_history WHERE played_at >= ?1 AND played_at <= ?2";
+/// Plays without a recorded title export their song id instead.
+const SELECT_SQL: &str = "SELECT played_at,
+        CASE WHEN track = '' THEN song_id ELSE track END,
+        artist, album, source, duration_listened
+     FROM play_history WHERE played_at >= ?1 AND played_at <= ?2
+     ORDER BY played_at, id";
+
+/// Stream the plays in `options.range` from `conn` to `output`.
+/// `on_progress` gets the rows written and the total every
+/// [`PROGRESS_EVERY`] rows and may abort by returning `false`.
+fn export_rows(
+    conn: &rusqlite::Connection,
+    output: &mut impl Write,
+    options: &ExportOptions<'_>,
+    mut on_progress: impl FnMut(u64, u64) -> bool,
+) -> Result<u64, HistoryExportError> {
+    let (from, to) = options.range.bounds();
+    let total: u64 = conn.query_row(COUNT_SQL, [from, to], |row| row.get(0))?;
+    let mut stmt = conn.prepare(SELECT_SQL)?;
+    let mut records = stmt.query_map([from, to], |row| {
+        Ok(HistoryRecord {
+            played_at_ms: row.get(0)?,
+            track: row.get(1)?,
+            artist: row.get(2)?,
+            album: row.get(3)?,
+            source: row.get(4)?,
+            duration_ms: row.get(5)?,
+        })
+    })?;
+    let mut rows = 0u64;
+    match options.format {
+        HistoryFormat::Csv => writeln!(output, "{}", CSV_HEADER)?,
+        HistoryFormat::Json => write!(output, "[")?,
+    }
+    for record in &mut records {
+        let mut record = record?;
+        if let Some(anonymizer) = options.anonymizer {
+            record.track = anonymizer.apply(&record.track);
+            record.artist = anonymizer.apply(&record.artist);
+            record.album = anonymizer.apply(&record.album);
+        }
+        match options.format {
+            HistoryFormat::Csv => writeln!(
+                output,
+                "{},{},{},{},{},{}",
+                record.played_at_ms,
+                csv_field(&record.track),
+                csv_field(&record.artist),
+                csv_field(&record.album),
+                csv_field(&record.source),
+                record.duration_ms
+            )?,
+            HistoryFormat::Json => {
+                if rows > 0 {
+                    write!(output, ",")?;
+                }
+                write!(output, "\n  ")?;
+                serde_json::to_writer(&mut *output, &record)
+                    .map_err(|e| HistoryExportError::Io(e.to_string()))?;
+            }
+        }
+        rows += 1;
+        if rows % PROGRESS_EVERY == 0 && !on_progress(rows, total) {
+            return Err(HistoryExportError::Cancelled(
+                "History export cancelled".into(),
+            ));
+        }
+    }
+    if options.format == HistoryFormat::Json {
+        write!(output, "{}]", if rows > 0 { "\n" } else { "" })?;
+    }
+    Ok(rows)
+}
+
+/// Write the playback history in `range` to `dest_path` as CSV or JSON.
+/// Runs as the [`HISTORY_EXPORT_TASK`] scheduler task, which reports its
+/// progress.
+#[tauri::command]
+pub async fn export_history(
+    app: AppHandle,
+    format: HistoryFormat,
+    range: HistoryRange,
+    dest_path: String,
+    anonymize: bool,
+) -> Result<HistoryExportSummary, HistoryExportError> {
+    let dest = file_manager::resolve_output_path(&app, Path::new(&dest_path))?;
+    let scheduler = app
+        .try_state::<Scheduler>()
+        .ok_or_else(|| HistoryExportError::Io("Task scheduler unavailable".into()))?;
+    let (done, result) = oneshot::channel();
+    let task_app = app.clone();
+    scheduler.submit(HISTORY_EXPORT_TASK, TaskClass::Interactive, move |ctx| {
+        let _ = done.send(run_export(&task_app, dest, format, range, anonymize, ctx));
+    });
+    result
+        .await
+        .map_err(|_| HistoryExportError::Io("History export task stopped".into()))?
+}
+
+fn run_export(
+    app: &AppHandle,
+    dest: PathBuf,
+    format: HistoryFormat,
+    range: HistoryRange,
+    anonymize: bool,
+    ctx: &TaskContext,
+) -> Result<HistoryExportSummary, HistoryExportError> {
+    let state = app.state::<HistoryState>();
+    if state.exporting.swap(true, Ordering::SeqCst) {
+        return Err(HistoryExportError::Busy(
+            "A history export is already running".into(),
+        ));
+    }
+    let _guard = ExportGuard(&state);
+    state.cancel.store(false, Ordering::SeqCst);
+
+    let conn = app
+        .try_state::<CacheStore>()
+        .ok_or_else(|| HistoryExportError::Io("Cache store unavailable".into()))?
+        .reader()
+        .map_err(HistoryExportError::Io)?;
+
+    let anonymizer = anonymize.then(Anonymizer::new);
+    let options = ExportOptions {
+        format,
+        range,
+        anonymizer: anonymizer.as_ref(),
+    };
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut output = BufWriter::new(File::create(&tmp)?);
+    let result = export_rows(&conn, &mut output, &options, |rows_written, total_rows| {
+        ctx.report_progress(rows_written, Some(total_rows));
+        !state.cancel.load(Ordering::SeqCst)
+    })
+    .and_then(|rows| {
+        output.flush()?;
+        Ok(rows)
+    });
+    drop(output);
+    let rows_written = match result {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&tmp, &dest)?;
+    Ok(HistoryExportSummary {
+        path: dest,
+        rows_written,
+    })
+}
+
+/// Ask a running export to stop; the partial file is removed.
+#[tauri::command]
+pub fn cancel_history_export(state: State<'_, HistoryState>) {
+    state.cancel.store(true, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plays() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE play_history (
+                id INTEGER PRIMARY KEY,
+                song_id TEXT NOT NULL,
+                played_at INTEGER NOT NULL,
+                duration_listened INTEGER NOT NULL,
+                track TEXT NOT NULL DEFAULT '',
+                artist TEXT NOT NULL DEFAULT '',
+                album TEXT NOT NULL DEFAULT '',
+                source TEXT NOT NULL DEFAULT ''
+            );
+            INSERT INTO play_history VALUES
+                (1, '1', 2000, 7, 'Other', 'B \"the\" band', '', ''),
+                (2, '2', 1000, 5, 'Song, Part 1', 'A', 'X', 'local'),
+                (3, '42', 3000, 0, '', 'C', '', '');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn run(
+        format: HistoryFormat,
+        range: HistoryRange,
+        anonymizer: Option<&Anonymizer>,
+    ) -> (String, u64) {
+        let options = ExportOptions {
+            format,
+            range,
+            anonymizer,
+        };
+        let mut out = Vec::new();
+        let rows = export_rows(&plays(), &mut out, &options, |_, _| true).unwrap();
+        (String::from_utf8(out).unwrap(), rows)
+    }
+
+    #[test]
+    fn csv_is_escaped_and_range_filtered() {
+        let range = HistoryRange {
+            from_ms: None,
+            to_ms: Some(2000),
+        };
+        let (csv, rows) = run(HistoryFormat::Csv, range, None);
+        assert_eq!(rows, 2);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "1000,\"Song, Part 1\",A,X,local,5");
+        assert_eq!(lines[2], "2000,Other,\"B \"\"the\"\" band\",,,7");
+    }
+
+    #[test]
+    fn json_output_parses_and_anonymizes() {
+        let anonymizer = Anonymizer::new();
+        let (json, rows) = run(
+            HistoryFormat::Json,
+            HistoryRange::default(),
+            Some(&anonymizer),
+        );
+        assert_eq!(rows, 3);
+        let parsed: Vec<HistoryRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0].track, anonymizer.apply("Song, Part 1"));
+        assert_ne!(parsed[0].track, "Song, Part 1");
+        assert_eq!(parsed[2].track, anonymizer.apply("42"));
+        assert_eq!(parsed[2].album, "");
+
+        let (empty, _) = run(
+            HistoryFormat::Json,
+            HistoryRange {
+                from_ms: Some(10_000),
+                to_ms: None,
+            },
+            None,
+        );
+        assert_eq!(empty, "[]");
+    }
+}
@@ -0,0 +1,450 @@
+//! Shared HTTP plumbing for backend modules.
+//!
+//! Every backend caller goes through [`fetch_with_policy`] so that an
+//! upstream outage is handled once instead of being hammered by each
+//! module's own retry loop:
+//!
+//! - transient failures (transport errors, 429, 5xx) are retried with
+//!   exponential backoff and jitter;
+//! - each host has a circuit breaker that opens after
+//!   `failure_threshold` consecutive failures and lets a single probe
+//!   through once `open_cooldown_ms` has passed (half-open);
+//! - each host has a concurrency cap.
+//!
+//! Counters are exposed to the frontend through `get_http_stats`. Circuits
+//! are reset when connectivity changes (`notify_network_changed`), since a
+//! failure streak from the previous network says nothing about the new one.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest::{self, header::RETRY_AFTER, Request, Response, StatusCode};
+use tokio::sync::Semaphore;
+
+use crate::settings::http::HttpPolicy;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+/// Returns redirects as responses instead of following them.
+static NO_REDIRECT_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("static HTTP client config is valid")
+});
+
+pub const NETWORK_CHANGED_EVENT: &str = "network-changed";
+
+static ONLINE: AtomicBool = AtomicBool::new(true);
+
+static HOSTS: LazyLock<Mutex<HashMap<String, HostState>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug)]
+pub enum HttpError {
+    /// The host's circuit is open; the request was not sent.
+    CircuitOpen {
+        host: String,
+    },
+    /// The URL has no host to key the breaker on.
+    InvalidUrl(String),
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CircuitOpen { host } => {
+                write!(
+                    f,
+                    "Requests to '{}' are paused after repeated failures",
+                    host
+                )
+            }
+            Self::InvalidUrl(url) => write!(f, "Invalid request URL '{}'", url),
+            Self::Request(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Circuit {
+    Closed,
+    Open {
+        until: Instant,
+    },
+    /// A probe is in flight. If its future is dropped before reporting
+    /// back, another probe is allowed once `since + cooldown` has passed.
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+#[derive(Debug)]
+struct Breaker {
+    circuit: Circuit,
+    consecutive_failures: u32,
+}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Self {
+            circuit: Circuit::Closed,
+            consecutive_failures: 0,
+        }
+    }
+}
+
+impl Breaker {
+    fn state(&self) -> CircuitState {
+        match self.circuit {
+            Circuit::Closed => CircuitState::Closed,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Whether a request may be sent now.
+    fn try_acquire(&mut self, now: Instant, cooldown: Duration) -> bool {
+        match self.circuit {
+            Circuit::Closed => true,
+            Circuit::Open { until } if now < until => false,
+            Circuit::HalfOpen { since } if now < since + cooldown => false,
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => {
+                self.circuit = Circuit::HalfOpen { since: now };
+                true
+            }
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.circuit = Circuit::Closed;
+    }
+
+    /// Returns whether this failure opened the circuit.
+    fn record_failure(&mut self, now: Instant, threshold: u32, cooldown: Duration) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let open = match self.circuit {
+            Circuit::HalfOpen { .. } => true,
+            Circuit::Closed => self.consecutive_failures >= threshold,
+            Circuit::Open { .. } => false,
+        };
+        if open {
+            self.circuit = Circuit::Open {
+                until: now + cooldown,
+            };
+        }
+        open
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpHostStats {
+    pub successes: u64,
+    pub failures: u64,
+    pub retries: u64,
+    /// Requests rejected without being sent because the circuit was open.
+    pub rejected_open: u64,
+    pub circuit: Option<CircuitState>,
+}
+
+struct HostState {
+    breaker: Breaker,
+    limit: usize,
+    permits: Arc<Semaphore>,
+    stats: HttpHostStats,
+}
+
+impl HostState {
+    fn new(limit: usize) -> Self {
+        Self {
+            breaker: Breaker::default(),
+            limit,
+            permits: Arc::new(Semaphore::new(limit)),
+            stats: HttpHostStats::default(),
+        }
+    }
+}
+
+/// Shared client for building requests passed to [`fetch_with_policy`].
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
+
+fn with_host<T>(host: &str, policy: &HttpPolicy, f: impl FnOnce(&mut HostState) -> T) -> T {
+    let mut hosts = HOSTS.lock();
+    let state = hosts
+        .entry(host.to_string())
+        .or_insert_with(|| HostState::new(policy.max_concurrent_per_host));
+    if state.limit != policy.max_concurrent_per_host {
+        // Requests holding permits of the old semaphore finish normally.
+        state.limit = policy.max_concurrent_per_host;
+        state.permits = Arc::new(Semaphore::new(state.limit));
+    }
+    f(state)
+}
+
+/// Backoff before retry number `retry` (0-based): exponential, capped at
+/// `max_delay_ms`, with the upper half randomized by `jitter` in `[0, 1]`.
+fn backoff_delay(policy: &HttpPolicy, retry: u32, jitter: f64) -> Duration {
+    let exp = policy
+        .base_delay_ms
+        .saturating_mul(1u64 << retry.min(20))
+        .min(policy.max_delay_ms);
+    let half = exp / 2;
+    Duration::from_millis(half + (half as f64 * jitter.clamp(0.0, 1.0)) as u64)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` in seconds; the HTTP-date form is ignored.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+enum Outcome {
+    Success,
+    Failure,
+}
+
+fn record(host: &str, policy: &HttpPolicy, outcome: Outcome) {
+    let cooldown = Duration::from_millis(policy.open_cooldown_ms);
+    with_host(host, policy, |state| match outcome {
+        Outcome::Success => {
+            state.stats.successes += 1;
+            state.breaker.record_success();
+        }
+        Outcome::Failure => {
+            state.stats.failures += 1;
+            let now = Instant::now();
+            if state
+                .breaker
+                .record_failure(now, policy.failure_threshold, cooldown)
+            {
+                warn!(
+                    "HTTP circuit for '{}' opened after {} consecutive failures",
+                    host, state.breaker.consecutive_failures
+                );
+            }
+        }
+    });
+}
+
+/// Send `request` under `policy`.
+///
+/// Retries only when the request can be cloned (bodies that are streams
+/// get a single attempt). A final 429/5xx response is returned as-is so the
+/// caller can inspect it; only transport errors and open circuits are `Err`.
+pub async fn fetch_with_policy(
+    request: Request,
+    policy: &HttpPolicy,
+) -> Result<Response, HttpError> {
+    send(&CLIENT, request, policy).await
+}
+
+/// [`fetch_with_policy`] without following redirects: a 3xx comes back as
+/// the response, for callers that need the headers of every hop.
+pub async fn fetch_hop_with_policy(
+    request: Request,
+    policy: &HttpPolicy,
+) -> Result<Response, HttpError> {
+    send(&NO_REDIRECT_CLIENT, request, policy).await
+}
+
+async fn send(
+    client: &reqwest::Client,
+    request: Request,
+    policy: &HttpPolicy,
+) -> Result<Response, HttpError> {
+    let host = request
+        .url()
+        .host_str()
+        .map(str::to_string)
+        .ok_or_else(|| HttpError::InvalidUrl(request.url().to_string()))?;
+    let cooldown = Duration::from_millis(policy.open_cooldown_ms);
+    let timeout = Duration::from_millis(policy.timeout_ms);
+    let mut attempt = request;
+    let mut retry = 0;
+
+    loop {
+        let next = attempt.try_clone();
+        *attempt.timeout_mut() = Some(timeout);
+
+        let permits = with_host(&host, policy, |state| {
+            if state.breaker.try_acquire(Instant::now(), cooldown) {
+                Ok(Arc::clone(&state.permits))
+            } else {
+                state.stats.rejected_open += 1;
+                Err(HttpError::CircuitOpen { host: host.clone() })
+            }
+        })?;
+        let permit = permits
+            .acquire_owned()
+            .await
+            .expect("http host semaphore is never closed");
+        let result = client.execute(attempt).await;
+        drop(permit);
+
+        let (retryable, server_hint) = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                record(&host, policy, Outcome::Failure);
+                (true, retry_after(response))
+            }
+            Ok(_) => {
+                record(&host, policy, Outcome::Success);
+                (false, None)
+            }
+            Err(e) if e.is_builder() => (false, None),
+            Err(_) => {
+                record(&host, policy, Outcome::Failure);
+                (true, None)
+            }
+        };
+
+        let Some(next) = next.filter(|_| retryable && retry < policy.max_retries) else {
+            return result.map_err(HttpError::Request);
+        };
+
+        let delay = backoff_delay(policy, retry, rand::thread_rng().gen())
+            .max(server_hint.unwrap_or_default())
+            .min(Duration::from_millis(policy.max_delay_ms));
+        with_host(&host, policy, |state| state.stats.retries += 1);
+        retry += 1;
+        tokio::time::sleep(delay).await;
+        attempt = next;
+    }
+}
+
+/// Close every circuit and clear failure streaks.
+pub fn reset_circuits() {
+    let mut hosts = HOSTS.lock();
+    for state in hosts.values_mut() {
+        state.breaker = Breaker::default();
+    }
+    if !hosts.is_empty() {
+        info!("HTTP circuits reset");
+    }
+}
+
+/// Per-host request counters and circuit state.
+#[tauri::command]
+pub fn get_http_stats() -> BTreeMap<String, HttpHostStats> {
+    HOSTS
+        .lock()
+        .iter()
+        .map(|(host, state)| {
+            let mut stats = state.stats.clone();
+            stats.circuit = Some(state.breaker.state());
+            (host.clone(), stats)
+        })
+        .collect()
+}
+
+/// Whether the frontend last reported the network as reachable.
+pub fn is_online() -> bool {
+    ONLINE.load(Ordering::SeqCst)
+}
+
+/// Reported by the frontend on `online` / `offline` events. Backend
+/// listeners get [`NETWORK_CHANGED_EVENT`] with the new state.
+#[tauri::command]
+pub fn notify_network_changed(app: AppHandle, online: Option<bool>) {
+    let online = online.unwrap_or(true);
+    ONLINE.store(online, Ordering::SeqCst);
+    reset_circuits();
+    let _ = app.emit(NETWORK_CHANGED_EVENT, online);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn breaker_opens_after_threshold_and_half_opens_after_cooldown() {
+        let start = Instant::now();
+        let mut breaker = Breaker::default();
+        assert!(!breaker.record_failure(start, 3, COOLDOWN));
+        assert!(!breaker.record_failure(start, 3, COOLDOWN));
+        assert!(breaker.record_failure(start, 3, COOLDOWN));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire(start + Duration::from_secs(10), COOLDOWN));
+
+        // One probe after the cooldown, everything else keeps waiting.
+        let probe_at = start + COOLDOWN;
+        assert!(breaker.try_acquire(probe_at, COOLDOWN));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(!breaker.try_acquire(probe_at, COOLDOWN));
+
+        // A failed probe reopens immediately; a successful one closes.
+        assert!(breaker.record_failure(probe_at, 3, COOLDOWN));
+        assert!(breaker.try_acquire(probe_at + COOLDOWN, COOLDOWN));
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire(probe_at + COOLDOWN, COOLDOWN));
+    }
+
+    #[test]
+    fn success_resets_failure_streak() {
+        let now = Instant::now();
+        let mut breaker = Breaker::default();
+        breaker.record_failure(now, 2, COOLDOWN);
+        breaker.record_success();
+        assert!(!breaker.record_failure(now, 2, COOLDOWN));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_within_bounds() {
+        let policy = HttpPolicy {
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+            ..HttpPolicy::default()
+        };
+        assert_eq!(backoff_delay(&policy, 0, 0.0), Duration::from_millis(50));
+        assert_eq!(backoff_delay(&policy, 0, 1.0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(&policy, 2, 1.0), Duration::from_millis(400));
+        assert_eq!(
+            backoff_delay(&policy, 10, 1.0),
+            Duration::from_millis(1_000)
+        );
+        assert_eq!(backoff_delay(&policy, 31, 0.5), Duration::from_millis(750));
+    }
+
+    #[test]
+    fn only_throttling_and_server_errors_are_retried() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::OK));
+    }
+}
@@ -1,15 +1,49 @@
 pub mod algorithms;
+pub mod api_client;
+pub mod cache_store;
+pub mod cover_cache;
+pub mod covers;
+pub mod debounce;
+pub mod endpoint_health;
+pub mod first_run;
+pub mod http_util;
+pub mod lyrics;
+pub mod playback_clock;
+pub mod queue_store;
+#[cfg(not(mobile))]
+pub mod remote;
+pub mod settings;
 pub mod shared;
+#[cfg(not(mobile))]
+pub mod shutdown;
+pub mod storage;
 
 #[cfg(not(mobile))]
 pub mod desktop;
 
+#[cfg(not(mobile))]
+pub mod downloads;
+
+#[cfg(not(mobile))]
+pub mod history;
+
+#[cfg(not(mobile))]
+pub mod library;
+
+#[cfg(not(mobile))]
+pub mod tasks;
+
 #[cfg(mobile)]
 pub mod mobile;
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+#[cfg(not(mobile))]
+pub use desktop::{build_app, handle_run_event};
+#[cfg(mobile)]
+pub use mobile::{build_app, handle_run_event};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     #[cfg(not(mobile))]
@@ -0,0 +1,294 @@
+//! Local music library: watched folders and the persisted track index.
+//!
+//! The index lives in `library.json` under the app data dir. Entries get a
+//! stable numeric id on first sight; moves and renames keep the id (and with
+//! it play counts and resume positions). A background watcher keeps the
+//! index in sync with the watched folders, see [`watcher`].
+
+mod reconcile;
+mod scan;
+mod watcher;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::debounce::Debounced;
+use crate::settings::write_atomic;
+
+pub(crate) use scan::{is_audio_file, read_tags, AUDIO_EXTENSIONS};
+pub use watcher::start_watcher;
+
+const LIBRARY_FILE: &str = "library.json";
+const LIBRARY_CHANGED_EVENT: &str = "library-changed";
+
+/// Playback progress arrives every few seconds per track; the index is
+/// rewritten once it settles, or on quit.
+static PROGRESS_SAVER: Debounced = Debounced::new("library-save", Duration::from_secs(10));
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrackTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    pub id: u64,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_ms: u64,
+    /// Hash of the file size plus its first and last blocks; identifies a
+    /// file across moves without reading it whole.
+    pub fingerprint: u64,
+    #[serde(default)]
+    pub tags: TrackTags,
+    #[serde(default)]
+    pub play_count: u32,
+    #[serde(default)]
+    pub last_position_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct LibraryIndex {
+    folders: Vec<PathBuf>,
+    next_id: u64,
+    entries: BTreeMap<u64, LibraryEntry>,
+}
+
+/// Payload of `library-changed`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryChanges {
+    pub added: Vec<u64>,
+    pub removed: Vec<u64>,
+    pub updated: Vec<u64>,
+}
+
+impl LibraryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Managed library state.
+pub struct LibraryState {
+    path: Option<PathBuf>,
+    index: Mutex<LibraryIndex>,
+    paused: AtomicBool,
+    watcher_tx: Mutex<Option<Sender<watcher::WatchMsg>>>,
+}
+
+impl LibraryState {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(LIBRARY_FILE)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app data dir, library will not persist: {}",
+                    e
+                );
+                None
+            }
+        };
+        let index = path
+            .as_deref()
+            .and_then(read_index_file)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            index: Mutex::new(index),
+            paused: AtomicBool::new(false),
+            watcher_tx: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn folders(&self) -> Vec<PathBuf> {
+        self.index.lock().folders.clone()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    fn notify_watcher(&self, msg: watcher::WatchMsg) {
+        if let Some(tx) = self.watcher_tx.lock().as_ref() {
+            let _ = tx.send(msg);
+        }
+    }
+
+    fn persist(&self, index: &LibraryIndex) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        let result = serde_json::to_vec(index)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_atomic(path, &json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save library index: {}", e);
+        }
+    }
+}
+
+/// Write progress that is still waiting for the debounced save; the app is
+/// about to exit.
+pub fn flush(app: &AppHandle) {
+    if PROGRESS_SAVER.take_pending() {
+        save_now(app);
+    }
+}
+
+fn save_now(app: &AppHandle) {
+    if let Some(state) = app.try_state::<LibraryState>() {
+        state.persist(&state.index.lock());
+    }
+}
+
+fn read_index_file(path: &Path) -> Option<LibraryIndex> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(index) => Some(index),
+        Err(e) => {
+            warn!("Ignoring malformed {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn emit_changes(app: &AppHandle, changes: &LibraryChanges) {
+    if !changes.is_empty() {
+        let _ = app.emit(LIBRARY_CHANGED_EVENT, changes);
+    }
+}
+
+/// All indexed tracks.
+#[tauri::command]
+pub fn list_library(state: State<'_, LibraryState>) -> Vec<LibraryEntry> {
+    state.index.lock().entries.values().cloned().collect()
+}
+
+/// Watched folders.
+#[tauri::command]
+pub fn get_library_folders(state: State<'_, LibraryState>) -> Vec<PathBuf> {
+    state.folders()
+}
+
+/// Start watching a folder. Its contents are imported by the watcher.
+#[tauri::command]
+pub fn add_library_folder(state: State<'_, LibraryState>, path: String) -> Result<(), String> {
+    let folder = PathBuf::from(&path);
+    if !folder.is_dir() {
+        return Err(format!("Folder '{}' does not exist", path));
+    }
+    {
+        let mut index = state.index.lock();
+        if index
+            .folders
+            .iter()
+            .any(|f| folder.starts_with(f) || f.starts_with(&folder))
+        {
+            return Err(format!("Folder '{}' overlaps a watched folder", path));
+        }
+        index.folders.push(folder);
+        state.persist(&index);
+    }
+    state.notify_watcher(watcher::WatchMsg::FoldersChanged);
+    Ok(())
+}
+
+/// Stop watching a folder and drop its tracks from the index.
+#[tauri::command]
+pub fn remove_library_folder(
+    app: AppHandle,
+    state: State<'_, LibraryState>,
+    path: String,
+) -> Result<(), String> {
+    let folder = PathBuf::from(&path);
+    let changes = {
+        let mut index = state.index.lock();
+        let before = index.folders.len();
+        index.folders.retain(|f| f != &folder);
+        if index.folders.len() == before {
+            return Err(format!("Folder '{}' is not watched", path));
+        }
+        let removed: Vec<u64> = index
+            .entries
+            .values()
+            .filter(|entry| entry.path.starts_with(&folder))
+            .map(|entry| entry.id)
+            .collect();
+        for id in &removed {
+            index.entries.remove(id);
+        }
+        state.persist(&index);
+        LibraryChanges {
+            removed,
+            ..Default::default()
+        }
+    };
+    state.notify_watcher(watcher::WatchMsg::FoldersChanged);
+    emit_changes(&app, &changes);
+    Ok(())
+}
+
+/// Reconcile every watched folder now instead of waiting for file events.
+#[tauri::command]
+pub fn rescan_library(state: State<'_, LibraryState>) {
+    state.notify_watcher(watcher::WatchMsg::Rescan);
+}
+
+/// Pause reconciliation during bulk file operations. Events keep being
+/// collected and are applied in one pass when the watcher is resumed.
+#[tauri::command]
+pub fn pause_library_watcher(state: State<'_, LibraryState>, paused: bool) {
+    state.paused.store(paused, Ordering::SeqCst);
+    if !paused {
+        state.notify_watcher(watcher::WatchMsg::Resume);
+    }
+}
+
+/// Record playback progress for a track; `completed` bumps its play count.
+/// The change is kept in memory and saved on a debounce.
+#[tauri::command]
+pub fn record_library_playback(
+    app: AppHandle,
+    state: State<'_, LibraryState>,
+    id: u64,
+    position_ms: u64,
+    completed: bool,
+) -> Result<(), String> {
+    let mut index = state.index.lock();
+    let entry = index
+        .entries
+        .get_mut(&id)
+        .ok_or_else(|| format!("Library entry {} not found", id))?;
+    if completed {
+        entry.play_count = entry.play_count.saturating_add(1);
+        entry.last_position_ms = 0;
+    } else {
+        entry.last_position_ms = position_ms;
+    }
+    drop(index);
+    PROGRESS_SAVER.trigger(move || save_now(&app));
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! Applies a batch of filesystem observations to the library index.
+//!
+//! Pure logic (no I/O besides the injected `path_exists` probe) so the
+//! move/rename and conflict rules can be tested directly.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::scan::FileInfo;
+use super::{LibraryChanges, LibraryEntry, LibraryIndex, TrackTags};
+
+/// Tag-matched tracks may differ this much in duration (re-encodes, padding).
+const DURATION_TOLERANCE_MS: u64 = 2000;
+
+/// State of one path after a debounced burst of file events.
+#[derive(Debug, Clone)]
+pub(super) enum Observed {
+    Missing,
+    /// Size and mtime match the index; tags were not re-read.
+    Unchanged,
+    Present(FileInfo),
+}
+
+/// Merge `observations` into `index`.
+///
+/// Files that vanished and files that appeared in the same batch are paired
+/// up as moves by size + fingerprint first, then by tags, so the entry (and
+/// its play count / resume position) follows the file. A new file whose tags
+/// match an entry whose file is gone also takes over that entry.
+pub(super) fn reconcile(
+    index: &mut LibraryIndex,
+    observations: Vec<(PathBuf, Observed)>,
+    path_exists: impl Fn(&Path) -> bool,
+) -> LibraryChanges {
+    let by_path: HashMap<PathBuf, u64> = index
+        .entries
+        .values()
+        .map(|entry| (entry.path.clone(), entry.id))
+        .collect();
+
+    let mut changes = LibraryChanges::default();
+    let mut vanished = BTreeSet::new();
+    let mut appeared = Vec::new();
+
+    for (path, observed) in observations {
+        match (by_path.get(&path).copied(), observed) {
+            (Some(id), Observed::Missing) => {
+                vanished.insert(id);
+            }
+            (Some(id), Observed::Present(info)) => {
+                if let Some(entry) = index.entries.get_mut(&id) {
+                    if apply_info(entry, info) {
+                        changes.updated.push(id);
+                    }
+                }
+            }
+            (None, Observed::Present(info)) => appeared.push((path, info)),
+            _ => {}
+        }
+    }
+
+    let mut unmatched = Vec::new();
+    for (path, info) in appeared {
+        let moved_from = vanished
+            .iter()
+            .copied()
+            .find(|id| {
+                index.entries.get(id).is_some_and(|entry| {
+                    entry.size == info.size && entry.fingerprint == info.fingerprint
+                })
+            })
+            .or_else(|| {
+                // Same song, different file (re-tagged, re-encoded): only
+                // take over entries whose file is really gone.
+                index
+                    .entries
+                    .values()
+                    .filter(|entry| tags_match(&entry.tags, &info.tags))
+                    .find(|entry| vanished.contains(&entry.id) || !path_exists(&entry.path))
+                    .map(|entry| entry.id)
+            });
+
+        match moved_from.and_then(|id| index.entries.get_mut(&id)) {
+            Some(entry) => {
+                vanished.remove(&entry.id);
+                entry.path = path;
+                apply_info(entry, info);
+                changes.updated.push(entry.id);
+            }
+            None => unmatched.push((path, info)),
+        }
+    }
+
+    for (path, info) in unmatched {
+        index.next_id += 1;
+        let id = index.next_id;
+        index.entries.insert(
+            id,
+            LibraryEntry {
+                id,
+                path,
+                size: info.size,
+                modified_ms: info.modified_ms,
+                fingerprint: info.fingerprint,
+                tags: info.tags,
+                play_count: 0,
+                last_position_ms: 0,
+            },
+        );
+        changes.added.push(id);
+    }
+
+    for id in vanished {
+        index.entries.remove(&id);
+        changes.removed.push(id);
+    }
+
+    changes.updated.sort_unstable();
+    changes.updated.dedup();
+    changes
+}
+
+/// Copy file facts into an entry; returns whether anything changed.
+fn apply_info(entry: &mut LibraryEntry, info: FileInfo) -> bool {
+    let changed = entry.size != info.size
+        || entry.modified_ms != info.modified_ms
+        || entry.fingerprint != info.fingerprint
+        || entry.tags != info.tags;
+    entry.size = info.size;
+    entry.modified_ms = info.modified_ms;
+    entry.fingerprint = info.fingerprint;
+    entry.tags = info.tags;
+    changed
+}
+
+fn tags_match(a: &TrackTags, b: &TrackTags) -> bool {
+    let same = |x: &Option<String>, y: &Option<String>| match (x, y) {
+        (Some(x), Some(y)) => x.eq_ignore_ascii_case(y),
+        (None, None) => true,
+        _ => false,
+    };
+    let Some(title) = a.title.as_deref() else {
+        return false;
+    };
+    if title.is_empty() || !same(&a.title, &b.title) {
+        return false;
+    }
+    if !same(&a.artist, &b.artist) || !same(&a.album, &b.album) {
+        return false;
+    }
+    match (a.duration_ms, b.duration_ms) {
+        (Some(x), Some(y)) => x.abs_diff(y) <= DURATION_TOLERANCE_MS,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(size: u64, fingerprint: u64, title: &str) -> FileInfo {
+        FileInfo {
+            size,
+            modified_ms: 1,
+            fingerprint,
+            tags: TrackTags {
+                title: Some(title.into()),
+                artist: Some("Artist".into()),
+                album: Some("Album".into()),
+                duration_ms: Some(180_000),
+            },
+        }
+    }
+
+    fn index_with(path: &str, file: FileInfo) -> LibraryIndex {
+        let mut index = LibraryIndex::default();
+        let changes = reconcile(
+            &mut index,
+            vec![(path.into(), Observed::Present(file))],
+            |_| true,
+        );
+        assert_eq!(changes.added, vec![1]);
+        index.entries.get_mut(&1).unwrap().play_count = 5;
+        index
+    }
+
+    #[test]
+    fn rename_keeps_id_and_play_count() {
+        let mut index = index_with("/m/a.mp3", info(100, 42, "Song"));
+        let changes = reconcile(
+            &mut index,
+            vec![
+                ("/m/a.mp3".into(), Observed::Missing),
+                (
+                    "/m/sub/b.mp3".into(),
+                    Observed::Present(info(100, 42, "Song")),
+                ),
+            ],
+            |_| true,
+        );
+        assert_eq!(changes.updated, vec![1]);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+        let entry = &index.entries[&1];
+        assert_eq!(entry.path, PathBuf::from("/m/sub/b.mp3"));
+        assert_eq!(entry.play_count, 5);
+    }
+
+    #[test]
+    fn tag_match_with_missing_old_file_updates_path() {
+        let mut index = index_with("/m/a.mp3", info(100, 42, "Song"));
+        // Re-encoded copy appears later; the old file is gone but its delete
+        // event was not part of this batch.
+        let changes = reconcile(
+            &mut index,
+            vec![("/m/a.flac".into(), Observed::Present(info(900, 7, "song")))],
+            |path| path != Path::new("/m/a.mp3"),
+        );
+        assert_eq!(changes.updated, vec![1]);
+        assert!(changes.added.is_empty());
+        assert_eq!(index.entries[&1].path, PathBuf::from("/m/a.flac"));
+    }
+
+    #[test]
+    fn tag_match_with_existing_old_file_is_a_new_entry() {
+        let mut index = index_with("/m/a.mp3", info(100, 42, "Song"));
+        let changes = reconcile(
+            &mut index,
+            vec![(
+                "/m/copy.flac".into(),
+                Observed::Present(info(900, 7, "Song")),
+            )],
+            |_| true,
+        );
+        assert_eq!(changes.added, vec![2]);
+        assert_eq!(index.entries.len(), 2);
+    }
+
+    #[test]
+    fn changed_deleted_and_unchanged_files() {
+        let mut index = index_with("/m/a.mp3", info(100, 42, "Song"));
+        reconcile(
+            &mut index,
+            vec![("/m/b.mp3".into(), Observed::Present(info(200, 43, "Other")))],
+            |_| true,
+        );
+
+        let changes = reconcile(
+            &mut index,
+            vec![
+                (
+                    "/m/a.mp3".into(),
+                    Observed::Present(info(101, 44, "Song (Live)")),
+                ),
+                ("/m/b.mp3".into(), Observed::Unchanged),
+            ],
+            |_| true,
+        );
+        assert_eq!(changes.updated, vec![1]);
+        assert_eq!(index.entries[&1].tags.title.as_deref(), Some("Song (Live)"));
+
+        let changes = reconcile(
+            &mut index,
+            vec![("/m/b.mp3".into(), Observed::Missing)],
+            |_| true,
+        );
+        assert_eq!(changes.removed, vec![2]);
+        assert!(!index.entries.contains_key(&2));
+    }
+
+    #[test]
+    fn duplicate_copies_only_claim_one_moved_entry() {
+        let mut index = index_with("/m/a.mp3", info(100, 42, "Song"));
+        let changes = reconcile(
+            &mut index,
+            vec![
+                ("/m/a.mp3".into(), Observed::Missing),
+                ("/m/x.mp3".into(), Observed::Present(info(100, 42, "Song"))),
+                ("/m/y.mp3".into(), Observed::Present(info(100, 42, "Song"))),
+            ],
+            |path| path != Path::new("/m/a.mp3"),
+        );
+        assert_eq!(changes.updated, vec![1]);
+        assert_eq!(changes.added, vec![2]);
+        assert!(changes.removed.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Filesystem helpers for the library: audio file detection, fingerprints
+//! and tag reading.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::Hint;
+
+use super::TrackTags;
+
+pub(crate) const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "m4a", "aac", "alac", "ogg", "oga", "opus", "wav", "aiff", "aif",
+];
+/// Bytes hashed from each end of the file for the fingerprint.
+const FINGERPRINT_BLOCK: u64 = 64 * 1024;
+
+/// What the scanner knows about a file on disk.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FileInfo {
+    pub size: u64,
+    pub modified_ms: u64,
+    pub fingerprint: u64,
+    pub tags: TrackTags,
+}
+
+pub(crate) fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            AUDIO_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Size and mtime only; cheap enough to run on every file of a rescan.
+pub(super) fn stat(path: &Path) -> std::io::Result<(u64, u64)> {
+    let meta = std::fs::metadata(path)?;
+    let modified_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok((meta.len(), modified_ms))
+}
+
+pub(super) fn file_info(path: &Path) -> std::io::Result<FileInfo> {
+    let (size, modified_ms) = stat(path)?;
+    Ok(FileInfo {
+        size,
+        modified_ms,
+        fingerprint: fingerprint(path, size)?,
+        tags: read_tags(path),
+    })
+}
+
+/// All audio files below `dir`. Unreadable subdirectories are skipped.
+pub(super) fn walk_audio_files(dir: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_audio_file(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+fn fingerprint(path: &Path, size: u64) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hash = Fnv64::default();
+    hash.write(&size.to_le_bytes());
+
+    let mut block = vec![0u8; FINGERPRINT_BLOCK as usize];
+    let head = read_up_to(&mut file, &mut block)?;
+    hash.write(&block[..head]);
+
+    if size > FINGERPRINT_BLOCK * 2 {
+        file.seek(SeekFrom::End(-(FINGERPRINT_BLOCK as i64)))?;
+        let tail = read_up_to(&mut file, &mut block)?;
+        hash.write(&block[..tail]);
+    }
+    Ok(hash.0)
+}
+
+fn read_up_to(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// FNV-1a; fingerprints are persisted, so the hash must be stable across
+/// Rust versions (unlike `DefaultHasher`).
+struct Fnv64(u64);
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv64 {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+/// Best-effort tag read; files symphonia cannot probe just get empty tags.
+pub(crate) fn read_tags(path: &Path) -> TrackTags {
+    let Ok(file) = File::open(path) else {
+        return TrackTags::default();
+    };
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let Ok(mut probed) = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) else {
+        return TrackTags::default();
+    };
+
+    let mut tags = TrackTags::default();
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_revision(&mut tags, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_revision(&mut tags, revision);
+    }
+    if let Some(track) = probed.format.default_track() {
+        let params = &track.codec_params;
+        if let (Some(frames), Some(time_base)) = (params.n_frames, params.time_base) {
+            let time = time_base.calc_time(frames);
+            tags.duration_ms = Some(time.seconds * 1000 + (time.frac * 1000.0) as u64);
+        }
+    }
+    tags
+}
+
+fn apply_revision(tags: &mut TrackTags, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let slot = match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => &mut tags.title,
+            Some(StandardTagKey::Artist) => &mut tags.artist,
+            Some(StandardTagKey::Album) => &mut tags.album,
+            _ => continue,
+        };
+        let value = tag.value.to_string();
+        if slot.is_none() && !value.trim().is_empty() {
+            *slot = Some(value.trim().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_extensions_are_case_insensitive() {
+        assert!(is_audio_file(Path::new("/music/a.FLAC")));
+        assert!(is_audio_file(Path::new("b.mp3")));
+        assert!(!is_audio_file(Path::new("cover.jpg")));
+        assert!(!is_audio_file(Path::new("noext")));
+    }
+
+    #[test]
+    fn fingerprint_ignores_path_but_not_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.mp3");
+        let b = dir.path().join("b.mp3");
+        let c = dir.path().join("c.mp3");
+        let content = vec![7u8; 200 * 1024];
+        std::fs::write(&a, &content).unwrap();
+        std::fs::write(&b, &content).unwrap();
+        let mut changed = content.clone();
+        *changed.last_mut().unwrap() = 8;
+        std::fs::write(&c, &changed).unwrap();
+
+        let fp = |p: &Path| fingerprint(p, std::fs::metadata(p).unwrap().len()).unwrap();
+        assert_eq!(fp(&a), fp(&b));
+        assert_ne!(fp(&a), fp(&c));
+    }
+}
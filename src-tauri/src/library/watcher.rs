@@ -0,0 +1,281 @@
+//! Background watcher that keeps the library index in sync with the watched
+//! folders.
+//!
+//! File events are collected until the folder has been quiet for
+//! [`DEBOUNCE`] (capped at [`MAX_DELAY`] so an endless copy still shows
+//! progress), then the touched paths are re-stat'ed and reconciled in one
+//! pass. Folders on drives that disappear are marked offline instead of
+//! having all their tracks removed, and are rescanned when they come back.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+
+use super::reconcile::{reconcile, Observed};
+use super::{emit_changes, scan, LibraryState};
+use crate::tasks::Scheduler;
+
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+const TICK: Duration = Duration::from_millis(500);
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Batches larger than this (full rescans, a drive coming back) count as
+/// maintenance work and yield to the user every [`CHECKPOINT_EVERY`] files.
+const BULK_RECONCILE: usize = 200;
+const CHECKPOINT_EVERY: usize = 64;
+
+pub(super) enum WatchMsg {
+    Paths(Vec<PathBuf>),
+    FoldersChanged,
+    Rescan,
+    Resume,
+}
+
+/// Start the watcher thread. Call once after `LibraryState` is managed.
+pub fn start_watcher(app: &AppHandle) {
+    let state = app.state::<LibraryState>();
+    let (tx, rx) = mpsc::channel();
+
+    let event_tx = tx.clone();
+    let watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                let _ = event_tx.send(WatchMsg::Paths(event.paths));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Library watcher error: {}", e),
+        });
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("Failed to create library watcher: {}", e);
+            return;
+        }
+    };
+
+    *state.watcher_tx.lock() = Some(tx);
+
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("library-watcher".into())
+        .spawn(move || WatchLoop::new(app, watcher).run(rx));
+    if let Err(e) = spawned {
+        warn!("Failed to spawn library watcher thread: {}", e);
+    }
+}
+
+struct WatchLoop {
+    app: AppHandle,
+    watcher: RecommendedWatcher,
+    /// Watched folders and whether they are currently reachable.
+    online: HashMap<PathBuf, bool>,
+    pending: HashSet<PathBuf>,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
+    last_health_check: Instant,
+}
+
+impl WatchLoop {
+    fn new(app: AppHandle, watcher: RecommendedWatcher) -> Self {
+        Self {
+            app,
+            watcher,
+            online: HashMap::new(),
+            pending: HashSet::new(),
+            first_event: None,
+            last_event: None,
+            last_health_check: Instant::now(),
+        }
+    }
+
+    fn run(mut self, rx: Receiver<WatchMsg>) {
+        self.sync_watches();
+        loop {
+            match rx.recv_timeout(TICK) {
+                Ok(WatchMsg::Paths(paths)) => self.queue(paths),
+                Ok(WatchMsg::FoldersChanged) => self.sync_watches(),
+                Ok(WatchMsg::Rescan) => {
+                    let folders = self.online_folders();
+                    self.queue(folders);
+                    // Explicit rescans should not wait for the quiet period.
+                    self.last_event = Instant::now().checked_sub(DEBOUNCE);
+                }
+                Ok(WatchMsg::Resume) => {}
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            if self.last_health_check.elapsed() >= HEALTH_CHECK_INTERVAL {
+                self.sync_watches();
+            }
+            if self.should_flush() {
+                self.flush();
+            }
+        }
+    }
+
+    fn queue(&mut self, paths: Vec<PathBuf>) {
+        if paths.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        self.pending.extend(paths);
+        self.first_event.get_or_insert(now);
+        self.last_event = Some(now);
+    }
+
+    fn should_flush(&self) -> bool {
+        if self.pending.is_empty() || self.app.state::<LibraryState>().is_paused() {
+            return false;
+        }
+        let quiet = self.last_event.map_or(true, |t| t.elapsed() >= DEBOUNCE);
+        let overdue = self.first_event.is_some_and(|t| t.elapsed() >= MAX_DELAY);
+        quiet || overdue
+    }
+
+    fn online_folders(&self) -> Vec<PathBuf> {
+        self.online
+            .iter()
+            .filter(|(_, online)| **online)
+            .map(|(folder, _)| folder.clone())
+            .collect()
+    }
+
+    /// Align OS watches with the configured folders and their reachability.
+    fn sync_watches(&mut self) {
+        self.last_health_check = Instant::now();
+        let folders = self.app.state::<LibraryState>().folders();
+
+        let stale: Vec<PathBuf> = self
+            .online
+            .keys()
+            .filter(|folder| !folders.contains(folder))
+            .cloned()
+            .collect();
+        for folder in stale {
+            let _ = self.watcher.unwatch(&folder);
+            self.online.remove(&folder);
+        }
+
+        for folder in folders {
+            let was_online = self.online.get(&folder).copied();
+            let is_online = folder.is_dir();
+            match (was_online, is_online) {
+                (Some(true), true) | (Some(false), false) => {}
+                (_, true) => match self.watcher.watch(&folder, RecursiveMode::Recursive) {
+                    Ok(()) => {
+                        if was_online == Some(false) {
+                            info!("Library folder '{}' is back online", folder.display());
+                        }
+                        self.online.insert(folder.clone(), true);
+                        // New or returning folder: pick up whatever changed
+                        // while it was not watched.
+                        self.queue(vec![folder]);
+                    }
+                    Err(e) => {
+                        warn!("Failed to watch '{}': {}", folder.display(), e);
+                        self.online.insert(folder, false);
+                    }
+                },
+                (_, false) => {
+                    if was_online == Some(true) {
+                        warn!("Library folder '{}' went offline", folder.display());
+                        let _ = self.watcher.unwatch(&folder);
+                    }
+                    self.online.insert(folder, false);
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        let paths: Vec<PathBuf> = self.pending.drain().collect();
+        self.first_event = None;
+        self.last_event = None;
+
+        let state = self.app.state::<LibraryState>();
+        let known: HashMap<PathBuf, (u64, u64)> = state
+            .index
+            .lock()
+            .entries
+            .values()
+            .map(|entry| (entry.path.clone(), (entry.size, entry.modified_ms)))
+            .collect();
+
+        let scheduler = self.app.try_state::<Scheduler>();
+        let mut observations = Vec::new();
+        let mut seen = HashSet::new();
+        for path in paths {
+            // Never treat tracks on an unreachable drive as deleted.
+            if !self.is_under_online_folder(&path) {
+                continue;
+            }
+            for file in expand(&path, &known) {
+                if seen.insert(file.clone()) {
+                    if seen.len() > BULK_RECONCILE && seen.len() % CHECKPOINT_EVERY == 0 {
+                        if let Some(scheduler) = &scheduler {
+                            scheduler.checkpoint();
+                        }
+                    }
+                    let observed = observe(&file, known.get(&file).copied());
+                    observations.push((file, observed));
+                }
+            }
+        }
+        if observations.is_empty() {
+            return;
+        }
+
+        let changes = {
+            let mut index = state.index.lock();
+            let changes = reconcile(&mut index, observations, |p| p.exists());
+            if !changes.is_empty() {
+                state.persist(&index);
+            }
+            changes
+        };
+        emit_changes(&self.app, &changes);
+    }
+
+    fn is_under_online_folder(&self, path: &Path) -> bool {
+        self.online
+            .iter()
+            .any(|(folder, online)| *online && path.starts_with(folder) && folder.is_dir())
+    }
+}
+
+/// Turn an event path into the audio files it may affect: a directory
+/// covers everything on disk below it plus indexed files that used to be.
+fn expand(path: &Path, known: &HashMap<PathBuf, (u64, u64)>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = known
+        .keys()
+        .filter(|p| p.starts_with(path))
+        .cloned()
+        .collect();
+    if path.is_dir() {
+        files.extend(scan::walk_audio_files(path));
+    } else if scan::is_audio_file(path) {
+        files.push(path.to_path_buf());
+    }
+    files
+}
+
+fn observe(path: &Path, known: Option<(u64, u64)>) -> Observed {
+    match scan::stat(path) {
+        Err(_) => Observed::Missing,
+        Ok(stat) if Some(stat) == known => Observed::Unchanged,
+        Ok(_) => match scan::file_info(path) {
+            Ok(info) => Observed::Present(info),
+            Err(e) => {
+                // Usually a file still being copied; the next event retries.
+                warn!("Failed to read '{}': {}", path.display(), e);
+                Observed::Unchanged
+            }
+        },
+    }
+}
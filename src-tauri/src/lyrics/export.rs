@@ -0,0 +1,270 @@
+//! "Save lyrics": write parsed lyrics back out as a standard `.lrc` file.
+//!
+//! Word timing is dropped; each line keeps its start time. Translations,
+//! when asked for, follow their line under the same timestamp, which is
+//! how most players pair them up again.
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tokio::sync::oneshot;
+
+use super::MergedLine;
+use crate::desktop::file_manager::{self, FileManagerError};
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum LrcExportError {
+    /// Destination folder is outside the allowed roots or missing.
+    InvalidDestination(String),
+    /// The file exists and `overwrite` was not set.
+    AlreadyExists(String),
+    /// The save dialog was dismissed.
+    Cancelled(String),
+    Io(String),
+}
+
+impl fmt::Display for LrcExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDestination(message) => write!(f, "Invalid destination: {}", message),
+            Self::AlreadyExists(path) => write!(f, "File already exists: {}", path),
+            Self::Cancelled(message) | Self::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<FileManagerError> for LrcExportError {
+    fn from(e: FileManagerError) -> Self {
+        Self::InvalidDestination(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SongMeta {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+}
+
+/// `[mm:ss.xx]`; minutes go past 99 rather than wrapping.
+fn timestamp(time_ms: u64) -> String {
+    format!(
+        "[{:02}:{:02}.{:02}]",
+        time_ms / 60_000,
+        time_ms / 1000 % 60,
+        time_ms % 1000 / 10
+    )
+}
+
+/// Text on one line, without anything that would end a tag early.
+fn single_line(text: &str) -> String {
+    text.split(['\r', '\n'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_lrc(meta: &SongMeta, lines: &[MergedLine], include_translation: bool) -> String {
+    let mut out = String::new();
+    for (tag, value) in [
+        ("ti", &meta.title),
+        ("ar", &meta.artist),
+        ("al", &meta.album),
+    ] {
+        let value = single_line(value).replace(']', ")");
+        if !value.trim().is_empty() {
+            out.push_str(&format!("[{}:{}]\n", tag, value.trim()));
+        }
+    }
+    out.push_str("[by:GMPlayer]\n");
+    for line in lines {
+        let stamp = timestamp(line.time_ms);
+        out.push_str(&format!("{}{}\n", stamp, single_line(&line.text)));
+        if let Some(translation) = line.translation.as_deref().filter(|_| include_translation) {
+            out.push_str(&format!("{}{}\n", stamp, single_line(translation)));
+        }
+    }
+    out
+}
+
+/// `Artist - Title.lrc` with characters Windows, macOS or Linux reject in
+/// file names replaced.
+pub fn file_name(meta: &SongMeta) -> String {
+    let stem = match (meta.artist.trim(), meta.title.trim()) {
+        ("", "") => "lyrics".to_string(),
+        ("", title) => title.to_string(),
+        (artist, "") => artist.to_string(),
+        (artist, title) => format!("{} - {}", artist, title),
+    };
+    let mut stem: String = stem
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces, turning the name into another.
+    stem.truncate(stem.trim_end_matches(['.', ' ']).len());
+    const RESERVED: [&str; 22] = [
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+    if stem.is_empty() || RESERVED.iter().any(|name| stem.eq_ignore_ascii_case(name)) {
+        stem.insert(0, '_');
+    }
+    // Leave room for the extension within the common 255 byte limit.
+    let mut end = stem.len().min(240);
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    stem.truncate(end);
+    format!("{}.lrc", stem)
+}
+
+async fn ask_save_path(app: &AppHandle, name: &str) -> Result<PathBuf, LrcExportError> {
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .file()
+        .set_file_name(name)
+        .add_filter("LRC lyrics", &["lrc"])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let path = rx
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| LrcExportError::Cancelled("Export cancelled".into()))?;
+    path.into_path()
+        .map_err(|e| LrcExportError::InvalidDestination(e.to_string()))
+}
+
+/// Write `text` to `dest`. Without `overwrite` the file is created with
+/// `create_new`, so one that appears after the name was picked is never
+/// replaced; a failed write removes the partial file.
+fn write_lrc(dest: &Path, text: &str, overwrite: bool) -> Result<(), LrcExportError> {
+    let io = |e: std::io::Error| LrcExportError::Io(e.to_string());
+    if overwrite {
+        return crate::settings::write_atomic(dest, text.as_bytes()).map_err(io);
+    }
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(dest) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            return Err(LrcExportError::AlreadyExists(dest.display().to_string()));
+        }
+        Err(e) => return Err(io(e)),
+    };
+    if let Err(e) = file
+        .write_all(text.as_bytes())
+        .and_then(|()| file.sync_all())
+    {
+        drop(file);
+        let _ = std::fs::remove_file(dest);
+        return Err(io(e));
+    }
+    Ok(())
+}
+
+/// Write `lines` as an `.lrc` file named after the song into `dest_dir`,
+/// or wherever the user picks in a save dialog when `dest_dir` is empty.
+/// An existing file in `dest_dir` is only replaced with `overwrite`; the
+/// dialog asks about that itself.
+#[tauri::command]
+pub async fn export_lrc(
+    app: AppHandle,
+    song_meta: SongMeta,
+    lines: Vec<MergedLine>,
+    dest_dir: Option<String>,
+    include_translation: bool,
+    overwrite: bool,
+) -> Result<PathBuf, LrcExportError> {
+    let name = file_name(&song_meta);
+    let (dest, overwrite) = match dest_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+    {
+        Some(dir) => (
+            file_manager::resolve_output_path(&app, &Path::new(dir).join(&name))?,
+            overwrite,
+        ),
+        None => (ask_save_path(&app, &name).await?, true),
+    };
+    let text = to_lrc(&song_meta, &lines, include_translation);
+    write_lrc(&dest, &text, overwrite)?;
+    info!("Exported lyrics to {}", dest.display());
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(time_ms: u64, text: &str, translation: Option<&str>) -> MergedLine {
+        MergedLine {
+            time_ms,
+            duration_ms: None,
+            text: text.into(),
+            words: Vec::new(),
+            translation: translation.map(Into::into),
+            roma: None,
+        }
+    }
+
+    #[test]
+    fn lines_and_translations_share_timestamps() {
+        let meta = SongMeta {
+            title: "晴天".into(),
+            artist: "周杰伦".into(),
+            album: String::new(),
+        };
+        let lines = [
+            line(1_230, "故事的小黄花", Some("Little yellow flowers")),
+            line(6_005_990, "multi\nline", None),
+        ];
+        assert_eq!(
+            to_lrc(&meta, &lines, true),
+            "[ti:晴天]\n[ar:周杰伦]\n[by:GMPlayer]\n\
+             [00:01.23]故事的小黄花\n[00:01.23]Little yellow flowers\n\
+             [100:05.99]multi line\n"
+        );
+        assert!(!to_lrc(&meta, &lines, false).contains("Little"));
+    }
+
+    #[test]
+    fn existing_files_are_kept_without_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("song.lrc");
+        write_lrc(&dest, "first", false).unwrap();
+        assert!(matches!(
+            write_lrc(&dest, "second", false),
+            Err(LrcExportError::AlreadyExists(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "first");
+        write_lrc(&dest, "third", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "third");
+    }
+
+    #[test]
+    fn file_names_are_sanitized() {
+        let meta = |artist: &str, title: &str| SongMeta {
+            title: title.into(),
+            artist: artist.into(),
+            album: String::new(),
+        };
+        assert_eq!(file_name(&meta("AC/DC", "T.N.T.")), "AC_DC - T.N.T.lrc");
+        assert_eq!(file_name(&meta("", "What?: \"Yes\"")), "What__ _Yes_.lrc");
+        assert_eq!(file_name(&meta("", "con")), "_con.lrc");
+        assert_eq!(file_name(&meta(" ", "")), "lyrics.lrc");
+        assert!(file_name(&meta("", &"歌".repeat(200))).len() <= 244);
+    }
+}
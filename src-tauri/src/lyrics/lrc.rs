@@ -0,0 +1,275 @@
+//! LRC parsing and merging of the original, translated and romanized
+//! lyrics into one timeline.
+//!
+//! Accepted timestamps are `[mm:ss]`, `[mm:ss.x]` to `[mm:ss.xxx]`,
+//! `[mm:ss:xx]` and `[hh:mm:ss.xx]`. A line may start with several of them
+//! and is then repeated at each. `[offset:±ms]` shifts the whole file, with
+//! positive values making lyrics appear earlier, as in the format's
+//! original players. Other tags (`[ar:…]`, `[ti:…]`) and lines without a
+//! leading timestamp are skipped. Nothing in here panics on bad input.
+
+use serde::{Deserialize, Serialize};
+
+use super::MergedLine;
+
+/// Translation and romanization lines further than this from an original
+/// line are not attached to it.
+pub const MERGE_TOLERANCE_MS: u64 = 500;
+/// Timestamps beyond this are garbage, not lyrics.
+const MAX_TIME_MS: u64 = 100 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Line {
+    pub time_ms: u64,
+    /// Empty for blank lines, which mark where the previous line ends.
+    pub text: String,
+}
+
+impl From<Line> for MergedLine {
+    fn from(line: Line) -> Self {
+        Self {
+            time_ms: line.time_ms,
+            duration_ms: None,
+            text: line.text,
+            words: Vec::new(),
+            translation: None,
+            roma: None,
+        }
+    }
+}
+
+/// Milliseconds in a fraction of a second written with these digits; only
+/// the first three count.
+fn fraction_ms(digits: &str) -> Option<u64> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let kept = &digits[..digits.len().min(3)];
+    let value: u64 = kept.parse().ok()?;
+    Some(value * 10u64.pow(3 - kept.len() as u32))
+}
+
+fn number(digits: &str) -> Option<u64> {
+    if digits.is_empty() || digits.len() > 6 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Milliseconds of a timestamp tag's contents, e.g. `01:02.50`.
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let parts: Vec<&str> = tag.split(':').collect();
+    let ms = match parts.as_slice() {
+        // mm:ss.xx / mm:ss
+        [minutes, seconds] => {
+            let (seconds, fraction) = match seconds.split_once('.') {
+                Some((seconds, fraction)) => (seconds, fraction_ms(fraction)?),
+                None => (*seconds, 0),
+            };
+            (number(minutes)? * 60 + number(seconds)?) * 1000 + fraction
+        }
+        // mm:ss:xx (fraction after a colon) or hh:mm:ss.xx
+        [first, second, third] => match third.split_once('.') {
+            Some((seconds, fraction)) => {
+                ((number(first)? * 60 + number(second)?) * 60 + number(seconds)?) * 1000
+                    + fraction_ms(fraction)?
+            }
+            None => (number(first)? * 60 + number(second)?) * 1000 + fraction_ms(third)?,
+        },
+        _ => return None,
+    };
+    (ms <= MAX_TIME_MS).then_some(ms)
+}
+
+/// The `[offset:…]` value in milliseconds.
+fn parse_offset(tag: &str) -> Option<i64> {
+    let (name, value) = tag.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("offset") {
+        return None;
+    }
+    let value = value.trim();
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let magnitude = i64::try_from(number(digits)?).ok()?;
+    Some(if value.starts_with('-') {
+        -magnitude
+    } else {
+        magnitude
+    })
+}
+
+/// Parse LRC text into lines sorted by time; lines sharing a timestamp keep
+/// their order in the file.
+pub fn parse_lrc(text: &str) -> Vec<Line> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut offset_ms = 0i64;
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim_start();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some((tag, after)) = tag.split_once(']') else {
+                break;
+            };
+            match parse_timestamp(tag.trim()) {
+                Some(time_ms) => times.push(time_ms),
+                None => {
+                    if let Some(offset) = parse_offset(tag) {
+                        offset_ms = offset;
+                    }
+                }
+            }
+            rest = after;
+        }
+        let text = rest.trim();
+        lines.extend(times.into_iter().map(|time_ms| Line {
+            time_ms,
+            text: text.to_string(),
+        }));
+    }
+    if offset_ms != 0 {
+        for line in &mut lines {
+            line.time_ms = line.time_ms.saturating_add_signed(-offset_ms);
+        }
+    }
+    lines.sort_by_key(|line| line.time_ms);
+    lines
+}
+
+/// For each of `targets`, the index of the nearest unused non-blank line in
+/// `lines` within the tolerance. Each line is attached at most once, so a
+/// doubled timestamp in the original pairs with a doubled one in the
+/// translation instead of repeating it.
+fn align(targets: &[MergedLine], lines: &[Line]) -> Vec<Option<usize>> {
+    let mut used = vec![false; lines.len()];
+    targets
+        .iter()
+        .map(|target| {
+            if target.text.is_empty() {
+                return None;
+            }
+            let low = target.time_ms.saturating_sub(MERGE_TOLERANCE_MS);
+            let high = target.time_ms.saturating_add(MERGE_TOLERANCE_MS);
+            let start = lines.partition_point(|line| line.time_ms < low);
+            let best = lines[start..]
+                .iter()
+                .enumerate()
+                .take_while(|(_, line)| line.time_ms <= high)
+                .filter(|(i, line)| !used[start + i] && !line.text.is_empty())
+                .min_by_key(|(_, line)| line.time_ms.abs_diff(target.time_ms))
+                .map(|(i, _)| start + i)?;
+            used[best] = true;
+            Some(best)
+        })
+        .collect()
+}
+
+/// Attach translation and romanization to the original lines. All three
+/// must be sorted by time, as the parsers return them.
+pub fn merge_lyrics(
+    mut original: Vec<MergedLine>,
+    translation: &[Line],
+    roma: &[Line],
+) -> Vec<MergedLine> {
+    let translated = align(&original, translation);
+    let romanized = align(&original, roma);
+    for (line, (t, r)) in original
+        .iter_mut()
+        .zip(translated.into_iter().zip(romanized))
+    {
+        line.translation = t.map(|i| translation[i].text.clone());
+        line.roma = r.map(|i| roma[i].text.clone());
+    }
+    original
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn merged_lines(lines: Vec<Line>) -> Vec<MergedLine> {
+        lines.into_iter().map(Into::into).collect()
+    }
+
+    fn times(lines: &[Line]) -> Vec<u64> {
+        lines.iter().map(|line| line.time_ms).collect()
+    }
+
+    #[test]
+    fn timestamps_offsets_bom_and_junk() {
+        let text = "\u{feff}[ti:Song]\r\n[offset:-250]\r\n\
+                    [00:01.5][00:10.25]Chorus\r\n\
+                    [01:02:03.5]hours\n\
+                    [00:03:07]colon fraction\n\
+                    [00:02.000]first\n[00:02.000]second\n\
+                    no tag\n[xx:yy]bad\n[00:04.00\n[00:05]";
+        let lines = parse_lrc(text);
+        assert_eq!(
+            times(&lines),
+            vec![1750, 2250, 2250, 3320, 5250, 10_500, 3_723_750]
+        );
+        assert_eq!(lines[1].text, "first");
+        assert_eq!(lines[2].text, "second");
+        assert_eq!(lines[4].text, "");
+
+        // A positive offset moves lines earlier, stopping at zero.
+        assert_eq!(
+            times(&parse_lrc("[offset:+1500]\n[00:01.00]a\n[00:02.00]b")),
+            vec![0, 500]
+        );
+    }
+
+    #[test]
+    fn merge_pairs_nearest_lines_once() {
+        let original = parse_lrc(
+            "[00:01.00]one\n[00:05.00]two\n[00:05.00]two again\n[00:09.00]\n[00:20.00]three",
+        );
+        let translation = parse_lrc(
+            "[00:01.30]一\n[00:05.00]二\n[00:05.00]二again\n[00:09.00]blank\n[00:30.00]far",
+        );
+        let roma = parse_lrc("[00:00.80]ichi\n[00:01.10]ichi?");
+        let merged = merge_lyrics(merged_lines(original), &translation, &roma);
+        let pairs: Vec<(Option<&str>, Option<&str>)> = merged
+            .iter()
+            .map(|line| (line.translation.as_deref(), line.roma.as_deref()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Some("一"), Some("ichi?")),
+                (Some("二"), None),
+                (Some("二again"), None),
+                (None, None),
+                (None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn random_input_never_panics() {
+        const ALPHABET: &[char] = &[
+            '[', ']', ':', '.', '+', '-', '0', '1', '5', '9', 'o', 'f', 's', 'e', 't', '\n', '\r',
+            ' ', '\u{feff}', 'é', '歌',
+        ];
+        let mut rng = StdRng::seed_from_u64(0x1c5);
+        for _ in 0..2_000 {
+            let len = rng.gen_range(0..80);
+            let text: String = (0..len)
+                .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+                .collect();
+            let lines = parse_lrc(&text);
+            assert!(lines.windows(2).all(|w| w[0].time_ms <= w[1].time_ms));
+            merge_lyrics(merged_lines(lines.clone()), &lines, &lines);
+        }
+        let huge = parse_lrc("[99999999999999:00]x\n[00:00.99999999999999999999]y");
+        assert_eq!(
+            huge,
+            vec![Line {
+                time_ms: 999,
+                text: "y".into()
+            }]
+        );
+    }
+}
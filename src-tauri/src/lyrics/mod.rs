@@ -0,0 +1,261 @@
+//! Lyric data shared by every lyric consumer (desktop lyrics, tray popup,
+//! media session timed lyrics), and the parsers that produce it.
+
+#[cfg(not(mobile))]
+pub mod export;
+pub mod lrc;
+pub mod qrc;
+pub mod session;
+pub mod yrc;
+
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// One timed lyric line as produced by the frontend parsers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLine {
+    pub time_ms: u64,
+    pub text: String,
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+/// Index of the line active at `position_ms`: the last line whose start time
+/// is not after the position. `lines` must be sorted by `time_ms`.
+pub fn active_line_index(lines: &[LyricLine], position_ms: u64) -> Option<usize> {
+    lines
+        .partition_point(|line| line.time_ms <= position_ms)
+        .checked_sub(1)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum LyricsError {
+    /// Encrypted lyrics that do not decrypt or decompress.
+    Decrypt(String),
+    /// A wrapper without the expected lyric content.
+    Malformed(String),
+    /// The parse stopped before it finished.
+    Interrupted(String),
+}
+
+impl fmt::Display for LyricsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decrypt(message) => write!(f, "Failed to decrypt lyrics: {}", message),
+            Self::Malformed(message) => write!(f, "Malformed lyrics: {}", message),
+            Self::Interrupted(message) => write!(f, "Lyric parsing stopped: {}", message),
+        }
+    }
+}
+
+/// One word of a word-timed line. Times are from the start of the track.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Word {
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    pub text: String,
+}
+
+/// A line of word-timed (karaoke) lyrics. Credit lines have no words.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordLine {
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    pub text: String,
+    pub words: Vec<Word>,
+}
+
+/// A line as returned by `parse_lyrics`, whatever the source format:
+/// `words` is empty and `duration_ms` is `None` for line-timed lyrics.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedLine {
+    pub time_ms: u64,
+    pub duration_ms: Option<u64>,
+    pub text: String,
+    #[serde(default)]
+    pub words: Vec<Word>,
+    pub translation: Option<String>,
+    pub roma: Option<String>,
+}
+
+impl From<WordLine> for MergedLine {
+    fn from(line: WordLine) -> Self {
+        Self {
+            time_ms: line.start_ms,
+            duration_ms: Some(line.duration_ms),
+            text: line.text,
+            words: line.words,
+            translation: None,
+            roma: None,
+        }
+    }
+}
+
+/// Timing tags made of `fields` comma-separated numbers between `open` and
+/// `close`, as `(byte range, numbers)`. Anything else in brackets, nested
+/// or not, is left for the caller to treat as text.
+fn timing_tags(line: &str, open: u8, close: u8, fields: usize) -> Vec<(usize, usize, Vec<u64>)> {
+    let bytes = line.as_bytes();
+    let mut tags = Vec::new();
+    let mut at = 0;
+    while let Some(found) = bytes[at..].iter().position(|&b| b == open) {
+        let start = at + found;
+        let parsed = bytes[start + 1..]
+            .iter()
+            .position(|&b| b == close)
+            .and_then(|len| {
+                let inner = &line[start + 1..start + 1 + len];
+                let numbers: Vec<u64> = inner
+                    .split(',')
+                    .map(|n| {
+                        let n = n.trim();
+                        if n.is_empty() || n.len() > 10 || !n.bytes().all(|b| b.is_ascii_digit()) {
+                            return None;
+                        }
+                        n.parse().ok()
+                    })
+                    .collect::<Option<_>>()?;
+                (numbers.len() == fields).then_some((start + len + 2, numbers))
+            });
+        match parsed {
+            Some((end, numbers)) => {
+                tags.push((start, end, numbers));
+                at = end;
+            }
+            None => at = start + 1,
+        }
+    }
+    tags
+}
+
+/// Keep word highlights moving forward: a word starting before the one in
+/// front of it is moved up to it, and a word running into the next one is
+/// cut short where the next begins.
+fn settle_words(words: &mut [Word]) {
+    for i in 1..words.len() {
+        let previous_start = words[i - 1].start_ms;
+        let word = &mut words[i];
+        if word.start_ms < previous_start {
+            let end = word.start_ms + word.duration_ms;
+            word.duration_ms = end.saturating_sub(previous_start);
+            word.start_ms = previous_start;
+        }
+    }
+    for i in 1..words.len() {
+        let next_start = words[i].start_ms;
+        let word = &mut words[i - 1];
+        word.duration_ms = word.duration_ms.min(next_start - word.start_ms);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LyricFormat {
+    Lrc,
+    /// QQ Music, plain or in its encrypted XML wrapper.
+    Qrc,
+    /// NetEase word-timed lyrics.
+    Yrc,
+}
+
+/// Parse the original lyrics in `format` and attach the translation and
+/// romanization (both LRC) to their nearest lines. Runs on the blocking
+/// pool, so long lyrics never stall the main thread.
+#[tauri::command]
+pub async fn parse_lyrics(
+    format: LyricFormat,
+    original: String,
+    translation: Option<String>,
+    roma: Option<String>,
+) -> Result<Vec<MergedLine>, LyricsError> {
+    tauri::async_runtime::spawn_blocking(move || parse(format, &original, translation, roma))
+        .await
+        .map_err(|e| LyricsError::Interrupted(e.to_string()))?
+}
+
+fn parse(
+    format: LyricFormat,
+    original: &str,
+    translation: Option<String>,
+    roma: Option<String>,
+) -> Result<Vec<MergedLine>, LyricsError> {
+    let original: Vec<MergedLine> = match format {
+        LyricFormat::Lrc => lrc::parse_lrc(original)
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        LyricFormat::Qrc => qrc::parse_qrc(original)?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        LyricFormat::Yrc => yrc::parse_yrc(original)
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    };
+    let translation = translation
+        .as_deref()
+        .map(lrc::parse_lrc)
+        .unwrap_or_default();
+    let roma = roma.as_deref().map(lrc::parse_lrc).unwrap_or_default();
+    Ok(lrc::merge_lyrics(original, &translation, &roma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(time_ms: u64) -> LyricLine {
+        LyricLine {
+            time_ms,
+            text: time_ms.to_string(),
+            translation: None,
+        }
+    }
+
+    fn word(start_ms: u64, duration_ms: u64) -> Word {
+        Word {
+            start_ms,
+            duration_ms,
+            text: String::new(),
+        }
+    }
+
+    #[test]
+    fn overlapping_and_backward_words_are_settled() {
+        let mut words = [
+            word(1000, 500),
+            word(1300, 400),
+            word(1200, 700),
+            word(2000, 100),
+        ];
+        settle_words(&mut words);
+        let timing: Vec<(u64, u64)> = words.iter().map(|w| (w.start_ms, w.duration_ms)).collect();
+        assert_eq!(
+            timing,
+            vec![(1000, 300), (1300, 0), (1300, 600), (2000, 100)]
+        );
+
+        let tags = timing_tags("(1,2)a(b)(3,x)(4,5,6)(7,8)", b'(', b')', 2);
+        let found: Vec<&Vec<u64>> = tags.iter().map(|(_, _, numbers)| numbers).collect();
+        assert_eq!(found, vec![&vec![1, 2], &vec![7, 8]]);
+    }
+
+    #[test]
+    fn active_line_is_last_started_line() {
+        let lines = [line(1000), line(2000), line(2000), line(5000)];
+        assert_eq!(active_line_index(&lines, 0), None);
+        assert_eq!(active_line_index(&lines, 999), None);
+        assert_eq!(active_line_index(&lines, 1000), Some(0));
+        assert_eq!(active_line_index(&lines, 2500), Some(2));
+        assert_eq!(active_line_index(&lines, 60_000), Some(3));
+        assert_eq!(active_line_index(&[], 1000), None);
+    }
+}
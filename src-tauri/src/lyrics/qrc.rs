@@ -0,0 +1,255 @@
+//! QQ Music QRC word-timed lyrics.
+//!
+//! A line is `[start,duration]` followed by words, each followed by its
+//! `(start,duration)` tag; times are milliseconds from the start of the
+//! track. The lines sit in the `LyricContent` attribute of a small XML
+//! document, and what the API returns is that document zlib-compressed,
+//! triple-DES encrypted with a fixed key and hex-encoded. QQ's DES reads
+//! each half of a block (and of each key) as a little-endian word, so the
+//! bytes are reversed per half around a standard DES. Any of the three
+//! layers may already have been removed by the caller.
+
+use std::io::Read;
+
+use des::cipher::generic_array::GenericArray;
+use des::cipher::{BlockDecrypt, KeyInit};
+use des::TdesEde3;
+use flate2::read::ZlibDecoder;
+
+use super::{settle_words, timing_tags, LyricsError, Word, WordLine};
+
+const QRC_KEY: &[u8; 24] = b"!@#)(*$%123ZXC!@!@#)(NHL";
+/// Decompressed lyrics past this size are not lyrics.
+const MAX_DECOMPRESSED_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Reverse the bytes of each 32-bit half, converting between QQ's and
+/// standard DES byte order.
+fn swap_halves(block: &mut [u8]) {
+    for half in block.chunks_mut(4) {
+        half.reverse();
+    }
+}
+
+fn cipher() -> TdesEde3 {
+    let mut key = *QRC_KEY;
+    swap_halves(&mut key);
+    TdesEde3::new(&key.into())
+}
+
+/// Decrypt and inflate the hex-encoded payload.
+fn decrypt(hex_text: &str) -> Result<String, LyricsError> {
+    let mut data = hex::decode(hex_text).map_err(|e| LyricsError::Decrypt(e.to_string()))?;
+    if data.is_empty() || data.len() % 8 != 0 {
+        return Err(LyricsError::Decrypt(format!(
+            "{} bytes is not a whole number of blocks",
+            data.len()
+        )));
+    }
+    let cipher = cipher();
+    for block in data.chunks_exact_mut(8) {
+        swap_halves(block);
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        swap_halves(block);
+    }
+    let mut text = String::new();
+    ZlibDecoder::new(data.as_slice())
+        .take(MAX_DECOMPRESSED_BYTES)
+        .read_to_string(&mut text)
+        .map_err(|e| LyricsError::Decrypt(e.to_string()))?;
+    Ok(text)
+}
+
+fn unescape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|&semi| semi <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let decoded = match &rest[1..semi] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The lyric lines out of the XML wrapper, or `text` itself when it is not
+/// wrapped.
+fn unwrap_xml(text: &str) -> Result<String, LyricsError> {
+    if !text.trim_start().starts_with('<') {
+        return Ok(text.to_string());
+    }
+    const ATTRIBUTE: &str = "LyricContent=\"";
+    let start = text
+        .find(ATTRIBUTE)
+        .ok_or_else(|| LyricsError::Malformed("no LyricContent in QRC document".into()))?
+        + ATTRIBUTE.len();
+    let len = text[start..]
+        .find('"')
+        .ok_or_else(|| LyricsError::Malformed("unterminated LyricContent".into()))?;
+    Ok(unescape_xml(&text[start..start + len]))
+}
+
+fn parse_line(line: &str) -> Option<WordLine> {
+    let (_, header_end, header) = timing_tags(line, b'[', b']', 2)
+        .into_iter()
+        .next()
+        .filter(|(start, _, _)| *start == 0)?;
+    let body = &line[header_end..];
+    let mut text_start = 0;
+    let mut words: Vec<Word> = Vec::new();
+    for (start, end, numbers) in timing_tags(body, b'(', b')', 2) {
+        let text = &body[text_start..start];
+        text_start = end;
+        if !text.is_empty() {
+            words.push(Word {
+                start_ms: numbers[0],
+                duration_ms: numbers[1],
+                text: text.to_string(),
+            });
+        }
+    }
+    settle_words(&mut words);
+    let text = if words.is_empty() {
+        body.trim().to_string()
+    } else {
+        words.iter().map(|word| word.text.as_str()).collect()
+    };
+    Some(WordLine {
+        start_ms: header[0],
+        duration_ms: header[1],
+        text,
+        words,
+    })
+}
+
+/// Parse QRC lyrics, encrypted, in their XML wrapper or bare, into lines
+/// sorted by start time. Tag lines such as `[ti:…]` are skipped.
+pub fn parse_qrc(input: &str) -> Result<Vec<WordLine>, LyricsError> {
+    let input = input.trim().trim_start_matches('\u{feff}');
+    let decrypted;
+    let text = if !input.is_empty() && input.bytes().all(|b| b.is_ascii_hexdigit()) {
+        decrypted = decrypt(input)?;
+        decrypted.trim_start_matches('\u{feff}')
+    } else {
+        input
+    };
+    let mut lines: Vec<WordLine> = unwrap_xml(text)?
+        .lines()
+        .filter_map(|line| parse_line(line.trim()))
+        .collect();
+    lines.sort_by_key(|line| line.start_ms);
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use des::cipher::BlockEncrypt;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    const DOCUMENT: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <QrcInfos><LyricInfo LyricCount=\"1\">\n\
+        <Lyric_1 LyricType=\"1\" LyricContent=\"[ti:晴天]\n\
+        [0,2000]故(0,400)事(400,300)的(700,300)小(1000,500)黄(1500,200)花(1700,300)\n\
+        [2000,1500]Rock &amp; (Roll)(2000,800) &quot;ok&quot;(2700,900)\n\"/>\n\
+        </LyricInfo></QrcInfos>";
+
+    /// The inverse of `decrypt`, built from the same key and byte order.
+    /// A round trip through it only shows the three layers fit together; it
+    /// cannot catch a wrong key, table or byte order. For that, see
+    /// `captured_payloads_decrypt_to_their_plaintext`.
+    fn encrypt(text: &str) -> String {
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(text.as_bytes()).unwrap();
+        let mut data = zlib.finish().unwrap();
+        data.resize(data.len().next_multiple_of(8), 0);
+        let cipher = cipher();
+        for block in data.chunks_exact_mut(8) {
+            swap_halves(block);
+            cipher.encrypt_block(GenericArray::from_mut_slice(block));
+            swap_halves(block);
+        }
+        hex::encode_upper(data)
+    }
+
+    #[test]
+    fn wrapped_and_encrypted_documents_parse_alike() {
+        let lines = parse_qrc(DOCUMENT).unwrap();
+        assert_eq!(lines.len(), 2);
+        let words: Vec<&str> = lines[0].words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, vec!["故", "事", "的", "小", "黄", "花"]);
+        assert_eq!(lines[0].text, "故事的小黄花");
+        assert_eq!(lines[0].words[5].start_ms, 1700);
+
+        // Brackets without numbers stay text, and a word running into the
+        // next one is cut short.
+        assert_eq!(lines[1].text, "Rock & (Roll) \"ok\"");
+        assert_eq!(lines[1].words[0].duration_ms, 700);
+
+        assert_eq!(parse_qrc(&encrypt(DOCUMENT)).unwrap(), lines);
+        assert!(matches!(parse_qrc("ABCD"), Err(LyricsError::Decrypt(_))));
+        assert!(matches!(
+            parse_qrc("<QrcInfos/>"),
+            Err(LyricsError::Malformed(_))
+        ));
+    }
+
+    /// Each `<name>.hex` under `tests/fixtures/qrc` is a payload as the QQ
+    /// Music API returned it, and `<name>.xml` the document it decrypts to.
+    /// Ignored until such a pair is captured: they cannot be made up with
+    /// this module's own cipher without defeating the point.
+    #[test]
+    #[ignore = "needs a captured QQ Music payload in tests/fixtures/qrc"]
+    fn captured_payloads_decrypt_to_their_plaintext() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/qrc");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension() != Some("hex".as_ref()) {
+                continue;
+            }
+            let payload = std::fs::read_to_string(&path).unwrap();
+            let expected = std::fs::read_to_string(path.with_extension("xml")).unwrap();
+            assert_eq!(
+                decrypt(payload.trim()).unwrap(),
+                expected,
+                "{}",
+                path.display()
+            );
+            checked += 1;
+        }
+        assert!(checked > 0, "no captured payloads in {}", dir.display());
+    }
+}
@@ -0,0 +1,345 @@
+//! Backend lyric session: one place that tracks the active lyric line.
+//!
+//! The main window loads the parsed lyrics once per track; the position
+//! comes from the shared [`PlaybackClock`], whose anchor is copied in on
+//! every sync. A ticker thread extrapolates from that anchor and emits
+//! `lyric-line-changed` only when the active line actually changes, so desktop lyrics, the tray popup and the media session all
+//! follow a single event instead of each re-deriving the line. While the
+//! clock is paused the ticker parks; the next clock sync wakes it.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::warn;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::{active_line_index, LyricLine};
+use crate::playback_clock::{ClockAnchor, PlaybackClock};
+
+pub const LYRIC_LINE_CHANGED_EVENT: &str = "lyric-line-changed";
+const TICK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Payload of `lyric-line-changed`. `index` is `None` before the first line,
+/// for tracks without lyrics, and once the session is torn down.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLineChange {
+    pub track_id: String,
+    pub index: Option<usize>,
+    pub text: Option<String>,
+    pub translation: Option<String>,
+}
+
+/// Managed lyric session state.
+#[derive(Default)]
+pub struct LyricSessionState {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl LyricSessionState {
+    /// Track id and offset of the running session, if any.
+    pub fn offset(&self) -> Option<(String, i64)> {
+        let inner = self.inner.lock();
+        let session = inner.session.as_ref()?;
+        Some((session.track_id.clone(), session.offset_ms))
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    session: Option<Session>,
+    /// Bumped on every load/clear so the previous ticker thread exits.
+    generation: u64,
+    /// The running ticker, unparked by clock syncs and on load/clear.
+    ticker: Option<std::thread::Thread>,
+}
+
+impl Inner {
+    /// Start a new generation; the old ticker is woken so it can exit.
+    fn next_generation(&mut self) -> u64 {
+        self.generation += 1;
+        if let Some(ticker) = self.ticker.take() {
+            ticker.unpark();
+        }
+        self.generation
+    }
+}
+
+struct Session {
+    track_id: String,
+    lines: Vec<LyricLine>,
+    offset_ms: i64,
+    /// Copy of the playback clock's anchor, refreshed on every sync.
+    anchor: ClockAnchor,
+    /// Last announced line; `None` until the first change is emitted.
+    current: Option<Option<usize>>,
+}
+
+impl Session {
+    fn new(track_id: String, mut lines: Vec<LyricLine>, offset_ms: i64, now: Instant) -> Self {
+        lines.sort_by_key(|line| line.time_ms);
+        Self {
+            track_id,
+            lines,
+            offset_ms,
+            anchor: ClockAnchor::new(0, false, 1.0, now),
+            current: None,
+        }
+    }
+
+    /// Re-anchor at a known position at normal speed. The running app copies
+    /// the clock's anchor instead.
+    #[cfg(test)]
+    fn set_position(&mut self, position_ms: u64, playing: bool, now: Instant) {
+        self.anchor = ClockAnchor::new(position_ms, playing, 1.0, now);
+    }
+
+    /// Playback position at `now` with the per-track offset applied. A
+    /// positive offset shows lines earlier.
+    fn lyric_position_ms(&self, now: Instant) -> u64 {
+        (self.anchor.position_at(now) as i64 + self.offset_ms).max(0) as u64
+    }
+
+    /// Recompute the active line; returns a change only if it differs from
+    /// the last announced one. Works the same for forward and backward seeks.
+    fn poll(&mut self, now: Instant) -> Option<LyricLineChange> {
+        let index = active_line_index(&self.lines, self.lyric_position_ms(now));
+        if self.current == Some(index) {
+            return None;
+        }
+        self.current = Some(index);
+        Some(self.change(index))
+    }
+
+    fn change(&self, index: Option<usize>) -> LyricLineChange {
+        let line = index.and_then(|i| self.lines.get(i));
+        LyricLineChange {
+            track_id: self.track_id.clone(),
+            index,
+            text: line.map(|l| l.text.clone()),
+            translation: line.and_then(|l| l.translation.clone()),
+        }
+    }
+}
+
+impl LyricSessionState {
+    /// Run `f` on the active session and emit whatever change it reports.
+    fn with_session(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut Session) -> Option<LyricLineChange>,
+    ) {
+        let change = self.inner.lock().session.as_mut().and_then(f);
+        if let Some(change) = change {
+            let _ = app.emit(LYRIC_LINE_CHANGED_EVENT, change);
+        }
+    }
+
+    fn spawn_ticker(&self, app: AppHandle, generation: u64) {
+        // Held until the handle is stored, so the first tick cannot park
+        // before a sync is able to wake it.
+        let mut guard = self.inner.lock();
+        let inner = Arc::clone(&self.inner);
+        let spawned = std::thread::Builder::new()
+            .name("lyric-session".into())
+            .spawn(move || loop {
+                std::thread::sleep(TICK_INTERVAL);
+                let (change, playing) = {
+                    let mut inner = inner.lock();
+                    if inner.generation != generation {
+                        return;
+                    }
+                    let Some(session) = inner.session.as_mut() else {
+                        return;
+                    };
+                    let playing = session.anchor.is_playing();
+                    let change = if playing {
+                        session.poll(Instant::now())
+                    } else {
+                        None
+                    };
+                    (change, playing)
+                };
+                if let Some(change) = change {
+                    let _ = app.emit(LYRIC_LINE_CHANGED_EVENT, change);
+                }
+                // Paused positions only change through a sync, which polls
+                // and unparks us.
+                if !playing {
+                    std::thread::park();
+                }
+            });
+        match spawned {
+            Ok(handle) if guard.generation == generation => {
+                guard.ticker = Some(handle.thread().clone());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to spawn lyric session ticker: {}", e),
+        }
+    }
+}
+
+fn clock_anchor(app: &AppHandle) -> Option<ClockAnchor> {
+    app.try_state::<PlaybackClock>()?.anchor()
+}
+
+/// Re-anchor the session on the clock right after a sync, so seeks (also
+/// while paused) show up without waiting for the next tick.
+pub(crate) fn on_clock_sync(app: &AppHandle) {
+    let Some(state) = app.try_state::<LyricSessionState>() else {
+        return;
+    };
+    let Some(anchor) = clock_anchor(app) else {
+        return;
+    };
+    state.with_session(app, |session| {
+        session.anchor = anchor;
+        session.poll(Instant::now())
+    });
+    let ticker = state.inner.lock().ticker.clone();
+    if let Some(ticker) = ticker {
+        ticker.unpark();
+    }
+}
+
+/// Start a lyric session for `track_id`, replacing any previous one.
+#[tauri::command(rename_all = "snake_case")]
+pub fn load_lyric_session(
+    app: AppHandle,
+    state: State<'_, LyricSessionState>,
+    track_id: String,
+    lines: Vec<LyricLine>,
+    offset_ms: Option<i64>,
+) {
+    let has_lines = !lines.is_empty();
+    let now = Instant::now();
+    let mut session = Session::new(track_id, lines, offset_ms.unwrap_or(0), now);
+    if let Some(anchor) = clock_anchor(&app) {
+        session.anchor = anchor;
+    }
+    // Announce the initial state right away so consumers drop the previous
+    // track's line even if this one has no lyrics at all.
+    let change = session.poll(now);
+
+    let generation = {
+        let mut inner = state.inner.lock();
+        inner.session = Some(session);
+        inner.next_generation()
+    };
+    if let Some(change) = change {
+        let _ = app.emit(LYRIC_LINE_CHANGED_EVENT, change);
+    }
+    if has_lines {
+        state.spawn_ticker(app, generation);
+    }
+}
+
+/// Change the per-track lyric offset of the running session.
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_lyric_offset(app: AppHandle, state: State<'_, LyricSessionState>, offset_ms: i64) {
+    state.with_session(&app, |session| {
+        session.offset_ms = offset_ms;
+        session.poll(Instant::now())
+    });
+}
+
+/// Tear down the session (playback stopped, or the new track has no id yet).
+#[tauri::command]
+pub fn clear_lyric_session(app: AppHandle, state: State<'_, LyricSessionState>) {
+    let previous = {
+        let mut inner = state.inner.lock();
+        inner.next_generation();
+        inner.session.take()
+    };
+    if let Some(session) = previous {
+        let _ = app.emit(LYRIC_LINE_CHANGED_EVENT, session.change(None));
+    }
+}
+
+/// Current line for consumers that open mid-song (e.g. desktop lyrics).
+#[tauri::command]
+pub fn get_current_lyric_line(state: State<'_, LyricSessionState>) -> Option<LyricLineChange> {
+    let inner = state.inner.lock();
+    let session = inner.session.as_ref()?;
+    let index = active_line_index(&session.lines, session.lyric_position_ms(Instant::now()));
+    Some(session.change(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines() -> Vec<LyricLine> {
+        [(3000, "c"), (1000, "a"), (2000, "b")]
+            .into_iter()
+            .map(|(time_ms, text)| LyricLine {
+                time_ms,
+                text: text.into(),
+                translation: Some(format!("{}-tr", text)),
+            })
+            .collect()
+    }
+
+    fn at(base: Instant, ms: u64) -> Instant {
+        base + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn emits_only_when_line_changes() {
+        let t0 = Instant::now();
+        let mut session = Session::new("1".into(), lines(), 0, t0);
+        assert_eq!(session.poll(t0).unwrap().index, None);
+        assert!(session.poll(t0).is_none());
+
+        session.set_position(900, true, t0);
+        assert!(session.poll(at(t0, 50)).is_none());
+        let change = session.poll(at(t0, 150)).unwrap();
+        assert_eq!(change.index, Some(0));
+        assert_eq!(change.text.as_deref(), Some("a"));
+        assert_eq!(change.translation.as_deref(), Some("a-tr"));
+        assert!(session.poll(at(t0, 600)).is_none());
+        assert_eq!(session.poll(at(t0, 1200)).unwrap().index, Some(1));
+    }
+
+    #[test]
+    fn backward_seek_and_pause_are_handled() {
+        let t0 = Instant::now();
+        let mut session = Session::new("1".into(), lines(), 0, t0);
+        session.set_position(3500, true, t0);
+        assert_eq!(session.poll(t0).unwrap().index, Some(2));
+
+        session.set_position(1500, false, at(t0, 100));
+        assert_eq!(session.poll(at(t0, 100)).unwrap().index, Some(0));
+        // Paused: time passing must not advance the line.
+        assert!(session.poll(at(t0, 5000)).is_none());
+
+        session.set_position(0, true, at(t0, 5000));
+        assert_eq!(session.poll(at(t0, 5000)).unwrap().index, None);
+    }
+
+    #[test]
+    fn offset_shifts_lines_and_never_goes_negative() {
+        let t0 = Instant::now();
+        let mut session = Session::new("1".into(), lines(), 500, t0);
+        session.set_position(1600, false, t0);
+        assert_eq!(session.poll(t0).unwrap().index, Some(1));
+
+        session.offset_ms = -5000;
+        assert_eq!(session.lyric_position_ms(t0), 0);
+        assert_eq!(session.poll(t0).unwrap().index, None);
+    }
+
+    #[test]
+    fn empty_lyrics_announce_no_line_once() {
+        let t0 = Instant::now();
+        let mut session = Session::new("1".into(), Vec::new(), 0, t0);
+        let change = session.poll(t0).unwrap();
+        assert_eq!(change.index, None);
+        assert!(change.text.is_none());
+        session.set_position(10_000, true, t0);
+        assert!(session.poll(at(t0, 1000)).is_none());
+    }
+}
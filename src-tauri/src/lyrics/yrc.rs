@@ -0,0 +1,120 @@
+//! NetEase YRC word-timed lyrics.
+//!
+//! A line is `[start,duration]` followed by words, each introduced by its
+//! own `(start,duration,0)` tag; all times are milliseconds from the start
+//! of the track. Credits come as JSON lines,
+//! `{"t":0,"c":[{"tx":"作词: "},{"tx":"name"}]}`, and become lines without
+//! words. Parentheses that are not timing tags are part of the text, so
+//! `(live)` or `((哦))` survive.
+
+use serde_json::Value;
+
+use super::{settle_words, timing_tags, Word, WordLine};
+
+fn parse_credit(line: &str) -> Option<WordLine> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let start_ms = value.get("t")?.as_u64()?;
+    let text: String = value
+        .get("c")?
+        .as_array()?
+        .iter()
+        .filter_map(|part| part.get("tx")?.as_str())
+        .collect();
+    Some(WordLine {
+        start_ms,
+        duration_ms: 0,
+        text,
+        words: Vec::new(),
+    })
+}
+
+fn parse_line(line: &str) -> Option<WordLine> {
+    let (_, header_end, header) = timing_tags(line, b'[', b']', 2)
+        .into_iter()
+        .next()
+        .filter(|(start, _, _)| *start == 0)?;
+    let body = &line[header_end..];
+    let tags = timing_tags(body, b'(', b')', 3);
+    let mut words: Vec<Word> = tags
+        .iter()
+        .enumerate()
+        .map(|(i, (_, end, numbers))| {
+            let next = tags.get(i + 1).map_or(body.len(), |(start, _, _)| *start);
+            Word {
+                start_ms: numbers[0],
+                duration_ms: numbers[1],
+                text: body[*end..next].to_string(),
+            }
+        })
+        .filter(|word| !word.text.is_empty())
+        .collect();
+    settle_words(&mut words);
+    let text = if words.is_empty() {
+        body.trim().to_string()
+    } else {
+        words.iter().map(|word| word.text.as_str()).collect()
+    };
+    Some(WordLine {
+        start_ms: header[0],
+        duration_ms: header[1],
+        text,
+        words,
+    })
+}
+
+/// Parse YRC text into lines sorted by start time. Lines that are neither
+/// timed nor credits are skipped.
+pub fn parse_yrc(text: &str) -> Vec<WordLine> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines: Vec<WordLine> = text
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| match line.as_bytes().first() {
+            Some(b'{') => parse_credit(line),
+            Some(b'[') => parse_line(line),
+            _ => None,
+        })
+        .collect();
+    lines.sort_by_key(|line| line.start_ms);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_credits_and_bracketed_text() {
+        let text = "{\"t\":0,\"c\":[{\"tx\":\"作词: \"},{\"tx\":\"林夕\"}]}\n\
+                    [16210,3460](16210,670,0)还(16880,410,0)没 (17290,500,0)(live)\
+                    (17790,300,0)((哦))(17500,200,0)了\n\
+                    not a line\n\
+                    [20000,1000]";
+        let lines = parse_yrc(text);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].text, "作词: 林夕");
+        assert!(lines[0].words.is_empty());
+
+        let line = &lines[1];
+        assert_eq!((line.start_ms, line.duration_ms), (16210, 3460));
+        assert_eq!(line.text, "还没 (live)((哦))了");
+        let words: Vec<(u64, u64, &str)> = line
+            .words
+            .iter()
+            .map(|w| (w.start_ms, w.duration_ms, w.text.as_str()))
+            .collect();
+        // The last word starts before the one in front of it and is moved
+        // up to it; the word it overlaps is cut short.
+        assert_eq!(
+            words,
+            vec![
+                (16210, 670, "还"),
+                (16880, 410, "没 "),
+                (17290, 500, "(live)"),
+                (17790, 0, "((哦))"),
+                (17790, 0, "了"),
+            ]
+        );
+        assert_eq!(lines[2].text, "");
+    }
+}
@@ -1,5 +1,7 @@
 //! Mobile (iOS / Android) backend: HTTP, logging, and native media session.
 
+use crate::lyrics::session::LyricSessionState;
+use crate::settings::{self, SettingsStore};
 use crate::shared;
 use gmplayer_audio_backend::commands;
 use tauri::Manager;
@@ -23,18 +25,30 @@ pub fn run() {
             });
     }
 
+    build_app()
+        .build(context)
+        .expect("error while building tauri application")
+        .run(handle_run_event);
+}
+
+/// The app's builder with every plugin, managed state, command and the
+/// setup hook registered, ready for a context.
+pub fn build_app() -> tauri::Builder<tauri::Wry> {
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::new()
                 .level(tauri_plugin_log::log::LevelFilter::Info)
                 .build(),
         )
+        .plugin(crate::first_run::init())
         .plugin(tauri_plugin_http::init())
         // Register the Android MediaNotification / MediaPlaybackService bridge.
         // On non-Android targets this is compiled as a no-op plugin so the same
         // binary can be built for iOS and simulator targets without any changes.
         .plugin(tauri_plugin_media_session::init())
         .plugin(gmplayer_orientation::init())
+        .manage(LyricSessionState::default())
+        .manage(crate::playback_clock::PlaybackClock::default())
         .invoke_handler(tauri::generate_handler![
             shared::detect_desktop,
             shared::desktop_environment,
@@ -49,9 +63,98 @@ pub fn run() {
             commands::audio_analyze_automix_source,
             commands::audio_set_session,
             commands::audio_poll_events,
+            settings::export_settings_backup,
+            settings::eq::get_eq_state,
+            settings::eq::set_eq_band,
+            settings::eq::set_eq_bands,
+            settings::eq::set_eq_preamp,
+            settings::eq::set_eq_enabled,
+            settings::eq::save_eq_preset,
+            settings::eq::load_eq_preset,
+            settings::eq::delete_eq_preset,
+            settings::eq::list_eq_presets,
+            settings::http::get_http_policy,
+            settings::http::set_http_policy,
+            crate::http_util::get_http_stats,
+            crate::http_util::notify_network_changed,
+            crate::api_client::api_fetch,
+            crate::api_client::cookies_get,
+            crate::api_client::cookies_set,
+            crate::api_client::cookies_clear,
+            settings::api_endpoints::get_api_endpoints,
+            settings::api_endpoints::set_api_endpoint,
+            crate::endpoint_health::check_api_endpoint,
+            crate::endpoint_health::get_api_endpoint_health,
+            crate::first_run::get_run_context,
+            // Custom covers
+            crate::covers::set_custom_cover,
+            crate::covers::clear_custom_cover,
+            crate::covers::get_custom_cover,
+            crate::covers::list_custom_covers,
+            // Downloaded cover cache
+            crate::cover_cache::get_cover,
+            crate::cover_cache::clear_cover_cache,
+            crate::cover_cache::cover_cache_stats,
+            crate::algorithms::color::extract_colors,
+            settings::cover_cache::get_cover_cache_settings,
+            settings::cover_cache::set_cover_cache_settings,
+            crate::algorithms::crossfade::plan_crossfade,
+            // Array chunking
+            crate::algorithms::chunk::get_chunk,
+            // Number formatting
+            crate::algorithms::format_number::commands::format_number,
+            // API request crypto
+            crate::algorithms::apicrypto::commands::weapi_encrypt,
+            crate::algorithms::apicrypto::commands::eapi_encrypt,
+            crate::algorithms::apicrypto::commands::linuxapi_encrypt,
+            crate::algorithms::apicrypto::commands::eapi_decrypt,
+            crate::algorithms::apicrypto::commands::md5_sign,
+            // Login QR codes
+            crate::algorithms::qr::generate_qr,
+            // Local library search
+            crate::algorithms::search::search_rank,
+            // NCM container decryption
+            crate::algorithms::ncm::decrypt_ncm,
+            // Lyric session
+            crate::lyrics::parse_lyrics,
+            crate::lyrics::session::load_lyric_session,
+            crate::lyrics::session::set_lyric_offset,
+            crate::lyrics::session::clear_lyric_session,
+            crate::lyrics::session::get_current_lyric_line,
+            crate::playback_clock::sync_playback_clock,
+            crate::playback_clock::get_estimated_position,
+            crate::queue_store::replace_queue,
+            crate::queue_store::apply_queue_diff,
+            crate::queue_store::get_queue_page,
+            crate::queue_store::get_queue_meta,
+            crate::storage::get_storage_health,
+            crate::storage::run_storage_maintenance,
+            crate::cache_store::cache_get,
+            crate::cache_store::cache_set,
+            crate::cache_store::cache_evict,
+            crate::cache_store::cache_stats,
+            crate::cache_store::history_add,
+            crate::cache_store::history_query,
         ])
         .setup(|app| {
             let app_handle = app.handle().clone();
+            app.manage(SettingsStore::load(&app_handle));
+            app.manage(crate::covers::CustomCoverState::load(&app_handle));
+            app.manage(crate::cover_cache::CoverCache::load(&app_handle));
+            match crate::queue_store::QueueStore::load(&app_handle) {
+                Ok(store) => {
+                    app.manage(store);
+                }
+                Err(e) => log::warn!("Queue store disabled: {}", e),
+            }
+            match crate::cache_store::CacheStore::load(&app_handle) {
+                Ok(store) => {
+                    app.manage(store);
+                }
+                Err(e) => log::warn!("Cache store disabled: {}", e),
+            }
+            app.manage(crate::api_client::ApiClient::load(&app_handle));
+            crate::storage::start_integrity_check(&app_handle);
             #[cfg(target_os = "android")]
             {
                 commands::set_android_context_ready_check(android_ndk_context_ready);
@@ -60,11 +163,11 @@ pub fn run() {
             app.manage(commands::PlayerState::new(app_handle));
             Ok(())
         })
-        .build(context)
-        .expect("error while building tauri application")
-        .run(|_app_handle, _event| {});
 }
 
+/// Nothing on mobile reacts to run events yet.
+pub fn handle_run_event(_app_handle: &tauri::AppHandle, _event: tauri::RunEvent) {}
+
 #[cfg(target_os = "android")]
 fn android_ndk_context_ready() -> bool {
     ANDROID_NDK_READY.get().is_some()
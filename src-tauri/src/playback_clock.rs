@@ -0,0 +1,204 @@
+//! The backend's single notion of "where is playback now".
+//!
+//! The frontend syncs the clock only when something discontinuous happens
+//! (play, pause, seek, track or rate change); between syncs the position is
+//! extrapolated from a monotonic `Instant`. Backend consumers read
+//! [`PlaybackClock::now_position`] instead of keeping their own copies, and
+//! push-style consumers (the OS media session, the lyric session) are
+//! notified on every sync.
+
+use std::time::Instant;
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+/// A sync that disagrees with the extrapolation by more than this, without
+/// being a seek, means the clock (or the frontend's timer) drifted.
+const DRIFT_WARN_MS: i64 = 2000;
+const MIN_RATE: f64 = 0.25;
+const MAX_RATE: f64 = 4.0;
+
+/// Position at a known instant plus how it advances.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ClockAnchor {
+    position_ms: u64,
+    at: Instant,
+    playing: bool,
+    rate: f64,
+}
+
+impl ClockAnchor {
+    pub(crate) fn new(position_ms: u64, playing: bool, rate: f64, at: Instant) -> Self {
+        let rate = if rate.is_finite() {
+            rate.clamp(MIN_RATE, MAX_RATE)
+        } else {
+            1.0
+        };
+        Self {
+            position_ms,
+            at,
+            playing,
+            rate,
+        }
+    }
+
+    pub(crate) fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub(crate) fn position_at(&self, now: Instant) -> u64 {
+        if !self.playing {
+            return self.position_ms;
+        }
+        let elapsed = now.saturating_duration_since(self.at).as_millis() as f64;
+        self.position_ms
+            .saturating_add((elapsed * self.rate).round() as u64)
+    }
+}
+
+/// Managed playback clock. Empty until the first sync.
+#[derive(Default)]
+pub struct PlaybackClock {
+    anchor: Mutex<Option<ClockAnchor>>,
+}
+
+impl PlaybackClock {
+    /// Extrapolated position in ms, or `None` before the first sync.
+    pub fn now_position(&self) -> Option<u64> {
+        self.anchor
+            .lock()
+            .map(|anchor| anchor.position_at(Instant::now()))
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.anchor.lock().is_some_and(|anchor| anchor.playing)
+    }
+
+    /// The current anchor, for consumers that extrapolate on their own.
+    pub(crate) fn anchor(&self) -> Option<ClockAnchor> {
+        *self.anchor.lock()
+    }
+
+    /// Re-anchor the clock. Returns the drift (reported minus extrapolated)
+    /// when this was a plain resync rather than a seek.
+    fn sync(
+        &self,
+        position_ms: u64,
+        playing: bool,
+        rate: f64,
+        seeked: bool,
+        now: Instant,
+    ) -> Option<i64> {
+        let mut anchor = self.anchor.lock();
+        let drift = match (*anchor, seeked) {
+            (Some(previous), false) => Some(position_ms as i64 - previous.position_at(now) as i64),
+            _ => None,
+        };
+        *anchor = Some(ClockAnchor::new(position_ms, playing, rate, now));
+        drift
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimatedPosition {
+    pub position_ms: u64,
+    pub playing: bool,
+    pub rate: f64,
+    /// Time since the last sync; long gaps are expected while playing.
+    pub since_sync_ms: u64,
+}
+
+/// Re-anchor the clock and notify push-style consumers. `seeked` marks a
+/// discontinuity (seek or new track) so it is not mistaken for drift.
+pub fn sync(app: &AppHandle, position_ms: u64, playing: bool, rate: Option<f64>, seeked: bool) {
+    let Some(clock) = app.try_state::<PlaybackClock>() else {
+        return;
+    };
+    let drift = clock.sync(
+        position_ms,
+        playing,
+        rate.unwrap_or(1.0),
+        seeked,
+        Instant::now(),
+    );
+    if let Some(drift) = drift.filter(|drift| drift.abs() > DRIFT_WARN_MS) {
+        warn!(
+            "Playback clock drifted {} ms from the reported position {} ms",
+            drift, position_ms
+        );
+    }
+
+    crate::lyrics::session::on_clock_sync(app);
+    #[cfg(not(mobile))]
+    gmplayer_now_playing_controls::sync_playback(app, playing, position_ms, seeked);
+}
+
+/// Report a play, pause, seek, track or rate change.
+#[tauri::command]
+pub fn sync_playback_clock(
+    app: AppHandle,
+    position_ms: u64,
+    playing: bool,
+    rate: Option<f64>,
+    seeked: Option<bool>,
+) {
+    sync(&app, position_ms, playing, rate, seeked.unwrap_or(false));
+}
+
+/// The clock's current estimate, for debugging.
+#[tauri::command]
+pub fn get_estimated_position(clock: State<'_, PlaybackClock>) -> Option<EstimatedPosition> {
+    let anchor = (*clock.anchor.lock())?;
+    let now = Instant::now();
+    Some(EstimatedPosition {
+        position_ms: anchor.position_at(now),
+        playing: anchor.playing,
+        rate: anchor.rate,
+        since_sync_ms: now.saturating_duration_since(anchor.at).as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(base: Instant, ms: u64) -> Instant {
+        base + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn extrapolates_only_while_playing() {
+        let t0 = Instant::now();
+        let playing = ClockAnchor::new(1000, true, 1.0, t0);
+        assert_eq!(playing.position_at(at(t0, 500)), 1500);
+
+        let fast = ClockAnchor::new(1000, true, 1.5, t0);
+        assert_eq!(fast.position_at(at(t0, 1000)), 2500);
+
+        let paused = ClockAnchor::new(1000, false, 1.0, t0);
+        assert_eq!(paused.position_at(at(t0, 5000)), 1000);
+
+        let bogus_rate = ClockAnchor::new(0, true, f64::NAN, t0);
+        assert_eq!(bogus_rate.position_at(at(t0, 100)), 100);
+    }
+
+    #[test]
+    fn drift_is_reported_for_resyncs_but_not_seeks() {
+        let t0 = Instant::now();
+        let clock = PlaybackClock::default();
+        assert_eq!(clock.sync(0, true, 1.0, false, t0), None);
+        assert_eq!(
+            clock.sync(10_300, true, 1.0, false, at(t0, 10_000)),
+            Some(300)
+        );
+        assert_eq!(clock.sync(60_000, true, 1.0, true, at(t0, 11_000)), None);
+        assert_eq!(
+            clock.sync(57_000, false, 1.0, false, at(t0, 14_000)),
+            Some(-6000)
+        );
+    }
+}
@@ -0,0 +1,518 @@
+//! Play queue persistence in SQLite.
+//!
+//! Large queues (thousands of tracks) are too slow to re-serialize into
+//! localStorage on every change. The frontend instead replaces the queue
+//! once and then sends small diffs (insert / remove / move), and reads it
+//! back a page at a time. Each diff op carries the revision it produces:
+//! ops at or below the stored revision were already applied and are
+//! skipped, so resending a batch after a timeout is harmless, while a gap
+//! is rejected and the frontend falls back to [`replace_queue`].
+//!
+//! Rows are keyed by their position, so a page is a primary-key range scan.
+
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+
+pub const QUEUE_DB_FILE: &str = "queue.sqlite3";
+/// Upper bound on a single `get_queue_page` call.
+const MAX_PAGE: usize = 1_000;
+/// Temporary position of the row being moved. [`shift`] uses positions
+/// below this, so the two never collide.
+const PARKED: i64 = -1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS queue_items (
+        position INTEGER PRIMARY KEY,
+        track_key TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS queue_meta (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        revision INTEGER NOT NULL,
+        current_index INTEGER,
+        hash TEXT NOT NULL
+    );
+    INSERT OR IGNORE INTO queue_meta (id, revision, current_index, hash)
+        VALUES (0, 0, NULL, '');
+";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueMeta {
+    pub length: usize,
+    pub current_index: Option<usize>,
+    /// SHA-256 over the track ids in order; empty for an empty queue.
+    pub hash: String,
+    pub revision: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum QueueOpKind {
+    Insert { index: usize, items: Vec<Value> },
+    Remove { index: usize, count: usize },
+    Move { from: usize, to: usize },
+    SetCurrent { index: Option<usize> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueOp {
+    /// Revision the queue has after this op.
+    pub revision: u64,
+    #[serde(flatten)]
+    pub kind: QueueOpKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePage {
+    pub offset: usize,
+    pub items: Vec<Value>,
+    pub length: usize,
+    pub revision: u64,
+}
+
+/// Managed SQLite-backed queue.
+pub struct QueueStore {
+    conn: Mutex<Connection>,
+    /// Database file; `None` when the app data dir is unavailable.
+    path: Option<PathBuf>,
+}
+
+impl QueueStore {
+    /// Open (or create) the queue database in the app data dir. Falls back
+    /// to an in-memory database so the commands keep working; the startup
+    /// integrity check may repair the file and reopen it later. Errors only
+    /// if SQLite cannot open even that.
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => {
+                let _ = std::fs::create_dir_all(&dir);
+                Some(dir.join(QUEUE_DB_FILE))
+            }
+            Err(e) => {
+                warn!("Failed to resolve app data dir for the queue store: {}", e);
+                None
+            }
+        };
+        let conn = match path
+            .as_deref()
+            .ok_or_else(|| "no app data dir".to_string())
+            .and_then(connect)
+        {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(
+                    "Queue store unavailable, keeping the queue in memory: {}",
+                    e
+                );
+                connect_in_memory()?
+            }
+        };
+        Ok(Self {
+            conn: Mutex::new(conn),
+            path,
+        })
+    }
+
+    #[cfg(test)]
+    fn open_in_memory() -> Result<Self, String> {
+        Ok(Self {
+            conn: Mutex::new(connect_in_memory()?),
+            path: None,
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Close the database file while `f` runs (so it can be replaced or
+    /// moved), then reopen it. Commands block until this returns.
+    pub fn with_file_released<T>(&self, f: impl FnOnce() -> T) -> Result<T, String> {
+        let path = self.path.as_deref().ok_or("Queue store has no file")?;
+        let mut conn = self.conn.lock();
+        *conn = connect_in_memory()?;
+        let result = f();
+        *conn = connect(path)?;
+        Ok(result)
+    }
+
+    pub fn meta(&self) -> Result<QueueMeta, String> {
+        read_meta(&self.conn.lock()).map_err(|e| e.to_string())
+    }
+
+    fn replace(&self, items: &[Value], current_index: Option<usize>) -> Result<QueueMeta, String> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let revision = read_meta(&tx).map_err(|e| e.to_string())?.revision + 1;
+        tx.execute("DELETE FROM queue_items", [])
+            .map_err(|e| e.to_string())?;
+        insert_rows(&tx, 0, items)?;
+        let current_index = current_index.filter(|&index| index < items.len());
+        finish(tx, revision, current_index)
+    }
+
+    fn apply(&self, ops: &[QueueOp]) -> Result<QueueMeta, String> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let meta = read_meta(&tx).map_err(|e| e.to_string())?;
+        let mut revision = meta.revision;
+        let mut length = meta.length;
+        let mut current = meta.current_index;
+        for op in ops {
+            if op.revision <= revision {
+                continue;
+            }
+            if op.revision != revision + 1 {
+                return Err(format!(
+                    "Queue revision gap: at {}, got op for {}",
+                    revision, op.revision
+                ));
+            }
+            current = apply_op(&tx, &op.kind, length, current)?;
+            length = count_rows(&tx)?;
+            revision = op.revision;
+        }
+        if revision == meta.revision {
+            return Ok(meta);
+        }
+        finish(tx, revision, current)
+    }
+
+    fn page(&self, offset: usize, limit: usize) -> Result<QueuePage, String> {
+        let conn = self.conn.lock();
+        let meta = read_meta(&conn).map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT data FROM queue_items WHERE position >= ?1 ORDER BY position LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let items = stmt
+            .query_map(params![offset as i64, limit.min(MAX_PAGE) as i64], |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|data| data.ok())
+            .filter_map(|data| serde_json::from_str(&data).ok())
+            .collect();
+        Ok(QueuePage {
+            offset,
+            items,
+            length: meta.length,
+            revision: meta.revision,
+        })
+    }
+}
+
+fn connect(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| e.to_string())?;
+    conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn connect_in_memory() -> Result<Connection, String> {
+    let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn read_meta(conn: &Connection) -> rusqlite::Result<QueueMeta> {
+    let (revision, current_index, hash) = conn.query_row(
+        "SELECT revision, current_index, hash FROM queue_meta WHERE id = 0",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        },
+    )?;
+    let length: i64 = conn.query_row("SELECT COUNT(*) FROM queue_items", [], |row| row.get(0))?;
+    Ok(QueueMeta {
+        length: length as usize,
+        current_index: current_index.map(|index| index as usize),
+        hash,
+        revision: revision as u64,
+    })
+}
+
+fn count_rows(tx: &Transaction) -> Result<usize, String> {
+    tx.query_row("SELECT COUNT(*) FROM queue_items", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|count| count as usize)
+    .map_err(|e| e.to_string())
+}
+
+/// The id used for hashing: the item's `id` field, or the whole item.
+fn track_key(item: &Value) -> String {
+    match item.get("id") {
+        Some(Value::String(id)) => id.clone(),
+        Some(id) if !id.is_null() => id.to_string(),
+        _ => item.to_string(),
+    }
+}
+
+fn insert_rows(tx: &Transaction, start: usize, items: &[Value]) -> Result<(), String> {
+    let mut stmt = tx
+        .prepare_cached("INSERT INTO queue_items (position, track_key, data) VALUES (?1, ?2, ?3)")
+        .map_err(|e| e.to_string())?;
+    for (i, item) in items.iter().enumerate() {
+        stmt.execute(params![
+            (start + i) as i64,
+            track_key(item),
+            item.to_string()
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Shift positions in `[from, to)` by `delta`. Goes through negative
+/// positions (below [`PARKED`]) first so the primary key never collides
+/// mid-update.
+fn shift(tx: &Transaction, from: usize, to: usize, delta: i64) -> Result<(), String> {
+    tx.execute(
+        "UPDATE queue_items SET position = -(position + ?3) - 2
+         WHERE position >= ?1 AND position < ?2",
+        params![from as i64, to as i64, delta],
+    )
+    .and_then(|_| {
+        tx.execute(
+            "UPDATE queue_items SET position = -position - 2 WHERE position <= -2",
+            [],
+        )
+    })
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Apply one op; returns the new current index, which follows its track.
+fn apply_op(
+    tx: &Transaction,
+    op: &QueueOpKind,
+    length: usize,
+    current: Option<usize>,
+) -> Result<Option<usize>, String> {
+    match *op {
+        QueueOpKind::Insert { index, ref items } => {
+            let index = index.min(length);
+            shift(tx, index, length, items.len() as i64)?;
+            insert_rows(tx, index, items)?;
+            Ok(current.map(|c| if c >= index { c + items.len() } else { c }))
+        }
+        QueueOpKind::Remove { index, count } => {
+            let end = index.saturating_add(count).min(length);
+            if index >= end {
+                return Ok(current);
+            }
+            tx.execute(
+                "DELETE FROM queue_items WHERE position >= ?1 AND position < ?2",
+                params![index as i64, end as i64],
+            )
+            .map_err(|e| e.to_string())?;
+            shift(tx, end, length, -((end - index) as i64))?;
+            let removed = end - index;
+            Ok(match current {
+                Some(c) if c >= end => Some(c - removed),
+                Some(c) if c >= index => (index < length - removed).then_some(index),
+                other => other,
+            })
+        }
+        QueueOpKind::Move { from, to } => {
+            if from >= length || to >= length || from == to {
+                return Ok(current);
+            }
+            // Park the moved row, close the gap, then drop it in place.
+            tx.execute(
+                "UPDATE queue_items SET position = ?1 WHERE position = ?2",
+                params![PARKED, from as i64],
+            )
+            .map_err(|e| e.to_string())?;
+            if from < to {
+                shift(tx, from + 1, to + 1, -1)?;
+            } else {
+                shift(tx, to, from, 1)?;
+            }
+            tx.execute(
+                "UPDATE queue_items SET position = ?1 WHERE position = ?2",
+                params![to as i64, PARKED],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(current.map(|c| {
+                if c == from {
+                    to
+                } else if from < c && c <= to {
+                    c - 1
+                } else if to <= c && c < from {
+                    c + 1
+                } else {
+                    c
+                }
+            }))
+        }
+        QueueOpKind::SetCurrent { index } => Ok(index.filter(|&index| index < length)),
+    }
+}
+
+/// Recompute the hash and store the new revision, then commit.
+fn finish(
+    tx: Transaction,
+    revision: u64,
+    current_index: Option<usize>,
+) -> Result<QueueMeta, String> {
+    let mut hasher = Sha256::new();
+    let mut length = 0usize;
+    {
+        let mut stmt = tx
+            .prepare_cached("SELECT track_key FROM queue_items ORDER BY position")
+            .map_err(|e| e.to_string())?;
+        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            let key: String = row.get(0).map_err(|e| e.to_string())?;
+            hasher.update(key.as_bytes());
+            hasher.update(b"\n");
+            length += 1;
+        }
+    }
+    let hash = if length == 0 {
+        String::new()
+    } else {
+        hex::encode(hasher.finalize())
+    };
+    let current_index = current_index.filter(|&index| index < length);
+    tx.execute(
+        "UPDATE queue_meta SET revision = ?1, current_index = ?2, hash = ?3 WHERE id = 0",
+        params![revision as i64, current_index.map(|i| i as i64), hash],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(QueueMeta {
+        length,
+        current_index,
+        hash,
+        revision,
+    })
+}
+
+/// Replace the whole queue. Bumps the revision by one.
+#[tauri::command]
+pub fn replace_queue(
+    store: State<'_, QueueStore>,
+    items: Vec<Value>,
+    current_index: Option<usize>,
+) -> Result<QueueMeta, String> {
+    store.replace(&items, current_index)
+}
+
+/// Apply diff ops in one transaction; all or nothing.
+#[tauri::command]
+pub fn apply_queue_diff(
+    store: State<'_, QueueStore>,
+    ops: Vec<QueueOp>,
+) -> Result<QueueMeta, String> {
+    store.apply(&ops)
+}
+
+#[tauri::command]
+pub fn get_queue_page(
+    store: State<'_, QueueStore>,
+    offset: usize,
+    limit: usize,
+) -> Result<QueuePage, String> {
+    store.page(offset, limit)
+}
+
+#[tauri::command]
+pub fn get_queue_meta(store: State<'_, QueueStore>) -> Result<QueueMeta, String> {
+    store.meta()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(store: &QueueStore) -> Vec<i64> {
+        store
+            .page(0, MAX_PAGE)
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    fn op(revision: u64, kind: QueueOpKind) -> QueueOp {
+        QueueOp { revision, kind }
+    }
+
+    #[test]
+    fn diffs_edit_in_place_and_are_idempotent() {
+        let store = QueueStore::open_in_memory().unwrap();
+        let items: Vec<Value> = (0..5).map(|id| json!({ "id": id })).collect();
+        let meta = store.replace(&items, Some(2)).unwrap();
+        assert_eq!(meta.revision, 1);
+        let original_hash = meta.hash.clone();
+
+        let ops = [
+            op(2, QueueOpKind::Move { from: 0, to: 3 }),
+            op(
+                3,
+                QueueOpKind::Insert {
+                    index: 1,
+                    items: vec![json!({ "id": 9 })],
+                },
+            ),
+            op(4, QueueOpKind::Remove { index: 4, count: 1 }),
+        ];
+        let meta = store.apply(&ops).unwrap();
+        assert_eq!(ids(&store), vec![1, 9, 2, 3, 4]);
+        // Track 2 was current throughout.
+        assert_eq!(meta.current_index, Some(2));
+        assert_eq!(meta.revision, 4);
+        assert_ne!(meta.hash, original_hash);
+
+        // Resending the same batch changes nothing.
+        assert_eq!(store.apply(&ops).unwrap(), meta);
+        assert_eq!(ids(&store), vec![1, 9, 2, 3, 4]);
+    }
+
+    #[test]
+    fn revision_gaps_roll_back_the_whole_batch() {
+        let store = QueueStore::open_in_memory().unwrap();
+        store
+            .replace(&[json!({ "id": 1 }), json!({ "id": 2 })], None)
+            .unwrap();
+        let ops = [
+            op(2, QueueOpKind::Remove { index: 0, count: 1 }),
+            op(4, QueueOpKind::SetCurrent { index: Some(0) }),
+        ];
+        assert!(store.apply(&ops).is_err());
+        assert_eq!(ids(&store), vec![1, 2]);
+        assert_eq!(store.meta().unwrap().revision, 1);
+    }
+
+    #[test]
+    fn pages_read_a_large_queue_by_position() {
+        let store = QueueStore::open_in_memory().unwrap();
+        let items: Vec<Value> = (0..10_000).map(|id| json!({ "id": id })).collect();
+        store.replace(&items, Some(0)).unwrap();
+        let page = store.page(9_990, 50).unwrap();
+        assert_eq!(page.length, 10_000);
+        assert_eq!(page.items.len(), 10);
+        assert_eq!(page.items[0]["id"], 9_990);
+    }
+}
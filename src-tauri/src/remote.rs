@@ -0,0 +1,151 @@
+//! Tokens for the LAN remote-control API.
+//!
+//! Tokens are issued with an explicit set of [`RemoteScope`]s and stored
+//! only as SHA-256 hashes in the settings store. This module just issues,
+//! lists and revokes them: there is no HTTP / WebSocket server in the app
+//! yet, so no request is checked against a token. Enforcing the scopes, and
+//! switching the API on, belong with that server.
+
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::settings::remote::{RemoteToken, MAX_REMOTE_TOKENS};
+use crate::settings::SettingsStore;
+
+pub use crate::settings::remote::RemoteScope;
+
+const MAX_LABEL_LEN: usize = 64;
+
+/// Token metadata shown in the settings UI; never includes the secret.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteTokenInfo {
+    pub id: String,
+    pub label: String,
+    pub scopes: BTreeSet<RemoteScope>,
+    pub created_ms: u64,
+    pub last_used_ms: Option<u64>,
+    pub last_origin: Option<String>,
+}
+
+impl From<&RemoteToken> for RemoteTokenInfo {
+    fn from(token: &RemoteToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            label: token.label.clone(),
+            scopes: token.scopes.clone(),
+            created_ms: token.created_ms,
+            last_used_ms: token.last_used_ms,
+            last_origin: token.last_origin.clone(),
+        }
+    }
+}
+
+/// A freshly issued token. `token` is the only time the secret is returned.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedRemoteToken {
+    pub token: String,
+    pub info: RemoteTokenInfo,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn issue(label: String, scopes: BTreeSet<RemoteScope>) -> (String, RemoteToken) {
+    let secret = random_hex(32);
+    let token = RemoteToken {
+        id: random_hex(8),
+        label,
+        scopes,
+        token_hash: hash_token(&secret),
+        created_ms: now_ms(),
+        last_used_ms: None,
+        last_origin: None,
+    };
+    (secret, token)
+}
+
+#[tauri::command]
+pub fn create_remote_token(
+    store: State<'_, SettingsStore>,
+    permissions: Vec<RemoteScope>,
+    label: String,
+) -> Result<CreatedRemoteToken, String> {
+    let label = label.trim();
+    if label.is_empty() || label.chars().count() > MAX_LABEL_LEN {
+        return Err(format!(
+            "Token label must be 1-{} characters",
+            MAX_LABEL_LEN
+        ));
+    }
+    if permissions.is_empty() {
+        return Err("A remote token needs at least one permission".into());
+    }
+    let (secret, token) = issue(label.to_string(), permissions.into_iter().collect());
+    store.update(|s| {
+        if s.remote.tokens.len() >= MAX_REMOTE_TOKENS {
+            return Err(format!(
+                "At most {} remote tokens can exist",
+                MAX_REMOTE_TOKENS
+            ));
+        }
+        s.remote.tokens.push(token.clone());
+        Ok(())
+    })?;
+    Ok(CreatedRemoteToken {
+        token: secret,
+        info: RemoteTokenInfo::from(&token),
+    })
+}
+
+/// Revoke a token. Returns whether it existed.
+#[tauri::command]
+pub fn revoke_remote_token(store: State<'_, SettingsStore>, id: String) -> Result<bool, String> {
+    store.update(|s| {
+        let before = s.remote.tokens.len();
+        s.remote.tokens.retain(|t| t.id != id);
+        Ok(s.remote.tokens.len() != before)
+    })
+}
+
+#[tauri::command]
+pub fn list_remote_tokens(store: State<'_, SettingsStore>) -> Vec<RemoteTokenInfo> {
+    store.read(|s| s.remote.tokens.iter().map(RemoteTokenInfo::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_hash_is_stored() {
+        let (secret, token) = issue("tablet".into(), BTreeSet::new());
+        assert_ne!(token.token_hash, secret);
+        assert_eq!(token.token_hash, hash_token(&secret));
+        assert_eq!(
+            serde_json::to_value(RemoteScope::QueueWrite).unwrap(),
+            "queue-write"
+        );
+    }
+}
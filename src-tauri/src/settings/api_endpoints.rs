@@ -0,0 +1,130 @@
+//! Per-source API base URLs for self-hosted music API instances.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Url};
+
+use super::SettingsStore;
+
+const MAX_SOURCE_LEN: usize = 32;
+
+/// Base URL per source id (`"netease"`, ...). Sources without an entry use
+/// the frontend's built-in default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ApiEndpoints(pub BTreeMap<String, String>);
+
+impl ApiEndpoints {
+    pub(super) fn sanitize(&mut self) {
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .filter(|(source, _)| check_source(source).is_ok())
+            .filter_map(|(source, url)| Some((source, normalize_url(&url).ok()?)))
+            .collect();
+    }
+}
+
+fn check_source(source: &str) -> Result<(), String> {
+    let valid = !source.is_empty()
+        && source.len() <= MAX_SOURCE_LEN
+        && source
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid API source id '{}'", source))
+    }
+}
+
+/// Accept only absolute http(s) URLs with a host and no credentials, and
+/// drop trailing slashes so callers can append paths directly.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid API URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("API URL must use http or https: '{}'", url));
+    }
+    if parsed.host_str().map_or(true, str::is_empty) {
+        return Err(format!("API URL has no host: '{}'", url));
+    }
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err("API URL must not contain credentials".into());
+    }
+    if parsed.fragment().is_some() {
+        return Err(format!("API URL must not contain a fragment: '{}'", url));
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+#[tauri::command]
+pub fn get_api_endpoints(store: State<'_, SettingsStore>) -> BTreeMap<String, String> {
+    store.read(|s| s.api_endpoints.0.clone())
+}
+
+/// Set the base URL for `source`; an empty `url` restores the default.
+/// Returns the stored (normalized) URL.
+#[tauri::command]
+pub fn set_api_endpoint(
+    store: State<'_, SettingsStore>,
+    source: String,
+    url: String,
+) -> Result<Option<String>, String> {
+    check_source(&source)?;
+    let url = if url.trim().is_empty() {
+        None
+    } else {
+        Some(normalize_url(&url)?)
+    };
+    store.update(|s| {
+        match &url {
+            Some(url) => s.api_endpoints.0.insert(source.clone(), url.clone()),
+            None => s.api_endpoints.0.remove(&source),
+        };
+        Ok(())
+    })?;
+    crate::endpoint_health::forget(&source);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_validated_and_normalized() {
+        assert_eq!(
+            normalize_url(" https://api.example.com/v1/// ").unwrap(),
+            "https://api.example.com/v1"
+        );
+        assert_eq!(
+            normalize_url("http://192.168.1.5:3000/").unwrap(),
+            "http://192.168.1.5:3000"
+        );
+        for bad in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "ftp://example.com",
+            "https://user:pw@example.com",
+            "example.com",
+            "",
+        ] {
+            assert!(normalize_url(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn sanitize_drops_invalid_entries() {
+        let mut endpoints = ApiEndpoints(BTreeMap::from([
+            ("netease".to_string(), "https://a.example/".to_string()),
+            ("Bad Source".to_string(), "https://b.example".to_string()),
+            ("qq".to_string(), "file:///tmp".to_string()),
+        ]));
+        endpoints.sanitize();
+        assert_eq!(
+            endpoints.0,
+            BTreeMap::from([("netease".to_string(), "https://a.example".to_string())])
+        );
+    }
+}
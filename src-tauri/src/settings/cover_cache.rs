@@ -0,0 +1,52 @@
+//! Size limit of the downloaded cover cache, see [`crate::cover_cache`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CoverCacheSettings {
+    /// Least recently used covers are deleted beyond this.
+    pub max_mb: u64,
+}
+
+impl Default for CoverCacheSettings {
+    fn default() -> Self {
+        Self { max_mb: 200 }
+    }
+}
+
+impl CoverCacheSettings {
+    pub(super) fn sanitize(&mut self) {
+        self.max_mb = self.max_mb.clamp(16, 10_240);
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_mb * 1024 * 1024
+    }
+}
+
+#[tauri::command]
+pub fn get_cover_cache_settings(store: State<'_, SettingsStore>) -> CoverCacheSettings {
+    store.read(|s| s.cover_cache.clone())
+}
+
+/// Save the limit; a smaller one evicts right away.
+#[tauri::command]
+pub fn set_cover_cache_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    settings: CoverCacheSettings,
+) -> Result<CoverCacheSettings, String> {
+    let settings = store.update(|s| {
+        let mut settings = settings;
+        settings.sanitize();
+        s.cover_cache = settings.clone();
+        Ok(settings)
+    })?;
+    crate::cover_cache::trim(&app);
+    Ok(settings)
+}
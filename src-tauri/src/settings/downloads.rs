@@ -0,0 +1,52 @@
+//! Download queue limits, see [`crate::downloads`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DownloadSettings {
+    /// Downloads running at once; the rest wait in the queue.
+    pub max_concurrent: usize,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self { max_concurrent: 3 }
+    }
+}
+
+impl DownloadSettings {
+    pub(super) fn sanitize(&mut self) {
+        self.max_concurrent = self.max_concurrent.clamp(1, 8);
+    }
+}
+
+#[tauri::command]
+pub fn get_download_settings(store: State<'_, SettingsStore>) -> DownloadSettings {
+    store.read(|s| s.downloads.clone())
+}
+
+/// Save the limit; a higher one starts queued downloads right away, a lower
+/// one lets running downloads finish.
+#[tauri::command]
+pub fn set_download_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    settings: DownloadSettings,
+) -> Result<DownloadSettings, String> {
+    let settings = store.update(|s| {
+        let mut settings = settings;
+        settings.sanitize();
+        s.downloads = settings.clone();
+        Ok(settings)
+    })?;
+    #[cfg(not(mobile))]
+    crate::downloads::pump(&app);
+    #[cfg(mobile)]
+    let _ = app;
+    Ok(settings)
+}
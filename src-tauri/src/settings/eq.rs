@@ -0,0 +1,729 @@
+//! Equalizer / preamp settings and preset management.
+//!
+//! The EQ itself runs in the frontend DSP chain; this section only owns the
+//! persisted state so every window (main, mini-player, equalizer) reads the
+//! same values and is told about changes through `eq-changed`. Bands use the
+//! frontend's model (filter type, frequency, gain, Q), and edits are saved on
+//! a debounce because they arrive once per slider step.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use super::SettingsStore;
+
+pub const EQ_GAIN_MIN_DB: f32 = -12.0;
+pub const EQ_GAIN_MAX_DB: f32 = 12.0;
+const EQ_FREQUENCY_MIN_HZ: f32 = 10.0;
+const EQ_FREQUENCY_MAX_HZ: f32 = 22000.0;
+const EQ_Q_MIN: f32 = 0.1;
+const EQ_Q_MAX: f32 = 18.0;
+const DEFAULT_BAND_COUNT: usize = 10;
+const MAX_PRESET_NAME_LEN: usize = 64;
+pub const EQ_CHANGED_EVENT: &str = "eq-changed";
+
+const FREQUENCIES_10: &[f32] = &[
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+const FREQUENCIES_15: &[f32] = &[
+    25.0, 40.0, 63.0, 100.0, 160.0, 250.0, 400.0, 630.0, 1000.0, 1600.0, 2500.0, 4000.0, 6300.0,
+    10000.0, 16000.0,
+];
+const FREQUENCIES_31: &[f32] = &[
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0, 6300.0,
+    8000.0, 10000.0, 12500.0, 16000.0, 20000.0,
+];
+
+/// Built-in presets, described as (frequency, gain dB) anchors so they can be
+/// evaluated at any band frequency. Ids match the frontend preset picker.
+struct BuiltinPreset {
+    id: &'static str,
+    name: &'static str,
+    curve: &'static [(f32, f32)],
+}
+
+const BUILTIN_PRESETS: &[BuiltinPreset] = &[
+    BuiltinPreset {
+        id: "flat",
+        name: "Flat",
+        curve: &[(20.0, 0.0), (20000.0, 0.0)],
+    },
+    BuiltinPreset {
+        id: "bass",
+        name: "Bass",
+        curve: &[
+            (20.0, 5.0),
+            (63.0, 4.0),
+            (125.0, 3.0),
+            (250.0, 1.5),
+            (500.0, 0.0),
+            (20000.0, 0.0),
+        ],
+    },
+    BuiltinPreset {
+        id: "vocal",
+        name: "Vocal",
+        curve: &[
+            (20.0, -2.0),
+            (125.0, -1.0),
+            (250.0, 0.0),
+            (500.0, 2.0),
+            (1000.0, 3.0),
+            (2500.0, 2.5),
+            (5000.0, 0.0),
+            (20000.0, -2.0),
+        ],
+    },
+    BuiltinPreset {
+        id: "rock",
+        name: "Rock",
+        curve: &[
+            (20.0, 3.0),
+            (80.0, 2.0),
+            (250.0, -1.0),
+            (500.0, -2.0),
+            (1000.0, 0.0),
+            (4000.0, 3.0),
+            (8000.0, 2.0),
+            (20000.0, 1.0),
+        ],
+    },
+    BuiltinPreset {
+        id: "electronic",
+        name: "Electronic",
+        curve: &[
+            (20.0, 4.0),
+            (80.0, 3.0),
+            (250.0, 1.0),
+            (500.0, -1.0),
+            (1000.0, 0.0),
+            (4000.0, 3.0),
+            (10000.0, 4.0),
+            (20000.0, 3.0),
+        ],
+    },
+    BuiltinPreset {
+        id: "classical",
+        name: "Classical",
+        curve: &[
+            (20.0, 0.0),
+            (2000.0, 0.0),
+            (4000.0, -1.0),
+            (8000.0, 0.0),
+            (16000.0, 2.0),
+            (20000.0, 2.0),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EqFilterType {
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+/// One EQ band, as the frontend DSP chain consumes it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EqBand {
+    pub enabled: bool,
+    pub filter_type: EqFilterType,
+    pub frequency: f32,
+    pub gain_db: f32,
+    pub q: f32,
+}
+
+impl EqBand {
+    /// Default band `index` of a `count`-band EQ: shelves at both ends,
+    /// peaking filters in between, narrower Q for the 31-band layout.
+    fn default_at(frequency: f32, index: usize, count: usize) -> Self {
+        Self {
+            enabled: true,
+            filter_type: if index == 0 {
+                EqFilterType::LowShelf
+            } else if index + 1 == count {
+                EqFilterType::HighShelf
+            } else {
+                EqFilterType::Peaking
+            },
+            frequency,
+            gain_db: 0.0,
+            q: if count >= 31 { 4.318 } else { 1.414 },
+        }
+    }
+
+    fn sanitize(&mut self, fallback: &EqBand) {
+        self.gain_db = clamp_gain(self.gain_db);
+        self.frequency = if self.frequency.is_finite() {
+            self.frequency
+                .clamp(EQ_FREQUENCY_MIN_HZ, EQ_FREQUENCY_MAX_HZ)
+        } else {
+            fallback.frequency
+        };
+        self.q = if self.q.is_finite() {
+            self.q.clamp(EQ_Q_MIN, EQ_Q_MAX)
+        } else {
+            fallback.q
+        };
+    }
+
+    fn validate(self) -> Result<Self, String> {
+        validate_gain(self.gain_db)?;
+        if !(self.frequency.is_finite()
+            && (EQ_FREQUENCY_MIN_HZ..=EQ_FREQUENCY_MAX_HZ).contains(&self.frequency))
+        {
+            return Err(format!(
+                "Frequency {} Hz is outside {}..{} Hz",
+                self.frequency, EQ_FREQUENCY_MIN_HZ, EQ_FREQUENCY_MAX_HZ
+            ));
+        }
+        if !(self.q.is_finite() && (EQ_Q_MIN..=EQ_Q_MAX).contains(&self.q)) {
+            return Err(format!(
+                "Q {} is outside {}..{}",
+                self.q, EQ_Q_MIN, EQ_Q_MAX
+            ));
+        }
+        Ok(self)
+    }
+}
+
+/// Default bands for a supported band count.
+fn default_bands(count: usize) -> Vec<EqBand> {
+    let frequencies = band_frequencies(count).unwrap_or(FREQUENCIES_10);
+    frequencies
+        .iter()
+        .enumerate()
+        .map(|(index, &f)| EqBand::default_at(f, index, frequencies.len()))
+        .collect()
+}
+
+/// Bands as stored on disk. Builds before the band model matched the
+/// frontend saved plain gains, which map onto the default bands.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredBands {
+    Bands(Vec<EqBand>),
+    Gains(Vec<f32>),
+}
+
+fn deserialize_bands<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<EqBand>, D::Error> {
+    Ok(match StoredBands::deserialize(deserializer)? {
+        StoredBands::Bands(bands) => bands,
+        StoredBands::Gains(gains) => bands_from_gains(&gains).unwrap_or_default(),
+    })
+}
+
+/// Default bands carrying `gains`, if there are 10, 15 or 31 of them.
+pub(crate) fn bands_from_gains(gains: &[f32]) -> Option<Vec<EqBand>> {
+    band_frequencies(gains.len())?;
+    let mut bands = default_bands(gains.len());
+    for (band, &gain) in bands.iter_mut().zip(gains) {
+        band.gain_db = gain;
+    }
+    Some(bands)
+}
+
+/// Persisted EQ section of the settings store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EqSettings {
+    pub enabled: bool,
+    pub preamp_db: f32,
+    /// The band count must be 10, 15 or 31.
+    #[serde(deserialize_with = "deserialize_bands")]
+    pub bands: Vec<EqBand>,
+    /// Id of the last applied preset, or "custom" after manual edits.
+    pub preset: String,
+    pub custom_presets: BTreeMap<String, EqPreset>,
+}
+
+impl Default for EqSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            preamp_db: 0.0,
+            bands: default_bands(DEFAULT_BAND_COUNT),
+            preset: "flat".into(),
+            custom_presets: BTreeMap::new(),
+        }
+    }
+}
+
+/// A user-saved preset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqPreset {
+    pub preamp_db: f32,
+    #[serde(deserialize_with = "deserialize_bands")]
+    pub bands: Vec<EqBand>,
+}
+
+/// EQ state as reported to the frontend and broadcast with `eq-changed`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EqState {
+    pub enabled: bool,
+    pub preamp_db: f32,
+    pub bands: Vec<EqBand>,
+    pub preset: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EqPresetInfo {
+    pub id: String,
+    pub name: String,
+    pub builtin: bool,
+}
+
+impl EqSettings {
+    pub(super) fn sanitize(&mut self) {
+        sanitize_bands(&mut self.bands);
+        self.preamp_db = clamp_gain(self.preamp_db);
+        for preset in self.custom_presets.values_mut() {
+            preset.preamp_db = clamp_gain(preset.preamp_db);
+            sanitize_bands(&mut preset.bands);
+        }
+        if self.preset != "custom"
+            && builtin_preset(&self.preset).is_none()
+            && !self.custom_presets.contains_key(&self.preset)
+        {
+            self.preset = "custom".into();
+        }
+    }
+
+    fn state(&self) -> EqState {
+        EqState {
+            enabled: self.enabled,
+            preamp_db: self.preamp_db,
+            bands: self.bands.clone(),
+            preset: self.preset.clone(),
+        }
+    }
+}
+
+fn sanitize_bands(bands: &mut Vec<EqBand>) {
+    if band_frequencies(bands.len()).is_none() {
+        *bands = default_bands(DEFAULT_BAND_COUNT);
+    }
+    let defaults = default_bands(bands.len());
+    for (band, fallback) in bands.iter_mut().zip(&defaults) {
+        band.sanitize(fallback);
+    }
+}
+
+/// Frequency set for a supported band count.
+fn band_frequencies(count: usize) -> Option<&'static [f32]> {
+    match count {
+        10 => Some(FREQUENCIES_10),
+        15 => Some(FREQUENCIES_15),
+        31 => Some(FREQUENCIES_31),
+        _ => None,
+    }
+}
+
+fn check_band_count(count: usize) -> Result<(), String> {
+    match band_frequencies(count) {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "Unsupported band count {} (expected 10, 15 or 31)",
+            count
+        )),
+    }
+}
+
+fn clamp_gain(gain: f32) -> f32 {
+    if gain.is_finite() {
+        gain.clamp(EQ_GAIN_MIN_DB, EQ_GAIN_MAX_DB)
+    } else {
+        0.0
+    }
+}
+
+fn validate_gain(gain: f32) -> Result<f32, String> {
+    if gain.is_finite() && (EQ_GAIN_MIN_DB..=EQ_GAIN_MAX_DB).contains(&gain) {
+        Ok(gain)
+    } else {
+        Err(format!(
+            "Gain {} dB is outside {}..{} dB",
+            gain, EQ_GAIN_MIN_DB, EQ_GAIN_MAX_DB
+        ))
+    }
+}
+
+/// Evaluate a (frequency, gain) curve at `frequency`, interpolating on a log2 axis.
+fn curve_gain_at(curve: &[(f32, f32)], frequency: f32) -> f32 {
+    let Some(&(first_freq, first_gain)) = curve.first() else {
+        return 0.0;
+    };
+    if frequency <= first_freq {
+        return first_gain;
+    }
+    let x = frequency.log2();
+    for pair in curve.windows(2) {
+        let (prev_freq, prev_gain) = pair[0];
+        let (next_freq, next_gain) = pair[1];
+        if frequency <= next_freq {
+            let t = (x - prev_freq.log2()) / (next_freq.log2() - prev_freq.log2());
+            return prev_gain + (next_gain - prev_gain) * t;
+        }
+    }
+    curve[curve.len() - 1].1
+}
+
+/// Map bands onto the default layout for another band count. Each new band
+/// takes the gain and on/off state of the nearest old band on a log axis.
+fn resample_bands(bands: &[EqBand], count: usize) -> Vec<EqBand> {
+    if bands.len() == count {
+        return bands.to_vec();
+    }
+    let mut resampled = default_bands(count);
+    for band in &mut resampled {
+        let distance = |other: &EqBand| (other.frequency.log2() - band.frequency.log2()).abs();
+        if let Some(nearest) = bands
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        {
+            band.enabled = nearest.enabled;
+            band.gain_db = nearest.gain_db;
+        }
+    }
+    resampled
+}
+
+fn builtin_preset(id: &str) -> Option<&'static BuiltinPreset> {
+    BUILTIN_PRESETS
+        .iter()
+        .find(|preset| preset.id.eq_ignore_ascii_case(id) || preset.name.eq_ignore_ascii_case(id))
+}
+
+fn validate_preset_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Preset name must not be empty".into());
+    }
+    if name.chars().count() > MAX_PRESET_NAME_LEN {
+        return Err(format!(
+            "Preset name must be at most {} characters",
+            MAX_PRESET_NAME_LEN
+        ));
+    }
+    if name.eq_ignore_ascii_case("custom") || builtin_preset(name).is_some() {
+        return Err(format!("'{}' is reserved for a built-in preset", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Apply `f` in memory, tell every window, and leave the disk write to the
+/// store's debounced saver.
+fn update_and_broadcast(
+    app: &AppHandle,
+    store: &SettingsStore,
+    f: impl FnOnce(&mut EqSettings) -> Result<(), String>,
+) -> Result<EqState, String> {
+    let state = store.update_deferred(app, |settings| {
+        f(&mut settings.eq)?;
+        Ok(settings.eq.state())
+    })?;
+    let _ = app.emit(EQ_CHANGED_EVENT, &state);
+    Ok(state)
+}
+
+/// Get the current EQ state.
+#[tauri::command]
+pub fn get_eq_state(store: State<'_, SettingsStore>) -> EqState {
+    store.read(|settings| settings.eq.state())
+}
+
+/// Replace a single band (gain -12..=12 dB, frequency 10..=22000 Hz, Q 0.1..=18).
+#[tauri::command]
+pub async fn set_eq_band(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    index: usize,
+    band: EqBand,
+) -> Result<EqState, String> {
+    let band = band.validate()?;
+    update_and_broadcast(&app, &store, |eq| {
+        let count = eq.bands.len();
+        let slot = eq
+            .bands
+            .get_mut(index)
+            .ok_or_else(|| format!("Band index {} out of range (0..{})", index, count))?;
+        *slot = band;
+        eq.preset = "custom".into();
+        Ok(())
+    })
+}
+
+/// Replace all bands at once. The band count must be 10, 15 or 31.
+#[tauri::command]
+pub async fn set_eq_bands(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    bands: Vec<EqBand>,
+) -> Result<EqState, String> {
+    check_band_count(bands.len())?;
+    let bands = bands
+        .into_iter()
+        .map(EqBand::validate)
+        .collect::<Result<Vec<_>, _>>()?;
+    update_and_broadcast(&app, &store, |eq| {
+        eq.bands = bands;
+        eq.preset = "custom".into();
+        Ok(())
+    })
+}
+
+/// Switch to the default 10, 15 or 31 band layout, carrying gains over
+/// from the nearest current band.
+#[tauri::command]
+pub async fn set_eq_band_count(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    count: usize,
+) -> Result<EqState, String> {
+    check_band_count(count)?;
+    update_and_broadcast(&app, &store, |eq| {
+        if eq.bands.len() != count {
+            eq.bands = resample_bands(&eq.bands, count);
+            eq.preset = "custom".into();
+        }
+        Ok(())
+    })
+}
+
+/// Set the preamp gain (dB, -12..=12).
+#[tauri::command]
+pub async fn set_eq_preamp(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    gain: f32,
+) -> Result<EqState, String> {
+    let gain = validate_gain(gain)?;
+    update_and_broadcast(&app, &store, |eq| {
+        eq.preamp_db = gain;
+        eq.preset = "custom".into();
+        Ok(())
+    })
+}
+
+/// Enable or bypass the equalizer.
+#[tauri::command]
+pub async fn set_eq_enabled(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<EqState, String> {
+    update_and_broadcast(&app, &store, |eq| {
+        eq.enabled = enabled;
+        Ok(())
+    })
+}
+
+/// Save the current bands and preamp as a named user preset (overwrites).
+#[tauri::command]
+pub async fn save_eq_preset(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    name: String,
+) -> Result<EqState, String> {
+    let name = validate_preset_name(&name)?;
+    update_and_broadcast(&app, &store, |eq| {
+        let preset = EqPreset {
+            preamp_db: eq.preamp_db,
+            bands: eq.bands.clone(),
+        };
+        eq.custom_presets.insert(name.clone(), preset);
+        eq.preset = name;
+        Ok(())
+    })
+}
+
+/// Apply a built-in or user preset. Built-in curves are evaluated at the
+/// current band frequencies; user presets are resampled to the band count.
+#[tauri::command]
+pub async fn load_eq_preset(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    name: String,
+) -> Result<EqState, String> {
+    update_and_broadcast(&app, &store, |eq| {
+        if let Some(preset) = builtin_preset(&name) {
+            for band in &mut eq.bands {
+                band.enabled = true;
+                // One decimal, like the slider step.
+                let gain = curve_gain_at(preset.curve, band.frequency);
+                band.gain_db = clamp_gain((gain * 10.0).round() / 10.0);
+            }
+            eq.preamp_db = 0.0;
+            eq.preset = preset.id.into();
+            return Ok(());
+        }
+
+        let preset = eq
+            .custom_presets
+            .get(name.trim())
+            .cloned()
+            .ok_or_else(|| format!("EQ preset '{}' not found", name))?;
+        eq.bands = resample_bands(&preset.bands, eq.bands.len());
+        eq.preamp_db = preset.preamp_db;
+        eq.preset = name.trim().to_string();
+        Ok(())
+    })
+}
+
+/// Delete a user preset. Built-in presets cannot be deleted.
+#[tauri::command]
+pub async fn delete_eq_preset(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    name: String,
+) -> Result<EqState, String> {
+    update_and_broadcast(&app, &store, |eq| {
+        eq.custom_presets
+            .remove(name.trim())
+            .ok_or_else(|| format!("EQ preset '{}' not found", name))?;
+        if eq.preset == name.trim() {
+            eq.preset = "custom".into();
+        }
+        Ok(())
+    })
+}
+
+/// List built-in presets followed by user presets.
+#[tauri::command]
+pub fn list_eq_presets(store: State<'_, SettingsStore>) -> Vec<EqPresetInfo> {
+    let builtin = BUILTIN_PRESETS.iter().map(|preset| EqPresetInfo {
+        id: preset.id.into(),
+        name: preset.name.into(),
+        builtin: true,
+    });
+    let custom: Vec<EqPresetInfo> = store.read(|settings| {
+        settings
+            .eq
+            .custom_presets
+            .keys()
+            .map(|name| EqPresetInfo {
+                id: name.clone(),
+                name: name.clone(),
+                builtin: false,
+            })
+            .collect()
+    });
+    builtin.chain(custom).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_interpolates_on_log_axis_and_clamps_ends() {
+        let curve = [(100.0, 0.0), (400.0, 6.0)];
+        assert_eq!(curve_gain_at(&curve, 50.0), 0.0);
+        assert!((curve_gain_at(&curve, 200.0) - 3.0).abs() < 1e-4);
+        assert_eq!(curve_gain_at(&curve, 1000.0), 6.0);
+    }
+
+    #[test]
+    fn resample_keeps_shape_between_band_counts() {
+        let mut bands = default_bands(10);
+        for band in &mut bands {
+            band.gain_db = 4.0;
+        }
+        bands[0].enabled = false;
+        let resampled = resample_bands(&bands, 31);
+        assert_eq!(resampled.len(), 31);
+        assert_eq!(resampled[30].filter_type, EqFilterType::HighShelf);
+        assert!(resampled.iter().all(|b| (b.gain_db - 4.0).abs() < 1e-4));
+        assert!(!resampled[0].enabled && resampled[10].enabled);
+    }
+
+    #[test]
+    fn sanitize_repairs_unsupported_band_counts_and_ranges() {
+        let mut eq = EqSettings {
+            bands: vec![EqBand::default_at(1000.0, 1, 7); 7],
+            preamp_db: f32::NAN,
+            ..EqSettings::default()
+        };
+        eq.sanitize();
+        assert_eq!(eq.bands.len(), DEFAULT_BAND_COUNT);
+        assert_eq!(eq.preamp_db, 0.0);
+
+        let mut eq = EqSettings::default();
+        for band in &mut eq.bands {
+            band.gain_db = 40.0;
+            band.q = f32::NAN;
+            band.frequency = 50000.0;
+        }
+        eq.sanitize();
+        assert!(eq.bands.iter().all(|b| b.gain_db == EQ_GAIN_MAX_DB));
+        assert!(eq.bands.iter().all(|b| b.frequency == EQ_FREQUENCY_MAX_HZ));
+        assert_eq!(eq.bands[0].q, 1.414);
+    }
+
+    #[test]
+    fn gain_only_bands_from_older_files_still_load() {
+        let gains: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let eq: EqSettings = serde_json::from_value(serde_json::json!({
+            "bands": gains,
+            "preset": "bass-boost",
+            "customPresets": { "Mine": { "preampDb": 1.0, "bands": vec![2.0; 15] } }
+        }))
+        .unwrap();
+        assert_eq!(eq.bands.len(), 10);
+        assert_eq!(eq.bands[3].gain_db, 3.0);
+        assert_eq!(eq.bands[3].frequency, 250.0);
+        assert_eq!(eq.bands[0].filter_type, EqFilterType::LowShelf);
+        assert_eq!(eq.custom_presets["Mine"].bands.len(), 15);
+
+        let mut eq = eq;
+        eq.sanitize();
+        assert_eq!(eq.preset, "custom");
+    }
+
+    #[test]
+    fn preset_names_cannot_shadow_builtins() {
+        assert!(validate_preset_name("Rock").is_err());
+        assert!(validate_preset_name("bass").is_err());
+        assert!(validate_preset_name("  ").is_err());
+        assert_eq!(validate_preset_name(" Late Night ").unwrap(), "Late Night");
+    }
+
+    #[test]
+    fn band_validation_checks_gain_frequency_and_q() {
+        let band = EqBand::default_at(1000.0, 1, 10);
+        assert!(band.clone().validate().is_ok());
+        assert!(EqBand {
+            q: 0.0,
+            ..band.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(EqBand {
+            frequency: 5.0,
+            ..band.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(EqBand {
+            gain_db: 13.0,
+            ..band
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn gain_validation_rejects_out_of_range_values() {
+        assert!(validate_gain(12.0).is_ok());
+        assert!(validate_gain(-12.5).is_err());
+        assert!(validate_gain(f32::INFINITY).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Retry / circuit-breaker policy for backend HTTP requests, see
+//! [`crate::http_util`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpPolicy {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each further retry.
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Consecutive failures that open a host's circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit rejects requests before letting a probe through.
+    pub open_cooldown_ms: u64,
+    pub max_concurrent_per_host: usize,
+    /// Per-attempt timeout.
+    pub timeout_ms: u64,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            failure_threshold: 5,
+            open_cooldown_ms: 30_000,
+            max_concurrent_per_host: 4,
+            timeout_ms: 15_000,
+        }
+    }
+}
+
+impl HttpPolicy {
+    pub(super) fn sanitize(&mut self) {
+        self.max_retries = self.max_retries.min(10);
+        self.base_delay_ms = self.base_delay_ms.clamp(50, 60_000);
+        self.max_delay_ms = self.max_delay_ms.clamp(self.base_delay_ms, 300_000);
+        self.failure_threshold = self.failure_threshold.clamp(1, 100);
+        self.open_cooldown_ms = self.open_cooldown_ms.clamp(1_000, 3_600_000);
+        self.max_concurrent_per_host = self.max_concurrent_per_host.clamp(1, 32);
+        self.timeout_ms = self.timeout_ms.clamp(1_000, 300_000);
+    }
+}
+
+#[tauri::command]
+pub fn get_http_policy(store: State<'_, SettingsStore>) -> HttpPolicy {
+    store.read(|s| s.http.clone())
+}
+
+#[tauri::command]
+pub fn set_http_policy(
+    store: State<'_, SettingsStore>,
+    policy: HttpPolicy,
+) -> Result<HttpPolicy, String> {
+    store.update(|s| {
+        let mut policy = policy;
+        policy.sanitize();
+        s.http = policy.clone();
+        Ok(policy)
+    })
+}
@@ -0,0 +1,261 @@
+//! Backend-owned persistent settings.
+//!
+//! Settings live in `settings.json` under the app config dir and are shared by
+//! every window through the managed [`SettingsStore`]. Each section is a plain
+//! serde struct with `#[serde(default)]`, so files written by older builds keep
+//! loading after new sections are added.
+
+pub mod api_endpoints;
+pub mod cover_cache;
+pub mod downloads;
+pub mod eq;
+pub mod http;
+pub mod notifications;
+pub mod osd;
+pub mod paths;
+pub mod power;
+pub mod remote;
+pub mod run;
+pub mod tray;
+pub mod window;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::warn;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::covers::{CustomCoverBackup, CustomCoverState};
+use crate::debounce::Debounced;
+
+pub(crate) const SETTINGS_FILE: &str = "settings.json";
+const BACKUP_VERSION: u32 = 1;
+
+static DEFERRED_SAVE: Debounced = Debounced::new("settings-save", Duration::from_millis(500));
+
+/// All persisted backend settings, one field per section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub api_endpoints: api_endpoints::ApiEndpoints,
+    pub cover_cache: cover_cache::CoverCacheSettings,
+    pub downloads: downloads::DownloadSettings,
+    pub eq: eq::EqSettings,
+    pub http: http::HttpPolicy,
+    pub notifications: notifications::NotificationSettings,
+    pub osd: osd::OsdSettings,
+    pub paths: paths::PathSettings,
+    pub power: power::PowerSettings,
+    pub remote: remote::RemoteSettings,
+    pub run: run::RunSettings,
+    pub tray: tray::TrayConfig,
+    pub window: window::WindowSettings,
+}
+
+/// Managed settings state. Reads are cheap; every update is written through
+/// to disk before the caller is told it succeeded.
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    data: RwLock<Settings>,
+}
+
+impl SettingsStore {
+    /// Load settings from the app config dir, falling back to defaults when
+    /// the file is missing or unreadable.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_config_dir() {
+            Ok(dir) => Some(dir.join(SETTINGS_FILE)),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve app config dir, settings will not persist: {}",
+                    e
+                );
+                None
+            }
+        };
+
+        let mut data = path
+            .as_deref()
+            .and_then(read_settings_file)
+            .unwrap_or_default();
+        data.sanitize();
+
+        Self {
+            path,
+            data: RwLock::new(data),
+        }
+    }
+
+    /// Read a value out of the current settings.
+    pub fn read<T>(&self, f: impl FnOnce(&Settings) -> T) -> T {
+        f(&self.data.read())
+    }
+
+    /// Clone the full settings tree.
+    pub fn snapshot(&self) -> Settings {
+        self.data.read().clone()
+    }
+
+    /// Mutate settings and persist the result.
+    ///
+    /// The closure may reject the change by returning `Err`, in which case
+    /// nothing is written and the in-memory state is left untouched.
+    pub fn update<T>(
+        &self,
+        f: impl FnOnce(&mut Settings) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut guard = self.data.write();
+        let mut next = guard.clone();
+        let value = f(&mut next)?;
+        self.persist(&next)?;
+        *guard = next;
+        Ok(value)
+    }
+
+    /// Mutate settings in memory now and save them once the changes have
+    /// been quiet for a moment. For edits that arrive in bursts, such as an
+    /// EQ slider being dragged; a failed save is only logged.
+    pub fn update_deferred<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut Settings) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let value = {
+            let mut guard = self.data.write();
+            let mut next = guard.clone();
+            let value = f(&mut next)?;
+            *guard = next;
+            value
+        };
+        let app = app.clone();
+        DEFERRED_SAVE.trigger(move || {
+            if let Some(store) = app.try_state::<SettingsStore>() {
+                store.save();
+            }
+        });
+        Ok(value)
+    }
+
+    /// Write a pending deferred save now; the app is about to exit.
+    pub fn flush(&self) {
+        if DEFERRED_SAVE.take_pending() {
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        // Upgradable reads exclude each other and writers, so a deferred
+        // save never races `update` or a flush over the temp file.
+        let guard = self.data.upgradable_read();
+        if let Err(e) = self.persist(&guard) {
+            warn!("{}", e);
+        }
+    }
+
+    fn persist(&self, settings: &Settings) -> Result<(), String> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(settings).map_err(|e| e.to_string())?;
+        write_atomic(path, &json).map_err(|e| format!("Failed to save settings: {}", e))
+    }
+}
+
+impl Settings {
+    /// Repair values that may have been hand-edited or written by another version.
+    fn sanitize(&mut self) {
+        self.api_endpoints.sanitize();
+        self.cover_cache.sanitize();
+        self.downloads.sanitize();
+        self.eq.sanitize();
+        self.http.sanitize();
+        self.osd.sanitize();
+        self.paths.sanitize();
+        self.remote.sanitize();
+        self.window.sanitize();
+    }
+}
+
+fn read_settings_file(path: &Path) -> Option<Settings> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            warn!("Ignoring malformed {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write a file by writing a sibling temp file and renaming it over the target,
+/// so a crash mid-write never leaves a truncated file behind.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsBackup {
+    version: u32,
+    app_version: String,
+    settings: Settings,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    custom_covers: Vec<CustomCoverBackup>,
+}
+
+/// Export the backend settings (EQ state, presets, custom covers, ...) to a
+/// JSON backup file. The destination folder must be an allowed root; the
+/// covers are read and encoded off the main thread.
+#[tauri::command]
+pub async fn export_settings_backup(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    dest_path: String,
+) -> Result<(), String> {
+    let dest = backup_path(&app, &dest_path)?;
+    let settings = store.snapshot();
+    tauri::async_runtime::spawn_blocking(move || {
+        let backup = SettingsBackup {
+            version: BACKUP_VERSION,
+            app_version: app.package_info().version.to_string(),
+            settings,
+            custom_covers: app
+                .try_state::<CustomCoverState>()
+                .map(|covers| covers.backup_entries())
+                .unwrap_or_default(),
+        };
+        let json = serde_json::to_vec_pretty(&backup).map_err(|e| e.to_string())?;
+        write_atomic(&dest, &json).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn backup_path(app: &AppHandle, dest_path: &str) -> Result<PathBuf, String> {
+    #[cfg(not(mobile))]
+    {
+        crate::desktop::file_manager::resolve_output_path(app, Path::new(dest_path))
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Ok(PathBuf::from(dest_path))
+    }
+}
@@ -0,0 +1,26 @@
+//! Desktop notification preferences.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    /// Show a now-playing notification on track change while the main
+    /// window is hidden.
+    pub enabled: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[tauri::command]
+pub fn get_notification_settings(store: State<'_, SettingsStore>) -> NotificationSettings {
+    store.read(|s| s.notifications.clone())
+}
@@ -0,0 +1,79 @@
+//! On-screen display (volume / track change overlay) preferences.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+pub const OSD_MIN_DURATION_MS: u64 = 500;
+pub const OSD_MAX_DURATION_MS: u64 = 10_000;
+
+/// Screen corner (or edge center) the OSD is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum OsdCorner {
+    TopLeft,
+    TopCenter,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OsdSettings {
+    pub enabled: bool,
+    pub corner: OsdCorner,
+    /// Distance from the work-area edge, in logical pixels.
+    pub margin: f64,
+    pub duration_ms: u64,
+    /// Also show the OSD while a fullscreen app (usually a game) is in front.
+    pub show_over_fullscreen: bool,
+}
+
+impl Default for OsdSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            corner: OsdCorner::default(),
+            margin: 24.0,
+            duration_ms: 1500,
+            show_over_fullscreen: false,
+        }
+    }
+}
+
+impl OsdSettings {
+    pub(super) fn sanitize(&mut self) {
+        if !self.margin.is_finite() {
+            self.margin = Self::default().margin;
+        }
+        self.margin = self.margin.clamp(0.0, 200.0);
+        self.duration_ms = clamp_duration(self.duration_ms);
+    }
+}
+
+pub fn clamp_duration(duration_ms: u64) -> u64 {
+    duration_ms.clamp(OSD_MIN_DURATION_MS, OSD_MAX_DURATION_MS)
+}
+
+#[tauri::command]
+pub fn get_osd_settings(store: State<'_, SettingsStore>) -> OsdSettings {
+    store.read(|s| s.osd.clone())
+}
+
+#[tauri::command]
+pub fn set_osd_settings(
+    store: State<'_, SettingsStore>,
+    settings: OsdSettings,
+) -> Result<OsdSettings, String> {
+    store.update(|s| {
+        let mut settings = settings;
+        settings.sanitize();
+        s.osd = settings.clone();
+        Ok(settings)
+    })
+}
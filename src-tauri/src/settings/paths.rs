@@ -0,0 +1,45 @@
+//! User-chosen output folders (downloads, exports).
+
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+/// `None` means "use the platform default".
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PathSettings {
+    pub download_dir: Option<PathBuf>,
+    pub export_dir: Option<PathBuf>,
+}
+
+impl PathSettings {
+    pub(super) fn sanitize(&mut self) {
+        for dir in [&mut self.download_dir, &mut self.export_dir] {
+            if dir.as_deref().is_some_and(|d| !d.is_absolute()) {
+                *dir = None;
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_path_settings(store: State<'_, SettingsStore>) -> PathSettings {
+    store.read(|s| s.paths.clone())
+}
+
+#[tauri::command]
+pub fn set_path_settings(
+    store: State<'_, SettingsStore>,
+    settings: PathSettings,
+) -> Result<PathSettings, String> {
+    store.update(|s| {
+        let mut settings = settings;
+        settings.sanitize();
+        s.paths = settings.clone();
+        Ok(settings)
+    })
+}
@@ -0,0 +1,31 @@
+//! Power-related preferences.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PowerSettings {
+    /// Defer maintenance work (loudness scans, prefetch, cache eviction,
+    /// library rescans) entirely while running on battery.
+    pub low_power_mode: bool,
+}
+
+#[tauri::command]
+pub fn get_power_settings(store: State<'_, SettingsStore>) -> PowerSettings {
+    store.read(|s| s.power.clone())
+}
+
+#[tauri::command]
+pub fn set_power_settings(
+    store: State<'_, SettingsStore>,
+    settings: PowerSettings,
+) -> Result<PowerSettings, String> {
+    store.update(|s| {
+        s.power = settings.clone();
+        Ok(settings)
+    })
+}
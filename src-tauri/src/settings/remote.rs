@@ -0,0 +1,48 @@
+//! LAN remote-control API tokens.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Permission a remote token can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemoteScope {
+    /// Play / pause / seek / skip / volume.
+    Playback,
+    QueueRead,
+    QueueWrite,
+    /// Window control, quitting the app and anything touching file paths.
+    System,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteToken {
+    pub id: String,
+    pub label: String,
+    pub scopes: BTreeSet<RemoteScope>,
+    /// Hex SHA-256 of the token; the token itself is only shown once.
+    pub token_hash: String,
+    pub created_ms: u64,
+    /// Reserved for the remote server; nothing records use yet.
+    pub last_used_ms: Option<u64>,
+    pub last_origin: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteSettings {
+    pub tokens: Vec<RemoteToken>,
+}
+
+/// Upper bound on issued tokens.
+pub const MAX_REMOTE_TOKENS: usize = 32;
+
+impl RemoteSettings {
+    pub(super) fn sanitize(&mut self) {
+        self.tokens
+            .retain(|t| !t.id.is_empty() && !t.token_hash.is_empty());
+        self.tokens.truncate(MAX_REMOTE_TOKENS);
+    }
+}
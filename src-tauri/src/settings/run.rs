@@ -0,0 +1,18 @@
+//! Startup bookkeeping used by [`crate::first_run`].
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunSettings {
+    /// App version of the previous launch; `None` before the first one.
+    pub last_version: Option<String>,
+    /// Ids of migrations that completed successfully.
+    pub completed_migrations: BTreeSet<String>,
+    /// Launches in a row that never reached a ready main window. Counted by
+    /// `first_run`, cleared by `safe_mode` and read by it to detect a
+    /// startup crash loop.
+    pub unfinished_launches: u32,
+}
@@ -0,0 +1,95 @@
+//! What clicking the tray icon does, and which menu it opens.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrayClickAction {
+    /// Show and focus the main window, even if it is already visible.
+    ShowMain,
+    ToggleMain,
+    /// Send `tray-play-pause` to the main window.
+    PlayPause,
+    /// Open the tray popup, like a right-click.
+    ShowPopup,
+    Nothing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayMenuMode {
+    /// Right-click opens the webview tray popup.
+    #[default]
+    Popup,
+    /// The tray icon carries a native menu instead. Linux never reports
+    /// tray clicks, so this is the only menu it can show.
+    Native,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrayConfig {
+    pub left_click_action: TrayClickAction,
+    /// On Windows and macOS a double-click also delivers both single clicks,
+    /// so this usually pairs with a left-click action that does no harm
+    /// twice, such as `show-main`.
+    pub double_click_action: TrayClickAction,
+    /// Switched at runtime with `set_tray_menu_mode`, which rebuilds the
+    /// tray icon.
+    pub menu_mode: TrayMenuMode,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            left_click_action: TrayClickAction::ShowMain,
+            double_click_action: TrayClickAction::Nothing,
+            menu_mode: TrayMenuMode::Popup,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_tray_config(store: State<'_, SettingsStore>) -> TrayConfig {
+    store.read(|s| s.tray)
+}
+
+#[tauri::command]
+pub fn set_tray_left_click_action(
+    store: State<'_, SettingsStore>,
+    action: TrayClickAction,
+) -> Result<TrayConfig, String> {
+    store.update(|s| {
+        s.tray.left_click_action = action;
+        Ok(s.tray)
+    })
+}
+
+#[tauri::command]
+pub fn set_tray_double_click_action(
+    store: State<'_, SettingsStore>,
+    action: TrayClickAction,
+) -> Result<TrayConfig, String> {
+    store.update(|s| {
+        s.tray.double_click_action = action;
+        Ok(s.tray)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_keep_the_old_left_click_behavior() {
+        let config: TrayConfig =
+            serde_json::from_str(r#"{ "doubleClickAction": "toggle-main" }"#).unwrap();
+        assert_eq!(config.left_click_action, TrayClickAction::ShowMain);
+        assert_eq!(config.double_click_action, TrayClickAction::ToggleMain);
+        assert_eq!(config.menu_mode, TrayMenuMode::Popup);
+    }
+}
@@ -0,0 +1,250 @@
+//! Window behavior preferences owned by the backend.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::SettingsStore;
+
+/// What a window's close button does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseBehavior {
+    /// Ask the user: the frontend's dialog for main (`main-close-requested`),
+    /// a native one for other windows.
+    #[default]
+    Ask,
+    /// Hide to tray. `tray` is the frontend's historical name for it.
+    #[serde(alias = "tray")]
+    Hide,
+    /// Save state and quit. For windows other than main, just close it.
+    Exit,
+}
+
+pub const DEFAULT_MIN_VISIBLE_FRACTION: f64 = 0.3;
+pub const DEFAULT_QUIT_GRACE_MS: u64 = 1500;
+pub const MAX_QUIT_GRACE_MS: u64 = 10_000;
+pub const MAX_LYRICS_SNAP_THRESHOLD: u32 = 200;
+
+/// Edge snapping of the desktop-lyrics window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LyricsSnap {
+    pub enabled: bool,
+    /// Distance to a guide at which the window snaps, in logical pixels.
+    pub threshold_px: u32,
+}
+
+impl Default for LyricsSnap {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_px: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowSettings {
+    /// Close behavior of the main window.
+    pub close_behavior: CloseBehavior,
+    /// Close behavior of other windows by label. Unset labels hide if their
+    /// preset is `closeable_to_tray` and close otherwise.
+    pub close_behaviors: BTreeMap<String, CloseBehavior>,
+    /// Last always-on-top choice per window label (main and mini-player).
+    pub pinned: BTreeMap<String, bool>,
+    /// Create the settings window and mini player hidden after startup so
+    /// they open instantly.
+    pub prewarm_windows: bool,
+    /// Reopen the windows that were open at the last exit.
+    pub restore_session: bool,
+    pub lyrics_snap: LyricsSnap,
+    /// Hide desktop lyrics while a fullscreen app is in front of them.
+    pub lyrics_autohide_fullscreen: bool,
+    /// Webview zoom per window label; absent means 100%.
+    pub zoom: BTreeMap<String, f64>,
+    /// A restored window with less than this share of its area on any
+    /// monitor is moved onto the primary one.
+    pub min_visible_fraction: f64,
+    /// How long quitting waits for windows to ack `app-will-quit`.
+    pub quit_grace_ms: u64,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            close_behavior: CloseBehavior::default(),
+            close_behaviors: BTreeMap::new(),
+            pinned: BTreeMap::new(),
+            prewarm_windows: true,
+            restore_session: true,
+            lyrics_snap: LyricsSnap::default(),
+            lyrics_autohide_fullscreen: false,
+            zoom: BTreeMap::new(),
+            min_visible_fraction: DEFAULT_MIN_VISIBLE_FRACTION,
+            quit_grace_ms: DEFAULT_QUIT_GRACE_MS,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_session_restore_enabled(store: State<'_, SettingsStore>) -> bool {
+    store.read(|s| s.window.restore_session)
+}
+
+/// Takes effect from the next launch.
+#[tauri::command]
+pub fn set_session_restore_enabled(
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    store.update(|s| {
+        s.window.restore_session = enabled;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_lyrics_snap(store: State<'_, SettingsStore>) -> LyricsSnap {
+    store.read(|s| s.window.lyrics_snap)
+}
+
+/// Returns the stored value; the threshold is clamped to 1..=200.
+#[tauri::command]
+pub fn set_lyrics_snap(
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+    threshold_px: u32,
+) -> Result<LyricsSnap, String> {
+    let snap = LyricsSnap {
+        enabled,
+        threshold_px: threshold_px.clamp(1, MAX_LYRICS_SNAP_THRESHOLD),
+    };
+    store.update(|s| {
+        s.window.lyrics_snap = snap;
+        Ok(snap)
+    })
+}
+
+#[tauri::command]
+pub fn get_quit_grace_ms(store: State<'_, SettingsStore>) -> u64 {
+    store.read(|s| s.window.quit_grace_ms)
+}
+
+/// Returns the stored value, capped at 10 s.
+#[tauri::command]
+pub fn set_quit_grace_ms(store: State<'_, SettingsStore>, grace_ms: u64) -> Result<u64, String> {
+    let grace_ms = grace_ms.min(MAX_QUIT_GRACE_MS);
+    store.update(|s| {
+        s.window.quit_grace_ms = grace_ms;
+        Ok(grace_ms)
+    })
+}
+
+impl WindowSettings {
+    pub(super) fn sanitize(&mut self) {
+        if !self.min_visible_fraction.is_finite() {
+            self.min_visible_fraction = DEFAULT_MIN_VISIBLE_FRACTION;
+        }
+        self.min_visible_fraction = self.min_visible_fraction.clamp(0.0, 1.0);
+        self.lyrics_snap.threshold_px = self
+            .lyrics_snap
+            .threshold_px
+            .clamp(1, MAX_LYRICS_SNAP_THRESHOLD);
+        self.quit_grace_ms = self.quit_grace_ms.min(MAX_QUIT_GRACE_MS);
+    }
+
+    /// What closing `label` does. `hides_by_default` is the preset's
+    /// `closeable_to_tray`, used when nothing is saved for the label.
+    pub fn close_behavior_for(&self, label: &str, hides_by_default: bool) -> CloseBehavior {
+        if label == MAIN_LABEL {
+            return self.close_behavior;
+        }
+        match self.close_behaviors.get(label) {
+            Some(behavior) => *behavior,
+            None if hides_by_default => CloseBehavior::Hide,
+            None => CloseBehavior::Exit,
+        }
+    }
+}
+
+const MAIN_LABEL: &str = "main";
+
+/// Set the close behavior of a window; `main` when no label is given.
+#[tauri::command]
+pub fn set_close_behavior(
+    store: State<'_, SettingsStore>,
+    label: Option<String>,
+    behavior: CloseBehavior,
+) -> Result<(), String> {
+    store.update(|s| {
+        match label.as_deref() {
+            None | Some(MAIN_LABEL) => s.window.close_behavior = behavior,
+            Some(label) => {
+                s.window.close_behaviors.insert(label.to_string(), behavior);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_prewarm_windows(store: State<'_, SettingsStore>) -> bool {
+    store.read(|s| s.window.prewarm_windows)
+}
+
+/// Takes effect from the next launch, or cancels a pre-warm still pending.
+#[tauri::command]
+pub fn set_prewarm_windows(store: State<'_, SettingsStore>, enabled: bool) -> Result<(), String> {
+    store.update(|s| {
+        s.window.prewarm_windows = enabled;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_behavior_is_looked_up_per_label() {
+        let mut settings = WindowSettings {
+            close_behavior: CloseBehavior::Exit,
+            ..WindowSettings::default()
+        };
+        settings
+            .close_behaviors
+            .insert("settings".into(), CloseBehavior::Hide);
+        assert_eq!(
+            settings.close_behavior_for("main", false),
+            CloseBehavior::Exit
+        );
+        assert_eq!(
+            settings.close_behavior_for("settings", false),
+            CloseBehavior::Hide
+        );
+        assert_eq!(
+            settings.close_behavior_for("about", false),
+            CloseBehavior::Exit
+        );
+        assert_eq!(
+            settings.close_behavior_for("about", true),
+            CloseBehavior::Hide
+        );
+    }
+
+    #[test]
+    fn close_behavior_accepts_frontend_tray_alias() {
+        let parse = |s: &str| serde_json::from_str::<CloseBehavior>(s).unwrap();
+        assert_eq!(parse("\"tray\""), CloseBehavior::Hide);
+        assert_eq!(parse("\"hide\""), CloseBehavior::Hide);
+        assert_eq!(parse("\"exit\""), CloseBehavior::Exit);
+        assert_eq!(
+            serde_json::to_string(&CloseBehavior::Hide).unwrap(),
+            "\"hide\""
+        );
+    }
+}
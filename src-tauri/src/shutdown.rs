@@ -0,0 +1,154 @@
+//! Orderly quit.
+//!
+//! Quitting used to save window geometry and exit on the spot, cutting off
+//! writes still in flight. [`quit`] now tells every window with
+//! `app-will-quit`, gives them the grace period from the window settings
+//! (1.5 s by default) to flush their own state and answer with
+//! `quit_ack`, runs the native [`ShutdownHooks`], and only then saves
+//! window state and exits. A forced quit skips the wait for the windows
+//! but still runs the hooks.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use parking_lot::{Condvar, Mutex};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::desktop::window::manager;
+use crate::settings::window::DEFAULT_QUIT_GRACE_MS;
+use crate::settings::SettingsStore;
+
+pub const APP_WILL_QUIT_EVENT: &str = "app-will-quit";
+
+/// Payload of `app-will-quit`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppWillQuit {
+    /// How long the app waits for `quit_ack` before exiting anyway.
+    pub grace_ms: u64,
+}
+
+type Hook = Box<dyn FnOnce(&AppHandle) + Send>;
+
+static HOOKS: Mutex<Vec<(&'static str, Hook)>> = Mutex::new(Vec::new());
+/// Windows that have not acked `app-will-quit` yet.
+static AWAITING_ACK: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+static ACKED: Condvar = Condvar::new();
+static QUITTING: AtomicBool = AtomicBool::new(false);
+
+/// Native cleanup that has to finish before the process exits.
+pub struct ShutdownHooks;
+
+impl ShutdownHooks {
+    /// Run `hook` on quit. Hooks run in the order they were registered, on
+    /// a background thread, after the windows have had their say.
+    pub fn register(name: &'static str, hook: impl FnOnce(&AppHandle) + Send + 'static) {
+        HOOKS.lock().push((name, Box::new(hook)));
+    }
+
+    fn run_all(app: &AppHandle) {
+        let hooks = std::mem::take(&mut *HOOKS.lock());
+        for (name, hook) in hooks {
+            let started = Instant::now();
+            hook(app);
+            info!(
+                "Shutdown hook '{}' done in {} ms",
+                name,
+                started.elapsed().as_millis()
+            );
+        }
+    }
+}
+
+/// Start accepting acks from `labels`. Must happen before
+/// `app-will-quit` goes out, or a quick window's ack would be dropped.
+fn expect_acks(labels: HashSet<String>) {
+    *AWAITING_ACK.lock() = Some(labels);
+}
+
+/// Block until every expected label has acked or `grace` has passed.
+/// Returns the labels that never answered.
+fn wait_for_acks(grace: Duration) -> HashSet<String> {
+    let deadline = Instant::now() + grace;
+    let mut waiting = AWAITING_ACK.lock();
+    while waiting.as_ref().is_some_and(|labels| !labels.is_empty()) {
+        if ACKED.wait_until(&mut waiting, deadline).timed_out() {
+            break;
+        }
+    }
+    waiting.take().unwrap_or_default()
+}
+
+fn ack(label: &str) {
+    if let Some(labels) = AWAITING_ACK.lock().as_mut() {
+        if labels.remove(label) {
+            ACKED.notify_all();
+        }
+    }
+}
+
+/// Quit the app: ask the windows to wrap up (unless `force`), run the
+/// shutdown hooks, save window state and exit. Later calls while a quit is
+/// under way do nothing.
+pub fn quit(app: &AppHandle, force: bool) {
+    if QUITTING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("Quitting{}", if force { " (forced)" } else { "" });
+    let handle = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("shutdown".into())
+        .spawn(move || {
+            if !force {
+                let grace_ms = handle
+                    .try_state::<SettingsStore>()
+                    .map_or(DEFAULT_QUIT_GRACE_MS, |store| {
+                        store.read(|s| s.window.quit_grace_ms)
+                    });
+                expect_acks(handle.webview_windows().into_keys().collect());
+                let _ = handle.emit(APP_WILL_QUIT_EVENT, AppWillQuit { grace_ms });
+                let silent = wait_for_acks(Duration::from_millis(grace_ms));
+                if !silent.is_empty() {
+                    warn!("No quit ack from {:?}, exiting anyway", silent);
+                }
+            }
+            ShutdownHooks::run_all(&handle);
+            manager::exit_app(&handle);
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to spawn shutdown thread, exiting now: {}", e);
+        manager::exit_app(app);
+    }
+}
+
+/// A window has finished its own cleanup after `app-will-quit`.
+#[tauri::command]
+pub fn quit_ack(label: String) {
+    ack(&label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acks_end_the_wait_early_and_silent_windows_are_reported() {
+        expect_acks(["main", "mini-player"].map(String::from).into());
+        // Answers before the wait has begun still count.
+        ack("main");
+        let acker = std::thread::spawn(|| ack("mini-player"));
+        let started = Instant::now();
+        assert!(wait_for_acks(Duration::from_secs(10)).is_empty());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        acker.join().unwrap();
+
+        expect_acks(["desktop-lyrics"].map(String::from).into());
+        let silent = wait_for_acks(Duration::from_millis(20));
+        assert!(silent.contains("desktop-lyrics"));
+        assert!(AWAITING_ACK.lock().is_none());
+    }
+}
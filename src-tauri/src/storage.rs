@@ -0,0 +1,382 @@
+//! Integrity checks and maintenance for the SQLite databases.
+//!
+//! At startup every database gets `PRAGMA quick_check` on a blocking task.
+//! A failing file is recovered by copying whatever SQLite can still read
+//! into a fresh file; if even that fails, the corrupt file is moved aside
+//! (kept for bug reports) and the store starts empty. Either way the result
+//! shows up in [`get_storage_health`] and as a `storage-repaired` event,
+//! instead of every later command failing with an opaque error.
+//!
+//! The SQLite databases are the play queue (`queue_store`) and the API
+//! cache (`cache_store`); the history log and cover index are plain files
+//! and are not covered here.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cache_store::CacheStore;
+use crate::queue_store::QueueStore;
+
+pub const STORAGE_REPAIRED_EVENT: &str = "storage-repaired";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DatabaseStatus {
+    /// Not checked yet.
+    Pending,
+    Ok,
+    /// Corrupt; readable data was copied into a fresh file.
+    Repaired,
+    /// Corrupt and unreadable; moved aside and started empty.
+    Reset,
+    /// Check or repair itself failed; the store may keep returning errors.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseHealth {
+    pub name: &'static str,
+    pub status: DatabaseStatus,
+    pub detail: Option<String>,
+    pub checked_at_ms: Option<u64>,
+    pub maintained_at_ms: Option<u64>,
+}
+
+/// A SQLite database owned by some managed store.
+struct Database {
+    name: &'static str,
+    /// The file, once the owning store is managed.
+    path: fn(&AppHandle) -> Option<PathBuf>,
+    /// Run the closure with the store's connection closed.
+    release: fn(&AppHandle, &mut dyn FnMut()) -> Result<(), String>,
+}
+
+const DATABASES: [Database; 2] = [
+    Database {
+        name: "queue",
+        path: |app| {
+            app.try_state::<QueueStore>()
+                .and_then(|store| store.path().map(Path::to_path_buf))
+        },
+        release: |app, f| match app.try_state::<QueueStore>() {
+            Some(store) => store.with_file_released(f),
+            None => {
+                f();
+                Ok(())
+            }
+        },
+    },
+    Database {
+        name: "cache",
+        path: |app| {
+            app.try_state::<CacheStore>()
+                .and_then(|store| store.path().map(Path::to_path_buf))
+        },
+        release: |app, f| match app.try_state::<CacheStore>() {
+            Some(store) => store.with_file_released(f),
+            None => {
+                f();
+                Ok(())
+            }
+        },
+    },
+];
+
+static HEALTH: LazyLock<Mutex<Vec<DatabaseHealth>>> = LazyLock::new(|| {
+    Mutex::new(
+        DATABASES
+            .iter()
+            .map(|db| DatabaseHealth {
+                name: db.name,
+                status: DatabaseStatus::Pending,
+                detail: None,
+                checked_at_ms: None,
+                maintained_at_ms: None,
+            })
+            .collect(),
+    )
+});
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn update_health(name: &str, f: impl FnOnce(&mut DatabaseHealth)) -> Option<DatabaseHealth> {
+    let mut health = HEALTH.lock();
+    let entry = health.iter_mut().find(|h| h.name == name)?;
+    f(entry);
+    Some(entry.clone())
+}
+
+/// `Ok` when `PRAGMA quick_check` reports nothing; the problems otherwise.
+fn quick_check(path: &Path) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("PRAGMA quick_check")
+        .map_err(|e| e.to_string())?;
+    let problems: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    match problems.as_slice() {
+        [ok] if ok == "ok" => Ok(()),
+        _ => Err(problems.join("; ")),
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Recreate the schema of `source` in a new file at `target` and copy every
+/// table's rows across.
+fn dump_and_reload(source: &Path, target: &Path) -> Result<(), String> {
+    let fresh = Connection::open(target).map_err(|e| e.to_string())?;
+    fresh
+        .execute(
+            "ATTACH DATABASE ?1 AS old",
+            [source.to_string_lossy().as_ref()],
+        )
+        .map_err(|e| e.to_string())?;
+    // Tables first so indexes and triggers have something to attach to.
+    let objects: Vec<(String, String, String)> = {
+        let mut stmt = fresh
+            .prepare(
+                "SELECT type, name, sql FROM old.sqlite_master
+                 WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+                 ORDER BY type = 'table' DESC",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+    for (kind, name, sql) in &objects {
+        fresh.execute_batch(sql).map_err(|e| e.to_string())?;
+        if kind == "table" {
+            fresh
+                .execute_batch(&format!(
+                    "INSERT INTO main.{0} SELECT * FROM old.{0}",
+                    quote(name)
+                ))
+                .map_err(|e| format!("{}: {}", name, e))?;
+        }
+    }
+    fresh
+        .execute_batch("DETACH DATABASE old")
+        .map_err(|e| e.to_string())?;
+    drop(fresh);
+    quick_check(target)
+}
+
+/// Replace the corrupt file at `path`, which must not be open. The corrupt
+/// file (and its WAL) is kept next to it with a `.corrupt-<ms>` suffix.
+fn recover(path: &Path) -> (DatabaseStatus, String) {
+    let fresh = with_suffix(path, ".recovering");
+    let _ = std::fs::remove_file(&fresh);
+    let dumped = dump_and_reload(path, &fresh);
+
+    let aside = with_suffix(path, &format!(".corrupt-{}", now_ms()));
+    if let Err(e) = std::fs::rename(path, &aside) {
+        let _ = std::fs::remove_file(&fresh);
+        return (
+            DatabaseStatus::Failed,
+            format!("could not move the corrupt file aside: {}", e),
+        );
+    }
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::rename(with_suffix(path, suffix), with_suffix(&aside, suffix));
+    }
+
+    match dumped {
+        Ok(()) => match std::fs::rename(&fresh, path) {
+            Ok(()) => (
+                DatabaseStatus::Repaired,
+                format!(
+                    "recovered into a fresh file; original kept as {}",
+                    aside.display()
+                ),
+            ),
+            Err(e) => (
+                DatabaseStatus::Reset,
+                format!(
+                    "recovered copy could not be moved in ({}); started empty",
+                    e
+                ),
+            ),
+        },
+        Err(e) => {
+            let _ = std::fs::remove_file(&fresh);
+            (
+                DatabaseStatus::Reset,
+                format!(
+                    "unreadable ({}); started empty, original kept as {}",
+                    e,
+                    aside.display()
+                ),
+            )
+        }
+    }
+}
+
+fn check_database(app: &AppHandle, db: &Database) {
+    let Some(path) = (db.path)(app) else {
+        update_health(db.name, |h| {
+            h.status = DatabaseStatus::Ok;
+            h.detail = Some("in memory".into());
+            h.checked_at_ms = Some(now_ms());
+        });
+        return;
+    };
+    let problems = match quick_check(&path) {
+        Ok(()) => {
+            update_health(db.name, |h| {
+                h.status = DatabaseStatus::Ok;
+                h.detail = None;
+                h.checked_at_ms = Some(now_ms());
+            });
+            return;
+        }
+        Err(problems) => problems,
+    };
+
+    warn!(
+        "Database '{}' failed its integrity check: {}",
+        db.name, problems
+    );
+    let mut outcome = (DatabaseStatus::Failed, String::new());
+    let released = (db.release)(app, &mut || outcome = recover(&path));
+    if let Err(e) = released {
+        outcome = (DatabaseStatus::Failed, e);
+    }
+    let (status, detail) = outcome;
+    info!("Database '{}': {:?}, {}", db.name, status, detail);
+    let health = update_health(db.name, |h| {
+        h.status = status;
+        h.detail = Some(detail);
+        h.checked_at_ms = Some(now_ms());
+    });
+    if let Some(health) = health {
+        let _ = app.emit(STORAGE_REPAIRED_EVENT, health);
+    }
+}
+
+/// Check every database on a blocking task. Call once the stores are managed.
+pub fn start_integrity_check(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        for db in &DATABASES {
+            check_database(&app, db);
+        }
+    });
+}
+
+/// Checkpoint the WAL, rebuild indexes, compact and refresh statistics.
+/// Expired cache rows are pruned by the cache store itself, not here.
+fn maintain(path: &Path) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.execute_batch(
+        "PRAGMA wal_checkpoint(TRUNCATE);
+         REINDEX;
+         VACUUM;
+         PRAGMA optimize;",
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn run_maintenance(app: &AppHandle) {
+    for db in &DATABASES {
+        let Some(path) = (db.path)(app) else {
+            continue;
+        };
+        match maintain(&path) {
+            Ok(()) => {
+                info!("Database '{}' maintained", db.name);
+                update_health(db.name, |h| h.maintained_at_ms = Some(now_ms()));
+            }
+            Err(e) => warn!("Maintenance of database '{}' failed: {}", db.name, e),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_storage_health() -> Vec<DatabaseHealth> {
+    HEALTH.lock().clone()
+}
+
+/// Vacuum and reindex every database. Queued as maintenance work, so it
+/// waits for the user to be idle (or for `run_maintenance_now`).
+#[tauri::command]
+pub fn run_storage_maintenance(app: AppHandle) {
+    #[cfg(not(mobile))]
+    if let Some(scheduler) = app.try_state::<crate::tasks::Scheduler>() {
+        let handle = app.clone();
+        scheduler.submit(
+            "storage-maintenance",
+            crate::tasks::TaskClass::Maintenance,
+            move |_| run_maintenance(&handle),
+        );
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || run_maintenance(&app));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healthy_database_is_copied_with_its_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.sqlite3");
+        let conn = Connection::open(&source).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, v TEXT);
+             CREATE INDEX t_v ON t (v);
+             INSERT INTO t (v) VALUES ('a'), ('b');",
+        )
+        .unwrap();
+        drop(conn);
+        assert!(quick_check(&source).is_ok());
+
+        let target = dir.path().join("target.sqlite3");
+        dump_and_reload(&source, &target).unwrap();
+        let copied: i64 = Connection::open(&target)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(copied, 2);
+    }
+
+    #[test]
+    fn unreadable_file_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("garbage.sqlite3");
+        std::fs::write(&path, vec![0x5a; 4096]).unwrap();
+        assert!(quick_check(&path).is_err());
+
+        let (status, _) = recover(&path);
+        assert_eq!(status, DatabaseStatus::Reset);
+        assert!(!path.exists());
+        assert!(!with_suffix(&path, ".recovering").exists());
+    }
+}
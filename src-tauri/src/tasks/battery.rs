@@ -0,0 +1,112 @@
+//! Whether the machine is currently running on battery.
+//!
+//! `None` means unknown (desktops without a battery, unreadable status);
+//! callers treat that like mains power.
+
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> Option<bool> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SAFETY: `status` is a live out-parameter.
+    unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// First line of `pmset -g batt`: "Now drawing from 'Battery Power'".
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let first = output.lines().next()?;
+    if first.contains("'Battery Power'") {
+        Some(true)
+    } else if first.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut supplies = Vec::new();
+    for entry in entries.flatten() {
+        let read = |name: &str| {
+            std::fs::read_to_string(entry.path().join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        supplies.push((read("type"), read("online"), read("status")));
+    }
+    from_power_supplies(&supplies)
+}
+
+/// `(type, online, status)` of every `/sys/class/power_supply` entry.
+#[cfg(any(target_os = "linux", test))]
+fn from_power_supplies(supplies: &[(String, String, String)]) -> Option<bool> {
+    if supplies
+        .iter()
+        .any(|(kind, online, _)| kind == "Mains" && online == "1")
+    {
+        return Some(false);
+    }
+    let batteries: Vec<_> = supplies
+        .iter()
+        .filter(|(kind, _, _)| kind == "Battery")
+        .collect();
+    if batteries.is_empty() {
+        return None;
+    }
+    Some(
+        batteries
+            .iter()
+            .any(|(_, _, status)| status == "Discharging"),
+    )
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn on_battery() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supply(kind: &str, online: &str, status: &str) -> (String, String, String) {
+        (kind.into(), online.into(), status.into())
+    }
+
+    #[test]
+    fn reads_linux_power_supplies() {
+        let laptop_unplugged = [
+            supply("Mains", "0", ""),
+            supply("Battery", "", "Discharging"),
+        ];
+        assert_eq!(from_power_supplies(&laptop_unplugged), Some(true));
+        let laptop_plugged = [supply("Mains", "1", ""), supply("Battery", "", "Charging")];
+        assert_eq!(from_power_supplies(&laptop_plugged), Some(false));
+        assert_eq!(from_power_supplies(&[supply("USB", "0", "")]), None);
+    }
+
+    #[test]
+    fn reads_pmset_output() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0\t80%; discharging";
+        assert_eq!(parse_pmset(battery), Some(true));
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), Some(false));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
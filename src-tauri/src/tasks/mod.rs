@@ -0,0 +1,9 @@
+//! Background work that must not compete with the user for disk and CPU.
+//!
+//! See [`scheduler`] for how maintenance tasks are held back while the user
+//! is active or the machine is on battery.
+
+mod battery;
+pub mod scheduler;
+
+pub use scheduler::{Scheduler, TaskClass, TaskContext};
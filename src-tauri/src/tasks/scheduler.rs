@@ -0,0 +1,468 @@
+//! Idle-aware scheduler for heavy background work.
+//!
+//! Interactive tasks start right away on their own thread. Maintenance tasks
+//! (loudness scans, cover prefetch, cache eviction, bulk library
+//! reconciliation) are queued and run one at a time on a single worker, and
+//! only while the gate is open:
+//!
+//! - the user has been idle for [`IDLE_THRESHOLD`], or the main window has
+//!   been hidden to the tray for [`HIDDEN_GRACE`];
+//! - and low power mode is off or the machine is on mains power.
+//!
+//! A running task calls [`TaskContext::checkpoint`] between units of work;
+//! the call blocks while the gate is closed, so work pauses as soon as the
+//! user comes back. `run_maintenance_now` opens the gate regardless until
+//! the queue drains.
+//!
+//! Tasks of either class report how far they are with
+//! [`TaskContext::report_progress`], which is emitted as `task-progress`
+//! and listed in the scheduler state until the task ends.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use parking_lot::{Condvar, Mutex};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+
+use super::battery;
+use crate::settings::SettingsStore;
+
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(60);
+pub const HIDDEN_GRACE: Duration = Duration::from_secs(30);
+/// How long `run_maintenance_now` keeps the gate open at most.
+const FORCE_WINDOW: Duration = Duration::from_secs(15 * 60);
+const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Closed gates are re-evaluated at least this often (idle time grows
+/// without any event to wake on).
+const GATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskClass {
+    /// Started immediately, never paused.
+    Interactive,
+    /// Deferred until the user is idle and not on battery in low power mode.
+    Maintenance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockReason {
+    UserActive,
+    OnBattery,
+}
+
+/// Payload of [`TASK_PROGRESS_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskProgress {
+    pub name: String,
+    pub done: u64,
+    /// `None` when the amount of work is not known up front.
+    pub total: Option<u64>,
+}
+
+type Job = Box<dyn FnOnce(&TaskContext) + Send>;
+
+struct Task {
+    name: String,
+    job: Job,
+}
+
+struct GateInput {
+    last_activity: Instant,
+    hidden_since: Option<Instant>,
+    forced_until: Option<Instant>,
+    low_power_mode: bool,
+    on_battery: Option<bool>,
+}
+
+fn evaluate(input: &GateInput, now: Instant) -> Result<(), BlockReason> {
+    if input.forced_until.is_some_and(|until| now < until) {
+        return Ok(());
+    }
+    if input.low_power_mode && input.on_battery == Some(true) {
+        return Err(BlockReason::OnBattery);
+    }
+    let idle = now.saturating_duration_since(input.last_activity) >= IDLE_THRESHOLD;
+    let hidden = input
+        .hidden_since
+        .is_some_and(|since| now.saturating_duration_since(since) >= HIDDEN_GRACE);
+    if idle || hidden {
+        Ok(())
+    } else {
+        Err(BlockReason::UserActive)
+    }
+}
+
+struct Inner {
+    app: AppHandle,
+    state: Mutex<SchedulerData>,
+    wake: Condvar,
+}
+
+struct SchedulerData {
+    queue: VecDeque<Task>,
+    running: Option<String>,
+    paused: bool,
+    progress: BTreeMap<String, TaskProgress>,
+    last_activity: Instant,
+    hidden_since: Option<Instant>,
+    forced_until: Option<Instant>,
+    on_battery: Option<bool>,
+    battery_checked: Option<Instant>,
+}
+
+impl Inner {
+    /// Refresh the battery state if stale. Runs outside the state lock;
+    /// on macOS this spawns `pmset`.
+    fn refresh_battery(&self) {
+        let stale = self
+            .state
+            .lock()
+            .battery_checked
+            .map_or(true, |at| at.elapsed() >= BATTERY_POLL_INTERVAL);
+        if stale {
+            let on_battery = battery::on_battery();
+            let mut data = self.state.lock();
+            data.on_battery = on_battery;
+            data.battery_checked = Some(Instant::now());
+        }
+    }
+
+    fn low_power_mode(&self) -> bool {
+        self.app
+            .try_state::<SettingsStore>()
+            .is_some_and(|store| store.read(|s| s.power.low_power_mode))
+    }
+
+    fn gate(&self, data: &SchedulerData, low_power_mode: bool) -> Result<(), BlockReason> {
+        evaluate(
+            &GateInput {
+                last_activity: data.last_activity,
+                hidden_since: data.hidden_since,
+                forced_until: data.forced_until,
+                low_power_mode,
+                on_battery: data.on_battery,
+            },
+            Instant::now(),
+        )
+    }
+
+    /// Block until maintenance work may proceed.
+    fn wait_for_gate(&self) {
+        loop {
+            self.refresh_battery();
+            let low_power_mode = self.low_power_mode();
+            let mut data = self.state.lock();
+            if self.gate(&data, low_power_mode).is_ok() {
+                data.paused = false;
+                return;
+            }
+            data.paused = data.running.is_some();
+            self.wake.wait_for(&mut data, GATE_POLL_INTERVAL);
+        }
+    }
+
+    fn next_task(&self) -> Task {
+        loop {
+            {
+                let mut data = self.state.lock();
+                while data.queue.is_empty() {
+                    self.wake.wait(&mut data);
+                }
+            }
+            self.wait_for_gate();
+            let mut data = self.state.lock();
+            if let Some(task) = data.queue.pop_front() {
+                data.running = Some(task.name.clone());
+                return task;
+            }
+        }
+    }
+
+    /// Run `job` with a context for `name`, then drop its progress.
+    fn run(self: &Arc<Self>, name: String, gated: bool, job: Job) {
+        let ctx = TaskContext {
+            inner: Arc::clone(self),
+            name,
+            gated,
+        };
+        job(&ctx);
+        self.state.lock().progress.remove(&ctx.name);
+    }
+
+    fn run_worker(self: Arc<Self>) {
+        loop {
+            let task = self.next_task();
+            info!("Running maintenance task '{}'", task.name);
+            self.run(task.name, true, task.job);
+            let mut data = self.state.lock();
+            data.running = None;
+            data.paused = false;
+            if data.queue.is_empty() {
+                data.forced_until = None;
+            }
+        }
+    }
+}
+
+/// Handed to every task. Maintenance tasks call [`checkpoint`](Self::checkpoint)
+/// between units of work.
+pub struct TaskContext {
+    inner: Arc<Inner>,
+    name: String,
+    /// `false` for interactive tasks, whose checkpoints never block.
+    gated: bool,
+}
+
+impl TaskContext {
+    /// Wait here while the user is active or low power mode defers work.
+    pub fn checkpoint(&self) {
+        if self.gated {
+            self.inner.wait_for_gate();
+        }
+    }
+
+    /// Publish how much of the task is done.
+    pub fn report_progress(&self, done: u64, total: Option<u64>) {
+        let progress = TaskProgress {
+            name: self.name.clone(),
+            done,
+            total,
+        };
+        self.inner
+            .state
+            .lock()
+            .progress
+            .insert(self.name.clone(), progress.clone());
+        let _ = self.inner.app.emit(TASK_PROGRESS_EVENT, progress);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulerState {
+    pub running: Option<String>,
+    /// The running task is waiting at a checkpoint.
+    pub paused: bool,
+    pub queued: Vec<String>,
+    /// Last reported progress of tasks still running, interactive ones
+    /// included.
+    pub progress: Vec<TaskProgress>,
+    pub idle_ms: u64,
+    pub main_window_hidden: bool,
+    pub on_battery: Option<bool>,
+    pub low_power_mode: bool,
+    /// Why maintenance is held back right now; `None` when it may run.
+    pub blocked: Option<BlockReason>,
+    pub forced: bool,
+}
+
+/// Managed scheduler handle.
+#[derive(Clone)]
+pub struct Scheduler {
+    inner: Arc<Inner>,
+}
+
+impl Scheduler {
+    /// Start the maintenance worker and follow main window visibility.
+    pub fn start(app: &AppHandle) -> Self {
+        let inner = Arc::new(Inner {
+            app: app.clone(),
+            state: Mutex::new(SchedulerData {
+                queue: VecDeque::new(),
+                running: None,
+                paused: false,
+                progress: BTreeMap::new(),
+                last_activity: Instant::now(),
+                hidden_since: None,
+                forced_until: None,
+                on_battery: None,
+                battery_checked: None,
+            }),
+            wake: Condvar::new(),
+        });
+
+        let worker = Arc::clone(&inner);
+        let spawned = std::thread::Builder::new()
+            .name("maintenance-scheduler".into())
+            .spawn(move || worker.run_worker());
+        if let Err(e) = spawned {
+            warn!("Failed to spawn maintenance scheduler thread: {}", e);
+        }
+
+        let scheduler = Self { inner };
+        let listener = scheduler.clone();
+        app.listen("main-window-visibility", move |event| {
+            let visible = serde_json::from_str::<bool>(event.payload()).unwrap_or(true);
+            listener.set_main_window_hidden(!visible);
+        });
+        scheduler
+    }
+
+    /// Queue or start a task.
+    pub fn submit(
+        &self,
+        name: impl Into<String>,
+        class: TaskClass,
+        job: impl FnOnce(&TaskContext) + Send + 'static,
+    ) {
+        let name = name.into();
+        match class {
+            TaskClass::Interactive => {
+                let inner = Arc::clone(&self.inner);
+                let task_name = name.clone();
+                let spawned = std::thread::Builder::new()
+                    .name(format!("task-{}", name))
+                    .spawn(move || inner.run(task_name, false, Box::new(job)));
+                if let Err(e) = spawned {
+                    warn!("Failed to spawn task '{}': {}", name, e);
+                }
+            }
+            TaskClass::Maintenance => {
+                self.inner.state.lock().queue.push_back(Task {
+                    name,
+                    job: Box::new(job),
+                });
+                self.inner.wake.notify_all();
+            }
+        }
+    }
+
+    /// Record user activity; closes the gate for [`IDLE_THRESHOLD`].
+    pub fn note_activity(&self) {
+        self.inner.state.lock().last_activity = Instant::now();
+    }
+
+    /// Checkpoint for maintenance work that runs outside the scheduler's
+    /// worker (e.g. a bulk reconciliation on the library watcher thread).
+    pub fn checkpoint(&self) {
+        self.inner.wait_for_gate();
+    }
+
+    fn set_main_window_hidden(&self, hidden: bool) {
+        let mut data = self.inner.state.lock();
+        if hidden {
+            data.hidden_since.get_or_insert_with(Instant::now);
+        } else {
+            data.hidden_since = None;
+            data.last_activity = Instant::now();
+        }
+        drop(data);
+        self.inner.wake.notify_all();
+    }
+
+    fn force(&self) {
+        self.inner.state.lock().forced_until = Some(Instant::now() + FORCE_WINDOW);
+        self.inner.wake.notify_all();
+    }
+
+    pub fn snapshot(&self) -> SchedulerState {
+        let low_power_mode = self.inner.low_power_mode();
+        let data = self.inner.state.lock();
+        SchedulerState {
+            running: data.running.clone(),
+            paused: data.paused,
+            queued: data.queue.iter().map(|task| task.name.clone()).collect(),
+            progress: data.progress.values().cloned().collect(),
+            idle_ms: data.last_activity.elapsed().as_millis() as u64,
+            main_window_hidden: data.hidden_since.is_some(),
+            on_battery: data.on_battery,
+            low_power_mode,
+            blocked: self.inner.gate(&data, low_power_mode).err(),
+            forced: data
+                .forced_until
+                .is_some_and(|until| Instant::now() < until),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_scheduler_state(scheduler: State<'_, Scheduler>) -> SchedulerState {
+    scheduler.snapshot()
+}
+
+/// Run queued maintenance now, ignoring idle and battery conditions.
+#[tauri::command]
+pub fn run_maintenance_now(scheduler: State<'_, Scheduler>) {
+    info!("Maintenance forced by user");
+    scheduler.force();
+}
+
+/// Reported (throttled) by the frontend on pointer / keyboard input.
+#[tauri::command]
+pub fn report_user_activity(scheduler: State<'_, Scheduler>) {
+    scheduler.note_activity();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(now: Instant) -> GateInput {
+        GateInput {
+            last_activity: now,
+            hidden_since: None,
+            forced_until: None,
+            low_power_mode: false,
+            on_battery: None,
+        }
+    }
+
+    #[test]
+    fn maintenance_waits_for_idle_or_hidden_window() {
+        let start = Instant::now();
+        let gate = input(start);
+        assert_eq!(
+            evaluate(&gate, start + Duration::from_secs(5)),
+            Err(BlockReason::UserActive)
+        );
+        assert_eq!(evaluate(&gate, start + IDLE_THRESHOLD), Ok(()));
+
+        let hidden = GateInput {
+            hidden_since: Some(start),
+            ..input(start)
+        };
+        assert!(evaluate(&hidden, start + Duration::from_secs(5)).is_err());
+        assert_eq!(evaluate(&hidden, start + HIDDEN_GRACE), Ok(()));
+    }
+
+    #[test]
+    fn low_power_mode_defers_only_on_battery() {
+        let start = Instant::now();
+        let later = start + IDLE_THRESHOLD;
+        let battery = GateInput {
+            low_power_mode: true,
+            on_battery: Some(true),
+            ..input(start)
+        };
+        assert_eq!(evaluate(&battery, later), Err(BlockReason::OnBattery));
+
+        let mains = GateInput {
+            on_battery: Some(false),
+            ..battery
+        };
+        assert_eq!(evaluate(&mains, later), Ok(()));
+    }
+
+    #[test]
+    fn forcing_overrides_everything_until_it_expires() {
+        let start = Instant::now();
+        let forced = GateInput {
+            low_power_mode: true,
+            on_battery: Some(true),
+            forced_until: Some(start + FORCE_WINDOW),
+            ..input(start)
+        };
+        assert_eq!(evaluate(&forced, start), Ok(()));
+        assert_eq!(
+            evaluate(&forced, start + FORCE_WINDOW),
+            Err(BlockReason::OnBattery)
+        );
+    }
+}
@@ -0,0 +1,48 @@
+import { onBeforeUnmount, onMounted, ref, type Ref } from "vue";
+import { isTauri } from "@/utils/tauri/windowManager";
+import { getDesktopEnvironment, isMobile } from "@/utils/tauri";
+import {
+  EVENTS,
+  getTitlebarStyle,
+  type TitlebarStyle,
+  type TitlebarStyleChanged,
+} from "@/utils/tauri/bindings";
+
+/**
+ * Whether the window `label` needs the DOM window controls: it has no
+ * decorum overlay titlebar and, on macOS, no traffic lights either. Follows
+ * `titlebar-style-changed`. Always false on mobile and outside Tauri.
+ */
+export function useDomWindowControls(label: () => string): Ref<boolean> {
+  const needed = ref(false);
+  let usesTrafficLights = false;
+  let unlisten: (() => void) | null = null;
+
+  async function refresh(style: TitlebarStyle) {
+    if (style === "overlay" || !usesTrafficLights) {
+      needed.value = style !== "overlay";
+      return;
+    }
+    // A decorated macOS window keeps its traffic lights.
+    const decorated = await window.__TAURI__!.core.invoke<boolean>("plugin:window|is_decorated", {
+      label: label(),
+    });
+    needed.value = !decorated;
+  }
+
+  onMounted(async () => {
+    if (!isTauri() || (await isMobile())) return;
+    usesTrafficLights = (await getDesktopEnvironment()).usesNativeTrafficLights;
+    unlisten = await window.__TAURI__!.event.listen<TitlebarStyleChanged>(
+      EVENTS.titlebarStyleChanged,
+      (e) => {
+        if (e.payload.label === label()) void refresh(e.payload.style);
+      },
+    );
+    await refresh(await getTitlebarStyle(label()));
+  });
+
+  onBeforeUnmount(() => unlisten?.());
+
+  return needed;
+}
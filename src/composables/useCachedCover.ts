@@ -0,0 +1,31 @@
+import { ref, watch, type Ref } from "vue";
+import { getCover } from "@/utils/tauri/coverCache";
+
+/**
+ * The cover at `source` through the shared on-disk cover cache, so the
+ * player windows download each cover once. The previous cover stays up
+ * until the new file is ready; the remote URL is used if caching fails.
+ */
+export function useCachedCover(source: () => string, size?: number): Ref<string> {
+  const cover = ref("");
+  let latest = "";
+
+  watch(
+    source,
+    (url) => {
+      latest = url;
+      if (!url) {
+        cover.value = "";
+        return;
+      }
+      getCover(url, size)
+        .catch(() => url)
+        .then((resolved) => {
+          if (latest === url) cover.value = resolved;
+        });
+    },
+    { immediate: true },
+  );
+
+  return cover;
+}
@@ -1,7 +1,17 @@
 import { settingStore } from "@/store";
 import { audioSendMsg, type DspConfig, type EqualizerBand } from "@/utils/tauri/audioBridge";
+import {
+  EVENTS,
+  getEqState,
+  setEqBands,
+  setEqEnabled,
+  setEqPreamp,
+  type EqState,
+} from "@/utils/tauri/bindings";
 
 const EQ_EPSILON_DB = 0.001;
+/** Set once the EQ this window kept in localStorage has been handed to the backend. */
+const EQ_MIGRATED_KEY = "dspEqMovedToBackend";
 const DISABLED_DSP_CONFIG: DspConfig = {
   enabled: false,
   inputGainDb: 0,
@@ -21,6 +31,9 @@ const DISABLED_DSP_CONFIG: DspConfig = {
 
 let initialized = false;
 let pendingTimer: ReturnType<typeof window.setTimeout> | null = null;
+/** EQ edits sent to the backend that have not been answered yet. */
+let eqWrites = 0;
+let eqQueue: Promise<void> = Promise.resolve();
 
 const clamp = (value: number, min: number, max: number) => Math.min(max, Math.max(min, value));
 
@@ -81,6 +94,62 @@ const scheduleApplyDspSettings = () => {
   }, 32);
 };
 
+const applyEqState = (state: EqState) => {
+  const setting = settingStore();
+  setting.dspEqEnabled = state.enabled;
+  setting.dspEqPreampDb = state.preampDb;
+  setting.dspEqPreset = state.preset;
+  setting.dspEqBandCount = state.bands.length;
+  setting.dspEqBands = state.bands.map((band) => ({ ...band }));
+};
+
+/**
+ * Send an EQ edit to the backend, which owns the EQ for every window.
+ * Edits go out one at a time so they land in order. While any are in
+ * flight, `eq-changed` echoes are skipped so a dragged slider does not
+ * jump back; the last reply is applied instead.
+ */
+export const updateEq = (write: () => Promise<EqState>): Promise<void> => {
+  eqWrites += 1;
+  eqQueue = eqQueue.then(write).then(
+    (state) => {
+      eqWrites -= 1;
+      if (eqWrites === 0) applyEqState(state);
+    },
+    (error) => {
+      eqWrites -= 1;
+      console.warn("[DSP] EQ update failed:", error);
+      if (eqWrites === 0) {
+        getEqState()
+          .then(applyEqState)
+          .catch(() => {});
+      }
+    },
+  );
+  return eqQueue;
+};
+
+/** Builds before the backend owned the EQ kept it in localStorage only. */
+const migrateLocalEq = async () => {
+  if (localStorage.getItem(EQ_MIGRATED_KEY)) return;
+  const setting = settingStore();
+  const bands = setting.dspEqBands.map((band) => ({ ...band }));
+  await setEqBands(bands);
+  await setEqPreamp(setting.dspEqPreampDb);
+  await setEqEnabled(setting.dspEqEnabled);
+  localStorage.setItem(EQ_MIGRATED_KEY, "1");
+};
+
+const syncEqWithBackend = async () => {
+  const tauri = window.__TAURI__;
+  if (!tauri) return;
+  await migrateLocalEq().catch((error) => console.warn("[DSP] EQ migration failed:", error));
+  await tauri.event.listen<EqState>(EVENTS.eqChanged, (event) => {
+    if (eqWrites === 0) applyEqState(event.payload);
+  });
+  applyEqState(await getEqState());
+};
+
 export function useDspSettings() {
   if (initialized) {
     return {
@@ -91,6 +160,7 @@ export function useDspSettings() {
 
   initialized = true;
   const setting = settingStore();
+  void syncEqWithBackend().catch((error) => console.warn("[DSP] EQ sync failed:", error));
 
   watch(
     () => [
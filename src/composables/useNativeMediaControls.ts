@@ -21,9 +21,9 @@ import {
   listenNowPlayingAction,
   updateNowPlayingPlayMode,
   updateNowPlayingState,
-  updateNowPlayingTimeline,
   type NowPlayingActionPayload,
 } from "@/utils/tauri/nowPlayingControls";
+import { getLivePlaybackMs } from "@/composables/usePlaybackClock";
 
 type PlaybackState = "playing" | "paused" | "buffering";
 type PlayMode = "normal" | "random" | "single";
@@ -43,6 +43,9 @@ interface NativeMediaPayload {
 interface NativeMediaAdapter {
   name: "media-session" | "now-playing-controls";
   artworkSize: number;
+  /** Progress reaches this adapter through the backend playback clock
+   * (see `usePlaybackClock`), so `updateProgress` is never called. */
+  clockDriven: boolean;
   initialize: () => Promise<void | undefined>;
   updateFull: (payload: NativeMediaPayload) => Promise<void | undefined>;
   updateProgress: (payload: {
@@ -65,6 +68,7 @@ interface NativeMediaAdapter {
 const mobileMediaSessionAdapter: NativeMediaAdapter = {
   name: "media-session",
   artworkSize: 256,
+  clockDriven: false,
   initialize: initializeMediaNotification,
   updateFull: (payload) => updateMediaNotification(payload),
   updateProgress: (payload) =>
@@ -87,19 +91,12 @@ const mobileMediaSessionAdapter: NativeMediaAdapter = {
 const desktopNowPlayingAdapter: NativeMediaAdapter = {
   name: "now-playing-controls",
   artworkSize: 512,
+  clockDriven: true,
   initialize: initializeNowPlayingControls,
   updateFull: (payload) => updateNowPlayingState(payload),
-  updateProgress: async (payload) => {
-    await updateNowPlayingTimeline({
-      position: payload.position,
-      duration: payload.duration,
-      seeked: payload.seeked,
-    });
-    await updateNowPlayingState({
-      isPlaying: payload.isPlaying,
-      playbackState: payload.isPlaying ? "playing" : "paused",
-    });
-  },
+  // The playback clock forwards to the OS media session and the lyric
+  // session, so there is a single source of truth for the position.
+  updateProgress: async () => undefined,
   updatePlaybackState: (payload) =>
     updateNowPlayingState({
       playbackState: payload.state,
@@ -127,39 +124,6 @@ export function useNativeMediaControls() {
 
   const PROGRESS_SYNC_INTERVAL = 5_000;
 
-  /**
-   * Live playback clock in milliseconds. Prefers the active sound's timeline
-   * (anchor + extrapolation — accurate even right after a backend-initiated
-   * track advance or a background wake-up) over the store snapshot, which is
-   * only refreshed by the RAF/interval loop and can be seconds stale.
-   */
-  function getLivePlaybackMs(): { position: number; duration: number } {
-    const playSongTime = music.getPlaySongTime;
-    let positionSec = playSongTime?.currentTime || 0;
-    let durationSec = playSongTime?.duration || 0;
-
-    const player = window.$player;
-    if (player) {
-      try {
-        const livePosition = player.seek();
-        if (typeof livePosition === "number" && Number.isFinite(livePosition)) {
-          positionSec = livePosition;
-        }
-        const liveDuration = player.duration();
-        if (Number.isFinite(liveDuration) && liveDuration > 0) {
-          durationSec = liveDuration;
-        }
-      } catch {
-        /* destroyed/mid-swap sound — store snapshot fallback is fine */
-      }
-    }
-
-    return {
-      position: Math.round(Math.max(0, positionSec) * 1_000),
-      duration: Math.round(Math.max(0, durationSec) * 1_000),
-    };
-  }
-
   function buildFullPayload(): NativeMediaPayload | null {
     const song = music.getPlaySongData;
     if (!song || !adapter) return null;
@@ -231,7 +195,7 @@ export function useNativeMediaControls() {
   });
 
   async function syncProgress(seeked = false): Promise<void> {
-    if (!active.value || !adapter || !music.getPlaySongData) return;
+    if (!active.value || !adapter || adapter.clockDriven || !music.getPlaySongData) return;
 
     const live = getLivePlaybackMs();
     await adapter.updateProgress({
@@ -264,7 +228,7 @@ export function useNativeMediaControls() {
   }
 
   function maybeSyncProgress(): void {
-    if (!active.value) return;
+    if (!active.value || adapter?.clockDriven) return;
     const now = Date.now();
     if (now - lastProgressSyncAt < PROGRESS_SYNC_INTERVAL) return;
     void syncProgress();
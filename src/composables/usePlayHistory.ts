@@ -0,0 +1,65 @@
+import { onUnmounted, watch } from "vue";
+import { musicStore } from "@/store";
+import type { SongData } from "@/store/musicTypes";
+import { historyAdd } from "@/utils/tauri/cacheStore";
+
+/** Plays shorter than this (a skip straight past the song) are not recorded. */
+const MIN_LISTENED_MS = 1_000;
+
+/**
+ * Records every play in the backend play history, for the listening report.
+ * A play ends when the track changes or the player goes away; the time
+ * listened counts only while actually playing.
+ */
+export function usePlayHistory() {
+  const music = musicStore();
+  let song: SongData | null = null;
+  let playedAt = 0;
+  let listenedMs = 0;
+  let playingSince: number | null = null;
+
+  function isPlaying(): boolean {
+    return music.getPlayState && !music.isLoadingSong;
+  }
+
+  function stopClock(): void {
+    if (playingSince === null) return;
+    listenedMs += Date.now() - playingSince;
+    playingSince = null;
+  }
+
+  function finish(): void {
+    stopClock();
+    if (song && listenedMs >= MIN_LISTENED_MS) {
+      historyAdd(song.id, playedAt, listenedMs, {
+        track: song.name,
+        artist: song.artist?.map((artist) => artist.name).join(" / ") ?? "",
+        album: song.album?.name ?? "",
+        source: song.localPath ? "local" : "netease",
+      }).catch((e) => console.warn("[PlayHistory] Failed to record a play:", e));
+    }
+    song = null;
+    listenedMs = 0;
+  }
+
+  watch(
+    () => music.getPlaySongData?.id,
+    () => {
+      finish();
+      song = music.getPlaySongData ?? null;
+      playedAt = Date.now();
+      if (song && isPlaying()) playingSince = Date.now();
+    },
+    { immediate: true },
+  );
+
+  watch(isPlaying, (playing) => {
+    if (!playing) {
+      stopClock();
+    } else if (song && playingSince === null) {
+      playingSince = Date.now();
+    }
+  });
+
+  onUnmounted(finish);
+}
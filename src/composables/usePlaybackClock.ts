@@ -0,0 +1,120 @@
+import { onMounted, onUnmounted, watch } from "vue";
+import { musicStore } from "@/store";
+import { isTauri } from "@/utils/tauri";
+import { syncPlaybackClock } from "@/utils/tauri/playbackClock";
+
+/** Off-by-more-than-this from the clock's expectation counts as a seek. */
+const SEEK_DETECT_THRESHOLD = 1_500;
+
+/**
+ * Live playback clock in milliseconds. Prefers the active sound's timeline
+ * (anchor + extrapolation — accurate even right after a backend-initiated
+ * track advance or a background wake-up) over the store snapshot, which is
+ * only refreshed by the RAF/interval loop and can be seconds stale.
+ */
+export function getLivePlaybackMs(): { position: number; duration: number; rate: number } {
+  const music = musicStore();
+  const playSongTime = music.getPlaySongTime;
+  let positionSec = playSongTime?.currentTime || 0;
+  let durationSec = playSongTime?.duration || 0;
+  let rate = 1;
+
+  const player = window.$player;
+  if (player) {
+    try {
+      const livePosition = player.seek();
+      if (typeof livePosition === "number" && Number.isFinite(livePosition)) {
+        positionSec = livePosition;
+      }
+      const liveDuration = player.duration();
+      if (Number.isFinite(liveDuration) && liveDuration > 0) {
+        durationSec = liveDuration;
+      }
+      const liveRate = player.rate?.();
+      if (typeof liveRate === "number" && Number.isFinite(liveRate) && liveRate > 0) {
+        rate = liveRate;
+      }
+    } catch {
+      /* destroyed/mid-swap sound — store snapshot fallback is fine */
+    }
+  }
+
+  return {
+    position: Math.round(Math.max(0, positionSec) * 1_000),
+    duration: Math.round(Math.max(0, durationSec) * 1_000),
+    rate,
+  };
+}
+
+let instanceCount = 0;
+
+/**
+ * Keeps the backend playback clock anchored to the player store. Syncs on
+ * play/pause, track and rate changes, and on jumps in the store's position
+ * (seeks from the progress bar, lyrics or media keys); the backend
+ * extrapolates in between, so steady playback costs nothing.
+ */
+export function usePlaybackClock() {
+  const music = musicStore();
+  let lastSyncedAt = 0;
+  let lastSyncedPosition = 0;
+  let lastSyncedPlaying = false;
+  let lastSyncedRate = 1;
+  let owner = false;
+
+  function sync(seeked = false): void {
+    if (!owner || !music.getPlaySongData) return;
+    const live = getLivePlaybackMs();
+    const playing = music.getPlayState && !music.isLoadingSong;
+    lastSyncedAt = Date.now();
+    lastSyncedPosition = live.position;
+    lastSyncedPlaying = playing;
+    lastSyncedRate = live.rate;
+    void syncPlaybackClock(live.position, playing, live.rate, seeked);
+  }
+
+  function checkForJump(): void {
+    if (!owner || !music.getPlaySongData) return;
+    const live = getLivePlaybackMs();
+    if (live.rate !== lastSyncedRate) {
+      sync();
+      return;
+    }
+    const elapsed = lastSyncedPlaying ? (Date.now() - lastSyncedAt) * lastSyncedRate : 0;
+    if (Math.abs(live.position - (lastSyncedPosition + elapsed)) > SEEK_DETECT_THRESHOLD) {
+      sync(true);
+    }
+  }
+
+  function onVisibilityChange(): void {
+    // Timers were frozen in the background; re-anchor on wake-up.
+    if (document.visibilityState === "visible") sync(true);
+  }
+
+  onMounted(() => {
+    if (instanceCount > 0 || !isTauri()) return;
+    instanceCount++;
+    owner = true;
+    document.addEventListener("visibilitychange", onVisibilityChange);
+    sync(true);
+  });
+
+  onUnmounted(() => {
+    if (!owner) return;
+    owner = false;
+    instanceCount = Math.max(0, instanceCount - 1);
+    document.removeEventListener("visibilitychange", onVisibilityChange);
+  });
+
+  watch(
+    () => music.getPlaySongData?.id,
+    () => sync(true),
+  );
+
+  watch(
+    () => [music.getPlayState, music.isLoadingSong],
+    () => sync(),
+  );
+
+  watch(() => music.getPlaySongTime?.currentTime, checkForJump);
+}
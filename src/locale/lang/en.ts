@@ -317,6 +317,14 @@ const messages: Record<string, unknown> = {
     noDesc: "Too lazy, do not even write the introduction",
     containing: "Song list containing this song",
     loginExpired: "Login is disabled, please login again",
+    migrationFailed: "Some settings from the previous version could not be migrated",
+    welcome: {
+      title: "Welcome to GMPlayer",
+      message:
+        "Sign in to sync your playlists, or pick a theme, audio output and desktop lyrics in Settings first.",
+      openSettings: "Open Settings",
+      later: "Later",
+    },
     cleanAll: "Reset successful",
     listenTogether: {
       title: "Listen Together",
@@ -376,6 +384,14 @@ const messages: Record<string, unknown> = {
     exit: "Exit",
     remember: "Remember my choice",
   },
+  // Safe mode banner
+  safeMode: {
+    title: "Safe Mode",
+    message:
+      "GMPlayer started in safe mode: saved window layout, the tray popup and the remote API are disabled. Reset what may be broken to restart normally, or close this to keep using safe mode.",
+    resetWindowState: "Reset Window Layout",
+    resetSettings: "Reset All Settings",
+  },
   // Setting
   setting: {
     dev: "WIP",
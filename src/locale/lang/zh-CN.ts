@@ -317,6 +317,13 @@ const messages: Record<string, unknown> = {
     noDesc: "太懒了吧，连简介都不写",
     containing: "包含这首歌的歌单",
     loginExpired: "登录已失效，请重新登录",
+    migrationFailed: "部分旧版本设置迁移失败",
+    welcome: {
+      title: "欢迎使用 GMPlayer",
+      message: "登录后即可同步歌单，也可以先到设置中选择主题、音频输出和桌面歌词。",
+      openSettings: "打开设置",
+      later: "稍后",
+    },
     cleanAll: "重置成功",
     listenTogether: {
       title: "一起听歌",
@@ -376,6 +383,14 @@ const messages: Record<string, unknown> = {
     exit: "退出",
     remember: "记住我的选择",
   },
+  // 安全模式提示
+  safeMode: {
+    title: "安全模式",
+    message:
+      "GMPlayer 以安全模式启动：已停用保存的窗口布局、托盘弹窗和远程 API。重置可能损坏的内容后将正常重启，关闭此提示则继续使用安全模式。",
+    resetWindowState: "重置窗口布局",
+    resetSettings: "重置全部设置",
+  },
   setting: {
     dev: "开发中功能",
     main: "基础",
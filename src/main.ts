@@ -7,6 +7,9 @@ import piniaPluginPersistedstate from "pinia-plugin-persistedstate";
 import App from "@/App.vue";
 import router from "@/router/index";
 import { audioPreheat, isTauri } from "@/utils/tauri/audioBridge";
+import { watchNetworkChanges } from "@/utils/tauri/httpStats";
+import { watchUserActivity } from "@/utils/tauri/scheduler";
+import { windowManager } from "@/utils/tauri/windowManager";
 
 // 全局样式
 import "@/style/global.scss";
@@ -25,6 +28,13 @@ await useMobileSafeAreaVars();
 app.mount("#app");
 
 if (isTauri()) {
+  // The main window is hidden until it reports its first paint (window
+  // effects would otherwise flash white); rAF does not run while hidden.
+  setTimeout(() => {
+    windowManager.notifyFirstPaint().catch(() => {});
+  }, 0);
+  watchNetworkChanges();
+  watchUserActivity();
   void audioPreheat().catch((err) => {
     console.warn("[main] native audio preheat failed", err);
   });
@@ -12,6 +12,7 @@ import { createI18n } from "vue-i18n";
 import piniaPluginPersistedstate from "pinia-plugin-persistedstate";
 
 import SlaveApp from "@/SlaveApp.vue";
+import { windowManager } from "@/utils/tauri/windowManager";
 import "@/style/global.scss";
 import "@/style/animate.scss";
 
@@ -70,6 +71,11 @@ const router = createRouter({
       name: "tray-popup",
       component: () => import("@/views/TrayPopup/index.vue"),
     },
+    {
+      path: "/osd",
+      name: "osd",
+      component: () => import("@/views/Osd/index.vue"),
+    },
     {
       path: "/settings/:section?",
       name: "slave-settings",
@@ -92,3 +98,21 @@ app.use(pinia);
 app.use(i18n);
 app.use(router);
 app.mount("#app");
+
+// Effect windows are kept hidden until the page has content. rAF does not
+// run in a hidden webview, so report on the next task after mounting.
+setTimeout(() => {
+  windowManager.notifyFirstPaint().catch(() => {});
+}, 0);
+
+// Lets the backend reload this window if its webview hangs or crashes.
+windowManager.startHeartbeat().catch(() => {});
+// Nothing to flush here; answering just keeps quitting from waiting on us.
+windowManager.onAppWillQuit().catch(() => {});
+
+// Pre-warmed windows are created hidden; pause CSS animations until shown.
+windowManager
+  .onWindowPrewarmed(({ paused }) => {
+    document.documentElement.classList.toggle("window-prewarmed", paused);
+  })
+  .catch(() => {});
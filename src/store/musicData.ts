@@ -4,6 +4,7 @@ import { getSongTime, getDailySongsDate } from "@/utils/timeTools";
 import { getPersonalFm, setFmTrash } from "@/api/home";
 import { getLikelist, setLikeSong } from "@/api/user";
 import { getPlayListCatlist } from "@/api/playlist";
+import { cachedRequest } from "@/utils/apiCache";
 import { resolveSongUrl } from "@/utils/AudioContext/resolveSongUrl";
 import { userStore } from "@/store";
 import { NIcon } from "naive-ui";
@@ -31,6 +32,9 @@ import {
 declare const $message: any;
 declare const $player: any;
 
+/** Playlist categories change a few times a year. */
+const CAT_LIST_TTL_SECS = 24 * 60 * 60;
+
 interface AutoMixStateData {
   phase: "idle" | "analyzing" | "waiting" | "crossfading" | "finishing";
   outroType: string | null;
@@ -630,6 +634,16 @@ const useMusicDataStore = defineStore("musicData", {
       if (play) this.setPlayState(true);
     },
 
+    /** Append songs that are not queued yet, without changing what plays. */
+    appendSongsToPlaylists(values: SongData[]) {
+      const queued = new Set(this.persistData.playlists.map((o) => o.id));
+      for (const value of values) {
+        if (queued.has(value.id)) continue;
+        queued.add(value.id);
+        this.persistData.playlists.push(value);
+      }
+    },
+
     addSongToNext(value: SongData) {
       this.persistData.playSongMode = "normal";
       const autoMix = getAutoMixEngine();
@@ -687,7 +701,14 @@ const useMusicDataStore = defineStore("musicData", {
     },
 
     setCatList(highquality: boolean = false) {
-      getPlayListCatlist().then((res: any) => {
+      const fetchCatList = (hq: boolean) =>
+        cachedRequest(
+          hq ? "playlist/catlist/highquality" : "playlist/catlist",
+          CAT_LIST_TTL_SECS,
+          () => getPlayListCatlist(hq),
+          (res: any) => res?.code === 200,
+        );
+      fetchCatList(false).then((res: any) => {
         if (res.code === 200) {
           this.catList = res;
         } else {
@@ -695,7 +716,7 @@ const useMusicDataStore = defineStore("musicData", {
         }
       });
       if (highquality) {
-        getPlayListCatlist(true).then((res: any) => {
+        fetchCatList(true).then((res: any) => {
           if (res.code === 200) {
             this.highqualityCatList = res.tags;
           } else {
@@ -1,13 +1,118 @@
 import { acceptHMRUpdate, defineStore } from "pinia";
-import { reactive } from "vue";
-import { createDefaultPersistData, type PersistData } from "./musicTypes";
+import { reactive, toRaw, watch } from "vue";
+import { debounce } from "throttle-debounce";
+import { createDefaultPersistData, type PersistData, type SongData } from "./musicTypes";
+import { isTauri } from "@/utils/tauri/windowManager";
+import {
+  LARGE_QUEUE_THRESHOLD,
+  applyQueueDiff,
+  diffQueue,
+  getQueueMeta,
+  loadQueue,
+  replaceQueue,
+  type QueueMeta,
+  type QueueOp,
+} from "@/utils/tauri/queueStore";
+
+// Backend queue mirror. Large queues are left out of localStorage and
+// restored from the backend queue store after hydration.
+let queueMeta: QueueMeta | null = null;
+let mirroredQueue: SongData[] = [];
+let restoringQueue = false;
+// Syncs and restores run one at a time, each diffing against the mirror the
+// previous one left behind; overlapping ones would reuse a revision.
+let queueTask: Promise<void> = Promise.resolve();
+
+function enqueueQueueTask(task: () => Promise<void>): Promise<void> {
+  queueTask = queueTask.then(task).catch((err) => console.warn("[QueueStore]", err));
+  return queueTask;
+}
+
+function syncQueue(persistData: PersistData): Promise<void> {
+  return enqueueQueueTask(() => sendQueue(persistData));
+}
+
+async function sendQueue(persistData: PersistData): Promise<void> {
+  if (restoringQueue) return;
+  const next = toRaw(persistData.playlists).slice();
+  const currentIndex = next.length > 0 ? persistData.playSongIndex : null;
+  try {
+    const changes = queueMeta ? diffQueue(mirroredQueue, next, (song) => song.id) : null;
+    if (queueMeta && changes) {
+      let revision = queueMeta.revision;
+      const ops: QueueOp[] = changes.map((change) => ({ ...change, revision: ++revision }));
+      if (currentIndex !== queueMeta.currentIndex) {
+        ops.push({ op: "setCurrent", index: currentIndex, revision: ++revision });
+      }
+      if (ops.length > 0) queueMeta = await applyQueueDiff(ops);
+    } else {
+      queueMeta = await replaceQueue(next, currentIndex);
+    }
+    mirroredQueue = next;
+  } catch (err) {
+    // Revision gap or storage error: resend the whole queue next time.
+    console.warn("[QueueStore] sync failed:", err);
+    queueMeta = null;
+  }
+}
+
+/**
+ * Replace the queue with the backend's copy. With `snapshot` (from a
+ * recovery payload) only if the backend still holds that exact queue, and
+ * at the snapshot's current index.
+ */
+async function restoreQueue(
+  persistData: PersistData,
+  snapshot?: { hash: string; currentIndex: number | null },
+): Promise<void> {
+  try {
+    const meta = await getQueueMeta();
+    if (!meta || meta.length === 0 || (snapshot && meta.hash !== snapshot.hash)) return;
+    const items = await loadQueue<SongData>();
+    if (items.length > 0) {
+      const currentIndex = snapshot ? snapshot.currentIndex : meta.currentIndex;
+      persistData.playlists = items;
+      persistData.playSongIndex = Math.min(currentIndex ?? 0, items.length - 1);
+      mirroredQueue = items.slice();
+      queueMeta = meta;
+    }
+  } catch (err) {
+    console.warn("[QueueStore] restore failed:", err);
+  } finally {
+    restoringQueue = false;
+  }
+}
 
 export const useMusicPersistedDataStore = defineStore(
   "musicPersistedData",
   () => {
     const persistData = reactive<PersistData>(createDefaultPersistData());
+    const scheduleSync = debounce(500, () => void syncQueue(persistData));
+
+    if (isTauri()) {
+      // `deep: 1` sees reorders and splices without walking every song.
+      watch(() => persistData.playlists, scheduleSync, { deep: 1 });
+      watch(() => persistData.playSongIndex, scheduleSync);
+    }
+
+    /** Send the queue to the backend now instead of after the debounce. */
+    async function flushQueue(): Promise<void> {
+      if (!isTauri()) return;
+      scheduleSync.cancel({ upcomingOnly: true });
+      await syncQueue(persistData);
+    }
+
+    /** Take over the queue a recovery payload points at, if it still matches. */
+    async function restoreRecoveredQueue(snapshot: {
+      hash: string;
+      currentIndex: number | null;
+    }): Promise<void> {
+      if (!isTauri()) return;
+      scheduleSync.cancel({ upcomingOnly: true });
+      await enqueueQueueTask(() => restoreQueue(persistData, snapshot));
+    }
 
-    return { persistData };
+    return { persistData, flushQueue, restoreRecoveredQueue };
   },
   {
     persist: [
@@ -15,6 +120,27 @@ export const useMusicPersistedDataStore = defineStore(
         key: "musicData",
         storage: localStorage,
         pick: ["persistData"],
+        serializer: {
+          serialize: (state) => {
+            const data = state.persistData as PersistData;
+            if (!isTauri() || data.playlists.length < LARGE_QUEUE_THRESHOLD) {
+              return JSON.stringify(state);
+            }
+            return JSON.stringify({
+              ...state,
+              persistData: { ...data, playlists: [] },
+              queueOffloaded: true,
+            });
+          },
+          deserialize: (value) => {
+            const parsed = JSON.parse(value);
+            restoringQueue = isTauri() && parsed?.queueOffloaded === true;
+            return parsed;
+          },
+        },
+        afterHydrate(ctx: { store: any }) {
+          if (restoringQueue) void enqueueQueueTask(() => restoreQueue(ctx.store.persistData));
+        },
       },
     ],
   },
@@ -299,6 +299,10 @@ export class BufferedSound implements ISound {
     return this._inner?.duration() ?? 0;
   }
 
+  rate(): number {
+    return this._inner?.rate() ?? 1;
+  }
+
   volume(vol?: number): number | this {
     if (this._inner) {
       if (vol === undefined) {
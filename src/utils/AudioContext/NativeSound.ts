@@ -444,6 +444,10 @@ export class NativeSound implements ISound {
     return this._audio.duration || 0;
   }
 
+  rate(): number {
+    return this._audio.playbackRate || 1;
+  }
+
   volume(vol?: number): number | this {
     if (vol === undefined) {
       return this._volume;
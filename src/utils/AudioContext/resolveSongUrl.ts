@@ -23,11 +23,13 @@ export interface SongUrlInput {
   fee?: number;
   pc?: any;
   name?: string;
+  /** Set for files opened from disk; the native backend plays the path. */
+  localPath?: string;
 }
 
 export interface ResolveSongUrlResult {
   url: string;
-  source: "ncm" | "unm";
+  source: "ncm" | "unm" | "local";
 }
 
 export interface ResolveSongUrlOptions {
@@ -50,6 +52,10 @@ export async function resolveSongUrl(
   const signal = options?.signal;
   const logPrefix = `[resolveSongUrl] ${song.name ?? song.id}`;
 
+  if (song.localPath) {
+    return { url: song.localPath, source: "local" };
+  }
+
   // Resolve effective level
   const settingStore = useSettingDataStore();
   const effectiveLevel = (level || settingStore.songLevel || "exhigh") as MusicLevel;
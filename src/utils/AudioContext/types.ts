@@ -47,6 +47,8 @@ export interface ISound {
   stop(): this;
   seek(pos?: number): number | this;
   duration(): number;
+  /** Playback speed multiplier; sounds without one play at 1. */
+  rate?(): number;
   volume(vol?: number): number | this;
   fade(from: number, to: number, duration: number): this;
   on(event: SoundEventType, callback: SoundEventCallback): this;
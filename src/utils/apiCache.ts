@@ -0,0 +1,33 @@
+/**
+ * API responses that rarely change, kept in the backend's SQLite cache so
+ * they survive restarts without filling localStorage. Outside Tauri the
+ * cache is unavailable and every call goes to the network.
+ */
+import { cacheGet, cacheSet } from "@/utils/tauri/cacheStore";
+
+/**
+ * The cached response for `key` while it is fresh, else `fetcher()`'s,
+ * which is stored for `ttlSecs` when `cacheable` accepts it.
+ */
+export async function cachedRequest<T>(
+  key: string,
+  ttlSecs: number,
+  fetcher: () => Promise<T>,
+  cacheable: (response: T) => boolean = () => true,
+): Promise<T> {
+  const hit = await cacheGet(key).catch(() => null);
+  if (hit && !hit.expired) {
+    try {
+      return JSON.parse(hit.value) as T;
+    } catch {
+      /* corrupt entry — fetch again and overwrite it */
+    }
+  }
+  const response = await fetcher();
+  if (cacheable(response)) {
+    cacheSet(key, JSON.stringify(response), ttlSecs).catch((e) =>
+      console.warn("[apiCache] Failed to store", key, e),
+    );
+  }
+  return response;
+}
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface ApiResponse {
+  status: number;
+  /** Lower-case names; repeated headers are joined with `, `. */
+  headers: Record<string, string>;
+  /** Text, or base64 when `base64` is set (binary or non-UTF-8 bodies). */
+  body: string;
+  base64: boolean;
+}
+
+export interface ApiFetchOptions {
+  method?: string;
+  headers?: Record<string, string>;
+  body?: string;
+  /** Attach cookies from the backend jar and keep the ones set in reply. */
+  useCookies?: boolean;
+  /** Per-attempt timeout; defaults to the HTTP policy's. */
+  timeoutMs?: number;
+}
+
+export interface CookieInfo {
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+  /** `null` for session cookies. */
+  expiresMs: number | null;
+  secure: boolean;
+  httpOnly: boolean;
+}
+
+/** Error returned by `api_fetch` and the cookie commands. */
+export interface ApiClientError {
+  kind: "invalidRequest" | "request" | "cookie" | "io";
+  message: string;
+}
+
+/**
+ * Send a request from the backend, which unlike the webview can send and
+ * receive cookies. HTTP error statuses resolve normally; only transport
+ * failures reject.
+ */
+export async function apiFetch(url: string, options: ApiFetchOptions = {}): Promise<ApiResponse> {
+  return invoke<ApiResponse>("api_fetch", {
+    url,
+    method: options.method,
+    headers: options.headers,
+    body: options.body,
+    useCookies: options.useCookies ?? false,
+    timeoutMs: options.timeoutMs,
+  });
+}
+
+/** Unexpired cookies that would be sent to `domain`. */
+export async function getCookies(domain: string): Promise<CookieInfo[]> {
+  return invoke<CookieInfo[]>("cookies_get", { domain });
+}
+
+/** Add or replace a cookie for `domain` and its subdomains, kept for a year. */
+export async function setCookie(domain: string, name: string, value: string): Promise<void> {
+  await invoke("cookies_set", { domain, name, value });
+}
+
+/** Drop every cookie, e.g. on logout. */
+export async function clearCookies(): Promise<void> {
+  await invoke("cookies_clear");
+}
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { isTauri } from "./windowManager";
+
+export interface ApiEndpointHealth {
+  source: string;
+  url: string;
+  /** Reachable and not answering with a 5xx. */
+  healthy: boolean;
+  status: number | null;
+  latencyMs: number | null;
+  version: string | null;
+  error: string | null;
+  checkedMs: number;
+}
+
+/** Configured base URL per source; missing sources use the built-in default. */
+export async function getApiEndpoints(): Promise<Record<string, string>> {
+  if (!isTauri()) return {};
+  return invoke<Record<string, string>>("get_api_endpoints");
+}
+
+/**
+ * Store a self-hosted API base URL for `source` (empty string resets it).
+ * Only http(s) URLs are accepted; resolves to the normalized URL.
+ */
+export async function setApiEndpoint(source: string, url: string): Promise<string | null> {
+  return invoke<string | null>("set_api_endpoint", { source, url });
+}
+
+export async function checkApiEndpoint(source: string): Promise<ApiEndpointHealth> {
+  return invoke<ApiEndpointHealth>("check_api_endpoint", { source });
+}
+
+export async function getApiEndpointHealth(): Promise<ApiEndpointHealth[]> {
+  if (!isTauri()) return [];
+  return invoke<ApiEndpointHealth[]>("get_api_endpoint_health");
+}
+
+export function onApiEndpointHealthChanged(
+  handler: (health: ApiEndpointHealth) => void,
+): Promise<UnlistenFn> {
+  return listen<ApiEndpointHealth>("api-endpoint-health-changed", (event) =>
+    handler(event.payload),
+  );
+}
@@ -0,0 +1,53 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { isTauri } from "./windowManager";
+
+/** Format the OS mixer runs the device at (shared mode). */
+export interface MixFormat {
+  sampleRate: number;
+  channels: number;
+  sampleFormat: string;
+}
+
+export interface DeviceCapabilities {
+  deviceId: string;
+  name: string;
+  sampleRates: number[];
+  channelCounts: number[];
+  sampleFormats: string[];
+  mixFormat: MixFormat | null;
+}
+
+export interface RecommendedAudioConfig {
+  deviceId: string;
+  sampleRate: number;
+  channels: number;
+  /** The rate is the device's mix rate, so the OS will not resample. */
+  native: boolean;
+}
+
+export interface DeviceFormatChanged {
+  deviceId: string;
+  mixFormat: MixFormat | null;
+}
+
+/** `deviceId` is a device name or `"default"`. */
+export async function getDeviceCapabilities(deviceId = "default"): Promise<DeviceCapabilities | null> {
+  if (!isTauri()) return null;
+  return invoke<DeviceCapabilities>("get_device_capabilities", { deviceId });
+}
+
+/** Sample rate / channels to create the AudioContext with. */
+export async function getRecommendedAudioConfig(): Promise<RecommendedAudioConfig | null> {
+  if (!isTauri()) return null;
+  return invoke<RecommendedAudioConfig>("get_recommended_audio_config");
+}
+
+/** Fires when the default output device or its mix format changes. */
+export function onDeviceFormatChanged(
+  handler: (change: DeviceFormatChanged) => void,
+): Promise<UnlistenFn> {
+  return listen<DeviceFormatChanged>("audio-device-format-changed", (event) =>
+    handler(event.payload),
+  );
+}
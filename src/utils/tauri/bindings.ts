@@ -0,0 +1,1197 @@
+// Generated by `gmplayer --dump-bindings` from src-tauri/src/desktop/bindings.rs.
+// Do not edit by hand.
+
+import { invoke } from "@tauri-apps/api/core";
+
+export interface AccentColor {
+  b: number;
+  g: number;
+  r: number;
+}
+
+/** Payload of `app-will-quit`. */
+export interface AppWillQuit {
+  /** How long the app waits for `quit_ack` before exiting anyway. */
+  graceMs: number;
+}
+
+/** What the OS will do at the next login. */
+export interface AutostartStatus {
+  enabled: boolean;
+  /** Launch with `--hidden`, leaving only the tray icon. */
+  startHidden: boolean;
+}
+
+/** Crop rectangle in logical pixels, relative to the window's client area. */
+export interface CaptureRegion {
+  height: number;
+  width: number;
+  x: number;
+  y: number;
+}
+
+/** What a window's close button does. */
+export type CloseBehavior = "ask" | "hide" | "exit";
+
+export interface ClosedWindow {
+  bounds?: WindowBounds | null;
+  closedMs: number;
+  label: string;
+  visible: boolean;
+}
+
+export type CoordinateUnits = "physical" | "logical" | "platformDependent";
+
+export interface CoverCacheSettings {
+  /** Least recently used covers are deleted beyond this. */
+  maxMb?: number;
+}
+
+export interface DownloadSettings {
+  /** Downloads running at once; the rest wait in the queue. */
+  maxConcurrent?: number;
+}
+
+/** One EQ band, as the frontend DSP chain consumes it. */
+export interface EqBand {
+  enabled: boolean;
+  filterType: EqFilterType;
+  frequency: number;
+  gainDb: number;
+  q: number;
+}
+
+export type EqFilterType = "peaking" | "lowShelf" | "highShelf";
+
+export interface EqPresetInfo {
+  builtin: boolean;
+  id: string;
+  name: string;
+}
+
+/** EQ state as reported to the frontend and broadcast with `eq-changed`. */
+export interface EqState {
+  bands: EqBand[];
+  enabled: boolean;
+  preampDb: number;
+  preset: string;
+}
+
+/** Payload of `files-opened`. */
+export interface FilesOpened {
+  files: OpenedFile[];
+  /** Dropped or passed paths that were not audio files. */
+  ignored: number;
+}
+
+/** A hit region defined by the frontend (logical coordinates relative to the webview client area). The backend uses these to decide whether the cursor is over an interactive element. */
+export interface HitRegion {
+  height: number;
+  id: string;
+  width: number;
+  x: number;
+  y: number;
+}
+
+export interface HttpPolicy {
+  /** Backoff before the first retry, doubled for each further retry. */
+  baseDelayMs?: number;
+  /** Consecutive failures that open a host's circuit. */
+  failureThreshold?: number;
+  maxConcurrentPerHost?: number;
+  maxDelayMs?: number;
+  /** Retries after the first attempt; 0 disables retrying. */
+  maxRetries?: number;
+  /** How long an open circuit rejects requests before letting a probe through. */
+  openCooldownMs?: number;
+  /** Per-attempt timeout. */
+  timeoutMs?: number;
+}
+
+/** Payload of `system-idle` and `system-active`. */
+export interface IdleChanged {
+  idleSecs: number;
+}
+
+/** How awake to keep the machine. Ordered from weakest to strongest. */
+export type KeepAwake = "off" | "system" | "display";
+
+/** Physical outer position for `moved`, physical inner size for `resized`. */
+export type LifecycleData = { x: number; y: number } | { height: number; width: number };
+
+export type LifecycleKind = "created" | "destroyed" | "focused" | "blurred" | "moved" | "resized" | "close-requested";
+
+/** One timed lyric line as produced by the frontend parsers. */
+export interface LyricLine {
+  text: string;
+  timeMs: number;
+  translation?: string | null;
+}
+
+/** Payload of `lyric-line-changed`. `index` is `None` before the first line, for tracks without lyrics, and once the session is torn down. */
+export interface LyricLineChange {
+  index?: number | null;
+  text?: string | null;
+  trackId: string;
+  translation?: string | null;
+}
+
+/** Payload of `desktop-lyrics-hover`. */
+export type LyricsHover = "enter" | "leave";
+
+export type LyricsOrientation = "horizontal" | "vertical";
+
+/** Edge snapping of the desktop-lyrics window. */
+export interface LyricsSnap {
+  enabled?: boolean;
+  /** Distance to a guide at which the window snaps, in logical pixels. */
+  thresholdPx?: number;
+}
+
+/** Payload of `desktop-lyrics-snapped`; `x`/`y` are the new physical position. */
+export interface LyricsSnapped {
+  edges: SnapEdge[];
+  x: number;
+  y: number;
+}
+
+/** Where `move_window_to_monitor` puts the window on the target monitor. */
+export type MonitorAnchor = "center" | "bottom-center" | "top-center" | "remember";
+
+/** A connected monitor, in physical pixels. */
+export interface MonitorInfo {
+  isPrimary: boolean;
+  name?: string | null;
+  position: ScreenPoint;
+  scaleFactor: number;
+  size: ScreenSize;
+  workArea: ScreenRect;
+}
+
+/** A button on the notification. */
+export type NotificationAction = "previous" | "play" | "pause" | "next";
+
+/** Payload of `notification-action`. */
+export interface NotificationActionPressed {
+  action: NotificationAction;
+}
+
+export interface NotificationSettings {
+  /** Show a now-playing notification on track change while the main window is hidden. */
+  enabled?: boolean;
+}
+
+export interface OpenedFile {
+  artist?: string | null;
+  durationMs?: number | null;
+  path: string;
+  title?: string | null;
+}
+
+/** Payload of `desktop-lyrics-orientation-clamped`, in physical pixels. */
+export interface OrientationClamped {
+  height: number;
+  orientation: LyricsOrientation;
+  width: number;
+}
+
+/** Screen corner (or edge center) the OSD is anchored to. */
+export type OsdCorner = "topLeft" | "topCenter" | "topRight" | "bottomLeft" | "bottomCenter" | "bottomRight";
+
+export interface OsdSettings {
+  corner?: OsdCorner;
+  durationMs?: number;
+  enabled?: boolean;
+  /** Distance from the work-area edge, in logical pixels. */
+  margin?: number;
+  /** Also show the OSD while a fullscreen app (usually a game) is in front. */
+  showOverFullscreen?: boolean;
+}
+
+/** `None` means "use the platform default". */
+export interface PathSettings {
+  downloadDir?: string | null;
+  exportDir?: string | null;
+}
+
+export interface PayloadCacheStats {
+  /** Size of the payloads as compact JSON. */
+  bytes: number;
+  entries: number;
+}
+
+export type PendingOpen = { kind: "file"; path: string } | { kind: "url"; url: string };
+
+export interface PositioningConvention {
+  command: string;
+  input?: CoordinateUnits | null;
+  note: string;
+  output?: CoordinateUnits | null;
+}
+
+export interface PowerSettings {
+  /** Defer maintenance work (loudness scans, prefetch, cache eviction, library rescans) entirely while running on battery. */
+  lowPowerMode?: boolean;
+}
+
+/** Edge or corner grabbed by `start_native_resize`. */
+export type ResizeEdge = "north" | "north-east" | "east" | "south-east" | "south" | "south-west" | "west" | "north-west";
+
+export interface ScreenPoint {
+  x: number;
+  y: number;
+}
+
+export interface ScreenRect {
+  height: number;
+  width: number;
+  x: number;
+  y: number;
+}
+
+export interface ScreenSize {
+  height: number;
+  width: number;
+}
+
+/** Payload of `second-instance`. */
+export interface SecondInstance {
+  /** Arguments after the executable path. */
+  args: string[];
+  cwd: string;
+}
+
+/** Payload of `shared-state-changed`. `value` is null when the key was deleted. */
+export interface SharedStateChanged {
+  key: string;
+  revision: number;
+  value: unknown;
+}
+
+/** A key's value and the revision it was written at. */
+export interface SharedValue {
+  revision: number;
+  value: unknown;
+}
+
+export type SnapEdge = "left" | "center" | "right" | "top" | "bottom";
+
+export type StartupMode = { mode: "normal" } | { mode: "hidden" } | { label: string; mode: "window" };
+
+export type SystemTheme = "light" | "dark";
+
+/** Payload of `system-volume-changed`. */
+export interface SystemVolume {
+  /** 0.0–1.0. */
+  level: number;
+  muted: boolean;
+}
+
+export type TaskbarProgressState = "normal" | "paused" | "indeterminate" | "none";
+
+export type TitlebarStyle = "overlay" | "hidden";
+
+export interface TitlebarStyleChanged {
+  label: string;
+  style: TitlebarStyle;
+}
+
+export type TrayClickAction = "toggle-main" | "nothing" | "show-main" | "play-pause" | "show-popup";
+
+export interface TrayConfig {
+  /** On Windows and macOS a double-click also delivers both single clicks, so this usually pairs with a left-click action that does no harm twice, such as `show-main`. */
+  doubleClickAction?: TrayClickAction;
+  leftClickAction?: TrayClickAction;
+  /** Switched at runtime with `set_tray_menu_mode`, which rebuilds the tray icon. */
+  menuMode?: TrayMenuMode;
+}
+
+export type TrayMenuMode = "popup" | "native";
+
+/** The last `update_tray_now_playing` payload. Sent with `tray-popup-opened`, and used to fill a rebuilt tray. */
+export interface TrayNowPlaying {
+  album?: string | null;
+  artist: string;
+  durationMs: number;
+  nextTitle?: string | null;
+  playing: boolean;
+  positionMs: number;
+  title: string;
+}
+
+/** What the tray icon badge shows. */
+export type TrayPlaybackState = "playing" | "paused" | "stopped";
+
+/** Logical outer position and inner size. */
+export interface WindowBounds {
+  height: number;
+  width: number;
+  x: number;
+  y: number;
+}
+
+export type WindowCapture = { bytes: number[]; height: number; kind: "png"; width: number } | { height: number; kind: "file"; path: string; width: number };
+
+/** Configuration for creating a window. */
+export interface WindowConfig {
+  /** Additional args for window */
+  additionalArgs?: string | null;
+  alwaysOnTop?: boolean;
+  center?: boolean;
+  /** If true, close button hides the window instead of destroying it. */
+  closeableToTray?: boolean;
+  /** Keep the window out of screenshots, recordings and screen sharing. Windows and macOS only. */
+  contentProtected?: boolean;
+  decorations?: boolean;
+  /** If false, the window never takes keyboard focus (overlays, OSD). */
+  focusable?: boolean;
+  height: number;
+  label: string;
+  maxHeight?: number | null;
+  maxWidth?: number | null;
+  minHeight?: number | null;
+  minWidth?: number | null;
+  /** Parent window label (for child windows) */
+  parentLabel?: string | null;
+  /** Create hidden for later use: effects and positioning are deferred until the window is first shown (see `prewarm.rs`). */
+  prewarm?: boolean;
+  resizable?: boolean;
+  /** Whether to show a native window shadow. Defaults to false for transparent windows. */
+  shadow?: boolean;
+  /** If true, reuse existing window instead of creating a duplicate. */
+  singleInstance?: boolean;
+  skipTaskbar?: boolean;
+  title: string;
+  /** macOS traffic lights inset (x, y). Only used on macOS. */
+  trafficLightsInset?: [number, number] | null;
+  transparent?: boolean;
+  url: string;
+  /** If true, apply decorum overlay titlebar (macOS and Windows). */
+  useOverlayTitlebar?: boolean;
+  visible?: boolean;
+  width: number;
+  /** Native window effect: "acrylic", "mica", "mica_dark", "tabbed", "blur" or "vibrancy[:material]" (macOS). Platform-specific. */
+  windowEffect?: string | null;
+}
+
+/** Fields to change on a preset before it is opened; anything left out keeps the preset's value. The label is fixed by the preset. Fields that are optional in `WindowConfig` can be set here but not cleared. */
+export interface WindowConfigOverrides {
+  additionalArgs?: string | null;
+  alwaysOnTop?: boolean | null;
+  center?: boolean | null;
+  closeableToTray?: boolean | null;
+  contentProtected?: boolean | null;
+  decorations?: boolean | null;
+  focusable?: boolean | null;
+  height?: number | null;
+  maxHeight?: number | null;
+  maxWidth?: number | null;
+  minHeight?: number | null;
+  minWidth?: number | null;
+  parentLabel?: string | null;
+  prewarm?: boolean | null;
+  resizable?: boolean | null;
+  shadow?: boolean | null;
+  singleInstance?: boolean | null;
+  skipTaskbar?: boolean | null;
+  title?: string | null;
+  trafficLightsInset?: [number, number] | null;
+  transparent?: boolean | null;
+  url?: string | null;
+  useOverlayTitlebar?: boolean | null;
+  visible?: boolean | null;
+  width?: number | null;
+  windowEffect?: string | null;
+}
+
+export interface WindowInfo {
+  alwaysOnTop: boolean;
+  label: string;
+  visible: boolean;
+}
+
+export interface WindowLifecycle {
+  data?: LifecycleData | null;
+  kind: LifecycleKind;
+  label: string;
+}
+
+export interface WindowPinChanged {
+  alwaysOnTop: boolean;
+  label: string;
+}
+
+export interface WindowPrewarmed {
+  label: string;
+  /** `true` while hidden and pre-warmed; `false` once really shown. */
+  paused: boolean;
+}
+
+/** Payload of `window-rpc-request`. */
+export interface WindowRpcRequest {
+  channel: string;
+  payload: unknown;
+  requestId: number;
+  /** Label of the window waiting for the answer. */
+  source: string;
+}
+
+/** Every flag is `false` when the window does not exist. */
+export interface WindowState {
+  /** Hidden from screen capture; see `set_content_protected`. */
+  contentProtected: boolean;
+  exists: boolean;
+  focused: boolean;
+  fullscreen: boolean;
+  maximized: boolean;
+  minimized: boolean;
+  visible: boolean;
+}
+
+export interface WindowUnresponsive {
+  label: string;
+}
+
+export interface WindowVisibilityChanged {
+  label: string;
+  visible: boolean;
+}
+
+export interface WindowZoomChanged {
+  factor: number;
+  label: string;
+}
+
+export const EVENTS = {
+  windowVisibilityChanged: "window-visibility-changed",
+  mainWindowVisibility: "main-window-visibility",
+  mainCloseRequested: "main-close-requested",
+  windowPinChanged: "window-pin-changed",
+  windowAlwaysOnTopChanged: "window-always-on-top-changed",
+  windowZoomChanged: "window-zoom-changed",
+  windowPrewarmed: "window-prewarmed",
+  windowLifecycle: "window-lifecycle",
+  sharedStateChanged: "shared-state-changed",
+  windowRpcRequest: "window-rpc-request",
+  windowUnresponsive: "window-unresponsive",
+  appWillQuit: "app-will-quit",
+  systemIdle: "system-idle",
+  systemActive: "system-active",
+  systemThemeChanged: "system-theme-changed",
+  accentColorChanged: "accent-color-changed",
+  desktopLyricsSnapped: "desktop-lyrics-snapped",
+  desktopLyricsHover: "desktop-lyrics-hover",
+  titlebarStyleChanged: "titlebar-style-changed",
+  desktopLyricsAutohidden: "desktop-lyrics-autohidden",
+  desktopLyricsOrientationClamped: "desktop-lyrics-orientation-clamped",
+  trayPopupOpened: "tray-popup-opened",
+  trayMenuPlayPause: "tray-menu-play-pause",
+  trayMenuNextTrack: "tray-menu-next-track",
+  trayMenuPrevTrack: "tray-menu-prev-track",
+  thumbbarPrev: "thumbbar-prev",
+  thumbbarPlayPause: "thumbbar-play-pause",
+  thumbbarNext: "thumbbar-next",
+  eqChanged: "eq-changed",
+  secondInstance: "second-instance",
+  filesOpened: "files-opened",
+  notificationAction: "notification-action",
+  systemVolumeChanged: "system-volume-changed",
+  lyricLineChanged: "lyric-line-changed",
+} as const;
+
+export interface EventPayloads {
+  "window-visibility-changed": WindowVisibilityChanged;
+  "main-window-visibility": boolean;
+  "main-close-requested": null;
+  "window-pin-changed": WindowPinChanged;
+  "window-always-on-top-changed": WindowPinChanged;
+  "window-zoom-changed": WindowZoomChanged;
+  "window-prewarmed": WindowPrewarmed;
+  "window-lifecycle": WindowLifecycle;
+  "shared-state-changed": SharedStateChanged;
+  "window-rpc-request": WindowRpcRequest;
+  "window-unresponsive": WindowUnresponsive;
+  "app-will-quit": AppWillQuit;
+  "system-idle": IdleChanged;
+  "system-active": IdleChanged;
+  "system-theme-changed": SystemTheme;
+  "accent-color-changed": AccentColor;
+  "desktop-lyrics-snapped": LyricsSnapped;
+  "desktop-lyrics-hover": LyricsHover;
+  "titlebar-style-changed": TitlebarStyleChanged;
+  "desktop-lyrics-autohidden": boolean;
+  "desktop-lyrics-orientation-clamped": OrientationClamped;
+  "tray-popup-opened": TrayNowPlaying | null;
+  "tray-menu-play-pause": null;
+  "tray-menu-next-track": null;
+  "tray-menu-prev-track": null;
+  "thumbbar-prev": null;
+  "thumbbar-play-pause": null;
+  "thumbbar-next": null;
+  "eq-changed": EqState;
+  "second-instance": SecondInstance;
+  "files-opened": FilesOpened;
+  "notification-action": NotificationActionPressed;
+  "system-volume-changed": SystemVolume;
+  "lyric-line-changed": LyricLineChange;
+}
+
+export function createWindow(label: string): Promise<void> {
+  return invoke<void>("create_window", { label });
+}
+
+export function createWindowWithOverrides(label: string, overrides: WindowConfigOverrides): Promise<void> {
+  return invoke<void>("create_window_with_overrides", { label, overrides });
+}
+
+export function createCustomWindow(config: WindowConfig): Promise<void> {
+  return invoke<void>("create_custom_window", { config });
+}
+
+export function registerWindowPreset(config: WindowConfig): Promise<void> {
+  return invoke<void>("register_window_preset", { config });
+}
+
+export function removeWindowPreset(label: string): Promise<boolean> {
+  return invoke<boolean>("remove_window_preset", { label });
+}
+
+export function listWindowPresets(): Promise<WindowConfig[]> {
+  return invoke<WindowConfig[]>("list_window_presets");
+}
+
+export function createWindowWithPayload(label: string, payload: unknown): Promise<void> {
+  return invoke<void>("create_window_with_payload", { label, payload });
+}
+
+export function showWindow(label: string): Promise<void> {
+  return invoke<void>("show_window", { label });
+}
+
+export function hideWindow(label: string): Promise<void> {
+  return invoke<void>("hide_window", { label });
+}
+
+export function closeManagedWindow(label: string): Promise<void> {
+  return invoke<void>("close_managed_window", { label });
+}
+
+export function toggleWindow(label: string): Promise<void> {
+  return invoke<void>("toggle_window", { label });
+}
+
+export function focusWindow(label: string): Promise<void> {
+  return invoke<void>("focus_window", { label });
+}
+
+export function maximizeWindow(label: string): Promise<void> {
+  return invoke<void>("maximize_window", { label });
+}
+
+export function unmaximizeWindow(label: string): Promise<void> {
+  return invoke<void>("unmaximize_window", { label });
+}
+
+export function minimizeWindow(label: string): Promise<void> {
+  return invoke<void>("minimize_window", { label });
+}
+
+export function toggleMaximize(label: string): Promise<boolean> {
+  return invoke<boolean>("toggle_maximize", { label });
+}
+
+export function getWindowState(label: string): Promise<WindowState> {
+  return invoke<WindowState>("get_window_state", { label });
+}
+
+export function getAllWindowStates(): Promise<Record<string, WindowState>> {
+  return invoke<Record<string, WindowState>>("get_all_window_states");
+}
+
+export function listWindows(): Promise<string[]> {
+  return invoke<string[]>("list_windows");
+}
+
+export function subscribeWindowEvents(): Promise<string[]> {
+  return invoke<string[]>("subscribe_window_events");
+}
+
+export function openWindowDevtools(label: string): Promise<void> {
+  return invoke<void>("open_window_devtools", { label });
+}
+
+export function showWindowAtPosition(label: string, x: number, y: number): Promise<void> {
+  return invoke<void>("show_window_at_position", { label, x, y });
+}
+
+export function setWindowEffectColor(label: string, r: number, g: number, b: number, a: number): Promise<void> {
+  return invoke<void>("set_window_effect_color", { label, r, g, b, a });
+}
+
+export function setIgnoreCursorEvents(label: string, ignore: boolean): Promise<void> {
+  return invoke<void>("set_ignore_cursor_events", { label, ignore });
+}
+
+export function startNativeDrag(label: string): Promise<void> {
+  return invoke<void>("start_native_drag", { label });
+}
+
+export function getTitlebarStyle(label: string): Promise<TitlebarStyle> {
+  return invoke<TitlebarStyle>("get_titlebar_style", { label });
+}
+
+export function setTitlebarStyle(label: string, style: TitlebarStyle): Promise<void> {
+  return invoke<void>("set_titlebar_style", { label, style });
+}
+
+export function startNativeResize(label: string, direction: ResizeEdge): Promise<void> {
+  return invoke<void>("start_native_resize", { label, direction });
+}
+
+export function resizeWindow(label: string, width: number, height: number): Promise<void> {
+  return invoke<void>("resize_window", { label, width, height });
+}
+
+export function quitApp(force?: boolean | null): Promise<void> {
+  return invoke<void>("quit_app", { force });
+}
+
+export function flushWindowState(): Promise<void> {
+  return invoke<void>("flush_window_state");
+}
+
+export function quitAck(label: string): Promise<void> {
+  return invoke<void>("quit_ack", { label });
+}
+
+export function getCursorPosition(): Promise<[number, number]> {
+  return invoke<[number, number]>("get_cursor_position");
+}
+
+export function getWindowBounds(label: string): Promise<[number, number, number, number]> {
+  return invoke<[number, number, number, number]>("get_window_bounds", { label });
+}
+
+export function ackCloseRequest(): Promise<void> {
+  return invoke<void>("ack_close_request");
+}
+
+export function setAlwaysOnTop(label: string, onTop: boolean): Promise<void> {
+  return invoke<void>("set_always_on_top", { label, onTop });
+}
+
+export function getAlwaysOnTop(label: string): Promise<boolean> {
+  return invoke<boolean>("get_always_on_top", { label });
+}
+
+export function setContentProtected(label: string, enabled: boolean): Promise<void> {
+  return invoke<void>("set_content_protected", { label, enabled });
+}
+
+export function listMonitors(): Promise<MonitorInfo[]> {
+  return invoke<MonitorInfo[]>("list_monitors");
+}
+
+export function moveWindowToMonitor(label: string, monitorName: string, anchor: MonitorAnchor): Promise<ScreenPoint> {
+  return invoke<ScreenPoint>("move_window_to_monitor", { label, monitorName, anchor });
+}
+
+export function getWindowInfo(label: string): Promise<WindowInfo> {
+  return invoke<WindowInfo>("get_window_info", { label });
+}
+
+export function showWindowAtLogicalPosition(label: string, x: number, y: number, monitor?: string | null): Promise<void> {
+  return invoke<void>("show_window_at_logical_position", { label, x, y, monitor });
+}
+
+export function setWindowLogicalPosition(label: string, x: number, y: number, monitor?: string | null): Promise<void> {
+  return invoke<void>("set_window_logical_position", { label, x, y, monitor });
+}
+
+export function getPositioningConventions(): Promise<PositioningConvention[]> {
+  return invoke<PositioningConvention[]>("get_positioning_conventions");
+}
+
+export function ensureWindowOnScreen(label: string): Promise<boolean> {
+  return invoke<boolean>("ensure_window_on_screen", { label });
+}
+
+export function notifyMainWindowReady(): Promise<void> {
+  return invoke<void>("notify_main_window_ready");
+}
+
+export function notifyFirstPaint(label: string): Promise<void> {
+  return invoke<void>("notify_first_paint", { label });
+}
+
+export function setWebviewZoom(label: string, factor: number): Promise<number> {
+  return invoke<number>("set_webview_zoom", { label, factor });
+}
+
+export function getWebviewZoom(label: string): Promise<number> {
+  return invoke<number>("get_webview_zoom", { label });
+}
+
+export function setWindowOpacity(label: string, opacity: number): Promise<number> {
+  return invoke<number>("set_window_opacity", { label, opacity });
+}
+
+export function getWindowOpacity(label: string): Promise<number> {
+  return invoke<number>("get_window_opacity", { label });
+}
+
+export function setExclusivePair(labelA: string, labelB: string, enabled: boolean): Promise<void> {
+  return invoke<void>("set_exclusive_pair", { labelA, labelB, enabled });
+}
+
+export function switchToMiniPlayer(): Promise<void> {
+  return invoke<void>("switch_to_mini_player");
+}
+
+export function reopenLastClosedWindow(): Promise<string | null> {
+  return invoke<string | null>("reopen_last_closed_window");
+}
+
+export function listRecentlyClosedWindows(): Promise<ClosedWindow[]> {
+  return invoke<ClosedWindow[]>("list_recently_closed_windows");
+}
+
+export function captureWindow(label: string, region?: CaptureRegion | null): Promise<WindowCapture> {
+  return invoke<WindowCapture>("capture_window", { label, region });
+}
+
+export function updateCurrentRoute(route: string): Promise<void> {
+  return invoke<void>("update_current_route", { route });
+}
+
+export function recoverMainWindow(): Promise<void> {
+  return invoke<void>("recover_main_window");
+}
+
+export function restartApp(): Promise<void> {
+  return invoke<void>("restart_app");
+}
+
+export function getStartupMode(): Promise<StartupMode> {
+  return invoke<StartupMode>("get_startup_mode");
+}
+
+export function getAutostart(): Promise<AutostartStatus> {
+  return invoke<AutostartStatus>("get_autostart");
+}
+
+export function setAutostart(enabled: boolean, startHidden: boolean): Promise<AutostartStatus> {
+  return invoke<AutostartStatus>("set_autostart", { enabled, startHidden });
+}
+
+export function setKeepAwake(mode: KeepAwake): Promise<KeepAwake> {
+  return invoke<KeepAwake>("set_keep_awake", { mode });
+}
+
+export function getKeepAwake(): Promise<KeepAwake> {
+  return invoke<KeepAwake>("get_keep_awake");
+}
+
+export function getSystemIdleSeconds(): Promise<number> {
+  return invoke<number>("get_system_idle_seconds");
+}
+
+export function setIdleWatch(thresholdSecs: number): Promise<void> {
+  return invoke<void>("set_idle_watch", { thresholdSecs });
+}
+
+export function getSystemTheme(): Promise<SystemTheme> {
+  return invoke<SystemTheme>("get_system_theme");
+}
+
+export function getAccentColor(): Promise<AccentColor> {
+  return invoke<AccentColor>("get_accent_color");
+}
+
+export function setBackendLocale(tag: string): Promise<string> {
+  return invoke<string>("set_backend_locale", { tag });
+}
+
+export function getBackendLocale(): Promise<string> {
+  return invoke<string>("get_backend_locale");
+}
+
+export function isSafeMode(): Promise<boolean> {
+  return invoke<boolean>("is_safe_mode");
+}
+
+export function resetWindowState(): Promise<void> {
+  return invoke<void>("reset_window_state");
+}
+
+export function setWindowPosition(label: string, x: number, y: number): Promise<void> {
+  return invoke<void>("set_window_position", { label, x, y });
+}
+
+export function startMouseThrough(label: string, regions: HitRegion[]): Promise<void> {
+  return invoke<void>("start_mouse_through", { label, regions });
+}
+
+export function stopMouseThrough(label: string): Promise<void> {
+  return invoke<void>("stop_mouse_through", { label });
+}
+
+export function updateMouseThroughRegions(label: string, regions: HitRegion[]): Promise<void> {
+  return invoke<void>("update_mouse_through_regions", { label, regions });
+}
+
+export function setLyricsLocked(locked: boolean): Promise<void> {
+  return invoke<void>("set_lyrics_locked", { locked });
+}
+
+export function getLyricsOrientation(): Promise<LyricsOrientation> {
+  return invoke<LyricsOrientation>("get_lyrics_orientation");
+}
+
+export function setLyricsOrientation(orientation: LyricsOrientation): Promise<void> {
+  return invoke<void>("set_lyrics_orientation", { orientation });
+}
+
+export function getLyricsAutohideFullscreen(): Promise<boolean> {
+  return invoke<boolean>("get_lyrics_autohide_fullscreen");
+}
+
+export function setLyricsAutohideFullscreen(enabled: boolean): Promise<void> {
+  return invoke<void>("set_lyrics_autohide_fullscreen", { enabled });
+}
+
+export function setWindowPayload(label: string, payload: unknown, ttlSecs?: number | null): Promise<void> {
+  return invoke<void>("set_window_payload", { label, payload, ttlSecs });
+}
+
+export function takeWindowPayload(label: string): Promise<unknown> {
+  return invoke<unknown>("take_window_payload", { label });
+}
+
+export function peekWindowPayload(label: string): Promise<unknown> {
+  return invoke<unknown>("peek_window_payload", { label });
+}
+
+export function payloadCacheStats(): Promise<PayloadCacheStats> {
+  return invoke<PayloadCacheStats>("payload_cache_stats");
+}
+
+export function stateSet(key: string, value: unknown, persistent?: boolean | null): Promise<number> {
+  return invoke<number>("state_set", { key, value, persistent });
+}
+
+export function stateGet(key: string): Promise<SharedValue | null> {
+  return invoke<SharedValue | null>("state_get", { key });
+}
+
+export function stateDelete(key: string): Promise<boolean> {
+  return invoke<boolean>("state_delete", { key });
+}
+
+export function stateSnapshot(): Promise<Record<string, SharedValue>> {
+  return invoke<Record<string, SharedValue>>("state_snapshot");
+}
+
+export function windowRequest(targetLabel: string, channel: string, payload: unknown, timeoutMs?: number | null): Promise<unknown> {
+  return invoke<unknown>("window_request", { targetLabel, channel, payload, timeoutMs });
+}
+
+export function windowRespond(requestId: number, payload: unknown): Promise<void> {
+  return invoke<void>("window_respond", { requestId, payload });
+}
+
+export function emitOrQueue(label: string, event: string, payload: unknown): Promise<void> {
+  return invoke<void>("emit_or_queue", { label, event, payload });
+}
+
+export function windowReady(label: string): Promise<number> {
+  return invoke<number>("window_ready", { label });
+}
+
+export function clearEventQueue(label: string): Promise<number> {
+  return invoke<number>("clear_event_queue", { label });
+}
+
+export function windowHeartbeat(label: string): Promise<void> {
+  return invoke<void>("window_heartbeat", { label });
+}
+
+export function setWatchdogEnabled(label: string, enabled: boolean): Promise<void> {
+  return invoke<void>("set_watchdog_enabled", { label, enabled });
+}
+
+export function setTrayTooltip(text: string): Promise<void> {
+  return invoke<void>("set_tray_tooltip", { text });
+}
+
+export function updateTrayNowPlaying(title: string, artist: string, positionMs: number, durationMs: number, nextTitle?: string | null, album?: string | null, playing?: boolean | null): Promise<void> {
+  return invoke<void>("update_tray_now_playing", { title, artist, positionMs, durationMs, nextTitle, album, playing });
+}
+
+export function clearTrayNowPlaying(): Promise<void> {
+  return invoke<void>("clear_tray_now_playing");
+}
+
+export function setTrayPlaybackState(state: TrayPlaybackState): Promise<void> {
+  return invoke<void>("set_tray_playback_state", { state });
+}
+
+export function updateTrayPopupLayout(width: number, height: number): Promise<void> {
+  return invoke<void>("update_tray_popup_layout", { width, height });
+}
+
+export function setTrayMenuMode(mode: TrayMenuMode): Promise<TrayConfig> {
+  return invoke<TrayConfig>("set_tray_menu_mode", { mode });
+}
+
+export function rebuildTrayIcon(): Promise<void> {
+  return invoke<void>("rebuild_tray_icon");
+}
+
+export function dismissTrayPopup(): Promise<void> {
+  return invoke<void>("dismiss_tray_popup");
+}
+
+export function setTaskbarProgress(state: TaskbarProgressState, value: number): Promise<void> {
+  return invoke<void>("set_taskbar_progress", { state, value });
+}
+
+export function setThumbbarState(playing: boolean): Promise<void> {
+  return invoke<void>("set_thumbbar_state", { playing });
+}
+
+export function panicHide(mute: boolean): Promise<void> {
+  return invoke<void>("panic_hide", { mute });
+}
+
+export function panicRestore(): Promise<void> {
+  return invoke<void>("panic_restore");
+}
+
+export function takePendingOpen(): Promise<PendingOpen | null> {
+  return invoke<PendingOpen | null>("take_pending_open");
+}
+
+export function takeOpenedFiles(): Promise<FilesOpened | null> {
+  return invoke<FilesOpened | null>("take_opened_files");
+}
+
+export function pickMusicFiles(multiple: boolean, extensions?: string[] | null): Promise<string[] | null> {
+  return invoke<string[] | null>("pick_music_files", { multiple, extensions });
+}
+
+export function pickMusicFolder(): Promise<string | null> {
+  return invoke<string | null>("pick_music_folder");
+}
+
+export function pickSavePath(defaultName: string, extension: string): Promise<string | null> {
+  return invoke<string | null>("pick_save_path", { defaultName, extension });
+}
+
+export function notifyNowPlaying(title: string, artist: string, coverPath: string | null, actions: NotificationAction[]): Promise<boolean> {
+  return invoke<boolean>("notify_now_playing", { title, artist, coverPath, actions });
+}
+
+export function setNotificationsEnabled(enabled: boolean): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("set_notifications_enabled", { enabled });
+}
+
+export function getSystemVolume(): Promise<number> {
+  return invoke<number>("get_system_volume");
+}
+
+export function setSystemVolume(level: number): Promise<number> {
+  return invoke<number>("set_system_volume", { level });
+}
+
+export function getSystemMuted(): Promise<boolean> {
+  return invoke<boolean>("get_system_muted");
+}
+
+export function setSystemMuted(muted: boolean): Promise<void> {
+  return invoke<void>("set_system_muted", { muted });
+}
+
+export function showOsd(kind: string, payload: unknown, durationMs?: number | null): Promise<boolean> {
+  return invoke<boolean>("show_osd", { kind, payload, durationMs });
+}
+
+export function loadLyricSession(track_id: string, lines: LyricLine[], offset_ms?: number | null): Promise<void> {
+  return invoke<void>("load_lyric_session", { track_id, lines, offset_ms });
+}
+
+export function setLyricOffset(offset_ms: number): Promise<void> {
+  return invoke<void>("set_lyric_offset", { offset_ms });
+}
+
+export function clearLyricSession(): Promise<void> {
+  return invoke<void>("clear_lyric_session");
+}
+
+export function getCurrentLyricLine(): Promise<LyricLineChange | null> {
+  return invoke<LyricLineChange | null>("get_current_lyric_line");
+}
+
+export function hideOsd(): Promise<void> {
+  return invoke<void>("hide_osd");
+}
+
+export function exportSettingsBackup(destPath: string): Promise<void> {
+  return invoke<void>("export_settings_backup", { destPath });
+}
+
+export function resetSettings(section: string): Promise<void> {
+  return invoke<void>("reset_settings", { section });
+}
+
+export function getEqState(): Promise<EqState> {
+  return invoke<EqState>("get_eq_state");
+}
+
+export function setEqBand(index: number, band: EqBand): Promise<EqState> {
+  return invoke<EqState>("set_eq_band", { index, band });
+}
+
+export function setEqBands(bands: EqBand[]): Promise<EqState> {
+  return invoke<EqState>("set_eq_bands", { bands });
+}
+
+export function setEqBandCount(count: number): Promise<EqState> {
+  return invoke<EqState>("set_eq_band_count", { count });
+}
+
+export function setEqPreamp(gain: number): Promise<EqState> {
+  return invoke<EqState>("set_eq_preamp", { gain });
+}
+
+export function setEqEnabled(enabled: boolean): Promise<EqState> {
+  return invoke<EqState>("set_eq_enabled", { enabled });
+}
+
+export function saveEqPreset(name: string): Promise<EqState> {
+  return invoke<EqState>("save_eq_preset", { name });
+}
+
+export function loadEqPreset(name: string): Promise<EqState> {
+  return invoke<EqState>("load_eq_preset", { name });
+}
+
+export function deleteEqPreset(name: string): Promise<EqState> {
+  return invoke<EqState>("delete_eq_preset", { name });
+}
+
+export function listEqPresets(): Promise<EqPresetInfo[]> {
+  return invoke<EqPresetInfo[]>("list_eq_presets");
+}
+
+export function getOsdSettings(): Promise<OsdSettings> {
+  return invoke<OsdSettings>("get_osd_settings");
+}
+
+export function setOsdSettings(settings: OsdSettings): Promise<OsdSettings> {
+  return invoke<OsdSettings>("set_osd_settings", { settings });
+}
+
+export function getTrayConfig(): Promise<TrayConfig> {
+  return invoke<TrayConfig>("get_tray_config");
+}
+
+export function setTrayLeftClickAction(action: TrayClickAction): Promise<TrayConfig> {
+  return invoke<TrayConfig>("set_tray_left_click_action", { action });
+}
+
+export function setTrayDoubleClickAction(action: TrayClickAction): Promise<TrayConfig> {
+  return invoke<TrayConfig>("set_tray_double_click_action", { action });
+}
+
+export function getCloseBehavior(label?: string | null): Promise<CloseBehavior> {
+  return invoke<CloseBehavior>("get_close_behavior", { label });
+}
+
+export function setCloseBehavior(label: string | null, behavior: CloseBehavior): Promise<void> {
+  return invoke<void>("set_close_behavior", { label, behavior });
+}
+
+export function getPrewarmWindows(): Promise<boolean> {
+  return invoke<boolean>("get_prewarm_windows");
+}
+
+export function setPrewarmWindows(enabled: boolean): Promise<void> {
+  return invoke<void>("set_prewarm_windows", { enabled });
+}
+
+export function getSessionRestoreEnabled(): Promise<boolean> {
+  return invoke<boolean>("get_session_restore_enabled");
+}
+
+export function setSessionRestoreEnabled(enabled: boolean): Promise<void> {
+  return invoke<void>("set_session_restore_enabled", { enabled });
+}
+
+export function getLyricsSnap(): Promise<LyricsSnap> {
+  return invoke<LyricsSnap>("get_lyrics_snap");
+}
+
+export function setLyricsSnap(enabled: boolean, thresholdPx: number): Promise<LyricsSnap> {
+  return invoke<LyricsSnap>("set_lyrics_snap", { enabled, thresholdPx });
+}
+
+export function getQuitGraceMs(): Promise<number> {
+  return invoke<number>("get_quit_grace_ms");
+}
+
+export function setQuitGraceMs(graceMs: number): Promise<number> {
+  return invoke<number>("set_quit_grace_ms", { graceMs });
+}
+
+export function getPathSettings(): Promise<PathSettings> {
+  return invoke<PathSettings>("get_path_settings");
+}
+
+export function setPathSettings(settings: PathSettings): Promise<PathSettings> {
+  return invoke<PathSettings>("set_path_settings", { settings });
+}
+
+export function getCoverCacheSettings(): Promise<CoverCacheSettings> {
+  return invoke<CoverCacheSettings>("get_cover_cache_settings");
+}
+
+export function setCoverCacheSettings(settings: CoverCacheSettings): Promise<CoverCacheSettings> {
+  return invoke<CoverCacheSettings>("set_cover_cache_settings", { settings });
+}
+
+export function getDownloadSettings(): Promise<DownloadSettings> {
+  return invoke<DownloadSettings>("get_download_settings");
+}
+
+export function setDownloadSettings(settings: DownloadSettings): Promise<DownloadSettings> {
+  return invoke<DownloadSettings>("set_download_settings", { settings });
+}
+
+export function getPowerSettings(): Promise<PowerSettings> {
+  return invoke<PowerSettings>("get_power_settings");
+}
+
+export function setPowerSettings(settings: PowerSettings): Promise<PowerSettings> {
+  return invoke<PowerSettings>("set_power_settings", { settings });
+}
+
+export function getNotificationSettings(): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("get_notification_settings");
+}
+
+export function getHttpPolicy(): Promise<HttpPolicy> {
+  return invoke<HttpPolicy>("get_http_policy");
+}
+
+export function setHttpPolicy(policy: HttpPolicy): Promise<HttpPolicy> {
+  return invoke<HttpPolicy>("set_http_policy", { policy });
+}
+
+export function getApiEndpoints(): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("get_api_endpoints");
+}
+
+export function setApiEndpoint(source: string, url: string): Promise<string | null> {
+  return invoke<string | null>("set_api_endpoint", { source, url });
+}
@@ -0,0 +1,85 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** A cached API response. */
+export interface CacheEntry {
+  value: string;
+  etag: string | null;
+  /** Milliseconds since the epoch; `null` never expires. */
+  expiresAt: number | null;
+  /** Past `expiresAt`; only returned when `etag` can revalidate it. */
+  expired: boolean;
+}
+
+/** What was played, for the listening report. */
+export interface PlayDetails {
+  track?: string;
+  artist?: string;
+  album?: string;
+  /** "local", "netease", ... */
+  source?: string;
+}
+
+export interface PlayRecord extends Required<PlayDetails> {
+  songId: string;
+  playedAt: number;
+  /** Milliseconds actually listened. */
+  durationListened: number;
+}
+
+/** Inclusive `playedAt` bounds; omit either side for open-ended. */
+export interface PlayRange {
+  fromMs?: number;
+  toMs?: number;
+}
+
+export interface CacheStats {
+  cacheRows: number;
+  historyRows: number;
+  /** Database plus WAL, in bytes. */
+  fileBytes: number;
+}
+
+export async function cacheGet(key: string): Promise<CacheEntry | null> {
+  if (!isTauri()) return null;
+  return invoke<CacheEntry | null>("cache_get", { key });
+}
+
+/** Store `value` under `key`; without `ttlSecs` it never expires. */
+export async function cacheSet(
+  key: string,
+  value: string,
+  ttlSecs?: number,
+  etag?: string,
+): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("cache_set", { key, value, ttlSecs, etag });
+}
+
+/** Drop every entry whose key starts with `prefix`; returns how many went. */
+export async function cacheEvict(prefix: string): Promise<number> {
+  if (!isTauri()) return 0;
+  return invoke<number>("cache_evict", { prefix });
+}
+
+export async function getCacheStats(): Promise<CacheStats | null> {
+  if (!isTauri()) return null;
+  return invoke<CacheStats>("cache_stats");
+}
+
+/** Record one play; `details` names it in the listening report. */
+export async function historyAdd(
+  songId: string | number,
+  playedAt: number,
+  durationListened: number,
+  details?: PlayDetails,
+): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("history_add", { songId: String(songId), playedAt, durationListened, details });
+}
+
+/** Plays within `range`, newest first. */
+export async function historyQuery(range: PlayRange, limit: number): Promise<PlayRecord[]> {
+  if (!isTauri()) return [];
+  return invoke<PlayRecord[]>("history_query", { range, limit });
+}
@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** A DLNA / UPnP media renderer found on the LAN. */
+export interface CastTarget {
+  id: string;
+  name: string;
+  model: string | null;
+  location: string;
+  controlUrl: string;
+  renderingControlUrl: string | null;
+}
+
+export interface CastMetadata {
+  title: string;
+  artist?: string;
+  album?: string;
+  coverUrl?: string;
+  /** Defaults to `audio/mpeg`. */
+  mimeType?: string;
+}
+
+export type CastAction =
+  | { type: "play" }
+  | { type: "pause" }
+  | { type: "stop" }
+  | { type: "seek"; positionMs: number }
+  | { type: "setVolume"; volume: number };
+
+export interface CastStatus {
+  /** AVTransport state, e.g. `PLAYING`, `PAUSED_PLAYBACK`, `STOPPED`. */
+  transportState: string;
+  positionMs: number | null;
+  durationMs: number | null;
+  volume: number | null;
+}
+
+/** Error returned by the cast commands. */
+export interface CastError {
+  kind: "unknownTarget" | "invalidMedia" | "unreachable" | "rejected" | "failed";
+  message: string;
+}
+
+export async function discoverCastTargets(timeoutMs?: number): Promise<CastTarget[]> {
+  if (!isTauri()) return [];
+  return invoke<CastTarget[]>("discover_cast_targets", { timeoutMs: timeoutMs ?? null });
+}
+
+/**
+ * Play `mediaUrl` on a renderer. The renderer fetches the URL itself, so it
+ * must be reachable from the LAN; loopback URLs are rejected.
+ */
+export async function castToTarget(
+  targetId: string,
+  mediaUrl: string,
+  metadata: CastMetadata,
+): Promise<void> {
+  await invoke("cast_to_target", { targetId, mediaUrl, metadata });
+}
+
+export async function castControl(targetId: string, action: CastAction): Promise<void> {
+  await invoke("cast_control", { targetId, action });
+}
+
+export async function getCastState(targetId: string): Promise<CastStatus> {
+  return invoke<CastStatus>("get_cast_state", { targetId });
+}
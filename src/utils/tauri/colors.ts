@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type Rgb = [number, number, number];
+
+export interface PaletteColor {
+  rgb: Rgb;
+  /** Pixels of the 64×64 sample this color stands for. */
+  population: number;
+  /** White text reads better on this color than black. */
+  isDark: boolean;
+}
+
+export interface ColorPalette {
+  /** Most common first. */
+  colors: PaletteColor[];
+  /** The dominant color. */
+  accent: Rgb;
+  /** Text color for `accent` with a WCAG contrast of at least 4.5. */
+  foreground: Rgb;
+  contrast: number;
+}
+
+/** Error returned by `extract_colors`. */
+export interface ColorError {
+  kind: "invalidSource" | "download" | "invalidImage" | "io";
+  message: string;
+}
+
+/**
+ * Theme colors of a cover, computed natively so CDNs without CORS headers
+ * work. `source` is an http(s) URL, fetched through the cover cache, or a
+ * local path.
+ * @param count Palette size, 1–16; defaults to 5.
+ */
+export async function extractColors(source: string, count?: number): Promise<ColorPalette> {
+  return invoke<ColorPalette>("extract_colors", { source, count });
+}
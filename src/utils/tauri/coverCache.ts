@@ -0,0 +1,37 @@
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+export interface CoverCacheStats {
+  files: number;
+  bytes: number;
+  maxBytes: number;
+}
+
+/** Error returned by `get_cover` / `clear_cover_cache`. */
+export interface CoverCacheError {
+  kind: "invalidUrl" | "download" | "invalidImage" | "io";
+  message: string;
+}
+
+/**
+ * A webview URL for the cover at `url`, downloaded once and shared by every
+ * window. With `size` the cover is cropped and scaled to a square of that
+ * many pixels. A custom cover set for `scopeId` is returned instead when
+ * there is one. Outside Tauri the remote URL is returned unchanged.
+ */
+export async function getCover(url: string, size?: number, scopeId?: string): Promise<string> {
+  if (!isTauri()) return url;
+  const path = await invoke<string>("get_cover", { url, size, scopeId });
+  return convertFileSrc(path);
+}
+
+/** Delete every cached cover; resolves to the bytes freed. */
+export async function clearCoverCache(): Promise<number> {
+  if (!isTauri()) return 0;
+  return invoke<number>("clear_cover_cache");
+}
+
+export async function getCoverCacheStats(): Promise<CoverCacheStats | null> {
+  if (!isTauri()) return null;
+  return invoke<CoverCacheStats>("cover_cache_stats");
+}
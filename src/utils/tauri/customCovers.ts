@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** A user-chosen cover stored by the backend. */
+export interface CustomCover {
+  /** Track or album id the override applies to. */
+  scopeId: string;
+  path: string;
+  width: number;
+  height: number;
+  updatedMs: number;
+}
+
+/** Error returned by `set_custom_cover` / `clear_custom_cover`. */
+export interface CoverError {
+  kind: "invalidImage" | "tooLarge" | "invalidScope" | "io";
+  message: string;
+}
+
+/**
+ * Store an image as the cover for a track or album. The backend re-encodes
+ * it as a JPEG of at most 1200px; rejects with a `CoverError`.
+ */
+export async function setCustomCover(scopeId: string, image: Blob): Promise<CustomCover> {
+  const imageBytes = Array.from(new Uint8Array(await image.arrayBuffer()));
+  return invoke<CustomCover>("set_custom_cover", { scopeId, imageBytes });
+}
+
+export async function clearCustomCover(scopeId: string): Promise<boolean> {
+  if (!isTauri()) return false;
+  return invoke<boolean>("clear_custom_cover", { scopeId });
+}
+
+export async function listCustomCovers(): Promise<CustomCover[]> {
+  if (!isTauri()) return [];
+  return invoke<CustomCover[]>("list_custom_covers");
+}
+
+/** Override for a track or album, checked before fetching the remote cover. */
+export async function getCustomCover(scopeId: string): Promise<CustomCover | null> {
+  if (!isTauri()) return null;
+  return invoke<CustomCover | null>("get_custom_cover", { scopeId });
+}
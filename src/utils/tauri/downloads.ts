@@ -0,0 +1,73 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { isTauri } from "./windowManager";
+
+export type DownloadStatus = "queued" | "running" | "paused" | "completed" | "failed" | "cancelled";
+
+export interface DownloadTask {
+  id: number;
+  url: string;
+  destPath: string;
+  headers: Record<string, string>;
+  md5: string | null;
+  status: DownloadStatus;
+  bytes: number;
+  total: number | null;
+  error: string | null;
+  createdMs: number;
+}
+
+/** Sent at most every 500 ms per running task, and on every status change. */
+export interface DownloadProgress {
+  taskId: number;
+  status: DownloadStatus;
+  bytes: number;
+  total: number | null;
+  /** Bytes per second since the previous event. */
+  speed: number;
+}
+
+/** Error returned by the `download_*` commands. */
+export interface DownloadError {
+  kind: "invalidDestination" | "notFound" | "duplicate";
+  message: string;
+}
+
+/**
+ * Queue a download. `destPath` must be inside the download or export
+ * folder; with `md5` the finished file is checked against it. Resolves to
+ * the task id; rejects with a `DownloadError`.
+ */
+export async function downloadStart(
+  url: string,
+  destPath: string,
+  headers?: Record<string, string>,
+  md5?: string,
+): Promise<number> {
+  return invoke<number>("download_start", { url, destPath, headers, md5 });
+}
+
+export async function downloadPause(taskId: number): Promise<void> {
+  await invoke("download_pause", { taskId });
+}
+
+/** Queue a paused or failed download again; it continues where it stopped. */
+export async function downloadResume(taskId: number): Promise<void> {
+  await invoke("download_resume", { taskId });
+}
+
+/** Stop a download and delete its partial file. */
+export async function downloadCancel(taskId: number): Promise<void> {
+  await invoke("download_cancel", { taskId });
+}
+
+export async function downloadList(): Promise<DownloadTask[]> {
+  if (!isTauri()) return [];
+  return invoke<DownloadTask[]>("download_list");
+}
+
+export function onDownloadProgress(
+  handler: (progress: DownloadProgress) => void,
+): Promise<UnlistenFn> {
+  return listen<DownloadProgress>("download-progress", (event) => handler(event.payload));
+}
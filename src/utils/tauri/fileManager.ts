@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** Error returned by `reveal_in_file_manager` and `open_path`. */
+export interface FileManagerError {
+  kind: "notFound" | "notAllowed" | "failed";
+  message: string;
+}
+
+export function isFileManagerError(err: unknown): err is FileManagerError {
+  return typeof err === "object" && err !== null && "kind" in err && "message" in err;
+}
+
+/**
+ * Select a local / downloaded / exported file in Explorer, Finder or the
+ * Linux file manager. Only paths under library folders, the configured
+ * download / export dirs, the home folder or the app data dir are accepted;
+ * rejects with a `FileManagerError`.
+ */
+export async function revealInFileManager(path: string): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("reveal_in_file_manager", { path });
+}
+
+/** Open a folder (or the folder holding a file); same rules as `revealInFileManager`. */
+export async function openPath(path: string): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("open_path", { path });
+}
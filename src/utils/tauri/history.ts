@@ -0,0 +1,61 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import { onTaskProgress, type TaskProgress } from "./scheduler";
+import { isTauri } from "./windowManager";
+
+/** One play in a JSON export. Plays are recorded with `historyAdd`. */
+export interface HistoryRecord {
+  playedAtMs: number;
+  track: string;
+  artist: string;
+  album: string;
+  /** "local", "netease", ... */
+  source: string;
+  /** Time actually listened. */
+  durationMs: number;
+}
+
+export type HistoryFormat = "csv" | "json";
+
+/** Inclusive `playedAtMs` bounds; omit either side for open-ended. */
+export interface HistoryRange {
+  fromMs?: number;
+  toMs?: number;
+}
+
+export interface HistoryExportSummary {
+  path: string;
+  rowsWritten: number;
+}
+
+/** Error returned by `export_history`. */
+export interface HistoryExportError {
+  kind: "invalidDestination" | "busy" | "cancelled" | "io";
+  message: string;
+}
+
+/**
+ * Export the listening history. `destPath` must be inside the export /
+ * download folder; `anonymize` replaces track, artist and album names with
+ * hashes. Rejects with a `HistoryExportError`.
+ */
+export async function exportHistory(
+  format: HistoryFormat,
+  range: HistoryRange,
+  destPath: string,
+  anonymize = false,
+): Promise<HistoryExportSummary> {
+  return invoke<HistoryExportSummary>("export_history", { format, range, destPath, anonymize });
+}
+
+export async function cancelHistoryExport(): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("cancel_history_export");
+}
+
+/** Export progress; `done` and `total` count rows. */
+export function onHistoryExportProgress(
+  handler: (progress: TaskProgress) => void,
+): Promise<UnlistenFn> {
+  return onTaskProgress(handler, "history-export");
+}
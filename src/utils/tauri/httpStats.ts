@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+export type CircuitState = "closed" | "open" | "halfOpen";
+
+/** Per-host counters of the backend HTTP client (`http_util`). */
+export interface HttpHostStats {
+  successes: number;
+  failures: number;
+  retries: number;
+  rejectedOpen: number;
+  circuit: CircuitState | null;
+}
+
+export async function getHttpStats(): Promise<Record<string, HttpHostStats>> {
+  if (!isTauri()) return {};
+  return invoke<Record<string, HttpHostStats>>("get_http_stats");
+}
+
+/**
+ * Forward browser connectivity changes to the backend so its circuit
+ * breakers start fresh on the new network. Call once per app.
+ */
+export function watchNetworkChanges(): void {
+  if (!isTauri()) return;
+  const notify = () => {
+    invoke("notify_network_changed", { online: navigator.onLine }).catch(() => {});
+  };
+  window.addEventListener("online", notify);
+  window.addEventListener("offline", notify);
+}
@@ -4,11 +4,180 @@ import { isTauri } from "./windowManager";
 
 export { windowManager, isTauri, isWindowsTauri } from "./windowManager";
 export { isMobile, isMobileDevice } from "./mobile";
-export type { WindowConfig, WindowLabel, WindowState } from "./types";
+export type { ResizeEdge, WindowConfig, WindowError, WindowLabel, WindowState } from "./types";
 export { usePlayerBridge } from "./playerBridge";
+export {
+  revealInFileManager,
+  isFileManagerError,
+  type FileManagerError,
+} from "./fileManager";
+export {
+  getHttpStats,
+  watchNetworkChanges,
+  type HttpHostStats,
+  type CircuitState,
+} from "./httpStats";
+export {
+  apiFetch,
+  getCookies,
+  setCookie,
+  clearCookies,
+  type ApiResponse,
+  type ApiFetchOptions,
+  type CookieInfo,
+  type ApiClientError,
+} from "./apiClient";
+export {
+  discoverCastTargets,
+  castToTarget,
+  castControl,
+  getCastState,
+  type CastTarget,
+  type CastMetadata,
+  type CastAction,
+  type CastStatus,
+  type CastError,
+} from "./cast";
+export {
+  getSchedulerState,
+  runMaintenanceNow,
+  watchUserActivity,
+  onTaskProgress,
+  type SchedulerState,
+  type TaskProgress,
+} from "./scheduler";
+export {
+  setCustomCover,
+  clearCustomCover,
+  listCustomCovers,
+  getCustomCover,
+  type CustomCover,
+  type CoverError,
+} from "./customCovers";
+export {
+  getCover,
+  clearCoverCache,
+  getCoverCacheStats,
+  type CoverCacheStats,
+  type CoverCacheError,
+} from "./coverCache";
+export {
+  extractColors,
+  type Rgb,
+  type PaletteColor,
+  type ColorPalette,
+  type ColorError,
+} from "./colors";
+export {
+  createRemoteToken,
+  revokeRemoteToken,
+  listRemoteTokens,
+  type RemoteScope,
+  type RemoteTokenInfo,
+  type CreatedRemoteToken,
+} from "./remote";
+export {
+  exportHistory,
+  cancelHistoryExport,
+  onHistoryExportProgress,
+  type HistoryRecord,
+  type HistoryFormat,
+  type HistoryRange,
+  type HistoryExportSummary,
+  type HistoryExportError,
+} from "./history";
+export {
+  downloadStart,
+  downloadPause,
+  downloadResume,
+  downloadCancel,
+  downloadList,
+  onDownloadProgress,
+  type DownloadTask,
+  type DownloadStatus,
+  type DownloadProgress,
+  type DownloadError,
+} from "./downloads";
+export {
+  getDeviceCapabilities,
+  getRecommendedAudioConfig,
+  onDeviceFormatChanged,
+  type DeviceCapabilities,
+  type RecommendedAudioConfig,
+  type DeviceFormatChanged,
+  type MixFormat,
+} from "./audioDevices";
+export {
+  getApiEndpoints,
+  setApiEndpoint,
+  checkApiEndpoint,
+  getApiEndpointHealth,
+  onApiEndpointHealthChanged,
+  type ApiEndpointHealth,
+} from "./apiEndpoints";
+export { getRunContext, type RunContext, type MigrationFailure } from "./runContext";
+export {
+  replaceQueue,
+  applyQueueDiff,
+  getQueuePage,
+  getQueueMeta,
+  loadQueue,
+  diffQueue,
+  type QueueMeta,
+  type QueueOp,
+  type QueuePage,
+} from "./queueStore";
+export {
+  getStorageHealth,
+  runStorageMaintenance,
+  onStorageRepaired,
+  type DatabaseHealth,
+  type DatabaseStatus,
+} from "./storage";
+export {
+  cacheGet,
+  cacheSet,
+  cacheEvict,
+  getCacheStats,
+  historyAdd,
+  historyQuery,
+  type CacheEntry,
+  type CacheStats,
+  type PlayDetails,
+  type PlayRecord,
+  type PlayRange,
+} from "./cacheStore";
+export {
+  syncPlaybackClock,
+  getEstimatedPosition,
+  type EstimatedPosition,
+} from "./playbackClock";
+export {
+  decryptNcm,
+  onNcmDecryptProgress,
+  type NcmDecrypted,
+  type NcmDecryptProgress,
+  type NcmError,
+  type NcmMetadata,
+} from "./ncm";
+export { generateQr, type QrEcLevel, type QrOptions, type QrError } from "./qr";
+export { searchRank, type SearchMatch } from "./search";
+export {
+  parseLyrics,
+  exportLrc,
+  type LyricFormat,
+  type LyricWord,
+  type MergedLyricLine,
+  type LyricsError,
+  type LrcSongMeta,
+  type LrcExportError,
+} from "./lyrics";
+// Generated command/event bindings (see src-tauri/src/desktop/bindings.rs)
+export * as bindings from "./bindings";
 export {
   PLAYER_COMMUNICATION_EVENTS,
   PLAYER_CONTENT_WINDOW_LABELS,
+  PLAYER_SHARED_STATE_KEY,
   PLAYER_STATE_WINDOW_LABELS,
 } from "./playerCommunicationTypes";
 export type {
@@ -36,6 +205,8 @@ export {
   initializeNowPlayingControls,
   updateNowPlayingState,
   updateNowPlayingTimeline,
+  updateNowPlayingPlaybackState,
+  notifyNowPlayingSeek,
   updateNowPlayingPlayMode,
   setNowPlayingEnabled,
   clearNowPlayingControls,
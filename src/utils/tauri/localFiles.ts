@@ -0,0 +1,121 @@
+/**
+ * Things opened from outside the app: audio files (launch arguments, drops,
+ * "Open With") and `gmplayer://` links from a second launch.
+ */
+import { musicStore } from "@/store";
+import type { SongData } from "@/store/musicTypes";
+import { getSongTime } from "@/utils/timeTools";
+import { EVENTS, takeOpenedFiles, type FilesOpened, type OpenedFile } from "./bindings";
+import { decryptNcm } from "./ncm";
+
+const APP_LINK_ROUTES = new Set(["song", "playlist", "album", "artist"]);
+
+/** File a second launch asked to play, until its `files-opened` batch lands. */
+let pendingPlayPath: string | null = null;
+
+/**
+ * Local files share the queue with NetEase songs, whose ids are positive.
+ * A negative hash of the path keeps the two apart and stays stable, so
+ * opening the same file twice finds the existing queue entry.
+ */
+function localSongId(path: string): number {
+  let hash = 0;
+  for (let i = 0; i < path.length; i++) {
+    hash = (Math.imul(hash, 31) + path.charCodeAt(i)) | 0;
+  }
+  return -(Math.abs(hash) + 1);
+}
+
+function fileName(path: string): string {
+  const name = path.split(/[\\/]/).pop() ?? path;
+  return name.replace(/\.[^.]+$/, "") || name;
+}
+
+export function openedFileToSong(file: OpenedFile): SongData {
+  return {
+    id: localSongId(file.path),
+    name: file.title || fileName(file.path),
+    artist: [{ id: 0, name: file.artist || "" }],
+    album: { id: 0, name: "", picUrl: "" },
+    time: file.durationMs ? getSongTime(file.durationMs) : "",
+    fee: 0,
+    localPath: file.path,
+  };
+}
+
+/**
+ * An `.ncm` file decrypted into the app cache, tagged from its metadata
+ * chunk; other files as they are. `null` if decryption fails.
+ */
+async function playableFile(file: OpenedFile): Promise<OpenedFile | null> {
+  if (!/\.ncm$/i.test(file.path)) return file;
+  try {
+    const decrypted = await decryptNcm(file.path);
+    const metadata = decrypted.metadata;
+    return {
+      path: decrypted.audioPath,
+      title: metadata?.title || file.title,
+      artist: metadata?.artists.join(" / ") || file.artist,
+      durationMs: metadata?.durationMs ?? file.durationMs,
+    };
+  } catch (e) {
+    console.warn(`[LocalFiles] Failed to decrypt ${file.path}`, e);
+    return null;
+  }
+}
+
+/**
+ * Add opened files to the queue and play the one a second launch asked
+ * for, or else the first.
+ * Files already queued are not added twice.
+ */
+export async function enqueueOpenedFiles(batch: FilesOpened): Promise<void> {
+  if (batch.ignored > 0) {
+    console.info(`[LocalFiles] Ignored ${batch.ignored} non-audio path(s)`);
+  }
+  const requested = batch.files.findIndex((file) => file.path === pendingPlayPath);
+  if (requested >= 0) pendingPlayPath = null;
+  const files = await Promise.all(batch.files.map(playableFile));
+  const songs = files.map((file) => file && openedFileToSong(file));
+  const playable = songs.filter((song): song is SongData => song !== null);
+  if (playable.length === 0) return;
+  const music = musicStore();
+  music.appendSongsToPlaylists(playable);
+  music.addSongToPlaylists(songs[requested] ?? playable[0], true);
+}
+
+/**
+ * Play a file a second launch was started with. Its tags are still being
+ * read, so unless it is queued already it plays once its batch arrives.
+ */
+export function playOpenedPath(path: string): void {
+  const music = musicStore();
+  const queued = music.getPlaylists.find((song) => song.localPath === path);
+  if (queued) {
+    music.addSongToPlaylists(queued, true);
+  } else {
+    pendingPlayPath = path;
+  }
+}
+
+/** Route for a `gmplayer://<song|playlist|album|artist>/<id>` link. */
+export function appLinkRoute(url: string): string | null {
+  const match = /^gmplayer:\/\/([a-z]+)\/(\d+)\/?$/i.exec(url.trim());
+  if (!match) return null;
+  const kind = match[1].toLowerCase();
+  return APP_LINK_ROUTES.has(kind) ? `/${kind}?id=${match[2]}` : null;
+}
+
+/**
+ * Main window only: take the files opened before the frontend was
+ * listening, then follow `files-opened` for later launches and drops.
+ */
+export async function setupOpenedFiles(): Promise<void> {
+  const tauri = window.__TAURI__;
+  if (!tauri) return;
+  await tauri.event.listen<FilesOpened>(EVENTS.filesOpened, (event) => {
+    enqueueOpenedFiles(event.payload);
+  });
+  const held = await takeOpenedFiles().catch(() => null);
+  if (held) await enqueueOpenedFiles(held);
+}
@@ -0,0 +1,79 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `qrc` may be QQ Music's encrypted hex payload as returned by its API. */
+export type LyricFormat = "lrc" | "qrc" | "yrc";
+
+/** One word of a word-timed line; times are from the start of the track. */
+export interface LyricWord {
+  startMs: number;
+  durationMs: number;
+  text: string;
+}
+
+/**
+ * An original lyric line with its translation and romanization, if any.
+ * Line-timed (LRC) lines have no words and a `null` duration.
+ */
+export interface MergedLyricLine {
+  timeMs: number;
+  durationMs: number | null;
+  /** Empty for blank lines, which end the previous line. */
+  text: string;
+  words: LyricWord[];
+  translation: string | null;
+  roma: string | null;
+}
+
+/** Error returned by `parse_lyrics`. */
+export interface LyricsError {
+  kind: "decrypt" | "malformed" | "interrupted";
+  message: string;
+}
+
+/**
+ * Parse `original` and attach the LRC `translation` and `roma` lines that
+ * lie within 500 ms of each original line.
+ */
+export async function parseLyrics(
+  format: LyricFormat,
+  original: string,
+  translation?: string,
+  roma?: string,
+): Promise<MergedLyricLine[]> {
+  return invoke<MergedLyricLine[]>("parse_lyrics", { format, original, translation, roma });
+}
+
+/** Title, artist and album written to the `[ti:]`, `[ar:]` and `[al:]` tags. */
+export interface LrcSongMeta {
+  title?: string;
+  artist?: string;
+  album?: string;
+}
+
+/** Error returned by `export_lrc`. */
+export interface LrcExportError {
+  kind: "invalidDestination" | "alreadyExists" | "cancelled" | "io";
+  message: string;
+}
+
+/**
+ * Save `lines` as `Artist - Title.lrc` in `destDir`, or wherever the user
+ * picks in a save dialog when `destDir` is empty. Rejects with
+ * `alreadyExists` unless `overwrite` is set. Desktop only.
+ * @returns The path written.
+ */
+export async function exportLrc(
+  songMeta: LrcSongMeta,
+  lines: MergedLyricLine[],
+  destDir?: string,
+  includeTranslation = false,
+  overwrite = false,
+): Promise<string> {
+  return invoke<string>("export_lrc", {
+    songMeta,
+    lines,
+    destDir,
+    includeTranslation,
+    overwrite,
+  });
+}
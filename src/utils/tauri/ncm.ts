@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/** Song fields from an `.ncm` file's metadata chunk. */
+export interface NcmMetadata {
+  musicId?: number;
+  title: string;
+  artists: string[];
+  album: string;
+  /** "mp3" or "flac", as declared by the file. */
+  format?: string;
+  durationMs?: number;
+  bitrate?: number;
+}
+
+export interface NcmDecrypted {
+  audioPath: string;
+  format: string;
+  metadata?: NcmMetadata;
+  coverPath?: string;
+}
+
+export interface NcmDecryptProgress {
+  path: string;
+  bytesDone: number;
+  totalBytes: number;
+}
+
+/** Error returned by `decrypt_ncm`. */
+export interface NcmError {
+  kind: "notNcm" | "truncated" | "corrupt" | "io";
+  message: string;
+}
+
+/**
+ * Decrypt an `.ncm` file into the app cache and return the playable audio
+ * and embedded cover paths. Rejects with an `NcmError`.
+ */
+export async function decryptNcm(path: string): Promise<NcmDecrypted> {
+  return invoke<NcmDecrypted>("decrypt_ncm", { path });
+}
+
+/** Progress of `decryptNcm`, sent only for files over 8 MiB. */
+export function onNcmDecryptProgress(
+  handler: (progress: NcmDecryptProgress) => void,
+): Promise<UnlistenFn> {
+  return listen<NcmDecryptProgress>("ncm-decrypt-progress", (event) => handler(event.payload));
+}
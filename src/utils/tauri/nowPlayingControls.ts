@@ -40,7 +40,10 @@ export interface NowPlayingActionPayload {
     | "toggleShuffle"
     | "toggleRepeat"
     | "setRate"
-    | "setVolume";
+    | "setVolume"
+    // MPRIS only; the backend shows the main window or quits by itself.
+    | "raise"
+    | "quit";
   position?: number;
   rate?: number;
   volume?: number;
@@ -97,6 +100,28 @@ export function updateNowPlayingTimeline(
   });
 }
 
+/**
+ * Report play state and position (ms). The backend only re-pushes the OS
+ * timeline when its extrapolated position drifts by more than a second, so
+ * this is cheap to call often.
+ */
+export function updateNowPlayingPlaybackState(
+  isPlaying: boolean,
+  position: number,
+): Promise<void | undefined> {
+  return call("update_playback_state", {
+    playing: isPlaying,
+    positionMs: Math.max(0, Math.round(position)),
+  });
+}
+
+/**
+ * Push an explicit seek (ms) to the OS immediately.
+ */
+export function notifyNowPlayingSeek(position: number): Promise<void | undefined> {
+  return call("notify_seek", { positionMs: Math.max(0, Math.round(position)) });
+}
+
 export function updateNowPlayingPlayMode(
   req: NowPlayingPlayModeRequest,
 ): Promise<void | undefined> {
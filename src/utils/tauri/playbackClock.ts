@@ -0,0 +1,39 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+export interface EstimatedPosition {
+  positionMs: number;
+  playing: boolean;
+  rate: number;
+  sinceSyncMs: number;
+}
+
+/**
+ * Re-anchor the backend playback clock. Only needed on play, pause, seek,
+ * track or rate change; the backend extrapolates in between. Pass
+ * `seeked` for discontinuities so they are not logged as drift.
+ */
+export async function syncPlaybackClock(
+  positionMs: number,
+  playing: boolean,
+  rate?: number,
+  seeked?: boolean,
+): Promise<void> {
+  if (!isTauri()) return;
+  try {
+    await invoke("sync_playback_clock", {
+      positionMs: Math.max(0, Math.round(positionMs)),
+      playing,
+      rate,
+      seeked,
+    });
+  } catch (err) {
+    console.warn("[PlaybackClock] sync failed:", err);
+  }
+}
+
+/** The backend's current estimate, for debugging. */
+export async function getEstimatedPosition(): Promise<EstimatedPosition | null> {
+  if (!isTauri()) return null;
+  return invoke<EstimatedPosition | null>("get_estimated_position");
+}
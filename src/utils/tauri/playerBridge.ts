@@ -7,6 +7,7 @@ import {
   type PlayerStatePayload,
   type PlayerTimePayload,
 } from "./playerCommunicationTypes";
+import { EVENTS, type LyricLineChange } from "./bindings";
 
 // ── Payload Types ──────────────────────────────────────────────────────────
 
@@ -60,7 +61,6 @@ const defaultSettings: PlayerSettingsPayload = {
   },
 };
 
-const READY_RETRY_DELAYS = [150, 600, 1200, 2500, 5000] as const;
 /** Slave-local extrapolation tick. Anchors from the master are sparse
  * (discontinuities + 2s heartbeat); this keeps `currentTime` live for
  * consumers without their own RAF interpolation (e.g. MiniPlayer). */
@@ -80,6 +80,13 @@ function emitToMain(eventName: string, payload?: unknown) {
   getTauri()?.event.emitTo("main", eventName, payload).catch(noop);
 }
 
+/** Like `emitToMain`, but held by the backend until main has called `window_ready`. */
+function emitOrQueueToMain(eventName: string, payload: unknown) {
+  getTauri()
+    ?.core.invoke("emit_or_queue", { label: "main", event: eventName, payload })
+    .catch(noop);
+}
+
 // ── Composable ─────────────────────────────────────────────────────────────
 
 /**
@@ -252,6 +259,15 @@ export function usePlayerBridge() {
     );
     unlisteners.push(u5);
 
+    // Active line from the backend lyric session, which the master feeds.
+    // Between the master's sparse time anchors this keeps the line exact.
+    const u6 = await tauri.event.listen<LyricLineChange>(EVENTS.lyricLineChanged, (e) => {
+      const songId = state.songId ?? lastAcceptedTimeSongId;
+      if (songId === null || e.payload.trackId !== String(songId)) return;
+      lyricIndex.value = e.payload.index ?? -1;
+    });
+    unlisteners.push(u6);
+
     // Notify master that we're ready
     const routePath = window.location.hash || window.location.pathname;
     const windowLabel = routePath.includes("mini-player")
@@ -262,16 +278,11 @@ export function usePlayerBridge() {
           ? "taskbar-lyric"
           : "unknown";
 
-    const notifyMaster = () => {
-      emitToMain(PLAYER_COMMUNICATION_EVENTS.slaveReady, { label: windowLabel });
-    };
-
-    notifyMaster();
-    const retryTimers: number[] = [];
-    for (const delay of READY_RETRY_DELAYS) {
-      retryTimers.push(window.setTimeout(notifyMaster, delay));
-    }
-    unlisteners.push(() => retryTimers.forEach((timer) => window.clearTimeout(timer)));
+    // Listeners are in place: take what the master queued while we loaded,
+    // then say hello. If main is still loading itself, the backend holds the
+    // handshake until it is ready, so no retries are needed.
+    await tauri.core.invoke("window_ready", { label: windowLabel }).catch(noop);
+    emitOrQueueToMain(PLAYER_COMMUNICATION_EVENTS.slaveReady, { label: windowLabel });
   }
 
   function disconnect(): void {
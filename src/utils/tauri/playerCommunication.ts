@@ -1,10 +1,31 @@
 import { toRaw } from "vue";
 import { musicStore, settingStore, siteStore } from "@/store";
-import { getProcessedLyrics, type AMLLLine, type SongLyric } from "@/utils/LyricsProcessor";
+import {
+  getProcessedLyrics,
+  type AMLLLine,
+  type ParsedLrcLine,
+  type ParsedYrcLine,
+  type SongLyric,
+} from "@/utils/LyricsProcessor";
 import { windowManager, isTauri } from "./windowManager";
+import { setupOpenedFiles } from "./localFiles";
+import {
+  EVENTS,
+  clearLyricSession,
+  emitOrQueue,
+  loadLyricSession,
+  notifyNowPlaying,
+  setKeepAwake,
+  setLyricOffset,
+  stateSet,
+  windowReady,
+  type LyricLine,
+  type NotificationActionPressed,
+} from "./bindings";
 import {
   PLAYER_COMMUNICATION_EVENTS,
   PLAYER_CONTENT_WINDOW_LABELS,
+  PLAYER_SHARED_STATE_KEY,
   PLAYER_STATE_WINDOW_LABELS,
   type PlayerFullStatePayload,
   type PlayerLyricPayload,
@@ -16,6 +37,7 @@ import {
 export {
   PLAYER_COMMUNICATION_EVENTS,
   PLAYER_CONTENT_WINDOW_LABELS,
+  PLAYER_SHARED_STATE_KEY,
   PLAYER_STATE_WINDOW_LABELS,
 } from "./playerCommunicationTypes";
 
@@ -316,13 +338,58 @@ function syncTrayEffectColor() {
   windowManager.setWindowEffectColor("tray-popup", r, g, b, 200).catch(noop);
 }
 
+let keepingAwake = false;
+
+/** Hold off idle sleep while something is playing. */
+function syncKeepAwake(isPlaying: boolean) {
+  if (isPlaying === keepingAwake) return;
+  keepingAwake = isPlaying;
+  setKeepAwake(isPlaying ? "system" : "off").catch(noop);
+}
+
+let notified: Pick<PlayerStatePayload, "songId" | "isPlaying"> | null = null;
+
+/**
+ * Announce a new track, and re-issue the notification when play/pause
+ * flips so its button matches. The backend skips it while the main window
+ * is showing.
+ */
+function syncNowPlayingNotification(payload: PlayerStatePayload) {
+  if (!payload.songId || !payload.title) return;
+  if (notified?.songId === payload.songId && notified.isPlaying === payload.isPlaying) return;
+  notified = { songId: payload.songId, isPlaying: payload.isPlaying };
+  notifyNowPlaying(payload.title, payload.artist, payload.coverUrlLarge || null, [
+    "previous",
+    payload.isPlaying ? "pause" : "play",
+    "next",
+  ]).catch(noop);
+}
+
+/** Last value written to the shared "player" key, serialized. */
+let lastSharedPlayerState = "";
+
+/**
+ * Windows that open later (the tray popup) read the shared "player" key
+ * instead of asking us. The position is left out: it is stale at once and
+ * would rewrite the key, and notify every window, on each tick.
+ */
+function publishSharedPlayerState(payload: PlayerStatePayload) {
+  const { currentTime: _currentTime, ...shared } = payload;
+  const serialized = JSON.stringify(shared);
+  if (serialized === lastSharedPlayerState) return;
+  lastSharedPlayerState = serialized;
+  stateSet(PLAYER_SHARED_STATE_KEY, shared).catch(() => {
+    lastSharedPlayerState = "";
+  });
+}
+
 export function broadcastPlayerState() {
   if (!isTauri()) return;
-  emitToLabels(
-    PLAYER_COMMUNICATION_EVENTS.state,
-    buildPlayerStatePayload(),
-    PLAYER_STATE_WINDOW_LABELS,
-  );
+  const payload = buildPlayerStatePayload();
+  syncKeepAwake(payload.isPlaying);
+  syncNowPlayingNotification(payload);
+  emitToLabels(PLAYER_COMMUNICATION_EVENTS.state, payload, PLAYER_STATE_WINDOW_LABELS);
+  publishSharedPlayerState(payload);
   syncTrayEffectColor();
 }
 
@@ -365,9 +432,45 @@ export function broadcastPlayerTime(force = false) {
   emitToLabels(PLAYER_COMMUNICATION_EVENTS.time, buildPlayerTimePayload(), labels);
 }
 
+let lyricSessionSource: (ParsedLrcLine | ParsedYrcLine)[] | null = null;
+let lyricSessionOffset = 0;
+
+/**
+ * Hand the lines `playSongLyricIndex` walks (YRC when shown, else LRC) to
+ * the backend lyric session, whose `lyric-line-changed` events the slave
+ * windows follow. Offset-only changes just move the session's offset.
+ */
+function syncLyricSession(songId: number | null) {
+  const music = getMusic();
+  const setting = getSetting();
+  const songLyric = toRaw(music.songLyric) as SongLyric | null;
+  if (!songId || !songLyric) {
+    if (lyricSessionSource !== null) clearLyricSession().catch(noop);
+    lyricSessionSource = null;
+    return;
+  }
+  const source: (ParsedLrcLine | ParsedYrcLine)[] =
+    (!songLyric.hasYrc || !setting.showYrc ? songLyric.lrc : songLyric.yrc) ?? [];
+  const offsetMs = Math.round(setting.lyricTimeOffset ?? 0);
+  if (source === lyricSessionSource) {
+    if (offsetMs !== lyricSessionOffset) setLyricOffset(offsetMs).catch(noop);
+    lyricSessionOffset = offsetMs;
+    return;
+  }
+  lyricSessionSource = source;
+  lyricSessionOffset = offsetMs;
+  const lines: LyricLine[] = source.map((line) => ({
+    timeMs: Math.max(0, Math.round(line.time * 1000)),
+    text: "TextContent" in line ? line.TextContent : line.content,
+    translation: line.tran ?? null,
+  }));
+  loadLyricSession(String(songId), lines, offsetMs).catch(noop);
+}
+
 export function broadcastPlayerLyrics(force = false) {
   if (!isTauri()) return;
   const payload = buildPlayerLyricPayload(force);
+  syncLyricSession(payload?.songId ?? null);
   if (!payload) return;
   emitToLabels(PLAYER_COMMUNICATION_EVENTS.lyric, payload, PLAYER_CONTENT_WINDOW_LABELS);
 }
@@ -391,7 +494,9 @@ export function broadcastPlayerFullState(targetLabel: string) {
     settings: buildPlayerSettingsPayload(),
   };
 
-  getTauriEvent()?.emitTo(targetLabel, PLAYER_COMMUNICATION_EVENTS.fullState, payload).catch(noop);
+  // Queued until the slave has called `window_ready`, so a window that is
+  // still loading gets the snapshot as soon as it can listen.
+  emitOrQueue(targetLabel, PLAYER_COMMUNICATION_EVENTS.fullState, payload).catch(noop);
 }
 
 export async function setupMainPlayerCommunication(options: MainPlayerCommunicationOptions) {
@@ -414,8 +519,43 @@ export async function setupMainPlayerCommunication(options: MainPlayerCommunicat
     music.setPlaySongIndex("next");
   });
 
-  await tauri.event.listen("tray-popup-opened", () => {
-    broadcastPlayerState();
+  // Native tray menu (trayConfig.menuMode === "native")
+  await tauri.event.listen("tray-menu-play-pause", () => {
+    music.setPlayState(!music.getPlayState);
+  });
+
+  await tauri.event.listen("tray-menu-prev-track", () => {
+    music.setPlaySongIndex("prev");
+  });
+
+  await tauri.event.listen("tray-menu-next-track", () => {
+    music.setPlaySongIndex("next");
+  });
+
+  // Windows taskbar thumbnail toolbar
+  await tauri.event.listen("thumbbar-play-pause", () => {
+    music.setPlayState(!music.getPlayState);
+  });
+
+  await tauri.event.listen("thumbbar-prev", () => {
+    music.setPlaySongIndex("prev");
+  });
+
+  await tauri.event.listen("thumbbar-next", () => {
+    music.setPlaySongIndex("next");
+  });
+
+  // Buttons on the now-playing notification
+  await tauri.event.listen<NotificationActionPressed>(EVENTS.notificationAction, (event) => {
+    const { action } = event.payload;
+    if (action === "previous") music.setPlaySongIndex("prev");
+    else if (action === "next") music.setPlaySongIndex("next");
+    else music.setPlayState(action === "play");
+  });
+
+  // The backend resets effect tints to the theme default; redo the tray's.
+  await tauri.event.listen(EVENTS.systemThemeChanged, () => {
+    syncTrayEffectColor();
   });
 
   await tauri.event.listen("tray-cycle-play-mode", () => {
@@ -504,4 +644,9 @@ export async function setupMainPlayerCommunication(options: MainPlayerCommunicat
       broadcastPlayerFullState(label);
     }
   });
+
+  // Handshakes from slaves that opened before these listeners existed.
+  windowReady("main").catch(noop);
+
+  void setupOpenedFiles();
 }
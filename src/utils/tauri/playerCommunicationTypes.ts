@@ -6,7 +6,11 @@ export const PLAYER_CONTENT_WINDOW_LABELS = [
   "taskbar-lyric",
 ] as const;
 
-export const PLAYER_STATE_WINDOW_LABELS = [...PLAYER_CONTENT_WINDOW_LABELS, "tray-popup"] as const;
+/** The tray popup follows the shared "player" key instead. */
+export const PLAYER_STATE_WINDOW_LABELS = PLAYER_CONTENT_WINDOW_LABELS;
+
+/** Shared-state key the main window publishes its player state under. */
+export const PLAYER_SHARED_STATE_KEY = "player";
 
 export const PLAYER_COMMUNICATION_EVENTS = {
   state: "player-state-update",
@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type QrEcLevel = "L" | "M" | "Q" | "H";
+
+export interface QrOptions {
+  /** Canvas edge in pixels, 64–2048; defaults to 256. */
+  sizePx?: number;
+  /** `#rgb`, `#rrggbb` or `#rrggbbaa`; defaults to black. */
+  darkColor?: string;
+  /** Defaults to white. */
+  lightColor?: string;
+  /** Defaults to `M`; raised to at least `Q` when there is a logo. */
+  ecLevel?: QrEcLevel;
+  /** Local image laid over the center of the code. */
+  logoPath?: string;
+}
+
+/** Error returned by `generate_qr`. */
+export interface QrError {
+  kind: "tooLong" | "invalidColor" | "invalidLogo" | "encode";
+  message: string;
+}
+
+/** Render `text` as a QR code; resolves to a `data:image/png` URL for an `<img>`. */
+export async function generateQr(text: string, options: QrOptions = {}): Promise<string> {
+  return invoke<string>("generate_qr", { text, ...options });
+}
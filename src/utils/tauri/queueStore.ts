@@ -0,0 +1,109 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** Queues at least this long are kept in the backend instead of localStorage. */
+export const LARGE_QUEUE_THRESHOLD = 1_000;
+const PAGE_SIZE = 1_000;
+
+export interface QueueMeta {
+  length: number;
+  currentIndex: number | null;
+  hash: string;
+  revision: number;
+}
+
+export interface QueuePage<T = unknown> {
+  offset: number;
+  items: T[];
+  length: number;
+  revision: number;
+}
+
+export type QueueOpKind =
+  | { op: "insert"; index: number; items: unknown[] }
+  | { op: "remove"; index: number; count: number }
+  | { op: "move"; from: number; to: number }
+  | { op: "setCurrent"; index: number | null };
+
+/** `revision` is the queue revision after the op is applied. */
+export type QueueOp = QueueOpKind & { revision: number };
+
+export async function replaceQueue(
+  items: unknown[],
+  currentIndex: number | null,
+): Promise<QueueMeta | null> {
+  if (!isTauri()) return null;
+  return invoke<QueueMeta>("replace_queue", { items, currentIndex });
+}
+
+export async function applyQueueDiff(ops: QueueOp[]): Promise<QueueMeta | null> {
+  if (!isTauri()) return null;
+  return invoke<QueueMeta>("apply_queue_diff", { ops });
+}
+
+export async function getQueuePage<T = unknown>(
+  offset: number,
+  limit: number,
+): Promise<QueuePage<T> | null> {
+  if (!isTauri()) return null;
+  return invoke<QueuePage<T>>("get_queue_page", { offset, limit });
+}
+
+export async function getQueueMeta(): Promise<QueueMeta | null> {
+  if (!isTauri()) return null;
+  return invoke<QueueMeta>("get_queue_meta");
+}
+
+/** Read the whole persisted queue, page by page. */
+export async function loadQueue<T = unknown>(): Promise<T[]> {
+  const items: T[] = [];
+  for (;;) {
+    const page = await getQueuePage<T>(items.length, PAGE_SIZE);
+    if (!page || page.items.length === 0) return items;
+    items.push(...page.items);
+    if (items.length >= page.length) return items;
+  }
+}
+
+/**
+ * Describe `next` relative to `prev` as at most one insert, remove or move,
+ * comparing items by `key`. `[]` means no change; null means a bigger
+ * change, for which the caller should replace the whole queue.
+ */
+export function diffQueue<T>(
+  prev: readonly T[],
+  next: readonly T[],
+  key: (item: T) => unknown,
+): QueueOpKind[] | null {
+  let head = 0;
+  while (head < prev.length && head < next.length && key(prev[head]) === key(next[head])) head++;
+  if (head === prev.length && head === next.length) return [];
+  let tail = 0;
+  while (
+    tail < prev.length - head &&
+    tail < next.length - head &&
+    key(prev[prev.length - 1 - tail]) === key(next[next.length - 1 - tail])
+  ) {
+    tail++;
+  }
+  const removed = prev.length - head - tail;
+  const inserted = next.length - head - tail;
+
+  if (removed === 0) return [{ op: "insert", index: head, items: next.slice(head, head + inserted) }];
+  if (inserted === 0) return [{ op: "remove", index: head, count: removed }];
+  if (removed === inserted) {
+    const last = head + removed - 1;
+    // One item moved from the start of the window to its end, or back.
+    const same = (a: number, b: number, n: number) => {
+      for (let i = 0; i < n; i++) if (key(prev[a + i]) !== key(next[b + i])) return false;
+      return true;
+    };
+    if (key(prev[head]) === key(next[last]) && same(head + 1, head, removed - 1)) {
+      return [{ op: "move", from: head, to: last }];
+    }
+    if (key(prev[last]) === key(next[head]) && same(head, head + 1, removed - 1)) {
+      return [{ op: "move", from: last, to: head }];
+    }
+  }
+  return null;
+}
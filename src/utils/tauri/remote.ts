@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+/** Permission granted to a LAN remote API token. */
+export type RemoteScope = "playback" | "queue-read" | "queue-write" | "system";
+
+export interface RemoteTokenInfo {
+  id: string;
+  label: string;
+  scopes: RemoteScope[];
+  createdMs: number;
+  lastUsedMs: number | null;
+  lastOrigin: string | null;
+}
+
+/** Newly issued token; `token` is shown once and never returned again. */
+export interface CreatedRemoteToken {
+  token: string;
+  info: RemoteTokenInfo;
+}
+
+export async function createRemoteToken(
+  permissions: RemoteScope[],
+  label: string,
+): Promise<CreatedRemoteToken> {
+  return invoke<CreatedRemoteToken>("create_remote_token", { permissions, label });
+}
+
+export async function revokeRemoteToken(id: string): Promise<boolean> {
+  return invoke<boolean>("revoke_remote_token", { id });
+}
+
+export async function listRemoteTokens(): Promise<RemoteTokenInfo[]> {
+  if (!isTauri()) return [];
+  return invoke<RemoteTokenInfo[]>("list_remote_tokens");
+}
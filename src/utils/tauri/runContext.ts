@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import { isTauri } from "./windowManager";
+
+export interface MigrationFailure {
+  id: string;
+  error: string;
+}
+
+/** How this launch relates to previous installs (see `first_run.rs`). */
+export interface RunContext {
+  firstRun: boolean;
+  /** Previous version, or `"legacy"` for installs predating version tracking. */
+  upgradedFrom: string | null;
+  migrationPerformed: boolean;
+  failedMigrations: MigrationFailure[];
+}
+
+export async function getRunContext(): Promise<RunContext | null> {
+  if (!isTauri()) return null;
+  return invoke<RunContext>("get_run_context");
+}
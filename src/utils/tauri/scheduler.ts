@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { isTauri } from "./windowManager";
+
+/** Minimum gap between two `report_user_activity` calls. */
+const ACTIVITY_REPORT_INTERVAL_MS = 5_000;
+
+/** How far a scheduler task is, as reported on `task-progress`. */
+export interface TaskProgress {
+  name: string;
+  done: number;
+  /** `null` when the amount of work is not known up front. */
+  total: number | null;
+}
+
+export interface SchedulerState {
+  running: string | null;
+  paused: boolean;
+  queued: string[];
+  /** Last reported progress of tasks still running. */
+  progress: TaskProgress[];
+  idleMs: number;
+  mainWindowHidden: boolean;
+  onBattery: boolean | null;
+  lowPowerMode: boolean;
+  blocked: "userActive" | "onBattery" | null;
+  forced: boolean;
+}
+
+export async function getSchedulerState(): Promise<SchedulerState | null> {
+  if (!isTauri()) return null;
+  return invoke<SchedulerState>("get_scheduler_state");
+}
+
+/** Follow progress reports; pass `name` to hear from one task only. */
+export function onTaskProgress(
+  handler: (progress: TaskProgress) => void,
+  name?: string,
+): Promise<UnlistenFn> {
+  return listen<TaskProgress>("task-progress", ({ payload }) => {
+    if (name === undefined || payload.name === name) handler(payload);
+  });
+}
+
+/** Run deferred maintenance now, ignoring idle and battery conditions. */
+export async function runMaintenanceNow(): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("run_maintenance_now");
+}
+
+/**
+ * Report pointer / keyboard / wheel input to the backend scheduler so
+ * maintenance work pauses while the user is interacting. Call once per window.
+ */
+export function watchUserActivity(): void {
+  if (!isTauri()) return;
+  let lastReport = 0;
+  const report = () => {
+    const now = Date.now();
+    if (now - lastReport < ACTIVITY_REPORT_INTERVAL_MS) return;
+    lastReport = now;
+    invoke("report_user_activity").catch(() => {});
+  };
+  for (const type of ["pointerdown", "keydown", "wheel"]) {
+    window.addEventListener(type, report, { passive: true, capture: true });
+  }
+}
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** One ranked candidate returned by `searchRank`. */
+export interface SearchMatch {
+  /** Position in the candidate array. */
+  index: number;
+  score: number;
+  /**
+   * Matched `[start, end)` spans of the candidate, in the same units as
+   * `String.prototype.slice`. Pinyin matches highlight the characters
+   * they were read from.
+   */
+  matchedRanges: [number, number][];
+}
+
+/**
+ * Fuzzy-rank `candidates` against `query`, best first. Chinese text also
+ * matches by full pinyin (`zhoujielun`) and initials (`zjl`). An empty
+ * query returns the first `limit` candidates in order.
+ * @param limit Maximum number of matches; defaults to 100.
+ */
+export async function searchRank(
+  query: string,
+  candidates: string[],
+  limit?: number,
+): Promise<SearchMatch[]> {
+  return invoke<SearchMatch[]>("search_rank", { query, candidates, limit });
+}
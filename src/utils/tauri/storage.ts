@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { isTauri } from "./windowManager";
+
+export type DatabaseStatus = "pending" | "ok" | "repaired" | "reset" | "failed";
+
+export interface DatabaseHealth {
+  name: string;
+  status: DatabaseStatus;
+  detail: string | null;
+  checkedAtMs: number | null;
+  maintainedAtMs: number | null;
+}
+
+/** Result of the startup integrity check for each SQLite database. */
+export async function getStorageHealth(): Promise<DatabaseHealth[]> {
+  if (!isTauri()) return [];
+  return invoke<DatabaseHealth[]>("get_storage_health");
+}
+
+/** Queue a vacuum/reindex pass; it runs once the user is idle. */
+export async function runStorageMaintenance(): Promise<void> {
+  if (!isTauri()) return;
+  return invoke<void>("run_storage_maintenance");
+}
+
+/** Fired when a database failed its check and was repaired or reset. */
+export function onStorageRepaired(
+  handler: (health: DatabaseHealth) => void,
+): Promise<UnlistenFn> {
+  return listen<DatabaseHealth>("storage-repaired", (event) => handler(event.payload));
+}
@@ -11,6 +11,7 @@ export type WindowLabel =
   | "settings"
   | "about"
   | "tray-popup"
+  | "osd"
   | (string & {});
 
 /**
@@ -33,17 +34,182 @@ export interface WindowConfig {
   skipTaskbar?: boolean;
   center?: boolean;
   visible?: boolean;
+  focusable?: boolean;
   singleInstance?: boolean;
   closeableToTray?: boolean;
   useOverlayTitlebar?: boolean;
   trafficLightsInset?: [number, number];
+  windowEffect?: string;
+  shadow?: boolean;
+  additionalArgs?: string;
   parentLabel?: string;
+  prewarm?: boolean;
+  /** Keep the window out of screen capture (Windows, macOS). */
+  contentProtected?: boolean;
 }
 
+/** Preset fields to change in `create_window_with_overrides`. */
+export type WindowConfigOverrides = Partial<Omit<WindowConfig, "label">>;
+
 /**
- * Window state returned from get_window_state.
+ * Window state returned from get_window_state. Every flag is false when the
+ * window does not exist.
  */
 export interface WindowState {
   exists: boolean;
   visible: boolean;
+  focused: boolean;
+  minimized: boolean;
+  maximized: boolean;
+  fullscreen: boolean;
+  /** Hidden from screen capture (Windows, macOS). */
+  contentProtected: boolean;
+}
+
+/** A connected monitor; every value is in physical pixels. */
+export interface MonitorInfo {
+  name: string | null;
+  position: { x: number; y: number };
+  size: { width: number; height: number };
+  workArea: { x: number; y: number; width: number; height: number };
+  scaleFactor: number;
+  isPrimary: boolean;
+}
+
+/** "remember" keeps the window's relative spot in the work area. */
+export type MonitorAnchor = "center" | "bottom-center" | "top-center" | "remember";
+
+export type TrayClickAction = "show-main" | "toggle-main" | "play-pause" | "show-popup" | "nothing";
+
+/** "none" removes the bar; "indeterminate" is for buffering. */
+export type TaskbarProgressState = "none" | "normal" | "paused" | "indeterminate";
+
+/** "native" replaces the webview popup with an OS menu on the tray icon. */
+export type TrayMenuMode = "popup" | "native";
+
+export interface TrayConfig {
+  leftClickAction: TrayClickAction;
+  doubleClickAction: TrayClickAction;
+  menuMode: TrayMenuMode;
+}
+
+/** "overlay" keeps native caption buttons over the webview (macOS, Windows). */
+export type TitlebarStyle = "overlay" | "hidden";
+
+/** Edge or corner for `startNativeResize`. */
+export type ResizeEdge =
+  | "north"
+  | "north-east"
+  | "east"
+  | "south-east"
+  | "south"
+  | "south-west"
+  | "west"
+  | "north-west";
+
+/** Rejection of the native drag/resize commands. */
+export interface WindowError {
+  kind: "notFound" | "failed" | "unsupported";
+  message: string;
+}
+
+/** Payload of `window-lifecycle`; `data` is set for `moved` and `resized`. */
+export interface WindowLifecycle {
+  label: string;
+  kind:
+    | "created"
+    | "destroyed"
+    | "focused"
+    | "blurred"
+    | "moved"
+    | "resized"
+    | "close-requested";
+  /** Physical pixels: outer position or inner size. */
+  data: { x: number; y: number } | { width: number; height: number } | null;
+}
+
+/** Session handoff stored for "main" before its webview is recreated. */
+export interface RecoveryPayload {
+  kind: "recovery";
+  version: number;
+  appVersion: string;
+  route: string | null;
+  playback: {
+    state: string;
+    musicId: string;
+    position: number;
+    duration: number;
+    volume: number;
+  } | null;
+  volume: number | null;
+  lyricOffset: { trackId: string; offsetMs: number } | null;
+  /** The queue itself stays in the backend queue store. */
+  queue: { hash: string; currentIndex: number | null } | null;
+}
+
+/** Logical-pixel crop rectangle, relative to the window's client area. */
+export interface CaptureRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/** PNG bytes inline, or a temp file path for large captures. */
+export type WindowCapture =
+  | { kind: "png"; width: number; height: number; bytes: number[] }
+  | { kind: "file"; width: number; height: number; path: string };
+
+/** A window closed by the user that can be reopened (newest first). */
+export interface ClosedWindow {
+  label: string;
+  /** Logical outer position and inner size at close time. */
+  bounds: { x: number; y: number; width: number; height: number } | null;
+  visible: boolean;
+  closedMs: number;
+}
+
+/** `platformDependent`: points on macOS, pixels elsewhere. */
+export type CoordinateUnits = "physical" | "logical" | "platformDependent";
+
+export interface PositioningConvention {
+  command: string;
+  input: CoordinateUnits | null;
+  output: CoordinateUnits | null;
+  note: string;
+}
+
+/** An audio file or `gmplayer://` link a second launch asked to open. */
+export type PendingOpen = { kind: "file"; path: string } | { kind: "url"; url: string };
+
+/** An audio file opened from outside the app, with its tags if readable. */
+export interface OpenedFile {
+  path: string;
+  title: string | null;
+  artist: string | null;
+  durationMs: number | null;
+}
+
+/** Payload of `files-opened`; `ignored` counts dropped non-audio files. */
+export interface FilesOpened {
+  files: OpenedFile[];
+  ignored: number;
+}
+
+/** Payload of `second-instance`: the later launch's arguments and cwd. */
+export interface SecondInstance {
+  args: string[];
+  cwd: string;
+}
+
+/** How the app was launched: `--hidden`, `--window <label>` or neither. */
+export type StartupMode =
+  | { mode: "normal" }
+  | { mode: "hidden" }
+  | { mode: "window"; label: WindowLabel };
+
+export interface WindowInfo {
+  label: string;
+  visible: boolean;
+  alwaysOnTop: boolean;
 }
@@ -1,4 +1,32 @@
-import type { WindowConfig, WindowLabel, WindowState } from "./types";
+import type {
+  CaptureRegion,
+  ClosedWindow,
+  FilesOpened,
+  MonitorAnchor,
+  MonitorInfo,
+  PendingOpen,
+  PositioningConvention,
+  RecoveryPayload,
+  ResizeEdge,
+  TitlebarStyle,
+  TrayClickAction,
+  TrayConfig,
+  TrayMenuMode,
+  TaskbarProgressState,
+  StartupMode,
+  WindowCapture,
+  WindowConfig,
+  WindowConfigOverrides,
+  WindowInfo,
+  WindowLabel,
+  WindowLifecycle,
+  WindowState,
+} from "./types";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import type { WindowRpcRequest } from "./bindings";
+
+/** Must match `RECOVERY_PAYLOAD_VERSION` in `window/recovery.rs`. */
+const RECOVERY_PAYLOAD_VERSION = 2;
 
 declare global {
   interface Window {
@@ -58,6 +86,17 @@ export const windowManager = {
     await invoke("create_window", { label });
   },
 
+  /**
+   * Create a window from a preset label with some fields changed, e.g. the
+   * settings window at another size or desktop lyrics initially hidden.
+   */
+  async createWindowWithOverrides(
+    label: WindowLabel,
+    overrides: WindowConfigOverrides,
+  ): Promise<void> {
+    await invoke("create_window_with_overrides", { label, overrides });
+  },
+
   /**
    * Create a window with a fully custom configuration.
    */
@@ -65,6 +104,28 @@ export const windowManager = {
     await invoke("create_custom_window", { config });
   },
 
+  /**
+   * Save a preset under its label so `createWindow(label)` can open it.
+   * Built-in labels are reserved.
+   */
+  async registerWindowPreset(config: WindowConfig): Promise<void> {
+    await invoke("register_window_preset", { config });
+  },
+
+  /**
+   * Delete a registered preset. Resolves to whether it existed.
+   */
+  async removeWindowPreset(label: string): Promise<boolean | null> {
+    return invoke<boolean>("remove_window_preset", { label });
+  },
+
+  /**
+   * Presets registered with `registerWindowPreset`; built-ins are not listed.
+   */
+  async listWindowPresets(): Promise<WindowConfig[] | null> {
+    return invoke<WindowConfig[]>("list_window_presets");
+  },
+
   /**
    * Create a window from a preset with an attached payload.
    */
@@ -107,6 +168,34 @@ export const windowManager = {
     await invoke("focus_window", { label });
   },
 
+  /**
+   * Maximize a window.
+   */
+  async maximizeWindow(label: WindowLabel): Promise<void> {
+    await invoke("maximize_window", { label });
+  },
+
+  /**
+   * Restore a maximized window.
+   */
+  async unmaximizeWindow(label: WindowLabel): Promise<void> {
+    await invoke("unmaximize_window", { label });
+  },
+
+  /**
+   * Minimize a window.
+   */
+  async minimizeWindow(label: WindowLabel): Promise<void> {
+    await invoke("minimize_window", { label });
+  },
+
+  /**
+   * Maximize or restore a window. Resolves to the new maximized state.
+   */
+  async toggleMaximize(label: WindowLabel): Promise<boolean | null> {
+    return invoke<boolean>("toggle_maximize", { label });
+  },
+
   /**
    * Get the state (exists, visible) of a window.
    */
@@ -114,6 +203,115 @@ export const windowManager = {
     return invoke<WindowState>("get_window_state", { label });
   },
 
+  /**
+   * States of all open windows, keyed by label.
+   */
+  async getAllWindowStates(): Promise<Record<string, WindowState> | null> {
+    return invoke<Record<string, WindowState>>("get_all_window_states");
+  },
+
+  /**
+   * Get window details, including the tracked always-on-top state.
+   */
+  async getWindowInfo(label: WindowLabel): Promise<WindowInfo | null> {
+    return invoke<WindowInfo>("get_window_info", { label });
+  },
+
+  /**
+   * Pin or unpin a window. Emits `window-pin-changed` and
+   * `window-always-on-top-changed`; the choice is remembered across
+   * restarts for the main window and mini player.
+   */
+  async setAlwaysOnTop(label: WindowLabel, onTop: boolean): Promise<void> {
+    await invoke("set_always_on_top", { label, onTop });
+  },
+
+  /**
+   * Effective always-on-top value; for a closed preset window, the value it
+   * will open with.
+   */
+  async getAlwaysOnTop(label: WindowLabel): Promise<boolean | null> {
+    return invoke<boolean>("get_always_on_top", { label });
+  },
+
+  /**
+   * Connected monitors, in physical pixels.
+   */
+  async listMonitors(): Promise<MonitorInfo[] | null> {
+    return invoke<MonitorInfo[]>("list_monitors");
+  },
+
+  /**
+   * Send a window to another monitor, keeping its apparent size on
+   * mixed-DPI setups. Resolves to the final physical position.
+   */
+  async moveWindowToMonitor(
+    label: WindowLabel,
+    monitorName: string,
+    anchor: MonitorAnchor,
+  ): Promise<{ x: number; y: number } | null> {
+    return invoke<{ x: number; y: number }>("move_window_to_monitor", {
+      label,
+      monitorName,
+      anchor,
+    });
+  },
+
+  /**
+   * Move a window onto the primary monitor if it is mostly off-screen, e.g.
+   * after a display was disconnected. Resolves to whether it moved.
+   */
+  async ensureOnScreen(label: WindowLabel): Promise<boolean | null> {
+    return invoke<boolean>("ensure_window_on_screen", { label });
+  },
+
+  /**
+   * Capture a window as PNG, optionally cropped to a logical region.
+   * Fails for content-protected windows and on Linux.
+   */
+  async captureWindow(label: WindowLabel, region?: CaptureRegion): Promise<WindowCapture | null> {
+    return invoke<WindowCapture>("capture_window", { label, region: region ?? null });
+  },
+
+  /**
+   * Never show both windows at once: showing one hides the other, including
+   * from the tray. Disabling leaves current visibility unchanged.
+   */
+  async setExclusivePair(
+    labelA: WindowLabel,
+    labelB: WindowLabel,
+    enabled: boolean,
+  ): Promise<void | null> {
+    return invoke<void>("set_exclusive_pair", { labelA, labelB, enabled });
+  },
+
+  /**
+   * Show the mini player, then hide main. With main and the mini player
+   * set as an exclusive pair, main stays up until the mini player is shown.
+   */
+  async switchToMiniPlayer(): Promise<void | null> {
+    return invoke<void>("switch_to_mini_player");
+  },
+
+  /**
+   * Reopen the most recently closed window at its last position and size.
+   * Resolves to its label, or `null` if there is nothing to reopen.
+   */
+  async reopenLastClosedWindow(): Promise<string | null> {
+    return invoke<string | null>("reopen_last_closed_window");
+  },
+
+  async listRecentlyClosedWindows(): Promise<ClosedWindow[] | null> {
+    return invoke<ClosedWindow[]>("list_recently_closed_windows");
+  },
+
+  /**
+   * Launch mode; the main window stays hidden unless it is `normal`.
+   */
+  async getStartupMode(): Promise<StartupMode | null> {
+    return invoke<StartupMode>("get_startup_mode");
+  },
+
   /**
    * List all open window labels.
    */
@@ -130,10 +328,11 @@ export const windowManager = {
   },
 
   /**
-   * Store a payload for a window label.
+   * Store a payload for a window label. With `ttlSecs` the payload is
+   * dropped after that many seconds if no window has taken it.
    */
-  async setPayload(label: WindowLabel, payload: unknown): Promise<void> {
-    await invoke("set_window_payload", { label, payload });
+  async setPayload(label: WindowLabel, payload: unknown, ttlSecs?: number): Promise<void> {
+    await invoke("set_window_payload", { label, payload, ttlSecs });
   },
 
   /**
@@ -150,6 +349,71 @@ export const windowManager = {
     return invoke<T>("peek_window_payload", { label });
   },
 
+  /**
+   * Ask another window for something on `channel` and wait for its answer.
+   * Rejects with `{ kind: "notFound" }` straight away when the target is
+   * closed, hidden or minimized, and `{ kind: "timeout" }` when it does not
+   * answer within `timeoutMs` (5 s by default).
+   */
+  async request<T = unknown>(
+    target: WindowLabel,
+    channel: string,
+    payload: unknown = null,
+    timeoutMs?: number,
+  ): Promise<T | null> {
+    return invoke<T>("window_request", { targetLabel: target, channel, payload, timeoutMs });
+  },
+
+  /**
+   * Answer requests sent to this window on `channel`. A handler that
+   * throws sends no answer, so the requester times out. Listens on this
+   * webview only: a global listener would also hear requests addressed to
+   * other windows and race them to answer.
+   */
+  async onRequest(
+    channel: string,
+    handler: (payload: unknown, source: string) => unknown,
+  ): Promise<() => void> {
+    if (!isTauri()) return () => {};
+    const webview = getCurrentWebviewWindow();
+    return webview.listen<WindowRpcRequest>("window-rpc-request", async ({ payload: request }) => {
+      if (request.channel !== channel) return;
+      try {
+        const answer = await handler(request.payload, request.source);
+        await invoke("window_respond", { requestId: request.requestId, payload: answer ?? null });
+      } catch (error) {
+        console.warn(`[windowManager] Request on "${channel}" failed:`, error);
+      }
+    });
+  },
+
+  /**
+   * Take the recovery payload left for "main" by a webview recreation or
+   * `restart_app`. Other "main" payloads are left untouched.
+   */
+  async takeRecoveryPayload(): Promise<RecoveryPayload | null> {
+    const payload = await invoke<Partial<RecoveryPayload>>("peek_window_payload", {
+      label: "main",
+    });
+    if (payload?.kind !== "recovery") return null;
+    await invoke("take_window_payload", { label: "main" });
+    return payload.version === RECOVERY_PAYLOAD_VERSION ? (payload as RecoveryPayload) : null;
+  },
+
+  /**
+   * Report the main window's current route for recovery snapshots.
+   */
+  async updateCurrentRoute(route: string): Promise<void> {
+    await invoke("update_current_route", { route });
+  },
+
+  /**
+   * Restart the app, carrying the current session over.
+   */
+  async restartApp(): Promise<void> {
+    await invoke("restart_app");
+  },
+
   /**
    * Listen for tray play/pause events.
    */
@@ -172,14 +436,41 @@ export const windowManager = {
   },
 
   /**
-   * Quit the application (saves window state first).
+   * Quit the application. Windows first get `app-will-quit` and a grace
+   * period to wrap up; `force` skips that wait.
+   */
+  async quitApp(force = false): Promise<void> {
+    await invoke("quit_app", { force });
+  },
+
+  /**
+   * Run `cleanup` when the app is about to quit, then tell the backend
+   * this window is done. Quitting waits for that up to a grace period.
+   */
+  async onAppWillQuit(cleanup?: () => unknown): Promise<() => void> {
+    if (!isTauri()) return () => {};
+    const { getCurrentWindow } = await import("@tauri-apps/api/window");
+    const label = getCurrentWindow().label;
+    return listen("app-will-quit", async () => {
+      try {
+        await cleanup?.();
+      } finally {
+        await invoke("quit_ack", { label }).catch(() => {});
+      }
+    });
+  },
+
+  /**
+   * Save window geometry now instead of waiting for the debounced save.
+   * Call before anything that might take the app down.
    */
-  async quitApp(): Promise<void> {
-    await invoke("quit_app");
+  async flushWindowState(): Promise<void> {
+    await invoke("flush_window_state");
   },
 
   /**
    * Update the native window effect tint color (e.g. Acrylic on Windows).
+   * Works for custom windows too; rejects if the window has no effect.
    */
   async setWindowEffectColor(
     label: WindowLabel,
@@ -191,6 +482,39 @@ export const windowManager = {
     await invoke("set_window_effect_color", { label, r, g, b, a });
   },
 
+  /**
+   * Show a window at a logical position. The backend converts with the
+   * scale of `monitor` (a monitor name or "current"), or of the monitor
+   * containing the point — no devicePixelRatio math needed.
+   */
+  async showWindowAtLogicalPosition(
+    label: WindowLabel,
+    x: number,
+    y: number,
+    monitor?: string,
+  ): Promise<void> {
+    await invoke("show_window_at_logical_position", { label, x, y, monitor: monitor ?? null });
+  },
+
+  /**
+   * Move a window to a logical position (see `showWindowAtLogicalPosition`).
+   */
+  async setWindowLogicalPosition(
+    label: WindowLabel,
+    x: number,
+    y: number,
+    monitor?: string,
+  ): Promise<void> {
+    await invoke("set_window_logical_position", { label, x, y, monitor: monitor ?? null });
+  },
+
+  /**
+   * Which commands take or return physical vs logical coordinates.
+   */
+  async getPositioningConventions(): Promise<PositioningConvention[] | null> {
+    return invoke<PositioningConvention[]>("get_positioning_conventions");
+  },
+
   /**
    * Set whether a window ignores cursor events (click-through).
    */
@@ -198,6 +522,36 @@ export const windowManager = {
     await invoke("set_ignore_cursor_events", { label, ignore });
   },
 
+  /**
+   * Start moving a frameless window with the OS drag loop. Call from a
+   * `pointerdown` handler on the window's own titlebar element; unlike
+   * `-webkit-app-region: drag` this works on WebKitGTK and leaves buttons
+   * clickable. Rejects with a `WindowError`.
+   */
+  async startNativeDrag(label: WindowLabel): Promise<void> {
+    await invoke("start_native_drag", { label });
+  },
+
+  async getTitlebarStyle(label: WindowLabel): Promise<TitlebarStyle | null> {
+    return invoke<TitlebarStyle>("get_titlebar_style", { label });
+  },
+
+  /**
+   * Switch a window between the decorum overlay titlebar and none, in which
+   * case the DOM titlebar draws the window controls.
+   */
+  async setTitlebarStyle(label: WindowLabel, style: TitlebarStyle): Promise<void> {
+    await invoke("set_titlebar_style", { label, style });
+  },
+
+  /**
+   * Start resizing a frameless window from an edge or corner with the OS
+   * resize loop. Rejects with a `WindowError`.
+   */
+  async startNativeResize(label: WindowLabel, direction: ResizeEdge): Promise<void> {
+    await invoke("start_native_resize", { label, direction });
+  },
+
   /**
    * Resize a window to a logical size.
    */
@@ -234,6 +588,266 @@ export const windowManager = {
     await invoke("plugin:taskbar-lyric|open_taskbar_lyric_devtools");
   },
 
+  /**
+   * Tell the backend the main window has rendered; it may then pre-warm the
+   * settings and mini-player webviews (once per run).
+   */
+  async notifyMainWindowReady(): Promise<void> {
+    await invoke("notify_main_window_ready");
+  },
+
+  /**
+   * Whether this launch is in safe mode (`--safe-mode`, or after two
+   * launches that never got the main window ready).
+   */
+  async isSafeMode(): Promise<boolean> {
+    return (await invoke<boolean>("is_safe_mode")) ?? false;
+  },
+
+  /**
+   * Delete saved window geometry and restart without safe mode.
+   */
+  async resetWindowState(): Promise<void> {
+    await invoke("reset_window_state");
+  },
+
+  /**
+   * Reset one backend settings section (or "all") and restart without safe mode.
+   */
+  async resetSettings(section: string): Promise<void> {
+    await invoke("reset_settings", { section });
+  },
+
+  /**
+   * Report that this window has rendered. Windows with a native effect stay
+   * hidden until then to avoid a white first frame.
+   */
+  async notifyFirstPaint(): Promise<void> {
+    if (!isTauri()) return;
+    const { getCurrentWindow } = await import("@tauri-apps/api/window");
+    await invoke("notify_first_paint", { label: getCurrentWindow().label });
+  },
+
+  /**
+   * Report this window alive every `intervalMs` so the backend can reload
+   * it if its webview hangs or crashes. Returns a function that stops it.
+   *
+   * Beats pause while the document is hidden: WebView2 throttles timers in
+   * occluded windows, which would otherwise read as a hang. The watchdog is
+   * switched off for that time and back on when the page is visible again.
+   */
+  async startHeartbeat(intervalMs = 5_000): Promise<() => void> {
+    if (!isTauri()) return () => {};
+    const { getCurrentWindow } = await import("@tauri-apps/api/window");
+    const label = getCurrentWindow().label;
+    const beat = () => invoke("window_heartbeat", { label }).catch(() => {});
+    let timer: number | null = null;
+    const resume = () => {
+      if (timer !== null) return;
+      invoke("set_watchdog_enabled", { label, enabled: true }).catch(() => {});
+      beat();
+      timer = window.setInterval(beat, intervalMs);
+    };
+    const pause = () => {
+      if (timer === null) return;
+      window.clearInterval(timer);
+      timer = null;
+      invoke("set_watchdog_enabled", { label, enabled: false }).catch(() => {});
+    };
+    const onVisibilityChange = () => (document.hidden ? pause() : resume());
+    document.addEventListener("visibilitychange", onVisibilityChange);
+    if (document.hidden) {
+      invoke("set_watchdog_enabled", { label, enabled: false }).catch(() => {});
+    } else {
+      resume();
+    }
+    return () => {
+      document.removeEventListener("visibilitychange", onVisibilityChange);
+      if (timer !== null) window.clearInterval(timer);
+      timer = null;
+    };
+  },
+
+  /**
+   * Exempt a window from (or return it to) the hang watchdog.
+   */
+  async setWatchdogEnabled(label: WindowLabel, enabled: boolean): Promise<void> {
+    await invoke("set_watchdog_enabled", { label, enabled });
+  },
+
+  /**
+   * Switch the language of backend-owned strings (default window titles,
+   * tray tooltip). Returns the locale actually used; unsupported ones fall
+   * back to "en".
+   */
+  async setBackendLocale(tag: string): Promise<string | null> {
+    return invoke<string>("set_backend_locale", { tag });
+  },
+
+  async getPrewarmWindows(): Promise<boolean | null> {
+    return invoke<boolean>("get_prewarm_windows");
+  },
+
+  /**
+   * Enable or disable startup pre-warming of auxiliary windows.
+   */
+  async setPrewarmWindows(enabled: boolean): Promise<void> {
+    await invoke("set_prewarm_windows", { enabled });
+  },
+
+  async getLyricsSnap(): Promise<{ enabled: boolean; thresholdPx: number } | null> {
+    return invoke<{ enabled: boolean; thresholdPx: number }>("get_lyrics_snap");
+  },
+
+  /**
+   * Configure edge snapping of the desktop lyrics window. The threshold is
+   * in logical pixels and clamped to 1–200.
+   */
+  async setLyricsSnap(
+    enabled: boolean,
+    thresholdPx: number,
+  ): Promise<{ enabled: boolean; thresholdPx: number } | null> {
+    return invoke<{ enabled: boolean; thresholdPx: number }>("set_lyrics_snap", {
+      enabled,
+      thresholdPx,
+    });
+  },
+
+  /**
+   * Listen for the desktop lyrics window snapping to a guide, e.g. to flash
+   * a hint. `x`/`y` are the new physical position.
+   */
+  async onDesktopLyricsSnapped(
+    handler: (event: {
+      edges: ("left" | "center" | "right" | "top" | "bottom")[];
+      x: number;
+      y: number;
+    }) => void,
+  ): Promise<() => void> {
+    return listen("desktop-lyrics-snapped", handler);
+  },
+
+  /**
+   * Lock desktop lyrics: the window becomes click-through and the backend
+   * watches the cursor, re-enabling clicks only over the unlock strip along
+   * its header.
+   */
+  async setLyricsLocked(locked: boolean): Promise<void> {
+    await invoke("set_lyrics_locked", { locked });
+  },
+
+  /**
+   * Cursor entered or left the locked desktop-lyrics window.
+   */
+  async onDesktopLyricsHover(handler: (event: "enter" | "leave") => void): Promise<() => void> {
+    return listen("desktop-lyrics-hover", handler);
+  },
+
+  async getLyricsOrientation(): Promise<"horizontal" | "vertical" | null> {
+    return invoke<"horizontal" | "vertical">("get_lyrics_orientation");
+  },
+
+  /**
+   * Turn desktop lyrics vertical (or back). Size limits swap with it and the
+   * choice is remembered across restarts.
+   */
+  async setLyricsOrientation(orientation: "horizontal" | "vertical"): Promise<void> {
+    await invoke("set_lyrics_orientation", { orientation });
+  },
+
+  /**
+   * The rotated lyrics window did not fit on its monitor and was shrunk to
+   * `width`×`height` physical pixels.
+   */
+  async onDesktopLyricsOrientationClamped(
+    handler: (event: {
+      orientation: "horizontal" | "vertical";
+      width: number;
+      height: number;
+    }) => void,
+  ): Promise<() => void> {
+    return listen("desktop-lyrics-orientation-clamped", handler);
+  },
+
+  async getLyricsAutohideFullscreen(): Promise<boolean | null> {
+    return invoke<boolean>("get_lyrics_autohide_fullscreen");
+  },
+
+  /**
+   * Hide desktop lyrics while a fullscreen app (game, video) is focused on
+   * their monitor. Windows and macOS only.
+   */
+  async setLyricsAutohideFullscreen(enabled: boolean): Promise<void> {
+    await invoke("set_lyrics_autohide_fullscreen", { enabled });
+  },
+
+  /**
+   * `true` when desktop lyrics were hidden for a fullscreen app, `false`
+   * when they are shown again.
+   */
+  async onDesktopLyricsAutohidden(handler: (hidden: boolean) => void): Promise<() => void> {
+    return listen("desktop-lyrics-autohidden", handler);
+  },
+
+  async getSessionRestoreEnabled(): Promise<boolean | null> {
+    return invoke<boolean>("get_session_restore_enabled");
+  },
+
+  /**
+   * Whether windows open at exit (desktop lyrics, mini player, ...) are
+   * reopened on the next launch.
+   */
+  async setSessionRestoreEnabled(enabled: boolean): Promise<void> {
+    await invoke("set_session_restore_enabled", { enabled });
+  },
+
+  /**
+   * Zoom a window's webview (0.5–2.0, snapped to 0.05) and remember it.
+   * Returns the factor applied. Not available for desktop-lyrics/tray-popup.
+   */
+  async setWebviewZoom(label: WindowLabel, factor: number): Promise<number | null> {
+    return invoke<number>("set_webview_zoom", { label, factor });
+  },
+
+  async getWebviewZoom(label: WindowLabel): Promise<number | null> {
+    return invoke<number>("get_webview_zoom", { label });
+  },
+
+  /**
+   * Listen for zoom changes, e.g. to show a transient indicator.
+   */
+  async onWindowZoomChanged(
+    handler: (event: { label: string; factor: number }) => void,
+  ): Promise<() => void> {
+    return listen("window-zoom-changed", handler);
+  },
+
+  /**
+   * Listen for this window being pre-warmed (`paused: true`, hidden and
+   * idle) or shown for real (`paused: false`).
+   */
+  async onWindowPrewarmed(
+    handler: (event: { label: string; paused: boolean }) => void,
+  ): Promise<() => void> {
+    return listen("window-prewarmed", handler);
+  },
+
+  /**
+   * Labels of the windows open now. Call before `onWindowLifecycle` to seed
+   * a window list; later changes arrive as lifecycle events.
+   */
+  async subscribeWindowEvents(): Promise<string[] | null> {
+    return invoke<string[]>("subscribe_window_events");
+  },
+
+  /**
+   * Listen for any window being created, destroyed, focused, blurred, moved,
+   * resized or asked to close.
+   */
+  async onWindowLifecycle(handler: (event: WindowLifecycle) => void): Promise<() => void> {
+    return listen("window-lifecycle", handler);
+  },
+
   /**
    * Listen for main window close-requested events.
    */
@@ -268,6 +882,174 @@ export const windowManager = {
   async setTrayTooltip(text: string): Promise<void> {
     await invoke("set_tray_tooltip", { text });
   },
+
+  /**
+   * Show title, album, progress and the next track in the tray tooltip and
+   * native tray menu. Call freely: the backend applies track and play/pause
+   * changes at once and progress every 5 seconds.
+   */
+  async updateTrayNowPlaying(
+    title: string,
+    artist: string,
+    positionMs: number,
+    durationMs: number,
+    nextTitle?: string,
+    album?: string,
+    playing?: boolean,
+  ): Promise<void> {
+    await invoke("update_tray_now_playing", {
+      title,
+      artist,
+      positionMs: Math.max(0, Math.round(positionMs)),
+      durationMs: Math.max(0, Math.round(durationMs)),
+      nextTitle: nextTitle ?? null,
+      album: album ?? null,
+      playing: playing ?? null,
+    });
+  },
+
+  /**
+   * Reset the tray tooltip to "GMPlayer".
+   */
+  async clearTrayNowPlaying(): Promise<void> {
+    await invoke("clear_tray_now_playing");
+  },
+
+  async getTrayConfig(): Promise<TrayConfig | null> {
+    return invoke<TrayConfig>("get_tray_config");
+  },
+
+  /**
+   * What a left click on the tray icon does. Defaults to "show-main".
+   */
+  async setTrayLeftClickAction(action: TrayClickAction): Promise<TrayConfig | null> {
+    return invoke<TrayConfig>("set_tray_left_click_action", { action });
+  },
+
+  /**
+   * What a double click on the tray icon does (Windows and macOS). Both
+   * single clicks still fire first. Defaults to "nothing".
+   */
+  async setTrayDoubleClickAction(action: TrayClickAction): Promise<TrayConfig | null> {
+    return invoke<TrayConfig>("set_tray_double_click_action", { action });
+  },
+
+  /**
+   * Use the webview popup or a native menu on the tray icon. Rebuilds the
+   * icon; badge and tooltip carry over.
+   */
+  async setTrayMenuMode(mode: TrayMenuMode): Promise<TrayConfig | null> {
+    return invoke<TrayConfig>("set_tray_menu_mode", { mode });
+  },
+
+  /**
+   * Recreate the tray icon if it has vanished from the notification area.
+   * Windows does this on its own after Explorer restarts.
+   */
+  async rebuildTrayIcon(): Promise<void> {
+    await invoke("rebuild_tray_icon");
+  },
+
+  /**
+   * Show playback progress (0–1) on the main window's taskbar button or
+   * Dock icon. Safe to call every second; does nothing while main is hidden.
+   */
+  async setTaskbarProgress(state: TaskbarProgressState, value: number): Promise<void> {
+    await invoke("set_taskbar_progress", { state, value });
+  },
+
+  /**
+   * Show play or pause on the middle taskbar thumbnail button (Windows only;
+   * a no-op elsewhere).
+   */
+  async setThumbbarState(playing: boolean): Promise<void> {
+    await invoke("set_thumbbar_state", { playing });
+  },
+
+  /**
+   * Boss key: hide every window (except the tray popup), optionally mute the
+   * system output, and blank the tray tooltip. Undo with `panicRestore`.
+   */
+  async panicHide(mute = false): Promise<void> {
+    await invoke("panic_hide", { mute });
+  },
+
+  /**
+   * Show exactly the windows `panicHide` hid and restore the mute state.
+   */
+  async panicRestore(): Promise<void> {
+    await invoke("panic_restore");
+  },
+
+  /**
+   * Take the audio file or `gmplayer://` link a second launch asked to
+   * open. Call on `second-instance`; returns null if there is none.
+   */
+  async takePendingOpen(): Promise<PendingOpen | null> {
+    return invoke<PendingOpen | null>("take_pending_open");
+  },
+
+  /**
+   * Take audio files opened before the app was listening (launch arguments,
+   * early drops). Call once on startup; later opens arrive as
+   * `files-opened` events.
+   */
+  async takeOpenedFiles(): Promise<FilesOpened | null> {
+    return invoke<FilesOpened | null>("take_opened_files");
+  },
+
+  /**
+   * Hide the tray popup if it is open.
+   */
+  async dismissTrayPopup(): Promise<void> {
+    await invoke("dismiss_tray_popup");
+  },
+
+  /**
+   * Badge the tray icon with a play or pause symbol; "stopped" restores the
+   * plain icon.
+   */
+  async setTrayPlaybackState(state: "playing" | "paused" | "stopped"): Promise<void> {
+    await invoke("set_tray_playback_state", { state });
+  },
+
+  /**
+   * Persist a window's close behavior (main by default) in the backend so
+   * the native close button works without the dialog. For windows other
+   * than main, "exit" closes just that window.
+   */
+  async setCloseBehavior(
+    behavior: "ask" | "hide" | "tray" | "exit",
+    label?: WindowLabel,
+  ): Promise<void> {
+    await invoke("set_close_behavior", { label, behavior });
+  },
+
+  async getCloseBehavior(label?: WindowLabel): Promise<"ask" | "hide" | "exit" | null> {
+    return invoke<"ask" | "hide" | "exit">("get_close_behavior", { label });
+  },
+
+  /**
+   * Acknowledge a main-close-requested event (stops the backend fallback).
+   */
+  async ackCloseRequest(): Promise<void> {
+    await invoke("ack_close_request");
+  },
+
+  /**
+   * Flash the hotkey OSD (volume / track change). Resolves to false when the
+   * OSD was suppressed (disabled, or a fullscreen app is in front).
+   */
+  async showOsd(kind: "volume" | "track", payload: unknown, durationMs?: number): Promise<boolean> {
+    return (await invoke<boolean>("show_osd", { kind, payload, durationMs })) ?? false;
+  },
+
+  /**
+   * Hide the OSD immediately.
+   */
+  async hideOsd(): Promise<void> {
+    await invoke("hide_osd");
+  },
 };
 
 if (import.meta.env.DEV && typeof window !== "undefined") {